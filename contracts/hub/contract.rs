@@ -5,12 +5,27 @@ use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Reply,
 
 use amulet_cw::{
     admin::{self, Repository as AdminRespository},
-    hub::{self, AdvanceFeeOracle, BalanceSheet, Ctx, SyntheticMint, VaultRegistry},
+    bank,
+    events::{EventBuilder, Kind},
+    hub::{
+        self, vault_registry::StorageExt as _, AdvanceFeeOracle, BalanceSheet, Ctx, SyntheticMint,
+        UserMsg, VaultRegistry,
+    },
+    reentrancy,
+    state_version::{self, MigrationStep},
     MigrateMsg,
 };
 
 use self::msg::{AdminExecuteMsg, ExecuteMsg, HubExecuteMsg, InstantiateMsg, QueryMsg};
 
+/// State migrations, the step at index `n` upgrades state from version `n` to `n + 1`
+const MIGRATIONS: &[MigrationStep] = &[
+    // v0 -> v1: record vault debt & credit totals
+    hub::balance_sheet::backfill_debt_credit_totals,
+    // v1 -> v2: record vault collateral totals
+    hub::balance_sheet::backfill_total_collateral,
+];
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
@@ -22,6 +37,8 @@ pub fn instantiate(
 
     hub::init_mint(deps.api, deps.storage, &msg.synthetic_mint)?;
 
+    state_version::init(deps.storage, MIGRATIONS);
+
     Ok(Response::default())
 }
 
@@ -39,10 +56,13 @@ pub fn execute_hub_msg(
 
     let balance_sheet = &BalanceSheet::new(deps.storage);
 
-    let advance_fee_oracle = &AdvanceFeeOracle::new(deps.querier);
+    let advance_fee_oracle = &AdvanceFeeOracle::new(deps.querier, &env);
 
     let ctx = Ctx {
         api: deps.api,
+        storage: deps.storage,
+        querier: deps.querier,
+        env: &env,
         vaults,
         admin_repository,
         mint,
@@ -56,6 +76,40 @@ pub fn execute_hub_msg(
         hub::handle_hub_cmd(deps.storage, &env, &mut response, cmd)?;
     }
 
+    response.messages = bank::coalesce_sends(response.messages);
+
+    Ok(response)
+}
+
+/// Execute each action of a batch against the state left by the previous, failing the whole batch if any fails
+pub fn execute_batch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    actions: Vec<UserMsg>,
+) -> Result<Response, Error> {
+    let mut response = Response::default().add_attributes(
+        EventBuilder::new(Kind::Batch)
+            .account(&info.sender)
+            .render(),
+    );
+
+    for (info, action) in hub::batch_actions(info, actions)? {
+        let action_response = execute_hub_msg(
+            deps.branch(),
+            env.clone(),
+            info,
+            HubExecuteMsg::User(action),
+        )?;
+
+        response = response
+            .add_submessages(action_response.messages)
+            .add_attributes(action_response.attributes)
+            .add_events(action_response.events);
+    }
+
+    response.messages = bank::coalesce_sends(response.messages);
+
     Ok(response)
 }
 
@@ -81,19 +135,28 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, Error> {
+    // a vault deposit callback is pending
+    reentrancy::ensure_unlocked(deps.storage)?;
+
     match msg {
         ExecuteMsg::Admin(admin_msg) => execute_admin_msg(deps, env, info, admin_msg),
+        ExecuteMsg::Hub(HubExecuteMsg::User(UserMsg::Batch { actions })) => {
+            execute_batch(deps, env, info, actions)
+        }
+
         ExecuteMsg::Hub(hub_msg) => execute_hub_msg(deps, env, info, hub_msg),
     }
 }
 
 #[entry_point]
 pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, Error> {
+    let reply_id = reply.id;
+
     let vaults = &VaultRegistry::new(deps.storage, deps.querier);
 
     let balance_sheet = &BalanceSheet::new(deps.storage);
 
-    let advance_fee_oracle = &AdvanceFeeOracle::new(deps.querier);
+    let advance_fee_oracle = &AdvanceFeeOracle::new(deps.querier, &env);
 
     let (cmds, mut response) = hub::handle_reply(
         deps.storage,
@@ -103,15 +166,24 @@ pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, Error> {
         reply,
     )?;
 
+    deps.storage.remove_vault_callback(reply_id);
+
+    // other deposits issued in the same transaction may still be awaiting their replies
+    if deps.storage.pending_vault_callback_count() == 0 {
+        reentrancy::unlock(deps.storage);
+    }
+
     for cmd in cmds {
         hub::handle_hub_cmd(deps.storage, &env, &mut response, cmd)?;
     }
 
+    response.messages = bank::coalesce_sends(response.messages);
+
     Ok(response)
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _: Env, msg: QueryMsg) -> Result<Binary, Error> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, Error> {
     let binary = match msg {
         QueryMsg::Admin(admin_query) => {
             admin::handle_query_msg(&AdminRespository::new(deps.storage), admin_query)?
@@ -119,9 +191,11 @@ pub fn query(deps: Deps, _: Env, msg: QueryMsg) -> Result<Binary, Error> {
 
         QueryMsg::Hub(hub_query) => hub::handle_query_msg(
             deps.storage,
+            deps.querier,
+            &env,
             &VaultRegistry::new(deps.storage, deps.querier),
             &BalanceSheet::new(deps.storage),
-            &AdvanceFeeOracle::new(deps.querier),
+            &AdvanceFeeOracle::new(deps.querier, &env),
             hub_query,
         )?,
     };
@@ -130,8 +204,12 @@ pub fn query(deps: Deps, _: Env, msg: QueryMsg) -> Result<Binary, Error> {
 }
 
 #[entry_point]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, Error> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, Error> {
+    let migration = state_version::migrate_state(deps, MIGRATIONS)?;
+
+    Ok(Response::default()
+        .add_attribute("state_version_from", migration.from.to_string())
+        .add_attribute("state_version_to", migration.to.to_string()))
 }
 
 #[cfg(test)]
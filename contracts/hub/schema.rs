@@ -1,3 +1,4 @@
+use amulet_cw::MigrateMsg;
 use amulet_hub::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 use cosmwasm_schema::write_api;
 
@@ -5,6 +6,7 @@ fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
-        query: QueryMsg
+        query: QueryMsg,
+        migrate: MigrateMsg
     }
 }
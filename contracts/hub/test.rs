@@ -1,37 +1,66 @@
-use amulet_core::vault::SHARES_DECIMAL_PLACES;
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use amulet_core::{
+    hub::{
+        AdvanceFee, BalanceSheet as _, CachedAdvanceFee, Cmd, VaultCmd, VaultDepositReason,
+        VaultRegistry as CoreVaultRegistry,
+    },
+    vault::{DepositAmount, SHARES_DECIMAL_PLACES},
+};
 use cosmwasm_schema::serde::de::DeserializeOwned;
 use cosmwasm_std::{
-    coins, from_json,
+    coin, coins, from_json,
     testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage},
-    to_json_binary, Addr, Binary, ContractResult, Empty, MessageInfo, OwnedDeps, Reply, Response,
-    SubMsgResponse, SystemResult, WasmMsg, WasmQuery,
+    to_json_binary, Addr, Binary, ContractResult, Decimal, Empty, MessageInfo, OwnedDeps,
+    QueryRequest, Reply, Response, Storage as _, SubMsgResponse, SystemResult, Uint128, WasmMsg,
+    WasmQuery,
 };
 use prost::Message;
 use test_utils::prelude::*;
 
 use amulet_cw::{
+    bank::UnaccountedBalancesResponse,
     hub::{
-        vault_registry::{DEPOSIT_REPLY_ID, MINT_REPLY_ID, REPAY_UNDERLYING_REPLY_ID},
-        AdminMsg as HubAdminMsg, ListVaultsResponse, PositionResponse, TreasuryResponse,
-        UserMsg as HubUserMsg, VaultMetadata,
+        handle_hub_cmd,
+        vault_registry::{StorageExt as _, VaultRegistry},
+        AccountOverviewResponse, AdminMsg as HubAdminMsg, AdvanceFeeResponse, AdvanceFeesResponse,
+        BalanceSheet, CollateralPriceOracle, GlobalPauseResponse, GuardianMsg, HealthcheckResponse,
+        ListVaultsResponse, LtvResponse, PositionResponse, ProtocolSummaryResponse, RedeemFeeTier,
+        SimulateAdvanceResponse, SimulateDepositResponse, SimulateWithdrawResponse,
+        TreasuryResponse, UserMsg as HubUserMsg, VaultMetadata,
     },
-    mint::{ExecuteMsg as MintExecuteMsg, Metadata as SynthMetadata, QueryMsg as MintQueryMsg},
+    mint::{
+        ExecuteMsg as MintExecuteMsg, Metadata as SynthMetadata, QueryMsg as MintQueryMsg,
+        WhitelistedResponse,
+    },
+    reentrancy,
     vault::{
-        DepositAssetResponse, DepositResponse, ExecuteMsg as VaultExecuteMsg,
-        QueryMsg as VaultQueryMsg, SharesAssetResponse, StateResponse,
-        UnderlyingAssetDecimalsResponse,
+        accepted_assets::{AcceptedAsset, ConversionOracleQueryMsg, ConversionRateResponse},
+        feature, AcceptedDepositAssetsResponse, DepositAssetResponse, DepositResponse,
+        ExecuteMsg as VaultExecuteMsg, InterfaceVersionResponse, PendingUnbondingResponse,
+        QueryMsg as VaultQueryMsg, SharesAssetResponse, SharesDecimalsResponse, StateResponse,
+        Timestamp, UnderlyingAssetDecimalsResponse, INTERFACE_VERSION,
     },
+    MigrateMsg,
 };
 
 use crate::msg::{AdminExecuteMsg, AdminQueryMsg, HubExecuteMsg, HubQueryMsg, InstantiateMsg};
 
-use super::{execute, instantiate, query, reply};
+use super::{execute, instantiate, migrate, query, reply};
 
 const VAULT: &str = "vault";
+const SECOND_VAULT: &str = "second_vault";
+const THIRD_VAULT: &str = "third_vault";
 const VAULT_SHARE: &str = "vault_share";
+const SIX_DECIMAL_SHARES_VAULT: &str = "six_decimal_shares_vault";
+const NEXT_MAJOR_INTERFACE_VAULT: &str = "next_major_interface_vault";
+const UNVERSIONED_INTERFACE_VAULT: &str = "unversioned_interface_vault";
 const VAULT_DEPOSIT_ASSET: &str = "vault_deposit_asset";
+const VAULT_SECONDARY_DEPOSIT_ASSET: &str = "vault_secondary_deposit_asset";
+const CONVERSION_ORACLE: &str = "conversion_oracle";
 const SYNTHETIC_MINT: &str = "synthetic_mint";
 const SYNTHETIC_ASSET: &str = "synthetic_asset";
+const UNBOND_START_HINT: u64 = 1_571_800_000;
 
 type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>;
 
@@ -69,10 +98,16 @@ fn update_querier(deps: &mut MockDeps, total_deposits: u128, total_issued_shares
         };
 
         let binary = match contract_addr.as_str() {
-            VAULT => match from_json(msg).unwrap() {
+            VAULT
+            | SECOND_VAULT
+            | THIRD_VAULT
+            | SIX_DECIMAL_SHARES_VAULT
+            | NEXT_MAJOR_INTERFACE_VAULT
+            | UNVERSIONED_INTERFACE_VAULT => match from_json(msg).unwrap() {
                 VaultQueryMsg::State {} => to_json_binary(&StateResponse {
                     total_deposits: total_deposits.into(),
                     total_issued_shares: total_issued_shares.into(),
+                    redemption_value: None,
                 }),
                 VaultQueryMsg::UnderlyingAssetDecimals {} => {
                     to_json_binary(&UnderlyingAssetDecimalsResponse { decimals: 6 })
@@ -80,17 +115,72 @@ fn update_querier(deps: &mut MockDeps, total_deposits: u128, total_issued_shares
                 VaultQueryMsg::DepositAsset {} => to_json_binary(&DepositAssetResponse {
                     denom: VAULT_DEPOSIT_ASSET.into(),
                 }),
+                VaultQueryMsg::AcceptedDepositAssets {} => {
+                    to_json_binary(&AcceptedDepositAssetsResponse {
+                        primary: VAULT_DEPOSIT_ASSET.into(),
+                        accepted: vec![AcceptedAsset {
+                            denom: VAULT_SECONDARY_DEPOSIT_ASSET.into(),
+                            conversion: CONVERSION_ORACLE.into(),
+                        }],
+                    })
+                }
                 VaultQueryMsg::SharesAsset {} => to_json_binary(&SharesAssetResponse {
                     denom: VAULT_SHARE.into(),
                 }),
+                VaultQueryMsg::SharesDecimals {} => {
+                    let decimals = if contract_addr == SIX_DECIMAL_SHARES_VAULT {
+                        6
+                    } else {
+                        SHARES_DECIMAL_PLACES
+                    };
+
+                    to_json_binary(&SharesDecimalsResponse { decimals })
+                }
+                VaultQueryMsg::InterfaceVersion {} if contract_addr == UNVERSIONED_INTERFACE_VAULT => {
+                    return SystemResult::Ok(ContractResult::Err(
+                        "Error parsing into type vault::QueryMsg: unknown variant `interface_version`"
+                            .into(),
+                    ));
+                }
+                VaultQueryMsg::InterfaceVersion {} => {
+                    let version = if contract_addr == NEXT_MAJOR_INTERFACE_VAULT {
+                        "2.0.0"
+                    } else {
+                        INTERFACE_VERSION
+                    };
+
+                    to_json_binary(&InterfaceVersionResponse {
+                        version: version.into(),
+                        features: vec![feature::DONATE.into(), feature::CLAIM_ALL.into()],
+                    })
+                }
+                VaultQueryMsg::PendingUnbonding { .. } => {
+                    to_json_binary(&PendingUnbondingResponse {
+                        amount: 0u128.into(),
+                        start_hint: Some(Timestamp(UNBOND_START_HINT)),
+                    })
+                }
                 q => panic!("unexpected vault query: {q:?}"),
             },
+            CONVERSION_ORACLE => match from_json(msg).unwrap() {
+                ConversionOracleQueryMsg::ConversionRate { .. } => {
+                    to_json_binary(&ConversionRateResponse {
+                        rate: Decimal::percent(150),
+                    })
+                }
+            },
             SYNTHETIC_MINT => match from_json(msg).unwrap() {
                 MintQueryMsg::Synthetic { denom } => to_json_binary(&SynthMetadata {
                     denom,
-                    ticker: "SYNTH".into(),
+                    ticker: "synth".into(),
                     decimals: 6,
+                    display_ticker: "SYNTH".into(),
+                    display_decimals: 6,
+                    description: String::new(),
                 }),
+                MintQueryMsg::Whitelisted { .. } => {
+                    to_json_binary(&WhitelistedResponse { whitelisted: true })
+                }
                 q => panic!("unexpected vault query: {q:?}"),
             },
             _ => panic!("unexpected contract query addr: {contract_addr}"),
@@ -168,6 +258,11 @@ fn init_with_registered_vault() -> MockDeps {
     deps
 }
 
+// The reply ID allocated to the latest vault deposit
+fn last_reply_id(deps: &MockDeps) -> u64 {
+    deps.storage.vault_callback_count()
+}
+
 fn execute_msgs(deps: &mut MockDeps, msgs: &[(MessageInfo, HubExecuteMsg)]) -> Response {
     let mut response = Response::default();
 
@@ -272,11 +367,13 @@ fn deposit() {
         ]"#]],
     );
 
+    let reply_id = last_reply_id(&deps);
+
     let response = reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -327,6 +424,10 @@ fn deposit() {
                   key: "account_collateral",
                   value: "1000",
                 ),
+                (
+                  key: "position_seq",
+                  value: "1",
+                ),
               ],
               events: [],
               data: None,
@@ -356,6 +457,16 @@ fn deposit() {
               fixed_advance_fee_bps: 25,
               advance_fee_recipient: None,
               advance_fee_oracle: None,
+              oracle_fee_cache_secs: None,
+              collateral_price_oracle: None,
+              min_collateral: None,
+              min_debt: None,
+              account_debt_cap: None,
+              reserve_floor: None,
+              redeem_fee_schedule: None,
+              loss_tolerance_bps: 0,
+              loss_socialisation_enabled: false,
+              min_deposit: "1",
               collateral_balance: "1000",
               collateral_shares: "1000000000000000000000",
               reserve_balance: "0",
@@ -364,11 +475,18 @@ fn deposit() {
               amo: None,
               amo_allocation: 0,
               amo_shares: "0",
+              amo_shares_returned: "0",
+              amo_deposits_returned: "0",
               sum_payment_ratio: None,
               deposit_proxy: None,
               advance_proxy: None,
               mint_proxy: None,
               redeem_proxy: None,
+              interface_version: Some("1.0.0"),
+              interface_features: [
+                "donate",
+                "claim_all",
+              ],
             )"#]],
     );
 
@@ -391,6 +509,235 @@ fn deposit() {
               credit: "0",
               sum_payment_ratio: "0.0",
               vault_loss_detected: false,
+              position_seq: 1,
+            )"#]],
+    );
+}
+
+#[test]
+fn concurrent_deposits_credit_their_recipients() {
+    let mut deps = init_with_registered_vault();
+
+    let env = mock_env();
+
+    let mut response: Response = Response::default();
+
+    // two deposits dispatched by a single execute, each awaiting its own reply
+    for (recipient, amount) in [("bob", 1_000), ("alice", 3_000)] {
+        handle_hub_cmd(
+            deps.as_mut().storage,
+            &env,
+            &mut response,
+            Cmd::Vault(VaultCmd::Deposit {
+                vault: VAULT.into(),
+                asset: VAULT_DEPOSIT_ASSET.into(),
+                amount: DepositAmount(amount),
+                callback_recipient: recipient.into(),
+                callback_reason: VaultDepositReason::Deposit,
+            }),
+        )
+        .unwrap();
+    }
+
+    let reply_ids: Vec<_> = response.messages.iter().map(|msg| msg.id).collect();
+
+    check(
+        &reply_ids,
+        expect![[r#"
+        [
+          1,
+          2,
+        ]"#]],
+    );
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_ids[0],
+            1_000,
+            shares_amount(1_000),
+            shares_amount(1_000),
+            1_000,
+        ),
+    )
+    .unwrap();
+
+    // the second deposit is still pending
+    assert!(reentrancy::is_locked(&deps.storage));
+
+    update_querier(&mut deps, 1_000, shares_amount(1_000));
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_ids[1],
+            4_000,
+            shares_amount(4_000),
+            shares_amount(3_000),
+            3_000,
+        ),
+    )
+    .unwrap();
+
+    assert!(!reentrancy::is_locked(&deps.storage));
+
+    assert_eq!(deps.storage.pending_vault_callback_count(), 0);
+
+    let collateral: Vec<_> = ["bob", "alice"]
+        .into_iter()
+        .map(|account| {
+            query(
+                deps.as_ref(),
+                mock_env(),
+                HubQueryMsg::Position {
+                    account: account.into(),
+                    vault: VAULT.into(),
+                }
+                .into(),
+            )
+            .map(into_response::<PositionResponse>)
+            .unwrap()
+            .collateral
+        })
+        .collect();
+
+    check(
+        collateral,
+        expect![[r#"
+        [
+          "1000",
+          "3000",
+        ]"#]],
+    );
+}
+
+#[test]
+fn deposit_secondary_asset() {
+    let mut deps = init_with_registered_vault();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+            vault: VAULT.into(),
+            enabled: true,
+        })
+        .into(),
+    )
+    .and_then(|_| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info!("bob", 1_000, "unknown_asset"),
+            HubExecuteMsg::from(HubUserMsg::Deposit {
+                vault: VAULT.into(),
+            })
+            .into(),
+        )
+    })
+    .unwrap_err()
+    .to_string();
+
+    check(err, expect![[r#""invalid deposit asset""#]]);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 1_000, VAULT_DEPOSIT_ASSET),
+        HubExecuteMsg::from(HubUserMsg::Deposit {
+            vault: VAULT.into(),
+        })
+        .into(),
+    )
+    .unwrap();
+
+    let reply_id = last_reply_id(&deps);
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_id,
+            1_000,
+            shares_amount(1_000),
+            shares_amount(1_000),
+            1_000,
+        ),
+    )
+    .unwrap();
+
+    let response = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 2_000, VAULT_SECONDARY_DEPOSIT_ASSET),
+        HubExecuteMsg::from(HubUserMsg::Deposit {
+            vault: VAULT.into(),
+        })
+        .into(),
+    )
+    .unwrap();
+
+    check(
+        &response.messages,
+        expect![[r#"
+            [
+              (
+                id: 2,
+                msg: wasm(execute(
+                  contract_addr: "vault",
+                  msg: "eyJkZXBvc2l0Ijp7fX0=",
+                  funds: [
+                    (
+                      denom: "vault_secondary_deposit_asset",
+                      amount: "2000",
+                    ),
+                  ],
+                )),
+                gas_limit: None,
+                reply_on: success,
+              ),
+            ]"#]],
+    );
+
+    // the vault credits the converted value of the secondary asset deposit, e.g. 2_000 @ 1.5
+    let reply_id = last_reply_id(&deps);
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_id,
+            4_000,
+            shares_amount(4_000),
+            shares_amount(3_000),
+            3_000,
+        ),
+    )
+    .unwrap();
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::Position {
+                account: "bob".into(),
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<PositionResponse>)
+        .unwrap(),
+        expect![[r#"
+            (
+              collateral: "4000",
+              debt: "0",
+              credit: "0",
+              sum_payment_ratio: "0.0",
+              vault_loss_detected: false,
+              position_seq: 2,
             )"#]],
     );
 }
@@ -492,11 +839,13 @@ fn deposit_on_behalf() {
         ]"#]],
     );
 
+    let reply_id = last_reply_id(&deps);
+
     let response = reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -547,6 +896,10 @@ fn deposit_on_behalf() {
                   key: "account_collateral",
                   value: "1000",
                 ),
+                (
+                  key: "position_seq",
+                  value: "1",
+                ),
               ],
               events: [],
               data: None,
@@ -572,12 +925,13 @@ fn deposit_on_behalf() {
               credit: "0",
               sum_payment_ratio: "0.0",
               vault_loss_detected: false,
+              position_seq: 1,
             )"#]],
     );
 }
 
 #[test]
-fn repay_underlying() {
+fn reentrant_execute_during_pending_deposit_errs() {
     let mut deps = init_with_registered_vault();
 
     execute_msgs(
@@ -590,13 +944,6 @@ fn repay_underlying() {
                     enabled: true,
                 }),
             ),
-            (
-                info!("creator"),
-                HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
-                    vault: VAULT.into(),
-                    enabled: true,
-                }),
-            ),
             (
                 info!("bob", 1_000),
                 HubExecuteMsg::from(HubUserMsg::Deposit {
@@ -606,11 +953,27 @@ fn repay_underlying() {
         ],
     );
 
+    // the vault re-enters the hub while handling the deposit submessage
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("vault", 1_000),
+        HubExecuteMsg::from(HubUserMsg::Deposit {
+            vault: VAULT.into(),
+        })
+        .into(),
+    )
+    .unwrap_err();
+
+    check(err.to_string(), expect![[r#""reentrancy detected""#]]);
+
+    let reply_id = last_reply_id(&deps);
+
     reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -619,50 +982,222 @@ fn repay_underlying() {
     )
     .unwrap();
 
-    let response = execute_msgs(
+    // executes are accepted once the callback has been handled
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 1_000),
+        HubExecuteMsg::from(HubUserMsg::Deposit {
+            vault: VAULT.into(),
+        })
+        .into(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn absorb_shares() {
+    let mut deps = init_with_registered_vault();
+
+    execute_msgs(
         &mut deps,
-        &[
-            (
-                info!("bob"),
-                HubExecuteMsg::from(HubUserMsg::Advance {
-                    vault: VAULT.into(),
-                    amount: 500u128.into(),
-                }),
-            ),
-            (
-                info!("bob", 400),
-                HubExecuteMsg::from(HubUserMsg::RepayUnderlying {
-                    vault: VAULT.into(),
-                }),
-            ),
-        ],
+        &[(
+            info!("creator"),
+            HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+                vault: VAULT.into(),
+                enabled: true,
+            }),
+        )],
     );
 
+    // bob deposited 1_000 directly into the vault, receiving raw shares the hub has never seen
+    update_querier(&mut deps, 1_000, shares_amount(1_000));
+
+    let response = execute(
+        deps.as_mut(),
+        mock_env(),
+        MessageInfo {
+            sender: Addr::unchecked("bob"),
+            funds: coins(shares_amount(500), VAULT_SHARE),
+        },
+        HubExecuteMsg::from(HubUserMsg::AbsorbShares {
+            vault: VAULT.into(),
+        })
+        .into(),
+    )
+    .unwrap();
+
     check(
-        &response,
+        response,
         expect![[r#"
             (
-              messages: [
-                (
-                  id: 2,
-                  msg: wasm(execute(
-                    contract_addr: "vault",
-                    msg: "eyJkZXBvc2l0Ijp7fX0=",
-                    funds: [
-                      (
-                        denom: "vault_deposit_asset",
-                        amount: "400",
-                      ),
-                    ],
-                  )),
-                  gas_limit: None,
-                  reply_on: success,
-                ),
-              ],
+              messages: [],
               attributes: [
                 (
                   key: "kind",
-                  value: "repay_underlying",
+                  value: "absorb_shares",
+                ),
+                (
+                  key: "vault",
+                  value: "vault",
+                ),
+                (
+                  key: "account",
+                  value: "bob",
+                ),
+                (
+                  key: "amount",
+                  value: "500000000000000000000",
+                ),
+                (
+                  key: "collateral_shares",
+                  value: "500000000000000000000",
+                ),
+                (
+                  key: "collateral_balance",
+                  value: "500",
+                ),
+                (
+                  key: "account_collateral",
+                  value: "500",
+                ),
+                (
+                  key: "position_seq",
+                  value: "1",
+                ),
+              ],
+              events: [],
+              data: None,
+            )"#]],
+    );
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::Position {
+                account: "bob".into(),
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<PositionResponse>)
+        .unwrap(),
+        expect![[r#"
+            (
+              collateral: "500",
+              debt: "0",
+              credit: "0",
+              sum_payment_ratio: "0.0",
+              vault_loss_detected: false,
+              position_seq: 1,
+            )"#]],
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 1_000, VAULT_DEPOSIT_ASSET),
+        HubExecuteMsg::from(HubUserMsg::AbsorbShares {
+            vault: VAULT.into(),
+        })
+        .into(),
+    )
+    .unwrap_err()
+    .to_string();
+
+    check(err, expect![[r#""invalid shares asset""#]]);
+}
+
+#[test]
+fn repay_underlying() {
+    let mut deps = init_with_registered_vault();
+
+    execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
+            (
+                info!("bob", 1_000),
+                HubExecuteMsg::from(HubUserMsg::Deposit {
+                    vault: VAULT.into(),
+                }),
+            ),
+        ],
+    );
+
+    let reply_id = last_reply_id(&deps);
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_id,
+            1_000,
+            shares_amount(1_000),
+            shares_amount(1_000),
+            1_000,
+        ),
+    )
+    .unwrap();
+
+    let response = execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("bob"),
+                HubExecuteMsg::from(HubUserMsg::Advance {
+                    vault: VAULT.into(),
+                    amount: 500u128.into(),
+                }),
+            ),
+            (
+                info!("bob", 400),
+                HubExecuteMsg::from(HubUserMsg::RepayUnderlying {
+                    vault: VAULT.into(),
+                }),
+            ),
+        ],
+    );
+
+    check(
+        &response,
+        expect![[r#"
+            (
+              messages: [
+                (
+                  id: 2,
+                  msg: wasm(execute(
+                    contract_addr: "vault",
+                    msg: "eyJkZXBvc2l0Ijp7fX0=",
+                    funds: [
+                      (
+                        denom: "vault_deposit_asset",
+                        amount: "400",
+                      ),
+                    ],
+                  )),
+                  gas_limit: None,
+                  reply_on: success,
+                ),
+              ],
+              attributes: [
+                (
+                  key: "kind",
+                  value: "repay_underlying",
                 ),
                 (
                   key: "vault",
@@ -701,11 +1236,13 @@ fn repay_underlying() {
         ]"#]],
     );
 
+    let reply_id = last_reply_id(&deps);
+
     let response = reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            REPAY_UNDERLYING_REPLY_ID,
+            reply_id,
             1_400,
             shares_amount(1_400),
             shares_amount(400),
@@ -756,6 +1293,10 @@ fn repay_underlying() {
                   key: "account_debt",
                   value: "100",
                 ),
+                (
+                  key: "position_seq",
+                  value: "3",
+                ),
               ],
               events: [],
               data: None,
@@ -785,6 +1326,16 @@ fn repay_underlying() {
               fixed_advance_fee_bps: 25,
               advance_fee_recipient: None,
               advance_fee_oracle: None,
+              oracle_fee_cache_secs: None,
+              collateral_price_oracle: None,
+              min_collateral: None,
+              min_debt: None,
+              account_debt_cap: None,
+              reserve_floor: None,
+              redeem_fee_schedule: None,
+              loss_tolerance_bps: 0,
+              loss_socialisation_enabled: false,
+              min_deposit: "1",
               collateral_balance: "1000",
               collateral_shares: "1000000000000000000000",
               reserve_balance: "400",
@@ -793,11 +1344,18 @@ fn repay_underlying() {
               amo: None,
               amo_allocation: 0,
               amo_shares: "0",
+              amo_shares_returned: "0",
+              amo_deposits_returned: "0",
               sum_payment_ratio: None,
               deposit_proxy: None,
               advance_proxy: None,
               mint_proxy: None,
               redeem_proxy: None,
+              interface_version: Some("1.0.0"),
+              interface_features: [
+                "donate",
+                "claim_all",
+              ],
             )"#]],
     );
 
@@ -820,6 +1378,7 @@ fn repay_underlying() {
               credit: "0",
               sum_payment_ratio: "0.0",
               vault_loss_detected: false,
+              position_seq: 3,
             )"#]],
     );
 }
@@ -854,11 +1413,13 @@ fn repay_synthetic() {
         ],
     );
 
+    let reply_id = last_reply_id(&deps);
+
     reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -928,6 +1489,10 @@ fn repay_synthetic() {
                   key: "account_debt",
                   value: "100",
                 ),
+                (
+                  key: "position_seq",
+                  value: "3",
+                ),
               ],
               events: [],
               data: None,
@@ -972,12 +1537,13 @@ fn repay_synthetic() {
               credit: "0",
               sum_payment_ratio: "0.0",
               vault_loss_detected: false,
+              position_seq: 3,
             )"#]],
     );
 }
 
 #[test]
-fn advance() {
+fn batch_repay_and_withdraw() {
     let mut deps = init_with_registered_vault();
 
     execute_msgs(
@@ -1006,11 +1572,13 @@ fn advance() {
         ],
     );
 
+    let reply_id = last_reply_id(&deps);
+
     reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -1019,7 +1587,9 @@ fn advance() {
     )
     .unwrap();
 
-    let response = execute_msgs(
+    update_querier(&mut deps, 1_000, shares_amount(1_000));
+
+    execute_msgs(
         &mut deps,
         &[(
             info!("bob"),
@@ -1030,97 +1600,285 @@ fn advance() {
         )],
     );
 
-    check(
-        &response,
-        expect![[r#"
-            (
-              messages: [
-                (
-                  id: 0,
-                  msg: wasm(execute(
-                    contract_addr: "synthetic_mint",
-                    msg: "eyJtaW50Ijp7InN5bnRoZXRpYyI6InN5bnRoZXRpY19hc3NldCIsImFtb3VudCI6IjUwMCIsInJlY2lwaWVudCI6ImJvYiJ9fQ==",
-                    funds: [],
-                  )),
-                  gas_limit: None,
-                  reply_on: never,
-                ),
-              ],
-              attributes: [
-                (
-                  key: "kind",
-                  value: "advance",
-                ),
-                (
-                  key: "vault",
-                  value: "vault",
-                ),
-                (
-                  key: "account",
-                  value: "bob",
-                ),
-                (
-                  key: "amount",
-                  value: "500",
-                ),
-                (
-                  key: "account_debt",
-                  value: "500",
-                ),
-              ],
-              events: [],
-              data: None,
-            )"#]],
-    );
-
-    let mint_msg: Vec<_> = response
-        .messages
-        .into_iter()
-        .filter_map(|m| match m.msg {
-            cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
-                from_json::<MintExecuteMsg>(msg).ok()
-            }
-            _ => None,
-        })
-        .collect();
-
-    check(
-        mint_msg,
-        expect![[r#"
-            [
-              mint(
-                synthetic: "synthetic_asset",
-                amount: "500",
-                recipient: "bob",
-              ),
-            ]"#]],
+    // the withdrawal is only possible once the repayment has cleared the debt
+    let response = execute_msgs(
+        &mut deps,
+        &[(
+            info!("bob", 500, SYNTHETIC_ASSET),
+            HubExecuteMsg::from(HubUserMsg::Batch {
+                actions: vec![
+                    HubUserMsg::RepaySynthetic {
+                        vault: VAULT.into(),
+                    },
+                    HubUserMsg::Withdraw {
+                        vault: VAULT.into(),
+                        amount: 1_000u128.into(),
+                    },
+                ],
+            }),
+        )],
     );
 
     check(
-        query(
-            deps.as_ref(),
-            mock_env(),
-            HubQueryMsg::Position {
-                account: "bob".into(),
-                vault: VAULT.into(),
-            }
-            .into(),
-        )
-        .map(into_response::<PositionResponse>)
-        .unwrap(),
+        &response,
         expect![[r#"
+        (
+          messages: [
             (
-              collateral: "1000",
-              debt: "500",
-              credit: "0",
-              sum_payment_ratio: "0.0",
-              vault_loss_detected: false,
-            )"#]],
-    );
-}
+              id: 0,
+              msg: wasm(execute(
+                contract_addr: "synthetic_mint",
+                msg: "eyJidXJuIjp7fX0=",
+                funds: [
+                  (
+                    denom: "synthetic_asset",
+                    amount: "500",
+                  ),
+                ],
+              )),
+              gas_limit: None,
+              reply_on: never,
+            ),
+            (
+              id: 0,
+              msg: wasm(execute(
+                contract_addr: "vault",
+                msg: "eyJyZWRlZW0iOnsicmVjaXBpZW50IjoiYm9iIn19",
+                funds: [
+                  (
+                    denom: "vault_share",
+                    amount: "1000000000000000000000",
+                  ),
+                ],
+              )),
+              gas_limit: None,
+              reply_on: never,
+            ),
+          ],
+          attributes: [
+            (
+              key: "kind",
+              value: "batch",
+            ),
+            (
+              key: "account",
+              value: "bob",
+            ),
+            (
+              key: "kind",
+              value: "repay_synthetic",
+            ),
+            (
+              key: "vault",
+              value: "vault",
+            ),
+            (
+              key: "account",
+              value: "bob",
+            ),
+            (
+              key: "amount",
+              value: "500",
+            ),
+            (
+              key: "account_debt",
+              value: "0",
+            ),
+            (
+              key: "position_seq",
+              value: "3",
+            ),
+            (
+              key: "kind",
+              value: "withdraw",
+            ),
+            (
+              key: "vault",
+              value: "vault",
+            ),
+            (
+              key: "account",
+              value: "bob",
+            ),
+            (
+              key: "amount",
+              value: "1000",
+            ),
+            (
+              key: "collateral_shares",
+              value: "0",
+            ),
+            (
+              key: "collateral_balance",
+              value: "0",
+            ),
+            (
+              key: "account_collateral",
+              value: "0",
+            ),
+            (
+              key: "position_seq",
+              value: "4",
+            ),
+            (
+              key: "redeem_shares",
+              value: "1000000000000000000000",
+            ),
+          ],
+          events: [],
+          data: None,
+        )"#]],
+    );
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::Position {
+                account: "bob".into(),
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<PositionResponse>)
+        .unwrap(),
+        expect![[r#"
+            (
+              collateral: "0",
+              debt: "0",
+              credit: "0",
+              sum_payment_ratio: "0.0",
+              vault_loss_detected: false,
+              position_seq: 4,
+            )"#]],
+    );
+}
 
 #[test]
-fn advance_on_behalf() {
+fn batch_enable_checks() {
+    let mut deps = init_with_registered_vault();
+
+    execute_msgs(
+        &mut deps,
+        &[(
+            info!("creator"),
+            HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+                vault: VAULT.into(),
+                enabled: true,
+            }),
+        )],
+    );
+
+    let batch_err = |deps: &mut MockDeps, info: MessageInfo, actions: Vec<HubUserMsg>| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            HubExecuteMsg::from(HubUserMsg::Batch { actions }).into(),
+        )
+        .unwrap_err()
+        .to_string()
+    };
+
+    // an action failing its checks fails the whole batch
+    check(
+        batch_err(
+            &mut deps,
+            info!("bob"),
+            vec![
+                HubUserMsg::Evaluate {
+                    vault: VAULT.into(),
+                },
+                HubUserMsg::Advance {
+                    vault: VAULT.into(),
+                    amount: 100u128.into(),
+                },
+            ],
+        ),
+        expect![[r#""advance disabled""#]],
+    );
+
+    check(
+        batch_err(&mut deps, info!("bob"), vec![]),
+        expect![[r#""empty batch""#]],
+    );
+
+    check(
+        batch_err(
+            &mut deps,
+            info!("bob"),
+            vec![HubUserMsg::Batch { actions: vec![] }],
+        ),
+        expect![[r#""batches cannot be nested""#]],
+    );
+
+    // the deposit only completes in the reply, so nothing may follow it
+    check(
+        batch_err(
+            &mut deps,
+            info!("bob", 1_000),
+            vec![
+                HubUserMsg::Deposit {
+                    vault: VAULT.into(),
+                },
+                HubUserMsg::Evaluate {
+                    vault: VAULT.into(),
+                },
+            ],
+        ),
+        expect![[r#""only the last action in a batch may await a vault deposit: deposit""#]],
+    );
+
+    check(
+        batch_err(
+            &mut deps,
+            info!("bob", 1_000),
+            vec![
+                HubUserMsg::AbsorbShares {
+                    vault: VAULT.into(),
+                },
+                HubUserMsg::Deposit {
+                    vault: VAULT.into(),
+                },
+            ],
+        ),
+        expect![[r#""at most one action in a batch may take the attached funds""#]],
+    );
+
+    check(
+        batch_err(
+            &mut deps,
+            info!("bob", 1_000),
+            vec![HubUserMsg::Evaluate {
+                vault: VAULT.into(),
+            }],
+        ),
+        expect![[r#""attached funds are not taken by any action in the batch""#]],
+    );
+
+    // a deposit may end the batch
+    let response = execute_msgs(
+        &mut deps,
+        &[(
+            info!("bob", 1_000),
+            HubExecuteMsg::from(HubUserMsg::Batch {
+                actions: vec![
+                    HubUserMsg::Evaluate {
+                        vault: VAULT.into(),
+                    },
+                    HubUserMsg::Deposit {
+                        vault: VAULT.into(),
+                    },
+                ],
+            }),
+        )],
+    );
+
+    check(response.messages.len(), expect!["1"]);
+}
+
+#[test]
+fn advance() {
     let mut deps = init_with_registered_vault();
 
     execute_msgs(
@@ -1140,16 +1898,6 @@ fn advance_on_behalf() {
                     enabled: true,
                 }),
             ),
-            (
-                info!("creator"),
-                HubExecuteMsg::from(HubAdminMsg::SetProxyConfig {
-                    vault: VAULT.into(),
-                    deposit: None,
-                    advance: Some("advance_proxy".into()),
-                    redeem: None,
-                    mint: None,
-                }),
-            ),
             (
                 info!("bob", 1_000),
                 HubExecuteMsg::from(HubUserMsg::Deposit {
@@ -1159,11 +1907,13 @@ fn advance_on_behalf() {
         ],
     );
 
+    let reply_id = last_reply_id(&deps);
+
     reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -1175,11 +1925,10 @@ fn advance_on_behalf() {
     let response = execute_msgs(
         &mut deps,
         &[(
-            info!("advance_proxy"),
-            HubExecuteMsg::from(HubUserMsg::AdvanceOnBehalf {
+            info!("bob"),
+            HubExecuteMsg::from(HubUserMsg::Advance {
                 vault: VAULT.into(),
                 amount: 500u128.into(),
-                behalf_of: "bob".into(),
             }),
         )],
     );
@@ -1221,6 +1970,10 @@ fn advance_on_behalf() {
                   key: "account_debt",
                   value: "500",
                 ),
+                (
+                  key: "position_seq",
+                  value: "2",
+                ),
               ],
               events: [],
               data: None,
@@ -1269,12 +2022,13 @@ fn advance_on_behalf() {
               credit: "0",
               sum_payment_ratio: "0.0",
               vault_loss_detected: false,
+              position_seq: 2,
             )"#]],
     );
 }
 
 #[test]
-fn withdraw() {
+fn advance_on_behalf() {
     let mut deps = init_with_registered_vault();
 
     execute_msgs(
@@ -1287,6 +2041,23 @@ fn withdraw() {
                     enabled: true,
                 }),
             ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetProxyConfig {
+                    vault: VAULT.into(),
+                    deposit: None,
+                    advance: Some("advance_proxy".into()),
+                    redeem: None,
+                    mint: None,
+                }),
+            ),
             (
                 info!("bob", 1_000),
                 HubExecuteMsg::from(HubUserMsg::Deposit {
@@ -1296,11 +2067,13 @@ fn withdraw() {
         ],
     );
 
+    let reply_id = last_reply_id(&deps);
+
     reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -1309,15 +2082,14 @@ fn withdraw() {
     )
     .unwrap();
 
-    update_querier(&mut deps, 1_000, shares_amount(1_000));
-
     let response = execute_msgs(
         &mut deps,
         &[(
-            info!("bob"),
-            HubExecuteMsg::from(HubUserMsg::Withdraw {
+            info!("advance_proxy"),
+            HubExecuteMsg::from(HubUserMsg::AdvanceOnBehalf {
                 vault: VAULT.into(),
                 amount: 500u128.into(),
+                behalf_of: "bob".into(),
             }),
         )],
     );
@@ -1330,14 +2102,9 @@ fn withdraw() {
                 (
                   id: 0,
                   msg: wasm(execute(
-                    contract_addr: "vault",
-                    msg: "eyJyZWRlZW0iOnsicmVjaXBpZW50IjoiYm9iIn19",
-                    funds: [
-                      (
-                        denom: "vault_share",
-                        amount: "500000000000000000000",
-                      ),
-                    ],
+                    contract_addr: "synthetic_mint",
+                    msg: "eyJtaW50Ijp7InN5bnRoZXRpYyI6InN5bnRoZXRpY19hc3NldCIsImFtb3VudCI6IjUwMCIsInJlY2lwaWVudCI6ImJvYiJ9fQ==",
+                    funds: [],
                   )),
                   gas_limit: None,
                   reply_on: never,
@@ -1346,7 +2113,7 @@ fn withdraw() {
               attributes: [
                 (
                   key: "kind",
-                  value: "withdraw",
+                  value: "advance",
                 ),
                 (
                   key: "vault",
@@ -1361,20 +2128,12 @@ fn withdraw() {
                   value: "500",
                 ),
                 (
-                  key: "collateral_shares",
-                  value: "500000000000000000000",
-                ),
-                (
-                  key: "collateral_balance",
-                  value: "500",
-                ),
-                (
-                  key: "account_collateral",
+                  key: "account_debt",
                   value: "500",
                 ),
                 (
-                  key: "redeem_shares",
-                  value: "500000000000000000000",
+                  key: "position_seq",
+                  value: "2",
                 ),
               ],
               events: [],
@@ -1382,22 +2141,24 @@ fn withdraw() {
             )"#]],
     );
 
-    let vault_msg: Vec<_> = response
+    let mint_msg: Vec<_> = response
         .messages
         .into_iter()
         .filter_map(|m| match m.msg {
             cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
-                from_json::<VaultExecuteMsg>(msg).ok()
+                from_json::<MintExecuteMsg>(msg).ok()
             }
             _ => None,
         })
         .collect();
 
     check(
-        vault_msg,
+        mint_msg,
         expect![[r#"
             [
-              redeem(
+              mint(
+                synthetic: "synthetic_asset",
+                amount: "500",
                 recipient: "bob",
               ),
             ]"#]],
@@ -1407,66 +2168,28 @@ fn withdraw() {
         query(
             deps.as_ref(),
             mock_env(),
-            HubQueryMsg::VaultMetadata {
+            HubQueryMsg::Position {
+                account: "bob".into(),
                 vault: VAULT.into(),
             }
             .into(),
         )
-        .map(into_response::<VaultMetadata>)
+        .map(into_response::<PositionResponse>)
         .unwrap(),
         expect![[r#"
             (
-              vault: "vault",
-              synthetic: "synthetic_asset",
-              deposit_enabled: true,
-              advance_enabled: false,
-              max_ltv_bps: 5000,
-              collateral_yield_fee_bps: 1000,
-              reserve_yield_fee_bps: 10000,
-              fixed_advance_fee_bps: 25,
-              advance_fee_recipient: None,
-              advance_fee_oracle: None,
-              collateral_balance: "500",
-              collateral_shares: "500000000000000000000",
-              reserve_balance: "0",
-              reserve_shares: "0",
-              treasury_shares: "0",
-              amo: None,
-              amo_allocation: 0,
-              amo_shares: "0",
-              sum_payment_ratio: None,
-              deposit_proxy: None,
-              advance_proxy: None,
-              mint_proxy: None,
-              redeem_proxy: None,
-            )"#]],
-    );
-
-    check(
-        query(
-            deps.as_ref(),
-            mock_env(),
-            HubQueryMsg::Position {
-                account: "bob".into(),
-                vault: VAULT.into(),
-            }
-            .into(),
-        )
-        .map(into_response::<PositionResponse>)
-        .unwrap(),
-        expect![[r#"
-            (
-              collateral: "500",
-              debt: "0",
+              collateral: "1000",
+              debt: "500",
               credit: "0",
               sum_payment_ratio: "0.0",
               vault_loss_detected: false,
+              position_seq: 2,
             )"#]],
     );
 }
 
 #[test]
-fn self_liquidate() {
+fn position_operator() {
     let mut deps = init_with_registered_vault();
 
     execute_msgs(
@@ -1495,11 +2218,13 @@ fn self_liquidate() {
         ],
     );
 
+    let reply_id = last_reply_id(&deps);
+
     reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -1508,48 +2233,286 @@ fn self_liquidate() {
     )
     .unwrap();
 
-    update_querier(&mut deps, 1_000, shares_amount(1_000));
+    let response = execute_msgs(
+        &mut deps,
+        &[(
+            info!("bob"),
+            HubExecuteMsg::from(HubUserMsg::SetPositionOperator {
+                vault: VAULT.into(),
+                operator: Some("operator".into()),
+                max_advance: Some(300u128.into()),
+                allow_withdraw: false,
+            }),
+        )],
+    );
+
+    check(
+        &response.attributes,
+        expect![[r#"
+        [
+          (
+            key: "kind",
+            value: "set_position_operator",
+          ),
+          (
+            key: "vault",
+            value: "vault",
+          ),
+          (
+            key: "account",
+            value: "bob",
+          ),
+          (
+            key: "operator",
+            value: "operator",
+          ),
+        ]"#]],
+    );
 
     let response = execute_msgs(
         &mut deps,
-        &[
+        &[(
+            info!("operator"),
+            HubExecuteMsg::from(HubUserMsg::AdvanceAsOperator {
+                vault: VAULT.into(),
+                amount: 200u128.into(),
+                owner: "bob".into(),
+            }),
+        )],
+    );
+
+    // the advance is minted to the position owner
+    check(
+        &response,
+        expect![[r#"
+        (
+          messages: [
             (
-                info!("bob"),
-                HubExecuteMsg::from(HubUserMsg::Advance {
-                    vault: VAULT.into(),
-                    amount: 500u128.into(),
-                }),
+              id: 0,
+              msg: wasm(execute(
+                contract_addr: "synthetic_mint",
+                msg: "eyJtaW50Ijp7InN5bnRoZXRpYyI6InN5bnRoZXRpY19hc3NldCIsImFtb3VudCI6IjIwMCIsInJlY2lwaWVudCI6ImJvYiJ9fQ==",
+                funds: [],
+              )),
+              gas_limit: None,
+              reply_on: never,
             ),
+          ],
+          attributes: [
             (
-                info!("bob", 600, SYNTHETIC_ASSET),
-                HubExecuteMsg::from(HubUserMsg::RepaySynthetic {
+              key: "kind",
+              value: "advance",
+            ),
+            (
+              key: "vault",
+              value: "vault",
+            ),
+            (
+              key: "account",
+              value: "bob",
+            ),
+            (
+              key: "operator",
+              value: "operator",
+            ),
+            (
+              key: "amount",
+              value: "200",
+            ),
+            (
+              key: "account_debt",
+              value: "200",
+            ),
+            (
+              key: "position_seq",
+              value: "2",
+            ),
+          ],
+          events: [],
+          data: None,
+        )"#]],
+    );
+
+    let operator_err = |deps: &mut MockDeps, info: MessageInfo, msg: HubUserMsg| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            HubExecuteMsg::from(msg).into(),
+        )
+        .unwrap_err()
+        .to_string()
+    };
+
+    // the advance limit is cumulative
+    check(
+        operator_err(
+            &mut deps,
+            info!("operator"),
+            HubUserMsg::AdvanceAsOperator {
+                vault: VAULT.into(),
+                amount: 101u128.into(),
+                owner: "bob".into(),
+            },
+        ),
+        expect![[r#""operator advance limit exceeded""#]],
+    );
+
+    check(
+        operator_err(
+            &mut deps,
+            info!("operator"),
+            HubUserMsg::WithdrawAsOperator {
+                vault: VAULT.into(),
+                amount: 100u128.into(),
+                owner: "bob".into(),
+            },
+        ),
+        expect![[r#""operator not allowed to withdraw""#]],
+    );
+
+    let response = execute_msgs(
+        &mut deps,
+        &[(
+            info!("operator", 100, SYNTHETIC_ASSET),
+            HubExecuteMsg::from(HubUserMsg::RepaySyntheticAsOperator {
+                vault: VAULT.into(),
+                owner: "bob".into(),
+            }),
+        )],
+    );
+
+    check(
+        &response.attributes,
+        expect![[r#"
+        [
+          (
+            key: "kind",
+            value: "repay_synthetic",
+          ),
+          (
+            key: "vault",
+            value: "vault",
+          ),
+          (
+            key: "account",
+            value: "bob",
+          ),
+          (
+            key: "operator",
+            value: "operator",
+          ),
+          (
+            key: "amount",
+            value: "100",
+          ),
+          (
+            key: "account_debt",
+            value: "100",
+          ),
+          (
+            key: "position_seq",
+            value: "3",
+          ),
+        ]"#]],
+    );
+
+    // revoking takes effect immediately
+    execute_msgs(
+        &mut deps,
+        &[(
+            info!("bob"),
+            HubExecuteMsg::from(HubUserMsg::SetPositionOperator {
+                vault: VAULT.into(),
+                operator: None,
+                max_advance: None,
+                allow_withdraw: false,
+            }),
+        )],
+    );
+
+    check(
+        operator_err(
+            &mut deps,
+            info!("operator", 100, SYNTHETIC_ASSET),
+            HubUserMsg::RepaySyntheticAsOperator {
+                vault: VAULT.into(),
+                owner: "bob".into(),
+            },
+        ),
+        expect![[r#""unauthorized""#]],
+    );
+
+    check(
+        operator_err(
+            &mut deps,
+            info!("operator"),
+            HubUserMsg::AdvanceAsOperator {
+                vault: VAULT.into(),
+                amount: 1u128.into(),
+                owner: "bob".into(),
+            },
+        ),
+        expect![[r#""unauthorized""#]],
+    );
+}
+
+#[test]
+fn withdraw() {
+    let mut deps = init_with_registered_vault();
+
+    execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
                     vault: VAULT.into(),
+                    enabled: true,
                 }),
             ),
             (
-                info!("bob"),
-                HubExecuteMsg::from(HubUserMsg::SelfLiquidate {
+                info!("bob", 1_000),
+                HubExecuteMsg::from(HubUserMsg::Deposit {
                     vault: VAULT.into(),
                 }),
             ),
         ],
     );
 
+    let reply_id = last_reply_id(&deps);
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_id,
+            1_000,
+            shares_amount(1_000),
+            shares_amount(1_000),
+            1_000,
+        ),
+    )
+    .unwrap();
+
+    update_querier(&mut deps, 1_000, shares_amount(1_000));
+
+    let response = execute_msgs(
+        &mut deps,
+        &[(
+            info!("bob"),
+            HubExecuteMsg::from(HubUserMsg::Withdraw {
+                vault: VAULT.into(),
+                amount: 500u128.into(),
+            }),
+        )],
+    );
+
     check(
         &response,
         expect![[r#"
             (
               messages: [
-                (
-                  id: 0,
-                  msg: wasm(execute(
-                    contract_addr: "synthetic_mint",
-                    msg: "eyJtaW50Ijp7InN5bnRoZXRpYyI6InN5bnRoZXRpY19hc3NldCIsImFtb3VudCI6IjEwMCIsInJlY2lwaWVudCI6ImJvYiJ9fQ==",
-                    funds: [],
-                  )),
-                  gas_limit: None,
-                  reply_on: never,
-                ),
                 (
                   id: 0,
                   msg: wasm(execute(
@@ -1558,7 +2521,7 @@ fn self_liquidate() {
                     funds: [
                       (
                         denom: "vault_share",
-                        amount: "1000000000000000000000",
+                        amount: "500000000000000000000",
                       ),
                     ],
                   )),
@@ -1569,7 +2532,7 @@ fn self_liquidate() {
               attributes: [
                 (
                   key: "kind",
-                  value: "self_liquidate",
+                  value: "withdraw",
                 ),
                 (
                   key: "vault",
@@ -1579,25 +2542,29 @@ fn self_liquidate() {
                   key: "account",
                   value: "bob",
                 ),
+                (
+                  key: "amount",
+                  value: "500",
+                ),
                 (
                   key: "collateral_shares",
-                  value: "0",
+                  value: "500000000000000000000",
                 ),
                 (
                   key: "collateral_balance",
-                  value: "0",
+                  value: "500",
                 ),
                 (
                   key: "account_collateral",
-                  value: "0",
+                  value: "500",
                 ),
                 (
-                  key: "account_credit",
-                  value: "0",
+                  key: "position_seq",
+                  value: "2",
                 ),
                 (
                   key: "redeem_shares",
-                  value: "1000000000000000000000",
+                  value: "500000000000000000000",
                 ),
               ],
               events: [],
@@ -1606,22 +2573,11 @@ fn self_liquidate() {
     );
 
     let vault_msg: Vec<_> = response
-        .messages
-        .iter()
-        .filter_map(|m| match &m.msg {
-            cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
-                from_json::<VaultExecuteMsg>(msg).ok()
-            }
-            _ => None,
-        })
-        .collect();
-
-    let mint_msg: Vec<_> = response
         .messages
         .into_iter()
         .filter_map(|m| match m.msg {
             cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
-                from_json::<MintExecuteMsg>(msg).ok()
+                from_json::<VaultExecuteMsg>(msg).ok()
             }
             _ => None,
         })
@@ -1637,18 +2593,6 @@ fn self_liquidate() {
             ]"#]],
     );
 
-    check(
-        mint_msg,
-        expect![[r#"
-            [
-              mint(
-                synthetic: "synthetic_asset",
-                amount: "100",
-                recipient: "bob",
-              ),
-            ]"#]],
-    );
-
     check(
         query(
             deps.as_ref(),
@@ -1665,26 +2609,43 @@ fn self_liquidate() {
               vault: "vault",
               synthetic: "synthetic_asset",
               deposit_enabled: true,
-              advance_enabled: true,
+              advance_enabled: false,
               max_ltv_bps: 5000,
               collateral_yield_fee_bps: 1000,
               reserve_yield_fee_bps: 10000,
               fixed_advance_fee_bps: 25,
               advance_fee_recipient: None,
               advance_fee_oracle: None,
-              collateral_balance: "0",
-              collateral_shares: "0",
+              oracle_fee_cache_secs: None,
+              collateral_price_oracle: None,
+              min_collateral: None,
+              min_debt: None,
+              account_debt_cap: None,
+              reserve_floor: None,
+              redeem_fee_schedule: None,
+              loss_tolerance_bps: 0,
+              loss_socialisation_enabled: false,
+              min_deposit: "1",
+              collateral_balance: "500",
+              collateral_shares: "500000000000000000000",
               reserve_balance: "0",
               reserve_shares: "0",
               treasury_shares: "0",
               amo: None,
               amo_allocation: 0,
               amo_shares: "0",
+              amo_shares_returned: "0",
+              amo_deposits_returned: "0",
               sum_payment_ratio: None,
               deposit_proxy: None,
               advance_proxy: None,
               mint_proxy: None,
               redeem_proxy: None,
+              interface_version: Some("1.0.0"),
+              interface_features: [
+                "donate",
+                "claim_all",
+              ],
             )"#]],
     );
 
@@ -1702,17 +2663,18 @@ fn self_liquidate() {
         .unwrap(),
         expect![[r#"
             (
-              collateral: "0",
+              collateral: "500",
               debt: "0",
               credit: "0",
               sum_payment_ratio: "0.0",
               vault_loss_detected: false,
+              position_seq: 2,
             )"#]],
     );
 }
 
 #[test]
-fn convert_credit() {
+fn withdraw_all() {
     let mut deps = init_with_registered_vault();
 
     execute_msgs(
@@ -1734,11 +2696,13 @@ fn convert_credit() {
         ],
     );
 
+    let reply_id = last_reply_id(&deps);
+
     reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -1747,115 +2711,76 @@ fn convert_credit() {
     )
     .unwrap();
 
-    update_querier(&mut deps, 1_100, shares_amount(1_000));
+    update_querier(&mut deps, 1_000, shares_amount(1_000));
 
     let response = execute_msgs(
         &mut deps,
         &[(
             info!("bob"),
-            HubExecuteMsg::from(HubUserMsg::ConvertCredit {
+            HubExecuteMsg::from(HubUserMsg::WithdrawAll {
                 vault: VAULT.into(),
-                amount: 89u128.into(),
             }),
         )],
     );
 
     check(
-        response,
-        expect![[r#"
-            (
-              messages: [],
-              attributes: [
-                (
-                  key: "kind",
-                  value: "convert_credit",
-                ),
-                (
-                  key: "vault",
-                  value: "vault",
-                ),
-                (
-                  key: "account",
-                  value: "bob",
-                ),
-                (
-                  key: "amount",
-                  value: "89",
-                ),
-                (
-                  key: "collateral_shares",
-                  value: "990000000000000000000",
-                ),
-                (
-                  key: "collateral_balance",
-                  value: "1088",
-                ),
-                (
-                  key: "reserve_shares",
-                  value: "909090909090909092",
-                ),
-                (
-                  key: "reserve_balance",
-                  value: "1",
-                ),
-                (
-                  key: "treasury_shares",
-                  value: "9090909090909090908",
-                ),
-                (
-                  key: "spr",
-                  value: "0.08999999999999999999999999999999",
-                ),
-                (
-                  key: "account_collateral",
-                  value: "1088",
-                ),
-              ],
-              events: [],
-              data: None,
-            )"#]],
-    );
-
-    check(
-        query(
-            deps.as_ref(),
-            mock_env(),
-            HubQueryMsg::VaultMetadata {
-                vault: VAULT.into(),
-            }
-            .into(),
-        )
-        .map(into_response::<VaultMetadata>)
-        .unwrap(),
+        &response,
         expect![[r#"
+        (
+          messages: [
             (
-              vault: "vault",
-              synthetic: "synthetic_asset",
-              deposit_enabled: true,
-              advance_enabled: false,
-              max_ltv_bps: 5000,
-              collateral_yield_fee_bps: 1000,
-              reserve_yield_fee_bps: 10000,
-              fixed_advance_fee_bps: 25,
-              advance_fee_recipient: None,
-              advance_fee_oracle: None,
-              collateral_balance: "1088",
-              collateral_shares: "990000000000000000000",
-              reserve_balance: "1",
-              reserve_shares: "909090909090909092",
-              treasury_shares: "9090909090909090908",
-              amo: None,
-              amo_allocation: 0,
-              amo_shares: "0",
-              sum_payment_ratio: Some((
-                ratio: "0.08999999999999999999999999999999",
-                timestamp: 1571797419,
+              id: 0,
+              msg: wasm(execute(
+                contract_addr: "vault",
+                msg: "eyJyZWRlZW0iOnsicmVjaXBpZW50IjoiYm9iIn19",
+                funds: [
+                  (
+                    denom: "vault_share",
+                    amount: "1000000000000000000000",
+                  ),
+                ],
               )),
-              deposit_proxy: None,
-              advance_proxy: None,
-              mint_proxy: None,
-              redeem_proxy: None,
-            )"#]],
+              gas_limit: None,
+              reply_on: never,
+            ),
+          ],
+          attributes: [
+            (
+              key: "kind",
+              value: "withdraw_all",
+            ),
+            (
+              key: "vault",
+              value: "vault",
+            ),
+            (
+              key: "account",
+              value: "bob",
+            ),
+            (
+              key: "collateral_shares",
+              value: "0",
+            ),
+            (
+              key: "collateral_balance",
+              value: "0",
+            ),
+            (
+              key: "account_collateral",
+              value: "0",
+            ),
+            (
+              key: "position_seq",
+              value: "2",
+            ),
+            (
+              key: "redeem_shares",
+              value: "1000000000000000000000",
+            ),
+          ],
+          events: [],
+          data: None,
+        )"#]],
     );
 
     check(
@@ -1872,17 +2797,17 @@ fn convert_credit() {
         .unwrap(),
         expect![[r#"
             (
-              collateral: "1088",
+              collateral: "0",
               debt: "0",
               credit: "0",
-              sum_payment_ratio: "0.08999999999999999999999999999999",
+              sum_payment_ratio: "0.0",
               vault_loss_detected: false,
+              position_seq: 2,
             )"#]],
     );
 }
-
 #[test]
-fn redeem() {
+fn self_liquidate() {
     let mut deps = init_with_registered_vault();
 
     execute_msgs(
@@ -1911,11 +2836,13 @@ fn redeem() {
         ],
     );
 
+    let reply_id = last_reply_id(&deps);
+
     reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -1924,7 +2851,7 @@ fn redeem() {
     )
     .unwrap();
 
-    update_querier(&mut deps, 1_100, shares_amount(1_000));
+    update_querier(&mut deps, 1_000, shares_amount(1_000));
 
     let response = execute_msgs(
         &mut deps,
@@ -1933,12 +2860,18 @@ fn redeem() {
                 info!("bob"),
                 HubExecuteMsg::from(HubUserMsg::Advance {
                     vault: VAULT.into(),
-                    amount: 200u128.into(),
+                    amount: 500u128.into(),
                 }),
             ),
             (
-                info!("bob", 90, SYNTHETIC_ASSET),
-                HubExecuteMsg::from(HubUserMsg::Redeem {
+                info!("bob", 600, SYNTHETIC_ASSET),
+                HubExecuteMsg::from(HubUserMsg::RepaySynthetic {
+                    vault: VAULT.into(),
+                }),
+            ),
+            (
+                info!("bob"),
+                HubExecuteMsg::from(HubUserMsg::SelfLiquidate {
                     vault: VAULT.into(),
                 }),
             ),
@@ -1953,14 +2886,9 @@ fn redeem() {
                 (
                   id: 0,
                   msg: wasm(execute(
-                    contract_addr: "vault",
-                    msg: "eyJyZWRlZW0iOnsicmVjaXBpZW50IjoiYm9iIn19",
-                    funds: [
-                      (
-                        denom: "vault_share",
-                        amount: "81818181818181818181",
-                      ),
-                    ],
+                    contract_addr: "synthetic_mint",
+                    msg: "eyJtaW50Ijp7InN5bnRoZXRpYyI6InN5bnRoZXRpY19hc3NldCIsImFtb3VudCI6IjEwMCIsInJlY2lwaWVudCI6ImJvYiJ9fQ==",
+                    funds: [],
                   )),
                   gas_limit: None,
                   reply_on: never,
@@ -1968,12 +2896,12 @@ fn redeem() {
                 (
                   id: 0,
                   msg: wasm(execute(
-                    contract_addr: "synthetic_mint",
-                    msg: "eyJidXJuIjp7fX0=",
+                    contract_addr: "vault",
+                    msg: "eyJyZWRlZW0iOnsicmVjaXBpZW50IjoiYm9iIn19",
                     funds: [
                       (
-                        denom: "synthetic_asset",
-                        amount: "90",
+                        denom: "vault_share",
+                        amount: "1000000000000000000000",
                       ),
                     ],
                   )),
@@ -1984,31 +2912,39 @@ fn redeem() {
               attributes: [
                 (
                   key: "kind",
-                  value: "redeem",
+                  value: "self_liquidate",
                 ),
                 (
                   key: "vault",
                   value: "vault",
                 ),
                 (
-                  key: "recipient",
+                  key: "account",
                   value: "bob",
                 ),
                 (
-                  key: "amount",
-                  value: "90",
+                  key: "collateral_shares",
+                  value: "0",
                 ),
                 (
-                  key: "reserve_shares",
-                  value: "1",
+                  key: "collateral_balance",
+                  value: "0",
                 ),
                 (
-                  key: "reserve_balance",
+                  key: "account_collateral",
                   value: "0",
                 ),
+                (
+                  key: "account_credit",
+                  value: "0",
+                ),
+                (
+                  key: "position_seq",
+                  value: "4",
+                ),
                 (
                   key: "redeem_shares",
-                  value: "81818181818181818181",
+                  value: "1000000000000000000000",
                 ),
               ],
               events: [],
@@ -2052,7 +2988,11 @@ fn redeem() {
         mint_msg,
         expect![[r#"
             [
-              burn(),
+              mint(
+                synthetic: "synthetic_asset",
+                amount: "100",
+                recipient: "bob",
+              ),
             ]"#]],
     );
 
@@ -2079,28 +3019,65 @@ fn redeem() {
               fixed_advance_fee_bps: 25,
               advance_fee_recipient: None,
               advance_fee_oracle: None,
-              collateral_balance: "1000",
-              collateral_shares: "909090909090909090910",
+              oracle_fee_cache_secs: None,
+              collateral_price_oracle: None,
+              min_collateral: None,
+              min_debt: None,
+              account_debt_cap: None,
+              reserve_floor: None,
+              redeem_fee_schedule: None,
+              loss_tolerance_bps: 0,
+              loss_socialisation_enabled: false,
+              min_deposit: "1",
+              collateral_balance: "0",
+              collateral_shares: "0",
               reserve_balance: "0",
-              reserve_shares: "1",
-              treasury_shares: "9090909090909090908",
+              reserve_shares: "0",
+              treasury_shares: "0",
               amo: None,
               amo_allocation: 0,
               amo_shares: "0",
-              sum_payment_ratio: Some((
-                ratio: "0.08999999999999999999999999999999",
-                timestamp: 1571797419,
-              )),
+              amo_shares_returned: "0",
+              amo_deposits_returned: "0",
+              sum_payment_ratio: None,
               deposit_proxy: None,
               advance_proxy: None,
               mint_proxy: None,
               redeem_proxy: None,
+              interface_version: Some("1.0.0"),
+              interface_features: [
+                "donate",
+                "claim_all",
+              ],
+            )"#]],
+    );
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::Position {
+                account: "bob".into(),
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<PositionResponse>)
+        .unwrap(),
+        expect![[r#"
+            (
+              collateral: "0",
+              debt: "0",
+              credit: "0",
+              sum_payment_ratio: "0.0",
+              vault_loss_detected: false,
+              position_seq: 4,
             )"#]],
     );
 }
 
 #[test]
-fn redeem_on_behalf() {
+fn convert_credit() {
     let mut deps = init_with_registered_vault();
 
     execute_msgs(
@@ -2113,23 +3090,6 @@ fn redeem_on_behalf() {
                     enabled: true,
                 }),
             ),
-            (
-                info!("creator"),
-                HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
-                    vault: VAULT.into(),
-                    enabled: true,
-                }),
-            ),
-            (
-                info!("creator"),
-                HubExecuteMsg::from(HubAdminMsg::SetProxyConfig {
-                    vault: VAULT.into(),
-                    deposit: None,
-                    advance: None,
-                    redeem: Some("redeem_proxy".into()),
-                    mint: None,
-                }),
-            ),
             (
                 info!("bob", 1_000),
                 HubExecuteMsg::from(HubUserMsg::Deposit {
@@ -2139,11 +3099,13 @@ fn redeem_on_behalf() {
         ],
     );
 
+    let reply_id = last_reply_id(&deps);
+
     reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -2156,8 +3118,231 @@ fn redeem_on_behalf() {
 
     let response = execute_msgs(
         &mut deps,
-        &[
-            (
+        &[(
+            info!("bob"),
+            HubExecuteMsg::from(HubUserMsg::ConvertCredit {
+                vault: VAULT.into(),
+                amount: 89u128.into(),
+            }),
+        )],
+    );
+
+    check(
+        response,
+        expect![[r#"
+            (
+              messages: [],
+              attributes: [
+                (
+                  key: "kind",
+                  value: "convert_credit",
+                ),
+                (
+                  key: "vault",
+                  value: "vault",
+                ),
+                (
+                  key: "account",
+                  value: "bob",
+                ),
+                (
+                  key: "amount",
+                  value: "89",
+                ),
+                (
+                  key: "collateral_shares",
+                  value: "990000000000000000000",
+                ),
+                (
+                  key: "collateral_balance",
+                  value: "1088",
+                ),
+                (
+                  key: "reserve_shares",
+                  value: "909090909090909092",
+                ),
+                (
+                  key: "reserve_balance",
+                  value: "1",
+                ),
+                (
+                  key: "treasury_shares",
+                  value: "9090909090909090908",
+                ),
+                (
+                  key: "spr",
+                  value: "0.08999999999999999999999999999999",
+                ),
+                (
+                  key: "account_collateral",
+                  value: "1088",
+                ),
+                (
+                  key: "position_seq",
+                  value: "2",
+                ),
+              ],
+              events: [
+                (
+                  type: "yield_fee_accrued",
+                  attributes: [
+                    (
+                      key: "vault",
+                      value: "vault",
+                    ),
+                    (
+                      key: "treasury_shares",
+                      value: "9090909090909090908",
+                    ),
+                    (
+                      key: "collateral_fee",
+                      value: "9090909090909090908",
+                    ),
+                    (
+                      key: "reserve_fee",
+                      value: "0",
+                    ),
+                  ],
+                ),
+              ],
+              data: None,
+            )"#]],
+    );
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::VaultMetadata {
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<VaultMetadata>)
+        .unwrap(),
+        expect![[r#"
+            (
+              vault: "vault",
+              synthetic: "synthetic_asset",
+              deposit_enabled: true,
+              advance_enabled: false,
+              max_ltv_bps: 5000,
+              collateral_yield_fee_bps: 1000,
+              reserve_yield_fee_bps: 10000,
+              fixed_advance_fee_bps: 25,
+              advance_fee_recipient: None,
+              advance_fee_oracle: None,
+              oracle_fee_cache_secs: None,
+              collateral_price_oracle: None,
+              min_collateral: None,
+              min_debt: None,
+              account_debt_cap: None,
+              reserve_floor: None,
+              redeem_fee_schedule: None,
+              loss_tolerance_bps: 0,
+              loss_socialisation_enabled: false,
+              min_deposit: "1",
+              collateral_balance: "1088",
+              collateral_shares: "990000000000000000000",
+              reserve_balance: "1",
+              reserve_shares: "909090909090909092",
+              treasury_shares: "9090909090909090908",
+              amo: None,
+              amo_allocation: 0,
+              amo_shares: "0",
+              amo_shares_returned: "0",
+              amo_deposits_returned: "0",
+              sum_payment_ratio: Some((
+                ratio: "0.08999999999999999999999999999999",
+                timestamp: 1571797419,
+              )),
+              deposit_proxy: None,
+              advance_proxy: None,
+              mint_proxy: None,
+              redeem_proxy: None,
+              interface_version: Some("1.0.0"),
+              interface_features: [
+                "donate",
+                "claim_all",
+              ],
+            )"#]],
+    );
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::Position {
+                account: "bob".into(),
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<PositionResponse>)
+        .unwrap(),
+        expect![[r#"
+            (
+              collateral: "1088",
+              debt: "0",
+              credit: "0",
+              sum_payment_ratio: "0.08999999999999999999999999999999",
+              vault_loss_detected: false,
+              position_seq: 2,
+            )"#]],
+    );
+}
+
+#[test]
+fn redeem() {
+    let mut deps = init_with_registered_vault();
+
+    execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
+            (
+                info!("bob", 1_000),
+                HubExecuteMsg::from(HubUserMsg::Deposit {
+                    vault: VAULT.into(),
+                }),
+            ),
+        ],
+    );
+
+    let reply_id = last_reply_id(&deps);
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_id,
+            1_000,
+            shares_amount(1_000),
+            shares_amount(1_000),
+            1_000,
+        ),
+    )
+    .unwrap();
+
+    update_querier(&mut deps, 1_100, shares_amount(1_000));
+
+    let response = execute_msgs(
+        &mut deps,
+        &[
+            (
                 info!("bob"),
                 HubExecuteMsg::from(HubUserMsg::Advance {
                     vault: VAULT.into(),
@@ -2165,10 +3350,9 @@ fn redeem_on_behalf() {
                 }),
             ),
             (
-                info!("redeem_proxy", 90, SYNTHETIC_ASSET),
-                HubExecuteMsg::from(HubUserMsg::RedeemOnBehalf {
+                info!("bob", 90, SYNTHETIC_ASSET),
+                HubExecuteMsg::from(HubUserMsg::Redeem {
                     vault: VAULT.into(),
-                    behalf_of: "bob".into(),
                 }),
             ),
         ],
@@ -2227,6 +3411,10 @@ fn redeem_on_behalf() {
                   key: "amount",
                   value: "90",
                 ),
+                (
+                  key: "unbond_start_hint",
+                  value: "1571800000",
+                ),
                 (
                   key: "reserve_shares",
                   value: "1",
@@ -2308,6 +3496,16 @@ fn redeem_on_behalf() {
               fixed_advance_fee_bps: 25,
               advance_fee_recipient: None,
               advance_fee_oracle: None,
+              oracle_fee_cache_secs: None,
+              collateral_price_oracle: None,
+              min_collateral: None,
+              min_debt: None,
+              account_debt_cap: None,
+              reserve_floor: None,
+              redeem_fee_schedule: None,
+              loss_tolerance_bps: 0,
+              loss_socialisation_enabled: false,
+              min_deposit: "1",
               collateral_balance: "1000",
               collateral_shares: "909090909090909090910",
               reserve_balance: "0",
@@ -2316,6 +3514,8 @@ fn redeem_on_behalf() {
               amo: None,
               amo_allocation: 0,
               amo_shares: "0",
+              amo_shares_returned: "0",
+              amo_deposits_returned: "0",
               sum_payment_ratio: Some((
                 ratio: "0.08999999999999999999999999999999",
                 timestamp: 1571797419,
@@ -2323,16 +3523,21 @@ fn redeem_on_behalf() {
               deposit_proxy: None,
               advance_proxy: None,
               mint_proxy: None,
-              redeem_proxy: Some("redeem_proxy"),
+              redeem_proxy: None,
+              interface_version: Some("1.0.0"),
+              interface_features: [
+                "donate",
+                "claim_all",
+              ],
             )"#]],
     );
 }
 
 #[test]
-fn mint() {
+fn redeem_with_insufficient_reserves_errs() {
     let mut deps = init_with_registered_vault();
 
-    let response = execute_msgs(
+    execute_msgs(
         &mut deps,
         &[
             (
@@ -2342,83 +3547,29 @@ fn mint() {
                     enabled: true,
                 }),
             ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
             (
                 info!("bob", 1_000),
-                HubExecuteMsg::from(HubUserMsg::Mint {
+                HubExecuteMsg::from(HubUserMsg::Deposit {
                     vault: VAULT.into(),
                 }),
             ),
         ],
     );
 
-    check(
-        &response,
-        expect![[r#"
-            (
-              messages: [
-                (
-                  id: 3,
-                  msg: wasm(execute(
-                    contract_addr: "vault",
-                    msg: "eyJkZXBvc2l0Ijp7fX0=",
-                    funds: [
-                      (
-                        denom: "vault_deposit_asset",
-                        amount: "1000",
-                      ),
-                    ],
-                  )),
-                  gas_limit: None,
-                  reply_on: success,
-                ),
-              ],
-              attributes: [
-                (
-                  key: "kind",
-                  value: "mint",
-                ),
-                (
-                  key: "vault",
-                  value: "vault",
-                ),
-                (
-                  key: "recipient",
-                  value: "bob",
-                ),
-                (
-                  key: "amount",
-                  value: "1000",
-                ),
-              ],
-              events: [],
-              data: None,
-            )"#]],
-    );
+    let reply_id = last_reply_id(&deps);
 
-    let vault_deposit_msg: Vec<_> = response
-        .messages
-        .into_iter()
-        .filter_map(|m| match m.msg {
-            cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
-                from_json::<VaultExecuteMsg>(msg).ok()
-            }
-            _ => None,
-        })
-        .collect();
-
-    check(
-        vault_deposit_msg,
-        expect![[r#"
-        [
-          deposit(),
-        ]"#]],
-    );
-
-    let response = reply(
+    reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            MINT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -2427,129 +3578,40 @@ fn mint() {
     )
     .unwrap();
 
-    check(
-        &response,
-        expect![[r#"
-        (
-          messages: [
-            (
-              id: 0,
-              msg: wasm(execute(
-                contract_addr: "synthetic_mint",
-                msg: "eyJtaW50Ijp7InN5bnRoZXRpYyI6InN5bnRoZXRpY19hc3NldCIsImFtb3VudCI6IjEwMDAiLCJyZWNpcGllbnQiOiJib2IifX0=",
-                funds: [],
-              )),
-              gas_limit: None,
-              reply_on: never,
-            ),
-          ],
-          attributes: [
-            (
-              key: "kind",
-              value: "vault_deposit_callback",
-            ),
-            (
-              key: "reason",
-              value: "mint",
-            ),
-            (
-              key: "vault",
-              value: "vault",
-            ),
-            (
-              key: "recipient",
-              value: "bob",
-            ),
-            (
-              key: "minted_shares",
-              value: "1000000000000000000000",
-            ),
-            (
-              key: "deposit_value",
-              value: "1000",
-            ),
-            (
-              key: "reserve_shares",
-              value: "1000000000000000000000",
-            ),
-            (
-              key: "reserve_balance",
-              value: "1000",
-            ),
-          ],
-          events: [],
-          data: None,
-        )"#]],
+    update_querier(&mut deps, 1_100, shares_amount(1_000));
+
+    execute_msgs(
+        &mut deps,
+        &[(
+            info!("bob"),
+            HubExecuteMsg::from(HubUserMsg::Advance {
+                vault: VAULT.into(),
+                amount: 200u128.into(),
+            }),
+        )],
     );
 
-    let mint_msg: Vec<_> = response
-        .messages
-        .into_iter()
-        .filter_map(|m| match m.msg {
-            cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
-                from_json::<MintExecuteMsg>(msg).ok()
-            }
-            _ => None,
+    // only 90 synthetics worth of yield has been paid into the reserves
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 91, SYNTHETIC_ASSET),
+        HubExecuteMsg::from(HubUserMsg::Redeem {
+            vault: VAULT.into(),
         })
-        .collect();
-
-    check(
-        mint_msg,
-        expect![[r#"
-            [
-              mint(
-                synthetic: "synthetic_asset",
-                amount: "1000",
-                recipient: "bob",
-              ),
-            ]"#]],
-    );
+        .into(),
+    )
+    .unwrap_err()
+    .to_string();
 
-    check(
-        query(
-            deps.as_ref(),
-            mock_env(),
-            HubQueryMsg::VaultMetadata {
-                vault: VAULT.into(),
-            }
-            .into(),
-        )
-        .map(into_response::<VaultMetadata>)
-        .unwrap(),
-        expect![[r#"
-            (
-              vault: "vault",
-              synthetic: "synthetic_asset",
-              deposit_enabled: true,
-              advance_enabled: false,
-              max_ltv_bps: 5000,
-              collateral_yield_fee_bps: 1000,
-              reserve_yield_fee_bps: 10000,
-              fixed_advance_fee_bps: 25,
-              advance_fee_recipient: None,
-              advance_fee_oracle: None,
-              collateral_balance: "0",
-              collateral_shares: "0",
-              reserve_balance: "1000",
-              reserve_shares: "1000000000000000000000",
-              treasury_shares: "0",
-              amo: None,
-              amo_allocation: 0,
-              amo_shares: "0",
-              sum_payment_ratio: None,
-              deposit_proxy: None,
-              advance_proxy: None,
-              mint_proxy: None,
-              redeem_proxy: None,
-            )"#]],
-    );
+    check(err, expect![[r#""insufficient reserves""#]]);
 }
 
 #[test]
-fn mint_on_behalf() {
+fn redeem_on_behalf() {
     let mut deps = init_with_registered_vault();
 
-    let response = execute_msgs(
+    execute_msgs(
         &mut deps,
         &[
             (
@@ -2559,19 +3621,62 @@ fn mint_on_behalf() {
                     enabled: true,
                 }),
             ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
             (
                 info!("creator"),
                 HubExecuteMsg::from(HubAdminMsg::SetProxyConfig {
                     vault: VAULT.into(),
                     deposit: None,
                     advance: None,
-                    redeem: None,
-                    mint: Some("mint_proxy".into()),
+                    redeem: Some("redeem_proxy".into()),
+                    mint: None,
                 }),
             ),
             (
-                info!("mint_proxy", 1_000),
-                HubExecuteMsg::from(HubUserMsg::MintOnBehalf {
+                info!("bob", 1_000),
+                HubExecuteMsg::from(HubUserMsg::Deposit {
+                    vault: VAULT.into(),
+                }),
+            ),
+        ],
+    );
+
+    let reply_id = last_reply_id(&deps);
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_id,
+            1_000,
+            shares_amount(1_000),
+            shares_amount(1_000),
+            1_000,
+        ),
+    )
+    .unwrap();
+
+    update_querier(&mut deps, 1_100, shares_amount(1_000));
+
+    let response = execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("bob"),
+                HubExecuteMsg::from(HubUserMsg::Advance {
+                    vault: VAULT.into(),
+                    amount: 200u128.into(),
+                }),
+            ),
+            (
+                info!("redeem_proxy", 90, SYNTHETIC_ASSET),
+                HubExecuteMsg::from(HubUserMsg::RedeemOnBehalf {
                     vault: VAULT.into(),
                     behalf_of: "bob".into(),
                 }),
@@ -2585,25 +3690,40 @@ fn mint_on_behalf() {
             (
               messages: [
                 (
-                  id: 3,
+                  id: 0,
                   msg: wasm(execute(
                     contract_addr: "vault",
-                    msg: "eyJkZXBvc2l0Ijp7fX0=",
+                    msg: "eyJyZWRlZW0iOnsicmVjaXBpZW50IjoiYm9iIn19",
                     funds: [
                       (
-                        denom: "vault_deposit_asset",
-                        amount: "1000",
+                        denom: "vault_share",
+                        amount: "81818181818181818181",
                       ),
                     ],
                   )),
                   gas_limit: None,
-                  reply_on: success,
+                  reply_on: never,
+                ),
+                (
+                  id: 0,
+                  msg: wasm(execute(
+                    contract_addr: "synthetic_mint",
+                    msg: "eyJidXJuIjp7fX0=",
+                    funds: [
+                      (
+                        denom: "synthetic_asset",
+                        amount: "90",
+                      ),
+                    ],
+                  )),
+                  gas_limit: None,
+                  reply_on: never,
                 ),
               ],
               attributes: [
                 (
                   key: "kind",
-                  value: "mint",
+                  value: "redeem",
                 ),
                 (
                   key: "vault",
@@ -2615,7 +3735,23 @@ fn mint_on_behalf() {
                 ),
                 (
                   key: "amount",
-                  value: "1000",
+                  value: "90",
+                ),
+                (
+                  key: "unbond_start_hint",
+                  value: "1571800000",
+                ),
+                (
+                  key: "reserve_shares",
+                  value: "1",
+                ),
+                (
+                  key: "reserve_balance",
+                  value: "0",
+                ),
+                (
+                  key: "redeem_shares",
+                  value: "81818181818181818181",
                 ),
               ],
               events: [],
@@ -2623,30 +3759,199 @@ fn mint_on_behalf() {
             )"#]],
     );
 
-    let vault_deposit_msg: Vec<_> = response
+    let vault_msg: Vec<_> = response
+        .messages
+        .iter()
+        .filter_map(|m| match &m.msg {
+            cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+                from_json::<VaultExecuteMsg>(msg).ok()
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mint_msg: Vec<_> = response
         .messages
         .into_iter()
         .filter_map(|m| match m.msg {
             cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
-                from_json::<VaultExecuteMsg>(msg).ok()
+                from_json::<MintExecuteMsg>(msg).ok()
             }
             _ => None,
         })
         .collect();
 
     check(
-        vault_deposit_msg,
+        vault_msg,
         expect![[r#"
-        [
-          deposit(),
-        ]"#]],
-    );
-
+            [
+              redeem(
+                recipient: "bob",
+              ),
+            ]"#]],
+    );
+
+    check(
+        mint_msg,
+        expect![[r#"
+            [
+              burn(),
+            ]"#]],
+    );
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::VaultMetadata {
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<VaultMetadata>)
+        .unwrap(),
+        expect![[r#"
+            (
+              vault: "vault",
+              synthetic: "synthetic_asset",
+              deposit_enabled: true,
+              advance_enabled: true,
+              max_ltv_bps: 5000,
+              collateral_yield_fee_bps: 1000,
+              reserve_yield_fee_bps: 10000,
+              fixed_advance_fee_bps: 25,
+              advance_fee_recipient: None,
+              advance_fee_oracle: None,
+              oracle_fee_cache_secs: None,
+              collateral_price_oracle: None,
+              min_collateral: None,
+              min_debt: None,
+              account_debt_cap: None,
+              reserve_floor: None,
+              redeem_fee_schedule: None,
+              loss_tolerance_bps: 0,
+              loss_socialisation_enabled: false,
+              min_deposit: "1",
+              collateral_balance: "1000",
+              collateral_shares: "909090909090909090910",
+              reserve_balance: "0",
+              reserve_shares: "1",
+              treasury_shares: "9090909090909090908",
+              amo: None,
+              amo_allocation: 0,
+              amo_shares: "0",
+              amo_shares_returned: "0",
+              amo_deposits_returned: "0",
+              sum_payment_ratio: Some((
+                ratio: "0.08999999999999999999999999999999",
+                timestamp: 1571797419,
+              )),
+              deposit_proxy: None,
+              advance_proxy: None,
+              mint_proxy: None,
+              redeem_proxy: Some("redeem_proxy"),
+              interface_version: Some("1.0.0"),
+              interface_features: [
+                "donate",
+                "claim_all",
+              ],
+            )"#]],
+    );
+}
+
+#[test]
+fn mint() {
+    let mut deps = init_with_registered_vault();
+
+    let response = execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
+            (
+                info!("bob", 1_000),
+                HubExecuteMsg::from(HubUserMsg::Mint {
+                    vault: VAULT.into(),
+                }),
+            ),
+        ],
+    );
+
+    check(
+        &response,
+        expect![[r#"
+            (
+              messages: [
+                (
+                  id: 1,
+                  msg: wasm(execute(
+                    contract_addr: "vault",
+                    msg: "eyJkZXBvc2l0Ijp7fX0=",
+                    funds: [
+                      (
+                        denom: "vault_deposit_asset",
+                        amount: "1000",
+                      ),
+                    ],
+                  )),
+                  gas_limit: None,
+                  reply_on: success,
+                ),
+              ],
+              attributes: [
+                (
+                  key: "kind",
+                  value: "mint",
+                ),
+                (
+                  key: "vault",
+                  value: "vault",
+                ),
+                (
+                  key: "recipient",
+                  value: "bob",
+                ),
+                (
+                  key: "amount",
+                  value: "1000",
+                ),
+              ],
+              events: [],
+              data: None,
+            )"#]],
+    );
+
+    let vault_deposit_msg: Vec<_> = response
+        .messages
+        .into_iter()
+        .filter_map(|m| match m.msg {
+            cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+                from_json::<VaultExecuteMsg>(msg).ok()
+            }
+            _ => None,
+        })
+        .collect();
+
+    check(
+        vault_deposit_msg,
+        expect![[r#"
+        [
+          deposit(),
+        ]"#]],
+    );
+
+    let reply_id = last_reply_id(&deps);
+
     let response = reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            MINT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -2756,6 +4061,16 @@ fn mint_on_behalf() {
               fixed_advance_fee_bps: 25,
               advance_fee_recipient: None,
               advance_fee_oracle: None,
+              oracle_fee_cache_secs: None,
+              collateral_price_oracle: None,
+              min_collateral: None,
+              min_debt: None,
+              account_debt_cap: None,
+              reserve_floor: None,
+              redeem_fee_schedule: None,
+              loss_tolerance_bps: 0,
+              loss_socialisation_enabled: false,
+              min_deposit: "1",
               collateral_balance: "0",
               collateral_shares: "0",
               reserve_balance: "1000",
@@ -2764,20 +4079,27 @@ fn mint_on_behalf() {
               amo: None,
               amo_allocation: 0,
               amo_shares: "0",
+              amo_shares_returned: "0",
+              amo_deposits_returned: "0",
               sum_payment_ratio: None,
               deposit_proxy: None,
               advance_proxy: None,
-              mint_proxy: Some("mint_proxy"),
+              mint_proxy: None,
               redeem_proxy: None,
+              interface_version: Some("1.0.0"),
+              interface_features: [
+                "donate",
+                "claim_all",
+              ],
             )"#]],
     );
 }
 
 #[test]
-fn claim_treasury() {
+fn mint_on_behalf() {
     let mut deps = init_with_registered_vault();
 
-    execute_msgs(
+    let response = execute_msgs(
         &mut deps,
         &[
             (
@@ -2789,91 +4111,61 @@ fn claim_treasury() {
             ),
             (
                 info!("creator"),
-                HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
-                    vault: VAULT.into(),
-                    enabled: true,
-                }),
-            ),
-            (
-                info!("creator"),
-                HubExecuteMsg::from(HubAdminMsg::SetTreasury {
-                    address: "treasury".into(),
-                }),
-            ),
-            (
-                info!("bob", 1_000),
-                HubExecuteMsg::from(HubUserMsg::Deposit {
-                    vault: VAULT.into(),
-                }),
-            ),
-        ],
-    );
-
-    reply(
-        deps.as_mut(),
-        mock_env(),
-        vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
-            1_000,
-            shares_amount(1_000),
-            shares_amount(1_000),
-            1_000,
-        ),
-    )
-    .unwrap();
-
-    update_querier(&mut deps, 1_100, shares_amount(1_000));
-
-    let response = execute_msgs(
-        &mut deps,
-        &[
-            (
-                info!("anyone"),
-                HubExecuteMsg::from(HubUserMsg::Evaluate {
+                HubExecuteMsg::from(HubAdminMsg::SetProxyConfig {
                     vault: VAULT.into(),
+                    deposit: None,
+                    advance: None,
+                    redeem: None,
+                    mint: Some("mint_proxy".into()),
                 }),
             ),
             (
-                info!("treasury"),
-                HubExecuteMsg::from(HubUserMsg::ClaimTreasury {
+                info!("mint_proxy", 1_000),
+                HubExecuteMsg::from(HubUserMsg::MintOnBehalf {
                     vault: VAULT.into(),
+                    behalf_of: "bob".into(),
                 }),
             ),
         ],
     );
 
     check(
-        response,
+        &response,
         expect![[r#"
             (
               messages: [
                 (
-                  id: 0,
-                  msg: bank(send(
-                    to_address: "treasury",
-                    amount: [
+                  id: 1,
+                  msg: wasm(execute(
+                    contract_addr: "vault",
+                    msg: "eyJkZXBvc2l0Ijp7fX0=",
+                    funds: [
                       (
-                        denom: "vault_share",
-                        amount: "9090909090909090908",
+                        denom: "vault_deposit_asset",
+                        amount: "1000",
                       ),
                     ],
                   )),
                   gas_limit: None,
-                  reply_on: never,
+                  reply_on: success,
                 ),
               ],
               attributes: [
                 (
                   key: "kind",
-                  value: "claim_treasury",
+                  value: "mint",
                 ),
                 (
                   key: "vault",
                   value: "vault",
                 ),
                 (
-                  key: "treasury_shares",
-                  value: "0",
+                  key: "recipient",
+                  value: "bob",
+                ),
+                (
+                  key: "amount",
+                  value: "1000",
                 ),
               ],
               events: [],
@@ -2881,51 +4173,177 @@ fn claim_treasury() {
             )"#]],
     );
 
-    check(
-        query(
-            deps.as_ref(),
-            mock_env(),
-            HubQueryMsg::VaultMetadata {
-                vault: VAULT.into(),
+    let vault_deposit_msg: Vec<_> = response
+        .messages
+        .into_iter()
+        .filter_map(|m| match m.msg {
+            cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+                from_json::<VaultExecuteMsg>(msg).ok()
             }
-            .into(),
-        )
-        .map(into_response::<VaultMetadata>)
-        .unwrap(),
+            _ => None,
+        })
+        .collect();
+
+    check(
+        vault_deposit_msg,
         expect![[r#"
-            (
-              vault: "vault",
-              synthetic: "synthetic_asset",
-              deposit_enabled: true,
-              advance_enabled: true,
+        [
+          deposit(),
+        ]"#]],
+    );
+
+    let reply_id = last_reply_id(&deps);
+
+    let response = reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_id,
+            1_000,
+            shares_amount(1_000),
+            shares_amount(1_000),
+            1_000,
+        ),
+    )
+    .unwrap();
+
+    check(
+        &response,
+        expect![[r#"
+        (
+          messages: [
+            (
+              id: 0,
+              msg: wasm(execute(
+                contract_addr: "synthetic_mint",
+                msg: "eyJtaW50Ijp7InN5bnRoZXRpYyI6InN5bnRoZXRpY19hc3NldCIsImFtb3VudCI6IjEwMDAiLCJyZWNpcGllbnQiOiJib2IifX0=",
+                funds: [],
+              )),
+              gas_limit: None,
+              reply_on: never,
+            ),
+          ],
+          attributes: [
+            (
+              key: "kind",
+              value: "vault_deposit_callback",
+            ),
+            (
+              key: "reason",
+              value: "mint",
+            ),
+            (
+              key: "vault",
+              value: "vault",
+            ),
+            (
+              key: "recipient",
+              value: "bob",
+            ),
+            (
+              key: "minted_shares",
+              value: "1000000000000000000000",
+            ),
+            (
+              key: "deposit_value",
+              value: "1000",
+            ),
+            (
+              key: "reserve_shares",
+              value: "1000000000000000000000",
+            ),
+            (
+              key: "reserve_balance",
+              value: "1000",
+            ),
+          ],
+          events: [],
+          data: None,
+        )"#]],
+    );
+
+    let mint_msg: Vec<_> = response
+        .messages
+        .into_iter()
+        .filter_map(|m| match m.msg {
+            cosmwasm_std::CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+                from_json::<MintExecuteMsg>(msg).ok()
+            }
+            _ => None,
+        })
+        .collect();
+
+    check(
+        mint_msg,
+        expect![[r#"
+            [
+              mint(
+                synthetic: "synthetic_asset",
+                amount: "1000",
+                recipient: "bob",
+              ),
+            ]"#]],
+    );
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::VaultMetadata {
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<VaultMetadata>)
+        .unwrap(),
+        expect![[r#"
+            (
+              vault: "vault",
+              synthetic: "synthetic_asset",
+              deposit_enabled: true,
+              advance_enabled: false,
               max_ltv_bps: 5000,
               collateral_yield_fee_bps: 1000,
               reserve_yield_fee_bps: 10000,
               fixed_advance_fee_bps: 25,
               advance_fee_recipient: None,
               advance_fee_oracle: None,
-              collateral_balance: "1000",
-              collateral_shares: "909090909090909090910",
-              reserve_balance: "90",
-              reserve_shares: "81818181818181818182",
+              oracle_fee_cache_secs: None,
+              collateral_price_oracle: None,
+              min_collateral: None,
+              min_debt: None,
+              account_debt_cap: None,
+              reserve_floor: None,
+              redeem_fee_schedule: None,
+              loss_tolerance_bps: 0,
+              loss_socialisation_enabled: false,
+              min_deposit: "1",
+              collateral_balance: "0",
+              collateral_shares: "0",
+              reserve_balance: "1000",
+              reserve_shares: "1000000000000000000000",
               treasury_shares: "0",
               amo: None,
               amo_allocation: 0,
               amo_shares: "0",
-              sum_payment_ratio: Some((
-                ratio: "0.08999999999999999999999999999999",
-                timestamp: 1571797419,
-              )),
+              amo_shares_returned: "0",
+              amo_deposits_returned: "0",
+              sum_payment_ratio: None,
               deposit_proxy: None,
               advance_proxy: None,
-              mint_proxy: None,
+              mint_proxy: Some("mint_proxy"),
               redeem_proxy: None,
+              interface_version: Some("1.0.0"),
+              interface_features: [
+                "donate",
+                "claim_all",
+              ],
             )"#]],
     );
 }
 
 #[test]
-fn claim_amo() {
+fn claim_treasury() {
     let mut deps = init_with_registered_vault();
 
     execute_msgs(
@@ -2947,16 +4365,8 @@ fn claim_amo() {
             ),
             (
                 info!("creator"),
-                HubExecuteMsg::from(HubAdminMsg::SetAmo {
-                    vault: VAULT.into(),
-                    amo: "amo".into(),
-                }),
-            ),
-            (
-                info!("creator"),
-                HubExecuteMsg::from(HubAdminMsg::SetAmoAllocation {
-                    vault: VAULT.into(),
-                    bps: 1_000,
+                HubExecuteMsg::from(HubAdminMsg::SetTreasury {
+                    address: "treasury".into(),
                 }),
             ),
             (
@@ -2968,11 +4378,13 @@ fn claim_amo() {
         ],
     );
 
+    let reply_id = last_reply_id(&deps);
+
     reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -2993,9 +4405,10 @@ fn claim_amo() {
                 }),
             ),
             (
-                info!("amo"),
-                HubExecuteMsg::from(HubUserMsg::ClaimAmo {
+                info!("treasury"),
+                HubExecuteMsg::from(HubUserMsg::ClaimTreasury {
                     vault: VAULT.into(),
+                    amount: None,
                 }),
             ),
         ],
@@ -3009,11 +4422,11 @@ fn claim_amo() {
                 (
                   id: 0,
                   msg: bank(send(
-                    to_address: "amo",
+                    to_address: "treasury",
                     amount: [
                       (
                         denom: "vault_share",
-                        amount: "8181818181818181818",
+                        amount: "9090909090909090908",
                       ),
                     ],
                   )),
@@ -3024,14 +4437,14 @@ fn claim_amo() {
               attributes: [
                 (
                   key: "kind",
-                  value: "claim_amo",
+                  value: "claim_treasury",
                 ),
                 (
                   key: "vault",
                   value: "vault",
                 ),
                 (
-                  key: "amo_shares",
+                  key: "treasury_shares",
                   value: "0",
                 ),
               ],
@@ -3063,28 +4476,45 @@ fn claim_amo() {
               fixed_advance_fee_bps: 25,
               advance_fee_recipient: None,
               advance_fee_oracle: None,
+              oracle_fee_cache_secs: None,
+              collateral_price_oracle: None,
+              min_collateral: None,
+              min_debt: None,
+              account_debt_cap: None,
+              reserve_floor: None,
+              redeem_fee_schedule: None,
+              loss_tolerance_bps: 0,
+              loss_socialisation_enabled: false,
+              min_deposit: "1",
               collateral_balance: "1000",
               collateral_shares: "909090909090909090910",
-              reserve_balance: "81",
-              reserve_shares: "73636363636363636364",
-              treasury_shares: "9090909090909090908",
-              amo: Some("amo"),
-              amo_allocation: 1000,
+              reserve_balance: "90",
+              reserve_shares: "81818181818181818182",
+              treasury_shares: "0",
+              amo: None,
+              amo_allocation: 0,
               amo_shares: "0",
+              amo_shares_returned: "0",
+              amo_deposits_returned: "0",
               sum_payment_ratio: Some((
-                ratio: "0.08899999999999999999999999999999",
+                ratio: "0.08999999999999999999999999999999",
                 timestamp: 1571797419,
               )),
               deposit_proxy: None,
               advance_proxy: None,
               mint_proxy: None,
               redeem_proxy: None,
+              interface_version: Some("1.0.0"),
+              interface_features: [
+                "donate",
+                "claim_all",
+              ],
             )"#]],
     );
 }
 
 #[test]
-fn evaluate() {
+fn claim_amo() {
     let mut deps = init_with_registered_vault();
 
     execute_msgs(
@@ -3127,11 +4557,13 @@ fn evaluate() {
         ],
     );
 
+    let reply_id = last_reply_id(&deps);
+
     reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -3144,55 +4576,54 @@ fn evaluate() {
 
     let response = execute_msgs(
         &mut deps,
-        &[(
-            info!("anyone"),
-            HubExecuteMsg::from(HubUserMsg::Evaluate {
-                vault: VAULT.into(),
-            }),
-        )],
+        &[
+            (
+                info!("anyone"),
+                HubExecuteMsg::from(HubUserMsg::Evaluate {
+                    vault: VAULT.into(),
+                }),
+            ),
+            (
+                info!("amo"),
+                HubExecuteMsg::from(HubUserMsg::ClaimAmo {
+                    vault: VAULT.into(),
+                }),
+            ),
+        ],
     );
 
     check(
         response,
         expect![[r#"
             (
-              messages: [],
+              messages: [
+                (
+                  id: 0,
+                  msg: bank(send(
+                    to_address: "amo",
+                    amount: [
+                      (
+                        denom: "vault_share",
+                        amount: "8181818181818181818",
+                      ),
+                    ],
+                  )),
+                  gas_limit: None,
+                  reply_on: never,
+                ),
+              ],
               attributes: [
                 (
                   key: "kind",
-                  value: "evaluate",
+                  value: "claim_amo",
                 ),
                 (
                   key: "vault",
                   value: "vault",
                 ),
-                (
-                  key: "account",
-                  value: "anyone",
-                ),
-                (
-                  key: "collateral_shares",
-                  value: "909090909090909090910",
-                ),
-                (
-                  key: "reserve_shares",
-                  value: "73636363636363636364",
-                ),
-                (
-                  key: "reserve_balance",
-                  value: "81",
-                ),
-                (
-                  key: "treasury_shares",
-                  value: "9090909090909090908",
-                ),
                 (
                   key: "amo_shares",
-                  value: "8181818181818181818",
-                ),
-                (
-                  key: "spr",
-                  value: "0.08899999999999999999999999999999",
+                  value: "0",
                 ),
               ],
               events: [],
@@ -3223,6 +4654,16 @@ fn evaluate() {
               fixed_advance_fee_bps: 25,
               advance_fee_recipient: None,
               advance_fee_oracle: None,
+              oracle_fee_cache_secs: None,
+              collateral_price_oracle: None,
+              min_collateral: None,
+              min_debt: None,
+              account_debt_cap: None,
+              reserve_floor: None,
+              redeem_fee_schedule: None,
+              loss_tolerance_bps: 0,
+              loss_socialisation_enabled: false,
+              min_deposit: "1",
               collateral_balance: "1000",
               collateral_shares: "909090909090909090910",
               reserve_balance: "81",
@@ -3230,7 +4671,9 @@ fn evaluate() {
               treasury_shares: "9090909090909090908",
               amo: Some("amo"),
               amo_allocation: 1000,
-              amo_shares: "8181818181818181818",
+              amo_shares: "0",
+              amo_shares_returned: "0",
+              amo_deposits_returned: "0",
               sum_payment_ratio: Some((
                 ratio: "0.08899999999999999999999999999999",
                 timestamp: 1571797419,
@@ -3239,12 +4682,16 @@ fn evaluate() {
               advance_proxy: None,
               mint_proxy: None,
               redeem_proxy: None,
+              interface_version: Some("1.0.0"),
+              interface_features: [
+                "donate",
+                "claim_all",
+              ],
             )"#]],
     );
 }
 
-#[test]
-fn position_query_vault_loss_detected() {
+fn init_with_amo_and_deposit() -> MockDeps {
     let mut deps = init_with_registered_vault();
 
     execute_msgs(
@@ -3252,35 +4699,41 @@ fn position_query_vault_loss_detected() {
         &[
             (
                 info!("creator"),
-                HubAdminMsg::SetDepositsEnabled {
+                HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
                     vault: VAULT.into(),
                     enabled: true,
-                }
-                .into(),
+                }),
             ),
             (
                 info!("creator"),
-                HubAdminMsg::SetAdvanceEnabled {
+                HubExecuteMsg::from(HubAdminMsg::SetAmo {
                     vault: VAULT.into(),
-                    enabled: true,
-                }
-                .into(),
+                    amo: "amo".into(),
+                }),
             ),
             (
-                info!("bob", 1_000),
-                HubUserMsg::Deposit {
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetAmoAllocation {
                     vault: VAULT.into(),
-                }
-                .into(),
+                    bps: 4_000,
+                }),
+            ),
+            (
+                info!("bob", 1_000),
+                HubExecuteMsg::from(HubUserMsg::Deposit {
+                    vault: VAULT.into(),
+                }),
             ),
         ],
     );
 
+    let reply_id = last_reply_id(&deps);
+
     reply(
         deps.as_mut(),
         mock_env(),
         vault_deposit_reply(
-            DEPOSIT_REPLY_ID,
+            reply_id,
             1_000,
             shares_amount(1_000),
             shares_amount(1_000),
@@ -3289,143 +4742,2893 @@ fn position_query_vault_loss_detected() {
     )
     .unwrap();
 
-    update_querier(&mut deps, 1100, shares_amount(1_000));
+    update_querier(&mut deps, 1_000, shares_amount(1_000));
 
-    execute_msgs(
+    deps
+}
+
+fn amo_proceeds_returned(deps: &MockDeps) -> (PositionResponse, [Uint128; 3]) {
+    let position = query(
+        deps.as_ref(),
+        mock_env(),
+        HubQueryMsg::Position {
+            account: "bob".into(),
+            vault: VAULT.into(),
+        }
+        .into(),
+    )
+    .map(into_response::<PositionResponse>)
+    .unwrap();
+
+    let metadata = query(
+        deps.as_ref(),
+        mock_env(),
+        HubQueryMsg::VaultMetadata {
+            vault: VAULT.into(),
+        }
+        .into(),
+    )
+    .map(into_response::<VaultMetadata>)
+    .unwrap();
+
+    (
+        position,
+        [
+            metadata.reserve_balance,
+            metadata.amo_shares_returned,
+            metadata.amo_deposits_returned,
+        ],
+    )
+}
+
+#[test]
+fn return_amo_proceeds_from_non_amo_errs() {
+    let mut deps = init_with_amo_and_deposit();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 100_000_000_000_000_000_000, VAULT_SHARE),
+        HubExecuteMsg::from(HubUserMsg::ReturnAmoProceeds {
+            vault: VAULT.into(),
+        })
+        .into(),
+    )
+    .unwrap_err();
+
+    assert_eq!(err.to_string(), "unauthorized");
+}
+
+#[test]
+fn return_amo_proceeds_as_shares() {
+    let mut deps = init_with_amo_and_deposit();
+
+    let response = execute_msgs(
         &mut deps,
         &[(
-            info!("anyone"),
-            HubUserMsg::Evaluate {
+            info!("amo", 100_000_000_000_000_000_000, VAULT_SHARE),
+            HubExecuteMsg::from(HubUserMsg::ReturnAmoProceeds {
                 vault: VAULT.into(),
-            }
-            .into(),
+            }),
         )],
     );
 
-    update_querier(&mut deps, 1050, shares_amount(1_000));
-
     check(
-        execute(
-            deps.as_mut(),
-            mock_env(),
-            info!("bob"),
-            HubExecuteMsg::from(HubUserMsg::Evaluate {
-                vault: VAULT.into(),
-            })
-            .into(),
-        )
-        .unwrap_err()
-        .to_string(),
-        expect![[r#""vault shares have suffered a loss in value""#]],
+        response,
+        expect![[r#"
+            (
+              messages: [],
+              attributes: [
+                (
+                  key: "kind",
+                  value: "return_amo_proceeds",
+                ),
+                (
+                  key: "vault",
+                  value: "vault",
+                ),
+                (
+                  key: "amount",
+                  value: "100000000000000000000",
+                ),
+                (
+                  key: "collateral_shares",
+                  value: "1060000000000000000001",
+                ),
+                (
+                  key: "reserve_shares",
+                  value: "39999999999999999999",
+                ),
+                (
+                  key: "reserve_balance",
+                  value: "39",
+                ),
+                (
+                  key: "spr",
+                  value: "0.03899999999999999999999999999999",
+                ),
+                (
+                  key: "amo_shares_returned",
+                  value: "100000000000000000000",
+                ),
+              ],
+              events: [],
+              data: None,
+            )"#]],
     );
 
     check(
-        query(
-            deps.as_ref(),
-            mock_env(),
-            HubQueryMsg::Position {
-                vault: VAULT.into(),
-                account: "bob".into(),
-            }
-            .into(),
-        )
-        .map(into_response::<PositionResponse>)
-        .unwrap(),
+        amo_proceeds_returned(&deps),
         expect![[r#"
-            (
+            ((
               collateral: "1000",
               debt: "0",
-              credit: "89",
-              sum_payment_ratio: "0.08999999999999999999999999999999",
-              vault_loss_detected: true,
-            )"#]],
+              credit: "91",
+              sum_payment_ratio: "0.09199999999999999999999999999999",
+              vault_loss_detected: false,
+              position_seq: 1,
+            ), ("39", "100000000000000000000", "0"))"#]],
     );
 }
 
 #[test]
-fn register_vault() {
-    let deps = init_with_registered_vault();
+fn return_amo_proceeds_as_deposit_asset() {
+    let mut deps = init_with_amo_and_deposit();
+
+    let response = execute_msgs(
+        &mut deps,
+        &[(
+            info!("amo", 100),
+            HubExecuteMsg::from(HubUserMsg::ReturnAmoProceeds {
+                vault: VAULT.into(),
+            }),
+        )],
+    );
 
     check(
-        query(deps.as_ref(), mock_env(), HubQueryMsg::ListVaults {}.into())
-            .map(into_response::<ListVaultsResponse>)
-            .unwrap(),
+        response,
         expect![[r#"
             (
-              vaults: [
+              messages: [
                 (
-                  vault: "vault",
-                  synthetic: "synthetic_asset",
-                  deposit_enabled: false,
-                  advance_enabled: false,
-                  max_ltv_bps: 5000,
-                  collateral_yield_fee_bps: 1000,
-                  reserve_yield_fee_bps: 10000,
-                  fixed_advance_fee_bps: 25,
-                  advance_fee_recipient: None,
-                  advance_fee_oracle: None,
-                  collateral_balance: "0",
-                  collateral_shares: "0",
-                  reserve_balance: "0",
-                  reserve_shares: "0",
-                  treasury_shares: "0",
-                  amo: None,
-                  amo_allocation: 0,
-                  amo_shares: "0",
-                  sum_payment_ratio: None,
-                  deposit_proxy: None,
-                  advance_proxy: None,
-                  mint_proxy: None,
-                  redeem_proxy: None,
+                  id: 2,
+                  msg: wasm(execute(
+                    contract_addr: "vault",
+                    msg: "eyJkZXBvc2l0Ijp7fX0=",
+                    funds: [
+                      (
+                        denom: "vault_deposit_asset",
+                        amount: "100",
+                      ),
+                    ],
+                  )),
+                  gas_limit: None,
+                  reply_on: success,
+                ),
+              ],
+              attributes: [
+                (
+                  key: "kind",
+                  value: "return_amo_proceeds",
+                ),
+                (
+                  key: "vault",
+                  value: "vault",
+                ),
+                (
+                  key: "amount",
+                  value: "100",
+                ),
+                (
+                  key: "amo_deposits_returned",
+                  value: "100",
                 ),
               ],
+              events: [],
+              data: None,
+            )"#]],
+    );
+
+    let reply_id = last_reply_id(&deps);
+
+    let response = reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_id,
+            1_000,
+            shares_amount(1_000),
+            shares_amount(100),
+            100,
+        ),
+    )
+    .unwrap();
+
+    check(
+        response,
+        expect![[r#"
+            (
+              messages: [],
+              attributes: [
+                (
+                  key: "kind",
+                  value: "vault_deposit_callback",
+                ),
+                (
+                  key: "reason",
+                  value: "amo_proceeds",
+                ),
+                (
+                  key: "vault",
+                  value: "vault",
+                ),
+                (
+                  key: "recipient",
+                  value: "amo",
+                ),
+                (
+                  key: "minted_shares",
+                  value: "100000000000000000000",
+                ),
+                (
+                  key: "deposit_value",
+                  value: "100",
+                ),
+                (
+                  key: "collateral_shares",
+                  value: "1060000000000000000001",
+                ),
+                (
+                  key: "reserve_shares",
+                  value: "39999999999999999999",
+                ),
+                (
+                  key: "reserve_balance",
+                  value: "39",
+                ),
+                (
+                  key: "spr",
+                  value: "0.03899999999999999999999999999999",
+                ),
+              ],
+              events: [],
+              data: None,
+            )"#]],
+    );
+
+    update_querier(&mut deps, 1_100, shares_amount(1_100));
+
+    check(
+        amo_proceeds_returned(&deps),
+        expect![[r#"
+            ((
+              collateral: "1000",
+              debt: "0",
+              credit: "91",
+              sum_payment_ratio: "0.09199999999999999999999999999999",
+              vault_loss_detected: false,
+              position_seq: 1,
+            ), ("39", "0", "100"))"#]],
+    );
+}
+
+#[test]
+fn evaluate() {
+    let mut deps = init_with_registered_vault();
+
+    execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetAmo {
+                    vault: VAULT.into(),
+                    amo: "amo".into(),
+                }),
+            ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetAmoAllocation {
+                    vault: VAULT.into(),
+                    bps: 1_000,
+                }),
+            ),
+            (
+                info!("bob", 1_000),
+                HubExecuteMsg::from(HubUserMsg::Deposit {
+                    vault: VAULT.into(),
+                }),
+            ),
+        ],
+    );
+
+    let reply_id = last_reply_id(&deps);
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_id,
+            1_000,
+            shares_amount(1_000),
+            shares_amount(1_000),
+            1_000,
+        ),
+    )
+    .unwrap();
+
+    update_querier(&mut deps, 1_100, shares_amount(1_000));
+
+    let response = execute_msgs(
+        &mut deps,
+        &[(
+            info!("anyone"),
+            HubExecuteMsg::from(HubUserMsg::Evaluate {
+                vault: VAULT.into(),
+            }),
+        )],
+    );
+
+    check(
+        response,
+        expect![[r#"
+            (
+              messages: [],
+              attributes: [
+                (
+                  key: "kind",
+                  value: "evaluate",
+                ),
+                (
+                  key: "vault",
+                  value: "vault",
+                ),
+                (
+                  key: "account",
+                  value: "anyone",
+                ),
+                (
+                  key: "collateral_shares",
+                  value: "909090909090909090910",
+                ),
+                (
+                  key: "reserve_shares",
+                  value: "73636363636363636364",
+                ),
+                (
+                  key: "reserve_balance",
+                  value: "81",
+                ),
+                (
+                  key: "treasury_shares",
+                  value: "9090909090909090908",
+                ),
+                (
+                  key: "amo_shares",
+                  value: "8181818181818181818",
+                ),
+                (
+                  key: "spr",
+                  value: "0.08899999999999999999999999999999",
+                ),
+              ],
+              events: [
+                (
+                  type: "yield_fee_accrued",
+                  attributes: [
+                    (
+                      key: "vault",
+                      value: "vault",
+                    ),
+                    (
+                      key: "treasury_shares",
+                      value: "9090909090909090908",
+                    ),
+                    (
+                      key: "collateral_fee",
+                      value: "9090909090909090908",
+                    ),
+                    (
+                      key: "reserve_fee",
+                      value: "0",
+                    ),
+                  ],
+                ),
+              ],
+              data: None,
+            )"#]],
+    );
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::VaultMetadata {
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<VaultMetadata>)
+        .unwrap(),
+        expect![[r#"
+            (
+              vault: "vault",
+              synthetic: "synthetic_asset",
+              deposit_enabled: true,
+              advance_enabled: true,
+              max_ltv_bps: 5000,
+              collateral_yield_fee_bps: 1000,
+              reserve_yield_fee_bps: 10000,
+              fixed_advance_fee_bps: 25,
+              advance_fee_recipient: None,
+              advance_fee_oracle: None,
+              oracle_fee_cache_secs: None,
+              collateral_price_oracle: None,
+              min_collateral: None,
+              min_debt: None,
+              account_debt_cap: None,
+              reserve_floor: None,
+              redeem_fee_schedule: None,
+              loss_tolerance_bps: 0,
+              loss_socialisation_enabled: false,
+              min_deposit: "1",
+              collateral_balance: "1000",
+              collateral_shares: "909090909090909090910",
+              reserve_balance: "81",
+              reserve_shares: "73636363636363636364",
+              treasury_shares: "9090909090909090908",
+              amo: Some("amo"),
+              amo_allocation: 1000,
+              amo_shares: "8181818181818181818",
+              amo_shares_returned: "0",
+              amo_deposits_returned: "0",
+              sum_payment_ratio: Some((
+                ratio: "0.08899999999999999999999999999999",
+                timestamp: 1571797419,
+              )),
+              deposit_proxy: None,
+              advance_proxy: None,
+              mint_proxy: None,
+              redeem_proxy: None,
+              interface_version: Some("1.0.0"),
+              interface_features: [
+                "donate",
+                "claim_all",
+              ],
+            )"#]],
+    );
+}
+
+#[test]
+fn position_query_vault_loss_detected() {
+    let mut deps = init_with_registered_vault();
+
+    execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("creator"),
+                HubAdminMsg::SetDepositsEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }
+                .into(),
+            ),
+            (
+                info!("creator"),
+                HubAdminMsg::SetAdvanceEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }
+                .into(),
+            ),
+            (
+                info!("bob", 1_000),
+                HubUserMsg::Deposit {
+                    vault: VAULT.into(),
+                }
+                .into(),
+            ),
+        ],
+    );
+
+    let reply_id = last_reply_id(&deps);
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_id,
+            1_000,
+            shares_amount(1_000),
+            shares_amount(1_000),
+            1_000,
+        ),
+    )
+    .unwrap();
+
+    update_querier(&mut deps, 1100, shares_amount(1_000));
+
+    execute_msgs(
+        &mut deps,
+        &[(
+            info!("anyone"),
+            HubUserMsg::Evaluate {
+                vault: VAULT.into(),
+            }
+            .into(),
+        )],
+    );
+
+    update_querier(&mut deps, 1050, shares_amount(1_000));
+
+    check(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info!("bob"),
+            HubExecuteMsg::from(HubUserMsg::Evaluate {
+                vault: VAULT.into(),
+            })
+            .into(),
+        )
+        .unwrap_err()
+        .to_string(),
+        expect![[r#""vault shares have suffered a loss in value""#]],
+    );
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::Position {
+                vault: VAULT.into(),
+                account: "bob".into(),
+            }
+            .into(),
+        )
+        .map(into_response::<PositionResponse>)
+        .unwrap(),
+        expect![[r#"
+            (
+              collateral: "1000",
+              debt: "0",
+              credit: "89",
+              sum_payment_ratio: "0.08999999999999999999999999999999",
+              vault_loss_detected: true,
+              position_seq: 1,
+            )"#]],
+    );
+}
+
+#[test]
+fn protocol_summary() {
+    let mut deps = init_with_registered_vault();
+
+    let summary = |deps: &MockDeps| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::ProtocolSummary {}.into(),
+        )
+        .map(into_response::<ProtocolSummaryResponse>)
+        .unwrap()
+    };
+
+    check(
+        summary(&mock_dependencies()),
+        expect![[r#"
+            (
+              total_collateral: "0",
+              total_debt: "0",
+              total_credit: "0",
+              total_reserves: "0",
+            )"#]],
+    );
+
+    let deposit = |deps: &mut MockDeps, vault: &str, info: MessageInfo, total: u128| {
+        execute_msgs(
+            deps,
+            &[
+                (
+                    info!("creator"),
+                    HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+                        vault: vault.into(),
+                        enabled: true,
+                    }),
+                ),
+                (
+                    info!("creator"),
+                    HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
+                        vault: vault.into(),
+                        enabled: true,
+                    }),
+                ),
+                (
+                    info.clone(),
+                    HubExecuteMsg::from(HubUserMsg::Deposit {
+                        vault: vault.into(),
+                    }),
+                ),
+            ],
+        );
+
+        let amount = info.funds[0].amount.u128();
+
+        let reply_id = last_reply_id(deps);
+
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            vault_deposit_reply(
+                reply_id,
+                total,
+                shares_amount(total),
+                shares_amount(amount),
+                amount,
+            ),
+        )
+        .unwrap();
+    };
+
+    deposit(&mut deps, VAULT, info!("bob", 1_000), 1_000);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::RegisterVault {
+            vault: SECOND_VAULT.into(),
+            synthetic: SYNTHETIC_ASSET.into(),
+        })
+        .into(),
+    )
+    .unwrap();
+
+    deposit(&mut deps, SECOND_VAULT, info!("alice", 2_000), 2_000);
+
+    update_querier(&mut deps, 2_000, shares_amount(2_000));
+
+    execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("bob"),
+                HubExecuteMsg::from(HubUserMsg::Advance {
+                    vault: VAULT.into(),
+                    amount: 200u128.into(),
+                }),
+            ),
+            (
+                info!("alice"),
+                HubExecuteMsg::from(HubUserMsg::Advance {
+                    vault: SECOND_VAULT.into(),
+                    amount: 500u128.into(),
+                }),
+            ),
+            (
+                info!("alice", 100),
+                HubExecuteMsg::from(HubUserMsg::RepayUnderlying {
+                    vault: SECOND_VAULT.into(),
+                }),
+            ),
+        ],
+    );
+
+    let reply_id = last_reply_id(&deps);
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_id,
+            2_100,
+            shares_amount(2_100),
+            shares_amount(100),
+            100,
+        ),
+    )
+    .unwrap();
+
+    check(
+        summary(&deps),
+        expect![[r#"
+        (
+          total_collateral: "3000",
+          total_debt: "600",
+          total_credit: "0",
+          total_reserves: "100",
+        )"#]],
+    );
+
+    // yield accrued by another account's evaluation pays down bob's debt ahead of his position being settled
+    update_querier(&mut deps, 2_200, shares_amount(2_000));
+
+    let evaluate = |deps: &mut MockDeps, sender: MessageInfo| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            sender,
+            HubExecuteMsg::from(HubUserMsg::Evaluate {
+                vault: VAULT.into(),
+            })
+            .into(),
+        )
+        .unwrap();
+    };
+
+    evaluate(&mut deps, info!("carol"));
+
+    let after_accrual = summary(&deps);
+
+    check(
+        &after_accrual,
+        expect![[r#"
+        (
+          total_collateral: "3000",
+          total_debt: "511",
+          total_credit: "0",
+          total_reserves: "190",
+        )"#]],
+    );
+
+    let total_collateral = BalanceSheet::new(&deps.storage).total_collateral(&VAULT.into());
+
+    assert!(total_collateral.is_some());
+
+    // fixture: state written before the totals were tracked
+    for vault in [VAULT, SECOND_VAULT] {
+        for map in [
+            "total_debt",
+            "total_credit",
+            "pending_debt_payment",
+            "total_collateral",
+        ] {
+            deps.storage
+                .remove(format!("hub_balance_sheet::{map}{vault}:").as_bytes());
+        }
+    }
+
+    deps.storage.remove(b"state_version::version");
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    assert_eq!(summary(&deps), after_accrual);
+
+    assert_eq!(
+        BalanceSheet::new(&deps.storage).total_collateral(&VAULT.into()),
+        total_collateral
+    );
+
+    // settling bob's position leaves the totals unchanged
+    evaluate(&mut deps, info!("bob"));
+
+    assert_eq!(summary(&deps), after_accrual);
+}
+
+#[test]
+fn account_overview() {
+    let mut deps = init_with_registered_vault();
+
+    let overview = |deps: &MockDeps, start_after: Option<&str>| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::AccountOverview {
+                account: "bob".into(),
+                start_after: start_after.map(Into::into),
+            }
+            .into(),
+        )
+        .map(into_response::<AccountOverviewResponse>)
+        .unwrap()
+    };
+
+    for vault in [SECOND_VAULT, THIRD_VAULT] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info!("creator"),
+            HubExecuteMsg::from(HubAdminMsg::RegisterVault {
+                vault: vault.into(),
+                synthetic: SYNTHETIC_ASSET.into(),
+            })
+            .into(),
+        )
+        .unwrap();
+    }
+
+    let deposit = |deps: &mut MockDeps, vault: &str, info: MessageInfo, total: u128| {
+        execute_msgs(
+            deps,
+            &[
+                (
+                    info!("creator"),
+                    HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+                        vault: vault.into(),
+                        enabled: true,
+                    }),
+                ),
+                (
+                    info!("creator"),
+                    HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
+                        vault: vault.into(),
+                        enabled: true,
+                    }),
+                ),
+                (
+                    info.clone(),
+                    HubExecuteMsg::from(HubUserMsg::Deposit {
+                        vault: vault.into(),
+                    }),
+                ),
+            ],
+        );
+
+        let amount = info.funds[0].amount.u128();
+
+        let reply_id = last_reply_id(deps);
+
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            vault_deposit_reply(
+                reply_id,
+                total,
+                shares_amount(total),
+                shares_amount(amount),
+                amount,
+            ),
+        )
+        .unwrap();
+    };
+
+    deposit(&mut deps, VAULT, info!("bob", 1_000), 1_000);
+    deposit(&mut deps, SECOND_VAULT, info!("bob", 1_000), 1_000);
+    // bob has nothing in the third vault
+    deposit(&mut deps, THIRD_VAULT, info!("alice", 1_000), 1_000);
+
+    update_querier(&mut deps, 1_000, shares_amount(1_000));
+
+    execute_msgs(
+        &mut deps,
+        &[(
+            info!("bob"),
+            HubExecuteMsg::from(HubUserMsg::Advance {
+                vault: VAULT.into(),
+                amount: 200u128.into(),
+            }),
+        )],
+    );
+
+    check(
+        overview(&deps, None),
+        expect![[r#"
+        (
+          positions: [
+            (
+              vault: "vault",
+              synthetic: "synthetic_asset",
+              unavailable: false,
+              position: (
+                collateral: "1000",
+                debt: "200",
+                credit: "0",
+                sum_payment_ratio: "0.0",
+                vault_loss_detected: false,
+                position_seq: 2,
+              ),
+            ),
+            (
+              vault: "second_vault",
+              synthetic: "synthetic_asset",
+              unavailable: false,
+              position: (
+                collateral: "1000",
+                debt: "0",
+                credit: "0",
+                sum_payment_ratio: "0.0",
+                vault_loss_detected: false,
+                position_seq: 1,
+              ),
+            ),
+          ],
+          totals: [
+            (
+              synthetic: "synthetic_asset",
+              collateral: "2000",
+              debt: "200",
+              credit: "0",
+            ),
+          ],
+          truncated: false,
+          next_start_after: None,
+        )"#]],
+    );
+
+    // the second vault can no longer be queried
+    deps.querier.update_wasm(|query| {
+        let WasmQuery::Smart { contract_addr, .. } = query else {
+            panic!("unexpected wasm query: {query:?}");
+        };
+
+        if contract_addr == SECOND_VAULT {
+            return SystemResult::Ok(ContractResult::Err("vault not found".to_owned()));
+        }
+
+        SystemResult::Ok(ContractResult::Ok(
+            to_json_binary(&StateResponse {
+                total_deposits: 1_000u128.into(),
+                total_issued_shares: shares_amount(1_000).into(),
+                redemption_value: None,
+            })
+            .unwrap(),
+        ))
+    });
+
+    check(
+        overview(&deps, None),
+        expect![[r#"
+        (
+          positions: [
+            (
+              vault: "vault",
+              synthetic: "synthetic_asset",
+              unavailable: false,
+              position: (
+                collateral: "1000",
+                debt: "200",
+                credit: "0",
+                sum_payment_ratio: "0.0",
+                vault_loss_detected: false,
+                position_seq: 2,
+              ),
+            ),
+            (
+              vault: "second_vault",
+              synthetic: "synthetic_asset",
+              unavailable: true,
+              position: (
+                collateral: "1000",
+                debt: "0",
+                credit: "0",
+                sum_payment_ratio: "0.0",
+                vault_loss_detected: false,
+                position_seq: 1,
+              ),
+            ),
+          ],
+          totals: [
+            (
+              synthetic: "synthetic_asset",
+              collateral: "2000",
+              debt: "200",
+              credit: "0",
+            ),
+          ],
+          truncated: false,
+          next_start_after: None,
+        )"#]],
+    );
+
+    // only the vaults following the start are visited
+    check(
+        overview(&deps, Some(VAULT)),
+        expect![[r#"
+            (
+              positions: [
+                (
+                  vault: "second_vault",
+                  synthetic: "synthetic_asset",
+                  unavailable: true,
+                  position: (
+                    collateral: "1000",
+                    debt: "0",
+                    credit: "0",
+                    sum_payment_ratio: "0.0",
+                    vault_loss_detected: false,
+                    position_seq: 1,
+                  ),
+                ),
+              ],
+              totals: [
+                (
+                  synthetic: "synthetic_asset",
+                  collateral: "1000",
+                  debt: "0",
+                  credit: "0",
+                ),
+              ],
+              truncated: false,
+              next_start_after: None,
+            )"#]],
+    );
+
+    // each vault's state is queried once, even though the position is evaluated after checking availability
+    let state_queries = Rc::new(RefCell::new(BTreeMap::<String, u32>::new()));
+
+    let mut inner = mock_dependencies();
+
+    update_querier(&mut inner, 1_000, shares_amount(1_000));
+
+    let counted = state_queries.clone();
+
+    deps.querier.update_wasm(move |query| {
+        if let WasmQuery::Smart { msg, contract_addr } = query {
+            if let Ok(VaultQueryMsg::State {}) = from_json(msg) {
+                *counted
+                    .borrow_mut()
+                    .entry(contract_addr.clone())
+                    .or_default() += 1;
+            }
+        }
+
+        inner
+            .querier
+            .handle_query(&QueryRequest::Wasm(query.clone()))
+    });
+
+    overview(&deps, None);
+
+    check(
+        state_queries.borrow().clone(),
+        expect![[r#"
+            {
+              "second_vault": 1,
+              "vault": 1,
+            }"#]],
+    );
+}
+
+#[test]
+fn simulate_queries() {
+    let mut deps = init_with_registered_vault();
+
+    execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
+            (
+                info!("bob", 1_000),
+                HubExecuteMsg::from(HubUserMsg::Deposit {
+                    vault: VAULT.into(),
+                }),
+            ),
+        ],
+    );
+
+    let reply_id = last_reply_id(&deps);
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_id,
+            1_000,
+            shares_amount(1_000),
+            shares_amount(1_000),
+            1_000,
+        ),
+    )
+    .unwrap();
+
+    update_querier(&mut deps, 1_000, shares_amount(1_000));
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::SimulateDeposit {
+                vault: VAULT.into(),
+                sender: "bob".into(),
+                asset: VAULT_DEPOSIT_ASSET.into(),
+                amount: 500u128.into(),
+                behalf_of: None,
+            }
+            .into(),
+        )
+        .map(into_response::<SimulateDepositResponse>)
+        .unwrap(),
+        expect![[r#"
+            (
+              deposited: "500",
+              collateral: "1500",
+              debt: "0",
+              credit: "0",
+            )"#]],
+    );
+
+    // a secondary asset deposited on behalf of another account is valued at its conversion rate
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::SimulateDeposit {
+                vault: VAULT.into(),
+                sender: "alice".into(),
+                asset: VAULT_SECONDARY_DEPOSIT_ASSET.into(),
+                amount: 200u128.into(),
+                behalf_of: Some("bob".into()),
+            }
+            .into(),
+        )
+        .map(into_response::<SimulateDepositResponse>)
+        .unwrap(),
+        expect![[r#"
+            (
+              deposited: "300",
+              collateral: "1300",
+              debt: "0",
+              credit: "0",
+            )"#]],
+    );
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::SimulateWithdraw {
+                vault: VAULT.into(),
+                account: "bob".into(),
+                amount: 500u128.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<SimulateWithdrawResponse>)
+        .unwrap(),
+        expect![[r#"
+            (
+              redeem_shares: "500000000000000000000",
+              collateral: "500",
+              debt: "0",
+              credit: "0",
+            )"#]],
+    );
+
+    let simulated_advance = query(
+        deps.as_ref(),
+        mock_env(),
+        HubQueryMsg::SimulateAdvance {
+            vault: VAULT.into(),
+            account: "bob".into(),
+            amount: 250u128.into(),
+        }
+        .into(),
+    )
+    .map(into_response::<SimulateAdvanceResponse>)
+    .unwrap();
+
+    check(
+        &simulated_advance,
+        expect![[r#"
+        (
+          amount: "250",
+          fee: "0",
+          collateral: "1000",
+          debt: "250",
+          credit: "0",
+        )"#]],
+    );
+
+    // simulating does not change the position
+    let position = |deps: &MockDeps| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::Position {
+                account: "bob".into(),
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<PositionResponse>)
+        .unwrap()
+    };
+
+    assert_eq!(position(&deps).debt.u128(), 0);
+
+    execute_msgs(
+        &mut deps,
+        &[(
+            info!("bob"),
+            HubExecuteMsg::from(HubUserMsg::Advance {
+                vault: VAULT.into(),
+                amount: 250u128.into(),
+            }),
+        )],
+    );
+
+    let position = position(&deps);
+
+    assert_eq!(position.collateral, simulated_advance.collateral);
+    assert_eq!(position.debt, simulated_advance.debt);
+    assert_eq!(position.credit, simulated_advance.credit);
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        HubQueryMsg::SimulateAdvance {
+            vault: VAULT.into(),
+            account: "bob".into(),
+            amount: 1_000u128.into(),
+        }
+        .into(),
+    )
+    .unwrap_err();
+
+    check(err.to_string(), expect![[r#""not enough collateral""#]]);
+}
+
+#[test]
+fn ltv_query() {
+    let mut deps = init_with_registered_vault();
+
+    execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
+            (
+                info!("bob", 1_000),
+                HubExecuteMsg::from(HubUserMsg::Deposit {
+                    vault: VAULT.into(),
+                }),
+            ),
+        ],
+    );
+
+    let reply_id = last_reply_id(&deps);
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_id,
+            1_000,
+            shares_amount(1_000),
+            shares_amount(1_000),
+            1_000,
+        ),
+    )
+    .unwrap();
+
+    update_querier(&mut deps, 1_000, shares_amount(1_000));
+
+    let ltv = |deps: &MockDeps, account: &str| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::Ltv {
+                vault: VAULT.into(),
+                account: account.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<LtvResponse>)
+        .unwrap()
+    };
+
+    check(
+        ltv(&deps, "bob"),
+        expect![[r#"
+            (
+              ltv_bps: Some("0"),
+              ltv: Some("0.0"),
+              max_ltv_bps: 5000,
+            )"#]],
+    );
+
+    execute_msgs(
+        &mut deps,
+        &[(
+            info!("bob"),
+            HubExecuteMsg::from(HubUserMsg::Advance {
+                vault: VAULT.into(),
+                amount: 250u128.into(),
+            }),
+        )],
+    );
+
+    check(
+        ltv(&deps, "bob"),
+        expect![[r#"
+            (
+              ltv_bps: Some("2500"),
+              ltv: Some("0.25"),
+              max_ltv_bps: 5000,
+            )"#]],
+    );
+
+    // an account without a position has no debt
+    check(
+        ltv(&deps, "alice"),
+        expect![[r#"
+            (
+              ltv_bps: Some("0"),
+              ltv: Some("0.0"),
+              max_ltv_bps: 5000,
+            )"#]],
+    );
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        HubQueryMsg::Ltv {
+            vault: "unknown".into(),
+            account: "bob".into(),
+        }
+        .into(),
+    )
+    .unwrap_err();
+
+    check(err.to_string(), expect![[r#""vault not registered""#]]);
+}
+
+#[test]
+fn register_vault() {
+    let deps = init_with_registered_vault();
+
+    check(
+        query(deps.as_ref(), mock_env(), HubQueryMsg::ListVaults {}.into())
+            .map(into_response::<ListVaultsResponse>)
+            .unwrap(),
+        expect![[r#"
+            (
+              vaults: [
+                (
+                  vault: "vault",
+                  synthetic: "synthetic_asset",
+                  deposit_enabled: false,
+                  advance_enabled: false,
+                  max_ltv_bps: 5000,
+                  collateral_yield_fee_bps: 1000,
+                  reserve_yield_fee_bps: 10000,
+                  fixed_advance_fee_bps: 25,
+                  advance_fee_recipient: None,
+                  advance_fee_oracle: None,
+                  oracle_fee_cache_secs: None,
+                  collateral_price_oracle: None,
+                  min_collateral: None,
+                  min_debt: None,
+                  account_debt_cap: None,
+                  reserve_floor: None,
+                  redeem_fee_schedule: None,
+                  loss_tolerance_bps: 0,
+                  loss_socialisation_enabled: false,
+                  min_deposit: "1",
+                  collateral_balance: "0",
+                  collateral_shares: "0",
+                  reserve_balance: "0",
+                  reserve_shares: "0",
+                  treasury_shares: "0",
+                  amo: None,
+                  amo_allocation: 0,
+                  amo_shares: "0",
+                  amo_shares_returned: "0",
+                  amo_deposits_returned: "0",
+                  sum_payment_ratio: None,
+                  deposit_proxy: None,
+                  advance_proxy: None,
+                  mint_proxy: None,
+                  redeem_proxy: None,
+                  interface_version: Some("1.0.0"),
+                  interface_features: [
+                    "donate",
+                    "claim_all",
+                  ],
+                ),
+              ],
+            )"#]],
+    );
+}
+
+#[test]
+fn deregister_empty_vault() {
+    let mut deps = init_with_registered_vault();
+
+    let list_vaults = |deps: &MockDeps| {
+        query(deps.as_ref(), mock_env(), HubQueryMsg::ListVaults {}.into())
+            .map(into_response::<ListVaultsResponse>)
+            .unwrap()
+            .vaults
+            .into_iter()
+            .map(|metadata| metadata.vault)
+            .collect::<Vec<_>>()
+    };
+
+    execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::RegisterVault {
+                    vault: SECOND_VAULT.into(),
+                    synthetic: SYNTHETIC_ASSET.into(),
+                }),
+            ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetMaxLtv {
+                    vault: VAULT.into(),
+                    bps: 6_000,
+                }),
+            ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetMinDeposit {
+                    vault: VAULT.into(),
+                    amount: 1_000u128.into(),
+                }),
+            ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetAmo {
+                    vault: VAULT.into(),
+                    amo: "amo".into(),
+                }),
+            ),
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::DeregisterVault {
+                    vault: VAULT.into(),
+                }),
+            ),
+        ],
+    );
+
+    assert_eq!(list_vaults(&deps), [SECOND_VAULT]);
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        HubQueryMsg::VaultMetadata {
+            vault: VAULT.into(),
+        }
+        .into(),
+    )
+    .unwrap_err();
+
+    check(err.to_string(), expect![[r#""vault not found""#]]);
+
+    // the registration is freed
+    execute_msgs(
+        &mut deps,
+        &[(
+            info!("creator"),
+            HubExecuteMsg::from(HubAdminMsg::RegisterVault {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC_ASSET.into(),
+            }),
+        )],
+    );
+
+    assert_eq!(list_vaults(&deps), [SECOND_VAULT, VAULT]);
+
+    // & starts from the default configuration
+    let metadata: VaultMetadata = query(
+        deps.as_ref(),
+        mock_env(),
+        HubQueryMsg::VaultMetadata {
+            vault: VAULT.into(),
+        }
+        .into(),
+    )
+    .map(into_response)
+    .unwrap();
+
+    check(
+        (metadata.max_ltv_bps, metadata.min_deposit, metadata.amo),
+        expect![[r#"(5000, "1", None)"#]],
+    );
+}
+
+#[test]
+fn deregister_invalidates_cached_oracle_fees() {
+    let mut deps = init_with_registered_vault();
+
+    let env = mock_env();
+
+    handle_hub_cmd::<Empty>(
+        deps.as_mut().storage,
+        &env,
+        &mut Response::default(),
+        Cmd::Vault(VaultCmd::SetCachedOracleFee {
+            vault: VAULT.into(),
+            recipient: "bob".into(),
+            cached: CachedAdvanceFee {
+                fee: AdvanceFee::new(50),
+                oracle_epoch: 0,
+                timestamp: env.block.time.seconds(),
+            },
+        }),
+    )
+    .unwrap();
+
+    let msgs = [
+        HubAdminMsg::DeregisterVault {
+            vault: VAULT.into(),
+        },
+        HubAdminMsg::RegisterVault {
+            vault: VAULT.into(),
+            synthetic: SYNTHETIC_ASSET.into(),
+        },
+        HubAdminMsg::SetOracleFeeCacheSecs {
+            vault: VAULT.into(),
+            secs: Some(600),
+        },
+    ];
+
+    for msg in msgs {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info!("creator"),
+            HubExecuteMsg::from(msg).into(),
+        )
+        .unwrap();
+    }
+
+    let registry = VaultRegistry::new(&deps.storage, deps.as_ref().querier);
+
+    // the cached fee outlives the registration, but is stale under the bumped epoch
+    assert_eq!(registry.advance_fee_oracle_epoch(&VAULT.into()), 1);
+    assert_eq!(
+        registry
+            .cached_oracle_fee(&VAULT.into(), &"bob".into())
+            .map(|cached| cached.oracle_epoch),
+        Some(0)
+    );
+
+    execute(
+        deps.as_mut(),
+        env,
+        info!("anyone"),
+        HubExecuteMsg::from(HubUserMsg::PruneOracleFeeCache {
+            vault: VAULT.into(),
+            start_after: None,
+            limit: 10,
+        })
+        .into(),
+    )
+    .unwrap();
+
+    assert!(VaultRegistry::new(&deps.storage, deps.as_ref().querier)
+        .cached_oracle_fee_recipients(&VAULT.into(), None, 10)
+        .is_empty());
+}
+
+#[test]
+fn deregister_vault_with_collateral_errs() {
+    let mut deps = init_with_registered_vault();
+
+    execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+                    vault: VAULT.into(),
+                    enabled: true,
+                }),
+            ),
+            (
+                info!("bob", 1_000),
+                HubExecuteMsg::from(HubUserMsg::Deposit {
+                    vault: VAULT.into(),
+                }),
+            ),
+        ],
+    );
+
+    let reply_id = last_reply_id(&deps);
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        vault_deposit_reply(
+            reply_id,
+            1_000,
+            shares_amount(1_000),
+            shares_amount(1_000),
+            1_000,
+        ),
+    )
+    .unwrap();
+
+    update_querier(&mut deps, 1_000, shares_amount(1_000));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::DeregisterVault {
+            vault: VAULT.into(),
+        })
+        .into(),
+    )
+    .unwrap_err();
+
+    check(err.to_string(), expect![[r#""vault not empty""#]]);
+}
+
+#[test]
+fn register_vault_mismatching_shares_decimals_errs() {
+    let mut deps = mock_dependencies();
+
+    update_querier(&mut deps, 0, 0);
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        InstantiateMsg {
+            synthetic_mint: SYNTHETIC_MINT.into(),
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::RegisterVault {
+            vault: SIX_DECIMAL_SHARES_VAULT.into(),
+            synthetic: SYNTHETIC_ASSET.into(),
+        })
+        .into(),
+    )
+    .unwrap_err();
+
+    check(err.to_string(), expect![[r#""shares decimals mismatch""#]]);
+}
+
+#[test]
+fn register_vault_unsupported_interface_version_errs() {
+    let mut deps = mock_dependencies();
+
+    update_querier(&mut deps, 0, 0);
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        InstantiateMsg {
+            synthetic_mint: SYNTHETIC_MINT.into(),
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::RegisterVault {
+            vault: NEXT_MAJOR_INTERFACE_VAULT.into(),
+            synthetic: SYNTHETIC_ASSET.into(),
+        })
+        .into(),
+    )
+    .unwrap_err();
+
+    check(
+        err.to_string(),
+        expect![[r#""unsupported vault interface version""#]],
+    );
+}
+
+#[test]
+fn register_vault_unversioned_interface_errs() {
+    let mut deps = mock_dependencies();
+
+    update_querier(&mut deps, 0, 0);
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        InstantiateMsg {
+            synthetic_mint: SYNTHETIC_MINT.into(),
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::RegisterVault {
+            vault: UNVERSIONED_INTERFACE_VAULT.into(),
+            synthetic: SYNTHETIC_ASSET.into(),
+        })
+        .into(),
+    )
+    .unwrap_err();
+
+    check(
+        err.to_string(),
+        expect![[r#""unsupported vault interface version""#]],
+    );
+}
+
+fn update_healthcheck_querier(
+    deps: &mut MockDeps,
+    vault_available: bool,
+    synthetic_decimals: Option<u32>,
+    hub_whitelisted: bool,
+) {
+    deps.querier.update_wasm(move |query| {
+        let WasmQuery::Smart { msg, contract_addr } = query else {
+            panic!("unexpected wasm query: {query:?}");
+        };
+
+        let result = match contract_addr.as_str() {
+            VAULT if !vault_available => Err("vault not found".to_owned()),
+            VAULT => match from_json(msg).unwrap() {
+                VaultQueryMsg::State {} => Ok(to_json_binary(&StateResponse {
+                    total_deposits: 0u128.into(),
+                    total_issued_shares: 0u128.into(),
+                    redemption_value: None,
+                })),
+                VaultQueryMsg::UnderlyingAssetDecimals {} => {
+                    Ok(to_json_binary(&UnderlyingAssetDecimalsResponse {
+                        decimals: 6,
+                    }))
+                }
+                q => panic!("unexpected vault query: {q:?}"),
+            },
+            SYNTHETIC_MINT => match from_json(msg).unwrap() {
+                MintQueryMsg::Synthetic { denom } => synthetic_decimals
+                    .map(|decimals| {
+                        to_json_binary(&SynthMetadata {
+                            denom,
+                            ticker: "synth".into(),
+                            decimals,
+                            display_ticker: "SYNTH".into(),
+                            display_decimals: decimals,
+                            description: String::new(),
+                        })
+                    })
+                    .ok_or_else(|| "synthetic not found".to_owned()),
+                MintQueryMsg::Whitelisted { .. } => Ok(to_json_binary(&WhitelistedResponse {
+                    whitelisted: hub_whitelisted,
+                })),
+                q => panic!("unexpected mint query: {q:?}"),
+            },
+            _ => panic!("unexpected contract query addr: {contract_addr}"),
+        };
+
+        match result {
+            Ok(binary) => SystemResult::Ok(ContractResult::Ok(binary.unwrap())),
+            Err(err) => SystemResult::Ok(ContractResult::Err(err)),
+        }
+    });
+}
+
+fn healthcheck(deps: &MockDeps) -> HealthcheckResponse {
+    query(
+        deps.as_ref(),
+        mock_env(),
+        HubQueryMsg::Healthcheck {}.into(),
+    )
+    .map(into_response)
+    .unwrap()
+}
+
+#[test]
+fn healthcheck_passes() {
+    let mut deps = init_with_registered_vault();
+
+    update_healthcheck_querier(&mut deps, true, Some(6), true);
+
+    check(
+        healthcheck(&deps),
+        expect![[r#"
+        (
+          vaults: [
+            (
+              vault: "vault",
+              checks: [
+                (
+                  check: "vault_state",
+                  passed: true,
+                  reason: None,
+                ),
+                (
+                  check: "synthetic_exists",
+                  passed: true,
+                  reason: None,
+                ),
+                (
+                  check: "hub_whitelisted",
+                  passed: true,
+                  reason: None,
+                ),
+                (
+                  check: "decimals_match",
+                  passed: true,
+                  reason: None,
+                ),
+              ],
+            ),
+          ],
+        )"#]],
+    );
+}
+
+#[test]
+fn healthcheck_reports_vault_unavailable() {
+    let mut deps = init_with_registered_vault();
+
+    update_healthcheck_querier(&mut deps, false, Some(6), true);
+
+    check(
+        healthcheck(&deps),
+        expect![[r#"
+        (
+          vaults: [
+            (
+              vault: "vault",
+              checks: [
+                (
+                  check: "vault_state",
+                  passed: false,
+                  reason: Some("Generic error: Querier contract error: vault not found"),
+                ),
+                (
+                  check: "synthetic_exists",
+                  passed: true,
+                  reason: None,
+                ),
+                (
+                  check: "hub_whitelisted",
+                  passed: true,
+                  reason: None,
+                ),
+                (
+                  check: "decimals_match",
+                  passed: false,
+                  reason: Some("vault underlying asset decimals unavailable: Generic error: Querier contract error: vault not found"),
+                ),
+              ],
+            ),
+          ],
+        )"#]],
+    );
+}
+
+#[test]
+fn healthcheck_reports_missing_synthetic() {
+    let mut deps = init_with_registered_vault();
+
+    update_healthcheck_querier(&mut deps, true, None, true);
+
+    check(
+        healthcheck(&deps),
+        expect![[r#"
+        (
+          vaults: [
+            (
+              vault: "vault",
+              checks: [
+                (
+                  check: "vault_state",
+                  passed: true,
+                  reason: None,
+                ),
+                (
+                  check: "synthetic_exists",
+                  passed: false,
+                  reason: Some("Generic error: Querier contract error: synthetic not found"),
+                ),
+                (
+                  check: "hub_whitelisted",
+                  passed: true,
+                  reason: None,
+                ),
+                (
+                  check: "decimals_match",
+                  passed: false,
+                  reason: Some("synthetic decimals unavailable: Generic error: Querier contract error: synthetic not found"),
+                ),
+              ],
+            ),
+          ],
+        )"#]],
+    );
+}
+
+#[test]
+fn healthcheck_reports_hub_not_whitelisted() {
+    let mut deps = init_with_registered_vault();
+
+    update_healthcheck_querier(&mut deps, true, Some(6), false);
+
+    check(
+        healthcheck(&deps),
+        expect![[r#"
+        (
+          vaults: [
+            (
+              vault: "vault",
+              checks: [
+                (
+                  check: "vault_state",
+                  passed: true,
+                  reason: None,
+                ),
+                (
+                  check: "synthetic_exists",
+                  passed: true,
+                  reason: None,
+                ),
+                (
+                  check: "hub_whitelisted",
+                  passed: false,
+                  reason: Some("hub is not a whitelisted minter"),
+                ),
+                (
+                  check: "decimals_match",
+                  passed: true,
+                  reason: None,
+                ),
+              ],
+            ),
+          ],
+        )"#]],
+    );
+}
+
+#[test]
+fn healthcheck_reports_decimals_mismatch() {
+    let mut deps = init_with_registered_vault();
+
+    update_healthcheck_querier(&mut deps, true, Some(18), true);
+
+    check(
+        healthcheck(&deps),
+        expect![[r#"
+        (
+          vaults: [
+            (
+              vault: "vault",
+              checks: [
+                (
+                  check: "vault_state",
+                  passed: true,
+                  reason: None,
+                ),
+                (
+                  check: "synthetic_exists",
+                  passed: true,
+                  reason: None,
+                ),
+                (
+                  check: "hub_whitelisted",
+                  passed: true,
+                  reason: None,
+                ),
+                (
+                  check: "decimals_match",
+                  passed: false,
+                  reason: Some("vault underlying asset decimals (6) do not match synthetic decimals (18)"),
+                ),
+              ],
+            ),
+          ],
+        )"#]],
+    );
+}
+
+#[test]
+fn set_treasury() {
+    let mut deps = init_with_registered_vault();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::SetTreasury {
+            address: "treasury".into(),
+        })
+        .into(),
+    )
+    .unwrap();
+
+    check(
+        query(deps.as_ref(), mock_env(), HubQueryMsg::Treasury {}.into())
+            .map(into_response::<TreasuryResponse>)
+            .unwrap(),
+        expect![[r#"
+            (
+              treasury: Some("treasury"),
+            )"#]],
+    );
+}
+
+#[test]
+fn set_deposits_enabled() {
+    let mut deps = init_with_registered_vault();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+            vault: VAULT.into(),
+            enabled: true,
+        })
+        .into(),
+    )
+    .unwrap();
+
+    assert!(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::VaultMetadata {
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<VaultMetadata>)
+        .unwrap()
+        .deposit_enabled
+    )
+}
+
+#[test]
+fn set_advance_enabled() {
+    let mut deps = init_with_registered_vault();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
+            vault: VAULT.into(),
+            enabled: true,
+        })
+        .into(),
+    )
+    .unwrap();
+
+    assert!(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::VaultMetadata {
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<VaultMetadata>)
+        .unwrap()
+        .advance_enabled
+    )
+}
+
+#[test]
+fn vault_flag_changed_event() {
+    let mut deps = init_with_registered_vault();
+
+    let mut set_flag = |msg: HubAdminMsg| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info!("creator"),
+            HubExecuteMsg::from(msg).into(),
+        )
+        .unwrap()
+        .events
+    };
+
+    let toggles = [
+        (true, false),
+        (true, true),
+        (false, false),
+        (false, true),
+        // setting an unchanged value still emits
+        (false, true),
+    ];
+
+    let events: Vec<_> = toggles
+        .into_iter()
+        .flat_map(|(deposits, enabled)| {
+            set_flag(if deposits {
+                HubAdminMsg::SetDepositsEnabled {
+                    vault: VAULT.into(),
+                    enabled,
+                }
+            } else {
+                HubAdminMsg::SetAdvanceEnabled {
+                    vault: VAULT.into(),
+                    enabled,
+                }
+            })
+        })
+        .map(|event| {
+            let attrs: Vec<_> = event
+                .attributes
+                .into_iter()
+                .map(|attr| format!("{}={}", attr.key, attr.value))
+                .collect();
+
+            format!("{}: {}", event.ty, attrs.join(", "))
+        })
+        .collect();
+
+    check(
+        events,
+        expect![[r#"
+        [
+          "vault_flag_changed: vault=vault, flag=deposits_enabled, enabled=false",
+          "vault_flag_changed: vault=vault, flag=deposits_enabled, enabled=true",
+          "vault_flag_changed: vault=vault, flag=advance_enabled, enabled=false",
+          "vault_flag_changed: vault=vault, flag=advance_enabled, enabled=true",
+          "vault_flag_changed: vault=vault, flag=advance_enabled, enabled=true",
+        ]"#]],
+    );
+}
+
+fn init_paused_with_guardian() -> MockDeps {
+    let mut deps = init_with_registered_vault();
+
+    execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetGuardian {
+                    address: "guardian".into(),
+                }),
+            ),
+            (
+                info!("guardian"),
+                HubExecuteMsg::from(GuardianMsg::SetGlobalPause { paused: true }),
+            ),
+        ],
+    );
+
+    deps
+}
+
+#[test]
+fn set_guardian_and_global_pause_authorization() {
+    let mut deps = init_with_registered_vault();
+
+    let mut try_execute = |info: MessageInfo, msg: HubExecuteMsg| {
+        execute(deps.as_mut(), mock_env(), info, msg.into())
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    };
+
+    let set_guardian = HubExecuteMsg::from(HubAdminMsg::SetGuardian {
+        address: "guardian".into(),
+    });
+
+    let pause = HubExecuteMsg::from(GuardianMsg::SetGlobalPause { paused: true });
+
+    let unpause = HubExecuteMsg::from(GuardianMsg::SetGlobalPause { paused: false });
+
+    // only the admin can assign the guardian
+    check(
+        try_execute(info!("guardian"), set_guardian.clone()).unwrap_err(),
+        expect![[r#""unauthorized""#]],
+    );
+
+    // no guardian is set yet
+    check(
+        try_execute(info!("guardian"), pause.clone()).unwrap_err(),
+        expect![[r#""unauthorized""#]],
+    );
+
+    try_execute(info!("creator"), set_guardian).unwrap();
+
+    check(
+        try_execute(info!("bob"), pause.clone()).unwrap_err(),
+        expect![[r#""unauthorized""#]],
+    );
+
+    try_execute(info!("guardian"), pause).unwrap();
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::GlobalPause {}.into(),
+        )
+        .map(into_response::<GlobalPauseResponse>)
+        .unwrap(),
+        expect![[r#"
+            (
+              paused: true,
+              guardian: Some("guardian"),
+            )"#]],
+    );
+
+    let response = execute(deps.as_mut(), mock_env(), info!("creator"), unpause.into()).unwrap();
+
+    check(
+        response.events,
+        expect![[r#"
+            [
+              (
+                type: "global_pause_changed",
+                attributes: [
+                  (
+                    key: "paused",
+                    value: "false",
+                  ),
+                  (
+                    key: "sender",
+                    value: "creator",
+                  ),
+                ],
+              ),
+            ]"#]],
+    );
+
+    assert!(
+        !query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::GlobalPause {}.into()
+        )
+        .map(into_response::<GlobalPauseResponse>)
+        .unwrap()
+        .paused
+    );
+}
+
+#[test]
+fn global_pause_halts_user_entry_points() {
+    let mut deps = init_paused_with_guardian();
+
+    let vault = || VAULT.to_owned();
+
+    let entry_points = [
+        (info!("bob"), HubUserMsg::Evaluate { vault: vault() }),
+        (info!("bob", 1_000), HubUserMsg::Deposit { vault: vault() }),
+        (
+            info!("proxy", 1_000),
+            HubUserMsg::DepositOnBehalf {
+                vault: vault(),
+                behalf_of: "bob".into(),
+            },
+        ),
+        (
+            info!("bob", 1_000, VAULT_SHARE),
+            HubUserMsg::AbsorbShares { vault: vault() },
+        ),
+        (
+            info!("bob", 1_000),
+            HubUserMsg::RepayUnderlying { vault: vault() },
+        ),
+        (
+            info!("bob", 1_000, SYNTHETIC_ASSET),
+            HubUserMsg::RepaySynthetic { vault: vault() },
+        ),
+        (
+            info!("bob"),
+            HubUserMsg::Advance {
+                vault: vault(),
+                amount: 100u128.into(),
+            },
+        ),
+        (
+            info!("proxy"),
+            HubUserMsg::AdvanceOnBehalf {
+                vault: vault(),
+                amount: 100u128.into(),
+                behalf_of: "bob".into(),
+            },
+        ),
+        (
+            info!("bob"),
+            HubUserMsg::Withdraw {
+                vault: vault(),
+                amount: 100u128.into(),
+            },
+        ),
+        (info!("bob"), HubUserMsg::WithdrawAll { vault: vault() }),
+        (info!("bob"), HubUserMsg::SelfLiquidate { vault: vault() }),
+        (
+            info!("bob"),
+            HubUserMsg::ConvertCredit {
+                vault: vault(),
+                amount: 100u128.into(),
+            },
+        ),
+        (
+            info!("bob"),
+            HubUserMsg::ConvertAllCredit { vault: vault() },
+        ),
+        (
+            info!("bob", 1_000, SYNTHETIC_ASSET),
+            HubUserMsg::Redeem { vault: vault() },
+        ),
+        (
+            info!("proxy", 1_000, SYNTHETIC_ASSET),
+            HubUserMsg::RedeemOnBehalf {
+                vault: vault(),
+                behalf_of: "bob".into(),
+            },
+        ),
+        (info!("bob", 1_000), HubUserMsg::Mint { vault: vault() }),
+        (
+            info!("proxy", 1_000),
+            HubUserMsg::MintOnBehalf {
+                vault: vault(),
+                behalf_of: "bob".into(),
+            },
+        ),
+        (
+            info!("treasury"),
+            HubUserMsg::ClaimTreasury {
+                vault: vault(),
+                amount: None,
+            },
+        ),
+        (info!("amo"), HubUserMsg::ClaimAmo { vault: vault() }),
+        (
+            info!("amo", 1_000, VAULT_SHARE),
+            HubUserMsg::ReturnAmoProceeds { vault: vault() },
+        ),
+        (
+            info!("bob"),
+            HubUserMsg::SetPositionOperator {
+                vault: vault(),
+                operator: Some("operator".into()),
+                max_advance: None,
+                allow_withdraw: true,
+            },
+        ),
+        (
+            info!("operator"),
+            HubUserMsg::AdvanceAsOperator {
+                vault: vault(),
+                amount: 100u128.into(),
+                owner: "bob".into(),
+            },
+        ),
+        (
+            info!("operator", 1_000, SYNTHETIC_ASSET),
+            HubUserMsg::RepaySyntheticAsOperator {
+                vault: vault(),
+                owner: "bob".into(),
+            },
+        ),
+        (
+            info!("operator"),
+            HubUserMsg::WithdrawAsOperator {
+                vault: vault(),
+                amount: 100u128.into(),
+                owner: "bob".into(),
+            },
+        ),
+    ];
+
+    for (info, msg) in entry_points {
+        let kind = msg.kind();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            HubExecuteMsg::from(msg).into(),
+        )
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "protocol paused", "{kind}");
+    }
+
+    // admin configuration remains available while paused
+    execute_msgs(
+        &mut deps,
+        &[(
+            info!("creator"),
+            HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+                vault: VAULT.into(),
+                enabled: true,
+            }),
+        )],
+    );
+
+    // as do queries
+    query(
+        deps.as_ref(),
+        mock_env(),
+        HubQueryMsg::VaultMetadata {
+            vault: VAULT.into(),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // unpausing restores user entry points
+    let response = execute_msgs(
+        &mut deps,
+        &[
+            (
+                info!("guardian"),
+                HubExecuteMsg::from(GuardianMsg::SetGlobalPause { paused: false }),
+            ),
+            (
+                info!("bob", 1_000),
+                HubExecuteMsg::from(HubUserMsg::Deposit {
+                    vault: VAULT.into(),
+                }),
+            ),
+        ],
+    );
+
+    assert_eq!(response.messages.len(), 1);
+}
+
+#[test]
+fn set_max_ltv() {
+    let mut deps = init_with_registered_vault();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::SetMaxLtv {
+            vault: VAULT.into(),
+            bps: 9_000,
+        })
+        .into(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::VaultMetadata {
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<VaultMetadata>)
+        .unwrap()
+        .max_ltv_bps,
+        9_000
+    )
+}
+
+#[test]
+fn set_redeem_fee_schedule() {
+    let mut deps = init_with_registered_vault();
+
+    let redeem_fee_schedule = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::VaultMetadata {
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<VaultMetadata>)
+        .unwrap()
+        .redeem_fee_schedule
+    };
+
+    let set_redeem_fee_schedule =
+        |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+         tiers: Option<Vec<RedeemFeeTier>>| {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info!("creator"),
+                HubExecuteMsg::from(HubAdminMsg::SetRedeemFeeSchedule {
+                    vault: VAULT.into(),
+                    tiers,
+                })
+                .into(),
+            )
+            .unwrap();
+        };
+
+    let tiers = vec![
+        RedeemFeeTier {
+            threshold_bps: 0,
+            fee_bps: 10,
+        },
+        RedeemFeeTier {
+            threshold_bps: 5_000,
+            fee_bps: 100,
+        },
+    ];
+
+    set_redeem_fee_schedule(&mut deps, Some(tiers.clone()));
+
+    assert_eq!(redeem_fee_schedule(&deps), Some(tiers));
+
+    // replacing the schedule with fewer tiers drops the previous ones
+    let tiers = vec![RedeemFeeTier {
+        threshold_bps: 2_500,
+        fee_bps: 50,
+    }];
+
+    set_redeem_fee_schedule(&mut deps, Some(tiers.clone()));
+
+    assert_eq!(redeem_fee_schedule(&deps), Some(tiers));
+
+    set_redeem_fee_schedule(&mut deps, None);
+
+    assert_eq!(redeem_fee_schedule(&deps), None);
+}
+
+#[test]
+fn set_collateral_yield_fee() {
+    let mut deps = init_with_registered_vault();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::SetCollateralYieldFee {
+            vault: VAULT.into(),
+            bps: 9_000,
+        })
+        .into(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::VaultMetadata {
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<VaultMetadata>)
+        .unwrap()
+        .collateral_yield_fee_bps,
+        9_000
+    )
+}
+
+#[test]
+fn set_reserves_treasury_fee() {
+    let mut deps = init_with_registered_vault();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::SetReservesTreasuryFee {
+            vault: VAULT.into(),
+            bps: 9_000,
+        })
+        .into(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::VaultMetadata {
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<VaultMetadata>)
+        .unwrap()
+        .reserve_yield_fee_bps,
+        9_000
+    )
+}
+
+#[test]
+fn set_advance_fee_recipient() {
+    let mut deps = init_with_registered_vault();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::SetAdvanceFeeRecipient {
+            vault: VAULT.into(),
+            recipient: "treasury".into(),
+        })
+        .into(),
+    )
+    .unwrap();
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::VaultMetadata {
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<VaultMetadata>)
+        .unwrap()
+        .advance_fee_recipient,
+        expect![[r#"Some("treasury")"#]],
+    )
+}
+
+#[test]
+fn set_fixed_advance_fee() {
+    let mut deps = init_with_registered_vault();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::SetFixedAdvanceFee {
+            vault: VAULT.into(),
+            bps: 100,
+        })
+        .into(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::VaultMetadata {
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<VaultMetadata>)
+        .unwrap()
+        .fixed_advance_fee_bps,
+        100
+    )
+}
+
+#[test]
+fn advance_fee_query() {
+    let mut deps = init_with_registered_vault();
+
+    let advance_fee = |deps: &MockDeps| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::AdvanceFee {
+                vault: VAULT.into(),
+                account: "bob".into(),
+                amount: 100_000u128.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<AdvanceFeeResponse>)
+        .unwrap()
+    };
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::SetFixedAdvanceFee {
+            vault: VAULT.into(),
+            bps: 100,
+        })
+        .into(),
+    )
+    .unwrap();
+
+    // no fee is charged without a fee recipient, regardless of the fixed fee
+    check(
+        advance_fee(&deps),
+        expect![[r#"
+            (
+              source: none,
+              bps: 0,
+              amount: "0",
+            )"#]],
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::SetAdvanceFeeRecipient {
+            vault: VAULT.into(),
+            recipient: "treasury".into(),
+        })
+        .into(),
+    )
+    .unwrap();
+
+    check(
+        advance_fee(&deps),
+        expect![[r#"
+            (
+              source: fixed,
+              bps: 100,
+              amount: "999",
             )"#]],
     );
 }
 
 #[test]
-fn set_treasury() {
+fn advance_fees_query() {
     let mut deps = init_with_registered_vault();
 
     execute(
         deps.as_mut(),
         mock_env(),
         info!("creator"),
-        HubExecuteMsg::from(HubAdminMsg::SetTreasury {
-            address: "treasury".into(),
+        HubExecuteMsg::from(HubAdminMsg::SetFixedAdvanceFee {
+            vault: VAULT.into(),
+            bps: 100,
         })
         .into(),
     )
     .unwrap();
 
     check(
-        query(deps.as_ref(), mock_env(), HubQueryMsg::Treasury {}.into())
-            .map(into_response::<TreasuryResponse>)
-            .unwrap(),
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::AdvanceFees {
+                vault: VAULT.into(),
+                recipients: vec!["bob".into(), "alice".into()],
+            }
+            .into(),
+        )
+        .map(into_response::<AdvanceFeesResponse>)
+        .unwrap(),
         expect![[r#"
             (
-              treasury: Some("treasury"),
+              bps: [
+                100,
+                100,
+              ],
             )"#]],
     );
 }
 
 #[test]
-fn set_deposits_enabled() {
+fn prune_oracle_fee_cache() {
+    let mut deps = init_with_registered_vault();
+
+    let env = mock_env();
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::SetOracleFeeCacheSecs {
+            vault: VAULT.into(),
+            secs: Some(600),
+        })
+        .into(),
+    )
+    .unwrap();
+
+    for (recipient, timestamp) in [
+        ("alice", env.block.time.seconds() - 600),
+        ("bob", env.block.time.seconds()),
+        ("carol", 0),
+    ] {
+        handle_hub_cmd::<Empty>(
+            deps.as_mut().storage,
+            &env,
+            &mut Response::default(),
+            Cmd::Vault(VaultCmd::SetCachedOracleFee {
+                vault: VAULT.into(),
+                recipient: recipient.into(),
+                cached: CachedAdvanceFee {
+                    fee: AdvanceFee::new(50),
+                    oracle_epoch: 0,
+                    timestamp,
+                },
+            }),
+        )
+        .unwrap();
+    }
+
+    let cached_recipients = |deps: &MockDeps| {
+        VaultRegistry::new(&deps.storage, deps.as_ref().querier)
+            .cached_oracle_fee_recipients(&VAULT.into(), None, 10)
+            .into_iter()
+            .map(|recipient| recipient.into_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(cached_recipients(&deps), ["alice", "bob", "carol"]);
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info!("anyone"),
+        HubExecuteMsg::from(HubUserMsg::PruneOracleFeeCache {
+            vault: VAULT.into(),
+            start_after: Some("alice".into()),
+            limit: 10,
+        })
+        .into(),
+    )
+    .unwrap();
+
+    assert_eq!(cached_recipients(&deps), ["alice", "bob"]);
+
+    execute(
+        deps.as_mut(),
+        env,
+        info!("anyone"),
+        HubExecuteMsg::from(HubUserMsg::PruneOracleFeeCache {
+            vault: VAULT.into(),
+            start_after: None,
+            limit: 10,
+        })
+        .into(),
+    )
+    .unwrap();
+
+    assert_eq!(cached_recipients(&deps), ["bob"]);
+}
+
+#[test]
+fn set_advance_fee_oracle() {
     let mut deps = init_with_registered_vault();
 
     execute(
         deps.as_mut(),
         mock_env(),
         info!("creator"),
-        HubExecuteMsg::from(HubAdminMsg::SetDepositsEnabled {
+        HubExecuteMsg::from(HubAdminMsg::SetAdvanceFeeOracle {
             vault: VAULT.into(),
-            enabled: true,
+            oracle: "advance_fee_oracle".into(),
         })
         .into(),
     )
     .unwrap();
 
-    assert!(
+    check(
         query(
             deps.as_ref(),
             mock_env(),
@@ -3436,27 +7639,28 @@ fn set_deposits_enabled() {
         )
         .map(into_response::<VaultMetadata>)
         .unwrap()
-        .deposit_enabled
+        .advance_fee_oracle,
+        expect![[r#"Some("advance_fee_oracle")"#]],
     )
 }
 
 #[test]
-fn set_advance_enabled() {
+fn set_oracle_fee_cache_secs() {
     let mut deps = init_with_registered_vault();
 
     execute(
         deps.as_mut(),
         mock_env(),
         info!("creator"),
-        HubExecuteMsg::from(HubAdminMsg::SetAdvanceEnabled {
+        HubExecuteMsg::from(HubAdminMsg::SetOracleFeeCacheSecs {
             vault: VAULT.into(),
-            enabled: true,
+            secs: Some(600),
         })
         .into(),
     )
     .unwrap();
 
-    assert!(
+    check(
         query(
             deps.as_ref(),
             mock_env(),
@@ -3467,27 +7671,85 @@ fn set_advance_enabled() {
         )
         .map(into_response::<VaultMetadata>)
         .unwrap()
-        .advance_enabled
+        .oracle_fee_cache_secs,
+        expect!["Some(600)"],
     )
 }
 
 #[test]
-fn set_max_ltv() {
+fn set_collateral_price_oracle() {
     let mut deps = init_with_registered_vault();
 
+    let collateral_price_oracle = |deps: &MockDeps| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::VaultMetadata {
+                vault: VAULT.into(),
+            }
+            .into(),
+        )
+        .map(into_response::<VaultMetadata>)
+        .unwrap()
+        .collateral_price_oracle
+    };
+
     execute(
         deps.as_mut(),
         mock_env(),
         info!("creator"),
-        HubExecuteMsg::from(HubAdminMsg::SetMaxLtv {
+        HubExecuteMsg::from(HubAdminMsg::SetCollateralPriceOracle {
             vault: VAULT.into(),
-            bps: 9_000,
+            oracle: Some(CollateralPriceOracle {
+                address: "collateral_price_oracle".into(),
+                max_age_secs: 600,
+            }),
         })
         .into(),
     )
     .unwrap();
 
-    assert_eq!(
+    check(
+        collateral_price_oracle(&deps),
+        expect![[r#"
+        Some((
+          address: "collateral_price_oracle",
+          max_age_secs: 600,
+        ))"#]],
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::SetCollateralPriceOracle {
+            vault: VAULT.into(),
+            oracle: None,
+        })
+        .into(),
+    )
+    .unwrap();
+
+    check(collateral_price_oracle(&deps), expect!["None"]);
+}
+
+#[test]
+fn set_min_collateral() {
+    let mut deps = init_with_registered_vault();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::SetMinCollateral {
+            vault: VAULT.into(),
+            min: Some(100u128.into()),
+        })
+        .into(),
+    )
+    .unwrap();
+
+    check(
         query(
             deps.as_ref(),
             mock_env(),
@@ -3498,28 +7760,28 @@ fn set_max_ltv() {
         )
         .map(into_response::<VaultMetadata>)
         .unwrap()
-        .max_ltv_bps,
-        9_000
+        .min_collateral,
+        expect![[r#"Some("100")"#]],
     )
 }
 
 #[test]
-fn set_collateral_yield_fee() {
+fn set_min_debt() {
     let mut deps = init_with_registered_vault();
 
     execute(
         deps.as_mut(),
         mock_env(),
         info!("creator"),
-        HubExecuteMsg::from(HubAdminMsg::SetCollateralYieldFee {
+        HubExecuteMsg::from(HubAdminMsg::SetMinDebt {
             vault: VAULT.into(),
-            bps: 9_000,
+            min: Some(100u128.into()),
         })
         .into(),
     )
     .unwrap();
 
-    assert_eq!(
+    check(
         query(
             deps.as_ref(),
             mock_env(),
@@ -3530,28 +7792,28 @@ fn set_collateral_yield_fee() {
         )
         .map(into_response::<VaultMetadata>)
         .unwrap()
-        .collateral_yield_fee_bps,
-        9_000
+        .min_debt,
+        expect![[r#"Some("100")"#]],
     )
 }
 
 #[test]
-fn set_reserves_treasury_fee() {
+fn set_account_debt_cap() {
     let mut deps = init_with_registered_vault();
 
     execute(
         deps.as_mut(),
         mock_env(),
         info!("creator"),
-        HubExecuteMsg::from(HubAdminMsg::SetReservesTreasuryFee {
+        HubExecuteMsg::from(HubAdminMsg::SetAccountDebtCap {
             vault: VAULT.into(),
-            bps: 9_000,
+            cap: Some(1_000u128.into()),
         })
         .into(),
     )
     .unwrap();
 
-    assert_eq!(
+    check(
         query(
             deps.as_ref(),
             mock_env(),
@@ -3562,22 +7824,22 @@ fn set_reserves_treasury_fee() {
         )
         .map(into_response::<VaultMetadata>)
         .unwrap()
-        .reserve_yield_fee_bps,
-        9_000
+        .account_debt_cap,
+        expect![[r#"Some("1000")"#]],
     )
 }
 
 #[test]
-fn set_advance_fee_recipient() {
+fn set_reserve_floor() {
     let mut deps = init_with_registered_vault();
 
     execute(
         deps.as_mut(),
         mock_env(),
         info!("creator"),
-        HubExecuteMsg::from(HubAdminMsg::SetAdvanceFeeRecipient {
+        HubExecuteMsg::from(HubAdminMsg::SetReserveFloor {
             vault: VAULT.into(),
-            recipient: "treasury".into(),
+            floor: Some(100u128.into()),
         })
         .into(),
     )
@@ -3594,20 +7856,20 @@ fn set_advance_fee_recipient() {
         )
         .map(into_response::<VaultMetadata>)
         .unwrap()
-        .advance_fee_recipient,
-        expect![[r#"Some("treasury")"#]],
+        .reserve_floor,
+        expect![[r#"Some("100")"#]],
     )
 }
 
 #[test]
-fn set_fixed_advance_fee() {
+fn set_loss_tolerance() {
     let mut deps = init_with_registered_vault();
 
     execute(
         deps.as_mut(),
         mock_env(),
         info!("creator"),
-        HubExecuteMsg::from(HubAdminMsg::SetFixedAdvanceFee {
+        HubExecuteMsg::from(HubAdminMsg::SetLossTolerance {
             vault: VAULT.into(),
             bps: 100,
         })
@@ -3615,7 +7877,7 @@ fn set_fixed_advance_fee() {
     )
     .unwrap();
 
-    assert_eq!(
+    check(
         query(
             deps.as_ref(),
             mock_env(),
@@ -3626,27 +7888,51 @@ fn set_fixed_advance_fee() {
         )
         .map(into_response::<VaultMetadata>)
         .unwrap()
-        .fixed_advance_fee_bps,
-        100
+        .loss_tolerance_bps,
+        expect!["100"],
     )
 }
 
 #[test]
-fn set_advance_fee_oracle() {
+fn set_loss_socialisation_enabled() {
     let mut deps = init_with_registered_vault();
 
-    execute(
+    let response = execute(
         deps.as_mut(),
         mock_env(),
         info!("creator"),
-        HubExecuteMsg::from(HubAdminMsg::SetAdvanceFeeOracle {
+        HubExecuteMsg::from(HubAdminMsg::SetLossSocialisationEnabled {
             vault: VAULT.into(),
-            oracle: "advance_fee_oracle".into(),
+            enabled: true,
         })
         .into(),
     )
     .unwrap();
 
+    check(
+        response.events,
+        expect![[r#"
+        [
+          (
+            type: "vault_flag_changed",
+            attributes: [
+              (
+                key: "vault",
+                value: "vault",
+              ),
+              (
+                key: "flag",
+                value: "loss_socialisation_enabled",
+              ),
+              (
+                key: "enabled",
+                value: "true",
+              ),
+            ],
+          ),
+        ]"#]],
+    );
+
     check(
         query(
             deps.as_ref(),
@@ -3658,9 +7944,171 @@ fn set_advance_fee_oracle() {
         )
         .map(into_response::<VaultMetadata>)
         .unwrap()
-        .advance_fee_oracle,
-        expect![[r#"Some("advance_fee_oracle")"#]],
+        .loss_socialisation_enabled,
+        expect!["true"],
+    )
+}
+
+#[test]
+fn acknowledge_loss_without_loss_errs() {
+    let mut deps = init_with_registered_vault();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        HubExecuteMsg::from(HubAdminMsg::AcknowledgeLoss {
+            vault: VAULT.into(),
+        })
+        .into(),
+    )
+    .unwrap_err();
+
+    check(err.to_string(), expect![[r#""no loss to acknowledge""#]]);
+}
+
+#[test]
+fn sweep_dust_without_dust_errs() {
+    let mut deps = init_with_amo_and_deposit();
+
+    let msg = HubExecuteMsg::from(HubAdminMsg::SweepDust {
+        vault: VAULT.into(),
+    });
+
+    let err = execute(deps.as_mut(), mock_env(), info!("bob"), msg.clone().into()).unwrap_err();
+
+    check(err.to_string(), expect![[r#""unauthorized""#]]);
+
+    // all of the deposited collateral is attributed to bob's position
+    let err = execute(deps.as_mut(), mock_env(), info!("creator"), msg.into()).unwrap_err();
+
+    check(err.to_string(), expect![[r#""no dust to sweep""#]]);
+}
+
+#[test]
+fn sweep_unaccounted_leaves_held_shares() {
+    let mut deps = init_with_amo_and_deposit();
+
+    // the shares backing bob's collateral are tracked, the rest are surplus
+    deps.querier.update_balance(
+        mock_env().contract.address,
+        vec![
+            coin(shares_amount(1_000) + 25, VAULT_SHARE),
+            coin(9, "ustray"),
+        ],
+    );
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            HubQueryMsg::UnaccountedBalances {}.into(),
+        )
+        .map(into_response::<UnaccountedBalancesResponse>)
+        .unwrap(),
+        expect![[r#"
+            (
+              balances: [
+                (
+                  denom: "vault_share",
+                  amount: "25",
+                ),
+                (
+                  denom: "ustray",
+                  amount: "9",
+                ),
+              ],
+            )"#]],
+    );
+
+    let sweep = |denom: &str| {
+        HubExecuteMsg::from(HubAdminMsg::SweepUnaccounted {
+            denom: denom.into(),
+            recipient: "treasury".into(),
+        })
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob"),
+        sweep(VAULT_SHARE).into(),
+    )
+    .unwrap_err();
+
+    check(err.to_string(), expect![[r#""unauthorized""#]]);
+
+    let response = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        sweep(VAULT_SHARE).into(),
+    )
+    .unwrap();
+
+    check(
+        response.messages,
+        expect![[r#"
+        [
+          (
+            id: 0,
+            msg: bank(send(
+              to_address: "treasury",
+              amount: [
+                (
+                  denom: "vault_share",
+                  amount: "25",
+                ),
+              ],
+            )),
+            gas_limit: None,
+            reply_on: never,
+          ),
+        ]"#]],
+    );
+
+    check(
+        response.events,
+        expect![[r#"
+        [
+          (
+            type: "unaccounted_swept",
+            attributes: [
+              (
+                key: "denom",
+                value: "vault_share",
+              ),
+              (
+                key: "amount",
+                value: "25",
+              ),
+              (
+                key: "recipient",
+                value: "treasury",
+              ),
+            ],
+          ),
+        ]"#]],
+    );
+
+    // with the surplus swept, only the held shares remain
+    deps.querier.update_balance(
+        mock_env().contract.address,
+        vec![coin(shares_amount(1_000), VAULT_SHARE)],
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        sweep(VAULT_SHARE).into(),
     )
+    .unwrap_err();
+
+    check(
+        err.to_string(),
+        expect![[r#""no unaccounted balance of vault_share to sweep""#]],
+    );
 }
 
 #[test]
@@ -4,15 +4,24 @@ use anyhow::Error;
 use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Response};
 use neutron_sdk::bindings::msg::NeutronMsg;
 
+use amulet_core::mint::{Cmd, ConfigCmd};
+
 use amulet_cw::{
     admin::{self, Repository as AdminRespository},
     mint::{self, Repository as MintRepository},
+    state_version::{self, MigrationStep},
     MigrateMsg,
 };
 use amulet_ntrn::token_factory::TokenFactory;
 
 use self::msg::{AdminExecuteMsg, ExecuteMsg, InstantiateMsg, MintExecuteMsg, QueryMsg};
 
+/// State migrations, the step at index `n` upgrades state from version `n` to `n + 1`
+const MIGRATIONS: &[MigrationStep] = &[
+    // v0 -> v1: scope the legacy global whitelist to each synthetic
+    mint::migrate_global_whitelist,
+];
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
@@ -22,6 +31,8 @@ pub fn instantiate(
 ) -> Result<Response, Error> {
     admin::init(deps.storage, &info);
 
+    state_version::init(deps.storage, MIGRATIONS);
+
     Ok(Response::default())
 }
 
@@ -39,9 +50,21 @@ pub fn execute_mint_msg(
         msg,
     )?;
 
-    let sub_msgs = mint::handle_cmd(deps.storage, TokenFactory::new(&env), cmd);
+    let token_factory = TokenFactory::new(&env);
+
+    if let Cmd::Config(ConfigCmd::CreateSynthetic { .. }) = &cmd {
+        let fee = token_factory.denom_creation_fee(&deps.querier)?;
+
+        mint::check_denom_creation_fee(&deps.querier, &env.contract.address, &fee)?;
+    }
+
+    let event = mint::cmd_event(&cmd);
+
+    let sub_msgs = mint::handle_cmd(deps.storage, token_factory, cmd);
 
-    Ok(Response::default().add_submessages(sub_msgs))
+    Ok(Response::default()
+        .add_submessages(sub_msgs)
+        .add_events(event))
 }
 
 pub fn execute_admin_msg(
@@ -86,8 +109,12 @@ pub fn query(deps: Deps, _: Env, msg: QueryMsg) -> Result<Binary, Error> {
 }
 
 #[entry_point]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, Error> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, Error> {
+    let migration = state_version::migrate_state(deps, MIGRATIONS)?;
+
+    Ok(Response::default()
+        .add_attribute("state_version_from", migration.from.to_string())
+        .add_attribute("state_version_to", migration.to.to_string()))
 }
 
 #[cfg(test)]
@@ -1,17 +1,59 @@
+use std::marker::PhantomData;
+
 use cosmwasm_std::{
     coins, from_json,
-    testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage},
-    Addr, Binary, Empty, MessageInfo, OwnedDeps,
+    testing::{mock_env, MockApi, MockQuerier, MockStorage},
+    to_json_binary, Addr, Binary, Coin, Empty, MessageInfo, OwnedDeps, Querier, QuerierResult,
+    QueryRequest, Response, Storage, SystemResult,
 };
+use neutron_sdk::bindings::msg::NeutronMsg;
 use test_utils::prelude::*;
 
-use amulet_cw::mint::{AllAssetsResponse, Metadata, WhitelistedResponse};
+use amulet_cw::{
+    mint::{
+        AllAssetsResponse, Metadata, WhitelistedDenomsResponse, WhitelistedMintersResponse,
+        WhitelistedResponse,
+    },
+    MigrateMsg,
+};
+use amulet_ntrn::{
+    query::{QueryTokenFactoryParamsResponse, TokenFactoryParams},
+    token_factory::Flavour,
+};
 
 use crate::msg::{AdminExecuteMsg, AdminQueryMsg, InstantiateMsg, MintExecuteMsg, MintQueryMsg};
 
-use super::{execute, instantiate, query};
+use super::{execute, instantiate, migrate, query};
 
-type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>;
+type MockDeps = OwnedDeps<MockStorage, MockApi, TokenFactoryQuerier, Empty>;
+
+struct TokenFactoryQuerier {
+    base: MockQuerier,
+    denom_creation_fee: Vec<Coin>,
+}
+
+impl Querier for TokenFactoryQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        match from_json(bin_request) {
+            Ok(QueryRequest::<Empty>::Stargate { path, .. })
+                if path == Flavour::Neutron.params_query_path() =>
+            {
+                SystemResult::Ok(
+                    to_json_binary(&QueryTokenFactoryParamsResponse {
+                        params: TokenFactoryParams {
+                            denom_creation_fee: self.denom_creation_fee.clone(),
+                            denom_creation_gas_consume: None,
+                            fee_collector_address: None,
+                        },
+                    })
+                    .into(),
+                )
+            }
+
+            _ => self.base.raw_query(bin_request),
+        }
+    }
+}
 
 macro_rules! info {
     ($sender:literal) => {
@@ -29,7 +71,22 @@ macro_rules! info {
 }
 
 fn init() -> MockDeps {
-    let mut deps = mock_dependencies();
+    init_with_denom_creation_fee(vec![], vec![])
+}
+
+fn init_with_denom_creation_fee(fee: Vec<Coin>, contract_balance: Vec<Coin>) -> MockDeps {
+    let mut deps = OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: TokenFactoryQuerier {
+            base: MockQuerier::new(&[(
+                mock_env().contract.address.as_str(),
+                contract_balance.as_slice(),
+            )]),
+            denom_creation_fee: fee,
+        },
+        custom_query_type: PhantomData,
+    };
 
     instantiate(
         deps.as_mut(),
@@ -128,6 +185,9 @@ fn create_synthetic() {
                   denom: "factory/cosmos2contract/synth",
                   ticker: "synth",
                   decimals: 6,
+                  display_ticker: "SYNTH",
+                  display_decimals: 6,
+                  description: "",
                 ),
               ],
               total_count: 1,
@@ -151,113 +211,415 @@ fn create_synthetic() {
               denom: "factory/cosmos2contract/synth",
               ticker: "synth",
               decimals: 6,
+              display_ticker: "SYNTH",
+              display_decimals: 6,
+              description: "",
             )"#]],
     );
 }
 
 #[test]
-fn set_whitelisted() {
-    let mut deps = init();
+fn create_synthetic_with_denom_creation_fee() {
+    let mut deps =
+        init_with_denom_creation_fee(coins(1_000_000, "untrn"), coins(1_000_000, "untrn"));
 
     let response = execute(
         deps.as_mut(),
         mock_env(),
-        info!("creator"),
-        MintExecuteMsg::SetWhitelisted {
-            minter: "minter".into(),
-            whitelisted: true,
+        info!("creator", 1_000_000, "untrn"),
+        MintExecuteMsg::CreateSynthetic {
+            ticker: "SYNTH".into(),
+            decimals: 6,
         }
         .into(),
     )
     .unwrap();
 
-    check(
-        response,
-        expect![[r#"
-            (
-              messages: [],
-              attributes: [],
-              events: [],
-              data: None,
-            )"#]],
+    assert_eq!(response.messages.len(), 2);
+}
+
+#[test]
+fn create_synthetic_without_denom_creation_fee_errs() {
+    let mut deps = init_with_denom_creation_fee(coins(1_000_000, "untrn"), coins(500_000, "untrn"));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator", 500_000, "untrn"),
+        MintExecuteMsg::CreateSynthetic {
+            ticker: "SYNTH".into(),
+            decimals: 6,
+        }
+        .into(),
+    )
+    .unwrap_err();
+
+    check_err(
+        err.root_cause(),
+        expect!["denom creation fee required: 1000000 untrn"],
     );
 
+    // nothing was created
     check(
         query(
             deps.as_ref(),
             mock_env(),
-            MintQueryMsg::Whitelisted {
-                minter: "minter".into(),
+            MintQueryMsg::AllAssets {
+                page: None,
+                limit: None,
             }
             .into(),
         )
-        .map(from_json::<WhitelistedResponse>)
-        .unwrap()
+        .map(into_json_string)
         .unwrap(),
-        expect![[r#"
-            (
-              whitelisted: true,
-            )"#]],
+        expect![[r#""{\"assets\":[],\"total_count\":0}""#]],
     );
+}
 
+#[test]
+fn create_synthetic_with_invalid_ticker_errs() {
+    let mut deps = init();
+
+    for (ticker, expected) in [
+        (
+            "SYN/TH".to_owned(),
+            expect!["ticker contains '/', only ascii letters and digits are allowed"],
+        ),
+        (
+            "S".repeat(33),
+            expect!["ticker must be between 3 and 32 characters long, got 33"],
+        ),
+    ] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info!("creator"),
+            MintExecuteMsg::CreateSynthetic {
+                ticker,
+                decimals: 6,
+            }
+            .into(),
+        )
+        .unwrap_err();
+
+        check_err(err.root_cause(), expected);
+    }
+}
+
+const SYNTH: &str = "factory/cosmos2contract/synth";
+const OTHER: &str = "factory/cosmos2contract/other";
+
+fn init_synthetic(deps: &mut MockDeps, ticker: &str) {
     execute(
         deps.as_mut(),
         mock_env(),
         info!("creator"),
-        MintExecuteMsg::SetWhitelisted {
-            minter: "minter".into(),
-            whitelisted: false,
+        MintExecuteMsg::CreateSynthetic {
+            ticker: ticker.into(),
+            decimals: 6,
         }
         .into(),
     )
     .unwrap();
+}
 
-    check(
-        query(
-            deps.as_ref(),
-            mock_env(),
-            MintQueryMsg::Whitelisted {
-                minter: "minter".into(),
-            }
-            .into(),
-        )
-        .map(from_json::<WhitelistedResponse>)
-        .unwrap()
-        .unwrap(),
-        expect![[r#"
-            (
-              whitelisted: false,
-            )"#]],
-    );
+fn whitelist(deps: &mut MockDeps, denom: &str, minter: &str, enabled: bool) {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        MintExecuteMsg::SetWhitelistedMinter {
+            denom: denom.into(),
+            minter: minter.into(),
+            enabled,
+        }
+        .into(),
+    )
+    .unwrap();
+}
+
+fn is_whitelisted(deps: &MockDeps, denom: &str, minter: &str) -> bool {
+    query(
+        deps.as_ref(),
+        mock_env(),
+        MintQueryMsg::Whitelisted {
+            denom: denom.into(),
+            minter: minter.into(),
+        }
+        .into(),
+    )
+    .map(from_json::<WhitelistedResponse>)
+    .unwrap()
+    .unwrap()
+    .whitelisted
+}
+
+fn whitelisted_minters(deps: &MockDeps, denom: &str) -> Vec<String> {
+    query(
+        deps.as_ref(),
+        mock_env(),
+        MintQueryMsg::WhitelistedMinters {
+            denom: denom.into(),
+        }
+        .into(),
+    )
+    .map(from_json::<WhitelistedMintersResponse>)
+    .unwrap()
+    .unwrap()
+    .minters
+}
+
+fn whitelisted_denoms(deps: &MockDeps, minter: &str) -> Vec<String> {
+    query(
+        deps.as_ref(),
+        mock_env(),
+        MintQueryMsg::WhitelistedDenoms {
+            minter: minter.into(),
+        }
+        .into(),
+    )
+    .map(from_json::<WhitelistedDenomsResponse>)
+    .unwrap()
+    .unwrap()
+    .denoms
+}
+
+fn mint_err(deps: &mut MockDeps, minter: &'static str, denom: &str) -> Option<String> {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        MessageInfo {
+            sender: Addr::unchecked(minter),
+            funds: vec![],
+        },
+        MintExecuteMsg::Mint {
+            synthetic: denom.into(),
+            amount: 1_000u128.into(),
+            recipient: "bob".into(),
+        }
+        .into(),
+    )
+    .err()
+    .map(|err| err.to_string())
 }
 
 #[test]
-fn mint() {
+fn set_whitelisted_minter() {
     let mut deps = init();
 
-    execute(
+    init_synthetic(&mut deps, "SYNTH");
+
+    let response = execute(
         deps.as_mut(),
         mock_env(),
         info!("creator"),
-        MintExecuteMsg::SetWhitelisted {
+        MintExecuteMsg::SetWhitelistedMinter {
+            denom: SYNTH.into(),
             minter: "minter".into(),
-            whitelisted: true,
+            enabled: true,
         }
         .into(),
     )
     .unwrap();
 
-    execute(
+    check(
+        response,
+        expect![[r#"
+            (
+              messages: [],
+              attributes: [],
+              events: [],
+              data: None,
+            )"#]],
+    );
+
+    assert!(is_whitelisted(&deps, SYNTH, "minter"));
+
+    whitelist(&mut deps, SYNTH, "minter", false);
+
+    assert!(!is_whitelisted(&deps, SYNTH, "minter"));
+
+    let err = execute(
         deps.as_mut(),
         mock_env(),
         info!("creator"),
-        MintExecuteMsg::CreateSynthetic {
-            ticker: "SYNTH".into(),
-            decimals: 6,
+        MintExecuteMsg::SetWhitelistedMinter {
+            denom: OTHER.into(),
+            minter: "minter".into(),
+            enabled: true,
         }
         .into(),
     )
-    .unwrap();
+    .unwrap_err();
+
+    check(err.to_string(), expect![[r#""synthetic not found""#]]);
+}
+
+#[test]
+fn whitelisted_minters_and_denoms() {
+    let mut deps = init();
+
+    init_synthetic(&mut deps, "SYNTH");
+    init_synthetic(&mut deps, "OTHER");
+
+    whitelist(&mut deps, SYNTH, "minter_a", true);
+    whitelist(&mut deps, SYNTH, "minter_b", true);
+    whitelist(&mut deps, SYNTH, "minter_c", true);
+    whitelist(&mut deps, OTHER, "minter_b", true);
+
+    check(
+        whitelisted_minters(&deps, SYNTH),
+        expect![[r#"
+        [
+          "minter_a",
+          "minter_b",
+          "minter_c",
+        ]"#]],
+    );
+    check(
+        whitelisted_minters(&deps, OTHER),
+        expect![[r#"
+        [
+          "minter_b",
+        ]"#]],
+    );
+    check(
+        whitelisted_denoms(&deps, "minter_b"),
+        expect![[r#"
+        [
+          "factory/cosmos2contract/synth",
+          "factory/cosmos2contract/other",
+        ]"#]],
+    );
+
+    whitelist(&mut deps, SYNTH, "minter_a", false);
+    whitelist(&mut deps, OTHER, "minter_b", false);
+
+    check(
+        whitelisted_minters(&deps, SYNTH),
+        expect![[r#"
+        [
+          "minter_c",
+          "minter_b",
+        ]"#]],
+    );
+    check(whitelisted_minters(&deps, OTHER), expect!["[]"]);
+    check(whitelisted_denoms(&deps, "minter_a"), expect!["[]"]);
+    check(
+        whitelisted_denoms(&deps, "minter_b"),
+        expect![[r#"
+        [
+          "factory/cosmos2contract/synth",
+        ]"#]],
+    );
+}
+
+#[test]
+fn minter_of_one_synthetic_cannot_mint_another() {
+    let mut deps = init();
+
+    init_synthetic(&mut deps, "SYNTH");
+    init_synthetic(&mut deps, "OTHER");
+
+    whitelist(&mut deps, SYNTH, "minter", true);
+
+    assert_eq!(mint_err(&mut deps, "minter", SYNTH), None);
+
+    check(
+        mint_err(&mut deps, "minter", OTHER),
+        expect![[r#"Some("unauthorized")"#]],
+    );
+}
+
+#[test]
+fn migrate_global_whitelist() {
+    let mut deps = init();
+
+    init_synthetic(&mut deps, "SYNTH");
+    init_synthetic(&mut deps, "OTHER");
+
+    // fixture: state written before the state version header, with entries in the legacy global whitelist,
+    // `mint::whitelist<minter>:`
+    deps.storage.remove(b"state_version::version");
+    deps.storage.set(b"mint::whitelistminter_a:", &[1]);
+    deps.storage.set(b"mint::whitelistminter_b:", &[1]);
+
+    let response = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    check(
+        response.attributes,
+        expect![[r#"
+            [
+              (
+                key: "state_version_from",
+                value: "v0",
+              ),
+              (
+                key: "state_version_to",
+                value: "v1",
+              ),
+            ]"#]],
+    );
+
+    // behaviour is preserved: legacy minters can mint every existing synthetic
+    check(
+        whitelisted_minters(&deps, SYNTH),
+        expect![[r#"
+        [
+          "minter_a",
+          "minter_b",
+        ]"#]],
+    );
+    check(
+        whitelisted_minters(&deps, OTHER),
+        expect![[r#"
+        [
+          "minter_a",
+          "minter_b",
+        ]"#]],
+    );
+
+    assert_eq!(mint_err(&mut deps, "minter_a", SYNTH), None);
+    assert_eq!(mint_err(&mut deps, "minter_a", OTHER), None);
+
+    // legacy entries are removed & the state version recorded, so migrating again is a no-op
+    assert_eq!(deps.storage.get(b"mint::whitelistminter_a:"), None);
+
+    let response = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    check(
+        response.attributes,
+        expect![[r#"
+            [
+              (
+                key: "state_version_from",
+                value: "v1",
+              ),
+              (
+                key: "state_version_to",
+                value: "v1",
+              ),
+            ]"#]],
+    );
+
+    // scoping a migrated minter to a single synthetic
+    whitelist(&mut deps, OTHER, "minter_a", false);
+
+    assert_eq!(mint_err(&mut deps, "minter_a", SYNTH), None);
+
+    check(
+        mint_err(&mut deps, "minter_a", OTHER),
+        expect![[r#"Some("unauthorized")"#]],
+    );
+}
+
+#[test]
+fn mint() {
+    let mut deps = init();
+
+    init_synthetic(&mut deps, "SYNTH");
+
+    whitelist(&mut deps, SYNTH, "minter", true);
 
     let response = execute(
         deps.as_mut(),
@@ -311,6 +673,9 @@ fn mint() {
               denom: "factory/cosmos2contract/synth",
               ticker: "synth",
               decimals: 6,
+              display_ticker: "SYNTH",
+              display_decimals: 6,
+              description: "",
             )"#]],
     );
 }
@@ -319,29 +684,9 @@ fn mint() {
 fn burn() {
     let mut deps = init();
 
-    execute(
-        deps.as_mut(),
-        mock_env(),
-        info!("creator"),
-        MintExecuteMsg::SetWhitelisted {
-            minter: "minter".into(),
-            whitelisted: true,
-        }
-        .into(),
-    )
-    .unwrap();
+    init_synthetic(&mut deps, "SYNTH");
 
-    execute(
-        deps.as_mut(),
-        mock_env(),
-        info!("creator"),
-        MintExecuteMsg::CreateSynthetic {
-            ticker: "SYNTH".into(),
-            decimals: 6,
-        }
-        .into(),
-    )
-    .unwrap();
+    whitelist(&mut deps, SYNTH, "minter", true);
 
     execute(
         deps.as_mut(),
@@ -359,7 +704,7 @@ fn burn() {
     let response = execute(
         deps.as_mut(),
         mock_env(),
-        info!("bob", 500, "factory/cosmos2contract/synth"),
+        info!("minter", 500, "factory/cosmos2contract/synth"),
         MintExecuteMsg::Burn {}.into(),
     )
     .unwrap();
@@ -387,6 +732,200 @@ fn burn() {
     );
 }
 
+fn update_metadata(
+    deps: &mut MockDeps,
+    sender: &'static str,
+    display_ticker: Option<&str>,
+    display_decimals: Option<u32>,
+    description: Option<&str>,
+) -> Result<Response<NeutronMsg>, anyhow::Error> {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        MessageInfo {
+            sender: Addr::unchecked(sender),
+            funds: vec![],
+        },
+        MintExecuteMsg::UpdateSyntheticMetadata {
+            denom: SYNTH.into(),
+            display_ticker: display_ticker.map(str::to_owned),
+            display_decimals,
+            description: description.map(str::to_owned),
+        }
+        .into(),
+    )
+}
+
+fn synthetic_metadata(deps: &MockDeps) -> Metadata {
+    query(
+        deps.as_ref(),
+        mock_env(),
+        MintQueryMsg::Synthetic {
+            denom: SYNTH.into(),
+        }
+        .into(),
+    )
+    .map(from_json::<Metadata>)
+    .unwrap()
+    .unwrap()
+}
+
+#[test]
+fn update_synthetic_metadata() {
+    let mut deps = init();
+
+    init_synthetic(&mut deps, "SYNTH");
+
+    let response = update_metadata(&mut deps, "creator", None, None, Some("Synthetic")).unwrap();
+
+    check(
+        response,
+        expect![[r#"
+            (
+              messages: [
+                (
+                  id: 0,
+                  msg: custom(set_denom_metadata(
+                    description: "Synthetic",
+                    denom_units: [
+                      (
+                        denom: "factory/cosmos2contract/synth",
+                        exponent: 0,
+                        aliases: [],
+                      ),
+                      (
+                        denom: "SYNTH",
+                        exponent: 6,
+                        aliases: [],
+                      ),
+                    ],
+                    base: "factory/cosmos2contract/synth",
+                    display: "SYNTH",
+                    name: "SYNTH",
+                    symbol: "SYNTH",
+                    uri: "",
+                    uri_hash: "",
+                  )),
+                  gas_limit: None,
+                  reply_on: never,
+                ),
+              ],
+              attributes: [],
+              events: [
+                (
+                  type: "synthetic_metadata_updated",
+                  attributes: [
+                    (
+                      key: "denom",
+                      value: "factory/cosmos2contract/synth",
+                    ),
+                    (
+                      key: "old_display_ticker",
+                      value: "SYNTH",
+                    ),
+                    (
+                      key: "new_display_ticker",
+                      value: "SYNTH",
+                    ),
+                    (
+                      key: "old_display_decimals",
+                      value: "6",
+                    ),
+                    (
+                      key: "new_display_decimals",
+                      value: "6",
+                    ),
+                    (
+                      key: "old_description",
+                      value: "",
+                    ),
+                    (
+                      key: "new_description",
+                      value: "Synthetic",
+                    ),
+                  ],
+                ),
+              ],
+              data: None,
+            )"#]],
+    );
+
+    // the display ticker may change beyond case, the denom metadata keeps its base
+    let response = update_metadata(&mut deps, "creator", Some("aSYNTH"), Some(8), None).unwrap();
+
+    check(
+        &response.messages[0].msg,
+        expect![[r#"
+            custom(set_denom_metadata(
+              description: "Synthetic",
+              denom_units: [
+                (
+                  denom: "factory/cosmos2contract/synth",
+                  exponent: 0,
+                  aliases: [],
+                ),
+                (
+                  denom: "aSYNTH",
+                  exponent: 8,
+                  aliases: [],
+                ),
+              ],
+              base: "factory/cosmos2contract/synth",
+              display: "aSYNTH",
+              name: "aSYNTH",
+              symbol: "aSYNTH",
+              uri: "",
+              uri_hash: "",
+            ))"#]],
+    );
+
+    // the ticker the denom derives from & the decimals used for value math are unchanged
+    check(
+        synthetic_metadata(&deps),
+        expect![[r#"
+            (
+              denom: "factory/cosmos2contract/synth",
+              ticker: "synth",
+              decimals: 6,
+              display_ticker: "aSYNTH",
+              display_decimals: 8,
+              description: "Synthetic",
+            )"#]],
+    );
+}
+
+#[test]
+fn update_synthetic_metadata_errs() {
+    let mut deps = init();
+
+    init_synthetic(&mut deps, "SYNTH");
+
+    check_err(
+        update_metadata(&mut deps, "bob", None, Some(8), None)
+            .unwrap_err()
+            .root_cause(),
+        expect!["unauthorized"],
+    );
+
+    init_synthetic(&mut deps, "OTHER");
+
+    check_err(
+        update_metadata(&mut deps, "creator", Some("other"), None, None)
+            .unwrap_err()
+            .root_cause(),
+        expect!["ticker already exists"],
+    );
+
+    check_err(
+        update_metadata(&mut deps, "creator", Some("SYN TH"), None, None)
+            .unwrap_err()
+            .root_cause(),
+        expect!["ticker contains ' ', only ascii letters and digits are allowed"],
+    );
+
+    assert_eq!(synthetic_metadata(&deps).display_decimals, 6);
+}
+
 #[test]
 fn admin() {
     let mut deps = init();
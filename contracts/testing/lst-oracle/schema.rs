@@ -1,3 +1,4 @@
+use amulet_cw::MigrateMsg;
 use cosmwasm_schema::write_api;
 use mock_lst_oracle::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 
@@ -5,6 +6,7 @@ fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
-        query: QueryMsg
+        query: QueryMsg,
+        migrate: MigrateMsg
     }
 }
@@ -2,29 +2,46 @@ pub mod msg;
 pub mod state;
 pub mod strategy;
 
-use anyhow::Error;
+use anyhow::{anyhow, bail, Error};
 use cosmwasm_std::{
     entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
-    Storage, SubMsg,
+    SubMsg,
 };
 use neutron_sdk::bindings::msg::NeutronMsg;
 
 use amulet_core::vault::Cmd as VaultCmd;
 use amulet_cw::{
     admin::{self, ExecuteMsg as AdminExecuteMsg, Repository as AdminRepository},
+    bank,
+    state_version::{self, MigrationStep},
     vault::{
-        self, handle_mint_cmd, handle_unbonding_log_cmd, init_mint_msg,
-        ExecuteMsg as VaultExecuteMsg, SharesMint, UnbondingLog,
+        self,
+        accepted_assets::{AcceptedAsset, AcceptedAssets, StorageExt as _},
+        feature, handle_mint_cmd, handle_shares_transfer_sudo_msg, handle_unbonding_log_cmd,
+        init_mint_msg, restrict_shares_msg, ExecuteMsg as VaultExecuteMsg, SharesMint,
+        UnbondingLog,
     },
     MigrateMsg,
 };
 use amulet_ntrn::token_factory::TokenFactory;
 
 use self::msg::{
-    ExecuteMsg, InstantiateMsg, MetadataResponse, QueryMsg, StrategyExecuteMsg, StrategyQueryMsg,
+    ExecuteMsg, InstantiateMsg, MetadataResponse, QueryMsg, SecondaryBalances, StrategyExecuteMsg,
+    StrategyQueryMsg, SudoMsg, UnaccountedBalancesResponse,
 };
 use self::state::StorageExt as _;
-use self::strategy::{lst_redeption_rate, Strategy};
+use self::strategy::{lst_rates, Strategy};
+
+/// State migrations, the step at index `n` upgrades state from version `n` to `n + 1`
+const MIGRATIONS: &[MigrationStep] = &[];
+
+/// The optional vault interface features supported, reported by the interface version query
+const FEATURES: &[&str] = &[
+    feature::DONATE,
+    feature::REDEEM_ON_BEHALF,
+    feature::CLAIM_ALL,
+    feature::UNBOND_SCHEDULE,
+];
 
 #[entry_point]
 pub fn instantiate(
@@ -35,6 +52,8 @@ pub fn instantiate(
 ) -> Result<Response<NeutronMsg>, Error> {
     admin::init(deps.storage, &info);
 
+    state_version::init(deps.storage, MIGRATIONS);
+
     deps.api.addr_validate(&msg.lst_redemption_rate_oracle)?;
 
     deps.storage
@@ -49,7 +68,21 @@ pub fn instantiate(
 
     let init_mint_msg = init_mint_msg(TokenFactory::new(&env));
 
-    Ok(Response::default().add_message(init_mint_msg))
+    let mut response = Response::default().add_message(init_mint_msg);
+
+    if msg.restricted_shares.unwrap_or_default() {
+        let hub = msg
+            .hub
+            .ok_or_else(|| anyhow!("hub is required to restrict shares"))?;
+
+        let hub = deps.api.addr_validate(&hub)?;
+
+        let restrict_msg = restrict_shares_msg(deps.storage, TokenFactory::new(&env), &env, &hub);
+
+        response = response.add_message(restrict_msg);
+    }
+
+    Ok(response)
 }
 
 pub fn execute_admin_msg(
@@ -73,9 +106,11 @@ pub fn execute_vault_msg(
     info: MessageInfo,
     msg: VaultExecuteMsg,
 ) -> Result<Response<NeutronMsg>, Error> {
-    let redemption_rate = lst_redeption_rate(deps.storage, deps.querier)?;
+    let rates = lst_rates(deps.storage, deps.querier)?;
 
-    let strategy = Strategy::new(deps.storage, &env, redemption_rate);
+    let accepted_assets = AcceptedAssets::query(deps.storage, deps.querier)?;
+
+    let strategy = Strategy::new(deps.storage, &env, rates, &accepted_assets);
 
     let unbonding_log = UnbondingLog::new(deps.storage);
 
@@ -93,7 +128,8 @@ pub fn execute_vault_msg(
             }
 
             VaultCmd::Strategy(cmd) => {
-                if let Some(msg) = strategy::handle_cmd(deps.storage, redemption_rate, cmd) {
+                if let Some(msg) = strategy::handle_cmd(deps.storage, rates, &accepted_assets, cmd)
+                {
                     response.messages.push(SubMsg::new(msg));
                 }
             }
@@ -102,12 +138,14 @@ pub fn execute_vault_msg(
         }
     }
 
+    response.messages = bank::coalesce_sends(response.messages);
+
     Ok(response)
 }
 
 pub fn execute_strategy_msg(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: StrategyExecuteMsg,
 ) -> Result<Response<NeutronMsg>, Error> {
@@ -119,6 +157,64 @@ pub fn execute_strategy_msg(
 
             deps.storage.set_lst_redemption_rate_oracle(&oracle);
         }
+
+        StrategyExecuteMsg::SetMarketPriceOracle { oracle } => {
+            let repository = AdminRepository::new(deps.storage);
+
+            let _ = admin::get_admin_role(&repository, &info)?;
+
+            match oracle {
+                Some(oracle) => {
+                    deps.api.addr_validate(&oracle)?;
+
+                    deps.storage.set_lst_market_price_oracle(&oracle);
+                }
+
+                None => deps.storage.clear_lst_market_price_oracle(),
+            }
+        }
+
+        StrategyExecuteMsg::SetAcceptedAsset { denom, conversion } => {
+            let repository = AdminRepository::new(deps.storage);
+
+            let _ = admin::get_admin_role(&repository, &info)?;
+
+            if denom == deps.storage.lst_denom() {
+                bail!("{denom} is the primary deposit asset");
+            }
+
+            match conversion {
+                Some(conversion) => {
+                    deps.api.addr_validate(&conversion)?;
+
+                    deps.storage.set_accepted_asset(&denom, &conversion);
+                }
+
+                None => {
+                    let (claimable, _) = deps.storage.claimable_secondary(&denom);
+
+                    if claimable != 0 {
+                        bail!("{denom} is still claimable from the vault");
+                    }
+
+                    deps.storage.remove_accepted_asset(&denom)?;
+                }
+            }
+        }
+
+        StrategyExecuteMsg::SweepUnaccounted { denom, recipient } => {
+            let repository = AdminRepository::new(deps.storage);
+
+            let _ = admin::get_admin_role(&repository, &info)?;
+
+            deps.api.addr_validate(&recipient)?;
+
+            let unaccounted = strategy::unaccounted_balances(deps.storage, deps.querier, &env)?;
+
+            let (sweep, swept) = bank::sweep_unaccounted(unaccounted, &denom, recipient)?;
+
+            return Ok(Response::default().add_message(sweep).add_event(swept));
+        }
     }
 
     Ok(Response::default())
@@ -139,17 +235,34 @@ pub fn execute(
 }
 
 pub fn handle_strategy_query(
-    storage: &dyn Storage,
+    deps: Deps,
+    env: &Env,
     query: StrategyQueryMsg,
 ) -> Result<Binary, StdError> {
+    let storage = deps.storage;
+
     match query {
         StrategyQueryMsg::Metadata {} => to_json_binary(&MetadataResponse {
             lst_redemption_rate_oracle: storage.lst_redemption_rate_oracle(),
+            lst_market_price_oracle: storage.lst_market_price_oracle(),
             lst_denom: storage.lst_denom(),
             lst_decimals: storage.lst_decimals(),
             underlying_decimals: storage.underlying_decimals(),
             active_lst_balance: storage.active_lst_balance().into(),
             claimable_lst_balance: storage.claimable_lst_balance().into(),
+            secondary_balances: storage
+                .accepted_assets()
+                .into_iter()
+                .map(|AcceptedAsset { denom, .. }| SecondaryBalances {
+                    active: storage.secondary_balance(&denom).into(),
+                    claimable: storage.claimable_secondary(&denom).0.into(),
+                    denom,
+                })
+                .collect(),
+        }),
+
+        StrategyQueryMsg::UnaccountedBalances {} => to_json_binary(&UnaccountedBalancesResponse {
+            balances: strategy::unaccounted_balances(storage, deps.querier, env)?,
         }),
     }
 }
@@ -164,28 +277,42 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, Error> {
         }
 
         QueryMsg::Vault(vault_query) => {
-            let redemption_rate = lst_redeption_rate(deps.storage, deps.querier)?;
+            let rates = lst_rates(deps.storage, deps.querier)?;
+
+            let accepted_assets = AcceptedAssets::query(deps.storage, deps.querier)?;
 
             vault::handle_query_msg(
                 deps.storage,
-                &Strategy::new(deps.storage, &env, redemption_rate),
+                &Strategy::new(deps.storage, &env, rates, &accepted_assets),
                 &UnbondingLog::new(deps.storage),
                 &SharesMint::new(deps.storage, &env),
                 &env,
+                FEATURES,
                 vault_query,
             )?
         }
 
-        QueryMsg::Strategy(strategy_query) => handle_strategy_query(deps.storage, strategy_query)?,
+        QueryMsg::Strategy(strategy_query) => handle_strategy_query(deps, &env, strategy_query)?,
     };
 
     Ok(binary)
 }
 
 #[entry_point]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, Error> {
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response<NeutronMsg>, Error> {
+    handle_shares_transfer_sudo_msg(deps.storage, &env, msg)?;
+
     Ok(Response::default())
 }
 
+#[entry_point]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, Error> {
+    let migration = state_version::migrate_state(deps, MIGRATIONS)?;
+
+    Ok(Response::default()
+        .add_attribute("state_version_from", migration.from.to_string())
+        .add_attribute("state_version_to", migration.to.to_string()))
+}
+
 #[cfg(test)]
 mod test;
@@ -3,7 +3,10 @@ use cosmwasm_std::Uint128;
 
 pub use amulet_cw::{
     admin::{ExecuteMsg as AdminExecuteMsg, QueryMsg as AdminQueryMsg},
-    vault::{ExecuteMsg as VaultExecuteMsg, QueryMsg as VaultQueryMsg},
+    bank::UnaccountedBalancesResponse,
+    vault::{
+        ExecuteMsg as VaultExecuteMsg, QueryMsg as VaultQueryMsg, SharesTransferSudoMsg as SudoMsg,
+    },
 };
 
 #[cw_serde]
@@ -12,21 +15,59 @@ pub struct InstantiateMsg {
     pub lst_denom: String,
     pub lst_decimals: u32,
     pub underlying_decimals: u32,
+    /// Restrict shares transfers to the hub and this vault, requires `hub` to be set
+    pub restricted_shares: Option<bool>,
+    /// The hub permitted to hold shares when they are restricted
+    pub hub: Option<String>,
 }
 
 #[cw_serde]
 pub struct MetadataResponse {
     pub lst_redemption_rate_oracle: String,
+    /// The oracle deposits are valued with instead of the redemption rate oracle, if set
+    pub lst_market_price_oracle: Option<String>,
     pub lst_denom: String,
     pub lst_decimals: u32,
     pub underlying_decimals: u32,
     pub active_lst_balance: Uint128,
     pub claimable_lst_balance: Uint128,
+    /// The balances of each accepted secondary deposit asset
+    pub secondary_balances: Vec<SecondaryBalances>,
+}
+
+#[cw_serde]
+pub struct SecondaryBalances {
+    pub denom: String,
+    /// The deposited balance, valued as its LST equivalent
+    pub active: Uint128,
+    /// The balance unbonded to settle claims
+    pub claimable: Uint128,
 }
 
 #[cw_serde]
 pub enum StrategyExecuteMsg {
-    SetRedemptionRateOracle { oracle: String },
+    SetRedemptionRateOracle {
+        oracle: String,
+    },
+    /// Admin role required
+    /// Value deposits at the LST's market price reported by the `oracle` rather than its redemption rate,
+    /// which is then reported to the hub for valuing collateral. `None` values deposits at the redemption rate.
+    SetMarketPriceOracle {
+        oracle: Option<String>,
+    },
+    /// Admin role required
+    /// Accept deposits of `denom` converted into the LST via the `conversion` oracle,
+    /// `None` stops accepting `denom` once none of it is held by the vault
+    SetAcceptedAsset {
+        denom: String,
+        conversion: Option<String>,
+    },
+    /// Admin role required
+    /// Send the vault's entire balance of `denom` in excess of the deposits it tracks to the `recipient`
+    SweepUnaccounted {
+        denom: String,
+        recipient: String,
+    },
 }
 
 #[cw_serde]
@@ -42,6 +83,9 @@ pub enum ExecuteMsg {
 pub enum StrategyQueryMsg {
     #[returns(MetadataResponse)]
     Metadata {},
+    /// The vault's balance of each denom in excess of its active & claimable LST balances
+    #[returns(UnaccountedBalancesResponse)]
+    UnaccountedBalances {},
 }
 
 #[cw_serde]
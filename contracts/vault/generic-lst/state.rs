@@ -3,18 +3,29 @@ use cosmwasm_std::Storage;
 
 #[rustfmt::skip]
 mod key {
+    use amulet_cw::MapKey;
+
     macro_rules! key {
         ($k:literal) => {
             concat!("generic_lst::", $k)
         };
     }
 
+    macro_rules! map_key {
+        ($k:literal) => {
+            amulet_cw::MapKey::new(key!($k))
+        };
+    }
+
     pub const LST_REDEMPTION_RATE_ORACLE : &str = key!("lst_redemption_rate_oracle");
+    pub const LST_MARKET_PRICE_ORACLE    : &str = key!("lst_market_price_oracle");
     pub const LST_DENOM                  : &str = key!("lst_denom");
     pub const LST_DECIMALS               : &str = key!("lst_decimals");
     pub const UNDERLYING_DECIMALS        : &str = key!("underlying_decimals");
     pub const ACTIVE_LST_BALANCE         : &str = key!("active_lst_balance");
     pub const CLAIMABLE_LST_BALANCE      : &str = key!("claimable_lst_balance");
+    pub const CLAIMABLE_SECONDARY        : MapKey = map_key!("claimable_secondary");
+    pub const CLAIMABLE_SECONDARY_LST    : MapKey = map_key!("claimable_secondary_lst");
 }
 
 pub trait StorageExt: Storage {
@@ -23,6 +34,10 @@ pub trait StorageExt: Storage {
             .expect("set during initialisation")
     }
 
+    fn lst_market_price_oracle(&self) -> Option<String> {
+        self.string_at(key::LST_MARKET_PRICE_ORACLE)
+    }
+
     fn lst_denom(&self) -> String {
         self.string_at(key::LST_DENOM)
             .expect("set during initialisation")
@@ -46,10 +61,31 @@ pub trait StorageExt: Storage {
         self.u128_at(key::CLAIMABLE_LST_BALANCE).unwrap_or_default()
     }
 
+    /// Returns the claimable balance of the secondary asset `denom` & the amount of LST it settles
+    fn claimable_secondary(&self, denom: &str) -> (u128, u128) {
+        let balance = self
+            .u128_at(key::CLAIMABLE_SECONDARY.with(denom))
+            .unwrap_or_default();
+
+        let lst_equivalent = self
+            .u128_at(key::CLAIMABLE_SECONDARY_LST.with(denom))
+            .unwrap_or_default();
+
+        (balance, lst_equivalent)
+    }
+
     fn set_lst_redemption_rate_oracle(&mut self, oracle: &str) {
         self.set_string(key::LST_REDEMPTION_RATE_ORACLE, oracle);
     }
 
+    fn set_lst_market_price_oracle(&mut self, oracle: &str) {
+        self.set_string(key::LST_MARKET_PRICE_ORACLE, oracle);
+    }
+
+    fn clear_lst_market_price_oracle(&mut self) {
+        self.remove(key::LST_MARKET_PRICE_ORACLE.as_bytes());
+    }
+
     fn set_lst_denom(&mut self, denom: &str) {
         self.set_string(key::LST_DENOM, denom);
     }
@@ -69,6 +105,11 @@ pub trait StorageExt: Storage {
     fn set_claimable_lst_balance(&mut self, balance: u128) {
         self.set_u128(key::CLAIMABLE_LST_BALANCE, balance)
     }
+
+    fn set_claimable_secondary(&mut self, denom: &str, balance: u128, lst_equivalent: u128) {
+        self.set_u128(key::CLAIMABLE_SECONDARY.with(denom), balance);
+        self.set_u128(key::CLAIMABLE_SECONDARY_LST.with(denom), lst_equivalent);
+    }
 }
 
 impl<T> StorageExt for T where T: Storage + ?Sized {}
@@ -1,10 +1,14 @@
 use core::panic;
 
-use amulet_cw::strategy::generic_lst::QuerierExt;
+use amulet_cw::{
+    bank,
+    strategy::generic_lst::QuerierExt,
+    vault::accepted_assets::{self, AcceptedAsset, AcceptedAssets, StorageExt as _},
+};
 use anyhow::Error;
 use cosmwasm_std::{
-    coins, BankMsg, CosmosMsg, CustomQuery, Decimal, Env, Fraction, QuerierWrapper, Storage,
-    Timestamp, Uint128,
+    coin, BankMsg, Coin, CosmosMsg, CustomQuery, Decimal, Env, Fraction, QuerierWrapper, StdError,
+    Storage, Timestamp, Uint128,
 };
 
 use amulet_core::{
@@ -14,6 +18,7 @@ use amulet_core::{
     },
     Asset, Decimals,
 };
+use num::FixedU256;
 
 use crate::state::StorageExt as _;
 
@@ -45,29 +50,83 @@ impl LstRedemptionRate {
     }
 }
 
-pub fn lst_redeption_rate(
+/// The rates of the LST in the underlying asset: deposits are valued at the market price if a
+/// market price oracle is set, otherwise at the redemption rate
+#[derive(Debug, Clone, Copy)]
+pub struct LstRates {
+    redemption_rate: LstRedemptionRate,
+    market_price: Option<LstRedemptionRate>,
+}
+
+impl LstRates {
+    fn valuation(self) -> LstRedemptionRate {
+        self.market_price.unwrap_or(self.redemption_rate)
+    }
+}
+
+pub fn lst_rates(
     storage: &dyn Storage,
     querier: QuerierWrapper<impl CustomQuery>,
-) -> Result<LstRedemptionRate, Error> {
+) -> Result<LstRates, Error> {
     let oracle = storage.lst_redemption_rate_oracle();
 
     let redemption_rate = querier.redemption_rate(&oracle)?;
 
-    Ok(LstRedemptionRate(redemption_rate))
+    // market price oracles answer the same query with the LST's market price
+    let market_price = storage
+        .lst_market_price_oracle()
+        .map(|oracle| querier.redemption_rate(&oracle))
+        .transpose()?;
+
+    Ok(LstRates {
+        redemption_rate: LstRedemptionRate(redemption_rate),
+        market_price: market_price.map(LstRedemptionRate),
+    })
+}
+
+/// Returns the vault's balance of each denom in excess of its active & claimable LST balances
+/// & the active & claimable balances of each accepted secondary asset
+pub fn unaccounted_balances(
+    storage: &dyn Storage,
+    querier: QuerierWrapper<impl CustomQuery>,
+    env: &Env,
+) -> Result<Vec<Coin>, StdError> {
+    let lst_denom = storage.lst_denom();
+
+    let mut tracked = vec![
+        coin(storage.active_lst_balance(), &lst_denom),
+        coin(storage.claimable_lst_balance(), &lst_denom),
+    ];
+
+    for AcceptedAsset { denom, .. } in storage.accepted_assets() {
+        tracked.push(coin(storage.secondary_balance(&denom), &denom));
+        tracked.push(coin(storage.claimable_secondary(&denom).0, &denom));
+    }
+
+    let balances = querier.query_all_balances(&env.contract.address)?;
+
+    Ok(bank::unaccounted_balances(balances, &tracked))
 }
 
 pub struct Strategy<'a> {
     storage: &'a dyn Storage,
     now: Timestamp,
-    redemption_rate: LstRedemptionRate,
+    rates: LstRates,
+    accepted_assets: &'a AcceptedAssets,
 }
 
 impl<'a> Strategy<'a> {
-    pub fn new(storage: &'a dyn Storage, env: &Env, redemption_rate: LstRedemptionRate) -> Self {
+    pub fn new(
+        storage: &'a dyn Storage,
+        env: &Env,
+        rates: LstRates,
+        accepted_assets: &'a AcceptedAssets,
+    ) -> Self {
         Self {
             storage,
             now: env.block.time,
-            redemption_rate,
+            rates,
+            accepted_assets,
         }
     }
 }
@@ -81,27 +140,41 @@ impl<'a> CoreStrategy for Strategy<'a> {
         self.storage.lst_denom().into()
     }
 
+    fn convert_secondary_deposit(
+        &self,
+        asset: &Asset,
+        amount: DepositAmount,
+    ) -> Option<DepositAmount> {
+        self.accepted_assets.convert(asset, amount)
+    }
+
     fn underlying_asset_decimals(&self) -> Decimals {
         self.storage.underlying_decimals()
     }
 
     fn total_deposits_value(&self) -> TotalDepositsValue {
-        let active_lst_balance = self.storage.active_lst_balance();
+        // secondary deposits are held as-is & valued as their LST equivalent
+        let active_lst_balance = self
+            .storage
+            .active_lst_balance()
+            .checked_add(self.accepted_assets.total_converted_balance(self.storage))
+            .expect("active lst balance should never overflow");
 
         let total_deposits_value = self
-            .redemption_rate
+            .rates
+            .valuation()
             .lst_to_underlying(DepositAmount(active_lst_balance));
 
         TotalDepositsValue(total_deposits_value)
     }
 
     fn deposit_value(&self, amount: DepositAmount) -> DepositValue {
-        let deposit_value = self.redemption_rate.lst_to_underlying(amount);
+        let deposit_value = self.rates.valuation().lst_to_underlying(amount);
         DepositValue(deposit_value)
     }
 
     fn unbond(&self, value: DepositValue) -> UnbondReadyStatus {
-        let amount = self.redemption_rate.underlying_to_lst(value);
+        let amount = self.rates.valuation().underlying_to_lst(value);
 
         UnbondReadyStatus::Ready {
             amount: ClaimAmount(amount),
@@ -111,6 +184,25 @@ impl<'a> CoreStrategy for Strategy<'a> {
             },
         }
     }
+
+    fn unbond_fee(&self, _value: DepositValue) -> DepositValue {
+        // no unbond fee is charged
+        DepositValue(0)
+    }
+
+    fn available_to_claim(&self) -> Option<ClaimAmount> {
+        None
+    }
+
+    fn redemption_rate(&self) -> Option<FixedU256> {
+        // deposits are only valued at a rate other than the LST redemption rate if a market price is set
+        let LstRedemptionRate(market_price) = self.rates.market_price?;
+
+        let LstRedemptionRate(redemption_rate) = self.rates.redemption_rate;
+
+        FixedU256::from_u128(redemption_rate.atomics().u128())
+            .checked_div(FixedU256::from_u128(market_price.atomics().u128()))
+    }
 }
 
 fn increase_active_deposits(storage: &mut dyn Storage, DepositAmount(amount): DepositAmount) {
@@ -149,9 +241,102 @@ fn decrease_claimable_deposits(storage: &mut dyn Storage, DepositAmount(amount):
     storage.set_claimable_lst_balance(claimable_lst_balance);
 }
 
+// Move `lst_amount` worth of deposits from active to claimable, drawing on the LST balance first
+// & then on the secondary balances at their current conversion rates
+fn unbond_deposits(storage: &mut dyn Storage, accepted_assets: &AcceptedAssets, lst_amount: u128) {
+    let from_lst = lst_amount.min(storage.active_lst_balance());
+
+    decrease_active_deposits(storage, DepositAmount(from_lst));
+
+    increase_claimable_deposits(storage, DepositAmount(from_lst));
+
+    let mut shortfall = lst_amount - from_lst;
+
+    for rate in accepted_assets.rates() {
+        if shortfall == 0 {
+            break;
+        }
+
+        let balance = storage.secondary_balance(&rate.denom);
+
+        let balance_lst = rate.to_primary(balance);
+
+        let lst = shortfall.min(balance_lst);
+
+        let amount = if lst == balance_lst {
+            balance
+        } else {
+            rate.to_secondary(lst)
+        };
+
+        storage.set_secondary_balance(&rate.denom, balance - amount);
+
+        let (claimable, claimable_lst) = storage.claimable_secondary(&rate.denom);
+
+        storage.set_claimable_secondary(&rate.denom, claimable + amount, claimable_lst + lst);
+
+        shortfall -= lst;
+    }
+
+    assert_eq!(
+        shortfall, 0,
+        "unbond amount should always be <= active balances"
+    );
+}
+
+// Settle a claim of `lst_amount` from the claimable LST balance first & then from the claimable secondary balances,
+// each paying out the secondary asset in proportion to the LST it was unbonded to settle
+fn claim_deposits(storage: &mut dyn Storage, lst_amount: u128) -> Vec<Coin> {
+    let from_lst = lst_amount.min(storage.claimable_lst_balance());
+
+    decrease_claimable_deposits(storage, DepositAmount(from_lst));
+
+    let mut claimed = vec![coin(from_lst, storage.lst_denom())];
+
+    let mut remaining = lst_amount - from_lst;
+
+    for AcceptedAsset { denom, .. } in storage.accepted_assets() {
+        if remaining == 0 {
+            break;
+        }
+
+        let (claimable, claimable_lst) = storage.claimable_secondary(&denom);
+
+        if claimable_lst == 0 {
+            continue;
+        }
+
+        let lst = remaining.min(claimable_lst);
+
+        let amount = if lst == claimable_lst {
+            claimable
+        } else {
+            Uint128::new(claimable)
+                .multiply_ratio(lst, claimable_lst)
+                .u128()
+        };
+
+        storage.set_claimable_secondary(&denom, claimable - amount, claimable_lst - lst);
+
+        claimed.push(coin(amount, denom));
+
+        remaining -= lst;
+    }
+
+    assert_eq!(
+        remaining, 0,
+        "claim amount should always be <= claimable balances"
+    );
+
+    claimed.retain(|coin| !coin.amount.is_zero());
+
+    claimed
+}
+
 pub fn handle_cmd<CustomMsg>(
     storage: &mut dyn Storage,
-    redemption_rate: LstRedemptionRate,
+    rates: LstRates,
+    accepted_assets: &AcceptedAssets,
     cmd: StrategyCmd,
 ) -> Option<CosmosMsg<CustomMsg>> {
     match cmd {
@@ -161,12 +346,16 @@ pub fn handle_cmd<CustomMsg>(
             None
         }
 
-        StrategyCmd::Unbond { value } => {
-            let lst_amount = redemption_rate.underlying_to_lst(value);
+        StrategyCmd::DepositSecondary { asset, amount } => {
+            accepted_assets::handle_deposit_secondary(storage, &asset, amount);
+
+            None
+        }
 
-            decrease_active_deposits(storage, DepositAmount(lst_amount));
+        StrategyCmd::Unbond { value } => {
+            let lst_amount = rates.valuation().underlying_to_lst(value);
 
-            increase_claimable_deposits(storage, DepositAmount(lst_amount));
+            unbond_deposits(storage, accepted_assets, lst_amount);
 
             None
         }
@@ -175,17 +364,23 @@ pub fn handle_cmd<CustomMsg>(
             amount: ClaimAmount(amount),
             recipient,
         } => {
-            decrease_claimable_deposits(storage, DepositAmount(amount));
+            let claimed = claim_deposits(storage, amount);
 
-            let lst_denom = storage.lst_denom();
+            if claimed.is_empty() {
+                return None;
+            }
 
             Some(
                 BankMsg::Send {
                     to_address: recipient.into_string(),
-                    amount: coins(amount, lst_denom),
+                    amount: claimed,
                 }
                 .into(),
             )
         }
+
+        StrategyCmd::CollectUnbondFee { .. } => {
+            unreachable!("strategy does not charge an unbond fee")
+        }
     }
 }
@@ -1,5 +1,5 @@
 use cosmwasm_std::{
-    coins, from_json,
+    coin, coins, from_json,
     testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage},
     to_json_binary, Addr, Binary, ContractResult, Empty, MessageInfo, OwnedDeps, SystemResult,
     WasmQuery,
@@ -9,6 +9,7 @@ use test_utils::prelude::*;
 use amulet_cw::{
     strategy::generic_lst::{QueryMsg as RedemptionRateOracleQuery, RedemptionRateResponse},
     vault::{
+        accepted_assets::{ConversionOracleQueryMsg, ConversionRateResponse},
         ActiveUnbondingsResponse, ClaimableResponse, DepositResponse, PendingUnbondingResponse,
         QueryMsg as VaultQueryMsg, StateResponse, UnbondingLogMetadata,
     },
@@ -16,13 +17,16 @@ use amulet_cw::{
 
 use crate::msg::{
     AdminExecuteMsg, AdminQueryMsg, InstantiateMsg, MetadataResponse, StrategyExecuteMsg,
-    StrategyQueryMsg, VaultExecuteMsg,
+    StrategyQueryMsg, SudoMsg, UnaccountedBalancesResponse, VaultExecuteMsg,
 };
 
-use super::{execute, instantiate, query};
+use super::{execute, instantiate, query, sudo};
 
 const REDEMPTION_RATE_ORACLE: &str = "redemption_rate_oracle";
+const MARKET_PRICE_ORACLE: &str = "market_price_oracle";
 const LST: &str = "liquid_staking_token";
+const CONVERSION_ORACLE: &str = "conversion_oracle";
+const NATIVE: &str = "native_token";
 
 type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>;
 
@@ -61,6 +65,24 @@ fn update_querier(deps: &mut MockDeps, rate: f64) {
                     })
                 }
             },
+            // the LST trades at 0.9 of the underlying asset
+            MARKET_PRICE_ORACLE => match from_json(msg).unwrap() {
+                RedemptionRateOracleQuery::RedemptionRate {} => {
+                    to_json_binary(&RedemptionRateResponse {
+                        rate: "0.9".parse().unwrap(),
+                    })
+                }
+            },
+            // the native token converts into 0.8 LST
+            CONVERSION_ORACLE => match from_json(msg).unwrap() {
+                ConversionOracleQueryMsg::ConversionRate { denom } => {
+                    assert_eq!(denom, NATIVE);
+
+                    to_json_binary(&ConversionRateResponse {
+                        rate: "0.8".parse().unwrap(),
+                    })
+                }
+            },
             _ => panic!("unexpected contract query addr: {contract_addr}"),
         }
         .unwrap();
@@ -83,6 +105,8 @@ fn init() -> MockDeps {
             lst_denom: LST.into(),
             lst_decimals: 6,
             underlying_decimals: 6,
+            restricted_shares: None,
+            hub: None,
         },
     )
     .unwrap();
@@ -90,6 +114,37 @@ fn init() -> MockDeps {
     deps
 }
 
+fn init_restricted() -> MockDeps {
+    let mut deps = mock_dependencies();
+
+    update_querier(&mut deps, 1.0);
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        InstantiateMsg {
+            lst_redemption_rate_oracle: REDEMPTION_RATE_ORACLE.into(),
+            lst_denom: LST.into(),
+            lst_decimals: 6,
+            underlying_decimals: 6,
+            restricted_shares: Some(true),
+            hub: Some("hub".into()),
+        },
+    )
+    .unwrap();
+
+    deps
+}
+
+fn block_before_send(from: &str, to: &str) -> SudoMsg {
+    SudoMsg::BlockBeforeSend {
+        from: from.into(),
+        to: to.into(),
+        amount: coin(1_000, "factory/cosmos2contract/share"),
+    }
+}
+
 fn into_json_string(bin: Binary) -> String {
     String::from_utf8(bin.0).unwrap()
 }
@@ -174,11 +229,13 @@ fn deposit() {
         expect![[r#"
             (
               lst_redemption_rate_oracle: "redemption_rate_oracle",
+              lst_market_price_oracle: None,
               lst_denom: "liquid_staking_token",
               lst_decimals: 6,
               underlying_decimals: 6,
               active_lst_balance: "1000",
               claimable_lst_balance: "0",
+              secondary_balances: [],
             )"#]],
     );
 
@@ -191,6 +248,7 @@ fn deposit() {
             (
               total_deposits: "1100",
               total_issued_shares: "1100000000000000",
+              redemption_value: None,
             )"#]],
     );
 }
@@ -245,11 +303,13 @@ fn donate() {
         expect![[r#"
             (
               lst_redemption_rate_oracle: "redemption_rate_oracle",
+              lst_market_price_oracle: None,
               lst_denom: "liquid_staking_token",
               lst_decimals: 6,
               underlying_decimals: 6,
               active_lst_balance: "1000",
               claimable_lst_balance: "0",
+              secondary_balances: [],
             )"#]],
     );
 
@@ -262,6 +322,7 @@ fn donate() {
             (
               total_deposits: "1100",
               total_issued_shares: "0",
+              redemption_value: None,
             )"#]],
     );
 }
@@ -370,11 +431,13 @@ fn redeem() {
         expect![[r#"
             (
               lst_redemption_rate_oracle: "redemption_rate_oracle",
+              lst_market_price_oracle: None,
               lst_denom: "liquid_staking_token",
               lst_decimals: 6,
               underlying_decimals: 6,
               active_lst_balance: "501",
               claimable_lst_balance: "499",
+              secondary_balances: [],
             )"#]],
     );
 
@@ -387,6 +450,7 @@ fn redeem() {
             (
               total_deposits: "551",
               total_issued_shares: "550000000000000",
+              redemption_value: None,
             )"#]],
     );
 
@@ -455,6 +519,274 @@ fn redeem() {
     );
 }
 
+#[test]
+fn deposit_and_claim_secondary_asset() {
+    let mut deps = init();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        StrategyExecuteMsg::SetAcceptedAsset {
+            denom: NATIVE.into(),
+            conversion: Some(CONVERSION_ORACLE.into()),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 1_000),
+        VaultExecuteMsg::Deposit {}.into(),
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("alice", 1_000, "native_token"),
+        VaultExecuteMsg::Deposit {}.into(),
+    )
+    .unwrap();
+
+    // the secondary deposit is valued as its LST equivalent
+    check(
+        query(deps.as_ref(), mock_env(), VaultQueryMsg::State {}.into())
+            .map(from_json::<StateResponse>)
+            .unwrap()
+            .unwrap(),
+        expect![[r#"
+            (
+              total_deposits: "1800",
+              total_issued_shares: "1800000000000000",
+              redemption_value: None,
+            )"#]],
+    );
+
+    // an asset still held by the vault can not be removed
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        StrategyExecuteMsg::SetAcceptedAsset {
+            denom: NATIVE.into(),
+            conversion: None,
+        }
+        .into(),
+    )
+    .unwrap_err();
+
+    // redeeming more than the LST balance draws on the secondary balance
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!(
+            "bob",
+            1_500_000_000_000_000, "factory/cosmos2contract/share"
+        ),
+        VaultExecuteMsg::Redeem {
+            recipient: "bob".into(),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            StrategyQueryMsg::Metadata {}.into(),
+        )
+        .map(from_json::<MetadataResponse>)
+        .unwrap()
+        .unwrap(),
+        expect![[r#"
+            (
+              lst_redemption_rate_oracle: "redemption_rate_oracle",
+              lst_market_price_oracle: None,
+              lst_denom: "liquid_staking_token",
+              lst_decimals: 6,
+              underlying_decimals: 6,
+              active_lst_balance: "0",
+              claimable_lst_balance: "1000",
+              secondary_balances: [
+                (
+                  denom: "native_token",
+                  active: "375",
+                  claimable: "625",
+                ),
+              ],
+            )"#]],
+    );
+
+    let response = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob"),
+        VaultExecuteMsg::Claim {}.into(),
+    )
+    .unwrap();
+
+    check(
+        response.messages,
+        expect![[r#"
+        [
+          (
+            id: 0,
+            msg: bank(send(
+              to_address: "bob",
+              amount: [
+                (
+                  denom: "liquid_staking_token",
+                  amount: "1000",
+                ),
+                (
+                  denom: "native_token",
+                  amount: "625",
+                ),
+              ],
+            )),
+            gas_limit: None,
+            reply_on: never,
+          ),
+        ]"#]],
+    );
+}
+
+#[test]
+fn claim_across_batches_sends_once() {
+    let mut deps = init();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        StrategyExecuteMsg::SetAcceptedAsset {
+            denom: NATIVE.into(),
+            conversion: Some(CONVERSION_ORACLE.into()),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 1_000),
+        VaultExecuteMsg::Deposit {}.into(),
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("alice", 1_000, "native_token"),
+        VaultExecuteMsg::Deposit {}.into(),
+    )
+    .unwrap();
+
+    // each redemption enters its own batch, the second drawing on the secondary balance
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 500_000_000_000_000, "factory/cosmos2contract/share"),
+        VaultExecuteMsg::Redeem {
+            recipient: "bob".into(),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!(
+            "bob",
+            1_000_000_000_000_000, "factory/cosmos2contract/share"
+        ),
+        VaultExecuteMsg::Redeem {
+            recipient: "bob".into(),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            VaultQueryMsg::UnbondingLogMetadata {
+                address: "bob".into(),
+            }
+            .into(),
+        )
+        .map(from_json::<UnbondingLogMetadata>)
+        .unwrap()
+        .unwrap(),
+        expect![[r#"
+            (
+              last_committed_batch_id: Some(1),
+              first_entered_batch: Some(0),
+              last_entered_batch: Some(1),
+              last_claimed_batch: None,
+            )"#]],
+    );
+
+    let response = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob"),
+        VaultExecuteMsg::Claim {}.into(),
+    )
+    .unwrap();
+
+    check(
+        response.attributes,
+        expect![[r#"
+        [
+          (
+            key: "kind",
+            value: "claim",
+          ),
+          (
+            key: "recipient",
+            value: "bob",
+          ),
+          (
+            key: "amount",
+            value: "1500",
+          ),
+        ]"#]],
+    );
+
+    check(
+        response.messages,
+        expect![[r#"
+        [
+          (
+            id: 0,
+            msg: bank(send(
+              to_address: "bob",
+              amount: [
+                (
+                  denom: "liquid_staking_token",
+                  amount: "1000",
+                ),
+                (
+                  denom: "native_token",
+                  amount: "625",
+                ),
+              ],
+            )),
+            gas_limit: None,
+            reply_on: never,
+          ),
+        ]"#]],
+    );
+}
+
 #[test]
 fn start_unbond_errs() {
     let mut deps = init();
@@ -593,11 +925,13 @@ fn claim() {
         expect![[r#"
             (
               lst_redemption_rate_oracle: "redemption_rate_oracle",
+              lst_market_price_oracle: None,
               lst_denom: "liquid_staking_token",
               lst_decimals: 6,
               underlying_decimals: 6,
               active_lst_balance: "501",
               claimable_lst_balance: "0",
+              secondary_balances: [],
             )"#]],
     );
 
@@ -641,6 +975,118 @@ fn claim() {
     );
 }
 
+#[test]
+fn claim_all() {
+    let mut deps = init();
+
+    update_querier(&mut deps, 1.1);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 1_000),
+        VaultExecuteMsg::Deposit {}.into(),
+    )
+    .unwrap();
+
+    check(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info!("bob"),
+            VaultExecuteMsg::ClaimAll {}.into(),
+        )
+        .unwrap_err()
+        .to_string(),
+        expect![[r#""nothing to claim""#]],
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 200_000_000_000_000, "factory/cosmos2contract/share"),
+        VaultExecuteMsg::Redeem {
+            recipient: "bob".into(),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 350_000_000_000_000, "factory/cosmos2contract/share"),
+        VaultExecuteMsg::Redeem {
+            recipient: "bob".into(),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    let response = execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob"),
+        VaultExecuteMsg::ClaimAll {}.into(),
+    )
+    .unwrap();
+
+    check(
+        response,
+        expect![[r#"
+        (
+          messages: [
+            (
+              id: 0,
+              msg: bank(send(
+                to_address: "bob",
+                amount: [
+                  (
+                    denom: "liquid_staking_token",
+                    amount: "499",
+                  ),
+                ],
+              )),
+              gas_limit: None,
+              reply_on: never,
+            ),
+          ],
+          attributes: [
+            (
+              key: "kind",
+              value: "claim_all",
+            ),
+            (
+              key: "recipient",
+              value: "bob",
+            ),
+            (
+              key: "claimed_batch",
+              value: "0",
+            ),
+            (
+              key: "claimed_batch_amount",
+              value: "181",
+            ),
+            (
+              key: "claimed_batch",
+              value: "1",
+            ),
+            (
+              key: "claimed_batch_amount",
+              value: "318",
+            ),
+            (
+              key: "amount",
+              value: "499",
+            ),
+          ],
+          events: [],
+          data: None,
+        )"#]],
+    );
+}
+
 #[test]
 fn set_redemption_rate_oracle_non_admin_errs() {
     let mut deps = init();
@@ -675,15 +1121,108 @@ fn initialisation() {
         expect![[r#"
             (
               lst_redemption_rate_oracle: "redemption_rate_oracle",
+              lst_market_price_oracle: None,
               lst_denom: "liquid_staking_token",
               lst_decimals: 6,
               underlying_decimals: 6,
               active_lst_balance: "0",
               claimable_lst_balance: "0",
+              secondary_balances: [],
             )"#]],
     );
 }
 
+#[test]
+fn restricted_shares_initialisation() {
+    let mut deps = mock_dependencies();
+
+    let response = instantiate(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        InstantiateMsg {
+            lst_redemption_rate_oracle: REDEMPTION_RATE_ORACLE.into(),
+            lst_denom: LST.into(),
+            lst_decimals: 6,
+            underlying_decimals: 6,
+            restricted_shares: Some(true),
+            hub: Some("hub".into()),
+        },
+    )
+    .unwrap();
+
+    check(
+        response.messages,
+        expect![[r#"
+        [
+          (
+            id: 0,
+            msg: custom(create_denom(
+              subdenom: "share",
+            )),
+            gas_limit: None,
+            reply_on: never,
+          ),
+          (
+            id: 0,
+            msg: custom(set_before_send_hook(
+              denom: "factory/cosmos2contract/share",
+              contract_addr: "cosmos2contract",
+            )),
+            gas_limit: None,
+            reply_on: never,
+          ),
+        ]"#]],
+    );
+
+    let err = instantiate(
+        mock_dependencies().as_mut(),
+        mock_env(),
+        info!("creator"),
+        InstantiateMsg {
+            lst_redemption_rate_oracle: REDEMPTION_RATE_ORACLE.into(),
+            lst_denom: LST.into(),
+            lst_decimals: 6,
+            underlying_decimals: 6,
+            restricted_shares: Some(true),
+            hub: None,
+        },
+    )
+    .unwrap_err();
+
+    check(
+        err.to_string(),
+        expect![[r#""hub is required to restrict shares""#]],
+    );
+}
+
+#[test]
+fn restricted_shares_block_before_send() {
+    let mut deps = init_restricted();
+
+    let env = mock_env();
+    let vault = env.contract.address.as_str();
+
+    sudo(deps.as_mut(), env.clone(), block_before_send("bob", "hub")).unwrap();
+    sudo(deps.as_mut(), env.clone(), block_before_send("hub", "bob")).unwrap();
+    sudo(deps.as_mut(), env.clone(), block_before_send("bob", vault)).unwrap();
+    sudo(deps.as_mut(), env.clone(), block_before_send(vault, "bob")).unwrap();
+
+    let err = sudo(deps.as_mut(), env, block_before_send("bob", "alice")).unwrap_err();
+
+    check(
+        err.to_string(),
+        expect![[r#""shares transfers are restricted to the hub and vault""#]],
+    );
+}
+
+#[test]
+fn unrestricted_shares_block_before_send() {
+    let mut deps = init();
+
+    sudo(deps.as_mut(), mock_env(), block_before_send("bob", "alice")).unwrap();
+}
+
 #[test]
 fn set_redemption_rate_oracle() {
     let mut deps = init();
@@ -726,6 +1265,267 @@ fn set_redemption_rate_oracle() {
     );
 }
 
+#[test]
+fn market_price_values_deposits() {
+    let mut deps = init();
+
+    update_querier(&mut deps, 1.2);
+
+    let set_market_price_oracle = |deps: &mut MockDeps, info, oracle: Option<&str>| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            StrategyExecuteMsg::SetMarketPriceOracle {
+                oracle: oracle.map(Into::into),
+            }
+            .into(),
+        )
+    };
+
+    let state = |deps: &MockDeps| {
+        query(deps.as_ref(), mock_env(), VaultQueryMsg::State {}.into())
+            .map(from_json::<StateResponse>)
+            .unwrap()
+            .unwrap()
+    };
+
+    check(
+        set_market_price_oracle(&mut deps, info!("bob"), Some(MARKET_PRICE_ORACLE))
+            .unwrap_err()
+            .to_string(),
+        expect![[r#""unauthorized""#]],
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 1_000),
+        VaultExecuteMsg::Deposit {}.into(),
+    )
+    .unwrap();
+
+    // deposits are valued at the redemption rate, which is not reported separately
+    check(
+        state(&deps),
+        expect![[r#"
+            (
+              total_deposits: "1200",
+              total_issued_shares: "1200000000000000",
+              redemption_value: None,
+            )"#]],
+    );
+
+    set_market_price_oracle(&mut deps, info!("creator"), Some(MARKET_PRICE_ORACLE)).unwrap();
+
+    // deposits are valued at the market price, reporting their value at the redemption rate
+    check(
+        state(&deps),
+        expect![[r#"
+            (
+              total_deposits: "900",
+              total_issued_shares: "1200000000000000",
+              redemption_value: Some("1199"),
+            )"#]],
+    );
+
+    set_market_price_oracle(&mut deps, info!("creator"), None).unwrap();
+
+    assert_eq!(state(&deps).total_deposits.u128(), 1_200);
+}
+
+#[test]
+fn sweep_unaccounted_leaves_tracked_funds() {
+    let mut deps = init();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 1_000),
+        VaultExecuteMsg::Donate {}.into(),
+    )
+    .unwrap();
+
+    // 30 LST & all of the stray denom are surplus to the donated deposits
+    deps.querier.update_balance(
+        mock_env().contract.address,
+        vec![coin(1_030, LST), coin(5, "ustray")],
+    );
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            StrategyQueryMsg::UnaccountedBalances {}.into(),
+        )
+        .map(from_json::<UnaccountedBalancesResponse>)
+        .unwrap()
+        .unwrap(),
+        expect![[r#"
+            (
+              balances: [
+                (
+                  denom: "liquid_staking_token",
+                  amount: "30",
+                ),
+                (
+                  denom: "ustray",
+                  amount: "5",
+                ),
+              ],
+            )"#]],
+    );
+
+    let sweep = |deps: &mut MockDeps, sender: &str, denom: &str| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            MessageInfo {
+                sender: Addr::unchecked(sender),
+                funds: vec![],
+            },
+            StrategyExecuteMsg::SweepUnaccounted {
+                denom: denom.into(),
+                recipient: "treasury".into(),
+            }
+            .into(),
+        )
+    };
+
+    check(
+        sweep(&mut deps, "average_joe", LST)
+            .unwrap_err()
+            .to_string(),
+        expect![[r#""unauthorized""#]],
+    );
+
+    check(
+        sweep(&mut deps, "creator", LST).unwrap(),
+        expect![[r#"
+        (
+          messages: [
+            (
+              id: 0,
+              msg: bank(send(
+                to_address: "treasury",
+                amount: [
+                  (
+                    denom: "liquid_staking_token",
+                    amount: "30",
+                  ),
+                ],
+              )),
+              gas_limit: None,
+              reply_on: never,
+            ),
+          ],
+          attributes: [],
+          events: [
+            (
+              type: "unaccounted_swept",
+              attributes: [
+                (
+                  key: "denom",
+                  value: "liquid_staking_token",
+                ),
+                (
+                  key: "amount",
+                  value: "30",
+                ),
+                (
+                  key: "recipient",
+                  value: "treasury",
+                ),
+              ],
+            ),
+          ],
+          data: None,
+        )"#]],
+    );
+
+    // with the surplus swept, only tracked funds remain
+    deps.querier
+        .update_balance(mock_env().contract.address, vec![coin(1_000, LST)]);
+
+    check(
+        sweep(&mut deps, "creator", LST).unwrap_err().to_string(),
+        expect![[r#""no unaccounted balance of liquid_staking_token to sweep""#]],
+    );
+}
+
+#[test]
+fn sweep_unaccounted_leaves_secondary_funds() {
+    let mut deps = init();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("creator"),
+        StrategyExecuteMsg::SetAcceptedAsset {
+            denom: NATIVE.into(),
+            conversion: Some(CONVERSION_ORACLE.into()),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("bob", 1_000),
+        VaultExecuteMsg::Deposit {}.into(),
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!("alice", 1_000, "native_token"),
+        VaultExecuteMsg::Deposit {}.into(),
+    )
+    .unwrap();
+
+    // leaves 375 of the secondary asset active & 625 claimable
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info!(
+            "bob",
+            1_500_000_000_000_000, "factory/cosmos2contract/share"
+        ),
+        VaultExecuteMsg::Redeem {
+            recipient: "bob".into(),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    deps.querier.update_balance(
+        mock_env().contract.address,
+        vec![coin(1_000, LST), coin(1_010, NATIVE)],
+    );
+
+    check(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            StrategyQueryMsg::UnaccountedBalances {}.into(),
+        )
+        .map(from_json::<UnaccountedBalancesResponse>)
+        .unwrap()
+        .unwrap(),
+        expect![[r#"
+            (
+              balances: [
+                (
+                  denom: "native_token",
+                  amount: "10",
+                ),
+              ],
+            )"#]],
+    );
+}
+
 #[test]
 fn admin() {
     let mut deps = init();
@@ -18,27 +18,34 @@ use cw_utils::must_pay;
 use msg::{ReconcileState, ValidatorSet};
 use neutron_sdk::{
     bindings::{msg::NeutronMsg, query::NeutronQuery},
-    sudo::msg::SudoMsg,
+    sudo::msg::SudoMsg as NeutronSudoMsg,
 };
 
-use amulet_core::vault::Cmd as VaultCmd;
+use amulet_core::vault::{pending_batch_id, Cmd as VaultCmd, UnbondingLog as _, UnbondingLogSet};
 use amulet_cw::{
     admin::{self, ExecuteMsg as AdminExecuteMsg, Repository as AdminRepository},
+    bank,
+    state_version::{self, MigrationStep},
+    strategy::weights::WeightsResponse,
     vault::{
-        self, handle_mint_cmd, handle_unbonding_log_cmd, init_mint_msg,
-        ExecuteMsg as VaultExecuteMsg, SharesMint, UnbondingLog,
+        self, feature, handle_mint_cmd, handle_shares_transfer_sudo_msg, handle_unbonding_log_cmd,
+        init_mint_msg, redemption_rate, restrict_shares_msg, ExecuteMsg as VaultExecuteMsg,
+        SharesMint, UnbondingLog,
     },
     MigrateMsg,
 };
-use amulet_ntrn::{query::QuerierExt as _, token_factory::TokenFactory};
-use pos_reconcile_fsm::types::{Weight, Weights};
+use amulet_ntrn::{ica, query::QuerierExt as _, token_factory::TokenFactory};
+use pos_reconcile_fsm::types::{
+    DelegationOrder, DurationSecs, ReconcileScope, Timestamp, Weight, Weights,
+};
 use reconcile::reconcile_cost;
-use state::StorageExt;
+use state::{StorageExt, RECONCILE_HISTORY};
 
 use self::{
     msg::{
-        Config, ExecuteMsg, InstantiateMsg, Metadata, QueryMsg, StrategyExecuteMsg,
-        StrategyQueryMsg,
+        Config, ExecuteMsg, FeeTotals, InstantiateMsg, InterchainQueries, Metadata,
+        PendingUnbondings, PerformanceFee, QueryMsg, ReconcileHistory, StrategyExecuteMsg,
+        StrategyQueryMsg, SudoMsg, UnbondFees,
     },
     reconcile::{reconcile, Source},
     reply::{Kind as ReplyKind, State as ReplyState},
@@ -46,6 +53,17 @@ use self::{
     types::{Ica, Icq},
 };
 
+/// State migrations, the step at index `n` upgrades state from version `n` to `n + 1`
+const MIGRATIONS: &[MigrationStep] = &[];
+
+/// The optional vault interface features supported, reported by the interface version query
+const FEATURES: &[&str] = &[
+    feature::DONATE,
+    feature::REDEEM_ON_BEHALF,
+    feature::CLAIM_ALL,
+    feature::UNBOND_SCHEDULE,
+];
+
 fn required_ica_icq_deposit(
     ica_register_fee: &Coin,
     icq_deposit_fee: &Coin,
@@ -65,6 +83,12 @@ fn required_ica_icq_deposit(
     total_ica_register_fee + total_icq_deposit_fee
 }
 
+const MAX_UNBOND_FEE_BPS: u32 = 10_000;
+
+const MAX_PERFORMANCE_FEE_BPS: u32 = 5_000;
+
+const MAX_RECONCILE_HISTORY_CAPACITY: u64 = 256;
+
 fn ibc_denom(channel: &str, remote_denom: &str) -> String {
     let ibc_denom_suffix_bytes =
         hmac_sha256::Hash::hash(format!("transfer/{channel}/{remote_denom}").as_bytes());
@@ -106,6 +130,37 @@ pub fn instantiate(
         "initial validator set length matches validator set weights length"
     );
 
+    let instant_unbond = msg.instant_unbond.unwrap_or_default();
+
+    if instant_unbond {
+        ensure!(
+            msg.config.unbonding_period == 0,
+            "unbonding period is zero when instant unbond is enabled"
+        );
+    } else {
+        ensure!(
+            msg.config.unbonding_period > 0,
+            "instant unbond is enabled when the unbonding period is zero"
+        );
+    }
+
+    let unbond_fee_bps = msg.unbond_fee_bps.unwrap_or_default();
+
+    ensure!(
+        unbond_fee_bps <= MAX_UNBOND_FEE_BPS,
+        "unbond fee bps does not exceed {MAX_UNBOND_FEE_BPS}"
+    );
+
+    let unbond_fee_recipient = msg
+        .unbond_fee_recipient
+        .map(|recipient| deps.api.addr_validate(&recipient))
+        .transpose()?;
+
+    ensure!(
+        unbond_fee_bps == 0 || unbond_fee_recipient.is_some(),
+        "unbond fee recipient is set when charging an unbond fee"
+    );
+
     let mut validator_hash_set = HashSet::with_capacity(msg.initial_validator_set.len());
 
     for val in &msg.initial_validator_set {
@@ -154,16 +209,30 @@ pub fn instantiate(
         .collect::<Option<Vec<_>>>()
         .ok_or_else(|| anyhow!("invalid initial validator slot weight"))?;
 
-    let weights =
-        Weights::new(&weights).ok_or_else(|| anyhow!("invalid initial validator slot weights"))?;
+    let weights = Weights::new(&weights)
+        .map_err(|err| anyhow!("invalid initial validator slot weights: {err}"))?;
+
+    let restricted_shares_hub = if msg.restricted_shares.unwrap_or_default() {
+        let hub = msg
+            .hub
+            .ok_or_else(|| anyhow!("hub is required to restrict shares"))?;
+
+        Some(deps.api.addr_validate(&hub)?)
+    } else {
+        None
+    };
 
     admin::init(store, &info);
 
+    state_version::init(store, MIGRATIONS);
+
     let ibc_deposit_asset = ibc_denom(&config.transfer_out_channel, &config.remote_denom);
 
     store.set_connection_id(&config.connection_id);
     store.set_delegations_icq_count(delegations_icq_count);
-    store.set_estimated_block_interval_seconds(config.estimated_block_interval_seconds);
+    store.set_estimated_block_interval_seconds(DurationSecs(
+        config.estimated_block_interval_seconds,
+    ));
     store.set_fee_bps_block_increment(config.fee_bps_block_increment);
     store.set_fee_payment_cooldown_blocks(config.fee_payment_cooldown_blocks);
     store.set_ibc_deposit_asset(&ibc_deposit_asset);
@@ -173,14 +242,22 @@ pub fn instantiate(
     store.set_max_ibc_msg_count(max_msg_count);
     store.set_max_unbonding_entries(config.max_unbonding_entries);
     store.set_max_validators_per_delegations_icq(config.max_validators_per_delegations_icq);
-    store.set_minimum_unbond_interval(config.unbonding_period / config.max_unbonding_entries);
+    store.set_minimum_unbond_interval(DurationSecs(
+        config.unbonding_period / config.max_unbonding_entries,
+    ));
     store.set_remote_denom(&config.remote_denom);
     store.set_remote_denom_decimals(config.remote_denom_decimals);
     store.set_transfer_in_channel(&config.transfer_in_channel);
     store.set_transfer_in_timeout_seconds(config.transfer_in_timeout_seconds);
     store.set_transfer_out_channel(&config.transfer_out_channel);
     store.set_transfer_out_timeout_seconds(config.transfer_out_timeout_seconds);
-    store.set_unbonding_period(config.unbonding_period);
+    store.set_unbonding_period(DurationSecs(config.unbonding_period));
+    store.set_instant_unbond(instant_unbond);
+    store.set_unbond_fee_bps(unbond_fee_bps);
+
+    if let Some(recipient) = unbond_fee_recipient {
+        store.set_unbond_fee_recipient(recipient.as_str());
+    }
     store.set_validator_set_size(msg.initial_validator_set.len());
 
     for (slot_idx, (validator, weight)) in msg
@@ -196,19 +273,19 @@ pub fn instantiate(
 
     let init_mint_msg = init_mint_msg(TokenFactory::new(&env));
 
+    let restrict_msg = restricted_shares_hub
+        .map(|hub| restrict_shares_msg(store, TokenFactory::new(&env), &env, &hub));
+
     Ok(Response::default()
         .add_message(init_mint_msg)
+        .add_messages(restrict_msg)
         .add_messages([
-            NeutronMsg::RegisterInterchainAccount {
-                connection_id: config.connection_id.clone(),
-                interchain_account_id: Ica::Main.id().to_owned(),
-                register_fee: Some(vec![ica_register_fee.clone()]),
-            },
-            NeutronMsg::RegisterInterchainAccount {
-                connection_id: config.connection_id,
-                interchain_account_id: Ica::Rewards.id().to_owned(),
-                register_fee: Some(vec![ica_register_fee.clone()]),
-            },
+            ica::register_ica(
+                &config.connection_id,
+                Ica::Main.id(),
+                ica_register_fee.clone(),
+            ),
+            ica::register_ica(config.connection_id, Ica::Rewards.id(), ica_register_fee),
         ]))
 }
 
@@ -256,13 +333,67 @@ pub fn execute_vault_msg(
                 }
             }
 
-            VaultCmd::UnbondingLog(cmd) => handle_unbonding_log_cmd(deps.storage, cmd),
+            VaultCmd::UnbondingLog(cmd) => {
+                // record when a batch receives its first redemption to track the batch period
+                if let UnbondingLogSet::BatchTotalUnbondValue { batch, .. } = &cmd {
+                    if deps.storage.batch_opened_at(*batch).is_none() {
+                        deps.storage
+                            .set_batch_opened_at(*batch, Timestamp(env.block.time.seconds()));
+                    }
+                }
+
+                strategy::track_claims(deps.storage, &cmd)?;
+
+                handle_unbonding_log_cmd(deps.storage, cmd)
+            }
         }
     }
 
+    response.messages = bank::coalesce_sends(response.messages);
+
     Ok(response)
 }
 
+fn commit_batch(
+    deps: DepsMut<NeutronQuery>,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response<NeutronMsg>> {
+    let unbonding_log = UnbondingLog::new(deps.storage);
+
+    let pending_batch_id = pending_batch_id(&unbonding_log);
+
+    let pending_batch_value = unbonding_log
+        .batch_unbond_value(pending_batch_id)
+        .unwrap_or_default();
+
+    ensure!(pending_batch_value.0 > 0, "cannot commit an empty batch");
+
+    let is_admin = admin::get_admin_role(&AdminRepository::new(deps.storage), &info).is_ok();
+
+    // even the admin may not unbond more often than the minimum unbond interval allows
+    let commit_after = if is_admin {
+        strategy::minimum_unbond_interval_elapses_at(deps.storage)
+    } else {
+        let Some(commit_after) =
+            strategy::batch_commit_after(deps.storage, Timestamp(env.block.time.seconds()))
+        else {
+            bail!("no batch period set");
+        };
+
+        Some(commit_after)
+    };
+
+    if let Some(commit_after) = commit_after {
+        ensure!(
+            env.block.time.seconds() >= commit_after.0,
+            "batch cannot be committed before {commit_after}"
+        );
+    }
+
+    execute_vault_msg(deps, env, info, VaultExecuteMsg::StartUnbond {})
+}
+
 pub fn execute_strategy_msg(
     deps: DepsMut<NeutronQuery>,
     env: Env,
@@ -270,6 +401,12 @@ pub fn execute_strategy_msg(
     msg: StrategyExecuteMsg,
 ) -> Result<Response<NeutronMsg>> {
     match msg {
+        StrategyExecuteMsg::ClaimUnbondFees {} => strategy::handle_claim_unbond_fees(deps, env),
+
+        StrategyExecuteMsg::CommitBatch {} => commit_batch(deps, env, info),
+
+        StrategyExecuteMsg::CompoundRewards {} => reconcile::compound_rewards(deps, env, info),
+
         StrategyExecuteMsg::ForceNext {} => reconcile::force_next(deps, env),
 
         StrategyExecuteMsg::Reconcile { fee_recipient } => {
@@ -277,8 +414,8 @@ pub fn execute_strategy_msg(
         }
 
         StrategyExecuteMsg::ReceiveUndelegated {
-            balance_icq_timestamp,
-        } => strategy::handle_receive_undelegated(deps, info, balance_icq_timestamp),
+            balance_icq_timestamp: vault::Timestamp(balance_icq_timestamp),
+        } => strategy::handle_receive_undelegated(deps, info, Timestamp(balance_icq_timestamp)),
 
         StrategyExecuteMsg::RedelegateSlot { slot, validator } => {
             let repository = AdminRepository::new(deps.storage);
@@ -316,22 +453,130 @@ pub fn execute_strategy_msg(
             Ok(Response::default())
         }
 
+        StrategyExecuteMsg::ResizeValidatorSet { new_validators } => {
+            let repository = AdminRepository::new(deps.storage);
+
+            admin::get_admin_role(&repository, &info)?;
+
+            strategy::handle_resize_validator_set(deps, info, new_validators)
+        }
+
+        StrategyExecuteMsg::SetDelegationOrder { order } => {
+            let repository = AdminRepository::new(deps.storage);
+
+            admin::get_admin_role(&repository, &info)?;
+
+            deps.storage.set_delegation_order(match order {
+                msg::DelegationOrder::SlotIndex => DelegationOrder::SlotIndex,
+                msg::DelegationOrder::MostUnderweightFirst => DelegationOrder::MostUnderweightFirst,
+            });
+
+            Ok(Response::default())
+        }
+
+        StrategyExecuteMsg::SetPerformanceFee {
+            performance_fee_bps,
+            recipient,
+        } => {
+            let repository = AdminRepository::new(deps.storage);
+
+            admin::get_admin_role(&repository, &info)?;
+
+            if let Some(recipient) = recipient {
+                let recipient = deps.api.addr_validate(&recipient)?;
+
+                deps.storage
+                    .set_performance_fee_recipient(recipient.as_str());
+            }
+
+            ensure!(
+                performance_fee_bps <= MAX_PERFORMANCE_FEE_BPS,
+                "performance fee bps does not exceed {MAX_PERFORMANCE_FEE_BPS}"
+            );
+
+            ensure!(
+                performance_fee_bps == 0 || deps.storage.performance_fee_recipient().is_some(),
+                "performance fee recipient is set when charging a performance fee"
+            );
+
+            // the mark is not tracked while no fee is charged, so only gains from here on are charged
+            if deps.storage.performance_fee_bps() == 0 {
+                deps.storage.clear_performance_fee_high_water_mark();
+            }
+
+            deps.storage.set_performance_fee_bps(performance_fee_bps);
+
+            Ok(Response::default())
+        }
+
+        StrategyExecuteMsg::SetReconcileHistoryCapacity { capacity } => {
+            let repository = AdminRepository::new(deps.storage);
+
+            admin::get_admin_role(&repository, &info)?;
+
+            ensure!(
+                capacity > 0 && capacity <= MAX_RECONCILE_HISTORY_CAPACITY,
+                "reconcile history capacity is between 1 and {MAX_RECONCILE_HISTORY_CAPACITY}"
+            );
+
+            RECONCILE_HISTORY.set_capacity(deps.storage, capacity);
+
+            Ok(Response::default())
+        }
+
+        StrategyExecuteMsg::SetTargetWeights { weights } => {
+            let repository = AdminRepository::new(deps.storage);
+
+            admin::get_admin_role(&repository, &info)?;
+
+            strategy::handle_set_target_weights(deps, weights)
+        }
+
+        StrategyExecuteMsg::SweepUnaccounted { denom, recipient } => {
+            let repository = AdminRepository::new(deps.storage);
+
+            admin::get_admin_role(&repository, &info)?;
+
+            deps.api.addr_validate(&recipient)?;
+
+            let unaccounted = strategy::unaccounted_balances(deps.storage, deps.querier, &env)?;
+
+            let (sweep, swept) = bank::sweep_unaccounted(unaccounted, &denom, recipient)?;
+
+            Ok(Response::default().add_message(sweep).add_event(swept))
+        }
+
         StrategyExecuteMsg::UpdateConfig {
+            batch_period_secs,
+            delegation_drift_tolerance,
             estimated_block_interval_seconds,
             fee_bps_block_increment,
             fee_payment_cooldown_blocks,
+            fee_payouts_enabled,
             icq_update_interval,
             interchain_tx_timeout_seconds,
             max_fee_bps,
+            max_undelegate_retries,
             transfer_in_timeout_seconds,
             transfer_out_timeout_seconds,
+            unbond_fee_bps,
+            unbond_fee_recipient,
         } => {
             let repository = AdminRepository::new(deps.storage);
 
             admin::get_admin_role(&repository, &info)?;
 
-            if let Some(v) = estimated_block_interval_seconds {
-                deps.storage.set_estimated_block_interval_seconds(v);
+            if let Some(vault::DurationSecs(v)) = batch_period_secs {
+                deps.storage.set_batch_period_secs(DurationSecs(v));
+            }
+
+            if let Some(v) = delegation_drift_tolerance {
+                deps.storage.set_delegation_drift_tolerance(v.u128());
+            }
+
+            if let Some(vault::DurationSecs(v)) = estimated_block_interval_seconds {
+                deps.storage
+                    .set_estimated_block_interval_seconds(DurationSecs(v));
             }
 
             if let Some(v) = fee_bps_block_increment {
@@ -342,6 +587,10 @@ pub fn execute_strategy_msg(
                 deps.storage.set_fee_payment_cooldown_blocks(v);
             }
 
+            if let Some(v) = fee_payouts_enabled {
+                deps.storage.set_fee_payouts_enabled(v);
+            }
+
             if let Some(v) = icq_update_interval {
                 deps.storage.set_icq_update_interval(v);
             }
@@ -354,6 +603,10 @@ pub fn execute_strategy_msg(
                 deps.storage.set_max_fee_bps(v);
             }
 
+            if let Some(v) = max_undelegate_retries {
+                deps.storage.set_max_undelegate_retries(v);
+            }
+
             if let Some(v) = transfer_in_timeout_seconds {
                 deps.storage.set_transfer_in_timeout_seconds(v);
             }
@@ -362,6 +615,26 @@ pub fn execute_strategy_msg(
                 deps.storage.set_transfer_out_timeout_seconds(v);
             }
 
+            if let Some(v) = unbond_fee_recipient {
+                let recipient = deps.api.addr_validate(&v)?;
+
+                deps.storage.set_unbond_fee_recipient(recipient.as_str());
+            }
+
+            if let Some(v) = unbond_fee_bps {
+                ensure!(
+                    v <= MAX_UNBOND_FEE_BPS,
+                    "unbond fee bps does not exceed {MAX_UNBOND_FEE_BPS}"
+                );
+
+                ensure!(
+                    v == 0 || deps.storage.unbond_fee_recipient().is_some(),
+                    "unbond fee recipient is set when charging an unbond fee"
+                );
+
+                deps.storage.set_unbond_fee_bps(v);
+            }
+
             Ok(Response::default())
         }
     }
@@ -381,11 +654,15 @@ pub fn execute(
     }
 }
 
-pub fn handle_strategy_query(deps: Deps<NeutronQuery>, query: StrategyQueryMsg) -> Result<Binary> {
+pub fn handle_strategy_query(
+    deps: Deps<NeutronQuery>,
+    env: &Env,
+    query: StrategyQueryMsg,
+) -> Result<Binary> {
     let response = match query {
         StrategyQueryMsg::Config {} => to_json_binary(&Config {
             connection_id: deps.storage.connection_id(),
-            estimated_block_interval_seconds: deps.storage.estimated_block_interval_seconds(),
+            estimated_block_interval_seconds: deps.storage.estimated_block_interval_seconds().0,
             fee_bps_block_increment: deps.storage.fee_bps_block_increment(),
             fee_payment_cooldown_blocks: deps.storage.fee_payment_cooldown_blocks(),
             icq_update_interval: deps.storage.icq_update_interval(),
@@ -399,27 +676,45 @@ pub fn handle_strategy_query(deps: Deps<NeutronQuery>, query: StrategyQueryMsg)
             transfer_in_timeout_seconds: deps.storage.transfer_in_timeout_seconds(),
             transfer_out_channel: deps.storage.transfer_out_channel(),
             transfer_out_timeout_seconds: deps.storage.transfer_out_timeout_seconds(),
-            unbonding_period: deps.storage.unbonding_period(),
+            unbonding_period: deps.storage.unbonding_period().0,
         })?,
 
         StrategyQueryMsg::Metadata {} => to_json_binary(&Metadata {
             available_to_claim: deps.storage.available_to_claim().0.into(),
+            batch_period_secs: deps
+                .storage
+                .batch_period_secs()
+                .map(|DurationSecs(secs)| vault::DurationSecs(secs)),
             delegated: deps.storage.delegated().0.into(),
+            delegation_drift_tolerance: deps.storage.delegation_drift_tolerance().map(Into::into),
+            delegation_order: match deps.storage.delegation_order() {
+                DelegationOrder::SlotIndex => msg::DelegationOrder::SlotIndex,
+                DelegationOrder::MostUnderweightFirst => msg::DelegationOrder::MostUnderweightFirst,
+            },
             delegations_icqs: deps.storage.delegations_icqs(),
             delegations_icq_count: deps.storage.delegations_icq_count(),
+            fee_payouts_enabled: deps.storage.fee_payouts_enabled(),
             ibc_deposit_asset: deps.storage.ibc_deposit_asset(),
             inflight_delegation: deps.storage.inflight_delegation().0.into(),
             inflight_deposit: deps.storage.inflight_deposit().0.into(),
             inflight_fee_payable: deps.storage.inflight_fee_payable().0.into(),
             inflight_rewards_receivable: deps.storage.inflight_rewards_receivable().0.into(),
             inflight_unbond: deps.storage.inflight_unbond().0.into(),
+            instant_unbond: deps.storage.instant_unbond(),
             last_reconcile_height: deps.storage.last_reconcile_height().map(|height| height.0),
-            last_unbond_timestamp: deps.storage.last_unbond_timestamp(),
-            last_main_ica_balance_icq_update: deps.storage.last_main_ica_balance_icq_update(),
+            last_unbond_timestamp: deps
+                .storage
+                .last_unbond_timestamp()
+                .map(|Timestamp(timestamp)| vault::Timestamp(timestamp)),
+            last_main_ica_balance_icq_update: deps
+                .storage
+                .last_main_ica_balance_icq_update()
+                .map(|Timestamp(timestamp)| vault::Timestamp(timestamp)),
             main_ica_address: deps.storage.main_ica_address(),
             main_ica_balance_icq: deps.storage.main_ica_balance_icq(),
             max_ibc_msg_count: deps.storage.max_ibc_msg_count(),
-            minimum_unbond_interval: deps.storage.minimum_unbond_interval(),
+            max_undelegate_retries: deps.storage.max_undelegate_retries(),
+            minimum_unbond_interval: vault::DurationSecs(deps.storage.minimum_unbond_interval().0),
             msg_issued_count: deps.storage.msg_issued_count().0,
             msg_success_count: deps.storage.msg_success_count().0,
             next_delegations_icqs: deps.storage.next_delegations_icqs(),
@@ -431,13 +726,22 @@ pub fn handle_strategy_query(deps: Deps<NeutronQuery>, query: StrategyQueryMsg)
             total_expected_unbonded: deps.storage.total_expected_unbonded().0.into(),
             unbonding_ack_count: deps.storage.unbonding_ack_count(),
             unbonding_issued_count: deps.storage.unbonding_issued_count(),
+            undelegate_retry_count: deps.storage.undelegate_retry_count().0,
         })?,
 
         StrategyQueryMsg::ReconcileState {} => {
             let phase = deps.storage.reconcile_phase();
             let state = deps.storage.reconcile_state();
 
-            let cost = reconcile_cost(deps, phase, state).map(|coin| coin.amount)?;
+            let cost = reconcile_cost(
+                deps,
+                env,
+                deps.storage.fee_recipient(),
+                ReconcileScope::Full,
+                phase,
+                state,
+            )
+            .map(|coin| coin.amount)?;
 
             to_json_binary(&ReconcileState {
                 fee_recipient: deps.storage.fee_recipient(),
@@ -460,6 +764,68 @@ pub fn handle_strategy_query(deps: Deps<NeutronQuery>, query: StrategyQueryMsg)
             pending_redelegation_slot: deps.storage.redelegate_slot(),
             pending_redelegate_to: deps.storage.redelegate_to(),
         })?,
+
+        StrategyQueryMsg::Weights {} => to_json_binary(&WeightsResponse::new(
+            reconcile::weights(deps.storage)
+                .as_slice()
+                .iter()
+                .copied()
+                .map(Weight::into_fixed),
+        ))?,
+
+        StrategyQueryMsg::PendingUnbondings {} => to_json_binary(&PendingUnbondings {
+            batches: strategy::pending_unbondings(
+                deps.storage,
+                Timestamp(env.block.time.seconds()),
+            ),
+        })?,
+
+        StrategyQueryMsg::UnbondFees {} => to_json_binary(&UnbondFees {
+            unbond_fee_bps: deps.storage.unbond_fee_bps(),
+            recipient: deps.storage.unbond_fee_recipient(),
+            collected: strategy::collected_unbond_fees(deps.storage).into(),
+            claimable: strategy::claimable_unbond_fees(deps.storage, env.block.time.seconds())
+                .amount
+                .into(),
+        })?,
+
+        StrategyQueryMsg::PerformanceFee {} => to_json_binary(&PerformanceFee {
+            performance_fee_bps: deps.storage.performance_fee_bps(),
+            recipient: deps.storage.performance_fee_recipient(),
+            high_water_mark: deps
+                .storage
+                .performance_fee_high_water_mark()
+                .map(|mark| mark.to_string()),
+        })?,
+
+        StrategyQueryMsg::FeeTotals {} => to_json_binary(&FeeTotals {
+            total_fees_paid: deps.storage.interchain_fees_paid().into(),
+            total_txs_issued: deps.storage.interchain_txs_issued(),
+            total_txs_failed: deps.storage.interchain_txs_failed(),
+        })?,
+
+        StrategyQueryMsg::ReconcileHistory { start_after, limit } => {
+            to_json_binary(&ReconcileHistory {
+                entries: strategy::reconcile_history(deps.storage, start_after, limit),
+            })?
+        }
+
+        StrategyQueryMsg::RedemptionRate { twap_secs } => to_json_binary(&redemption_rate::query(
+            deps.storage,
+            strategy::redemption_rate(deps.storage, env),
+            env.block.time.seconds(),
+            twap_secs,
+        ))?,
+
+        StrategyQueryMsg::InterchainQueries {} => to_json_binary(&InterchainQueries {
+            queries: icq::interchain_queries(deps.storage, Timestamp(env.block.time.seconds())),
+        })?,
+
+        StrategyQueryMsg::UnaccountedBalances {} => {
+            to_json_binary(&bank::UnaccountedBalancesResponse {
+                balances: strategy::unaccounted_balances(deps.storage, deps.querier, env)?,
+            })?
+        }
     };
 
     Ok(response)
@@ -480,10 +846,11 @@ pub fn query(deps: Deps<NeutronQuery>, env: Env, msg: QueryMsg) -> Result<Binary
             &UnbondingLog::new(deps.storage),
             &SharesMint::new(deps.storage, &env),
             &env,
+            FEATURES,
             vault_query,
         )?,
 
-        QueryMsg::Strategy(strategy_query) => handle_strategy_query(deps, strategy_query)?,
+        QueryMsg::Strategy(strategy_query) => handle_strategy_query(deps, &env, strategy_query)?,
     };
 
     Ok(binary)
@@ -508,28 +875,44 @@ pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response<NeutronM
 
 #[entry_point]
 pub fn sudo(deps: DepsMut<NeutronQuery>, env: Env, msg: SudoMsg) -> Result<Response<NeutronMsg>> {
+    let msg = match msg {
+        SudoMsg::Neutron(msg) => msg,
+
+        SudoMsg::Vault(msg) => {
+            handle_shares_transfer_sudo_msg(deps.storage, &env, msg)?;
+
+            return Ok(Response::default());
+        }
+    };
+
     match msg {
-        SudoMsg::OpenAck {
+        NeutronSudoMsg::OpenAck {
             port_id,
             counterparty_version,
             ..
         } => sudo::handle_open_ack(deps, port_id, counterparty_version),
 
-        SudoMsg::Response { .. } => sudo::handle_response(deps, env),
+        NeutronSudoMsg::Response { .. } => sudo::handle_response(deps, env),
 
-        SudoMsg::Error { .. } => sudo::handle_error(deps, env),
+        NeutronSudoMsg::Error { .. } => sudo::handle_error(deps, env),
 
-        SudoMsg::Timeout { .. } => sudo::handle_timeout(deps, env),
+        NeutronSudoMsg::Timeout { .. } => sudo::handle_timeout(deps, env),
 
-        SudoMsg::KVQueryResult { query_id } => sudo::handle_kv_query_result(deps, env, query_id),
+        NeutronSudoMsg::KVQueryResult { query_id } => {
+            sudo::handle_kv_query_result(deps, env, query_id)
+        }
 
-        SudoMsg::TxQueryResult { .. } => Ok(Response::default()),
+        NeutronSudoMsg::TxQueryResult { .. } => Ok(Response::default()),
     }
 }
 
 #[entry_point]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response> {
+    let migration = state_version::migrate_state(deps, MIGRATIONS)?;
+
+    Ok(Response::default()
+        .add_attribute("state_version_from", migration.from.to_string())
+        .add_attribute("state_version_to", migration.to.to_string()))
 }
 
 #[cfg(test)]
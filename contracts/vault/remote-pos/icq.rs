@@ -1,3 +1,4 @@
+use amulet_cw::vault;
 use cosmwasm_std::{Storage, SubMsg};
 use neutron_sdk::{
     bindings::msg::NeutronMsg,
@@ -5,8 +6,10 @@ use neutron_sdk::{
         new_register_balance_query_msg, new_register_delegator_delegations_query_msg,
     },
 };
+use pos_reconcile_fsm::types::Timestamp;
 
 use crate::{
+    msg::{InterchainQuery, InterchainQueryKind},
     reply::{Kind as ReplyKind, State as ReplyState},
     state::StorageExt as _,
     types::Ica,
@@ -129,3 +132,50 @@ pub fn main_ica_next_delegations_registration_msgs(
         ReplyKind::RegisterNextSetDelegationsIcq,
     )
 }
+
+/// Returns the ids & kinds of every interchain query currently registered by the vault
+pub fn registered_queries(storage: &dyn Storage) -> Vec<(u64, InterchainQueryKind)> {
+    let mut queries = vec![];
+
+    for index in 0..storage.delegations_icq_count() {
+        if let Some(id) = storage.delegations_icq(index) {
+            queries.push((id, InterchainQueryKind::Delegations { index }));
+        }
+    }
+
+    for index in 0..storage.delegations_icq_count() {
+        if let Some(id) = storage.next_delegations_icq(index) {
+            queries.push((id, InterchainQueryKind::NextDelegations { index }));
+        }
+    }
+
+    if let Some(id) = storage.main_ica_balance_icq() {
+        queries.push((id, InterchainQueryKind::UndelegatedBalance));
+    }
+
+    if let Some(id) = storage.rewards_ica_balance_icq() {
+        queries.push((id, InterchainQueryKind::RewardsBalance));
+    }
+
+    queries
+}
+
+pub fn interchain_queries(storage: &dyn Storage, now: Timestamp) -> Vec<InterchainQuery> {
+    registered_queries(storage)
+        .into_iter()
+        .map(|(id, kind)| {
+            let last_result_timestamp = storage.icq_last_result_timestamp(id);
+
+            InterchainQuery {
+                id,
+                kind,
+                update_period: storage.icq_update_period(id),
+                last_result_remote_height: storage.icq_last_result_remote_height(id),
+                last_result_local_height: storage.icq_last_result_local_height(id),
+                last_result_timestamp: last_result_timestamp.map(|ts| vault::Timestamp(ts.0)),
+                last_result_age: last_result_timestamp
+                    .map(|ts| vault::DurationSecs(now.0.saturating_sub(ts.0))),
+            }
+        })
+        .collect()
+}
@@ -2,9 +2,15 @@ use cosmwasm_schema::{cw_serde, QueryResponses};
 
 use amulet_cw::{
     admin::{ExecuteMsg as AdminExecuteMsg, QueryMsg as AdminQueryMsg},
-    vault::{ExecuteMsg as VaultExecuteMsg, QueryMsg as VaultQueryMsg},
+    bank::UnaccountedBalancesResponse,
+    strategy::weights::WeightsResponse,
+    vault::{
+        redemption_rate::RedemptionRateResponse, DurationSecs, ExecuteMsg as VaultExecuteMsg,
+        QueryMsg as VaultQueryMsg, SharesTransferSudoMsg as VaultSudoMsg, Timestamp,
+    },
 };
 use cosmwasm_std::Uint128;
+use neutron_sdk::sudo::msg::SudoMsg as NeutronSudoMsg;
 
 #[cw_serde]
 pub struct Config {
@@ -32,27 +38,43 @@ pub struct InstantiateMsg {
     pub config: Config,
     pub initial_validator_set: Vec<String>,
     pub initial_validator_weights: Vec<u32>,
+    /// Restrict shares transfers to the hub and this vault, requires `hub` to be set
+    pub restricted_shares: Option<bool>,
+    /// The hub permitted to hold shares when they are restricted
+    pub hub: Option<String>,
+    /// Treat unbondings as complete as soon as they are received, requires a zero `unbonding_period`
+    pub instant_unbond: Option<bool>,
+    /// The fee (in bps) retained from each redemption as it enters an unbonding batch, defaults to zero
+    pub unbond_fee_bps: Option<u32>,
+    /// The recipient of collected unbond fees, required if `unbond_fee_bps` is non-zero
+    pub unbond_fee_recipient: Option<String>,
 }
 
 #[cw_serde]
 pub struct Metadata {
     pub available_to_claim: Uint128,
+    pub batch_period_secs: Option<DurationSecs>,
     pub delegated: Uint128,
+    pub delegation_drift_tolerance: Option<Uint128>,
+    pub delegation_order: DelegationOrder,
     pub delegations_icqs: Vec<u64>,
     pub delegations_icq_count: u8,
+    pub fee_payouts_enabled: bool,
     pub ibc_deposit_asset: String,
     pub inflight_delegation: Uint128,
     pub inflight_deposit: Uint128,
     pub inflight_fee_payable: Uint128,
     pub inflight_rewards_receivable: Uint128,
     pub inflight_unbond: Uint128,
+    pub instant_unbond: bool,
     pub last_reconcile_height: Option<u64>,
-    pub last_unbond_timestamp: Option<u64>,
-    pub last_main_ica_balance_icq_update: Option<u64>,
+    pub last_unbond_timestamp: Option<Timestamp>,
+    pub last_main_ica_balance_icq_update: Option<Timestamp>,
     pub main_ica_address: Option<String>,
     pub main_ica_balance_icq: Option<u64>,
     pub max_ibc_msg_count: usize,
-    pub minimum_unbond_interval: u64,
+    pub max_undelegate_retries: u64,
+    pub minimum_unbond_interval: DurationSecs,
     pub msg_issued_count: usize,
     pub msg_success_count: usize,
     pub next_delegations_icqs: Vec<u64>,
@@ -64,6 +86,16 @@ pub struct Metadata {
     pub total_expected_unbonded: Uint128,
     pub unbonding_ack_count: Option<u64>,
     pub unbonding_issued_count: Option<u64>,
+    pub undelegate_retry_count: usize,
+}
+
+/// The order in which validator slots are funded when a delegation is split across several txs
+#[cw_serde]
+pub enum DelegationOrder {
+    /// Ascending slot index
+    SlotIndex,
+    /// The slots furthest below their target weight first, or the lowest weighted if no target is set
+    MostUnderweightFirst,
 }
 
 #[cw_serde]
@@ -75,6 +107,96 @@ pub struct ReconcileState {
     pub cost: Uint128,
 }
 
+#[cw_serde]
+pub struct PendingUnbondingBatch {
+    pub id: u64,
+    pub amount: Uint128,
+    pub expected_completion: Timestamp,
+    /// True if the unbonding has completed
+    pub claimable: bool,
+    /// True if the unbonded assets have been received & are awaiting claim
+    pub received: bool,
+}
+
+#[cw_serde]
+pub struct PendingUnbondings {
+    pub batches: Vec<PendingUnbondingBatch>,
+}
+
+#[cw_serde]
+pub struct UnbondFees {
+    pub unbond_fee_bps: u32,
+    pub recipient: Option<String>,
+    /// The value of unbond fees collected that are yet to be claimed
+    pub collected: Uint128,
+    /// The amount of collected unbond fees that can be claimed now
+    pub claimable: Uint128,
+}
+
+#[cw_serde]
+pub struct PerformanceFee {
+    pub performance_fee_bps: u32,
+    pub recipient: Option<String>,
+    /// The highest deposit value per share a performance fee has been charged up to, if recorded
+    pub high_water_mark: Option<String>,
+}
+
+#[cw_serde]
+pub struct ReconcileHistoryEntry {
+    /// The ID of the entry, to resume a paginated query after
+    pub id: u64,
+    pub height: u64,
+    pub timestamp: Timestamp,
+}
+
+#[cw_serde]
+pub struct ReconcileHistory {
+    /// The most recent reconcile completions, oldest first
+    pub entries: Vec<ReconcileHistoryEntry>,
+}
+
+#[cw_serde]
+pub struct FeeTotals {
+    /// The fees consumed by interchain txs, including the escrowed fees of any awaiting a response
+    pub total_fees_paid: Uint128,
+    pub total_txs_issued: u64,
+    /// The interchain txs that errored or timed out
+    pub total_txs_failed: u64,
+}
+
+#[cw_serde]
+pub enum InterchainQueryKind {
+    /// The main ICA delegations to a chunk of the current validator set
+    Delegations { index: u8 },
+    /// The main ICA delegations to a chunk of the validator set pending a redelegation
+    NextDelegations { index: u8 },
+    /// The main ICA balance of undelegated assets
+    UndelegatedBalance,
+    /// The rewards ICA balance
+    RewardsBalance,
+}
+
+#[cw_serde]
+pub struct InterchainQuery {
+    pub id: u64,
+    pub kind: InterchainQueryKind,
+    /// The update period in remote blocks, `None` if registered before it was recorded
+    pub update_period: Option<u64>,
+    /// The remote chain height of the last result received
+    pub last_result_remote_height: Option<u64>,
+    /// The local block height the last result was received at
+    pub last_result_local_height: Option<u64>,
+    /// The local block time the last result was received at
+    pub last_result_timestamp: Option<Timestamp>,
+    /// The seconds elapsed since the last result was received
+    pub last_result_age: Option<DurationSecs>,
+}
+
+#[cw_serde]
+pub struct InterchainQueries {
+    pub queries: Vec<InterchainQuery>,
+}
+
 #[cw_serde]
 pub struct ValidatorSet {
     pub size: usize,
@@ -86,6 +208,15 @@ pub struct ValidatorSet {
 
 #[cw_serde]
 pub enum StrategyExecuteMsg {
+    /// Send the claimable unbond fees to the unbond fee recipient
+    ClaimUnbondFees {},
+    /// Commit the pending unbonding batch so it is unbonded in the next reconcile.
+    /// Anyone may commit once the batch is older than the batch period, the admin may commit at any time.
+    /// Neither may commit before the minimum unbond interval since the last unbonding has elapsed.
+    CommitBatch {},
+    /// Delegate accrued rewards without running the full reconcile sequence.
+    /// Only available while reconcile is idle at the start of a cycle; the caller pays the IBC fees.
+    CompoundRewards {},
     /// Force a failed phase to continue to the next phase, if elligible
     ForceNext {},
     Reconcile {
@@ -93,7 +224,7 @@ pub enum StrategyExecuteMsg {
     },
     /// IBC hook callback for transferral of assets back to the contract
     ReceiveUndelegated {
-        balance_icq_timestamp: u64,
+        balance_icq_timestamp: Timestamp,
     },
     /// Admin role required
     RedelegateSlot {
@@ -113,15 +244,63 @@ pub enum StrategyExecuteMsg {
     /// Query the x/interchain-txs params for the max msg count and reset cached value
     ResetMaxMsgCount {},
     /// Admin role required
+    /// Replace the validator set with `new_validators`, growing or shrinking the number of slots.
+    /// Only allowed while reconcile is idle at the start of a cycle with nothing inflight or pending redelegation.
+    /// Validators removed from the set must have no delegations left, new validators start with zero weight.
+    /// Requires submitting the deposit for the new delegations ICQs
+    ResizeValidatorSet {
+        new_validators: Vec<String>,
+    },
+    /// Admin role required
+    /// Set the order in which validator slots are funded when delegations span several txs
+    SetDelegationOrder {
+        order: DelegationOrder,
+    },
+    /// Admin role required
+    /// Set the cut of the gain in share value above its high-water mark minted as shares on completing a reconcile.
+    /// A `recipient` is required to charge a non-zero fee if none has been set.
+    SetPerformanceFee {
+        performance_fee_bps: u32,
+        recipient: Option<String>,
+    },
+    /// Admin role required
+    /// Set the number of reconcile completions retained in the reconcile history.
+    /// If the capacity is reduced, the oldest entries are no longer returned & are evicted over the next completions.
+    SetReconcileHistoryCapacity {
+        capacity: u64,
+    },
+    /// Admin role required
+    /// Set the validator slot weights (in bps) that delegations will trend towards
+    SetTargetWeights {
+        weights: Vec<u32>,
+    },
+    /// Admin role required
+    /// Send the vault's entire balance of `denom` in excess of the funds it tracks to the `recipient`
+    SweepUnaccounted {
+        denom: String,
+        recipient: String,
+    },
+    /// Admin role required
     UpdateConfig {
-        estimated_block_interval_seconds: Option<u64>,
+        /// The age in seconds after which anyone may commit the pending unbonding batch
+        batch_period_secs: Option<DurationSecs>,
+        /// The max difference between the recorded delegations and a delegations report that is corrected
+        /// without being treated as a slashing, defaults to the validator set size
+        delegation_drift_tolerance: Option<Uint128>,
+        estimated_block_interval_seconds: Option<DurationSecs>,
         fee_bps_block_increment: Option<u64>,
         fee_payment_cooldown_blocks: Option<u64>,
+        /// Suspend or resume reconciler fee payouts, all rewards are delegated while suspended
+        fee_payouts_enabled: Option<bool>,
         icq_update_interval: Option<u64>,
         interchain_tx_timeout_seconds: Option<u64>,
         max_fee_bps: Option<u32>,
+        /// The number of times a failed undelegation batch is re-issued before the offending validator is skipped
+        max_undelegate_retries: Option<u64>,
         transfer_in_timeout_seconds: Option<u64>,
         transfer_out_timeout_seconds: Option<u64>,
+        unbond_fee_bps: Option<u32>,
+        unbond_fee_recipient: Option<String>,
     },
 }
 
@@ -133,6 +312,13 @@ pub enum ExecuteMsg {
     Strategy(StrategyExecuteMsg),
 }
 
+#[cw_serde]
+#[serde(untagged)]
+pub enum SudoMsg {
+    Neutron(NeutronSudoMsg),
+    Vault(VaultSudoMsg),
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum StrategyQueryMsg {
@@ -144,6 +330,39 @@ pub enum StrategyQueryMsg {
     ReconcileState {},
     #[returns(ValidatorSet)]
     ValidatorSet {},
+    /// Returns the validator slot weights the reconcile process is currently delegating by
+    #[returns(WeightsResponse)]
+    Weights {},
+    /// Returns every unbonding issued on the remote chain whose assets are yet to be received
+    #[returns(PendingUnbondings)]
+    PendingUnbondings {},
+    #[returns(UnbondFees)]
+    UnbondFees {},
+    #[returns(PerformanceFee)]
+    PerformanceFee {},
+    /// Returns the interchain fees spent & txs issued over the vault's lifetime
+    #[returns(FeeTotals)]
+    FeeTotals {},
+    /// Returns the height & time of the most recent reconcile completions, up to the configured
+    /// history capacity, oldest first.
+    /// Only entries after `start_after`, the last entry ID of a previous page, are included,
+    /// up to `limit` entries or `amulet_cw::event_log::DEFAULT_QUERY_LIMIT`, capped at `amulet_cw::event_log::MAX_QUERY_LIMIT`
+    #[returns(ReconcileHistory)]
+    ReconcileHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the vault's balance of each denom in excess of the deposits awaiting delegation
+    /// & the unbonded assets awaiting claim, the fee denom float is never included
+    #[returns(UnaccountedBalancesResponse)]
+    UnaccountedBalances {},
+    /// Returns the spot share redemption rate & if `twap_secs` is provided,
+    /// the time-weighted average of the rates observed on completing each reconcile over that many seconds
+    #[returns(RedemptionRateResponse)]
+    RedemptionRate { twap_secs: Option<u64> },
+    /// Returns the registered interchain queries with the heights & age of their last result
+    #[returns(InterchainQueries)]
+    InterchainQueries {},
 }
 
 #[cw_serde]
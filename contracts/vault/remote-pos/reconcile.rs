@@ -1,16 +1,18 @@
+use std::borrow::Cow;
+
 use anyhow::{bail, Result};
 use cosmos_sdk_proto::cosmos::{
-    authz::v1beta1::{GenericAuthorization, Grant, MsgExec, MsgGrant},
+    authz::v1beta1::{GenericAuthorization, Grant, MsgExec, MsgGrant, MsgRevoke},
     bank::v1beta1::MsgSend,
     distribution::v1beta1::{MsgSetWithdrawAddress, MsgWithdrawDelegatorReward},
     staking::v1beta1::{MsgBeginRedelegate, MsgDelegate, MsgUndelegate},
 };
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    coin, coins, to_json_string, BankMsg, Coin, Deps, DepsMut, Env as CwEnv, MessageInfo, Response,
-    Storage, SubMsg,
+    coin, coins, to_json_string, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Env as CwEnv,
+    MessageInfo, Response, Storage, SubMsg,
 };
-use cw_utils::must_pay;
+use cw_utils::may_pay;
 use neutron_sdk::{
     bindings::{
         msg::{IbcFee, NeutronMsg},
@@ -23,18 +25,22 @@ use neutron_sdk::{
 };
 use prost::{Message, Name};
 
+use amulet_cw::vault;
 use amulet_ntrn::{IbcFeeExt, IBC_FEE_DENOM};
 use pos_reconcile_fsm::{
-    fsm,
+    delegate_authz_msgs, fsm,
     types::{
-        Account, BalancesIcqResult, CurrentHeight, DelegateStartSlot, Delegated, Delegation,
-        DelegationsIcqResult, DelegationsReport, FeeBpsBlockIncrement, FeePaymentCooldownBlocks,
-        FeeRecipient, InflightDelegation, InflightDeposit, InflightFeePayable,
+        Account, BalancesIcqResult, CurrentHeight, DelegateSlotOrder, DelegateStartSlot, Delegated,
+        Delegation, DelegationDriftTolerance, DelegationOrder, DelegationsIcqResult,
+        DelegationsReport, DurationSecs, FeeBpsBlockIncrement, FeePaymentCooldownBlocks,
+        FeePayoutsEnabled, FeeRecipient, InflightDelegation, InflightDeposit, InflightFeePayable,
         InflightRewardsReceivable, InflightUnbond, LastReconcileHeight, MaxFeeBps, MaxMsgCount,
-        MsgIssuedCount, MsgSuccessCount, Now as ReconcilePosNow, PendingDeposit, PendingUnbond,
-        Phase, ReconcilerFee, RedelegationSlot, RemoteBalance, RemoteBalanceReport,
-        RewardsReceivable, State, UnbondingTimeSecs, UndelegateStartSlot, UndelegatedBalanceReport,
-        Validator, ValidatorSetSize, ValidatorSetSlot, Weights,
+        MaxUnbondingEntries, MaxUndelegateRetries, MsgIssuedCount, MsgSuccessCount,
+        Now as ReconcilePosNow, PendingDeposit, PendingUnbond, Phase, ReconcileScope,
+        ReconcilerFee, RedelegationSlot, RemoteBalance, RemoteBalanceReport, RewardsReceivable,
+        SetupAccounts, State, Timestamp, UnbondingEntriesReport, UnbondingTimeSecs,
+        UndelegateDeferredSlots, UndelegateRetryCount, UndelegateStartSlot,
+        UndelegatedBalanceReport, Validator, ValidatorSetSize, ValidatorSetSlot, Weights,
     },
     AuthzMsg, Cmd as ReconcileCmd, Config, Env as FsmEnv, Event, Fsm, Repository,
     Response as FsmResponse, TxMsg,
@@ -47,6 +53,13 @@ pub enum Status {
     Failure,
 }
 
+/// How an interchain tx was resolved, determining which part of its escrowed fee is refunded
+pub enum TxOutcome {
+    Response,
+    Error,
+    Timeout,
+}
+
 pub enum Source {
     Trigger(MessageInfo, Option<FeeRecipient>),
     Continuation(Status),
@@ -71,10 +84,36 @@ impl<'a> Config for StorageWrapper<'a> {
         MaxMsgCount(self.storage.max_ibc_msg_count())
     }
 
+    fn max_undelegate_retries(&self) -> MaxUndelegateRetries {
+        MaxUndelegateRetries(self.storage.max_undelegate_retries() as _)
+    }
+
+    fn max_unbonding_entries(&self) -> MaxUnbondingEntries {
+        MaxUnbondingEntries(self.storage.max_unbonding_entries())
+    }
+
+    fn delegation_drift_tolerance(&self) -> DelegationDriftTolerance {
+        // default to one unit of rounding per validator
+        let tolerance = self
+            .storage
+            .delegation_drift_tolerance()
+            .unwrap_or(self.validator_set_size().0 as u128);
+
+        DelegationDriftTolerance(tolerance)
+    }
+
+    fn delegation_order(&self) -> DelegationOrder {
+        self.storage.delegation_order()
+    }
+
     fn fee_payout_cooldown(&self) -> FeePaymentCooldownBlocks {
         FeePaymentCooldownBlocks(self.storage.fee_payment_cooldown_blocks())
     }
 
+    fn fee_payouts_enabled(&self) -> FeePayoutsEnabled {
+        FeePayoutsEnabled(self.storage.fee_payouts_enabled())
+    }
+
     fn fee_bps_block_increment(&self) -> FeeBpsBlockIncrement {
         FeeBpsBlockIncrement(self.storage.fee_bps_block_increment())
     }
@@ -87,6 +126,10 @@ impl<'a> Config for StorageWrapper<'a> {
         Weights::new_unchecked(self.storage.validator_initial_weights())
     }
 
+    fn target_weights(&self) -> Option<Weights> {
+        self.storage.validator_target_weights()
+    }
+
     fn validator_set_size(&self) -> ValidatorSetSize {
         ValidatorSetSize(self.storage.validator_set_size())
     }
@@ -101,6 +144,10 @@ impl<'a> Repository for StorageWrapper<'a> {
         self.storage.delegate_start_slot()
     }
 
+    fn delegate_slot_order(&self) -> DelegateSlotOrder {
+        self.storage.delegate_slot_order()
+    }
+
     fn inflight_delegation(&self) -> InflightDelegation {
         self.storage.inflight_delegation()
     }
@@ -160,12 +207,24 @@ impl<'a> Repository for StorageWrapper<'a> {
         self.storage.redelegate_to()
     }
 
+    fn setup_accounts(&self) -> Option<SetupAccounts> {
+        self.storage.setup_accounts()
+    }
+
+    fn undelegate_deferred_slots(&self) -> UndelegateDeferredSlots {
+        self.storage.undelegate_deferred_slots()
+    }
+
+    fn undelegate_retry_count(&self) -> UndelegateRetryCount {
+        self.storage.undelegate_retry_count()
+    }
+
     fn undelegate_start_slot(&self) -> UndelegateStartSlot {
         self.storage.undelegate_start_slot()
     }
 
-    fn weights(&self) -> Weights {
-        Weights::new_unchecked(self.storage.validator_weights())
+    fn weights(&self) -> Cow<'_, Weights> {
+        Cow::Owned(Weights::new_unchecked(self.storage.validator_weights()))
     }
 }
 
@@ -199,7 +258,7 @@ impl<'a> FsmEnv for Env<'a> {
     }
 
     fn now(&self) -> ReconcilePosNow {
-        ReconcilePosNow(self.env.block.time.seconds())
+        ReconcilePosNow(Timestamp(self.env.block.time.seconds()))
     }
 
     fn delegation_account_address(&self) -> Option<Account> {
@@ -278,6 +337,21 @@ impl<'a> FsmEnv for Env<'a> {
             remote_balance,
         })
     }
+
+    fn unbonding_entries_report(&self) -> Option<UnbondingEntriesReport> {
+        let now = Timestamp(self.env.block.time.seconds());
+
+        // entries are tracked per validator, so they stay with a validator that changes slot
+        let entries_per_slot = self
+            .deps
+            .storage
+            .validators()
+            .iter()
+            .map(|validator| self.deps.storage.inflight_unbonding_entries(validator, now))
+            .collect();
+
+        Some(UnbondingEntriesReport { entries_per_slot })
+    }
 }
 
 #[derive(Default)]
@@ -298,6 +372,15 @@ impl SubMsgSequence {
         self
     }
 
+    /// Push a msg issued by a granter, which is the rewards ICA unless it is the main ICA
+    fn push_granter_ica_msg(&mut self, from_main_ica: bool, msg: ProtobufAny) -> &mut Self {
+        if from_main_ica {
+            self.push_main_ica_msg(msg)
+        } else {
+            self.push_rewards_ica_msg(msg)
+        }
+    }
+
     fn push_local_msg(&mut self, msg: SubMsg<NeutronMsg>) -> &mut Self {
         self.local_msgs.push(msg);
         self
@@ -355,6 +438,7 @@ fn handle_reconcile_cmd(storage: &mut dyn Storage, cmd: ReconcileCmd) {
             storage.clear_redelegate_to();
         }
         ReconcileCmd::DelegateStartSlot(v) => storage.set_delegate_start_slot(v),
+        ReconcileCmd::DelegateSlotOrder(v) => storage.set_delegate_slot_order(v),
         ReconcileCmd::Delegated(v) => storage.set_delegated(v),
         ReconcileCmd::InflightDelegation(v) => storage.set_inflight_delegation(v),
         ReconcileCmd::InflightDeposit(v) => storage.set_inflight_deposit(v),
@@ -367,7 +451,10 @@ fn handle_reconcile_cmd(storage: &mut dyn Storage, cmd: ReconcileCmd) {
         ReconcileCmd::PendingDeposit(v) => storage.set_pending_deposit(v),
         ReconcileCmd::PendingUnbond(v) => storage.set_pending_unbond(v),
         ReconcileCmd::Phase(v) => storage.set_reconcile_phase(v),
+        ReconcileCmd::SetupAccounts(v) => storage.set_setup_accounts(v),
         ReconcileCmd::State(v) => storage.set_reconcile_state(v),
+        ReconcileCmd::UndelegateDeferredSlots(v) => storage.set_undelegate_deferred_slots(v),
+        ReconcileCmd::UndelegateRetryCount(v) => storage.set_undelegate_retry_count(v),
         ReconcileCmd::UndelegateStartSlot(v) => storage.set_undelegate_start_slot(v),
         ReconcileCmd::Weights(v) => storage.set_validator_weights(v),
     }
@@ -387,10 +474,9 @@ fn set_withdraw_address_msg(delegator_address: String, withdraw_address: String)
     }
 }
 
-fn grant_authz_send(granter: String, grantee: String) -> ProtobufAny {
-    let auth = GenericAuthorization {
-        msg: "/cosmos.bank.v1beta1.MsgSend".to_owned(),
-    };
+/// Grant the `grantee` generic authorisation to execute messages of `msg_type_url` on behalf of the `granter`
+pub fn grant_authz(granter: String, grantee: String, msg_type_url: String) -> ProtobufAny {
+    let auth = GenericAuthorization { msg: msg_type_url };
 
     let grant_msg = MsgGrant {
         granter,
@@ -415,6 +501,22 @@ fn grant_authz_send(granter: String, grantee: String) -> ProtobufAny {
     }
 }
 
+/// Revoke the `grantee`'s authorisation to execute messages of `msg_type_url` on behalf of the `granter`
+pub fn revoke_authz(granter: String, grantee: String, msg_type_url: String) -> ProtobufAny {
+    let revoke_msg = MsgRevoke {
+        granter,
+        grantee,
+        msg_type_url,
+    };
+
+    let encoded = revoke_msg.encode_to_vec();
+
+    ProtobufAny {
+        type_url: "/cosmos.authz.v1beta1.MsgRevoke".to_owned(),
+        value: encoded.into(),
+    }
+}
+
 fn transfer_in_undelegated(storage: &dyn Storage, env: &CwEnv, amount: u128) -> ProtobufAny {
     use cosmos_sdk_proto::cosmos::base::v1beta1::Coin;
     use cosmos_sdk_proto::ibc::core::client::v1::Height;
@@ -469,7 +571,7 @@ fn transfer_in_undelegated(storage: &dyn Storage, env: &CwEnv, amount: u128) ->
         .expect("always: timestamp set on every update");
 
     let callback = StrategyExecuteMsg::ReceiveUndelegated {
-        balance_icq_timestamp,
+        balance_icq_timestamp: vault::Timestamp(balance_icq_timestamp.0),
     };
 
     let ibc_hook = IbcHookMemo {
@@ -738,6 +840,10 @@ fn authz(storage: &dyn Storage, msgs: Vec<AuthzMsg>) -> ProtobufAny {
     }
 }
 
+fn is_main_ica(storage: &dyn Storage, account: &str) -> bool {
+    storage.main_ica_address().as_deref() == Some(account)
+}
+
 fn handle_reconcile_tx_msg(
     storage: &mut dyn Storage,
     env: &CwEnv,
@@ -752,10 +858,20 @@ fn handle_reconcile_tx_msg(
             response.push_main_ica_msg(msg);
         }
 
-        TxMsg::GrantAuthzSend(granter, grantee) => {
-            let msg = grant_authz_send(granter, grantee);
+        TxMsg::GrantAuthz(granter, grantee, msg_type_url) => {
+            let from_main_ica = is_main_ica(storage, &granter);
+
+            let msg = grant_authz(granter, grantee, msg_type_url);
+
+            response.push_granter_ica_msg(from_main_ica, msg);
+        }
+
+        TxMsg::RevokeAuthz(granter, grantee, msg_type_url) => {
+            let from_main_ica = is_main_ica(storage, &granter);
 
-            response.push_rewards_ica_msg(msg);
+            let msg = revoke_authz(granter, grantee, msg_type_url);
+
+            response.push_granter_ica_msg(from_main_ica, msg);
         }
 
         TxMsg::TransferInUndelegated(amount) => {
@@ -783,6 +899,8 @@ fn handle_reconcile_tx_msg(
         }
 
         TxMsg::Undelegate(slot, amount) => {
+            storage.set_undelegate_issued(slot);
+
             let msg = undelegate(storage, slot, amount);
 
             response.push_main_ica_msg(msg);
@@ -802,10 +920,20 @@ fn handle_reconcile_tx_msg(
     }
 }
 
-fn refund_msg(deps: Deps<NeutronQuery>, tx_count: usize) -> Result<SubMsg<NeutronMsg>> {
-    let fee = query_min_ibc_fee(deps).map(|res| res.min_fee)?;
+// Refunds the fees of the skipped txs to the reconcile trigger, up to the amount they attached that is yet to be refunded
+fn refund_msg(deps: DepsMut<NeutronQuery>, tx_count: usize) -> Result<Option<SubMsg<NeutronMsg>>> {
+    let fee = query_min_ibc_fee(deps.as_ref()).map(|res| res.min_fee)?;
+
+    let trigger_payment = deps.storage.reconcile_trigger_payment();
+
+    let refund_amount = (fee.total_fee_per_tx() * tx_count as u128).min(trigger_payment);
 
-    let refund_amount = fee.total_fee_per_tx() * tx_count as u128;
+    if refund_amount == 0 {
+        return Ok(None);
+    }
+
+    deps.storage
+        .set_reconcile_trigger_payment(trigger_payment - refund_amount);
 
     let to_address = deps
         .storage
@@ -817,24 +945,92 @@ fn refund_msg(deps: Deps<NeutronQuery>, tx_count: usize) -> Result<SubMsg<Neutro
         amount: coins(refund_amount, IBC_FEE_DENOM),
     };
 
-    Ok(SubMsg::new(msg))
+    Ok(Some(SubMsg::new(msg)))
+}
+
+// The full fee is escrowed for every interchain tx issued, so it is counted as paid until the unused part is refunded
+fn record_interchain_fees_escrowed(
+    storage: &mut dyn Storage,
+    fee: &IbcFee,
+    msgs: &[SubMsg<NeutronMsg>],
+) {
+    let tx_count = msgs
+        .iter()
+        .filter(|sub_msg| {
+            matches!(
+                sub_msg.msg,
+                CosmosMsg::Custom(NeutronMsg::SubmitTx { .. } | NeutronMsg::IbcTransfer { .. })
+            )
+        })
+        .count();
+
+    if tx_count == 0 {
+        return;
+    }
+
+    let fee_breakdown = fee.fee_breakdown();
+
+    let escrowed = fee_breakdown.total() * tx_count as u128;
+
+    storage.set_interchain_fees_paid(storage.interchain_fees_paid() + escrowed);
+    storage.set_interchain_txs_issued(storage.interchain_txs_issued() + tx_count as u64);
+    storage.set_interchain_tx_ack_fee(fee_breakdown.ack);
+    storage.set_interchain_tx_timeout_fee(fee_breakdown.timeout);
 }
 
-fn handle_reconcile_event(storage: &mut dyn Storage, env: &CwEnv, event: Event) {
+/// Deduct the part of a resolved interchain tx's escrowed fee that is refunded:
+/// the timeout fee once acknowledged, or the ack fee once timed out
+pub fn record_interchain_tx_outcome(storage: &mut dyn Storage, outcome: TxOutcome) {
+    let refunded = match outcome {
+        TxOutcome::Response | TxOutcome::Error => storage.interchain_tx_timeout_fee(),
+        TxOutcome::Timeout => storage.interchain_tx_ack_fee(),
+    };
+
+    storage.set_interchain_fees_paid(storage.interchain_fees_paid().saturating_sub(refunded));
+
+    if !matches!(outcome, TxOutcome::Response) {
+        storage.set_interchain_txs_failed(storage.interchain_txs_failed() + 1);
+    }
+}
+
+/// Returns any local message to issue for the event
+fn handle_reconcile_event(
+    storage: &mut dyn Storage,
+    env: &CwEnv,
+    event: Event,
+) -> Option<SubMsg<NeutronMsg>> {
     match event {
         Event::SlashDetected(slashed_ratio) => {
             strategy::acknowledge_slashing(storage, slashed_ratio)
         }
 
+        // events are handled after the reconcile cmds, so the completed cycle is fully recorded.
+        // The fee is taken first so that the observed rate is the one shareholders redeem at.
+        Event::ReconcileCompleted { height } => {
+            strategy::record_reconcile_completion(
+                storage,
+                height,
+                Timestamp(env.block.time.seconds()),
+            );
+
+            let fee_msg = strategy::take_performance_fee(storage, env);
+
+            strategy::record_redemption_rate(storage, env);
+
+            return fee_msg.map(SubMsg::new);
+        }
+
         Event::UnbondStarted(amount) => {
-            storage.set_last_unbond_timestamp(env.block.time.seconds());
+            let now = Timestamp(env.block.time.seconds());
+
+            storage.set_last_unbond_timestamp(now);
 
             let idx = storage.unbonding_issued_count().unwrap_or_default();
 
             let unbonding_period = storage.unbonding_period();
 
             storage.set_unbonding_expected_amount(idx, amount);
-            storage.set_unbonding_local_expiry(idx, env.block.time.seconds() + unbonding_period);
+            storage.set_unbonding_local_expiry(idx, now.plus(unbonding_period));
             storage.set_unbonding_issued_count(idx + 1);
         }
 
@@ -858,6 +1054,8 @@ fn handle_reconcile_event(storage: &mut dyn Storage, env: &CwEnv, event: Event)
 
         _ => {}
     }
+
+    None
 }
 
 struct AttrsBuilder<'a>(&'a mut Response<NeutronMsg>);
@@ -902,6 +1100,9 @@ fn add_cmd_attrs(cmd: &ReconcileCmd, response: &mut Response<NeutronMsg>) {
         ReconcileCmd::PendingUnbond(pending_unbond) => attr!(res, pending_unbond),
         ReconcileCmd::Phase(phase) => attr!(res, phase),
         ReconcileCmd::State(state) => attr!(res, state),
+        ReconcileCmd::UndelegateRetryCount(undelegate_retry_count) => {
+            attr!(res, undelegate_retry_count)
+        }
         _ => &mut res,
     };
 }
@@ -917,6 +1118,20 @@ fn add_event_attrs(event: &Event, response: &mut Response<NeutronMsg>) {
         Event::RedelegationSuccessful { slot, validator } => res
             .add_attr("redelegated_slot", slot)
             .add_attr("redelegated_to", validator),
+        Event::WeightsReset(err) => res.add_attr("weights_reset", err),
+        Event::UndelegateSlotSkipped(undelegate_slot_skipped) => {
+            attr!(res, undelegate_slot_skipped)
+        }
+        Event::UnbondingEntriesFull(unbonding_entries_full) => {
+            attr!(res, unbonding_entries_full)
+        }
+        Event::DelegationDriftCorrected { delta } => {
+            res.add_attr("delegation_drift_corrected", delta)
+        }
+        Event::DelegationSurplusAbsorbed(delegation_surplus_absorbed) => {
+            attr!(res, delegation_surplus_absorbed)
+        }
+        Event::ReconcileCompleted { height } => res.add_attr("reconcile_completed", height),
         _ => &mut res,
     };
 }
@@ -950,7 +1165,7 @@ fn add_tx_msg_attrs(msg: &TxMsg, response: &mut Response<NeutronMsg>) {
 }
 
 fn handle_reconcile_response(
-    deps: DepsMut<NeutronQuery>,
+    mut deps: DepsMut<NeutronQuery>,
     env: CwEnv,
     fsm: FsmResponse,
 ) -> Result<Response<NeutronMsg>> {
@@ -958,63 +1173,100 @@ fn handle_reconcile_response(
 
     AttrsBuilder(&mut response)
         .add_kind("reconcile")
-        .add_attr("tx_skip_count", fsm.tx_skip_count);
+        .add_attr("tx_skip_count", fsm.tx_skip_count)
+        .add_attr("transitions_traversed", fsm.transitions_traversed)
+        .add_attr("events_emitted", fsm.events_emitted);
+
+    if let Some(abort_reason) = fsm.abort_reason {
+        AttrsBuilder(&mut response).add_attr("abort_reason", abort_reason);
+    }
 
     for cmd in fsm.cmds {
         add_cmd_attrs(&cmd, &mut response);
         handle_reconcile_cmd(deps.storage, cmd);
     }
 
+    let mut event_msgs = vec![];
+
     for event in fsm.events {
         add_event_attrs(&event, &mut response);
-        handle_reconcile_event(deps.storage, &env, event)
+
+        if let Some(msg) = handle_reconcile_event(deps.storage, &env, event) {
+            event_msgs.push(msg);
+        }
     }
 
     let Some(tx_msgs) = fsm.tx_msgs else {
+        response.messages.extend(event_msgs);
+
         if fsm.tx_skip_count == 0 {
             return Ok(response);
         }
 
-        let refund_msg = refund_msg(deps.as_ref(), fsm.tx_skip_count)?;
+        let refund_msg = refund_msg(deps, fsm.tx_skip_count)?;
 
-        return Ok(response.add_submessage(refund_msg));
+        return Ok(response.add_submessages(refund_msg));
     };
 
     let fee = query_min_ibc_fee(deps.as_ref()).map(|res| res.min_fee)?;
 
     let mut sequence = SubMsgSequence::default();
 
+    for msg in event_msgs {
+        sequence.push_local_msg(msg);
+    }
+
     for msg in tx_msgs.msgs {
         add_tx_msg_attrs(&msg, &mut response);
         handle_reconcile_tx_msg(deps.storage, &env, &mut sequence, &fee, msg);
     }
 
     if fsm.tx_skip_count != 0 {
-        let refund_msg = refund_msg(deps.as_ref(), fsm.tx_skip_count)?;
-
-        sequence.push_local_msg(refund_msg);
+        if let Some(refund_msg) = refund_msg(deps.branch(), fsm.tx_skip_count)? {
+            sequence.push_local_msg(refund_msg);
+        }
     }
 
-    response.messages = sequence.build(deps.storage, fee);
+    response.messages = sequence.build(deps.storage, fee.clone());
+
+    record_interchain_fees_escrowed(deps.storage, &fee, &response.messages);
 
     Ok(response)
 }
 
-pub fn reconcile_cost(deps: Deps<NeutronQuery>, phase: Phase, state: State) -> Result<Coin> {
+pub fn reconcile_cost(
+    deps: Deps<NeutronQuery>,
+    env: &CwEnv,
+    fee_recipient: Option<FeeRecipient>,
+    scope: ReconcileScope,
+    phase: Phase,
+    state: State,
+) -> Result<Coin> {
     let storage_wrapper = StorageWrapper {
         storage: deps.storage,
     };
 
+    let reconcile_env = Env {
+        deps,
+        env,
+        fee_recipient,
+    };
+
+    let authz_msgs = delegate_authz_msgs(&storage_wrapper, &storage_wrapper, &reconcile_env);
+
     let unfunded_tx_count = match state {
-        State::Idle => phase.sequence_tx_count(
+        State::Idle => scope.sequence_tx_count(
+            phase,
             state,
             storage_wrapper.validator_set_size(),
             storage_wrapper.max_msg_count(),
+            authz_msgs,
         ),
 
         State::Failed => phase.tx_count(
             storage_wrapper.validator_set_size(),
             storage_wrapper.max_msg_count(),
+            authz_msgs,
         ),
 
         State::Pending => 0,
@@ -1032,6 +1284,25 @@ pub fn current_deposits(storage: &dyn Storage) -> u128 {
     pos_reconcile_fsm::current_deposits(&storage_wrapper)
 }
 
+/// The weights the reconcile FSM is currently delegating by
+pub fn weights(storage: &dyn Storage) -> Weights {
+    let storage_wrapper = StorageWrapper { storage };
+    storage_wrapper.weights().into_owned()
+}
+
+/// Applies a validator set resize to the reconcile state, each new slot maps to the existing slot it retains, if any
+pub fn resize_validator_set(storage: &mut dyn Storage, slot_map: &[Option<usize>]) -> Result<()> {
+    let storage_wrapper = StorageWrapper { storage };
+
+    let cmds = pos_reconcile_fsm::resize_validator_set(&storage_wrapper, slot_map)?;
+
+    for cmd in cmds {
+        handle_reconcile_cmd(storage, cmd);
+    }
+
+    Ok(())
+}
+
 fn trigger(
     deps: DepsMut<NeutronQuery>,
     env: CwEnv,
@@ -1046,13 +1317,16 @@ fn trigger(
 
     let phase = deps.storage.reconcile_phase();
 
-    let cost = reconcile_cost(deps.as_ref(), phase, state)?;
+    let cost = reconcile_cost(
+        deps.as_ref(),
+        &env,
+        fee_recipient.clone(),
+        ReconcileScope::Full,
+        phase,
+        state,
+    )?;
 
-    let payment = must_pay(&info, &cost.denom)?;
-
-    if payment < cost.amount {
-        bail!("insufficient funds for reconcile sequence: expected {} {IBC_FEE_DENOM}, received {payment} {IBC_FEE_DENOM}", cost.amount);
-    }
+    let (payment, refund) = ensure_funded(deps.as_ref(), &env, &info, &cost)?;
 
     if let Some(recipient) = fee_recipient.as_deref() {
         deps.storage.set_fee_recipient(recipient);
@@ -1063,6 +1337,9 @@ fn trigger(
     deps.storage
         .set_reconcile_trigger_address(info.sender.as_str());
 
+    // skipped txs are only refunded out of what the trigger attached
+    deps.storage.set_reconcile_trigger_payment(payment);
+
     let storage_wrapper = StorageWrapper {
         storage: deps.storage,
     };
@@ -1073,12 +1350,125 @@ fn trigger(
         fee_recipient,
     };
 
-    let response = fsm(&storage_wrapper, &storage_wrapper, &reconcile_env).reconcile();
+    let response = fsm(&storage_wrapper, &storage_wrapper, &reconcile_env).reconcile()?;
 
-    handle_reconcile_response(deps, env, response)
+    let response = handle_reconcile_response(deps, env, response)?;
+
+    Ok(response.add_messages(refund))
+}
+
+/// Ensures the attached funds plus the contract's existing fee balance cover the `cost` of the reconcile sequence,
+/// returning the attached funds retained to pay the cost & a refund of any attached funds in excess of it.
+fn ensure_funded(
+    deps: Deps<NeutronQuery>,
+    env: &CwEnv,
+    info: &MessageInfo,
+    cost: &Coin,
+) -> Result<(u128, Option<BankMsg>)> {
+    let payment = may_pay(info, &cost.denom)?;
+
+    // attached funds have already been added to the contract balance
+    let fee_balance = deps
+        .querier
+        .query_balance(&env.contract.address, &cost.denom)?
+        .amount
+        .saturating_sub(payment);
+
+    if payment + fee_balance < cost.amount {
+        bail!(
+            "insufficient funds for reconcile sequence: required {} {IBC_FEE_DENOM}, attached {payment} {IBC_FEE_DENOM} with a fee balance of {fee_balance} {IBC_FEE_DENOM}",
+            cost.amount
+        );
+    }
+
+    if payment <= cost.amount {
+        return Ok((payment.u128(), None));
+    }
+
+    let refund = BankMsg::Send {
+        to_address: info.sender.to_string(),
+        amount: coins((payment - cost.amount).u128(), IBC_FEE_DENOM),
+    };
+
+    Ok((cost.amount.u128(), Some(refund)))
+}
+
+/// Start a rewards-only reconcile cycle, delegating the accrued rewards outside of the full reconcile cadence.
+/// Only available while the reconcile sequence is idle at the start of a cycle.
+pub fn compound_rewards(
+    deps: DepsMut<NeutronQuery>,
+    env: CwEnv,
+    info: MessageInfo,
+) -> Result<Response<NeutronMsg>> {
+    let phase = deps.storage.reconcile_phase();
+
+    let state = deps.storage.reconcile_state();
+
+    if !phase.is_start_reconcile() || !state.is_idle() {
+        bail!("compound rewards not available for phase {phase} in state {state}");
+    }
+
+    let cost = reconcile_cost(
+        deps.as_ref(),
+        &env,
+        None,
+        ReconcileScope::RewardsOnly,
+        phase,
+        state,
+    )?;
+
+    let (payment, refund) = ensure_funded(deps.as_ref(), &env, &info, &cost)?;
+
+    // no reconciler fee is paid out of rewards compounded outside of a full reconcile
+    deps.storage.clear_fee_recipient();
+
+    deps.storage
+        .set_reconcile_trigger_address(info.sender.as_str());
+
+    // skipped txs are only refunded out of what the trigger attached
+    deps.storage.set_reconcile_trigger_payment(payment);
+
+    let storage_wrapper = StorageWrapper {
+        storage: deps.storage,
+    };
+
+    let reconcile_env = Env {
+        deps: deps.as_ref(),
+        env: &env,
+        fee_recipient: None,
+    };
+
+    let Some(response) =
+        fsm(&storage_wrapper, &storage_wrapper, &reconcile_env).reconcile_rewards_only()?
+    else {
+        bail!(
+            "compound rewards not available while accounts require setup or an unbond is in-flight"
+        );
+    };
+
+    let response = handle_reconcile_response(deps, env, response)?;
+
+    Ok(response.add_messages(refund))
+}
+
+// Each successful undelegate tx adds an unbonding entry with every validator undelegated from
+fn record_unbonding_entries(storage: &mut dyn Storage, env: &CwEnv) {
+    let now = Timestamp(env.block.time.seconds());
+
+    let DurationSecs(unbonding_period) = storage.unbonding_period();
+
+    let completion = Timestamp(now.0 + unbonding_period);
+
+    for ValidatorSetSlot(slot_idx) in storage.take_undelegate_issued_slots() {
+        let validator = storage.validator(slot_idx);
+
+        storage.push_unbonding_entry(&validator, now, completion);
+    }
 }
 
 fn success(deps: DepsMut<NeutronQuery>, env: CwEnv) -> Result<Response<NeutronMsg>> {
+    record_unbonding_entries(deps.storage, &env);
+
     let fee_recipient = deps.storage.fee_recipient();
 
     let storage_wrapper = StorageWrapper {
@@ -1091,12 +1481,15 @@ fn success(deps: DepsMut<NeutronQuery>, env: CwEnv) -> Result<Response<NeutronMs
         fee_recipient,
     };
 
-    let response = fsm(&storage_wrapper, &storage_wrapper, &reconcile_env).reconcile();
+    let response = fsm(&storage_wrapper, &storage_wrapper, &reconcile_env).reconcile()?;
 
     handle_reconcile_response(deps, env, response)
 }
 
 fn failure(deps: DepsMut<NeutronQuery>, env: CwEnv) -> Result<Response<NeutronMsg>> {
+    // a failed tx adds no unbonding entries
+    deps.storage.take_undelegate_issued_slots();
+
     let storage_wrapper = StorageWrapper {
         storage: deps.storage,
     };
@@ -1125,6 +1518,9 @@ pub fn reconcile(
 }
 
 pub fn force_next(deps: DepsMut<NeutronQuery>, env: CwEnv) -> Result<Response<NeutronMsg>> {
+    // the outcome of a forced tx is unknown, its undelegations are assumed to have added entries
+    record_unbonding_entries(deps.storage, &env);
+
     let fee_recipient = deps.storage.fee_recipient();
 
     let storage_wrapper = StorageWrapper {
@@ -1137,7 +1533,7 @@ pub fn force_next(deps: DepsMut<NeutronQuery>, env: CwEnv) -> Result<Response<Ne
         fee_recipient,
     };
 
-    let Some(response) = fsm(&storage_wrapper, &storage_wrapper, &reconcile_env).force_next()
+    let Some(response) = fsm(&storage_wrapper, &storage_wrapper, &reconcile_env).force_next()?
     else {
         bail!(
             "force next not available for phase {} in state {}",
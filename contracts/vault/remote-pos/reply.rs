@@ -1,6 +1,6 @@
 use anyhow::Result;
 use cosmwasm_schema::{cw_serde, serde::de::DeserializeOwned};
-use cosmwasm_std::{from_json, DepsMut, Reply, Response};
+use cosmwasm_std::{from_json, DepsMut, Reply, Response, Storage};
 use neutron_sdk::bindings::msg::NeutronMsg;
 
 use crate::{state::StorageExt, types::Ica};
@@ -80,6 +80,13 @@ fn parse_icq_registration_reply(reply: Reply) -> u64 {
     msg.id
 }
 
+// The registration message was built with the current update interval, later config changes don't affect it
+fn record_update_period(storage: &mut dyn Storage, query_id: u64) {
+    let update_period = storage.icq_update_interval();
+
+    storage.set_icq_update_period(query_id, update_period);
+}
+
 pub fn handle_register_current_set_delegations_icq(
     deps: DepsMut,
     reply: Reply,
@@ -89,6 +96,8 @@ pub fn handle_register_current_set_delegations_icq(
 
     deps.storage.set_delegations_icq(index, delegations_icq_id);
 
+    record_update_period(deps.storage, delegations_icq_id);
+
     Ok(Response::default())
 }
 
@@ -102,6 +111,8 @@ pub fn handle_register_next_set_delegations_icq(
     deps.storage
         .set_next_delegations_icq(index, delegations_icq_id);
 
+    record_update_period(deps.storage, delegations_icq_id);
+
     Ok(Response::default())
 }
 
@@ -117,5 +128,7 @@ pub fn handle_register_balance_icq(
         Ica::Rewards => deps.storage.set_rewards_ica_balance_icq(icq_id),
     }
 
+    record_update_period(deps.storage, icq_id);
+
     Ok(Response::default())
 }
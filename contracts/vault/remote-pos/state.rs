@@ -1,10 +1,12 @@
-use amulet_cw::StorageExt as _;
+use amulet_cw::{event_log::EventLog, StorageExt as _};
 use cosmwasm_std::Storage;
+use num::FixedU256;
 use pos_reconcile_fsm::types::{
-    DelegateStartSlot, Delegated, InflightDelegation, InflightDeposit, InflightFeePayable,
-    InflightRewardsReceivable, InflightUnbond, LastReconcileHeight, MsgIssuedCount,
-    MsgSuccessCount, PendingDeposit, PendingUnbond, Phase, State, UndelegateStartSlot, Weight,
-    Weights,
+    DelegateSlotOrder, DelegateStartSlot, Delegated, DelegationOrder, DurationSecs,
+    InflightDelegation, InflightDeposit, InflightFeePayable, InflightRewardsReceivable,
+    InflightUnbond, LastReconcileHeight, MsgIssuedCount, MsgSuccessCount, PendingDeposit,
+    PendingUnbond, Phase, SetupAccounts, State, Timestamp, UndelegateDeferredSlots,
+    UndelegateRetryCount, UndelegateStartSlot, ValidatorSetSlot, Weight, Weights,
 };
 
 use crate::types::{AvailableToClaim, TotalActualUnbonded, TotalExpectedUnbonded};
@@ -26,23 +28,39 @@ mod key {
     }
 
     pub const AVAILABLE_TO_CLAIM: &str                    = key!("available_to_claim");
+    pub const BATCH_OPENED_AT: MapKey                     = map_key!("batch_opened_at");
+    pub const BATCH_PERIOD_SECS: &str                     = key!("batch_period_secs");
     pub const CONNECTION_ID: &str                         = key!("connection_id");
     pub const DELEGATED: &str                             = key!("delegated");
+    pub const DELEGATION_DRIFT_TOLERANCE: &str            = key!("delegation_drift_tolerance");
+    pub const DELEGATION_ORDER: &str                      = key!("delegation_order");
     pub const DELEGATIONS_ICQ: MapKey                     = map_key!("delegations_icq");
     pub const DELEGATIONS_ICQ_COUNT: &str                  = key!("delegations_icq_count");
+    pub const DELEGATE_SLOT_ORDER: MapKey                 = map_key!("delegate_slot_order");
     pub const DELEGATE_START_SLOT: &str                   = key!("delegate_start_slot");
     pub const ESTIMATED_BLOCK_INTERVAL_SECONDS: &str      = key!("estimated_block_interval_seconds");
     pub const FEE_BPS_BLOCK_INCREMENT: &str               = key!("fee_bps_block_increment");
     pub const FEE_PAYMENT_COOLDOWN_BLOCKS: &str           = key!("fee_payment_cooldown_blocks");
+    pub const FEE_PAYOUTS_ENABLED: &str                   = key!("fee_payouts_enabled");
     pub const FEE_RECIPIENT: &str                         = key!("fee_recipient");
     pub const IBC_DEPOSIT_ASSET: &str                     = key!("ibc_deposit_asset");
+    pub const ICQ_LAST_RESULT_LOCAL_HEIGHT: MapKey        = map_key!("icq_last_result_local_height");
+    pub const ICQ_LAST_RESULT_REMOTE_HEIGHT: MapKey       = map_key!("icq_last_result_remote_height");
+    pub const ICQ_LAST_RESULT_TIMESTAMP: MapKey           = map_key!("icq_last_result_timestamp");
     pub const ICQ_UPDATE_INTERVAL: &str                   = key!("icq_update_interval");
+    pub const ICQ_UPDATE_PERIOD: MapKey                   = map_key!("icq_update_period");
     pub const INFLIGHT_DELEGATION: &str                   = key!("inflight_delegation");
     pub const INFLIGHT_DEPOSIT: &str                      = key!("inflight_deposit");
     pub const INFLIGHT_FEE_PAYABLE: &str                  = key!("inflight_fee_payable");
     pub const INFLIGHT_REWARDS_RECEIVABLE: &str           = key!("inflight_rewards_receivable");
     pub const INFLIGHT_UNBOND: &str                       = key!("inflight_unbond");
+    pub const INSTANT_UNBOND: &str                        = key!("instant_unbond");
+    pub const INTERCHAIN_FEES_PAID: &str                  = key!("interchain_fees_paid");
+    pub const INTERCHAIN_TX_ACK_FEE: &str                 = key!("interchain_tx_ack_fee");
+    pub const INTERCHAIN_TX_TIMEOUT_FEE: &str             = key!("interchain_tx_timeout_fee");
     pub const INTERCHAIN_TX_TIMEOUT_SECONDS: &str         = key!("interchain_tx_timeout_seconds");
+    pub const INTERCHAIN_TXS_FAILED: &str                 = key!("interchain_txs_failed");
+    pub const INTERCHAIN_TXS_ISSUED: &str                 = key!("interchain_txs_issued");
     pub const LAST_RECONCILE_HEIGHT: &str                 = key!("last_reconcile_height");
     pub const LAST_UNBOND_TIMESTAMP: &str                 = key!("last_unbond_timestamp");
     pub const LAST_MAIN_ICA_BALANCE_ICQ_UPDATE: &str      = key!("last_main_ica_balance_icq_update");
@@ -51,6 +69,7 @@ mod key {
     pub const MAX_FEE_BPS: &str                           = key!("max_fee_bps");
     pub const MAX_IBC_MSG_COUNT: &str                     = key!("max_ibc_msg_count");
     pub const MAX_UNBONDING_ENTRIES: &str                 = key!("max_unbonding_entries");
+    pub const MAX_UNDELEGATE_RETRIES: &str                = key!("max_undelegate_retries");
     pub const MAX_VALIDATORS_PER_DELEGATIONS_ICQ: &str    = key!("max_validators_per_delegations_icq");
     pub const MINIMUM_UNBOND_INTERVAL: &str               = key!("minimum_unbond_interval");
     pub const MSG_ISSUED_COUNT: &str                      = key!("msg_issued_count");
@@ -59,32 +78,58 @@ mod key {
     pub const PENDING_BATCH_SLASHED_AMOUNT: &str          = key!("pending_batch_slashed_amount");
     pub const PENDING_DEPOSIT: &str                       = key!("pending_deposit");
     pub const PENDING_UNBOND: &str                        = key!("pending_unbond");
+    pub const PERFORMANCE_FEE_BPS: &str                   = key!("performance_fee_bps");
+    pub const PERFORMANCE_FEE_HIGH_WATER_MARK: &str       = key!("performance_fee_high_water_mark");
+    pub const PERFORMANCE_FEE_RECIPIENT: &str             = key!("performance_fee_recipient");
     pub const RECONCILE_PHASE: &str                       = key!("reconcile_phase");
     pub const RECONCILE_STATE: &str                       = key!("reconcile_state");
     pub const RECONCILE_TRIGGER_ADDRESS: &str             = key!("reconcile_trigger_address");
+    pub const RECONCILE_TRIGGER_PAYMENT: &str             = key!("reconcile_trigger_payment");
     pub const REDELEGATE_SLOT: &str                       = key!("redelegate_slot");
     pub const REDELEGATE_TO: &str                         = key!("redelegate_to");
     pub const REMOTE_DENOM: &str                          = key!("remote_denom");
     pub const REMOTE_DENOM_DECIMALS: &str                 = key!("remote_denom_decimals");
     pub const REWARDS_ICA_ADDRESS: &str                   = key!("rewards_ica_address");
     pub const REWARDS_ICA_BALANCE_ICQ: &str               = key!("rewards_ica_balance_icq");
+    pub const SETUP_DELEGATION_ACCOUNT: &str              = key!("setup_delegation_account");
+    pub const SETUP_REWARDS_ACCOUNT: &str                 = key!("setup_rewards_account");
     pub const TOTAL_ACTUAL_UNBONDED: &str                 = key!("total_actual_unbonded");
     pub const TOTAL_EXPECTED_UNBONDED: &str               = key!("total_expected_unbonded");
     pub const TRANSFER_IN_CHANNEL: &str                   = key!("transfer_in_channel");
     pub const TRANSFER_IN_TIMEOUT_SECONDS: &str           = key!("transfer_in_timeout_seconds");
     pub const TRANSFER_OUT_CHANNEL: &str                  = key!("transfer_out_channel");
     pub const TRANSFER_OUT_TIMEOUT_SECONDS: &str          = key!("transfer_out_timeout_seconds");
+    pub const UNBOND_FEE_BPS: &str                        = key!("unbond_fee_bps");
+    pub const UNBOND_FEE_RECIPIENT: &str                  = key!("unbond_fee_recipient");
     pub const UNBONDING_ACK_COUNT: &str                   = key!("unbonding_ack_count");
+    pub const UNBONDING_CLAIMED_COUNT: &str               = key!("unbonding_claimed_count");
+    pub const UNBONDING_CLAIMED_TOTAL: &str               = key!("unbonding_claimed_total");
+    pub const UNBONDING_ENTRY_COMPLETION: MapKey          = map_key!("unbonding_entry_completion");
+    pub const UNBONDING_ENTRY_FIRST: MapKey               = map_key!("unbonding_entry_first");
+    pub const UNBONDING_ENTRY_NEXT: MapKey                = map_key!("unbonding_entry_next");
     pub const UNBONDING_EXPECTED_AMOUNT: MapKey           = map_key!("unbonding_expected_amount");
     pub const UNBONDING_ISSUED_COUNT: &str                = key!("unbonding_issued_count");
     pub const UNBONDING_LOCAL_EXPIRY: MapKey              = map_key!("unbonding_local_expiry");
     pub const UNBONDING_PERIOD: &str                      = key!("unbonding_period");
+    pub const UNDELEGATE_DEFERRED_SLOT: MapKey            = map_key!("undelegate_deferred_slot");
+    pub const UNDELEGATE_ISSUED_SLOT: MapKey              = map_key!("undelegate_issued_slot");
+    pub const UNDELEGATE_RETRY_COUNT: &str                = key!("undelegate_retry_count");
     pub const UNDELEGATE_START_SLOT: &str                 = key!("undelegate_start_slot");
     pub const VALIDATOR: MapKey                           = map_key!("validator");
     pub const VALIDATOR_SET_SIZE: &str                    = key!("validator_set_size");
     pub const VALIDATOR_WEIGHT: MapKey                    = map_key!("validator_weight");
 }
 
+/// The number of times a failed undelegation batch is re-issued before its offending slot is skipped
+pub const DEFAULT_MAX_UNDELEGATE_RETRIES: u64 = 3;
+
+/// The number of reconcile completions retained in the reconcile history
+pub const DEFAULT_RECONCILE_HISTORY_CAPACITY: u64 = 32;
+
+/// The height & time of the most recent reconcile completions
+pub const RECONCILE_HISTORY: EventLog =
+    EventLog::with_default_capacity("reconcile_history", DEFAULT_RECONCILE_HISTORY_CAPACITY);
+
 pub trait StorageExt: Storage {
     fn available_to_claim(&self) -> AvailableToClaim {
         self.u128_at(key::AVAILABLE_TO_CLAIM)
@@ -96,6 +141,22 @@ pub trait StorageExt: Storage {
         self.set_u128(key::AVAILABLE_TO_CLAIM, amount)
     }
 
+    fn batch_opened_at(&self, batch: u64) -> Option<Timestamp> {
+        self.u64_at(key::BATCH_OPENED_AT.with(batch)).map(Timestamp)
+    }
+
+    fn set_batch_opened_at(&mut self, batch: u64, Timestamp(timestamp): Timestamp) {
+        self.set_u64(key::BATCH_OPENED_AT.with(batch), timestamp);
+    }
+
+    fn batch_period_secs(&self) -> Option<DurationSecs> {
+        self.u64_at(key::BATCH_PERIOD_SECS).map(DurationSecs)
+    }
+
+    fn set_batch_period_secs(&mut self, DurationSecs(batch_period_secs): DurationSecs) {
+        self.set_u64(key::BATCH_PERIOD_SECS, batch_period_secs);
+    }
+
     fn connection_id(&self) -> String {
         self.string_at(key::CONNECTION_ID)
             .expect("set during initialisation")
@@ -115,6 +176,26 @@ pub trait StorageExt: Storage {
         self.set_u128(key::DELEGATED, delegated)
     }
 
+    fn delegation_drift_tolerance(&self) -> Option<u128> {
+        self.u128_at(key::DELEGATION_DRIFT_TOLERANCE)
+    }
+
+    fn set_delegation_drift_tolerance(&mut self, delegation_drift_tolerance: u128) {
+        self.set_u128(key::DELEGATION_DRIFT_TOLERANCE, delegation_drift_tolerance);
+    }
+
+    fn delegation_order(&self) -> DelegationOrder {
+        self.u8_at(key::DELEGATION_ORDER)
+            .map(DelegationOrder::try_from)
+            .transpose()
+            .expect("always: valid delegation order stored")
+            .unwrap_or_default()
+    }
+
+    fn set_delegation_order(&mut self, delegation_order: DelegationOrder) {
+        self.set_u8(key::DELEGATION_ORDER, delegation_order as _);
+    }
+
     fn delegations_icq(&self, idx: u8) -> Option<u64> {
         self.u64_at(key::DELEGATIONS_ICQ.with(idx))
     }
@@ -150,12 +231,36 @@ pub trait StorageExt: Storage {
         self.set_usize(key::DELEGATE_START_SLOT, slot)
     }
 
-    fn estimated_block_interval_seconds(&self) -> u64 {
+    fn delegate_slot_order(&self) -> DelegateSlotOrder {
+        let slot_order = (0..self.validator_set_size())
+            .map_while(|position| self.usize_at(key::DELEGATE_SLOT_ORDER.with(position)))
+            .map(ValidatorSetSlot)
+            .collect();
+
+        DelegateSlotOrder(slot_order)
+    }
+
+    fn set_delegate_slot_order(&mut self, DelegateSlotOrder(slot_order): DelegateSlotOrder) {
+        for position in 0..self.validator_set_size().max(slot_order.len()) {
+            let key = key::DELEGATE_SLOT_ORDER.with(position);
+
+            match slot_order.get(position) {
+                Some(&ValidatorSetSlot(slot)) => self.set_usize(key, slot),
+                None => self.remove(key.as_bytes()),
+            }
+        }
+    }
+
+    fn estimated_block_interval_seconds(&self) -> DurationSecs {
         self.u64_at(key::ESTIMATED_BLOCK_INTERVAL_SECONDS)
+            .map(DurationSecs)
             .expect("set during initialisation")
     }
 
-    fn set_estimated_block_interval_seconds(&mut self, estimated_block_interval_seconds: u64) {
+    fn set_estimated_block_interval_seconds(
+        &mut self,
+        DurationSecs(estimated_block_interval_seconds): DurationSecs,
+    ) {
         self.set_u64(
             key::ESTIMATED_BLOCK_INTERVAL_SECONDS,
             estimated_block_interval_seconds,
@@ -183,6 +288,14 @@ pub trait StorageExt: Storage {
         );
     }
 
+    fn fee_payouts_enabled(&self) -> bool {
+        self.bool_at(key::FEE_PAYOUTS_ENABLED).unwrap_or(true)
+    }
+
+    fn set_fee_payouts_enabled(&mut self, fee_payouts_enabled: bool) {
+        self.set_bool(key::FEE_PAYOUTS_ENABLED, fee_payouts_enabled);
+    }
+
     fn fee_recipient(&self) -> Option<String> {
         let recipient = self.string_at(key::FEE_RECIPIENT)?;
 
@@ -219,6 +332,48 @@ pub trait StorageExt: Storage {
         self.set_u64(key::ICQ_UPDATE_INTERVAL, icq_update_interval);
     }
 
+    fn icq_update_period(&self, query_id: u64) -> Option<u64> {
+        self.u64_at(key::ICQ_UPDATE_PERIOD.with(query_id))
+    }
+
+    fn set_icq_update_period(&mut self, query_id: u64, update_period: u64) {
+        self.set_u64(key::ICQ_UPDATE_PERIOD.with(query_id), update_period);
+    }
+
+    fn icq_last_result_local_height(&self, query_id: u64) -> Option<u64> {
+        self.u64_at(key::ICQ_LAST_RESULT_LOCAL_HEIGHT.with(query_id))
+    }
+
+    fn set_icq_last_result_local_height(&mut self, query_id: u64, height: u64) {
+        self.set_u64(key::ICQ_LAST_RESULT_LOCAL_HEIGHT.with(query_id), height);
+    }
+
+    fn icq_last_result_remote_height(&self, query_id: u64) -> Option<u64> {
+        self.u64_at(key::ICQ_LAST_RESULT_REMOTE_HEIGHT.with(query_id))
+    }
+
+    fn set_icq_last_result_remote_height(&mut self, query_id: u64, height: u64) {
+        self.set_u64(key::ICQ_LAST_RESULT_REMOTE_HEIGHT.with(query_id), height);
+    }
+
+    fn icq_last_result_timestamp(&self, query_id: u64) -> Option<Timestamp> {
+        self.u64_at(key::ICQ_LAST_RESULT_TIMESTAMP.with(query_id))
+            .map(Timestamp)
+    }
+
+    fn set_icq_last_result_timestamp(&mut self, query_id: u64, Timestamp(timestamp): Timestamp) {
+        self.set_u64(key::ICQ_LAST_RESULT_TIMESTAMP.with(query_id), timestamp);
+    }
+
+    /// Whether the host chain completes unbondings instantly (zero unbonding period)
+    fn instant_unbond(&self) -> bool {
+        self.bool_at(key::INSTANT_UNBOND).unwrap_or_default()
+    }
+
+    fn set_instant_unbond(&mut self, instant_unbond: bool) {
+        self.set_bool(key::INSTANT_UNBOND, instant_unbond);
+    }
+
     fn inflight_delegation(&self) -> InflightDelegation {
         self.u128_at(key::INFLIGHT_DELEGATION)
             .map(InflightDelegation)
@@ -281,6 +436,51 @@ pub trait StorageExt: Storage {
         self.set_u128(key::INFLIGHT_UNBOND, inflight_unbond)
     }
 
+    /// The total fees consumed by interchain txs over the vault's lifetime,
+    /// including the escrowed fees of any txs awaiting a response
+    fn interchain_fees_paid(&self) -> u128 {
+        self.u128_at(key::INTERCHAIN_FEES_PAID).unwrap_or_default()
+    }
+
+    fn set_interchain_fees_paid(&mut self, interchain_fees_paid: u128) {
+        self.set_u128(key::INTERCHAIN_FEES_PAID, interchain_fees_paid);
+    }
+
+    /// The ack fee attached to the most recently issued interchain txs
+    fn interchain_tx_ack_fee(&self) -> u128 {
+        self.u128_at(key::INTERCHAIN_TX_ACK_FEE).unwrap_or_default()
+    }
+
+    fn set_interchain_tx_ack_fee(&mut self, ack_fee: u128) {
+        self.set_u128(key::INTERCHAIN_TX_ACK_FEE, ack_fee);
+    }
+
+    /// The timeout fee attached to the most recently issued interchain txs
+    fn interchain_tx_timeout_fee(&self) -> u128 {
+        self.u128_at(key::INTERCHAIN_TX_TIMEOUT_FEE)
+            .unwrap_or_default()
+    }
+
+    fn set_interchain_tx_timeout_fee(&mut self, timeout_fee: u128) {
+        self.set_u128(key::INTERCHAIN_TX_TIMEOUT_FEE, timeout_fee);
+    }
+
+    fn interchain_txs_failed(&self) -> u64 {
+        self.u64_at(key::INTERCHAIN_TXS_FAILED).unwrap_or_default()
+    }
+
+    fn set_interchain_txs_failed(&mut self, interchain_txs_failed: u64) {
+        self.set_u64(key::INTERCHAIN_TXS_FAILED, interchain_txs_failed);
+    }
+
+    fn interchain_txs_issued(&self) -> u64 {
+        self.u64_at(key::INTERCHAIN_TXS_ISSUED).unwrap_or_default()
+    }
+
+    fn set_interchain_txs_issued(&mut self, interchain_txs_issued: u64) {
+        self.set_u64(key::INTERCHAIN_TXS_ISSUED, interchain_txs_issued);
+    }
+
     fn interchain_tx_timeout_seconds(&self) -> u64 {
         self.u64_at(key::INTERCHAIN_TX_TIMEOUT_SECONDS)
             .expect("set during initialisation")
@@ -293,19 +493,20 @@ pub trait StorageExt: Storage {
         );
     }
 
-    fn last_unbond_timestamp(&self) -> Option<u64> {
-        self.u64_at(key::LAST_UNBOND_TIMESTAMP)
+    fn last_unbond_timestamp(&self) -> Option<Timestamp> {
+        self.u64_at(key::LAST_UNBOND_TIMESTAMP).map(Timestamp)
     }
 
-    fn set_last_unbond_timestamp(&mut self, last_unbond_timestamp: u64) {
+    fn set_last_unbond_timestamp(&mut self, Timestamp(last_unbond_timestamp): Timestamp) {
         self.set_u64(key::LAST_UNBOND_TIMESTAMP, last_unbond_timestamp);
     }
 
-    fn last_main_ica_balance_icq_update(&self) -> Option<u64> {
+    fn last_main_ica_balance_icq_update(&self) -> Option<Timestamp> {
         self.u64_at(key::LAST_MAIN_ICA_BALANCE_ICQ_UPDATE)
+            .map(Timestamp)
     }
 
-    fn set_last_main_ica_balance_icq_update(&mut self, timestamp: u64) {
+    fn set_last_main_ica_balance_icq_update(&mut self, Timestamp(timestamp): Timestamp) {
         self.set_u64(key::LAST_MAIN_ICA_BALANCE_ICQ_UPDATE, timestamp);
     }
 
@@ -364,6 +565,15 @@ pub trait StorageExt: Storage {
         self.set_u64(key::MAX_UNBONDING_ENTRIES, max_unbonding_entries);
     }
 
+    fn max_undelegate_retries(&self) -> u64 {
+        self.u64_at(key::MAX_UNDELEGATE_RETRIES)
+            .unwrap_or(DEFAULT_MAX_UNDELEGATE_RETRIES)
+    }
+
+    fn set_max_undelegate_retries(&mut self, max_undelegate_retries: u64) {
+        self.set_u64(key::MAX_UNDELEGATE_RETRIES, max_undelegate_retries);
+    }
+
     fn max_validators_per_delegations_icq(&self) -> u8 {
         self.u8_at(key::MAX_VALIDATORS_PER_DELEGATIONS_ICQ)
             .expect("set during initialisation")
@@ -376,12 +586,13 @@ pub trait StorageExt: Storage {
         );
     }
 
-    fn minimum_unbond_interval(&self) -> u64 {
+    fn minimum_unbond_interval(&self) -> DurationSecs {
         self.u64_at(key::MINIMUM_UNBOND_INTERVAL)
+            .map(DurationSecs)
             .expect("set during initialisation")
     }
 
-    fn set_minimum_unbond_interval(&mut self, minimum_unbond_interval: u64) {
+    fn set_minimum_unbond_interval(&mut self, DurationSecs(minimum_unbond_interval): DurationSecs) {
         self.set_u64(key::MINIMUM_UNBOND_INTERVAL, minimum_unbond_interval);
     }
 
@@ -450,6 +661,38 @@ pub trait StorageExt: Storage {
         self.set_u128(key::PENDING_UNBOND, pending_unbond)
     }
 
+    fn performance_fee_bps(&self) -> u32 {
+        self.u32_at(key::PERFORMANCE_FEE_BPS).unwrap_or_default()
+    }
+
+    fn set_performance_fee_bps(&mut self, performance_fee_bps: u32) {
+        self.set_u32(key::PERFORMANCE_FEE_BPS, performance_fee_bps);
+    }
+
+    fn performance_fee_high_water_mark(&self) -> Option<FixedU256> {
+        self.u256_at(key::PERFORMANCE_FEE_HIGH_WATER_MARK)
+            .map(FixedU256::raw)
+    }
+
+    fn set_performance_fee_high_water_mark(&mut self, high_water_mark: FixedU256) {
+        self.set_u256(
+            key::PERFORMANCE_FEE_HIGH_WATER_MARK,
+            high_water_mark.into_raw(),
+        );
+    }
+
+    fn clear_performance_fee_high_water_mark(&mut self) {
+        self.remove(key::PERFORMANCE_FEE_HIGH_WATER_MARK.as_bytes());
+    }
+
+    fn performance_fee_recipient(&self) -> Option<String> {
+        self.string_at(key::PERFORMANCE_FEE_RECIPIENT)
+    }
+
+    fn set_performance_fee_recipient(&mut self, performance_fee_recipient: &str) {
+        self.set_string(key::PERFORMANCE_FEE_RECIPIENT, performance_fee_recipient);
+    }
+
     fn reconcile_phase(&self) -> Phase {
         self.u8_at(key::RECONCILE_PHASE)
             .map(Phase::try_from)
@@ -482,6 +725,16 @@ pub trait StorageExt: Storage {
         self.set_string(key::RECONCILE_TRIGGER_ADDRESS, reconcile_trigger_address);
     }
 
+    /// The fees attached by the reconcile trigger that are yet to be refunded
+    fn reconcile_trigger_payment(&self) -> u128 {
+        self.u128_at(key::RECONCILE_TRIGGER_PAYMENT)
+            .unwrap_or_default()
+    }
+
+    fn set_reconcile_trigger_payment(&mut self, payment: u128) {
+        self.set_u128(key::RECONCILE_TRIGGER_PAYMENT, payment);
+    }
+
     fn redelegate_slot(&self) -> Option<usize> {
         self.usize_at(key::REDELEGATE_SLOT)
     }
@@ -540,6 +793,23 @@ pub trait StorageExt: Storage {
         self.set_u64(key::REWARDS_ICA_BALANCE_ICQ, icq)
     }
 
+    fn setup_accounts(&self) -> Option<SetupAccounts> {
+        let delegation = self.string_at(key::SETUP_DELEGATION_ACCOUNT)?;
+        let rewards = self
+            .string_at(key::SETUP_REWARDS_ACCOUNT)
+            .expect("always: set alongside the setup delegation account");
+
+        Some(SetupAccounts {
+            delegation,
+            rewards,
+        })
+    }
+
+    fn set_setup_accounts(&mut self, accounts: SetupAccounts) {
+        self.set_string(key::SETUP_DELEGATION_ACCOUNT, &accounts.delegation);
+        self.set_string(key::SETUP_REWARDS_ACCOUNT, &accounts.rewards);
+    }
+
     fn total_actual_unbonded(&self) -> TotalActualUnbonded {
         self.u128_at(key::TOTAL_ACTUAL_UNBONDED)
             .map(TotalActualUnbonded)
@@ -608,6 +878,22 @@ pub trait StorageExt: Storage {
         );
     }
 
+    fn unbond_fee_bps(&self) -> u32 {
+        self.u32_at(key::UNBOND_FEE_BPS).unwrap_or_default()
+    }
+
+    fn set_unbond_fee_bps(&mut self, unbond_fee_bps: u32) {
+        self.set_u32(key::UNBOND_FEE_BPS, unbond_fee_bps);
+    }
+
+    fn unbond_fee_recipient(&self) -> Option<String> {
+        self.string_at(key::UNBOND_FEE_RECIPIENT)
+    }
+
+    fn set_unbond_fee_recipient(&mut self, unbond_fee_recipient: &str) {
+        self.set_string(key::UNBOND_FEE_RECIPIENT, unbond_fee_recipient);
+    }
+
     fn unbonding_ack_count(&self) -> Option<u64> {
         self.u64_at(key::UNBONDING_ACK_COUNT)
     }
@@ -616,6 +902,24 @@ pub trait StorageExt: Storage {
         self.set_u64(key::UNBONDING_ACK_COUNT, count);
     }
 
+    /// Returns the number of acknowledged unbondings entirely claimed & the sum of their expected amounts
+    fn unbonding_claimed(&self) -> (u64, u128) {
+        let count = self
+            .u64_at(key::UNBONDING_CLAIMED_COUNT)
+            .unwrap_or_default();
+
+        let total = self
+            .u128_at(key::UNBONDING_CLAIMED_TOTAL)
+            .unwrap_or_default();
+
+        (count, total)
+    }
+
+    fn set_unbonding_claimed(&mut self, count: u64, total: u128) {
+        self.set_u64(key::UNBONDING_CLAIMED_COUNT, count);
+        self.set_u128(key::UNBONDING_CLAIMED_TOTAL, total);
+    }
+
     fn unbonding_expected_amount(&self, idx: u64) -> Option<u128> {
         self.u128_at(key::UNBONDING_EXPECTED_AMOUNT.with(idx))
     }
@@ -632,23 +936,135 @@ pub trait StorageExt: Storage {
         self.set_u64(key::UNBONDING_ISSUED_COUNT, count);
     }
 
-    fn unbonding_local_expiry(&self, idx: u64) -> Option<u64> {
+    fn unbonding_local_expiry(&self, idx: u64) -> Option<Timestamp> {
         self.u64_at(key::UNBONDING_LOCAL_EXPIRY.with(idx))
+            .map(Timestamp)
     }
 
-    fn set_unbonding_local_expiry(&mut self, idx: u64, timestamp: u64) {
+    fn set_unbonding_local_expiry(&mut self, idx: u64, Timestamp(timestamp): Timestamp) {
         self.set_u64(key::UNBONDING_LOCAL_EXPIRY.with(idx), timestamp);
     }
 
-    fn unbonding_period(&self) -> u64 {
+    fn unbonding_period(&self) -> DurationSecs {
         self.u64_at(key::UNBONDING_PERIOD)
+            .map(DurationSecs)
             .expect("set during initialisation")
     }
 
-    fn set_unbonding_period(&mut self, unbonding_period: u64) {
+    fn set_unbonding_period(&mut self, DurationSecs(unbonding_period): DurationSecs) {
         self.set_u64(key::UNBONDING_PERIOD, unbonding_period);
     }
 
+    fn undelegate_deferred_slots(&self) -> UndelegateDeferredSlots {
+        let deferred_slots = (0..self.validator_set_size())
+            .filter(|slot_idx| {
+                self.bool_at(key::UNDELEGATE_DEFERRED_SLOT.with(slot_idx))
+                    .unwrap_or_default()
+            })
+            .map(ValidatorSetSlot)
+            .collect();
+
+        UndelegateDeferredSlots(deferred_slots)
+    }
+
+    fn set_undelegate_deferred_slots(
+        &mut self,
+        UndelegateDeferredSlots(deferred_slots): UndelegateDeferredSlots,
+    ) {
+        for slot_idx in 0..self.validator_set_size() {
+            self.set_bool(
+                key::UNDELEGATE_DEFERRED_SLOT.with(slot_idx),
+                deferred_slots.contains(&ValidatorSetSlot(slot_idx)),
+            );
+        }
+    }
+
+    /// Record that an undelegation from the slot was issued in the inflight tx
+    fn set_undelegate_issued(&mut self, ValidatorSetSlot(slot_idx): ValidatorSetSlot) {
+        self.set_bool(key::UNDELEGATE_ISSUED_SLOT.with(slot_idx), true);
+    }
+
+    /// Returns the slots undelegated from in the inflight tx, clearing them
+    fn take_undelegate_issued_slots(&mut self) -> Vec<ValidatorSetSlot> {
+        let issued_slots: Vec<_> = (0..self.validator_set_size())
+            .filter(|slot_idx| {
+                self.bool_at(key::UNDELEGATE_ISSUED_SLOT.with(slot_idx))
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        for slot_idx in &issued_slots {
+            self.remove(key::UNDELEGATE_ISSUED_SLOT.with(slot_idx).as_bytes());
+        }
+
+        issued_slots.into_iter().map(ValidatorSetSlot).collect()
+    }
+
+    /// The number of unbonding entries the delegation account has with the validator that complete after `now`
+    fn inflight_unbonding_entries(&self, validator: &str, Timestamp(now): Timestamp) -> u64 {
+        let first = self
+            .u64_at(key::UNBONDING_ENTRY_FIRST.with(validator))
+            .unwrap_or_default();
+
+        let next = self
+            .u64_at(key::UNBONDING_ENTRY_NEXT.with(validator))
+            .unwrap_or_default();
+
+        (first..next)
+            .filter(|idx| {
+                self.u64_at(key::UNBONDING_ENTRY_COMPLETION.multi([&validator, idx]))
+                    .is_some_and(|completion| completion > now)
+            })
+            .count() as u64
+    }
+
+    /// Record an unbonding entry with the validator completing at `completion`,
+    /// dropping the entries that completed by `now`
+    fn push_unbonding_entry(
+        &mut self,
+        validator: &str,
+        Timestamp(now): Timestamp,
+        Timestamp(completion): Timestamp,
+    ) {
+        let mut first = self
+            .u64_at(key::UNBONDING_ENTRY_FIRST.with(validator))
+            .unwrap_or_default();
+
+        let next = self
+            .u64_at(key::UNBONDING_ENTRY_NEXT.with(validator))
+            .unwrap_or_default();
+
+        // entries are recorded in the order they complete while the unbonding period is unchanged
+        while first < next {
+            let entry_key = key::UNBONDING_ENTRY_COMPLETION.multi([&validator, &first]);
+
+            if self.u64_at(&entry_key).is_some_and(|c| c > now) {
+                break;
+            }
+
+            self.remove(entry_key.as_bytes());
+
+            first += 1;
+        }
+
+        self.set_u64(
+            key::UNBONDING_ENTRY_COMPLETION.multi([&validator, &next]),
+            completion,
+        );
+        self.set_u64(key::UNBONDING_ENTRY_FIRST.with(validator), first);
+        self.set_u64(key::UNBONDING_ENTRY_NEXT.with(validator), next + 1);
+    }
+
+    fn undelegate_retry_count(&self) -> UndelegateRetryCount {
+        self.usize_at(key::UNDELEGATE_RETRY_COUNT)
+            .map(UndelegateRetryCount)
+            .unwrap_or_default()
+    }
+
+    fn set_undelegate_retry_count(&mut self, UndelegateRetryCount(count): UndelegateRetryCount) {
+        self.set_usize(key::UNDELEGATE_RETRY_COUNT, count)
+    }
+
     fn undelegate_start_slot(&self) -> UndelegateStartSlot {
         self.usize_at(key::UNDELEGATE_START_SLOT)
             .map(UndelegateStartSlot)
@@ -680,6 +1096,31 @@ pub trait StorageExt: Storage {
         self.set_string(key::VALIDATOR.with(slot_idx), validator)
     }
 
+    fn validator_target_weights(&self) -> Option<Weights> {
+        let set_size = self.validator_set_size();
+
+        let mut weights = Vec::with_capacity(set_size);
+
+        for slot_idx in 0..set_size {
+            let weight = self
+                .u256_at(key::VALIDATOR_WEIGHT.multi([&"target", &slot_idx]))
+                .map(Weight::raw)?;
+
+            weights.push(weight);
+        }
+
+        Some(Weights::new_unchecked(weights))
+    }
+
+    fn set_validator_target_weights(&mut self, weights: &Weights) {
+        for (slot_idx, weight) in weights.as_slice().iter().enumerate() {
+            self.set_u256(
+                key::VALIDATOR_WEIGHT.multi([&"target", &slot_idx]),
+                weight.into_raw(),
+            )
+        }
+    }
+
     fn validator_initial_weight(&self, slot_idx: usize) -> Weight {
         self.u256_at(key::VALIDATOR_WEIGHT.multi([&"initial", &slot_idx]))
             .map(Weight::raw)
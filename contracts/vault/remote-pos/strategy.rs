@@ -1,29 +1,38 @@
-use amulet_cw::vault::{unbonding_log, UnbondingLog};
-use amulet_ntrn::query::QuerierExt;
-use anyhow::{bail, ensure, Result};
+use amulet_cw::{
+    bank,
+    vault::{
+        self, claims::StorageExt as _, redemption_rate, unbonding_log, SharesMint, UnbondingLog,
+    },
+};
+use amulet_ntrn::{ica, query::QuerierExt, token_factory::TokenFactory, IBC_FEE_DENOM};
+use anyhow::{anyhow, bail, ensure, Result};
 use bech32::{Bech32, Hrp};
 use cosmwasm_std::{
-    coins, BankMsg, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, Storage, Timestamp,
-    Uint128,
+    coin, coins, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, QuerierWrapper,
+    Response, StdError, Storage, Uint128,
 };
 
 use amulet_core::{
     vault::{
-        ClaimAmount, DepositAmount, DepositValue, Now as VaultNow, Strategy as CoreStrategy,
-        StrategyCmd, TotalDepositsValue, UnbondEpoch, UnbondReadyStatus, UnbondingLog as _,
-        UnbondingLogSet,
+        pending_batch_id, vault as core_vault, BatchId, ClaimAmount, Cmd as VaultCmd,
+        DepositAmount, DepositValue, Now as VaultNow, PerformanceFeeResponse,
+        Strategy as CoreStrategy, StrategyCmd, TotalDepositsValue, UnbondEpoch, UnbondReadyStatus,
+        UnbondingLog as _, UnbondingLogSet, Vault as _,
     },
     Asset, Decimals, Identifier,
 };
 use cw_utils::must_pay;
 use neutron_sdk::bindings::{msg::NeutronMsg, query::NeutronQuery};
 use num::FixedU256;
-use pos_reconcile_fsm::types::{PendingDeposit, PendingUnbond};
+use pos_reconcile_fsm::types::{
+    InflightDeposit, PendingDeposit, PendingUnbond, Timestamp, Weight, Weights,
+};
 
 use crate::{
     icq,
-    reconcile::current_deposits,
-    state::StorageExt,
+    msg::{PendingUnbondingBatch, ReconcileHistoryEntry},
+    reconcile::{self, current_deposits},
+    state::{StorageExt, RECONCILE_HISTORY},
     types::{AvailableToClaim, Ica, Icq, TotalActualUnbonded, TotalExpectedUnbonded},
 };
 
@@ -36,11 +45,38 @@ impl<'a> Strategy<'a> {
     pub fn new(storage: &'a dyn Storage, env: &Env) -> Self {
         Self {
             storage,
-            now: env.block.time,
+            now: Timestamp(env.block.time.seconds()),
         }
     }
 }
 
+/// Returns the earliest timestamp the next batch may be unbonded, `None` if nothing has been unbonded yet
+pub fn minimum_unbond_interval_elapses_at(storage: &dyn Storage) -> Option<Timestamp> {
+    let last_unbond_timestamp = storage.last_unbond_timestamp()?;
+
+    Some(last_unbond_timestamp.plus(storage.minimum_unbond_interval()))
+}
+
+/// Returns the earliest timestamp anyone may commit the pending batch, `None` if no batch period is set.
+/// A batch that has not been opened yet is treated as opening `now`.
+/// The batch is never committed before the minimum unbond interval has elapsed.
+pub fn batch_commit_after(storage: &dyn Storage, now: Timestamp) -> Option<Timestamp> {
+    let batch_period_secs = storage.batch_period_secs()?;
+
+    let pending_batch_id = pending_batch_id(&UnbondingLog::new(storage));
+
+    let opened_at = storage.batch_opened_at(pending_batch_id).unwrap_or(now);
+
+    let period_elapses_at = opened_at.plus(batch_period_secs);
+
+    Some(
+        minimum_unbond_interval_elapses_at(storage)
+            .map_or(period_elapses_at, |interval_elapses_at| {
+                period_elapses_at.max(interval_elapses_at)
+            }),
+    )
+}
+
 fn unbond_ready(storage: &dyn Storage, now: Timestamp, unbond_amount: u128) -> UnbondReadyStatus {
     let unbonding_period = storage.unbonding_period();
 
@@ -48,7 +84,7 @@ fn unbond_ready(storage: &dyn Storage, now: Timestamp, unbond_amount: u128) -> U
 
     let fee_payment_cooldown_blocks = storage.fee_payment_cooldown_blocks();
 
-    let buffer_period = (fee_payment_cooldown_blocks * 3) * estimated_block_time;
+    let buffer_period = estimated_block_time.times(fee_payment_cooldown_blocks * 3);
 
     let pending_batch_slashed_amount = storage.pending_batch_slashed_amount();
 
@@ -57,9 +93,17 @@ fn unbond_ready(storage: &dyn Storage, now: Timestamp, unbond_amount: u128) -> U
         .map(ClaimAmount)
         .expect("always: pending batch slashed amount <= pending batch amount");
 
+    // instant unbondings are claimable as soon as they are received, claims are bounded by the
+    // amount available to claim so no maturity buffer is required
+    let end = if storage.instant_unbond() {
+        now
+    } else {
+        now.plus(unbonding_period).plus(buffer_period)
+    };
+
     let epoch = UnbondEpoch {
-        start: now.seconds(),
-        end: now.seconds() + unbonding_period + buffer_period,
+        start: now.0,
+        end: end.0,
     };
 
     UnbondReadyStatus::Ready { amount, epoch }
@@ -67,13 +111,22 @@ fn unbond_ready(storage: &dyn Storage, now: Timestamp, unbond_amount: u128) -> U
 
 impl<'a> CoreStrategy for Strategy<'a> {
     fn now(&self) -> VaultNow {
-        self.now.seconds()
+        self.now.0
     }
 
     fn deposit_asset(&self) -> Asset {
         self.storage.ibc_deposit_asset().into()
     }
 
+    fn convert_secondary_deposit(
+        &self,
+        _asset: &Asset,
+        _amount: DepositAmount,
+    ) -> Option<DepositAmount> {
+        // only the deposit asset is accepted
+        None
+    }
+
     fn underlying_asset_decimals(&self) -> Decimals {
         self.storage.remote_denom_decimals()
     }
@@ -99,10 +152,12 @@ impl<'a> CoreStrategy for Strategy<'a> {
 
         let minimun_unbond_interval = self.storage.minimum_unbond_interval();
 
-        let elapsed = self.now.seconds() - last_unbond_timestamp;
+        let elapsed = self.now.saturating_since(last_unbond_timestamp);
 
         if elapsed < minimun_unbond_interval {
-            return UnbondReadyStatus::Later(Some(last_unbond_timestamp + minimun_unbond_interval));
+            let hint = last_unbond_timestamp.plus(minimun_unbond_interval);
+
+            return UnbondReadyStatus::Later(Some(hint.0));
         }
 
         let PendingUnbond(pending_unbond) = self.storage.pending_unbond();
@@ -114,6 +169,80 @@ impl<'a> CoreStrategy for Strategy<'a> {
 
         unbond_ready(self.storage, self.now, unbond_amount)
     }
+
+    fn unbond_fee(&self, DepositValue(value): DepositValue) -> DepositValue {
+        let unbond_fee_bps = self.storage.unbond_fee_bps();
+
+        if unbond_fee_bps == 0 {
+            return DepositValue(0);
+        }
+
+        let fee = Uint128::new(value)
+            .checked_multiply_ratio(unbond_fee_bps, 10_000u32)
+            .expect("always: unbond fee bps <= 10,000")
+            .u128();
+
+        DepositValue(fee)
+    }
+
+    fn available_to_claim(&self) -> Option<ClaimAmount> {
+        Some(ClaimAmount(available_to_claim(self.storage)))
+    }
+
+    fn redemption_rate(&self) -> Option<FixedU256> {
+        // deposits are the underlying asset, so there is no redemption rate
+        None
+    }
+}
+
+// Returns the received unbondings yet to be claimed in claim terms,
+// as claims are paid out at the actual/expected unbonded ratio
+fn available_to_claim(storage: &dyn Storage) -> u128 {
+    let AvailableToClaim(available_to_claim) = storage.available_to_claim();
+
+    let TotalActualUnbonded(total_actual_unbonded) = storage.total_actual_unbonded();
+
+    let TotalExpectedUnbonded(total_expected_unbonded) = storage.total_expected_unbonded();
+
+    let total_actual_unbonded = total_actual_unbonded.min(total_expected_unbonded);
+
+    if total_actual_unbonded == 0 {
+        return 0;
+    }
+
+    Uint128::new(available_to_claim)
+        .checked_multiply_ratio(total_expected_unbonded, total_actual_unbonded)
+        .map_or(u128::MAX, |amount| amount.u128())
+}
+
+// Returns the number of acknowledged unbondings entirely claimed & the sum of their expected amounts.
+// Claims are paid from a pool of received unbondings, so they are attributed to unbondings in the order issued.
+fn claimed_unbondings(storage: &dyn Storage) -> (u64, u128) {
+    let TotalExpectedUnbonded(total_expected_unbonded) = storage.total_expected_unbonded();
+
+    let total_claimed = total_expected_unbonded.saturating_sub(available_to_claim(storage));
+
+    let ack_count = storage.unbonding_ack_count().unwrap_or_default();
+
+    let (mut claimed_count, mut claimed_total) = storage.unbonding_claimed();
+
+    while claimed_count < ack_count {
+        let expected_amount = storage
+            .unbonding_expected_amount(claimed_count)
+            .expect("always: unbonding record exists");
+
+        let Some(next_total) = claimed_total
+            .checked_add(expected_amount)
+            .filter(|next_total| *next_total <= total_claimed)
+        else {
+            break;
+        };
+
+        claimed_count += 1;
+        claimed_total = next_total;
+    }
+
+    (claimed_count, claimed_total)
 }
 
 fn send_claimed_unbondings<C>(
@@ -160,6 +289,10 @@ fn send_claimed_unbondings<C>(
 
     storage.set_available_to_claim(AvailableToClaim(available_to_claim));
 
+    let (claimed_count, claimed_total) = claimed_unbondings(storage);
+
+    storage.set_unbonding_claimed(claimed_count, claimed_total);
+
     let ibc_deposit_asset = storage.ibc_deposit_asset();
 
     Ok(BankMsg::Send {
@@ -196,11 +329,178 @@ pub fn handle_cmd<C>(storage: &mut dyn Storage, cmd: StrategyCmd) -> Result<Opti
         StrategyCmd::SendClaimed { amount, recipient } => {
             return send_claimed_unbondings(storage, amount.0, recipient).map(Some)
         }
+
+        StrategyCmd::CollectUnbondFee { batch, value } => {
+            storage.add_claimable(UNBOND_FEES_ACCOUNT, batch, value.0)?;
+        }
+
+        StrategyCmd::DepositSecondary { .. } => {
+            unreachable!("strategy does not accept secondary deposit assets")
+        }
     }
 
     Ok(None)
 }
 
+/// Track each recipient's unbondings in the claims module alongside the unbonding log: the value
+/// unbonded in a batch is added when the recipient redeems & every batch up to the last claimed
+/// batch is matured & claimed when the recipient claims.
+pub fn track_claims(storage: &mut dyn Storage, cmd: &UnbondingLogSet) -> Result<()> {
+    match cmd {
+        UnbondingLogSet::UnbondedValueInBatch {
+            recipient,
+            batch,
+            value: DepositValue(value),
+        } => {
+            let added = value
+                .checked_sub(storage.claimable_in_batch(recipient, *batch))
+                .ok_or_else(|| anyhow!("unbonded value in batch {batch} decreased"))?;
+
+            storage.add_claimable(recipient, *batch, added)?;
+        }
+
+        UnbondingLogSet::LastClaimedBatch { recipient, batch } => {
+            // unbondings entered before claims were tracked have nothing recorded
+            if storage.unclaimed_total(recipient) == 0 {
+                return Ok(());
+            }
+
+            for (unclaimed_batch, _) in storage.unclaimed_batches(recipient) {
+                if unclaimed_batch > *batch {
+                    break;
+                }
+
+                if !storage.is_matured(unclaimed_batch) {
+                    storage.mature(unclaimed_batch)?;
+                }
+            }
+
+            storage.claim(recipient)?;
+        }
+
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// The claims account unbond fees are collected against, on behalf of the unbond fee recipient
+const UNBOND_FEES_ACCOUNT: &str = "unbond_fees";
+
+/// The total value of unbond fees collected & yet to be claimed
+pub fn collected_unbond_fees(storage: &dyn Storage) -> u128 {
+    storage.unclaimed_total(UNBOND_FEES_ACCOUNT)
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ClaimableUnbondFees {
+    /// The amount of unbonded assets the fee recipient is entitled to
+    pub amount: u128,
+    /// The value of the collected unbond fees being claimed
+    pub value: u128,
+    /// The batches the claimable unbond fees were collected in
+    pub batches: Vec<BatchId>,
+}
+
+/// Returns the unbond fees retained in committed batches whose epochs have ended, in the same
+/// proportion of each batch's claimable amount as any other recipient in the batch.
+pub fn claimable_unbond_fees(storage: &dyn Storage, now: u64) -> ClaimableUnbondFees {
+    let unbonding_log = UnbondingLog::new(storage);
+
+    let mut claimable = ClaimableUnbondFees::default();
+
+    let Some(last_committed_batch_id) = unbonding_log.last_committed_batch_id() else {
+        return claimable;
+    };
+
+    for (batch, fee) in storage.unclaimed_batches(UNBOND_FEES_ACCOUNT) {
+        if batch > last_committed_batch_id {
+            break;
+        }
+
+        let epoch = unbonding_log
+            .committed_batch_epoch(batch)
+            .expect("always: committed batches have an epoch set");
+
+        if epoch.end > now {
+            break;
+        }
+
+        let (DepositValue(total_unbond_value), ClaimAmount(total_claimable)) = unbonding_log
+            .batch_unbond_value(batch)
+            .zip(unbonding_log.batch_claimable_amount(batch))
+            .expect("always: committed batches have an unbond value & claimable amount set");
+
+        let amount = Uint128::new(total_claimable)
+            .checked_multiply_ratio(fee, total_unbond_value)
+            .expect("always: batch unbond fee <= batch unbond value")
+            .u128();
+
+        claimable.amount = claimable
+            .amount
+            .checked_add(amount)
+            .expect("always: claimable unbond fees <= total claimable");
+
+        claimable.value = claimable
+            .value
+            .checked_add(fee)
+            .expect("always: claimable unbond fees value <= collected unbond fees");
+
+        claimable.batches.push(batch);
+    }
+
+    claimable
+}
+
+pub fn handle_claim_unbond_fees(
+    deps: DepsMut<NeutronQuery>,
+    env: Env,
+) -> Result<Response<NeutronMsg>> {
+    let recipient = deps
+        .storage
+        .unbond_fee_recipient()
+        .ok_or_else(|| anyhow!("no unbond fee recipient set"))?;
+
+    let ClaimableUnbondFees {
+        amount,
+        value,
+        batches,
+    } = claimable_unbond_fees(deps.storage, env.block.time.seconds());
+
+    ensure!(value > 0, "no unbond fees to claim");
+
+    // redeemers claiming from the same batches may have matured them already
+    for batch in batches {
+        if !deps.storage.is_matured(batch) {
+            deps.storage.mature(batch)?;
+        }
+    }
+
+    let claimed = deps.storage.claim(UNBOND_FEES_ACCOUNT)?;
+
+    ensure!(
+        claimed == value,
+        "claimed unbond fees value {claimed} does not match claimable value {value}"
+    );
+
+    // a fully slashed batch leaves nothing to send
+    let send_msg = if amount > 0 {
+        Some(send_claimed_unbondings(
+            deps.storage,
+            amount,
+            recipient.as_str().into(),
+        )?)
+    } else {
+        None
+    };
+
+    Ok(Response::default()
+        .add_messages(send_msg)
+        .add_attribute("kind", "claim_unbond_fees")
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount.to_string()))
+}
+
 // Mirrors: https://github.com/neutron-org/neutron/blob/v2.0.0/x/ibc-hooks/utils/utils.go#L68
 fn ica_ibc_hook_address(channel: &str, ica_address: &str) -> String {
     const HOOK_ADDR_PREFIX: &str = "ibc-wasm-hook-intermediary";
@@ -292,7 +592,7 @@ pub fn acknowledge_slashing(storage: &mut dyn Storage, slashed_ratio: FixedU256)
 
 pub fn acknowledge_expected_unbondings(
     storage: &mut dyn Storage,
-    balance_icq_timestamp: u64,
+    balance_icq_timestamp: Timestamp,
 ) -> u128 {
     let issued_count = storage.unbonding_issued_count().unwrap_or_default();
 
@@ -325,10 +625,154 @@ pub fn acknowledge_expected_unbondings(
     ack_amount
 }
 
+/// Returns every issued unbonding that has not yet been entirely claimed, in the order they were issued
+pub fn pending_unbondings(storage: &dyn Storage, now: Timestamp) -> Vec<PendingUnbondingBatch> {
+    let issued_count = storage.unbonding_issued_count().unwrap_or_default();
+
+    let ack_count = storage.unbonding_ack_count().unwrap_or_default();
+
+    let (claimed_count, _) = claimed_unbondings(storage);
+
+    (claimed_count..issued_count)
+        .map(|id| {
+            let expected_completion = storage
+                .unbonding_local_expiry(id)
+                .expect("always: unbonding record exists");
+
+            let amount = storage
+                .unbonding_expected_amount(id)
+                .expect("always: unbonding record exists")
+                .into();
+
+            PendingUnbondingBatch {
+                id,
+                amount,
+                expected_completion: vault::Timestamp(expected_completion.0),
+                claimable: id < ack_count || expected_completion <= now,
+                received: id < ack_count,
+            }
+        })
+        .collect()
+}
+
+/// Returns the vault's balance of each denom in excess of the deposits awaiting delegation
+/// & the unbonded assets awaiting claim.
+/// The fee denom balance is the float paying for interchain txs & queries, so it is never unaccounted for.
+pub fn unaccounted_balances(
+    storage: &dyn Storage,
+    querier: QuerierWrapper<NeutronQuery>,
+    env: &Env,
+) -> Result<Vec<Coin>, StdError> {
+    let ibc_denom = storage.ibc_deposit_asset();
+
+    let PendingDeposit(pending_deposit) = storage.pending_deposit();
+
+    // a failed transfer out refunds the inflight deposit to the vault
+    let InflightDeposit(inflight_deposit) = storage.inflight_deposit();
+
+    let AvailableToClaim(available_to_claim) = storage.available_to_claim();
+
+    let tracked = [
+        coin(pending_deposit, &ibc_denom),
+        coin(inflight_deposit, &ibc_denom),
+        coin(available_to_claim, &ibc_denom),
+    ];
+
+    let mut balances = querier.query_all_balances(&env.contract.address)?;
+
+    balances.retain(|balance| balance.denom != IBC_FEE_DENOM);
+
+    Ok(bank::unaccounted_balances(balances, &tracked))
+}
+
+/// Returns the current share redemption rate, `None` if no shares have been issued
+pub fn redemption_rate(storage: &dyn Storage, env: &Env) -> Option<FixedU256> {
+    redemption_rate::current_rate(
+        &Strategy::new(storage, env),
+        &UnbondingLog::new(storage),
+        &SharesMint::new(storage, env),
+    )
+}
+
+/// Record the current redemption rate as an observation, if any shares have been issued
+pub fn record_redemption_rate(storage: &mut dyn Storage, env: &Env) {
+    if let Some(rate) = redemption_rate(storage, env) {
+        redemption_rate::record(storage, env.block.height, env.block.time.seconds(), rate);
+    }
+}
+
+/// Record a reconcile completion at `height` in the reconcile history
+pub fn record_reconcile_completion(
+    storage: &mut dyn Storage,
+    height: u64,
+    Timestamp(now): Timestamp,
+) {
+    RECONCILE_HISTORY
+        .append(storage, height, &[vault::Timestamp(now)])
+        .expect("always: timestamp serializes");
+}
+
+/// Returns up to `limit` retained reconcile completions after the `start_after` entry, oldest first
+pub fn reconcile_history(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Vec<ReconcileHistoryEntry> {
+    RECONCILE_HISTORY
+        .query(storage, start_after, limit)
+        .entries
+        .into_iter()
+        .map(|entry| ReconcileHistoryEntry {
+            id: entry.id,
+            height: entry.height,
+            timestamp: entry
+                .parse_event()
+                .expect("always: timestamp stored for reconcile completions"),
+        })
+        .collect()
+}
+
+/// Mint the performance fee shares for any gain in share value above the high-water mark, if a fee is set
+pub fn take_performance_fee(storage: &mut dyn Storage, env: &Env) -> Option<CosmosMsg<NeutronMsg>> {
+    let fee_bps = storage.performance_fee_bps();
+
+    if fee_bps == 0 {
+        return None;
+    }
+
+    let recipient = storage
+        .performance_fee_recipient()
+        .expect("always: set when charging a performance fee");
+
+    let PerformanceFeeResponse {
+        cmds,
+        high_water_mark,
+        ..
+    } = core_vault(
+        &Strategy::new(storage, env),
+        &UnbondingLog::new(storage),
+        &SharesMint::new(storage, env),
+    )
+    .take_performance_fee(
+        fee_bps,
+        recipient.into(),
+        storage.performance_fee_high_water_mark(),
+    );
+
+    if let Some(high_water_mark) = high_water_mark {
+        storage.set_performance_fee_high_water_mark(high_water_mark);
+    }
+
+    cmds.into_iter().find_map(|cmd| match cmd {
+        VaultCmd::Mint(cmd) => Some(vault::handle_mint_cmd(storage, TokenFactory::new(env), cmd)),
+        _ => None,
+    })
+}
+
 pub fn handle_receive_undelegated(
     deps: DepsMut<NeutronQuery>,
     info: MessageInfo,
-    balance_icq_timestamp: u64,
+    balance_icq_timestamp: Timestamp,
 ) -> Result<Response<NeutronMsg>> {
     let transfer_out_channel = deps.storage.transfer_out_channel();
 
@@ -441,6 +885,89 @@ pub fn handle_redelegate_slot(
     Ok(Response::default().add_submessages(msgs))
 }
 
+pub fn handle_resize_validator_set(
+    deps: DepsMut<NeutronQuery>,
+    info: MessageInfo,
+    new_validators: Vec<String>,
+) -> Result<Response<NeutronMsg>> {
+    if new_validators.is_empty() {
+        bail!("validator set cannot be empty");
+    }
+
+    for (idx, validator) in new_validators.iter().enumerate() {
+        if new_validators[..idx].contains(validator) {
+            bail!("{validator} appears more than once in the set");
+        }
+    }
+
+    let existing_validator_set = deps.storage.validators();
+
+    let slot_map: Vec<_> = new_validators
+        .iter()
+        .map(|validator| existing_validator_set.iter().position(|v| v == validator))
+        .collect();
+
+    let delegations_icq_count = new_validators
+        .len()
+        .div_ceil(deps.storage.max_validators_per_delegations_icq().into())
+        .try_into()
+        .map_err(|_| anyhow!("delegations icq count exceeds {}", u8::MAX))?;
+
+    must_pay_icq_deposit(deps.as_ref(), &info, delegations_icq_count)?;
+
+    reconcile::resize_validator_set(deps.storage, &slot_map)?;
+
+    let initial_weights = Weights::new_unchecked(deps.storage.validator_initial_weights())
+        .resize(&slot_map)
+        .expect("checked: slot map accepted by the reconcile resize");
+
+    for (slot_idx, weight) in initial_weights.into_iter().enumerate() {
+        deps.storage.set_validator_initial_weight(slot_idx, weight);
+    }
+
+    if let Some(target_weights) = deps.storage.validator_target_weights() {
+        let target_weights = target_weights
+            .resize(&slot_map)
+            .expect("checked: slot map accepted by the reconcile resize");
+
+        deps.storage.set_validator_target_weights(&target_weights);
+    }
+
+    for (slot_idx, validator) in new_validators.iter().enumerate() {
+        deps.storage.set_validator(slot_idx, validator);
+    }
+
+    deps.storage.set_validator_set_size(new_validators.len());
+    deps.storage
+        .set_delegations_icq_count(delegations_icq_count);
+
+    let msgs = icq::main_ica_current_delegations_registration_msgs(deps.storage, new_validators);
+
+    Ok(Response::default().add_submessages(msgs))
+}
+
+pub fn handle_set_target_weights(
+    deps: DepsMut<NeutronQuery>,
+    weights: Vec<u32>,
+) -> Result<Response<NeutronMsg>> {
+    if weights.len() != deps.storage.validator_set_size() {
+        bail!("target weights length does not match validator set size");
+    }
+
+    let weights = weights
+        .into_iter()
+        .map(Weight::checked_from_bps)
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| anyhow!("invalid target validator slot weight"))?;
+
+    let weights = Weights::new(&weights)
+        .map_err(|err| anyhow!("invalid target validator slot weights: {err}"))?;
+
+    deps.storage.set_validator_target_weights(&weights);
+
+    Ok(Response::default())
+}
+
 pub fn handle_restore_ica(
     deps: DepsMut<NeutronQuery>,
     info: MessageInfo,
@@ -460,11 +987,7 @@ pub fn handle_restore_ica(
 
     let connection_id = deps.storage.connection_id();
 
-    let msg = NeutronMsg::RegisterInterchainAccount {
-        connection_id,
-        interchain_account_id: ica.id().to_owned(),
-        register_fee: Some(vec![ica_register_fee]),
-    };
+    let msg = ica::register_ica(connection_id, ica.id(), ica_register_fee);
 
     Ok(Response::default().add_message(msg))
 }
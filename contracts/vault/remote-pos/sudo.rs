@@ -1,24 +1,23 @@
 use anyhow::Result;
-use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{from_json, DepsMut, Env, Response};
-use neutron_sdk::bindings::{msg::NeutronMsg, query::NeutronQuery};
+use cosmwasm_std::{DepsMut, Env, Response};
+use neutron_sdk::{
+    bindings::{
+        msg::NeutronMsg,
+        query::{NeutronQuery, QueryRegisteredQueryResponse},
+    },
+    interchain_queries::queries::get_registered_query,
+};
+
+use amulet_ntrn::ica;
+use pos_reconcile_fsm::types::Timestamp;
 
 use crate::{
     icq,
-    reconcile::{reconcile, Source, Status},
+    reconcile::{reconcile, record_interchain_tx_outcome, Source, Status, TxOutcome},
     state::StorageExt,
     types::Ica,
 };
 
-#[must_use]
-fn ica_from_port_id(port_id: &str) -> Ica {
-    port_id
-        .split('.')
-        .last()
-        .and_then(Ica::from_id)
-        .expect("always: ica present in port id")
-}
-
 pub fn handle_main_ica_registered(
     deps: DepsMut<NeutronQuery>,
     address: &str,
@@ -60,44 +59,39 @@ pub fn handle_rewards_ica_registered(
     Response::default().add_submessage(msg)
 }
 
-#[cw_serde]
-struct OpenAckVersion {
-    pub version: String,
-    pub controller_connection_id: String,
-    pub host_connection_id: String,
-    pub address: String,
-    pub encoding: String,
-    pub tx_type: String,
-}
-
 pub fn handle_open_ack(
     deps: DepsMut<NeutronQuery>,
     port_id: String,
     counterparty_version: String,
 ) -> Result<Response<NeutronMsg>> {
-    let parsed_version: OpenAckVersion =
-        from_json(counterparty_version).expect("valid counterparty_version");
+    let registered = ica::parse_open_ack(&port_id, &counterparty_version)?;
 
-    let ica = ica_from_port_id(&port_id);
+    let ica = Ica::from_id(&registered.account_key).expect("always: ica present in port id");
 
     let response = match ica {
-        Ica::Main => handle_main_ica_registered(deps, &parsed_version.address),
+        Ica::Main => handle_main_ica_registered(deps, &registered.address),
 
-        Ica::Rewards => handle_rewards_ica_registered(deps, &parsed_version.address),
+        Ica::Rewards => handle_rewards_ica_registered(deps, &registered.address),
     };
 
     Ok(response)
 }
 
 pub fn handle_response(deps: DepsMut<NeutronQuery>, env: Env) -> Result<Response<NeutronMsg>> {
+    record_interchain_tx_outcome(deps.storage, TxOutcome::Response);
+
     reconcile(deps, env, Source::Continuation(Status::Success))
 }
 
 pub fn handle_error(deps: DepsMut<NeutronQuery>, env: Env) -> Result<Response<NeutronMsg>> {
+    record_interchain_tx_outcome(deps.storage, TxOutcome::Error);
+
     reconcile(deps, env, Source::Continuation(Status::Failure))
 }
 
 pub fn handle_timeout(deps: DepsMut<NeutronQuery>, env: Env) -> Result<Response<NeutronMsg>> {
+    record_interchain_tx_outcome(deps.storage, TxOutcome::Timeout);
+
     reconcile(deps, env, Source::Continuation(Status::Failure))
 }
 
@@ -106,16 +100,36 @@ pub fn handle_kv_query_result(
     env: Env,
     query_id: u64,
 ) -> Result<Response<NeutronMsg>> {
-    let Some(main_balance_query_id) = deps.storage.main_ica_balance_icq() else {
-        return Ok(Response::default());
-    };
+    let is_registered = icq::registered_queries(deps.storage)
+        .iter()
+        .any(|(id, _)| *id == query_id);
 
-    if query_id != main_balance_query_id {
+    if !is_registered {
         return Ok(Response::default());
     }
 
+    let now = Timestamp(env.block.time.seconds());
+
+    // a failed lookup must not reject the result, only the remote height goes unrecorded
+    if let Ok(QueryRegisteredQueryResponse { registered_query }) =
+        get_registered_query(deps.as_ref(), query_id)
+    {
+        deps.storage.set_icq_last_result_remote_height(
+            query_id,
+            registered_query
+                .last_submitted_result_remote_height
+                .revision_height,
+        );
+    }
+
     deps.storage
-        .set_last_main_ica_balance_icq_update(env.block.time.seconds());
+        .set_icq_last_result_local_height(query_id, env.block.height);
+
+    deps.storage.set_icq_last_result_timestamp(query_id, now);
+
+    if deps.storage.main_ica_balance_icq() == Some(query_id) {
+        deps.storage.set_last_main_ica_balance_icq_update(now);
+    }
 
     Ok(Response::default())
 }
@@ -1,20 +1,63 @@
-use amulet_core::vault::{DepositValue, Strategy as _, UnbondReadyStatus};
+use amulet_core::vault::{
+    ClaimAmount, DepositValue, MintCmd, SharesAmount, Strategy as _, StrategyCmd, UnbondEpoch,
+    UnbondReadyStatus, UnbondingLogSet,
+};
+use amulet_cw::{
+    bank::UnaccountedBalancesResponse,
+    strategy::weights::WeightsResponse,
+    vault::{
+        claims::StorageExt as _, handle_mint_cmd, redemption_rate::RedemptionRateResponse,
+        unbonding_log,
+    },
+};
+use cosmos_sdk_proto::cosmos::authz::v1beta1::{GenericAuthorization, MsgGrant, MsgRevoke};
 use cosmwasm_std::{
-    coins, from_json,
-    testing::{mock_dependencies, mock_env, MockQuerier},
-    to_json_binary, Addr, Binary, ContractResult, DepsMut, MessageInfo, Querier, QuerierResult,
-    QuerierWrapper, QueryRequest, SystemError, SystemResult,
+    coin, coins, from_json,
+    testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage},
+    to_json_binary, Addr, BankMsg, Binary, ContractResult, CosmosMsg, Deps, DepsMut, Event,
+    MessageInfo, OwnedDeps, Querier, QuerierResult, QuerierWrapper, QueryRequest, Reply, SubMsg,
+    SubMsgResponse, SubMsgResult, SystemError, SystemResult,
+};
+use neutron_sdk::{
+    bindings::{
+        msg::{IbcFee, NeutronMsg},
+        query::{NeutronQuery, QueryRegisteredQueryResponse},
+        types::{Height, RegisteredQuery},
+    },
+    interchain_queries::types::QueryType,
+    query::min_ibc_fee::MinIbcFeeResponse,
+    sudo::msg::{RequestPacket, SudoMsg as NeutronSudoMsg},
 };
-use neutron_sdk::bindings::query::NeutronQuery;
 
-use amulet_ntrn::query::{
-    IcqParams, InterchainTxsParams, QueryIcqParamsResponse, QueryInterchainTxParamsResponse,
+use amulet_ntrn::{
+    ica,
+    query::{
+        IcqParams, InterchainTxsParams, QueryIcqParamsResponse, QueryInterchainTxParamsResponse,
+    },
+    token_factory::TokenFactory,
+};
+use pos_reconcile_fsm::types::{
+    Delegated, DelegationOrder, DurationSecs, InflightDeposit, PendingDeposit, Phase, State,
+    Timestamp, ValidatorSetSlot, Weight, Weights,
 };
 
+use prost::Message as _;
 use test_utils::{check, prelude::expect};
 
 use crate::{
-    execute, instantiate, msg::Config, state::StorageExt, strategy::Strategy, InstantiateMsg,
+    execute, instantiate,
+    msg::{
+        self, Config, FeeTotals, InterchainQueries, PendingUnbondings, PerformanceFee,
+        ReconcileHistory, ReconcileState, StrategyExecuteMsg, StrategyQueryMsg, SudoMsg,
+        UnbondFees,
+    },
+    query, reconcile,
+    reply::{Kind as ReplyKind, State as ReplyState},
+    state::StorageExt,
+    strategy::{self, acknowledge_expected_unbondings, Strategy},
+    sudo,
+    types::{AvailableToClaim, Ica, TotalActualUnbonded, TotalExpectedUnbonded},
+    InstantiateMsg, QueryMsg,
 };
 
 macro_rules! info {
@@ -72,6 +115,39 @@ impl Querier for QueryWrapper {
                     })
                 }
             },
+            QueryRequest::Custom(NeutronQuery::MinIbcFee {}) => {
+                to_json_binary(&MinIbcFeeResponse {
+                    min_fee: IbcFee {
+                        recv_fee: vec![],
+                        ack_fee: coins(1_000, "untrn"),
+                        timeout_fee: coins(2_000, "untrn"),
+                    },
+                })
+                .into()
+            }
+            // every result is reported at a distinct remote height per query
+            QueryRequest::Custom(NeutronQuery::RegisteredInterchainQuery { query_id }) => {
+                to_json_binary(&QueryRegisteredQueryResponse {
+                    registered_query: RegisteredQuery {
+                        id: query_id,
+                        owner: "cosmos2contract".to_owned(),
+                        keys: vec![],
+                        query_type: QueryType::KV,
+                        transactions_filter: String::new(),
+                        connection_id: "connection-0".to_owned(),
+                        update_period: 10_000,
+                        last_submitted_result_local_height: 0,
+                        last_submitted_result_remote_height: Height {
+                            revision_number: 1,
+                            revision_height: 1_000 + query_id,
+                        },
+                        deposit: vec![],
+                        submit_timeout: 0,
+                        registered_at_height: 0,
+                    },
+                })
+                .into()
+            }
             _ => return self.0.raw_query(bin_request),
         };
 
@@ -117,6 +193,11 @@ fn instantiate_non_unique_validator_set_fails() {
                 "val3".to_owned(),
             ],
             initial_validator_weights: vec![2500, 2500, 2500, 2500],
+            restricted_shares: None,
+            hub: None,
+            instant_unbond: None,
+            unbond_fee_bps: None,
+            unbond_fee_recipient: None,
         },
     )
     .unwrap_err();
@@ -148,6 +229,11 @@ fn redelegate_to_existing_validator_fails() {
                 "val4".to_owned(),
             ],
             initial_validator_weights: vec![2500, 2500, 2500, 2500],
+            restricted_shares: None,
+            hub: None,
+            instant_unbond: None,
+            unbond_fee_bps: None,
+            unbond_fee_recipient: None,
         },
     )
     .unwrap();
@@ -173,6 +259,287 @@ fn redelegate_to_existing_validator_fails() {
     )
 }
 
+fn weights(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> WeightsResponse {
+    from_json(
+        query(
+            Deps {
+                storage: &deps.storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&QueryWrapper::default()),
+            },
+            mock_env(),
+            QueryMsg::Strategy(StrategyQueryMsg::Weights {}),
+        )
+        .unwrap(),
+    )
+    .unwrap()
+}
+
+fn set_target_weights(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, weights: Vec<u32>) {
+    execute(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        mock_env(),
+        info!("creator"),
+        crate::msg::ExecuteMsg::Strategy(StrategyExecuteMsg::SetTargetWeights { weights }),
+    )
+    .unwrap();
+}
+
+fn adopt_target_weights(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>) {
+    let targets = deps.storage.validator_target_weights().unwrap();
+
+    deps.storage.set_validator_weights(targets);
+}
+
+#[test]
+fn weights_query_round_trips() {
+    let mut deps = mock_dependencies();
+
+    instantiate(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        mock_env(),
+        info!("creator", 3_200_000, "untrn"),
+        InstantiateMsg {
+            config: config(),
+            initial_validator_set: vec![
+                "val1".to_owned(),
+                "val2".to_owned(),
+                "val3".to_owned(),
+                "val4".to_owned(),
+            ],
+            initial_validator_weights: vec![2500, 2500, 2500, 2500],
+            restricted_shares: None,
+            hub: None,
+            instant_unbond: None,
+            unbond_fee_bps: None,
+            unbond_fee_recipient: None,
+        },
+    )
+    .unwrap();
+
+    // the starting weights are delegated by until the reconcile process adopts a target
+    check(
+        weights(&deps),
+        expect![[r#"
+        (
+          weights: [
+            (
+              weight: "0.25",
+              bps: 2500,
+            ),
+            (
+              weight: "0.25",
+              bps: 2500,
+            ),
+            (
+              weight: "0.25",
+              bps: 2500,
+            ),
+            (
+              weight: "0.25",
+              bps: 2500,
+            ),
+          ],
+        )"#]],
+    );
+
+    set_target_weights(&mut deps, vec![7000, 2000, 700, 300]);
+
+    // targets are only delegated by once the reconcile process adopts them
+    assert_eq!(weights(&deps).bps(), vec![2500, 2500, 2500, 2500]);
+
+    adopt_target_weights(&mut deps);
+
+    let skewed = weights(&deps);
+
+    check(
+        &skewed,
+        expect![[r#"
+        (
+          weights: [
+            (
+              weight: "0.69999999999999999999999999999999",
+              bps: 7000,
+            ),
+            (
+              weight: "0.19999999999999999999999999999999",
+              bps: 2000,
+            ),
+            (
+              weight: "0.06999999999999999999999999999999",
+              bps: 700,
+            ),
+            (
+              weight: "0.02999999999999999999999999999999",
+              bps: 300,
+            ),
+          ],
+        )"#]],
+    );
+
+    // setting the queried weights leaves them unchanged
+    set_target_weights(&mut deps, skewed.bps());
+
+    adopt_target_weights(&mut deps);
+
+    assert_eq!(weights(&deps), skewed);
+}
+
+fn bech32_address(hrp: &str, id: u8) -> String {
+    bech32::encode::<bech32::Bech32>(bech32::Hrp::parse(hrp).unwrap(), &[id; 20]).unwrap()
+}
+
+fn resize_validator_set(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    new_validators: &[u8],
+) -> anyhow::Result<cosmwasm_std::Response<NeutronMsg>> {
+    execute(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        mock_env(),
+        info!("creator", 1_000_000, "untrn"),
+        crate::msg::ExecuteMsg::Strategy(StrategyExecuteMsg::ResizeValidatorSet {
+            new_validators: new_validators
+                .iter()
+                .map(|id| bech32_address("cosmosvaloper", *id))
+                .collect(),
+        }),
+    )
+}
+
+#[test]
+fn resize_validator_set_between_cycles() {
+    let mut deps = mock_dependencies();
+
+    instantiate(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        mock_env(),
+        info!("creator", 3_200_000, "untrn"),
+        InstantiateMsg {
+            config: config(),
+            initial_validator_set: (1..=4)
+                .map(|id| bech32_address("cosmosvaloper", id))
+                .collect(),
+            initial_validator_weights: vec![2500, 2500, 2500, 2500],
+            restricted_shares: None,
+            hub: None,
+            instant_unbond: None,
+            unbond_fee_bps: None,
+            unbond_fee_recipient: None,
+        },
+    )
+    .unwrap();
+
+    // the accounts are yet to be set up by a full reconcile
+    check(
+        resize_validator_set(&mut deps, &[1, 2, 3, 4, 5])
+            .unwrap_err()
+            .to_string(),
+        expect![[r#""reconcile in progress""#]],
+    );
+
+    deps.storage.set_reconcile_phase(Phase::StartReconcile);
+    deps.storage.set_reconcile_state(State::Idle);
+    deps.storage.set_delegated(Delegated(1_000_000));
+    deps.storage
+        .set_main_ica_address(&bech32_address("cosmos", 0));
+
+    assert!(resize_validator_set(&mut deps, &[1, 2, 1])
+        .unwrap_err()
+        .to_string()
+        .ends_with("appears more than once in the set"));
+
+    // every slot is delegated to
+    check(
+        resize_validator_set(&mut deps, &[1, 2, 4])
+            .unwrap_err()
+            .to_string(),
+        expect![[r#""removed validator slot 2 still has delegations""#]],
+    );
+
+    let response = resize_validator_set(&mut deps, &[1, 2, 3, 4, 5]).unwrap();
+
+    // the delegations ICQ is re-registered for the new set
+    assert_eq!(response.messages.len(), 1);
+
+    assert_eq!(deps.storage.validator_set_size(), 5);
+    assert_eq!(
+        deps.storage.validators()[4],
+        bech32_address("cosmosvaloper", 5)
+    );
+
+    check(
+        weights(&deps).bps(),
+        expect![[r#"
+        [
+          2500,
+          2500,
+          2500,
+          2500,
+          0,
+        ]"#]],
+    );
+
+    // once the third slot has been moved off, it can be removed
+    deps.storage.set_validator_weights(
+        Weights::new(
+            &[2500u32, 2500, 0, 5000, 0].map(|bps| Weight::checked_from_bps(bps).unwrap()),
+        )
+        .unwrap(),
+    );
+
+    resize_validator_set(&mut deps, &[5, 1, 2, 4]).unwrap();
+
+    assert_eq!(
+        deps.storage.validators(),
+        [5, 1, 2, 4].map(|id| bech32_address("cosmosvaloper", id))
+    );
+
+    check(
+        weights(&deps).bps(),
+        expect![[r#"
+        [
+          0,
+          2500,
+          2500,
+          5000,
+        ]"#]],
+    );
+
+    let initial_weights = WeightsResponse::new(
+        deps.storage
+            .validator_initial_weights()
+            .into_iter()
+            .map(Weight::into_fixed),
+    );
+
+    check(
+        initial_weights.bps(),
+        expect![[r#"
+        [
+          0,
+          3333,
+          3333,
+          3333,
+        ]"#]],
+    );
+}
+
 #[test]
 fn strategy_unbond_start_hint() {
     let mut deps = mock_dependencies();
@@ -196,23 +563,1930 @@ fn strategy_unbond_start_hint() {
                 "val4".to_owned(),
             ],
             initial_validator_weights: vec![2500, 2500, 2500, 2500],
+            restricted_shares: None,
+            hub: None,
+            instant_unbond: None,
+            unbond_fee_bps: None,
+            unbond_fee_recipient: None,
         },
     )
     .unwrap();
 
-    let minimum_unbond_interval = config().unbonding_period / config().max_unbonding_entries;
+    let minimum_unbond_interval =
+        DurationSecs(config().unbonding_period / config().max_unbonding_entries);
 
     deps.storage
-        .set_last_unbond_timestamp(env.block.time.seconds());
+        .set_last_unbond_timestamp(Timestamp(env.block.time.seconds()));
 
-    env.block.time = env.block.time.plus_seconds(minimum_unbond_interval / 2);
+    env.block.time = env.block.time.plus_seconds(minimum_unbond_interval.0 / 2);
 
     let unbond_status = Strategy::new(&deps.storage, &env).unbond(DepositValue(1_000_000_000));
 
     assert_eq!(
         unbond_status,
         UnbondReadyStatus::Later(Some(
-            deps.storage.last_unbond_timestamp().unwrap() + minimum_unbond_interval
+            deps.storage
+                .last_unbond_timestamp()
+                .unwrap()
+                .plus(minimum_unbond_interval)
+                .0
         ))
     )
 }
+
+#[test]
+fn pending_unbondings() {
+    let mut deps = mock_dependencies();
+
+    let mut env = mock_env();
+
+    instantiate(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        env.clone(),
+        info!("creator", 3_200_000, "untrn"),
+        InstantiateMsg {
+            config: config(),
+            initial_validator_set: vec![
+                "val1".to_owned(),
+                "val2".to_owned(),
+                "val3".to_owned(),
+                "val4".to_owned(),
+            ],
+            initial_validator_weights: vec![2500, 2500, 2500, 2500],
+            restricted_shares: None,
+            hub: None,
+            instant_unbond: None,
+            unbond_fee_bps: None,
+            unbond_fee_recipient: None,
+        },
+    )
+    .unwrap();
+
+    let unbonding_period = DurationSecs(config().unbonding_period);
+    let first_start = Timestamp(env.block.time.seconds());
+    let second_start = first_start.plus(DurationSecs(unbonding_period.0 / 3));
+
+    // two unbondings in flight at the same time, completing at different times
+    deps.storage.set_unbonding_expected_amount(0, 1_000);
+    deps.storage
+        .set_unbonding_local_expiry(0, first_start.plus(unbonding_period));
+    deps.storage.set_unbonding_expected_amount(1, 2_000);
+    deps.storage
+        .set_unbonding_local_expiry(1, second_start.plus(unbonding_period));
+    deps.storage.set_unbonding_issued_count(2);
+
+    let pending_unbondings = |deps: &OwnedDeps<_, _, _>, env| {
+        let bin = query(
+            Deps {
+                storage: &deps.storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&QueryWrapper::default()),
+            },
+            env,
+            QueryMsg::Strategy(StrategyQueryMsg::PendingUnbondings {}),
+        )
+        .unwrap();
+
+        from_json::<PendingUnbondings>(bin).unwrap()
+    };
+
+    check(
+        pending_unbondings(&deps, env.clone()),
+        expect![[r#"
+            (
+              batches: [
+                (
+                  id: 0,
+                  amount: "1000",
+                  expected_completion: 1573611819,
+                  claimable: false,
+                  received: false,
+                ),
+                (
+                  id: 1,
+                  amount: "2000",
+                  expected_completion: 1574216619,
+                  claimable: false,
+                  received: false,
+                ),
+              ],
+            )"#]],
+    );
+
+    // the first unbonding has completed but the assets are yet to be received
+    env.block.time = env.block.time.plus_seconds(unbonding_period.0 + 1);
+
+    check(
+        pending_unbondings(&deps, env.clone()),
+        expect![[r#"
+            (
+              batches: [
+                (
+                  id: 0,
+                  amount: "1000",
+                  expected_completion: 1573611819,
+                  claimable: true,
+                  received: false,
+                ),
+                (
+                  id: 1,
+                  amount: "2000",
+                  expected_completion: 1574216619,
+                  claimable: false,
+                  received: false,
+                ),
+              ],
+            )"#]],
+    );
+
+    let ack_amount =
+        acknowledge_expected_unbondings(&mut deps.storage, Timestamp(env.block.time.seconds()));
+
+    deps.storage
+        .set_total_expected_unbonded(TotalExpectedUnbonded(ack_amount));
+    deps.storage
+        .set_total_actual_unbonded(TotalActualUnbonded(ack_amount));
+    deps.storage
+        .set_available_to_claim(AvailableToClaim(ack_amount));
+
+    // the first unbonding has been received but is yet to be claimed
+    check(
+        pending_unbondings(&deps, env.clone()),
+        expect![[r#"
+            (
+              batches: [
+                (
+                  id: 0,
+                  amount: "1000",
+                  expected_completion: 1573611819,
+                  claimable: true,
+                  received: true,
+                ),
+                (
+                  id: 1,
+                  amount: "2000",
+                  expected_completion: 1574216619,
+                  claimable: false,
+                  received: false,
+                ),
+              ],
+            )"#]],
+    );
+
+    // the first unbonding has been entirely claimed
+    deps.storage.set_available_to_claim(AvailableToClaim(0));
+
+    check(
+        pending_unbondings(&deps, env),
+        expect![[r#"
+        (
+          batches: [
+            (
+              id: 1,
+              amount: "2000",
+              expected_completion: 1574216619,
+              claimable: false,
+              received: false,
+            ),
+          ],
+        )"#]],
+    );
+}
+
+#[test]
+fn instant_unbond_requires_zero_unbonding_period() {
+    let instantiate_err = |unbonding_period, instant_unbond| {
+        let mut deps = mock_dependencies();
+
+        instantiate(
+            DepsMut {
+                storage: &mut deps.storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&QueryWrapper::default()),
+            },
+            mock_env(),
+            info!("creator", 3_200_000, "untrn"),
+            InstantiateMsg {
+                config: Config {
+                    unbonding_period,
+                    ..config()
+                },
+                initial_validator_set: vec!["val1".to_owned()],
+                initial_validator_weights: vec![10_000],
+                restricted_shares: None,
+                hub: None,
+                instant_unbond,
+                unbond_fee_bps: None,
+                unbond_fee_recipient: None,
+            },
+        )
+        .unwrap_err()
+        .to_string()
+    };
+
+    check(
+        instantiate_err(config().unbonding_period, Some(true)),
+        expect![[r#""unbonding period is zero when instant unbond is enabled""#]],
+    );
+
+    check(
+        instantiate_err(0, None),
+        expect![[r#""instant unbond is enabled when the unbonding period is zero""#]],
+    );
+
+    check(
+        instantiate_err(0, Some(false)),
+        expect![[r#""instant unbond is enabled when the unbonding period is zero""#]],
+    );
+}
+
+#[test]
+fn strategy_unbond_epoch() {
+    let unbond_epoch = |unbonding_period, instant_unbond| {
+        let mut deps = mock_dependencies();
+
+        let env = mock_env();
+
+        instantiate(
+            DepsMut {
+                storage: &mut deps.storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&QueryWrapper::default()),
+            },
+            env.clone(),
+            info!("creator", 3_200_000, "untrn"),
+            InstantiateMsg {
+                config: Config {
+                    unbonding_period,
+                    ..config()
+                },
+                initial_validator_set: vec!["val1".to_owned()],
+                initial_validator_weights: vec![10_000],
+                restricted_shares: None,
+                hub: None,
+                instant_unbond,
+                unbond_fee_bps: None,
+                unbond_fee_recipient: None,
+            },
+        )
+        .unwrap();
+
+        let UnbondReadyStatus::Ready { epoch, .. } =
+            Strategy::new(&deps.storage, &env).unbond(DepositValue(1_000_000_000))
+        else {
+            panic!("unbond is ready");
+        };
+
+        epoch.end - epoch.start
+    };
+
+    // unbonding period plus the maturity buffer of three fee payment cooldowns
+    assert_eq!(
+        unbond_epoch(config().unbonding_period, None),
+        config().unbonding_period
+            + config().fee_payment_cooldown_blocks * 3 * config().estimated_block_interval_seconds
+    );
+
+    // claimable as soon as the unbonded assets are received
+    assert_eq!(unbond_epoch(0, Some(true)), 0);
+}
+
+#[test]
+fn instant_unbond_pending_unbondings() {
+    let mut deps = mock_dependencies();
+
+    let mut env = mock_env();
+
+    instantiate(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        env.clone(),
+        info!("creator", 3_200_000, "untrn"),
+        InstantiateMsg {
+            config: Config {
+                unbonding_period: 0,
+                ..config()
+            },
+            initial_validator_set: vec!["val1".to_owned()],
+            initial_validator_weights: vec![10_000],
+            restricted_shares: None,
+            hub: None,
+            instant_unbond: Some(true),
+            unbond_fee_bps: None,
+            unbond_fee_recipient: None,
+        },
+    )
+    .unwrap();
+
+    // the unbonding is issued & completes in the same block
+    deps.storage.set_unbonding_expected_amount(0, 1_000);
+    deps.storage.set_unbonding_local_expiry(
+        0,
+        Timestamp(env.block.time.seconds()).plus(deps.storage.unbonding_period()),
+    );
+    deps.storage.set_unbonding_issued_count(1);
+
+    let bin = query(
+        Deps {
+            storage: &deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        env.clone(),
+        QueryMsg::Strategy(StrategyQueryMsg::PendingUnbondings {}),
+    )
+    .unwrap();
+
+    check(
+        from_json::<PendingUnbondings>(bin).unwrap(),
+        expect![[r#"
+            (
+              batches: [
+                (
+                  id: 0,
+                  amount: "1000",
+                  expected_completion: 1571797419,
+                  claimable: true,
+                  received: false,
+                ),
+              ],
+            )"#]],
+    );
+
+    // the next balance icq following the transfer-in acknowledges the unbonding immediately
+    env.block.time = env.block.time.plus_seconds(1);
+
+    let acknowledged =
+        acknowledge_expected_unbondings(&mut deps.storage, Timestamp(env.block.time.seconds()));
+
+    assert_eq!(acknowledged, 1_000);
+}
+
+#[test]
+fn unbond_fee_claimable_once_batch_matures() {
+    let mut deps = mock_dependencies();
+
+    let mut env = mock_env();
+
+    instantiate(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        env.clone(),
+        info!("creator", 3_200_000, "untrn"),
+        InstantiateMsg {
+            config: config(),
+            initial_validator_set: vec!["val1".to_owned()],
+            initial_validator_weights: vec![10_000],
+            restricted_shares: None,
+            hub: None,
+            instant_unbond: None,
+            unbond_fee_bps: Some(100),
+            unbond_fee_recipient: Some("fee_recipient".to_owned()),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        Strategy::new(&deps.storage, &env).unbond_fee(DepositValue(1_000)),
+        DepositValue(10)
+    );
+
+    // a 1,000 batch retaining a fee of 10, committed with a 5% slash
+    strategy::handle_cmd::<()>(
+        &mut deps.storage,
+        StrategyCmd::CollectUnbondFee {
+            batch: 0,
+            value: DepositValue(10),
+        },
+    )
+    .unwrap();
+
+    for cmd in [
+        UnbondingLogSet::BatchTotalUnbondValue {
+            batch: 0,
+            value: DepositValue(1_000),
+        },
+        UnbondingLogSet::LastCommittedBatchId(0),
+        UnbondingLogSet::BatchClaimableAmount {
+            batch: 0,
+            amount: ClaimAmount(950),
+        },
+        UnbondingLogSet::BatchEpoch {
+            batch: 0,
+            epoch: UnbondEpoch {
+                start: env.block.time.seconds(),
+                end: env.block.time.seconds() + 10,
+            },
+        },
+    ] {
+        unbonding_log::handle_cmd(&mut deps.storage, cmd);
+    }
+
+    let unbond_fees = |deps: &OwnedDeps<_, _, _>, env| {
+        let bin = query(
+            Deps {
+                storage: &deps.storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&QueryWrapper::default()),
+            },
+            env,
+            QueryMsg::Strategy(StrategyQueryMsg::UnbondFees {}),
+        )
+        .unwrap();
+
+        from_json::<UnbondFees>(bin).unwrap()
+    };
+
+    let claim_unbond_fees = |deps: &mut OwnedDeps<_, _, _>, env| {
+        execute(
+            DepsMut {
+                storage: &mut deps.storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&QueryWrapper::default()),
+            },
+            env,
+            info!("anyone"),
+            crate::msg::ExecuteMsg::Strategy(StrategyExecuteMsg::ClaimUnbondFees {}),
+        )
+    };
+
+    check(
+        unbond_fees(&deps, env.clone()),
+        expect![[r#"
+        (
+          unbond_fee_bps: 100,
+          recipient: Some("fee_recipient"),
+          collected: "10",
+          claimable: "0",
+        )"#]],
+    );
+
+    check(
+        claim_unbond_fees(&mut deps, env.clone())
+            .unwrap_err()
+            .to_string(),
+        expect![[r#""no unbond fees to claim""#]],
+    );
+
+    // the batch matures & is received in full
+    env.block.time = env.block.time.plus_seconds(10);
+
+    deps.storage
+        .set_total_actual_unbonded(TotalActualUnbonded(950));
+    deps.storage
+        .set_total_expected_unbonded(TotalExpectedUnbonded(950));
+    deps.storage.set_available_to_claim(AvailableToClaim(950));
+
+    check(
+        unbond_fees(&deps, env.clone()),
+        expect![[r#"
+        (
+          unbond_fee_bps: 100,
+          recipient: Some("fee_recipient"),
+          collected: "10",
+          claimable: "9",
+        )"#]],
+    );
+
+    let response = claim_unbond_fees(&mut deps, env.clone()).unwrap();
+
+    assert_eq!(
+        response.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "fee_recipient".to_owned(),
+            amount: coins(9, deps.storage.ibc_deposit_asset()),
+        })
+    );
+
+    assert_eq!(deps.storage.available_to_claim().0, 941);
+
+    check(
+        unbond_fees(&deps, env.clone()),
+        expect![[r#"
+        (
+          unbond_fee_bps: 100,
+          recipient: Some("fee_recipient"),
+          collected: "0",
+          claimable: "0",
+        )"#]],
+    );
+
+    check(
+        claim_unbond_fees(&mut deps, env).unwrap_err().to_string(),
+        expect![[r#""no unbond fees to claim""#]],
+    );
+}
+
+#[test]
+fn recipient_claims_tracked() {
+    let mut storage = MockStorage::new();
+
+    let unbond = |storage: &mut MockStorage, recipient: &str, batch, value| {
+        strategy::track_claims(
+            storage,
+            &UnbondingLogSet::UnbondedValueInBatch {
+                recipient: recipient.into(),
+                batch,
+                value: DepositValue(value),
+            },
+        )
+    };
+
+    let claim = |storage: &mut MockStorage, recipient: &str, batch| {
+        strategy::track_claims(
+            storage,
+            &UnbondingLogSet::LastClaimedBatch {
+                recipient: recipient.into(),
+                batch,
+            },
+        )
+    };
+
+    // the unbonding log records the recipient's running total in the batch
+    unbond(&mut storage, "alice", 0, 100).unwrap();
+    unbond(&mut storage, "alice", 0, 150).unwrap();
+    unbond(&mut storage, "alice", 1, 200).unwrap();
+
+    assert_eq!(storage.unclaimed_batches("alice"), vec![(0, 150), (1, 200)]);
+
+    check(
+        unbond(&mut storage, "alice", 1, 199)
+            .unwrap_err()
+            .to_string(),
+        expect![[r#""unbonded value in batch 1 decreased""#]],
+    );
+
+    // claiming up to batch 0 matures & claims it, leaving batch 1
+    claim(&mut storage, "alice", 0).unwrap();
+
+    assert!(storage.is_matured(0));
+    assert_eq!(storage.unclaimed_batches("alice"), vec![(1, 200)]);
+    assert_eq!(storage.unclaimed_total("alice"), 200);
+
+    // a claim of unbondings entered before claims were tracked has nothing to claim
+    claim(&mut storage, "bob", 0).unwrap();
+
+    claim(&mut storage, "alice", 1).unwrap();
+
+    assert_eq!(storage.unclaimed_total("alice"), 0);
+}
+
+#[test]
+fn unbonding_entries_tracked_per_validator() {
+    let mut storage = MockStorage::new();
+
+    storage.push_unbonding_entry("val1", Timestamp(0), Timestamp(10));
+    storage.push_unbonding_entry("val1", Timestamp(5), Timestamp(15));
+    storage.push_unbonding_entry("val2", Timestamp(5), Timestamp(15));
+
+    assert_eq!(storage.inflight_unbonding_entries("val1", Timestamp(9)), 2);
+    assert_eq!(storage.inflight_unbonding_entries("val2", Timestamp(9)), 1);
+    assert_eq!(storage.inflight_unbonding_entries("val3", Timestamp(9)), 0);
+
+    // entries complete individually
+    assert_eq!(storage.inflight_unbonding_entries("val1", Timestamp(10)), 1);
+    assert_eq!(storage.inflight_unbonding_entries("val1", Timestamp(15)), 0);
+
+    // completed entries are dropped as new ones are recorded
+    storage.push_unbonding_entry("val1", Timestamp(12), Timestamp(22));
+
+    assert_eq!(storage.inflight_unbonding_entries("val1", Timestamp(12)), 2);
+    assert_eq!(storage.inflight_unbonding_entries("val1", Timestamp(15)), 1);
+
+    // the slots undelegated from in a tx are only taken once
+    storage.set_validator_set_size(3);
+    storage.set_undelegate_issued(ValidatorSetSlot(0));
+    storage.set_undelegate_issued(ValidatorSetSlot(2));
+
+    assert_eq!(
+        storage.take_undelegate_issued_slots(),
+        vec![ValidatorSetSlot(0), ValidatorSetSlot(2)]
+    );
+    assert_eq!(storage.take_undelegate_issued_slots(), vec![]);
+}
+
+#[test]
+fn compound_rewards_requires_idle_start_of_cycle() {
+    let mut deps = mock_dependencies();
+
+    let env = mock_env();
+
+    instantiate(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        env.clone(),
+        info!("creator", 3_200_000, "untrn"),
+        InstantiateMsg {
+            config: config(),
+            initial_validator_set: vec!["val1".to_owned()],
+            initial_validator_weights: vec![10_000],
+            restricted_shares: None,
+            hub: None,
+            instant_unbond: None,
+            unbond_fee_bps: None,
+            unbond_fee_recipient: None,
+        },
+    )
+    .unwrap();
+
+    // the accounts are yet to be set up by a full reconcile
+    let err = execute(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        env,
+        info!("anyone", 1_000, "untrn"),
+        crate::msg::ExecuteMsg::Strategy(StrategyExecuteMsg::CompoundRewards {}),
+    )
+    .unwrap_err();
+
+    check(
+        err.to_string(),
+        expect![[
+            r#""compound rewards not available for phase setup_rewards_address in state Idle""#
+        ]],
+    );
+}
+
+// longer than the minimum unbond interval of 3 days
+const BATCH_PERIOD_SECS: u64 = 4 * 24 * 60 * 60;
+
+// a pending batch of 1,000 opened now, shortly after the previous unbond
+fn init_with_pending_batch(
+    batch_period_secs: Option<u64>,
+) -> (
+    OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    cosmwasm_std::Env,
+) {
+    let mut deps = mock_dependencies();
+
+    let env = mock_env();
+
+    instantiate(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        env.clone(),
+        info!("creator", 3_200_000, "untrn"),
+        InstantiateMsg {
+            config: config(),
+            initial_validator_set: vec!["val1".to_owned()],
+            initial_validator_weights: vec![10_000],
+            restricted_shares: None,
+            hub: None,
+            instant_unbond: None,
+            unbond_fee_bps: None,
+            unbond_fee_recipient: None,
+        },
+    )
+    .unwrap();
+
+    if let Some(batch_period_secs) = batch_period_secs {
+        deps.storage
+            .set_batch_period_secs(DurationSecs(batch_period_secs));
+    }
+
+    deps.storage
+        .set_last_unbond_timestamp(Timestamp(env.block.time.seconds()));
+
+    deps.storage
+        .set_batch_opened_at(0, Timestamp(env.block.time.seconds()));
+
+    unbonding_log::handle_cmd(
+        &mut deps.storage,
+        UnbondingLogSet::BatchTotalUnbondValue {
+            batch: 0,
+            value: DepositValue(1_000),
+        },
+    );
+
+    (deps, env)
+}
+
+fn commit_batch(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    env: cosmwasm_std::Env,
+    info: MessageInfo,
+) -> anyhow::Result<cosmwasm_std::Response<NeutronMsg>> {
+    execute(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        env,
+        info,
+        crate::msg::ExecuteMsg::Strategy(StrategyExecuteMsg::CommitBatch {}),
+    )
+}
+
+#[test]
+fn commit_batch_before_batch_period_rejected() {
+    let (mut deps, mut env) = init_with_pending_batch(Some(BATCH_PERIOD_SECS));
+
+    env.block.time = env.block.time.plus_seconds(BATCH_PERIOD_SECS - 1);
+
+    let err = commit_batch(&mut deps, env, info!("anyone")).unwrap_err();
+
+    check(
+        err.to_string(),
+        expect![[r#""batch cannot be committed before 1572143019""#]],
+    );
+
+    assert_eq!(deps.storage.pending_unbond().0, 0);
+}
+
+#[test]
+fn commit_batch_without_batch_period_rejected() {
+    let (mut deps, env) = init_with_pending_batch(None);
+
+    let err = commit_batch(&mut deps, env, info!("anyone")).unwrap_err();
+
+    check(err.to_string(), expect![[r#""no batch period set""#]]);
+}
+
+#[test]
+fn commit_batch_permissionless_after_batch_period() {
+    let (mut deps, mut env) = init_with_pending_batch(Some(BATCH_PERIOD_SECS));
+
+    env.block.time = env.block.time.plus_seconds(BATCH_PERIOD_SECS);
+
+    let response = commit_batch(&mut deps, env, info!("anyone")).unwrap();
+
+    check(
+        response.attributes,
+        expect![[r#"
+            [
+              (
+                key: "kind",
+                value: "start_unbond",
+              ),
+              (
+                key: "batch_committed",
+                value: "0",
+              ),
+              (
+                key: "batch_total_claim",
+                value: "1000",
+              ),
+              (
+                key: "batch_start",
+                value: "1572143019",
+              ),
+              (
+                key: "batch_end",
+                value: "1574216619",
+              ),
+              (
+                key: "unbond_value",
+                value: "1000",
+              ),
+              (
+                key: "batch",
+                value: "0",
+              ),
+            ]"#]],
+    );
+
+    // folded into the pending unbond for the next reconcile
+    assert_eq!(deps.storage.pending_unbond().0, 1_000);
+
+    let err = commit_batch(&mut deps, mock_env(), info!("creator")).unwrap_err();
+
+    check(
+        err.to_string(),
+        expect![[r#""cannot commit an empty batch""#]],
+    );
+}
+
+#[test]
+fn commit_batch_before_minimum_unbond_interval_rejected() {
+    let (mut deps, mut env) = init_with_pending_batch(Some(BATCH_PERIOD_SECS));
+
+    let minimum_unbond_interval = deps.storage.minimum_unbond_interval();
+
+    // a batch period shorter than the minimum unbond interval does not allow committing sooner
+    deps.storage
+        .set_batch_period_secs(DurationSecs(minimum_unbond_interval.0 / 2));
+
+    env.block.time = env.block.time.plus_seconds(minimum_unbond_interval.0 - 1);
+
+    let err = commit_batch(&mut deps, env.clone(), info!("anyone")).unwrap_err();
+
+    check(
+        err.to_string(),
+        expect![[r#""batch cannot be committed before 1572056619""#]],
+    );
+
+    let err = commit_batch(&mut deps, env.clone(), info!("creator")).unwrap_err();
+
+    check(
+        err.to_string(),
+        expect![[r#""batch cannot be committed before 1572056619""#]],
+    );
+
+    assert_eq!(deps.storage.pending_unbond().0, 0);
+
+    env.block.time = env.block.time.plus_seconds(1);
+
+    commit_batch(&mut deps, env, info!("anyone")).unwrap();
+
+    assert_eq!(deps.storage.pending_unbond().0, 1_000);
+}
+
+#[test]
+fn admin_commit_batch_without_batch_period() {
+    let (mut deps, mut env) = init_with_pending_batch(None);
+
+    env.block.time = env
+        .block
+        .time
+        .plus_seconds(deps.storage.minimum_unbond_interval().0);
+
+    commit_batch(&mut deps, env, info!("creator")).unwrap();
+
+    assert_eq!(deps.storage.pending_unbond().0, 1_000);
+}
+
+#[test]
+fn strategy_unbond_start_hint_at_minimum_unbond_interval() {
+    let (deps, env) = init_with_pending_batch(Some(BATCH_PERIOD_SECS));
+
+    let unbond_status = Strategy::new(&deps.storage, &env).unbond(DepositValue(1_000));
+
+    assert_eq!(
+        unbond_status,
+        UnbondReadyStatus::Later(Some(
+            env.block.time.seconds() + deps.storage.minimum_unbond_interval().0
+        ))
+    );
+}
+
+#[test]
+fn redemption_rate_twap_of_reconcile_observations() {
+    let mut deps = mock_dependencies();
+
+    let mut env = mock_env();
+
+    instantiate(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        env.clone(),
+        info!("creator", 3_200_000, "untrn"),
+        InstantiateMsg {
+            config: config(),
+            initial_validator_set: vec!["val1".to_owned()],
+            initial_validator_weights: vec![10_000],
+            restricted_shares: None,
+            hub: None,
+            instant_unbond: None,
+            unbond_fee_bps: None,
+            unbond_fee_recipient: None,
+        },
+    )
+    .unwrap();
+
+    let redemption_rate = |deps: &OwnedDeps<_, _, _>, env, twap_secs| {
+        let bin = query(
+            Deps {
+                storage: &deps.storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&QueryWrapper::default()),
+            },
+            env,
+            QueryMsg::Strategy(StrategyQueryMsg::RedemptionRate { twap_secs }),
+        )
+        .unwrap();
+
+        from_json::<RedemptionRateResponse>(bin).unwrap()
+    };
+
+    // empty vault, the rate is undefined
+    check(
+        redemption_rate(&deps, env.clone(), Some(100)),
+        expect![[r#"
+        (
+          spot: None,
+          twap: None,
+        )"#]],
+    );
+
+    handle_mint_cmd(
+        &mut deps.storage,
+        TokenFactory::new(&env),
+        MintCmd::Mint {
+            amount: SharesAmount(1_000 * 10u128.pow(12)),
+            recipient: "depositor".to_owned().into(),
+        },
+    );
+
+    // rewards accrue between each completed reconcile
+    for delegated in [1_000, 1_010, 1_040] {
+        deps.storage.set_delegated(Delegated(delegated));
+
+        strategy::record_redemption_rate(&mut deps.storage, &env);
+
+        env.block.time = env.block.time.plus_seconds(100);
+    }
+
+    check(
+        redemption_rate(&deps, env.clone(), None),
+        expect![[r#"
+        (
+          spot: Some("1.03999999999999999999999999999999"),
+          twap: None,
+        )"#]],
+    );
+
+    check(
+        redemption_rate(&deps, env.clone(), Some(300)),
+        expect![[r#"
+        (
+          spot: Some("1.03999999999999999999999999999999"),
+          twap: Some("1.01666666666666666666666666666666"),
+        )"#]],
+    );
+}
+
+fn set_delegation_order(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    info: MessageInfo,
+    order: msg::DelegationOrder,
+) -> anyhow::Result<cosmwasm_std::Response<NeutronMsg>> {
+    execute(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        mock_env(),
+        info,
+        crate::msg::ExecuteMsg::Strategy(StrategyExecuteMsg::SetDelegationOrder { order }),
+    )
+}
+
+#[test]
+fn delegation_order_set_by_admin() {
+    let mut deps = mock_dependencies();
+
+    instantiate_for_reconcile(&mut deps, &mock_env());
+
+    assert_eq!(deps.storage.delegation_order(), DelegationOrder::SlotIndex);
+
+    set_delegation_order(
+        &mut deps,
+        info!("anyone"),
+        msg::DelegationOrder::MostUnderweightFirst,
+    )
+    .unwrap_err();
+
+    assert_eq!(deps.storage.delegation_order(), DelegationOrder::SlotIndex);
+
+    set_delegation_order(
+        &mut deps,
+        info!("creator"),
+        msg::DelegationOrder::MostUnderweightFirst,
+    )
+    .unwrap();
+
+    assert_eq!(
+        deps.storage.delegation_order(),
+        DelegationOrder::MostUnderweightFirst
+    );
+}
+
+fn set_performance_fee(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    performance_fee_bps: u32,
+    recipient: Option<&str>,
+) -> anyhow::Result<cosmwasm_std::Response<NeutronMsg>> {
+    execute(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        mock_env(),
+        info!("creator"),
+        crate::msg::ExecuteMsg::Strategy(StrategyExecuteMsg::SetPerformanceFee {
+            performance_fee_bps,
+            recipient: recipient.map(str::to_owned),
+        }),
+    )
+}
+
+#[test]
+fn performance_fee_taken_above_high_water_mark() {
+    let mut deps = mock_dependencies();
+
+    let env = mock_env();
+
+    instantiate_for_reconcile(&mut deps, &env);
+
+    check(
+        set_performance_fee(&mut deps, 1_000, None)
+            .unwrap_err()
+            .to_string(),
+        expect![[r#""performance fee recipient is set when charging a performance fee""#]],
+    );
+
+    check(
+        set_performance_fee(&mut deps, 5_001, Some("treasury"))
+            .unwrap_err()
+            .to_string(),
+        expect![[r#""performance fee bps does not exceed 5000""#]],
+    );
+
+    // unset, nothing is taken or tracked
+    check(
+        strategy::take_performance_fee(&mut deps.storage, &env).is_some(),
+        expect!["false"],
+    );
+
+    set_performance_fee(&mut deps, 1_000, Some("treasury")).unwrap();
+
+    handle_mint_cmd(
+        &mut deps.storage,
+        TokenFactory::new(&env),
+        MintCmd::Mint {
+            amount: SharesAmount(1_000 * 10u128.pow(12)),
+            recipient: "depositor".to_owned().into(),
+        },
+    );
+
+    // a gain, a loss & a recovery to the previous mark, only the gain is charged
+    let fee_msgs: Vec<_> = [1_000, 1_100, 900, 1_100]
+        .into_iter()
+        .map(|delegated| {
+            deps.storage.set_delegated(Delegated(delegated));
+
+            strategy::take_performance_fee(&mut deps.storage, &env)
+        })
+        .collect();
+
+    check(
+        fee_msgs,
+        expect![[r#"
+        [
+          None,
+          Some(custom(mint_tokens(
+            denom: "factory/cosmos2contract/share",
+            amount: "9174311926605",
+            mint_to_address: "treasury",
+          ))),
+          None,
+          None,
+        ]"#]],
+    );
+
+    let performance_fee: PerformanceFee = from_json(
+        query(
+            Deps {
+                storage: &deps.storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&QueryWrapper::default()),
+            },
+            env.clone(),
+            QueryMsg::Strategy(StrategyQueryMsg::PerformanceFee {}),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    check(
+        performance_fee,
+        expect![[r#"
+        (
+          performance_fee_bps: 1000,
+          recipient: Some("treasury"),
+          high_water_mark: Some("0.00000000000109000000000000054499"),
+        )"#]],
+    );
+}
+
+fn reconcile_history_page(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Vec<(u64, u64, u64)> {
+    let history: ReconcileHistory = from_json(
+        query(
+            Deps {
+                storage: &deps.storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&QueryWrapper::default()),
+            },
+            mock_env(),
+            QueryMsg::Strategy(StrategyQueryMsg::ReconcileHistory { start_after, limit }),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    history
+        .entries
+        .into_iter()
+        .map(|entry| (entry.id, entry.height, entry.timestamp.0))
+        .collect()
+}
+
+fn reconcile_history(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> Vec<(u64, u64)> {
+    reconcile_history_page(deps, None, None)
+        .into_iter()
+        .map(|(_, height, timestamp)| (height, timestamp))
+        .collect()
+}
+
+fn set_reconcile_history_capacity(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    capacity: u64,
+) -> anyhow::Result<cosmwasm_std::Response<NeutronMsg>> {
+    execute(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        mock_env(),
+        info!("creator"),
+        crate::msg::ExecuteMsg::Strategy(StrategyExecuteMsg::SetReconcileHistoryCapacity {
+            capacity,
+        }),
+    )
+}
+
+#[test]
+fn reconcile_history_records_successive_completions() {
+    let mut deps = mock_dependencies();
+
+    let env = mock_env();
+
+    instantiate_for_reconcile(&mut deps, &env);
+
+    assert!(reconcile_history(&deps).is_empty());
+
+    for (height, timestamp) in [(10, 100), (20, 200), (30, 300)] {
+        strategy::record_reconcile_completion(&mut deps.storage, height, Timestamp(timestamp));
+    }
+
+    check(
+        reconcile_history(&deps),
+        expect![[r#"
+        [
+          (10, 100),
+          (20, 200),
+          (30, 300),
+        ]"#]],
+    );
+}
+
+#[test]
+fn reconcile_history_evicts_oldest_past_capacity() {
+    let mut deps = mock_dependencies();
+
+    let env = mock_env();
+
+    instantiate_for_reconcile(&mut deps, &env);
+
+    check(
+        set_reconcile_history_capacity(&mut deps, 0)
+            .unwrap_err()
+            .to_string(),
+        expect![[r#""reconcile history capacity is between 1 and 256""#]],
+    );
+
+    set_reconcile_history_capacity(&mut deps, 3).unwrap();
+
+    for height in 1..=5 {
+        strategy::record_reconcile_completion(&mut deps.storage, height, Timestamp(height * 10));
+    }
+
+    check(
+        reconcile_history(&deps),
+        expect![[r#"
+        [
+          (3, 30),
+          (4, 40),
+          (5, 50),
+        ]"#]],
+    );
+
+    // shrinking the capacity excludes the oldest entries immediately
+    set_reconcile_history_capacity(&mut deps, 2).unwrap();
+
+    check(
+        reconcile_history(&deps),
+        expect![[r#"
+        [
+          (4, 40),
+          (5, 50),
+        ]"#]],
+    );
+
+    strategy::record_reconcile_completion(&mut deps.storage, 6, Timestamp(60));
+
+    check(
+        reconcile_history(&deps),
+        expect![[r#"
+        [
+          (5, 50),
+          (6, 60),
+        ]"#]],
+    );
+}
+
+#[test]
+fn reconcile_history_pagination() {
+    let mut deps = mock_dependencies();
+
+    let env = mock_env();
+
+    instantiate_for_reconcile(&mut deps, &env);
+
+    for height in 1..=5 {
+        strategy::record_reconcile_completion(&mut deps.storage, height, Timestamp(height * 10));
+    }
+
+    let page = reconcile_history_page(&deps, None, Some(2));
+
+    check(
+        &page,
+        expect![[r#"
+        [
+          (0, 1, 10),
+          (1, 2, 20),
+        ]"#]],
+    );
+
+    let page = reconcile_history_page(&deps, page.last().map(|(id, ..)| *id), Some(2));
+
+    check(
+        &page,
+        expect![[r#"
+        [
+          (2, 3, 30),
+          (3, 4, 40),
+        ]"#]],
+    );
+
+    let page = reconcile_history_page(&deps, page.last().map(|(id, ..)| *id), Some(2));
+
+    check(
+        &page,
+        expect![[r#"
+        [
+          (4, 5, 50),
+        ]"#]],
+    );
+
+    assert!(reconcile_history_page(&deps, Some(4), None).is_empty());
+}
+
+#[test]
+fn sweep_unaccounted_leaves_tracked_funds() {
+    let mut deps = mock_dependencies();
+
+    let env = mock_env();
+
+    instantiate_for_reconcile(&mut deps, &env);
+
+    let ibc_denom = deps.storage.ibc_deposit_asset();
+
+    deps.storage.set_pending_deposit(PendingDeposit(500));
+    deps.storage.set_inflight_deposit(InflightDeposit(200));
+    deps.storage.set_available_to_claim(AvailableToClaim(300));
+
+    // 50 of the deposit asset & all of the stray denom are surplus, the fee denom float is never swept
+    let querier = QueryWrapper(MockQuerier::new(&[(
+        env.contract.address.as_str(),
+        &[
+            coin(1_050, &ibc_denom),
+            coin(10_000, "untrn"),
+            coin(7, "ustray"),
+        ],
+    )]));
+
+    let unaccounted: UnaccountedBalancesResponse = from_json(
+        query(
+            Deps {
+                storage: &deps.storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&querier),
+            },
+            env.clone(),
+            QueryMsg::Strategy(StrategyQueryMsg::UnaccountedBalances {}),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        unaccounted.balances,
+        vec![coin(50, &ibc_denom), coin(7, "ustray")]
+    );
+
+    let mut sweep = |denom: &str| {
+        execute(
+            DepsMut {
+                storage: &mut deps.storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&querier),
+            },
+            env.clone(),
+            info!("creator"),
+            crate::msg::ExecuteMsg::Strategy(StrategyExecuteMsg::SweepUnaccounted {
+                denom: denom.to_owned(),
+                recipient: "treasury".to_owned(),
+            }),
+        )
+    };
+
+    let response = sweep(&ibc_denom).unwrap();
+
+    assert_eq!(
+        response.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "treasury".to_owned(),
+            amount: coins(50, &ibc_denom),
+        })]
+    );
+
+    assert_eq!(
+        response.events,
+        vec![Event::new("unaccounted_swept")
+            .add_attribute("denom", &ibc_denom)
+            .add_attribute("amount", "50")
+            .add_attribute("recipient", "treasury")]
+    );
+
+    check(
+        sweep("untrn").unwrap_err().to_string(),
+        expect![[r#""no unaccounted balance of untrn to sweep""#]],
+    );
+}
+
+/// Returns a querier where the contract holds `contract_balance` untrn, including any attached funds
+fn fee_balance_querier(env: &cosmwasm_std::Env, contract_balance: u128) -> QueryWrapper {
+    QueryWrapper(MockQuerier::new(&[(
+        env.contract.address.as_str(),
+        &coins(contract_balance, "untrn"),
+    )]))
+}
+
+fn instantiate_for_reconcile(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    env: &cosmwasm_std::Env,
+) {
+    instantiate(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        env.clone(),
+        info!("creator", 3_200_000, "untrn"),
+        InstantiateMsg {
+            config: config(),
+            initial_validator_set: vec!["val1".to_owned()],
+            initial_validator_weights: vec![10_000],
+            restricted_shares: None,
+            hub: None,
+            instant_unbond: None,
+            unbond_fee_bps: None,
+            unbond_fee_recipient: None,
+        },
+    )
+    .unwrap();
+
+    // the interchain accounts are registered, so the sequence can issue txs
+    deps.storage.set_main_ica_address("main_ica");
+    deps.storage.set_rewards_ica_address("rewards_ica");
+}
+
+fn reconcile_state(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    env: &cosmwasm_std::Env,
+) -> ReconcileState {
+    let querier = QueryWrapper::default();
+
+    from_json(
+        query(
+            Deps {
+                storage: &deps.storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&querier),
+            },
+            env.clone(),
+            QueryMsg::Strategy(StrategyQueryMsg::ReconcileState {}),
+        )
+        .unwrap(),
+    )
+    .unwrap()
+}
+
+fn trigger_reconcile(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    env: &cosmwasm_std::Env,
+    attached: u128,
+    fee_balance: u128,
+) -> anyhow::Result<cosmwasm_std::Response<neutron_sdk::bindings::msg::NeutronMsg>> {
+    let querier = fee_balance_querier(env, attached + fee_balance);
+
+    execute(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&querier),
+        },
+        env.clone(),
+        MessageInfo {
+            sender: Addr::unchecked("reconciler"),
+            funds: coins(attached, "untrn"),
+        },
+        crate::msg::ExecuteMsg::Strategy(StrategyExecuteMsg::Reconcile {
+            fee_recipient: None,
+        }),
+    )
+}
+
+fn refunds(
+    response: &cosmwasm_std::Response<neutron_sdk::bindings::msg::NeutronMsg>,
+) -> Vec<BankMsg> {
+    response
+        .messages
+        .iter()
+        .filter_map(|sub_msg| match &sub_msg.msg {
+            CosmosMsg::Bank(msg) => Some(msg.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn reconcile_with_exact_funding() {
+    let mut deps = mock_dependencies();
+
+    let env = mock_env();
+
+    instantiate_for_reconcile(&mut deps, &env);
+
+    let cost = reconcile_state(&deps, &env).cost.u128();
+
+    check(cost, expect!["21000"]);
+
+    let response = trigger_reconcile(&mut deps, &env, cost, 0).unwrap();
+
+    // nothing is refunded when the attached funds match the cost
+    check(refunds(&response), expect!["[]"]);
+
+    check(
+        reconcile_state(&deps, &env).state,
+        expect![[r#""pending""#]],
+    );
+}
+
+#[test]
+fn reconcile_over_funding_refunds_excess() {
+    let mut deps = mock_dependencies();
+
+    let env = mock_env();
+
+    instantiate_for_reconcile(&mut deps, &env);
+
+    let cost = reconcile_state(&deps, &env).cost.u128();
+
+    let response = trigger_reconcile(&mut deps, &env, cost + 500, 0).unwrap();
+
+    // skipped txs are refunded out of the retained payment
+    assert_eq!(deps.storage.reconcile_trigger_payment(), cost);
+
+    // only the excess over the cost is refunded
+    check(
+        refunds(&response),
+        expect![[r#"
+        [
+          send(
+            to_address: "reconciler",
+            amount: [
+              (
+                denom: "untrn",
+                amount: "500",
+              ),
+            ],
+          ),
+        ]"#]],
+    );
+
+    check(
+        reconcile_state(&deps, &env).state,
+        expect![[r#""pending""#]],
+    );
+}
+
+#[test]
+fn reconcile_under_funding_rejected_without_state_change() {
+    let mut deps = mock_dependencies();
+
+    let env = mock_env();
+
+    instantiate_for_reconcile(&mut deps, &env);
+
+    let before = reconcile_state(&deps, &env);
+
+    let err = trigger_reconcile(&mut deps, &env, before.cost.u128() - 1, 0).unwrap_err();
+
+    check(
+        err.to_string(),
+        expect![[
+            r#""insufficient funds for reconcile sequence: required 21000 untrn, attached 20999 untrn with a fee balance of 0 untrn""#
+        ]],
+    );
+
+    assert_eq!(reconcile_state(&deps, &env), before);
+}
+
+#[test]
+fn reconcile_shortfall_covered_by_fee_balance() {
+    let mut deps = mock_dependencies();
+
+    let env = mock_env();
+
+    instantiate_for_reconcile(&mut deps, &env);
+
+    let cost = reconcile_state(&deps, &env).cost.u128();
+
+    let response = trigger_reconcile(&mut deps, &env, cost - 1, 1).unwrap();
+
+    // nothing is refunded when the attached funds match the cost
+    check(refunds(&response), expect!["[]"]);
+
+    // skipped txs are never refunded beyond what was attached
+    assert_eq!(deps.storage.reconcile_trigger_payment(), cost - 1);
+
+    check(
+        reconcile_state(&deps, &env).state,
+        expect![[r#""pending""#]],
+    );
+}
+
+fn request_packet() -> RequestPacket {
+    RequestPacket {
+        sequence: None,
+        source_port: None,
+        source_channel: None,
+        destination_port: None,
+        destination_channel: None,
+        data: None,
+        timeout_height: None,
+        timeout_timestamp: None,
+    }
+}
+
+fn sudo_continuation(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    env: &cosmwasm_std::Env,
+    msg: NeutronSudoMsg,
+) -> cosmwasm_std::Response<NeutronMsg> {
+    sudo(
+        DepsMut {
+            storage: &mut deps.storage,
+            api: &deps.api,
+            querier: QuerierWrapper::new(&QueryWrapper::default()),
+        },
+        env.clone(),
+        SudoMsg::Neutron(msg),
+    )
+    .unwrap()
+}
+
+#[test]
+fn rewards_ica_open_ack_registers_balance_icq() {
+    let mut deps = mock_dependencies();
+
+    let env = mock_env();
+
+    instantiate_for_reconcile(&mut deps, &env);
+
+    let counterparty_version = format!(
+        r#"{{"version":"ics27-1","controller_connection_id":"connection-0","host_connection_id":"connection-1","address":"{}","encoding":"proto3","tx_type":"sdk_multi_msg"}}"#,
+        bech32_address("cosmos", 1)
+    );
+
+    let response = sudo_continuation(
+        &mut deps,
+        &env,
+        NeutronSudoMsg::OpenAck {
+            port_id: ica::port_id(env.contract.address.as_str(), Ica::Rewards.id()),
+            channel_id: "channel-1".to_owned(),
+            counterparty_channel_id: "channel-2".to_owned(),
+            counterparty_version,
+        },
+    );
+
+    assert_eq!(
+        deps.storage.rewards_ica_address(),
+        Some(bech32_address("cosmos", 1))
+    );
+
+    assert_eq!(response.messages.len(), 1);
+}
+
+fn fee_totals(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    env: &cosmwasm_std::Env,
+) -> FeeTotals {
+    from_json(
+        query(
+            Deps {
+                storage: &deps.storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&QueryWrapper::default()),
+            },
+            env.clone(),
+            QueryMsg::Strategy(StrategyQueryMsg::FeeTotals {}),
+        )
+        .unwrap(),
+    )
+    .unwrap()
+}
+
+// The fee escrowed by the interchain txs in a response, as the mock ack fee (1,000) + timeout fee (2,000) per tx
+fn escrowed_fees(response: &cosmwasm_std::Response<NeutronMsg>) -> u128 {
+    let tx_count = response
+        .messages
+        .iter()
+        .filter(|sub_msg| {
+            matches!(
+                sub_msg.msg,
+                CosmosMsg::Custom(NeutronMsg::SubmitTx { .. } | NeutronMsg::IbcTransfer { .. })
+            )
+        })
+        .count();
+
+    tx_count as u128 * 3_000
+}
+
+#[test]
+fn fee_totals_track_fees_consumed() {
+    let mut deps = mock_dependencies();
+
+    let env = mock_env();
+
+    instantiate_for_reconcile(&mut deps, &env);
+
+    let cost = reconcile_state(&deps, &env).cost.u128();
+
+    // the contract balance spent on interchain fees, refunds included
+    let mut balance_spent = 0u128;
+
+    let response = trigger_reconcile(&mut deps, &env, cost, 0).unwrap();
+
+    balance_spent += escrowed_fees(&response);
+
+    // acknowledged txs have their timeout fee refunded
+    let response = sudo_continuation(
+        &mut deps,
+        &env,
+        NeutronSudoMsg::Response {
+            request: request_packet(),
+            data: Binary::default(),
+        },
+    );
+
+    balance_spent -= 2_000;
+    balance_spent += escrowed_fees(&response);
+
+    // timed out txs have their ack fee refunded
+    sudo_continuation(
+        &mut deps,
+        &env,
+        NeutronSudoMsg::Timeout {
+            request: request_packet(),
+        },
+    );
+
+    balance_spent -= 1_000;
+
+    let totals = fee_totals(&deps, &env);
+
+    assert_eq!(totals.total_fees_paid.u128(), balance_spent);
+
+    check(
+        totals,
+        expect![[r#"
+        (
+          total_fees_paid: "3000",
+          total_txs_issued: 2,
+          total_txs_failed: 1,
+        )"#]],
+    );
+}
+
+fn icq_registered(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    kind: ReplyKind,
+    ica: Ica,
+    query_id: u64,
+) {
+    #[cosmwasm_schema::cw_serde]
+    struct MsgRegisterInterchainQueryResponse {
+        id: u64,
+    }
+
+    let data = to_json_binary(&MsgRegisterInterchainQueryResponse { id: query_id }).unwrap();
+
+    crate::reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: ReplyState {
+                kind,
+                ica,
+                index: 0,
+            }
+            .into(),
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(data),
+            }),
+        },
+    )
+    .unwrap();
+}
+
+fn interchain_queries(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    env: &cosmwasm_std::Env,
+) -> InterchainQueries {
+    from_json(
+        query(
+            Deps {
+                storage: &deps.storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&QueryWrapper::default()),
+            },
+            env.clone(),
+            QueryMsg::Strategy(StrategyQueryMsg::InterchainQueries {}),
+        )
+        .unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn interchain_queries_record_last_result() {
+    let mut deps = mock_dependencies();
+
+    let mut env = mock_env();
+
+    instantiate_for_reconcile(&mut deps, &env);
+
+    icq_registered(
+        &mut deps,
+        ReplyKind::RegisterCurrentSetDelegationsIcq,
+        Ica::Main,
+        1,
+    );
+    icq_registered(&mut deps, ReplyKind::RegisterBalanceIcq, Ica::Main, 2);
+
+    // queries registered afterwards keep the new interval, existing ones their original period
+    deps.storage.set_icq_update_interval(500);
+
+    icq_registered(&mut deps, ReplyKind::RegisterBalanceIcq, Ica::Rewards, 3);
+
+    sudo_continuation(
+        &mut deps,
+        &env,
+        NeutronSudoMsg::KVQueryResult { query_id: 2 },
+    );
+
+    // results of queries the vault doesn't know about are ignored
+    sudo_continuation(
+        &mut deps,
+        &env,
+        NeutronSudoMsg::KVQueryResult { query_id: 9 },
+    );
+
+    env.block.height += 100;
+    env.block.time = env.block.time.plus_seconds(600);
+
+    sudo_continuation(
+        &mut deps,
+        &env,
+        NeutronSudoMsg::KVQueryResult { query_id: 1 },
+    );
+
+    env.block.time = env.block.time.plus_seconds(60);
+
+    check(
+        interchain_queries(&deps, &env),
+        expect![[r#"
+        (
+          queries: [
+            (
+              id: 1,
+              kind: delegations(
+                index: 0,
+              ),
+              update_period: Some(10000),
+              last_result_remote_height: Some(1001),
+              last_result_local_height: Some(12445),
+              last_result_timestamp: Some(1571798019),
+              last_result_age: Some(60),
+            ),
+            (
+              id: 2,
+              kind: undelegated_balance,
+              update_period: Some(10000),
+              last_result_remote_height: Some(1002),
+              last_result_local_height: Some(12345),
+              last_result_timestamp: Some(1571797419),
+              last_result_age: Some(660),
+            ),
+            (
+              id: 3,
+              kind: rewards_balance,
+              update_period: Some(500),
+              last_result_remote_height: None,
+              last_result_local_height: None,
+              last_result_timestamp: None,
+              last_result_age: None,
+            ),
+          ],
+        )"#]],
+    );
+
+    check(
+        deps.storage.last_main_ica_balance_icq_update(),
+        expect!["Some(1571797419)"],
+    );
+}
+
+#[test]
+fn authz_grant_and_revoke_carry_granter_grantee_and_type_url() {
+    const MSG_DELEGATE_TYPE_URL: &str = "/cosmos.staking.v1beta1.MsgDelegate";
+
+    let grant = reconcile::grant_authz(
+        "rewards_ica".to_owned(),
+        "main_ica".to_owned(),
+        MSG_DELEGATE_TYPE_URL.to_owned(),
+    );
+
+    assert_eq!(grant.type_url, "/cosmos.authz.v1beta1.MsgGrant");
+
+    let grant = MsgGrant::decode(grant.value.as_slice()).unwrap();
+
+    assert_eq!(grant.granter, "rewards_ica");
+    assert_eq!(grant.grantee, "main_ica");
+
+    let authorization = grant.grant.unwrap().authorization.unwrap();
+
+    assert_eq!(
+        authorization.type_url,
+        "/cosmos.authz.v1beta1.GenericAuthorization"
+    );
+
+    assert_eq!(
+        GenericAuthorization::decode(authorization.value.as_slice())
+            .unwrap()
+            .msg,
+        MSG_DELEGATE_TYPE_URL
+    );
+
+    let revoke = reconcile::revoke_authz(
+        "rewards_ica".to_owned(),
+        "main_ica".to_owned(),
+        MSG_DELEGATE_TYPE_URL.to_owned(),
+    );
+
+    assert_eq!(revoke.type_url, "/cosmos.authz.v1beta1.MsgRevoke");
+
+    assert_eq!(
+        MsgRevoke::decode(revoke.value.as_slice()).unwrap(),
+        MsgRevoke {
+            granter: "rewards_ica".to_owned(),
+            grantee: "main_ica".to_owned(),
+            msg_type_url: MSG_DELEGATE_TYPE_URL.to_owned(),
+        }
+    );
+}
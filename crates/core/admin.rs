@@ -52,7 +52,7 @@ pub trait Admin {
 
 pub struct AdminImpl<'a>(&'a dyn Repository);
 
-pub fn admin(repository: &dyn Repository) -> AdminImpl {
+pub fn admin(repository: &dyn Repository) -> AdminImpl<'_> {
     AdminImpl(repository)
 }
 
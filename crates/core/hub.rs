@@ -1,37 +1,60 @@
 pub mod positions;
 pub mod rates;
 
+use std::cell::Cell;
+
+use num::FixedU256;
+
 use crate::{
     admin::AdminRole,
     cmds,
     hub::positions::{deposit_collateral, redeem_reserves},
     mint::{MintCmd, Synthetic, SyntheticAmount},
     vault::{
-        DepositAmount, DepositValue, RedemptionRate, SharesAmount, TotalDepositsValue,
-        TotalSharesIssued,
+        DepositAmount, DepositValue, Now, RedemptionRate, SharesAmount, TotalDepositsValue,
+        TotalSharesIssued, SHARES_DECIMAL_PLACES,
     },
-    Asset, Decimals, Identifier, Recipient, Sender, UnauthorizedError,
+    Asset, Decimals, Identifier, Rate, Recipient, Sender, UnauthorizedError,
 };
 
 use self::positions::{
-    add_vault_reserves, advance, claim_amo_shares, claim_treasury_shares, convert_credit, repay,
-    self_liquidate, update_cdp, update_vault, withdraw_collateral, Advance, ConvertCreditError,
-    LossError, NothingToClaimError, RedeemReservesError, SelfLiquidateError, SelfLiquidation,
-    SharesPool, Vault as VaultPosition, WithdrawCollateralError,
+    add_vault_reserves, advance, claim_amo_shares, claim_treasury_shares, convert_credit,
+    loss_within_tolerance, ltv_collateral, max_withdrawable_collateral, repay, return_amo_proceeds,
+    self_liquidate, update_cdp, update_vault, withdraw_collateral, AcknowledgeLossError, Advance,
+    ClaimTreasurySharesError, ConvertCreditError, FeeAmount, LossError, NothingToClaimError,
+    RedeemReservesError, SelfLiquidateError, SelfLiquidation, SharesPool, SweepDustError,
+    Vault as VaultPosition, WithdrawCollateralError, YieldFees,
 };
 
 pub use self::{
-    positions::{AmoShares, Cdp, Collateral, Credit, Debt, SumPaymentRatio, TreasuryShares},
-    rates::{AdvanceFee, AmoAllocation, CollateralYieldFee, MaxLtv, ReserveYieldFee},
+    positions::{
+        ltv, max_borrowable, AmoShares, Cdp, Collateral, CollateralFactor, Credit, Debt,
+        SumPaymentRatio, TreasuryShares,
+    },
+    rates::{
+        AdvanceFee, AmoAllocation, CollateralYieldFee, LossTolerance, MaxLtv, RedeemFee,
+        RedeemFeeSchedule, RedeemFeeTier, ReserveYieldFee,
+    },
 };
 
 pub type VaultId = Identifier;
 pub type Proxy = Identifier;
 pub type Treasury = Identifier;
+pub type Guardian = Identifier;
 pub type Account = Identifier;
 pub type Oracle = Identifier;
 pub type Amo = Identifier;
+pub type Operator = Identifier;
 pub type VaultShares = Asset;
+pub type OracleEpoch = u64;
+pub type PositionSeq = u64;
+pub type CacheWindowSecs = u64;
+
+/// The major version of the vault interface the hub is compatible with
+pub const SUPPORTED_VAULT_INTERFACE_MAJOR_VERSION: u64 = 1;
+
+/// The minimum deposit amount applied to a vault unless configured otherwise
+pub const DEFAULT_MIN_DEPOSIT: u128 = 1;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -56,18 +79,39 @@ pub enum Error {
     #[error(transparent)]
     Claim(#[from] NothingToClaimError),
 
+    #[error(transparent)]
+    ClaimTreasury(#[from] ClaimTreasurySharesError),
+
+    #[error(transparent)]
+    AcknowledgeLoss(#[from] AcknowledgeLossError),
+
+    #[error(transparent)]
+    SweepDust(#[from] SweepDustError),
+
     #[error("invalid deposit asset")]
     InvalidDepositAsset,
 
+    #[error("invalid shares asset")]
+    InvalidSharesAsset,
+
     #[error("cannot deposit zero")]
     CannotDepositZero,
 
+    #[error("deposit below minimum")]
+    DepositBelowMinimum,
+
     #[error("vault already registered")]
     VaultAlreadyRegistered,
 
     #[error("vault not registered")]
     VaultNotRegistered,
 
+    #[error("vault not empty")]
+    VaultNotEmpty,
+
+    #[error("total account collateral not tracked")]
+    TotalCollateralUntracked,
+
     #[error("vault not found")]
     VaultNotFound,
 
@@ -77,15 +121,27 @@ pub enum Error {
     #[error("decimals mismatch")]
     DecimalsMismatch,
 
+    #[error("shares decimals mismatch")]
+    SharesDecimalsMismatch,
+
+    #[error("unsupported vault interface version")]
+    UnsupportedVaultInterface,
+
     #[error("invalid rate")]
     InvalidRate,
 
+    #[error("invalid redeem fee schedule")]
+    InvalidRedeemFeeSchedule,
+
     #[error("not enough collateral")]
     NotEnoughCollateral,
 
     #[error("max ltv exceeded")]
     MaxLtvExceeded,
 
+    #[error("collateral price stale")]
+    CollateralPriceStale,
+
     #[error("cannot advance zero")]
     CannotAdvanceZero,
 
@@ -104,6 +160,9 @@ pub enum Error {
     #[error("cannot convert zero")]
     CannotConvertZero,
 
+    #[error("nothing to convert")]
+    NothingToConvert,
+
     #[error("cannot mint zero")]
     CannotMintZero,
 
@@ -119,11 +178,32 @@ pub enum Error {
     #[error("deposits disabled")]
     DepositsDisabled,
 
+    #[error("protocol paused")]
+    ProtocolPaused,
+
     #[error("advance disabled")]
     AdvanceDisabled,
 
     #[error("no proxy set")]
     NoProxySet,
+
+    #[error("position collateral below minimum")]
+    BelowMinCollateral,
+
+    #[error("position debt below minimum")]
+    BelowMinDebt,
+
+    #[error("account debt cap exceeded")]
+    AccountDebtCapExceeded,
+
+    #[error("reserve below floor")]
+    ReserveBelowFloor,
+
+    #[error("operator advance limit exceeded")]
+    OperatorAdvanceLimitExceeded,
+
+    #[error("operator not allowed to withdraw")]
+    OperatorWithdrawNotAllowed,
 }
 
 pub trait SyntheticMint {
@@ -150,6 +230,14 @@ pub enum VaultCmd {
         synthetic: Synthetic,
     },
 
+    /// Remove the vault's registration & configuration, only issued once the vault holds no collateral, debt,
+    /// reserves or unclaimed shares.
+    /// The advance fee oracle epoch & any cached oracle fees are retained, the fees being stale under the epoch
+    /// bumped beforehand, prunable once the vault is re-registered
+    Deregister {
+        vault: VaultId,
+    },
+
     SetDepositsEnabled {
         vault: VaultId,
         enabled: bool,
@@ -190,6 +278,33 @@ pub enum VaultCmd {
         oracle: Oracle,
     },
 
+    /// Any cached oracle fees recorded under a different epoch are considered stale
+    SetAdvanceFeeOracleEpoch {
+        vault: VaultId,
+        epoch: OracleEpoch,
+    },
+
+    SetOracleFeeCacheSecs {
+        vault: VaultId,
+        secs: Option<CacheWindowSecs>,
+    },
+
+    SetCollateralPriceOracle {
+        vault: VaultId,
+        oracle: Option<CollateralPriceOracle>,
+    },
+
+    SetCachedOracleFee {
+        vault: VaultId,
+        recipient: Recipient,
+        cached: CachedAdvanceFee,
+    },
+
+    RemoveCachedOracleFee {
+        vault: VaultId,
+        recipient: Recipient,
+    },
+
     SetAmo {
         vault: VaultId,
         amo: Amo,
@@ -220,6 +335,52 @@ pub enum VaultCmd {
         proxy: Option<Proxy>,
     },
 
+    SetMinCollateral {
+        vault: VaultId,
+        min: Option<Collateral>,
+    },
+
+    SetMinDebt {
+        vault: VaultId,
+        min: Option<Debt>,
+    },
+
+    SetAccountDebtCap {
+        vault: VaultId,
+        cap: Option<Debt>,
+    },
+
+    SetReserveFloor {
+        vault: VaultId,
+        floor: Option<Collateral>,
+    },
+
+    SetInterfaceVersion {
+        vault: VaultId,
+        version: String,
+        features: Vec<String>,
+    },
+
+    SetRedeemFeeSchedule {
+        vault: VaultId,
+        schedule: Option<RedeemFeeSchedule>,
+    },
+
+    SetLossTolerance {
+        vault: VaultId,
+        tolerance: LossTolerance,
+    },
+
+    SetLossSocialisationEnabled {
+        vault: VaultId,
+        enabled: bool,
+    },
+
+    SetMinDeposit {
+        vault: VaultId,
+        amount: u128,
+    },
+
     /// Deposit an `amount` of deposit `asset`s into the vault
     /// NOTE: The downstream libary user MUST provide the *same* `recipient` & `reason`
     /// in the associated `Hub::vault_deposit_callback` call.
@@ -244,6 +405,12 @@ pub trait VaultRegistry {
     /// Returns the decimals used in the underlying asset value (collateral), if the vault exists at all
     fn underlying_asset_decimals(&self, vault: &VaultId) -> Option<Decimals>;
 
+    /// Returns the decimals of the shares issued by the vault, if the vault exists at all
+    fn shares_decimals(&self, vault: &VaultId) -> Option<Decimals>;
+
+    /// Returns the interface reported by the vault, if the vault exists and supports the interface query
+    fn interface(&self, vault: &VaultId) -> Option<VaultInterface>;
+
     /// Returns true if the vault has been registered;
     fn is_registered(&self, vault: &VaultId) -> bool;
 
@@ -279,6 +446,36 @@ pub trait VaultRegistry {
     /// Panics if the vault is not registered.
     fn advance_fee_oracle(&self, vault: &VaultId) -> Option<Oracle>;
 
+    /// Returns the number of times the advance fee oracle has been set, zero if never
+    /// Panics if the vault is not registered.
+    fn advance_fee_oracle_epoch(&self, vault: &VaultId) -> OracleEpoch;
+
+    /// Returns Some(secs) if an oracle fee cache window has been set
+    /// Panics if the vault is not registered.
+    fn oracle_fee_cache_secs(&self, vault: &VaultId) -> Option<CacheWindowSecs>;
+
+    /// Returns Some(cached) if an oracle fee has been cached for the recipient
+    /// Panics if the vault is not registered.
+    fn cached_oracle_fee(&self, vault: &VaultId, recipient: &Recipient)
+        -> Option<CachedAdvanceFee>;
+
+    /// Returns up to `limit` recipients with a cached oracle fee in a stable order, starting after `start_after`
+    /// Panics if the vault is not registered.
+    fn cached_oracle_fee_recipients(
+        &self,
+        vault: &VaultId,
+        start_after: Option<&Recipient>,
+        limit: usize,
+    ) -> Vec<Recipient>;
+
+    /// Returns Some(oracle) if a collateral price oracle has been set
+    /// Panics if the vault is not registered.
+    fn collateral_price_oracle(&self, vault: &VaultId) -> Option<CollateralPriceOracle>;
+
+    /// Returns the latest collateral price reported by the `oracle`
+    /// Panics if the oracle cannot be queried
+    fn collateral_price(&self, oracle: &Oracle) -> CollateralPrice;
+
     /// Returns Some(amo) if one has been set
     /// Panics if the vault is not registered.
     fn amo(&self, vault: &VaultId) -> Option<Amo>;
@@ -303,10 +500,52 @@ pub trait VaultRegistry {
     /// Panics if the vault is not registered.
     fn mint_proxy(&self, vault: &VaultId) -> Option<Proxy>;
 
+    /// Returns Some(min) if a minimum non-zero position collateral has been set
+    /// Panics if the vault is not registered.
+    fn min_collateral(&self, vault: &VaultId) -> Option<Collateral>;
+
+    /// Returns Some(min) if a minimum non-zero position debt has been set
+    /// Panics if the vault is not registered.
+    fn min_debt(&self, vault: &VaultId) -> Option<Debt>;
+
+    /// Returns Some(cap) if a maximum debt for any single position has been set
+    /// Panics if the vault is not registered.
+    fn account_debt_cap(&self, vault: &VaultId) -> Option<Debt>;
+
+    /// Returns Some(floor) if a non-zero reserve floor has been set
+    /// Panics if the vault is not registered.
+    fn reserve_floor(&self, vault: &VaultId) -> Option<Collateral>;
+
+    /// Returns Some(schedule) if a redeem fee schedule has been set
+    /// Panics if the vault is not registered.
+    fn redeem_fee_schedule(&self, vault: &VaultId) -> Option<RedeemFeeSchedule>;
+
+    /// Returns Some(tolerance) if a loss tolerance has been set
+    /// Panics if the vault is not registered.
+    fn loss_tolerance(&self, vault: &VaultId) -> Option<LossTolerance>;
+
+    /// Returns true if losses within the loss tolerance are borne by the collateral holders rather than blocking the vault
+    /// Panics if the vault is not registered.
+    fn loss_socialisation_enabled(&self, vault: &VaultId) -> bool;
+
+    /// Returns Some(min) if a minimum deposit amount has been set
+    /// Panics if the vault is not registered.
+    fn min_deposit(&self, vault: &VaultId) -> Option<u128>;
+
     /// Returns the asset that the vault can accept for deposits
     /// Panics if the vault cannot be found
     fn deposit_asset(&self, vault: &VaultId) -> Asset;
 
+    /// Returns the `amount` of a secondary `asset` converted into the vault's deposit asset at its current rate,
+    /// or None if the vault does not accept the `asset` for deposits
+    /// Panics if the vault cannot be found
+    fn convert_secondary_deposit(
+        &self,
+        vault: &VaultId,
+        asset: &Asset,
+        amount: DepositAmount,
+    ) -> Option<DepositAmount>;
+
     /// Returns the shares asset that a vault issues
     /// Panics if the vault cannot be found
     fn shares_asset(&self, vault: &VaultId) -> Asset;
@@ -322,6 +561,11 @@ pub trait VaultRegistry {
     /// Returns the total value of all vault deposits in terms of the underlying asset (collateral)
     /// Panics if the vault cannot be found
     fn total_deposits_value(&self, vault: &VaultId) -> TotalDepositsValue;
+
+    /// Returns the vault strategy's redemption rate relative to the mark-to-market value of its deposits, if it reports one.
+    /// Used to value collateral for LTV purposes, see [`crate::vault::Strategy::redemption_rate`]
+    /// Panics if the vault cannot be found
+    fn strategy_redemption_rate(&self, vault: &VaultId) -> Option<FixedU256>;
 }
 
 #[derive(Debug, Clone)]
@@ -331,6 +575,15 @@ pub enum BalanceSheetCmd {
         treasury: Treasury,
     },
 
+    SetGuardian {
+        guardian: Guardian,
+    },
+
+    /// Set the global pause, halting all user entry points across every vault while `true`
+    SetGlobalPause {
+        paused: bool,
+    },
+
     SetCollateralShares {
         vault: VaultId,
         shares: SharesAmount,
@@ -361,9 +614,32 @@ pub enum BalanceSheetCmd {
         shares: AmoShares,
     },
 
+    SetAmoSharesReturned {
+        vault: VaultId,
+        shares: SharesAmount,
+    },
+
+    SetAmoDepositsReturned {
+        vault: VaultId,
+        amount: DepositAmount,
+    },
+
+    /// `payment` is the debt payment accrued across the vault's positions since the previous ratio,
+    /// settled in each position on its next update
     SetOverallSumPaymentRatio {
         vault: VaultId,
         spr: SumPaymentRatio,
+        payment: Debt,
+    },
+
+    SetCollateralFactor {
+        vault: VaultId,
+        factor: CollateralFactor,
+    },
+
+    /// Start tracking the sum of all account collateral recorded against a newly registered vault
+    TrackTotalCollateral {
+        vault: VaultId,
     },
 
     SetAccountCollateral {
@@ -384,10 +660,32 @@ pub enum BalanceSheetCmd {
         credit: Credit,
     },
 
+    /// `payment` is the debt payment accrued by the position since its previous ratio, settled by this update
     SetAccountSumPaymentRatio {
         vault: VaultId,
         account: Account,
         spr: SumPaymentRatio,
+        payment: Debt,
+    },
+
+    SetAccountCollateralFactor {
+        vault: VaultId,
+        account: Account,
+        factor: CollateralFactor,
+    },
+
+    /// Set the sequence number of the latest mutation of an account's position
+    SetAccountPositionSeq {
+        vault: VaultId,
+        account: Account,
+        seq: PositionSeq,
+    },
+
+    /// Set the operator managing an account's position, `None` revokes any existing operator
+    SetPositionOperator {
+        vault: VaultId,
+        account: Account,
+        operator: Option<PositionOperator>,
     },
 
     /// Send an `amount` of vault `shares` to a `recipient`
@@ -398,9 +696,30 @@ pub enum BalanceSheetCmd {
     },
 }
 
+/// Events recorded alongside balance sheet updates, without any effect on state
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub enum BalanceSheetEvent {
+    /// Yield was distributed across the vault's balances, advancing its sum payment ratio.
+    /// `collateral_fee` + `reserve_fee` is the increase in treasury shares up to `treasury_shares`.
+    YieldFeeAccrued {
+        vault: VaultId,
+        /// The vault's treasury shares after the fees accrued
+        treasury_shares: TreasuryShares,
+        /// The treasury shares taken from the collateral pool's yield
+        collateral_fee: TreasuryShares,
+        /// The treasury shares taken from the reserve pool's yield
+        reserve_fee: TreasuryShares,
+    },
+}
+
 pub trait BalanceSheet {
     fn treasury(&self) -> Option<Treasury>;
 
+    fn guardian(&self) -> Option<Guardian>;
+
+    fn global_paused(&self) -> bool;
+
     fn collateral_shares(&self, vault: &VaultId) -> Option<SharesAmount>;
 
     fn collateral_balance(&self, vault: &VaultId) -> Option<Collateral>;
@@ -413,8 +732,24 @@ pub trait BalanceSheet {
 
     fn amo_shares(&self, vault: &VaultId) -> Option<AmoShares>;
 
+    /// Cumulative vault shares returned by the AMO as proceeds
+    fn amo_shares_returned(&self, vault: &VaultId) -> Option<SharesAmount>;
+
+    /// Cumulative deposit asset returned by the AMO as proceeds
+    fn amo_deposits_returned(&self, vault: &VaultId) -> Option<DepositAmount>;
+
     fn overall_sum_payment_ratio(&self, vault: &VaultId) -> Option<SumPaymentRatio>;
 
+    fn collateral_factor(&self, vault: &VaultId) -> Option<CollateralFactor>;
+
+    /// The vault's outstanding debt: the sum of all account debts recorded against the vault,
+    /// less the debt payment pending in those positions
+    fn total_debt(&self, vault: &VaultId) -> Option<Debt>;
+
+    /// The sum of all account collateral recorded against the vault,
+    /// `None` if the vault was registered before the total was tracked
+    fn total_collateral(&self, vault: &VaultId) -> Option<Collateral>;
+
     fn account_collateral(&self, vault: &VaultId, account: &Account) -> Option<Collateral>;
 
     fn account_debt(&self, vault: &VaultId, account: &Account) -> Option<Debt>;
@@ -426,6 +761,16 @@ pub trait BalanceSheet {
         vault: &VaultId,
         account: &Account,
     ) -> Option<SumPaymentRatio>;
+
+    fn account_collateral_factor(
+        &self,
+        vault: &VaultId,
+        account: &Account,
+    ) -> Option<CollateralFactor>;
+
+    fn account_position_seq(&self, vault: &VaultId, account: &Account) -> Option<PositionSeq>;
+
+    fn position_operator(&self, vault: &VaultId, account: &Account) -> Option<PositionOperator>;
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -435,10 +780,76 @@ pub enum VaultDepositReason {
     Deposit = 1,
     RepayUnderlying = 2,
     Mint = 3,
+    AmoProceeds = 4,
 }
 
 pub trait AdvanceFeeOracle {
+    /// Returns current instant in terms of the oracle fee cache window
+    fn now(&self) -> Now;
+
     fn advance_fee(&self, oracle: &Oracle, recipient: &Recipient) -> Option<AdvanceFee>;
+
+    /// Batched variant of `advance_fee`, the returned fees are in the same order as the `recipients`
+    fn fees_for(&self, oracle: &Oracle, recipients: Vec<Recipient>) -> Vec<Option<AdvanceFee>>;
+}
+
+/// An oracle response recorded for a recipient
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct CachedAdvanceFee {
+    pub fee: Option<AdvanceFee>,
+    pub oracle_epoch: OracleEpoch,
+    pub timestamp: Now,
+}
+
+impl CachedAdvanceFee {
+    fn is_fresh(&self, epoch: OracleEpoch, window: CacheWindowSecs, now: Now) -> bool {
+        self.oracle_epoch == epoch && now < self.timestamp.saturating_add(window)
+    }
+}
+
+/// The interface version & optional features reported by a vault
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VaultInterface {
+    pub version: String,
+    pub features: Vec<String>,
+}
+
+/// An oracle pricing a vault's collateral for LTV purposes, applied on top of the strategy redemption rate
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct CollateralPriceOracle {
+    pub oracle: Oracle,
+    /// The age in seconds beyond which a price is stale, blocking advances & withdrawals against debt
+    pub max_age_secs: u64,
+}
+
+/// A price reported by a collateral price oracle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollateralPrice {
+    /// The value of the collateral relative to its redemption value, e.g. 0.95 at a 5% discount
+    pub price: FixedU256,
+    pub updated_at: Now,
+}
+
+impl CollateralPrice {
+    fn is_stale(&self, max_age_secs: u64, now: Now) -> bool {
+        now > self.updated_at.saturating_add(max_age_secs)
+    }
+}
+
+/// An operator granted management of an account's position.
+/// Advances & withdrawals made by the operator are always paid out to the position owner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct PositionOperator {
+    pub operator: Operator,
+    /// The cumulative amount the operator may advance under the grant, unlimited if `None`
+    pub max_advance: Option<Debt>,
+    /// Whether the operator may withdraw collateral
+    pub allow_withdraw: bool,
+    /// The cumulative amount advanced by the operator under the grant
+    pub advanced: Debt,
 }
 
 #[derive(Debug, Clone)]
@@ -447,6 +858,7 @@ pub enum Cmd {
     Mint(MintCmd),
     Vault(VaultCmd),
     BalanceSheet(BalanceSheetCmd),
+    Event(BalanceSheetEvent),
 }
 
 // extend a Vec<Cmd> type to add a builder method to chain adding different commands
@@ -468,6 +880,77 @@ pub struct PositionResponse {
     pub cdp: Cdp,
 }
 
+/// The outcome of depositing collateral into a position
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct DepositOutcome {
+    /// The resulting position
+    pub cdp: Cdp,
+    /// The amount of collateral credited to the position
+    pub collateral: Collateral,
+}
+
+/// The outcome of advancing against a position
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct AdvanceOutcome {
+    /// The resulting position
+    pub cdp: Cdp,
+    /// The amount minted to the recipient
+    pub amount: Debt,
+    /// The amount minted to the advance fee recipient, if any
+    pub fee: Option<FeeAmount>,
+}
+
+/// Where the advance fee applicable to an account was resolved from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub enum AdvanceFeeSource {
+    /// No advance fee recipient is set, so no fee is charged
+    None,
+    /// The vault's fixed advance fee
+    Fixed,
+    /// The vault's advance fee oracle (or its cached response)
+    Oracle,
+}
+
+/// The advance fee an account would be charged when advancing
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct EffectiveAdvanceFee {
+    pub source: AdvanceFeeSource,
+    pub fee: AdvanceFee,
+    /// The fee charged on the new debt created by the advance
+    pub amount: FeeAmount,
+}
+
+/// The outcome of withdrawing collateral from a position
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct WithdrawOutcome {
+    /// The resulting position
+    pub cdp: Cdp,
+    /// The amount of vault shares redeemed
+    pub shares: SharesAmount,
+}
+
+/// A position's current loan-to-value ratio
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct PositionLtv {
+    /// The current LTV, `None` if the position has debt against no collateral
+    pub ltv: Option<Rate>,
+    /// The vault's configured max LTV
+    pub max_ltv: MaxLtv,
+}
+
+// The commands carrying out an operation along with its outcome.
+// Executing an operation issues the `cmds`, simulating it discards them.
+struct Plan<T> {
+    cmds: Vec<Cmd>,
+    outcome: T,
+}
+
 pub trait ConfigureHub {
     fn register_vault(
         &self,
@@ -478,6 +961,8 @@ pub trait ConfigureHub {
 
     fn set_treasury(&self, role: AdminRole, treasury: Treasury) -> Result<Vec<Cmd>, Error>;
 
+    fn set_guardian(&self, role: AdminRole, guardian: Guardian) -> Result<Vec<Cmd>, Error>;
+
     fn set_deposit_enabled(
         &self,
         role: AdminRole,
@@ -529,6 +1014,21 @@ pub trait ConfigureHub {
         oracle: Oracle,
     ) -> Result<Vec<Cmd>, Error>;
 
+    fn set_oracle_fee_cache_secs(
+        &self,
+        role: AdminRole,
+        vault: VaultId,
+        secs: Option<CacheWindowSecs>,
+    ) -> Result<Vec<Cmd>, Error>;
+
+    /// Set the oracle pricing the vault's collateral for LTV purposes, `None` to value it at the share price
+    fn set_collateral_price_oracle(
+        &self,
+        role: AdminRole,
+        vault: VaultId,
+        oracle: Option<CollateralPriceOracle>,
+    ) -> Result<Vec<Cmd>, Error>;
+
     fn set_amo(&self, role: AdminRole, vault: VaultId, amo: Amo) -> Result<Vec<Cmd>, Error>;
 
     fn set_amo_allocation(
@@ -552,60 +1052,143 @@ pub trait ConfigureHub {
     fn remove_redeem_proxy(&self, role: AdminRole, vault: VaultId) -> Result<Vec<Cmd>, Error>;
 
     fn remove_mint_proxy(&self, role: AdminRole, vault: VaultId) -> Result<Vec<Cmd>, Error>;
-}
-
-pub trait Hub {
-    fn evaluate(&self, vault: VaultId, sender: Sender) -> Result<PositionResponse, Error>;
 
-    fn deposit(
+    fn set_min_collateral(
         &self,
+        role: AdminRole,
         vault: VaultId,
-        sender: Sender,
-        deposit_asset: Asset,
-        deposit_amount: DepositAmount,
-        recipient: Recipient,
+        min: Option<Collateral>,
     ) -> Result<Vec<Cmd>, Error>;
 
-    fn advance(
+    fn set_min_debt(
         &self,
+        role: AdminRole,
         vault: VaultId,
-        sender: Sender,
-        advance_amount: Debt,
-        recipient: Recipient,
+        min: Option<Debt>,
     ) -> Result<Vec<Cmd>, Error>;
 
-    fn repay_underlying(
+    fn set_account_debt_cap(
         &self,
+        role: AdminRole,
         vault: VaultId,
-        sender: Sender,
-        deposit_asset: Asset,
-        deposit_amount: DepositAmount,
+        cap: Option<Debt>,
     ) -> Result<Vec<Cmd>, Error>;
 
-    fn repay_synthetic(
+    fn set_reserve_floor(
         &self,
+        role: AdminRole,
         vault: VaultId,
-        sender: Sender,
-        synthetic_asset: Synthetic,
-        synthetic_amount: SyntheticAmount,
-    ) -> Result<PositionResponse, Error>;
+        floor: Option<Collateral>,
+    ) -> Result<Vec<Cmd>, Error>;
 
-    fn withdraw_collateral(
+    /// Set the `(threshold_bps, fee_bps)` redeem fee tiers, where a redemption taking at least `threshold_bps`
+    /// of the reserves is charged `fee_bps`, `None` to disable
+    fn set_redeem_fee_schedule(
         &self,
+        role: AdminRole,
         vault: VaultId,
-        sender: Sender,
-        collateral_amount: Collateral,
-    ) -> Result<PositionResponse, Error>;
-
-    fn self_liquidate_position(&self, vault: VaultId, sender: Sender) -> Result<Vec<Cmd>, Error>;
+        tiers: Option<Vec<(u32, u32)>>,
+    ) -> Result<Vec<Cmd>, Error>;
 
-    fn convert_credit(
+    /// Set the loss in share value, in basis points, within which withdrawals & repayments may proceed
+    fn set_loss_tolerance(
+        &self,
+        role: AdminRole,
+        vault: VaultId,
+        bps: u32,
+    ) -> Result<Vec<Cmd>, Error>;
+
+    /// Set whether a loss in share value within the loss tolerance is socialised across the collateral holders,
+    /// rebasing the vault so that all operations may proceed
+    fn set_loss_socialisation_enabled(
+        &self,
+        role: AdminRole,
+        vault: VaultId,
+        enabled: bool,
+    ) -> Result<Vec<Cmd>, Error>;
+
+    /// Set the minimum deposit asset amount accepted by the vault, never less than one
+    fn set_min_deposit(
+        &self,
+        role: AdminRole,
+        vault: VaultId,
+        amount: u128,
+    ) -> Result<Vec<Cmd>, Error>;
+}
+
+pub trait Hub {
+    fn evaluate(&self, vault: VaultId, sender: Sender) -> Result<PositionResponse, Error>;
+
+    fn deposit(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+        deposit_asset: Asset,
+        deposit_amount: DepositAmount,
+        recipient: Recipient,
+    ) -> Result<Vec<Cmd>, Error>;
+
+    /// Credit the sender with collateral equal to the current value of vault shares held outside of the hub
+    fn absorb_shares(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+        shares_asset: Asset,
+        shares_amount: SharesAmount,
+    ) -> Result<Vec<Cmd>, Error>;
+
+    fn advance(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+        advance_amount: Debt,
+        recipient: Recipient,
+    ) -> Result<Vec<Cmd>, Error>;
+
+    fn repay_underlying(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+        deposit_asset: Asset,
+        deposit_amount: DepositAmount,
+    ) -> Result<Vec<Cmd>, Error>;
+
+    fn repay_synthetic(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+        synthetic_asset: Synthetic,
+        synthetic_amount: SyntheticAmount,
+    ) -> Result<PositionResponse, Error>;
+
+    fn withdraw_collateral(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+        collateral_amount: Collateral,
+    ) -> Result<PositionResponse, Error>;
+
+    /// Withdraw as much collateral as the position allows after settling any accrued yield,
+    /// emptying it entirely if there is no debt
+    fn withdraw_all_collateral(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+    ) -> Result<PositionResponse, Error>;
+
+    fn self_liquidate_position(&self, vault: VaultId, sender: Sender) -> Result<Vec<Cmd>, Error>;
+
+    fn convert_credit(
         &self,
         vault: VaultId,
         sender: Sender,
         credit_amount: Credit,
     ) -> Result<PositionResponse, Error>;
 
+    /// Convert the lesser of the account's credit and the vault's reserve balance into collateral
+    fn convert_all_credit(&self, vault: VaultId, sender: Sender)
+        -> Result<PositionResponse, Error>;
+
     fn redeem_synthetic(
         &self,
         vault: VaultId,
@@ -633,9 +1216,120 @@ pub trait Hub {
         deposit_value: DepositValue,
     ) -> Result<Vec<Cmd>, Error>;
 
-    fn claim_treasury_shares(&self, vault: VaultId, sender: Sender) -> Result<Vec<Cmd>, Error>;
+    fn claim_treasury_shares(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+        amount: Option<SharesAmount>,
+    ) -> Result<Vec<Cmd>, Error>;
 
     fn claim_amo_shares(&self, vault: VaultId, sender: Sender) -> Result<Vec<Cmd>, Error>;
+
+    /// Return AMO proceeds to the vault's depositors, either as vault shares or the deposit asset.
+    /// The proceeds are split per the vault's AMO allocation: the allocated portion is paid against debt
+    /// via the reserves & the remainder accrues to the collateral pool as yield.
+    /// Only the configured AMO may return proceeds.
+    fn return_amo_proceeds(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+        asset: Asset,
+        amount: u128,
+    ) -> Result<Vec<Cmd>, Error>;
+
+    /// Grant an `operator` management of the sender's position, replacing any existing grant.
+    /// `None` revokes the existing grant.
+    fn set_position_operator(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+        operator: Option<Operator>,
+        max_advance: Option<Debt>,
+        allow_withdraw: bool,
+    ) -> Result<Vec<Cmd>, Error>;
+
+    /// Advance against the `owner`'s position as their operator, the advance is minted to the owner
+    fn operator_advance(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+        owner: Account,
+        advance_amount: Debt,
+    ) -> Result<Vec<Cmd>, Error>;
+
+    /// Repay the `owner`'s debt as their operator using the synthetic sent
+    fn operator_repay_synthetic(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+        owner: Account,
+        synthetic_asset: Synthetic,
+        synthetic_amount: SyntheticAmount,
+    ) -> Result<PositionResponse, Error>;
+
+    /// Withdraw collateral from the `owner`'s position as their operator, the collateral is redeemed to the owner
+    fn operator_withdraw_collateral(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+        owner: Account,
+        collateral_amount: Collateral,
+    ) -> Result<PositionResponse, Error>;
+
+    /// Remove the expired oracle fee cache entries among the `limit` entries following `start_after`
+    fn prune_oracle_fee_cache(
+        &self,
+        vault: VaultId,
+        start_after: Option<Recipient>,
+        limit: usize,
+    ) -> Result<Vec<Cmd>, Error>;
+
+    /// Preview the sender depositing `deposit_amount` of the `deposit_asset` into the recipient's position,
+    /// applying the same checks as a deposit & valuing it in the vault's deposit asset
+    fn simulate_deposit(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+        deposit_asset: Asset,
+        deposit_amount: DepositAmount,
+        recipient: Recipient,
+    ) -> Result<DepositOutcome, Error>;
+
+    /// Preview advancing `advance_amount` against the recipient's position
+    fn simulate_advance(
+        &self,
+        vault: VaultId,
+        advance_amount: Debt,
+        recipient: Recipient,
+    ) -> Result<AdvanceOutcome, Error>;
+
+    /// Preview withdrawing `collateral_amount` from the sender's position
+    fn simulate_withdraw(
+        &self,
+        vault: VaultId,
+        sender: Sender,
+        collateral_amount: Collateral,
+    ) -> Result<WithdrawOutcome, Error>;
+
+    /// The advance fee the account would be charged on advancing `advance_amount`, resolved the same way as when advancing.
+    /// Only the portion of `advance_amount` exceeding the account's credit creates debt & is charged.
+    fn effective_advance_fee(
+        &self,
+        vault: VaultId,
+        account: Account,
+        advance_amount: Debt,
+    ) -> Result<EffectiveAdvanceFee, Error>;
+
+    /// The advance fees for each of the `recipients` in the same order as given, resolved the same way as when advancing.
+    /// Any fees not freshly cached are requested from the oracle in a single batch.
+    fn advance_fees(
+        &self,
+        vault: VaultId,
+        recipients: Vec<Recipient>,
+    ) -> Result<Vec<AdvanceFee>, Error>;
+
+    /// The account's current LTV, with collateral valued the same way as when advancing
+    fn position_ltv(&self, vault: VaultId, account: Account) -> Result<PositionLtv, Error>;
 }
 
 pub struct ConfigureHubImpl<'a> {
@@ -650,6 +1344,84 @@ pub fn configure<'a>(
     ConfigureHubImpl { vaults, mint }
 }
 
+/// Set the global pause, authorised for the admin (`admin_role` is `Some`) or the guardian
+pub fn set_global_pause(
+    balance_sheet: &dyn BalanceSheet,
+    admin_role: Option<AdminRole>,
+    sender: Sender,
+    paused: bool,
+) -> Result<Vec<Cmd>, Error> {
+    let is_guardian = balance_sheet
+        .guardian()
+        .is_some_and(|guardian| guardian == sender);
+
+    if admin_role.is_none() && !is_guardian {
+        return Err(UnauthorizedError.into());
+    }
+
+    Ok(cmds![BalanceSheetCmd::SetGlobalPause { paused }])
+}
+
+/// Deregister a vault, freeing its registration.
+/// Only possible once the vault has no outstanding debt, collateral, reserves or unclaimed treasury & AMO shares.
+pub fn deregister_vault(
+    vaults: &dyn VaultRegistry,
+    balance_sheet: &dyn BalanceSheet,
+    _: AdminRole,
+    vault: VaultId,
+) -> Result<Vec<Cmd>, Error> {
+    if !vaults.is_registered(&vault) {
+        return Err(Error::VaultNotRegistered);
+    }
+
+    let total_debt = balance_sheet.total_debt(&vault).unwrap_or_default();
+
+    let collateral_balance = balance_sheet.collateral_balance(&vault).unwrap_or_default();
+
+    let reserve_balance = balance_sheet.reserve_balance(&vault).unwrap_or_default();
+
+    let SharesAmount(treasury_shares) = balance_sheet.treasury_shares(&vault).unwrap_or_default();
+
+    let SharesAmount(amo_shares) = balance_sheet.amo_shares(&vault).unwrap_or_default();
+
+    if total_debt > 0
+        || collateral_balance > 0
+        || reserve_balance > 0
+        || treasury_shares > 0
+        || amo_shares > 0
+    {
+        return Err(Error::VaultNotEmpty);
+    }
+
+    // cached oracle fees are left in place, invalidated by the epoch rather than removed one by one
+    let epoch = vaults
+        .advance_fee_oracle_epoch(&vault)
+        .checked_add(1)
+        .expect("always: oracle epoch < u64::MAX");
+
+    Ok(cmds![
+        VaultCmd::SetAdvanceFeeOracleEpoch {
+            vault: vault.clone(),
+            epoch
+        },
+        VaultCmd::Deregister { vault }
+    ])
+}
+
+/// Returns an error if the global pause is set
+pub fn ensure_not_paused(balance_sheet: &dyn BalanceSheet) -> Result<(), Error> {
+    if balance_sheet.global_paused() {
+        return Err(Error::ProtocolPaused);
+    }
+
+    Ok(())
+}
+
+/// Returns the major component of a semver `version` string, if it has one
+pub fn interface_major_version(version: &str) -> Option<u64> {
+    version.split('.').next()?.parse().ok()
+}
+
 macro_rules! issue_cmd {
     ($registry:expr, $vault:ident, $cmd:expr) => {{
         if !$registry.is_registered(&$vault) {
@@ -685,13 +1457,48 @@ impl<'a> ConfigureHub for ConfigureHubImpl<'a> {
             return Err(Error::DecimalsMismatch);
         }
 
-        Ok(cmds![VaultCmd::Register { vault, synthetic }])
+        let shares_decimals = self
+            .vaults
+            .shares_decimals(&vault)
+            .ok_or(Error::VaultNotFound)?;
+
+        if shares_decimals != SHARES_DECIMAL_PLACES {
+            return Err(Error::SharesDecimalsMismatch);
+        }
+
+        let interface = self
+            .vaults
+            .interface(&vault)
+            .ok_or(Error::UnsupportedVaultInterface)?;
+
+        if interface_major_version(&interface.version)
+            != Some(SUPPORTED_VAULT_INTERFACE_MAJOR_VERSION)
+        {
+            return Err(Error::UnsupportedVaultInterface);
+        }
+
+        Ok(cmds![
+            VaultCmd::Register {
+                vault: vault.clone(),
+                synthetic
+            },
+            VaultCmd::SetInterfaceVersion {
+                vault: vault.clone(),
+                version: interface.version,
+                features: interface.features
+            },
+            BalanceSheetCmd::TrackTotalCollateral { vault }
+        ])
     }
 
     fn set_treasury(&self, _: AdminRole, treasury: Treasury) -> Result<Vec<Cmd>, Error> {
         Ok(cmds![BalanceSheetCmd::SetTreasury { treasury }])
     }
 
+    fn set_guardian(&self, _: AdminRole, guardian: Guardian) -> Result<Vec<Cmd>, Error> {
+        Ok(cmds![BalanceSheetCmd::SetGuardian { guardian }])
+    }
+
     fn set_deposit_enabled(
         &self,
         _: AdminRole,
@@ -795,11 +1602,53 @@ impl<'a> ConfigureHub for ConfigureHubImpl<'a> {
         _: AdminRole,
         vault: VaultId,
         oracle: Oracle,
+    ) -> Result<Vec<Cmd>, Error> {
+        if !self.vaults.is_registered(&vault) {
+            return Err(Error::VaultNotRegistered);
+        }
+
+        // invalidate any fees cached from a previous oracle
+        let epoch = self
+            .vaults
+            .advance_fee_oracle_epoch(&vault)
+            .checked_add(1)
+            .expect("always: oracle epoch < u64::MAX");
+
+        Ok(cmds![
+            VaultCmd::SetAdvanceFeeOracle {
+                vault: vault.clone(),
+                oracle
+            },
+            VaultCmd::SetAdvanceFeeOracleEpoch { vault, epoch }
+        ])
+    }
+
+    fn set_oracle_fee_cache_secs(
+        &self,
+        _: AdminRole,
+        vault: VaultId,
+        secs: Option<CacheWindowSecs>,
+    ) -> Result<Vec<Cmd>, Error> {
+        issue_cmd!(
+            self.vaults,
+            vault,
+            VaultCmd::SetOracleFeeCacheSecs {
+                vault,
+                secs: secs.filter(|secs| *secs > 0)
+            }
+        )
+    }
+
+    fn set_collateral_price_oracle(
+        &self,
+        _: AdminRole,
+        vault: VaultId,
+        oracle: Option<CollateralPriceOracle>,
     ) -> Result<Vec<Cmd>, Error> {
         issue_cmd!(
             self.vaults,
             vault,
-            VaultCmd::SetAdvanceFeeOracle { vault, oracle }
+            VaultCmd::SetCollateralPriceOracle { vault, oracle }
         )
     }
 
@@ -916,37 +1765,178 @@ impl<'a> ConfigureHub for ConfigureHubImpl<'a> {
 
         Ok(cmds![VaultCmd::SetMintProxy { vault, proxy: None }])
     }
-}
 
-pub struct HubImpl<'a> {
-    vaults: &'a dyn VaultRegistry,
-    balance_sheet: &'a dyn BalanceSheet,
-    advance_fee_oracle: &'a dyn AdvanceFeeOracle,
-}
+    fn set_min_collateral(
+        &self,
+        _: AdminRole,
+        vault: VaultId,
+        min: Option<Collateral>,
+    ) -> Result<Vec<Cmd>, Error> {
+        issue_cmd!(
+            self.vaults,
+            vault,
+            VaultCmd::SetMinCollateral {
+                vault,
+                min: min.filter(|min| *min > 0)
+            }
+        )
+    }
 
-pub fn hub<'a>(
-    vaults: &'a dyn VaultRegistry,
-    balance_sheet: &'a dyn BalanceSheet,
-    advance_fee_oracle: &'a dyn AdvanceFeeOracle,
-) -> HubImpl<'a> {
-    HubImpl {
-        vaults,
-        balance_sheet,
-        advance_fee_oracle,
+    fn set_min_debt(
+        &self,
+        _: AdminRole,
+        vault: VaultId,
+        min: Option<Debt>,
+    ) -> Result<Vec<Cmd>, Error> {
+        issue_cmd!(
+            self.vaults,
+            vault,
+            VaultCmd::SetMinDebt {
+                vault,
+                min: min.filter(|min| *min > 0)
+            }
+        )
     }
-}
 
-#[derive(Debug, Clone)]
-struct Evaluation {
-    prev_vault: VaultPosition,
-    prev_cdp: Cdp,
-    redemption_rate: Option<RedemptionRate>,
-    current_vault: VaultPosition,
-    current_cdp: Cdp,
-}
+    fn set_account_debt_cap(
+        &self,
+        _: AdminRole,
+        vault: VaultId,
+        cap: Option<Debt>,
+    ) -> Result<Vec<Cmd>, Error> {
+        issue_cmd!(
+            self.vaults,
+            vault,
+            VaultCmd::SetAccountDebtCap { vault, cap }
+        )
+    }
 
-fn push_update_vault_position_cmds(
-    id: &VaultId,
+    fn set_reserve_floor(
+        &self,
+        _: AdminRole,
+        vault: VaultId,
+        floor: Option<Collateral>,
+    ) -> Result<Vec<Cmd>, Error> {
+        issue_cmd!(
+            self.vaults,
+            vault,
+            VaultCmd::SetReserveFloor {
+                vault,
+                floor: floor.filter(|floor| *floor > 0)
+            }
+        )
+    }
+
+    fn set_redeem_fee_schedule(
+        &self,
+        _: AdminRole,
+        vault: VaultId,
+        tiers: Option<Vec<(u32, u32)>>,
+    ) -> Result<Vec<Cmd>, Error> {
+        let schedule = tiers
+            .map(|tiers| RedeemFeeSchedule::new(&tiers).ok_or(Error::InvalidRedeemFeeSchedule))
+            .transpose()?;
+
+        issue_cmd!(
+            self.vaults,
+            vault,
+            VaultCmd::SetRedeemFeeSchedule { vault, schedule }
+        )
+    }
+
+    fn set_loss_tolerance(
+        &self,
+        _: AdminRole,
+        vault: VaultId,
+        bps: u32,
+    ) -> Result<Vec<Cmd>, Error> {
+        issue_cmd!(
+            self.vaults,
+            vault,
+            VaultCmd::SetLossTolerance {
+                vault,
+                tolerance: LossTolerance::new(bps).ok_or(Error::InvalidRate)?
+            }
+        )
+    }
+
+    fn set_loss_socialisation_enabled(
+        &self,
+        _: AdminRole,
+        vault: VaultId,
+        enabled: bool,
+    ) -> Result<Vec<Cmd>, Error> {
+        issue_cmd!(
+            self.vaults,
+            vault,
+            VaultCmd::SetLossSocialisationEnabled { vault, enabled }
+        )
+    }
+
+    fn set_min_deposit(
+        &self,
+        _: AdminRole,
+        vault: VaultId,
+        amount: u128,
+    ) -> Result<Vec<Cmd>, Error> {
+        issue_cmd!(
+            self.vaults,
+            vault,
+            VaultCmd::SetMinDeposit {
+                vault,
+                amount: amount.max(DEFAULT_MIN_DEPOSIT)
+            }
+        )
+    }
+}
+
+pub struct HubImpl<'a> {
+    vaults: &'a dyn VaultRegistry,
+    balance_sheet: &'a dyn BalanceSheet,
+    advance_fee_oracle: &'a dyn AdvanceFeeOracle,
+}
+
+pub fn hub<'a>(
+    vaults: &'a dyn VaultRegistry,
+    balance_sheet: &'a dyn BalanceSheet,
+    advance_fee_oracle: &'a dyn AdvanceFeeOracle,
+) -> HubImpl<'a> {
+    HubImpl {
+        vaults,
+        balance_sheet,
+        advance_fee_oracle,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Evaluation {
+    prev_vault: VaultPosition,
+    prev_cdp: Cdp,
+    redemption_rate: Option<RedemptionRate>,
+    current_vault: VaultPosition,
+    current_cdp: Cdp,
+    yield_fee_accrued: Option<BalanceSheetEvent>,
+}
+
+// The fees taken from the yield distributed to the `accrued` vault position
+fn yield_fee_accrued_event(
+    id: &VaultId,
+    accrued: &VaultPosition,
+    YieldFees {
+        collateral,
+        reserve,
+    }: YieldFees,
+) -> BalanceSheetEvent {
+    BalanceSheetEvent::YieldFeeAccrued {
+        vault: id.clone(),
+        treasury_shares: accrued.treasury_shares,
+        collateral_fee: collateral,
+        reserve_fee: reserve,
+    }
+}
+
+fn push_update_vault_position_cmds(
+    id: &VaultId,
     old: &VaultPosition,
     new: &VaultPosition,
     cmds: &mut Vec<Cmd>,
@@ -997,6 +1987,19 @@ fn push_update_vault_position_cmds(
         cmds.push_cmd(BalanceSheetCmd::SetOverallSumPaymentRatio {
             vault: id.clone(),
             spr: new.spr,
+            payment: positions::sum_payment(
+                old.spr,
+                new.spr,
+                old.collateral_pool.quota,
+                old.collateral_factor,
+            ),
+        });
+    }
+
+    if old.collateral_factor != new.collateral_factor {
+        cmds.push_cmd(BalanceSheetCmd::SetCollateralFactor {
+            vault: id.clone(),
+            factor: new.collateral_factor,
         });
     }
 }
@@ -1037,46 +2040,160 @@ fn push_update_cdp_cmds(
             vault: vault.clone(),
             account: account.clone(),
             spr: new.spr,
+            payment: positions::sum_payment(
+                old.spr,
+                new.spr,
+                old.collateral,
+                old.collateral_factor,
+            ),
+        });
+    }
+
+    if old.collateral_factor != new.collateral_factor {
+        cmds.push_cmd(BalanceSheetCmd::SetAccountCollateralFactor {
+            vault: vault.clone(),
+            account: account.clone(),
+            factor: new.collateral_factor,
         });
     }
 }
 
-impl<'a> HubImpl<'a> {
-    pub fn current_vault_position(&self, vault: &VaultId) -> VaultPosition {
-        VaultPosition {
-            collateral_pool: {
-                let shares = self
-                    .balance_sheet
-                    .collateral_shares(vault)
-                    .unwrap_or_default();
-
-                let quota = self
-                    .balance_sheet
-                    .collateral_balance(vault)
-                    .unwrap_or_default();
-
-                SharesPool { shares, quota }
-            },
-            reserve_pool: {
-                let shares = self.balance_sheet.reserve_shares(vault).unwrap_or_default();
+fn vault_position(balance_sheet: &dyn BalanceSheet, vault: &VaultId) -> VaultPosition {
+    VaultPosition {
+        collateral_pool: {
+            let shares = balance_sheet.collateral_shares(vault).unwrap_or_default();
 
-                let quota = self
-                    .balance_sheet
-                    .reserve_balance(vault)
-                    .unwrap_or_default();
+            let quota = balance_sheet.collateral_balance(vault).unwrap_or_default();
 
-                SharesPool { shares, quota }
-            },
-            treasury_shares: self
-                .balance_sheet
-                .treasury_shares(vault)
-                .unwrap_or_default(),
-            amo_shares: self.balance_sheet.amo_shares(vault).unwrap_or_default(),
-            spr: self
-                .balance_sheet
-                .overall_sum_payment_ratio(vault)
-                .unwrap_or(SumPaymentRatio::zero()),
-        }
+            SharesPool { shares, quota }
+        },
+        reserve_pool: {
+            let shares = balance_sheet.reserve_shares(vault).unwrap_or_default();
+
+            let quota = balance_sheet.reserve_balance(vault).unwrap_or_default();
+
+            SharesPool { shares, quota }
+        },
+        treasury_shares: balance_sheet.treasury_shares(vault).unwrap_or_default(),
+        amo_shares: balance_sheet.amo_shares(vault).unwrap_or_default(),
+        spr: balance_sheet
+            .overall_sum_payment_ratio(vault)
+            .unwrap_or(SumPaymentRatio::zero()),
+        collateral_factor: balance_sheet.collateral_factor(vault).unwrap_or_default(),
+    }
+}
+
+fn vault_redemption_rate(vaults: &dyn VaultRegistry, vault: &VaultId) -> Option<RedemptionRate> {
+    let total_shares_issued = vaults.total_shares_issued(vault);
+    let total_deposit_value = vaults.total_deposits_value(vault);
+
+    RedemptionRate::new(total_shares_issued, total_deposit_value)
+}
+
+/// Acknowledge a loss in the value of the vault's shares, rebasing the collateral & reserve balances to their current value.
+/// The collateral loss is distributed across all positions in proportion to their collateral.
+pub fn acknowledge_loss(
+    vaults: &dyn VaultRegistry,
+    balance_sheet: &dyn BalanceSheet,
+    _: AdminRole,
+    vault: VaultId,
+) -> Result<Vec<Cmd>, Error> {
+    if !vaults.is_registered(&vault) {
+        return Err(Error::VaultNotRegistered);
+    }
+
+    let redemption_rate =
+        vault_redemption_rate(vaults, &vault).ok_or(AcknowledgeLossError::NoLoss)?;
+
+    let prev_vault = vault_position(balance_sheet, &vault);
+
+    let updated_vault = positions::acknowledge_loss(prev_vault.clone(), redemption_rate)?;
+
+    let mut cmds = vec![];
+
+    push_update_vault_position_cmds(&vault, &prev_vault, &updated_vault, &mut cmds);
+
+    Ok(cmds)
+}
+
+/// Sweep collateral dust left by rounding, i.e. the vault's collateral balance not attributed to any position, into the reserves.
+/// Any outstanding yield is paid out first, failing if a loss in share value is detected.
+pub fn sweep_dust(
+    vaults: &dyn VaultRegistry,
+    balance_sheet: &dyn BalanceSheet,
+    _: AdminRole,
+    vault: VaultId,
+) -> Result<Vec<Cmd>, Error> {
+    if !vaults.is_registered(&vault) {
+        return Err(Error::VaultNotRegistered);
+    }
+
+    let total_collateral = balance_sheet
+        .total_collateral(&vault)
+        .ok_or(Error::TotalCollateralUntracked)?;
+
+    let redemption_rate = vault_redemption_rate(vaults, &vault);
+
+    let prev_vault = vault_position(balance_sheet, &vault);
+
+    let accrued = update_vault(
+        prev_vault.clone(),
+        redemption_rate,
+        || vaults.amo_allocation(&vault).unwrap_or_default(),
+        || vaults.collateral_yield_fee(&vault).unwrap_or_default(),
+        || vaults.reserve_yield_fee(&vault).unwrap_or_default(),
+    )?;
+
+    let yield_fee_accrued = accrued
+        .as_ref()
+        .map(|(accrued_vault, fees)| yield_fee_accrued_event(&vault, accrued_vault, *fees));
+
+    let current_vault = accrued.map_or_else(|| prev_vault.clone(), |(vault, _)| vault);
+
+    let updated_vault = positions::sweep_dust(current_vault, total_collateral, redemption_rate)?;
+
+    let mut cmds = vec![];
+
+    push_update_vault_position_cmds(&vault, &prev_vault, &updated_vault, &mut cmds);
+
+    cmds.extend(yield_fee_accrued.map(Cmd::Event));
+
+    Ok(cmds)
+}
+
+fn evaluation_response(
+    vault_id: &VaultId,
+    account: &Account,
+    evaluation: Evaluation,
+) -> PositionResponse {
+    let mut cmds = vec![];
+
+    push_update_vault_position_cmds(
+        vault_id,
+        &evaluation.prev_vault,
+        &evaluation.current_vault,
+        &mut cmds,
+    );
+
+    cmds.extend(evaluation.yield_fee_accrued.map(Cmd::Event));
+
+    push_update_cdp_cmds(
+        vault_id,
+        account,
+        &evaluation.prev_cdp,
+        &evaluation.current_cdp,
+        &mut cmds,
+    );
+
+    PositionResponse {
+        cmds,
+        cdp: evaluation.current_cdp,
+    }
+}
+
+impl<'a> HubImpl<'a> {
+    pub fn current_vault_position(&self, vault: &VaultId) -> VaultPosition {
+        vault_position(self.balance_sheet, vault)
     }
 
     pub fn current_cdp(&self, vault: &VaultId, account: &Account) -> Cdp {
@@ -1100,46 +2217,591 @@ impl<'a> HubImpl<'a> {
             .account_sum_payment_ratio(vault, account)
             .unwrap_or(SumPaymentRatio::zero());
 
+        let collateral_factor = self
+            .balance_sheet
+            .account_collateral_factor(vault, account)
+            .unwrap_or_default();
+
         Cdp {
             collateral,
             debt,
             credit,
             spr,
+            collateral_factor,
         }
     }
 
-    fn redemption_rate(&self, id: &VaultId) -> Option<RedemptionRate> {
-        let total_shares_issued = self.vaults.total_shares_issued(id);
-        let total_deposit_value = self.vaults.total_deposits_value(id);
+    /// Increments the sequence number of the account's position, ordering every mutation of the position
+    fn push_next_position_seq_cmd(&self, vault: &VaultId, account: &Account, cmds: &mut Vec<Cmd>) {
+        let seq = self
+            .balance_sheet
+            .account_position_seq(vault, account)
+            .map_or(1, |seq| seq + 1);
 
-        RedemptionRate::new(total_shares_issued, total_deposit_value)
+        cmds.push_cmd(BalanceSheetCmd::SetAccountPositionSeq {
+            vault: vault.clone(),
+            account: account.clone(),
+            seq,
+        });
+    }
+
+    fn redemption_rate(&self, id: &VaultId) -> Option<RedemptionRate> {
+        vault_redemption_rate(self.vaults, id)
     }
 
     fn max_ltv(&self, vault: &VaultId) -> MaxLtv {
         self.vaults.max_ltv(vault).unwrap_or_default()
     }
 
-    fn advance_fee(&self, vault: &VaultId, recipient: &Recipient) -> AdvanceFee {
+    // The rate collateral is valued at for LTV purposes & whether it is stale:
+    // the strategy redemption rate discounted by the collateral price oracle's latest price if one is set,
+    // otherwise the (never stale) strategy redemption rate alone
+    fn ltv_collateral_rate(&self, vault: &VaultId) -> (Option<FixedU256>, bool) {
+        let strategy_rate = self.vaults.strategy_redemption_rate(vault);
+
+        let Some(CollateralPriceOracle {
+            oracle,
+            max_age_secs,
+        }) = self.vaults.collateral_price_oracle(vault)
+        else {
+            return (strategy_rate, false);
+        };
+
+        let price = self.vaults.collateral_price(&oracle);
+
+        // the advance fee oracle's clock is the block time
+        let stale = price.is_stale(max_age_secs, self.advance_fee_oracle.now());
+
+        // the price is relative to the redemption value, so it discounts the strategy rate rather than replacing it
+        let rate = match strategy_rate {
+            Some(strategy_rate) => strategy_rate
+                .checked_mul(price.price)
+                .expect("never: collateral rate overflow"),
+            None => price.price,
+        };
+
+        (Some(rate), stale)
+    }
+
+    // The rate collateral backing the `cdp`'s debt is valued at, a stale price is rejected
+    // as withdrawing against it could leave the position above the max LTV at the current price
+    fn checked_ltv_collateral_rate(
+        &self,
+        vault: &VaultId,
+        cdp: &Cdp,
+    ) -> Result<Option<FixedU256>, Error> {
+        let (rate, stale) = self.ltv_collateral_rate(vault);
+
+        // without debt the collateral is never valued
+        if stale && cdp.debt > 0 {
+            return Err(Error::CollateralPriceStale);
+        }
+
+        Ok(rate)
+    }
+
+    fn check_deposits_allowed(&self, vault: &VaultId) -> Result<(), Error> {
+        if !self.vaults.is_registered(vault) {
+            return Err(Error::VaultNotRegistered);
+        }
+
+        if !self.vaults.deposits_enabled(vault) {
+            return Err(Error::DepositsDisabled);
+        }
+
+        Ok(())
+    }
+
+    fn check_advance_allowed(&self, vault: &VaultId, advance_amount: Debt) -> Result<(), Error> {
+        if !self.vaults.is_registered(vault) {
+            return Err(Error::VaultNotRegistered);
+        }
+
+        if !self.vaults.advance_enabled(vault) {
+            return Err(Error::AdvanceDisabled);
+        }
+
+        if advance_amount == 0 {
+            return Err(Error::CannotAdvanceZero);
+        }
+
+        Ok(())
+    }
+
+    fn plan_deposit_collateral(
+        &self,
+        vault_id: &VaultId,
+        recipient: &Recipient,
+        current_vault: VaultPosition,
+        current_cdp: Cdp,
+        deposit_value: Collateral,
+        issued_shares: SharesAmount,
+    ) -> Result<Plan<DepositOutcome>, Error> {
+        let (updated_vault, updated_cdp) = deposit_collateral(
+            current_vault.clone(),
+            current_cdp.clone(),
+            deposit_value,
+            issued_shares,
+        );
+
+        self.check_min_collateral(vault_id, &updated_cdp)?;
+
+        let mut cmds = vec![];
+
+        push_update_vault_position_cmds(vault_id, &current_vault, &updated_vault, &mut cmds);
+
+        push_update_cdp_cmds(vault_id, recipient, &current_cdp, &updated_cdp, &mut cmds);
+
+        self.push_next_position_seq_cmd(vault_id, recipient, &mut cmds);
+
+        Ok(Plan {
+            cmds,
+            outcome: DepositOutcome {
+                cdp: updated_cdp,
+                collateral: deposit_value,
+            },
+        })
+    }
+
+    fn plan_advance(
+        &self,
+        vault_id: VaultId,
+        advance_amount: Debt,
+        recipient: Recipient,
+    ) -> Result<Plan<AdvanceOutcome>, Error> {
+        let evaluation = self._evaluate(&vault_id, &recipient)?;
+
+        if self
+            .vaults
+            .reserve_floor(&vault_id)
+            .is_some_and(|floor| evaluation.current_vault.reserve_pool.quota < floor)
+        {
+            return Err(Error::ReserveBelowFloor);
+        }
+
+        let advance_fee_recipient = self.vaults.advance_fee_recipient(&vault_id);
+
+        let fee_cache_cmd = Cell::new(None);
+
+        let stale_collateral_price = Cell::new(false);
+
+        let advanced = advance(
+            evaluation.current_cdp,
+            advance_amount,
+            || self.max_ltv(&vault_id),
+            || {
+                let (rate, stale) = self.ltv_collateral_rate(&vault_id);
+                stale_collateral_price.set(stale);
+                rate
+            },
+            || {
+                advance_fee_recipient.is_some().then(|| {
+                    let (fee, cmd) = self.advance_fee(&vault_id, &recipient);
+                    fee_cache_cmd.set(cmd);
+                    fee
+                })
+            },
+        );
+
+        // only an advance taking on debt values the collateral
+        if stale_collateral_price.get() {
+            return Err(Error::CollateralPriceStale);
+        }
+
+        let Advance {
+            cdp: current_cdp,
+            amount,
+            fee,
+        } = advanced.ok_or(Error::NotEnoughCollateral)?;
+
+        self.check_min_debt(&vault_id, &current_cdp)?;
+
+        self.check_account_debt_cap(&vault_id, &current_cdp)?;
+
+        let synthetic = self.vaults.synthetic_asset(&vault_id);
+
+        let mut cmds = vec![];
+
+        if let Some(cmd) = fee_cache_cmd.take() {
+            cmds.push_cmd(cmd);
+        }
+
+        push_update_cdp_cmds(
+            &vault_id,
+            &recipient,
+            &evaluation.prev_cdp,
+            &current_cdp,
+            &mut cmds,
+        );
+
+        self.push_next_position_seq_cmd(&vault_id, &recipient, &mut cmds);
+
+        push_update_vault_position_cmds(
+            &vault_id,
+            &evaluation.prev_vault,
+            &evaluation.current_vault,
+            &mut cmds,
+        );
+
+        cmds.extend(evaluation.yield_fee_accrued.map(Cmd::Event));
+
+        cmds.push_cmd(MintCmd::Mint {
+            synthetic: synthetic.clone(),
+            amount,
+            recipient,
+        });
+
+        if let Some((fee, recipient)) = fee.zip(advance_fee_recipient) {
+            cmds.push_cmd(MintCmd::Mint {
+                synthetic: synthetic.clone(),
+                amount: fee,
+                recipient,
+            });
+        }
+
+        Ok(Plan {
+            cmds,
+            outcome: AdvanceOutcome {
+                cdp: current_cdp,
+                amount,
+                fee,
+            },
+        })
+    }
+
+    fn plan_withdraw_collateral(
+        &self,
+        vault_id: VaultId,
+        sender: Sender,
+        collateral_amount: Collateral,
+    ) -> Result<Plan<WithdrawOutcome>, Error> {
+        if !self.vaults.is_registered(&vault_id) {
+            return Err(Error::VaultNotRegistered);
+        }
+
+        if collateral_amount == 0 {
+            return Err(Error::CannotWithdrawZero);
+        }
+
+        let evaluation = self._evaluate_tolerating_loss(&vault_id, &sender)?;
+
+        let max_ltv = self.max_ltv(&vault_id);
+
+        let collateral_rate =
+            self.checked_ltv_collateral_rate(&vault_id, &evaluation.current_cdp)?;
+
+        let (updated_vault, updated_cdp, shares_amount) = withdraw_collateral(
+            evaluation.current_vault,
+            evaluation.current_cdp,
+            collateral_amount,
+            max_ltv,
+            collateral_rate,
+            evaluation.redemption_rate,
+        )?;
+
+        self.check_min_collateral(&vault_id, &updated_cdp)?;
+
+        let mut cmds = vec![];
+
+        push_update_vault_position_cmds(
+            &vault_id,
+            &evaluation.prev_vault,
+            &updated_vault,
+            &mut cmds,
+        );
+
+        cmds.extend(evaluation.yield_fee_accrued.map(Cmd::Event));
+
+        push_update_cdp_cmds(
+            &vault_id,
+            &sender,
+            &evaluation.prev_cdp,
+            &updated_cdp,
+            &mut cmds,
+        );
+
+        self.push_next_position_seq_cmd(&vault_id, &sender, &mut cmds);
+
+        let shares_asset = self.vaults.shares_asset(&vault_id);
+
+        cmds.push_cmd(VaultCmd::Redeem {
+            vault: vault_id,
+            shares: shares_asset,
+            amount: shares_amount,
+            recipient: sender,
+        });
+
+        Ok(Plan {
+            cmds,
+            outcome: WithdrawOutcome {
+                cdp: updated_cdp,
+                shares: shares_amount,
+            },
+        })
+    }
+
+    // A position may hold no collateral at all, otherwise at least the vault minimum (if any)
+    fn check_min_collateral(&self, vault: &VaultId, cdp: &Cdp) -> Result<(), Error> {
+        if cdp.collateral > 0
+            && self
+                .vaults
+                .min_collateral(vault)
+                .is_some_and(|min| cdp.collateral < min)
+        {
+            return Err(Error::BelowMinCollateral);
+        }
+
+        Ok(())
+    }
+
+    // A position may hold no debt at all, otherwise at least the vault minimum (if any)
+    fn check_min_debt(&self, vault: &VaultId, cdp: &Cdp) -> Result<(), Error> {
+        if cdp.debt > 0
+            && self
+                .vaults
+                .min_debt(vault)
+                .is_some_and(|min| cdp.debt < min)
+        {
+            return Err(Error::BelowMinDebt);
+        }
+
+        Ok(())
+    }
+
+    fn check_account_debt_cap(&self, vault: &VaultId, cdp: &Cdp) -> Result<(), Error> {
+        if self
+            .vaults
+            .account_debt_cap(vault)
+            .is_some_and(|cap| cdp.debt > cap)
+        {
+            return Err(Error::AccountDebtCapExceeded);
+        }
+
+        Ok(())
+    }
+
+    // Returns the fresh cached oracle fee for the recipient, if caching is enabled for the vault
+    fn fresh_cached_oracle_fee(
+        &self,
+        vault: &VaultId,
+        recipient: &Recipient,
+        now: Now,
+    ) -> Option<Option<AdvanceFee>> {
+        let window = self.vaults.oracle_fee_cache_secs(vault)?;
+
+        let epoch = self.vaults.advance_fee_oracle_epoch(vault);
+
+        self.vaults
+            .cached_oracle_fee(vault, recipient)
+            .filter(|cached| cached.is_fresh(epoch, window, now))
+            .map(|cached| cached.fee)
+    }
+
+    // Returns a command to cache the oracle `fee` for the recipient, if caching is enabled for the vault
+    fn cache_oracle_fee_cmd(
+        &self,
+        vault: &VaultId,
+        recipient: &Recipient,
+        fee: Option<AdvanceFee>,
+        now: Now,
+    ) -> Option<VaultCmd> {
+        self.vaults.oracle_fee_cache_secs(vault)?;
+
+        Some(VaultCmd::SetCachedOracleFee {
+            vault: vault.clone(),
+            recipient: recipient.clone(),
+            cached: CachedAdvanceFee {
+                fee,
+                oracle_epoch: self.vaults.advance_fee_oracle_epoch(vault),
+                timestamp: now,
+            },
+        })
+    }
+
+    // Returns the advance fee for the recipient & a command to update the oracle fee cache, if required
+    fn advance_fee(
+        &self,
+        vault: &VaultId,
+        recipient: &Recipient,
+    ) -> (AdvanceFee, Option<VaultCmd>) {
+        let (_, fee, cmd) = self.resolve_advance_fee(vault, recipient);
+
+        (fee, cmd)
+    }
+
+    // Returns the advance fee for the recipient along with where it was resolved from
+    // & a command to update the oracle fee cache, if required
+    fn resolve_advance_fee(
+        &self,
+        vault: &VaultId,
+        recipient: &Recipient,
+    ) -> (AdvanceFeeSource, AdvanceFee, Option<VaultCmd>) {
+        let fixed_fee = || self.vaults.fixed_advance_fee(vault).unwrap_or_default();
+
         // check if a fee oracle is set
         let Some(oracle) = self.vaults.advance_fee_oracle(vault) else {
             // if not, use the fixed fee
-            return self.vaults.fixed_advance_fee(vault).unwrap_or_default();
+            return (AdvanceFeeSource::Fixed, fixed_fee(), None);
         };
 
-        // request fee for the recipient from the oracle
-        self.advance_fee_oracle
-            .advance_fee(&oracle, recipient)
-            .unwrap_or_default()
+        let now = self.advance_fee_oracle.now();
+
+        let (fee, cmd) = match self.fresh_cached_oracle_fee(vault, recipient, now) {
+            Some(fee) => (fee, None),
+            None => {
+                // request fee for the recipient from the oracle
+                let fee = self.advance_fee_oracle.advance_fee(&oracle, recipient);
+
+                (fee, self.cache_oracle_fee_cmd(vault, recipient, fee, now))
+            }
+        };
+
+        // fall back to the fixed fee if the oracle has no fee for the recipient
+        match fee {
+            Some(fee) => (AdvanceFeeSource::Oracle, fee, cmd),
+            None => (AdvanceFeeSource::Fixed, fixed_fee(), cmd),
+        }
+    }
+
+    // Returns the advance fees for each of the `recipients` in the same order as given,
+    // requesting any fees not freshly cached from the oracle in a single batch
+    // & commands to update the oracle fee cache, if required.
+    // Only the AdvanceFees query resolves several recipients at once, execution advances to one recipient.
+    fn resolve_advance_fees(
+        &self,
+        vault: &VaultId,
+        recipients: &[Recipient],
+    ) -> (Vec<AdvanceFee>, Vec<Cmd>) {
+        let fixed_fee = self.vaults.fixed_advance_fee(vault).unwrap_or_default();
+
+        let Some(oracle) = self.vaults.advance_fee_oracle(vault) else {
+            return (vec![fixed_fee; recipients.len()], vec![]);
+        };
+
+        let now = self.advance_fee_oracle.now();
+
+        let mut fees: Vec<_> = recipients
+            .iter()
+            .map(|recipient| self.fresh_cached_oracle_fee(vault, recipient, now))
+            .collect();
+
+        let uncached: Vec<_> = recipients
+            .iter()
+            .zip(&fees)
+            .filter(|(_, fee)| fee.is_none())
+            .map(|(recipient, _)| recipient.clone())
+            .collect();
+
+        let mut cmds = vec![];
+
+        if !uncached.is_empty() {
+            let mut oracle_fees = self
+                .advance_fee_oracle
+                .fees_for(&oracle, uncached.clone())
+                .into_iter();
+
+            for (fee, recipient) in fees.iter_mut().filter(|fee| fee.is_none()).zip(&uncached) {
+                let oracle_fee = oracle_fees
+                    .next()
+                    .expect("always: one fee per requested recipient");
+
+                if let Some(cmd) = self.cache_oracle_fee_cmd(vault, recipient, oracle_fee, now) {
+                    cmds.push_cmd(cmd);
+                }
+
+                *fee = Some(oracle_fee);
+            }
+        }
+
+        let fees = fees
+            .into_iter()
+            .map(|fee| fee.flatten().unwrap_or(fixed_fee))
+            .collect();
+
+        (fees, cmds)
     }
 
     fn _evaluate(&self, vault_id: &VaultId, account: &Account) -> Result<Evaluation, Error> {
+        self.evaluate_position(vault_id, account, false)
+    }
+
+    // Evaluate allowing a loss in share value within the vault's loss tolerance.
+    // Only for operations that reduce risk, i.e. withdrawals & repayments.
+    fn _evaluate_tolerating_loss(
+        &self,
+        vault_id: &VaultId,
+        account: &Account,
+    ) -> Result<Evaluation, Error> {
+        self.evaluate_position(vault_id, account, true)
+    }
+
+    // Check that the sender may deposit the `deposit_amount` of the `deposit_asset`,
+    // returning its value in the vault's deposit asset at the current conversion rate
+    fn value_deposit(
+        &self,
+        vault: &VaultId,
+        sender: &Sender,
+        deposit_asset: &Asset,
+        DepositAmount(deposit_amount): DepositAmount,
+    ) -> Result<DepositValue, Error> {
+        self.check_deposits_allowed(vault)?;
+
+        if self
+            .vaults
+            .deposit_proxy(vault)
+            .is_some_and(|proxy| *sender != proxy)
+        {
+            return Err(UnauthorizedError.into());
+        }
+
+        if deposit_amount == 0 {
+            return Err(Error::CannotDepositZero);
+        }
+
+        let DepositAmount(deposit_value) = if *deposit_asset == self.vaults.deposit_asset(vault) {
+            DepositAmount(deposit_amount)
+        } else {
+            self.vaults
+                .convert_secondary_deposit(vault, deposit_asset, DepositAmount(deposit_amount))
+                .ok_or(Error::InvalidDepositAsset)?
+        };
+
+        self.check_min_deposit(vault, DepositValue(deposit_value))?;
+
+        Ok(DepositValue(deposit_value))
+    }
+
+    // The minimum deposit is denominated in the vault's deposit asset, so it applies to the deposit's value
+    fn check_min_deposit(
+        &self,
+        vault: &VaultId,
+        DepositValue(deposit_value): DepositValue,
+    ) -> Result<(), Error> {
+        let min_deposit = self
+            .vaults
+            .min_deposit(vault)
+            .unwrap_or(DEFAULT_MIN_DEPOSIT);
+
+        if deposit_value < min_deposit {
+            return Err(Error::DepositBelowMinimum);
+        }
+
+        Ok(())
+    }
+
+    fn evaluate_position(
+        &self,
+        vault_id: &VaultId,
+        account: &Account,
+        tolerate_loss: bool,
+    ) -> Result<Evaluation, Error> {
         let prev_vault = self.current_vault_position(vault_id);
 
         let prev_cdp = self.current_cdp(vault_id, account);
 
         let redemption_rate = self.redemption_rate(vault_id);
 
-        let current_vault = update_vault(
+        let (current_vault, yield_fee_accrued) = match update_vault(
             prev_vault.clone(),
             redemption_rate,
             || self.vaults.amo_allocation(vault_id).unwrap_or_default(),
@@ -1149,8 +2811,31 @@ impl<'a> HubImpl<'a> {
                     .unwrap_or_default()
             },
             || self.vaults.reserve_yield_fee(vault_id).unwrap_or_default(),
-        )?
-        .unwrap_or_else(|| prev_vault.clone());
+        ) {
+            Ok(Some((accrued, fees))) => {
+                let event = yield_fee_accrued_event(vault_id, &accrued, fees);
+
+                (accrued, Some(event))
+            }
+            Ok(None) => (prev_vault.clone(), None),
+            // a tolerated loss is borne by the collateral holders, rebasing the vault so that all operations may proceed
+            Err(LossError)
+                if self.vaults.loss_socialisation_enabled(vault_id)
+                    && self.loss_tolerated(vault_id, &prev_vault) =>
+            {
+                let redemption_rate = redemption_rate
+                    .expect("always: a loss is only detected with a redemption rate");
+
+                let rebased = positions::acknowledge_loss(prev_vault.clone(), redemption_rate)?;
+
+                (rebased, None)
+            }
+            // no yield accrues while at a tolerated loss
+            Err(LossError) if tolerate_loss && self.loss_tolerated(vault_id, &prev_vault) => {
+                (prev_vault.clone(), None)
+            }
+            Err(err) => return Err(err.into()),
+        };
 
         let current_cdp = update_cdp(&current_vault, prev_cdp.clone());
 
@@ -1160,75 +2845,158 @@ impl<'a> HubImpl<'a> {
             redemption_rate,
             current_vault,
             current_cdp,
+            yield_fee_accrued,
+        })
+    }
+
+    fn loss_tolerated(&self, vault_id: &VaultId, vault: &VaultPosition) -> bool {
+        let tolerance = self.vaults.loss_tolerance(vault_id).unwrap_or_default();
+
+        self.redemption_rate(vault_id)
+            .is_some_and(|rate| loss_within_tolerance(vault, rate, tolerance))
+    }
+
+    // The owner's position operator, provided it is the sender
+    fn authorized_operator(
+        &self,
+        vault_id: &VaultId,
+        sender: &Sender,
+        owner: &Account,
+    ) -> Result<PositionOperator, Error> {
+        if !self.vaults.is_registered(vault_id) {
+            return Err(Error::VaultNotRegistered);
+        }
+
+        self.balance_sheet
+            .position_operator(vault_id, owner)
+            .filter(|grant| &grant.operator == sender)
+            .ok_or_else(|| UnauthorizedError.into())
+    }
+
+    fn _repay_synthetic(
+        &self,
+        vault_id: VaultId,
+        account: Account,
+        synthetic_asset: Synthetic,
+        synthetic_amount: SyntheticAmount,
+    ) -> Result<PositionResponse, Error> {
+        if !self.vaults.is_registered(&vault_id) {
+            return Err(Error::VaultNotRegistered);
+        }
+
+        if synthetic_amount == 0 {
+            return Err(Error::CannotRepayZero);
+        }
+
+        if synthetic_asset != self.vaults.synthetic_asset(&vault_id) {
+            return Err(Error::InvalidSyntheticAsset);
+        }
+
+        let evaluation = self._evaluate_tolerating_loss(&vault_id, &account)?;
+
+        if evaluation.current_cdp.debt == 0 {
+            return Err(Error::NothingToRepay);
+        }
+
+        let updated_cdp = repay(evaluation.current_cdp, synthetic_amount);
+
+        let mut cmds = vec![];
+
+        push_update_cdp_cmds(
+            &vault_id,
+            &account,
+            &evaluation.prev_cdp,
+            &updated_cdp,
+            &mut cmds,
+        );
+
+        self.push_next_position_seq_cmd(&vault_id, &account, &mut cmds);
+
+        push_update_vault_position_cmds(
+            &vault_id,
+            &evaluation.prev_vault,
+            &evaluation.current_vault,
+            &mut cmds,
+        );
+
+        cmds.extend(evaluation.yield_fee_accrued.map(Cmd::Event));
+
+        cmds.push_cmd(MintCmd::Burn {
+            synthetic: synthetic_asset,
+            amount: synthetic_amount,
+        });
+
+        Ok(PositionResponse {
+            cmds,
+            cdp: updated_cdp,
         })
     }
-}
 
-impl<'a> Hub for HubImpl<'a> {
-    fn evaluate(&self, vault_id: VaultId, sender: Sender) -> Result<PositionResponse, Error> {
-        let evaluation = self._evaluate(&vault_id, &sender)?;
+    fn _convert_credit(
+        &self,
+        vault_id: &VaultId,
+        sender: &Sender,
+        evaluation: Evaluation,
+        credit_amount: Credit,
+    ) -> Result<PositionResponse, Error> {
+        let (updated_vault, updated_cdp) = convert_credit(
+            evaluation.current_vault,
+            evaluation.current_cdp,
+            credit_amount,
+            evaluation.redemption_rate,
+        )?;
 
         let mut cmds = vec![];
 
         push_update_vault_position_cmds(
-            &vault_id,
+            vault_id,
             &evaluation.prev_vault,
-            &evaluation.current_vault,
+            &updated_vault,
             &mut cmds,
         );
 
+        cmds.extend(evaluation.yield_fee_accrued.map(Cmd::Event));
+
         push_update_cdp_cmds(
-            &vault_id,
-            &sender,
+            vault_id,
+            sender,
             &evaluation.prev_cdp,
-            &evaluation.current_cdp,
+            &updated_cdp,
             &mut cmds,
         );
 
+        self.push_next_position_seq_cmd(vault_id, sender, &mut cmds);
+
         Ok(PositionResponse {
             cmds,
-            cdp: evaluation.current_cdp,
+            cdp: updated_cdp,
         })
     }
+}
+
+impl<'a> Hub for HubImpl<'a> {
+    fn evaluate(&self, vault_id: VaultId, sender: Sender) -> Result<PositionResponse, Error> {
+        let evaluation = self._evaluate(&vault_id, &sender)?;
+
+        Ok(evaluation_response(&vault_id, &sender, evaluation))
+    }
 
     fn deposit(
         &self,
         vault: VaultId,
         sender: Sender,
         deposit_asset: Asset,
-        DepositAmount(deposit_amount): DepositAmount,
+        deposit_amount: DepositAmount,
         recipient: Recipient,
     ) -> Result<Vec<Cmd>, Error> {
-        if !self.vaults.is_registered(&vault) {
-            return Err(Error::VaultNotRegistered);
-        }
-
-        if !self.vaults.deposits_enabled(&vault) {
-            return Err(Error::DepositsDisabled);
-        }
-
-        if self
-            .vaults
-            .deposit_proxy(&vault)
-            .is_some_and(|proxy| sender != proxy)
-        {
-            return Err(UnauthorizedError.into());
-        }
-
-        if deposit_amount == 0 {
-            return Err(Error::CannotDepositZero);
-        }
-
-        if deposit_asset != self.vaults.deposit_asset(&vault) {
-            return Err(Error::InvalidDepositAsset);
-        }
+        self.value_deposit(&vault, &sender, &deposit_asset, deposit_amount)?;
 
         let PositionResponse { mut cmds, .. } = self.evaluate(vault.clone(), recipient.clone())?;
 
         cmds.push_cmd(VaultCmd::Deposit {
             vault,
             asset: deposit_asset,
-            amount: DepositAmount(deposit_amount),
+            amount: deposit_amount,
             callback_recipient: recipient,
             callback_reason: VaultDepositReason::Deposit,
         });
@@ -1236,86 +3004,96 @@ impl<'a> Hub for HubImpl<'a> {
         Ok(cmds)
     }
 
-    fn advance(
+    fn absorb_shares(
         &self,
         vault_id: VaultId,
         sender: Sender,
-        advance_amount: Debt,
-        recipient: Recipient,
+        shares_asset: Asset,
+        shares_amount: SharesAmount,
     ) -> Result<Vec<Cmd>, Error> {
-        if !self.vaults.is_registered(&vault_id) {
-            return Err(Error::VaultNotRegistered);
-        }
-
-        if !self.vaults.advance_enabled(&vault_id) {
-            return Err(Error::AdvanceDisabled);
-        }
-
-        if advance_amount == 0 {
-            return Err(Error::CannotAdvanceZero);
-        }
+        self.check_deposits_allowed(&vault_id)?;
 
         if self
             .vaults
-            .advance_proxy(&vault_id)
+            .deposit_proxy(&vault_id)
             .is_some_and(|proxy| sender != proxy)
         {
             return Err(UnauthorizedError.into());
         }
 
-        let evaluation = self._evaluate(&vault_id, &recipient)?;
+        if shares_amount.0 == 0 {
+            return Err(Error::CannotDepositZero);
+        }
 
-        let advance_fee_recipient = self.vaults.advance_fee_recipient(&vault_id);
+        if shares_asset != self.vaults.shares_asset(&vault_id) {
+            return Err(Error::InvalidSharesAsset);
+        }
 
-        let Advance {
-            cdp: current_cdp,
-            amount,
-            fee,
-        } = advance(
+        let evaluation = self._evaluate(&vault_id, &sender)?;
+
+        let DepositValue(deposit_value) = evaluation
+            .redemption_rate
+            .map(|rate| rate.shares_to_deposits(shares_amount))
+            .unwrap_or_default();
+
+        if deposit_value == 0 {
+            return Err(Error::CannotDepositZero);
+        }
+
+        self.check_min_deposit(&vault_id, DepositValue(deposit_value))?;
+
+        let (updated_vault, updated_cdp) = deposit_collateral(
+            evaluation.current_vault,
             evaluation.current_cdp,
-            advance_amount,
-            || self.max_ltv(&vault_id),
-            || {
-                advance_fee_recipient
-                    .is_some()
-                    .then(|| self.advance_fee(&vault_id, &recipient))
-            },
-        )
-        .ok_or(Error::NotEnoughCollateral)?;
+            deposit_value,
+            shares_amount,
+        );
 
-        let synthetic = self.vaults.synthetic_asset(&vault_id);
+        self.check_min_collateral(&vault_id, &updated_cdp)?;
 
         let mut cmds = vec![];
 
-        push_update_cdp_cmds(
+        push_update_vault_position_cmds(
             &vault_id,
-            &recipient,
-            &evaluation.prev_cdp,
-            &current_cdp,
+            &evaluation.prev_vault,
+            &updated_vault,
             &mut cmds,
         );
 
-        push_update_vault_position_cmds(
+        cmds.extend(evaluation.yield_fee_accrued.map(Cmd::Event));
+
+        push_update_cdp_cmds(
             &vault_id,
-            &evaluation.prev_vault,
-            &evaluation.current_vault,
+            &sender,
+            &evaluation.prev_cdp,
+            &updated_cdp,
             &mut cmds,
         );
 
-        cmds.push_cmd(MintCmd::Mint {
-            synthetic: synthetic.clone(),
-            amount,
-            recipient,
-        });
+        self.push_next_position_seq_cmd(&vault_id, &sender, &mut cmds);
 
-        if let Some((fee, recipient)) = fee.zip(advance_fee_recipient) {
-            cmds.push_cmd(MintCmd::Mint {
-                synthetic: synthetic.clone(),
-                amount: fee,
-                recipient,
-            });
+        Ok(cmds)
+    }
+
+    fn advance(
+        &self,
+        vault_id: VaultId,
+        sender: Sender,
+        advance_amount: Debt,
+        recipient: Recipient,
+    ) -> Result<Vec<Cmd>, Error> {
+        self.check_advance_allowed(&vault_id, advance_amount)?;
+
+        if self
+            .vaults
+            .advance_proxy(&vault_id)
+            .is_some_and(|proxy| sender != proxy)
+        {
+            return Err(UnauthorizedError.into());
         }
 
+        let Plan { cmds, .. } = self.plan_advance(vault_id, advance_amount, recipient)?;
+
         Ok(cmds)
     }
 
@@ -1338,7 +3116,9 @@ impl<'a> Hub for HubImpl<'a> {
             return Err(Error::InvalidDepositAsset);
         }
 
-        let PositionResponse { mut cmds, cdp } = self.evaluate(vault.clone(), sender.clone())?;
+        let evaluation = self._evaluate_tolerating_loss(&vault, &sender)?;
+
+        let PositionResponse { mut cmds, cdp } = evaluation_response(&vault, &sender, evaluation);
 
         if cdp.debt == 0 {
             return Err(Error::NothingToRepay);
@@ -1362,110 +3142,54 @@ impl<'a> Hub for HubImpl<'a> {
         synthetic_asset: Synthetic,
         synthetic_amount: SyntheticAmount,
     ) -> Result<PositionResponse, Error> {
-        if !self.vaults.is_registered(&vault_id) {
-            return Err(Error::VaultNotRegistered);
-        }
-
-        if synthetic_amount == 0 {
-            return Err(Error::CannotRepayZero);
-        }
-
-        if synthetic_asset != self.vaults.synthetic_asset(&vault_id) {
-            return Err(Error::InvalidSyntheticAsset);
-        }
-
-        let evaluation = self._evaluate(&vault_id, &sender)?;
-
-        if evaluation.current_cdp.debt == 0 {
-            return Err(Error::NothingToRepay);
-        }
-
-        let updated_cdp = repay(evaluation.current_cdp, synthetic_amount);
-
-        let mut cmds = vec![];
-
-        push_update_cdp_cmds(
-            &vault_id,
-            &sender,
-            &evaluation.prev_cdp,
-            &updated_cdp,
-            &mut cmds,
-        );
-
-        push_update_vault_position_cmds(
-            &vault_id,
-            &evaluation.prev_vault,
-            &evaluation.current_vault,
-            &mut cmds,
-        );
+        self._repay_synthetic(vault_id, sender, synthetic_asset, synthetic_amount)
+    }
 
-        cmds.push_cmd(MintCmd::Burn {
-            synthetic: synthetic_asset,
-            amount: synthetic_amount,
-        });
+    fn withdraw_collateral(
+        &self,
+        vault_id: VaultId,
+        sender: Sender,
+        collateral_amount: Collateral,
+    ) -> Result<PositionResponse, Error> {
+        let Plan { cmds, outcome } =
+            self.plan_withdraw_collateral(vault_id, sender, collateral_amount)?;
 
         Ok(PositionResponse {
             cmds,
-            cdp: updated_cdp,
+            cdp: outcome.cdp,
         })
     }
 
-    fn withdraw_collateral(
+    fn withdraw_all_collateral(
         &self,
         vault_id: VaultId,
         sender: Sender,
-        collateral_amount: Collateral,
     ) -> Result<PositionResponse, Error> {
         if !self.vaults.is_registered(&vault_id) {
             return Err(Error::VaultNotRegistered);
         }
 
-        if collateral_amount == 0 {
-            return Err(Error::CannotWithdrawZero);
-        }
-
-        let evaluation = self._evaluate(&vault_id, &sender)?;
-
-        let max_ltv = self.max_ltv(&vault_id);
-
-        let (updated_vault, updated_cdp, shares_amount) = withdraw_collateral(
-            evaluation.current_vault,
-            evaluation.current_cdp,
-            collateral_amount,
-            max_ltv,
-            evaluation.redemption_rate,
-        )?;
-
-        let mut cmds = vec![];
-
-        push_update_vault_position_cmds(
-            &vault_id,
-            &evaluation.prev_vault,
-            &updated_vault,
-            &mut cmds,
-        );
+        let Evaluation { current_cdp, .. } = self._evaluate_tolerating_loss(&vault_id, &sender)?;
 
-        push_update_cdp_cmds(
-            &vault_id,
-            &sender,
-            &evaluation.prev_cdp,
-            &updated_cdp,
-            &mut cmds,
+        let mut collateral_amount = max_withdrawable_collateral(
+            &current_cdp,
+            self.max_ltv(&vault_id),
+            self.checked_ltv_collateral_rate(&vault_id, &current_cdp)?,
         );
 
-        let shares_asset = self.vaults.shares_asset(&vault_id);
+        // a position that cannot be emptied must keep at least the vault minimum
+        if collateral_amount < current_cdp.collateral {
+            if let Some(min) = self.vaults.min_collateral(&vault_id) {
+                collateral_amount =
+                    collateral_amount.min(current_cdp.collateral.saturating_sub(min));
+            }
+        }
 
-        cmds.push_cmd(VaultCmd::Redeem {
-            vault: vault_id,
-            shares: shares_asset,
-            amount: shares_amount,
-            recipient: sender,
-        });
+        if collateral_amount == 0 {
+            return Err(Error::NotEnoughCollateral);
+        }
 
-        Ok(PositionResponse {
-            cmds,
-            cdp: updated_cdp,
-        })
+        self.withdraw_collateral(vault_id, sender, collateral_amount)
     }
 
     fn self_liquidate_position(
@@ -1499,6 +3223,8 @@ impl<'a> Hub for HubImpl<'a> {
             &mut cmds,
         );
 
+        cmds.extend(evaluation.yield_fee_accrued.map(Cmd::Event));
+
         push_update_cdp_cmds(
             &vault_id,
             &sender,
@@ -1507,6 +3233,8 @@ impl<'a> Hub for HubImpl<'a> {
             &mut cmds,
         );
 
+        self.push_next_position_seq_cmd(&vault_id, &sender, &mut cmds);
+
         if let Some(amount) = mint_credit {
             let synthetic = self.vaults.synthetic_asset(&vault_id);
 
@@ -1547,34 +3275,34 @@ impl<'a> Hub for HubImpl<'a> {
 
         let evaluation = self._evaluate(&vault_id, &sender)?;
 
-        let (updated_vault, updated_cdp) = convert_credit(
-            evaluation.current_vault,
-            evaluation.current_cdp,
-            credit_amount,
-            evaluation.redemption_rate,
-        )?;
+        self._convert_credit(&vault_id, &sender, evaluation, credit_amount)
+    }
+
+    fn convert_all_credit(
+        &self,
+        vault_id: VaultId,
+        sender: Sender,
+    ) -> Result<PositionResponse, Error> {
+        if !self.vaults.is_registered(&vault_id) {
+            return Err(Error::VaultNotRegistered);
+        }
+
+        let evaluation = self._evaluate(&vault_id, &sender)?;
 
-        let mut cmds = vec![];
+        if evaluation.current_cdp.credit == 0 {
+            return Err(Error::NothingToConvert);
+        }
 
-        push_update_vault_position_cmds(
-            &vault_id,
-            &evaluation.prev_vault,
-            &updated_vault,
-            &mut cmds,
-        );
+        let credit_amount = evaluation
+            .current_cdp
+            .credit
+            .min(evaluation.current_vault.reserve_pool.quota);
 
-        push_update_cdp_cmds(
-            &vault_id,
-            &sender,
-            &evaluation.prev_cdp,
-            &updated_cdp,
-            &mut cmds,
-        );
+        if credit_amount == 0 {
+            return Err(ConvertCreditError::InsufficientReserves.into());
+        }
 
-        Ok(PositionResponse {
-            cmds,
-            cdp: updated_cdp,
-        })
+        self._convert_credit(&vault_id, &sender, evaluation, credit_amount)
     }
 
     fn redeem_synthetic(
@@ -1607,9 +3335,33 @@ impl<'a> Hub for HubImpl<'a> {
 
         let evaluation = self._evaluate(&vault_id, &sender)?;
 
+        // the fee is left in the reserves, so only the remainder of the burnt synthetics is redeemed
+        let fee_amount = self
+            .vaults
+            .redeem_fee_schedule(&vault_id)
+            .and_then(|schedule| {
+                schedule.fee(
+                    synthetic_amount,
+                    evaluation.current_vault.reserve_pool.quota,
+                )
+            })
+            .map_or(0, |fee| {
+                fee.rate()
+                    .apply_u128(synthetic_amount)
+                    .expect("always: fee <= 100%")
+            });
+
+        let redeem_amount = synthetic_amount
+            .checked_sub(fee_amount)
+            .expect("always: fee amount <= synthetic amount");
+
+        if redeem_amount == 0 {
+            return Err(Error::CannotRedeemZero);
+        }
+
         let (updated_vault, shares_amount) = redeem_reserves(
             evaluation.current_vault,
-            synthetic_amount,
+            redeem_amount,
             evaluation.redemption_rate,
         )?;
 
@@ -1622,6 +3374,8 @@ impl<'a> Hub for HubImpl<'a> {
             &mut cmds,
         );
 
+        cmds.extend(evaluation.yield_fee_accrued.map(Cmd::Event));
+
         push_update_cdp_cmds(
             &vault_id,
             &recipient,
@@ -1709,21 +3463,18 @@ impl<'a> Hub for HubImpl<'a> {
 
                 let current_cdp = self.current_cdp(&vault_id, &recipient);
 
-                let (updated_vault, updated_cdp) = deposit_collateral(
-                    current_vault.clone(),
-                    current_cdp.clone(),
+                let Plan {
+                    cmds: deposit_cmds, ..
+                } = self.plan_deposit_collateral(
+                    &vault_id,
+                    &recipient,
+                    current_vault,
+                    current_cdp,
                     deposit_value,
                     issued_shares,
-                );
-
-                push_update_vault_position_cmds(
-                    &vault_id,
-                    &current_vault,
-                    &updated_vault,
-                    &mut cmds,
-                );
+                )?;
 
-                push_update_cdp_cmds(&vault_id, &recipient, &current_cdp, &updated_cdp, &mut cmds);
+                cmds.extend(deposit_cmds);
             }
 
             VaultDepositReason::RepayUnderlying => {
@@ -1744,6 +3495,8 @@ impl<'a> Hub for HubImpl<'a> {
                 );
 
                 push_update_cdp_cmds(&vault_id, &recipient, &current_cdp, &updated_cdp, &mut cmds);
+
+                self.push_next_position_seq_cmd(&vault_id, &recipient, &mut cmds);
             }
 
             VaultDepositReason::Mint => {
@@ -1767,12 +3520,35 @@ impl<'a> Hub for HubImpl<'a> {
                     recipient,
                 });
             }
+
+            VaultDepositReason::AmoProceeds => {
+                let current_vault = self.current_vault_position(&vault_id);
+
+                let updated_vault = return_amo_proceeds(
+                    current_vault.clone(),
+                    deposit_value,
+                    issued_shares,
+                    self.vaults.amo_allocation(&vault_id).unwrap_or_default(),
+                );
+
+                push_update_vault_position_cmds(
+                    &vault_id,
+                    &current_vault,
+                    &updated_vault,
+                    &mut cmds,
+                );
+            }
         };
 
         Ok(cmds)
     }
 
-    fn claim_treasury_shares(&self, vault_id: VaultId, sender: Sender) -> Result<Vec<Cmd>, Error> {
+    fn claim_treasury_shares(
+        &self,
+        vault_id: VaultId,
+        sender: Sender,
+        amount: Option<SharesAmount>,
+    ) -> Result<Vec<Cmd>, Error> {
         if !self.vaults.is_registered(&vault_id) {
             return Err(Error::VaultNotRegistered);
         }
@@ -1785,7 +3561,8 @@ impl<'a> Hub for HubImpl<'a> {
 
         let evaluation = self._evaluate(&vault_id, &sender)?;
 
-        let (updated_vault, treasury_shares) = claim_treasury_shares(evaluation.current_vault)?;
+        let (updated_vault, treasury_shares) =
+            claim_treasury_shares(evaluation.current_vault, amount)?;
 
         let mut cmds = vec![];
 
@@ -1796,6 +3573,8 @@ impl<'a> Hub for HubImpl<'a> {
             &mut cmds,
         );
 
+        cmds.extend(evaluation.yield_fee_accrued.map(Cmd::Event));
+
         push_update_cdp_cmds(
             &vault_id,
             &sender,
@@ -1839,6 +3618,8 @@ impl<'a> Hub for HubImpl<'a> {
             &mut cmds,
         );
 
+        cmds.extend(evaluation.yield_fee_accrued.map(Cmd::Event));
+
         push_update_cdp_cmds(
             &vault_id,
             &sender,
@@ -1857,6 +3638,380 @@ impl<'a> Hub for HubImpl<'a> {
 
         Ok(cmds)
     }
+
+    fn return_amo_proceeds(
+        &self,
+        vault_id: VaultId,
+        sender: Sender,
+        asset: Asset,
+        amount: u128,
+    ) -> Result<Vec<Cmd>, Error> {
+        if !self.vaults.is_registered(&vault_id) {
+            return Err(Error::VaultNotRegistered);
+        }
+
+        let amo = self.vaults.amo(&vault_id).ok_or(Error::NoAmoSet)?;
+
+        if sender != amo {
+            return Err(UnauthorizedError.into());
+        }
+
+        if amount == 0 {
+            return Err(Error::CannotDepositZero);
+        }
+
+        if asset == self.vaults.shares_asset(&vault_id) {
+            let evaluation = self._evaluate(&vault_id, &sender)?;
+
+            let shares = SharesAmount(amount);
+
+            let DepositValue(value) = evaluation
+                .redemption_rate
+                .map(|rate| rate.shares_to_deposits(shares))
+                .unwrap_or_default();
+
+            if value == 0 {
+                return Err(Error::CannotDepositZero);
+            }
+
+            let updated_vault = return_amo_proceeds(
+                evaluation.current_vault,
+                value,
+                shares,
+                self.vaults.amo_allocation(&vault_id).unwrap_or_default(),
+            );
+
+            let mut cmds = vec![];
+
+            push_update_vault_position_cmds(
+                &vault_id,
+                &evaluation.prev_vault,
+                &updated_vault,
+                &mut cmds,
+            );
+
+            cmds.extend(evaluation.yield_fee_accrued.map(Cmd::Event));
+
+            push_update_cdp_cmds(
+                &vault_id,
+                &sender,
+                &evaluation.prev_cdp,
+                &evaluation.current_cdp,
+                &mut cmds,
+            );
+
+            let SharesAmount(returned) = self
+                .balance_sheet
+                .amo_shares_returned(&vault_id)
+                .unwrap_or_default();
+
+            cmds.push_cmd(BalanceSheetCmd::SetAmoSharesReturned {
+                vault: vault_id,
+                shares: SharesAmount(
+                    returned
+                        .checked_add(amount)
+                        .expect("never: amo shares returned overflow"),
+                ),
+            });
+
+            return Ok(cmds);
+        }
+
+        if asset == self.vaults.deposit_asset(&vault_id) {
+            let PositionResponse { mut cmds, .. } =
+                self.evaluate(vault_id.clone(), sender.clone())?;
+
+            let DepositAmount(returned) = self
+                .balance_sheet
+                .amo_deposits_returned(&vault_id)
+                .unwrap_or_default();
+
+            cmds.push_cmd(BalanceSheetCmd::SetAmoDepositsReturned {
+                vault: vault_id.clone(),
+                amount: DepositAmount(
+                    returned
+                        .checked_add(amount)
+                        .expect("never: amo deposits returned overflow"),
+                ),
+            });
+
+            cmds.push_cmd(VaultCmd::Deposit {
+                vault: vault_id,
+                asset,
+                amount: DepositAmount(amount),
+                callback_recipient: sender,
+                callback_reason: VaultDepositReason::AmoProceeds,
+            });
+
+            return Ok(cmds);
+        }
+
+        Err(Error::InvalidDepositAsset)
+    }
+
+    fn set_position_operator(
+        &self,
+        vault_id: VaultId,
+        sender: Sender,
+        operator: Option<Operator>,
+        max_advance: Option<Debt>,
+        allow_withdraw: bool,
+    ) -> Result<Vec<Cmd>, Error> {
+        if !self.vaults.is_registered(&vault_id) {
+            return Err(Error::VaultNotRegistered);
+        }
+
+        // a new grant starts with nothing advanced against its limit
+        let operator = operator.map(|operator| PositionOperator {
+            operator,
+            max_advance,
+            allow_withdraw,
+            advanced: 0,
+        });
+
+        Ok(cmds![BalanceSheetCmd::SetPositionOperator {
+            vault: vault_id,
+            account: sender,
+            operator,
+        }])
+    }
+
+    fn operator_advance(
+        &self,
+        vault_id: VaultId,
+        sender: Sender,
+        owner: Account,
+        advance_amount: Debt,
+    ) -> Result<Vec<Cmd>, Error> {
+        self.check_advance_allowed(&vault_id, advance_amount)?;
+
+        // proxied vaults only permit advances via their proxy
+        if self.vaults.advance_proxy(&vault_id).is_some() {
+            return Err(UnauthorizedError.into());
+        }
+
+        let grant = self.authorized_operator(&vault_id, &sender, &owner)?;
+
+        let advanced = grant
+            .advanced
+            .checked_add(advance_amount)
+            .filter(|advanced| grant.max_advance.is_none_or(|max| *advanced <= max))
+            .ok_or(Error::OperatorAdvanceLimitExceeded)?;
+
+        let Plan { mut cmds, .. } =
+            self.plan_advance(vault_id.clone(), advance_amount, owner.clone())?;
+
+        cmds.push_cmd(BalanceSheetCmd::SetPositionOperator {
+            vault: vault_id,
+            account: owner,
+            operator: Some(PositionOperator { advanced, ..grant }),
+        });
+
+        Ok(cmds)
+    }
+
+    fn operator_repay_synthetic(
+        &self,
+        vault_id: VaultId,
+        sender: Sender,
+        owner: Account,
+        synthetic_asset: Synthetic,
+        synthetic_amount: SyntheticAmount,
+    ) -> Result<PositionResponse, Error> {
+        self.authorized_operator(&vault_id, &sender, &owner)?;
+
+        self._repay_synthetic(vault_id, owner, synthetic_asset, synthetic_amount)
+    }
+
+    fn operator_withdraw_collateral(
+        &self,
+        vault_id: VaultId,
+        sender: Sender,
+        owner: Account,
+        collateral_amount: Collateral,
+    ) -> Result<PositionResponse, Error> {
+        let grant = self.authorized_operator(&vault_id, &sender, &owner)?;
+
+        if !grant.allow_withdraw {
+            return Err(Error::OperatorWithdrawNotAllowed);
+        }
+
+        let Plan { cmds, outcome } =
+            self.plan_withdraw_collateral(vault_id, owner, collateral_amount)?;
+
+        Ok(PositionResponse {
+            cmds,
+            cdp: outcome.cdp,
+        })
+    }
+
+    fn prune_oracle_fee_cache(
+        &self,
+        vault_id: VaultId,
+        start_after: Option<Recipient>,
+        limit: usize,
+    ) -> Result<Vec<Cmd>, Error> {
+        if !self.vaults.is_registered(&vault_id) {
+            return Err(Error::VaultNotRegistered);
+        }
+
+        let window = self.vaults.oracle_fee_cache_secs(&vault_id);
+
+        let epoch = self.vaults.advance_fee_oracle_epoch(&vault_id);
+
+        let now = self.advance_fee_oracle.now();
+
+        let mut cmds = vec![];
+
+        for recipient in
+            self.vaults
+                .cached_oracle_fee_recipients(&vault_id, start_after.as_ref(), limit)
+        {
+            let cached = self
+                .vaults
+                .cached_oracle_fee(&vault_id, &recipient)
+                .expect("always: listed recipients have a cached oracle fee");
+
+            // every entry is expired once caching is disabled
+            if window.is_some_and(|window| cached.is_fresh(epoch, window, now)) {
+                continue;
+            }
+
+            cmds.push_cmd(VaultCmd::RemoveCachedOracleFee {
+                vault: vault_id.clone(),
+                recipient,
+            });
+        }
+
+        Ok(cmds)
+    }
+
+    fn simulate_deposit(
+        &self,
+        vault_id: VaultId,
+        sender: Sender,
+        deposit_asset: Asset,
+        deposit_amount: DepositAmount,
+        recipient: Recipient,
+    ) -> Result<DepositOutcome, Error> {
+        let DepositValue(deposit_value) =
+            self.value_deposit(&vault_id, &sender, &deposit_asset, deposit_amount)?;
+
+        let evaluation = self._evaluate(&vault_id, &recipient)?;
+
+        // the vault issues the shares upon deposit, estimate them at the current redemption rate
+        let issued_shares = evaluation
+            .redemption_rate
+            .map(|rate| rate.deposits_to_shares(DepositValue(deposit_value)))
+            .unwrap_or_default();
+
+        let Plan { outcome, .. } = self.plan_deposit_collateral(
+            &vault_id,
+            &recipient,
+            evaluation.current_vault,
+            evaluation.current_cdp,
+            deposit_value,
+            issued_shares,
+        )?;
+
+        Ok(outcome)
+    }
+
+    fn simulate_advance(
+        &self,
+        vault_id: VaultId,
+        advance_amount: Debt,
+        recipient: Recipient,
+    ) -> Result<AdvanceOutcome, Error> {
+        self.check_advance_allowed(&vault_id, advance_amount)?;
+
+        let Plan { outcome, .. } = self.plan_advance(vault_id, advance_amount, recipient)?;
+
+        Ok(outcome)
+    }
+
+    fn simulate_withdraw(
+        &self,
+        vault_id: VaultId,
+        sender: Sender,
+        collateral_amount: Collateral,
+    ) -> Result<WithdrawOutcome, Error> {
+        let Plan { outcome, .. } =
+            self.plan_withdraw_collateral(vault_id, sender, collateral_amount)?;
+
+        Ok(outcome)
+    }
+
+    fn effective_advance_fee(
+        &self,
+        vault_id: VaultId,
+        account: Account,
+        advance_amount: Debt,
+    ) -> Result<EffectiveAdvanceFee, Error> {
+        if !self.vaults.is_registered(&vault_id) {
+            return Err(Error::VaultNotRegistered);
+        }
+
+        // without a fee recipient no fee is resolved when advancing, regardless of any configured fee
+        if self.vaults.advance_fee_recipient(&vault_id).is_none() {
+            return Ok(EffectiveAdvanceFee {
+                source: AdvanceFeeSource::None,
+                fee: AdvanceFee::new(0).expect("always: zero fee is valid"),
+                amount: 0,
+            });
+        }
+
+        let evaluation = self._evaluate(&vault_id, &account)?;
+
+        // credit use is not charged, only the debt increase
+        let debt_increase = advance_amount.saturating_sub(evaluation.current_cdp.credit);
+
+        let (source, fee, _) = self.resolve_advance_fee(&vault_id, &account);
+
+        let amount = fee
+            .rate()
+            .apply_u128(debt_increase)
+            .expect("always: fee <= 100%");
+
+        Ok(EffectiveAdvanceFee {
+            source,
+            fee,
+            amount,
+        })
+    }
+
+    fn advance_fees(
+        &self,
+        vault_id: VaultId,
+        recipients: Vec<Recipient>,
+    ) -> Result<Vec<AdvanceFee>, Error> {
+        if !self.vaults.is_registered(&vault_id) {
+            return Err(Error::VaultNotRegistered);
+        }
+
+        let (fees, _) = self.resolve_advance_fees(&vault_id, &recipients);
+
+        Ok(fees)
+    }
+
+    fn position_ltv(&self, vault_id: VaultId, account: Account) -> Result<PositionLtv, Error> {
+        if !self.vaults.is_registered(&vault_id) {
+            return Err(Error::VaultNotRegistered);
+        }
+
+        let evaluation = self._evaluate(&vault_id, &account)?;
+
+        let Cdp {
+            collateral, debt, ..
+        } = evaluation.current_cdp;
+
+        let collateral = ltv_collateral(collateral, self.ltv_collateral_rate(&vault_id).0);
+
+        Ok(PositionLtv {
+            ltv: ltv(collateral, debt),
+            max_ltv: self.max_ltv(&vault_id),
+        })
+    }
 }
 
 impl From<MintCmd> for Cmd {
@@ -1877,5 +4032,11 @@ impl From<BalanceSheetCmd> for Cmd {
     }
 }
 
+impl From<BalanceSheetEvent> for Cmd {
+    fn from(v: BalanceSheetEvent) -> Self {
+        Self::Event(v)
+    }
+}
+
 #[cfg(test)]
 mod test;
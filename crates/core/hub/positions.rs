@@ -5,7 +5,9 @@ use crate::{
     Rate,
 };
 
-use super::{AdvanceFee, AmoAllocation, CollateralYieldFee, MaxLtv, ReserveYieldFee};
+use super::{
+    AdvanceFee, AmoAllocation, CollateralYieldFee, LossTolerance, MaxLtv, ReserveYieldFee,
+};
 
 #[derive(Debug, PartialEq, Eq, thiserror::Error)]
 #[error("vault shares have suffered a loss in value")]
@@ -57,6 +59,39 @@ impl SumPaymentRatio {
     }
 }
 
+/// Π v/q - where v is the collateral pool value and q is the collateral pool quota at the time of an acknowledged loss
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct CollateralFactor(FixedU256);
+
+impl CollateralFactor {
+    pub const fn raw(x: U256) -> Self {
+        Self(FixedU256::raw(x))
+    }
+
+    pub fn one() -> Self {
+        Self(FixedU256::from_u128(1))
+    }
+
+    pub fn is_one(&self) -> bool {
+        *self == Self::one()
+    }
+
+    pub const fn fixed_u256(self) -> FixedU256 {
+        self.0
+    }
+
+    pub const fn into_raw(self) -> U256 {
+        self.0.into_raw()
+    }
+}
+
+impl Default for CollateralFactor {
+    fn default() -> Self {
+        Self::one()
+    }
+}
+
 // NOTE: `Vault` & `Cdp` intentionally do not implement `Copy`.
 // Updates should consume the old value to avoid mistakenly using a stale binding.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -66,6 +101,7 @@ pub struct Vault {
     pub treasury_shares: TreasuryShares,
     pub amo_shares: AmoShares,
     pub spr: SumPaymentRatio,
+    pub collateral_factor: CollateralFactor,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -75,6 +111,8 @@ pub struct Cdp {
     pub debt: Debt,
     pub credit: Credit,
     pub spr: SumPaymentRatio,
+    #[cfg_attr(test, serde(skip_serializing_if = "CollateralFactor::is_one"))]
+    pub collateral_factor: CollateralFactor,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -125,6 +163,14 @@ fn share_pool_surplus(
     Ok(Some(Surplus { shares }))
 }
 
+/// The treasury shares taken as fees from a vault's yield, by the pool the yield accrued to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct YieldFees {
+    pub collateral: TreasuryShares,
+    pub reserve: TreasuryShares,
+}
+
 struct Payments {
     treasury_shares: SharesAmount,
     amo_shares: SharesAmount,
@@ -143,6 +189,8 @@ impl std::ops::Add for Payments {
     }
 }
 
+// Shares are floored rather than rounded to nearest: rounding up takes a share unit from the reserve,
+// and since shares are valued rounding down, the reserve then credits a whole deposit unit less.
 fn payments(surplus: Surplus, treasury_fee: Rate, amo_allocation: Rate) -> Payments {
     let SharesAmount(surplus_shares) = surplus.shares;
 
@@ -225,11 +273,22 @@ fn increase_sum_payment_ratio(
     spr: SumPaymentRatio,
     payment: Debt,
     collateral: Collateral,
+    collateral_factor: CollateralFactor,
 ) -> SumPaymentRatio {
     let increase = FixedU256::from_u128(payment)
         .checked_div(FixedU256::from_u128(collateral))
         .expect("checked: collateral > 0");
 
+    // payments accrue per unit of collateral as it was prior to any acknowledged losses,
+    // so positions are paid without first being rescaled, see `update_cdp`
+    let increase = if collateral_factor.is_one() {
+        increase
+    } else {
+        increase
+            .checked_mul(collateral_factor.fixed_u256())
+            .expect("always: collateral factor <= 1")
+    };
+
     spr.fixed_u256()
         .checked_add(increase)
         .map(SumPaymentRatio)
@@ -241,7 +300,12 @@ fn increase_vault_spr(mut vault: Vault, payment: Debt) -> Vault {
         return vault;
     }
 
-    vault.spr = increase_sum_payment_ratio(vault.spr, payment, vault.collateral_pool.quota);
+    vault.spr = increase_sum_payment_ratio(
+        vault.spr,
+        payment,
+        vault.collateral_pool.quota,
+        vault.collateral_factor,
+    );
 
     vault
 }
@@ -259,7 +323,7 @@ where
     }
 }
 
-/// If vault shares have increased in value, perform re-balancing accordingly and return `Ok(Some(<updated vault position>))`.
+/// If vault shares have increased in value, perform re-balancing accordingly and return `Ok(Some((<updated vault position>, <yield fees>)))`.
 /// In the case of where there is no increase in value, Ok(None) is returned.
 /// `Err(LossError)` is returned if a loss in share value in detected.
 pub fn update_vault(
@@ -268,7 +332,7 @@ pub fn update_vault(
     amo_allocation: impl Lazy<AmoAllocation>,
     collateral_treasury_fee: impl Lazy<CollateralYieldFee>,
     reserve_treasury_fee: impl Lazy<ReserveYieldFee>,
-) -> Result<Option<Vault>, LossError> {
+) -> Result<Option<(Vault, YieldFees)>, LossError> {
     // Step 1: get the current vault shares redemption rate (if one exists otherwise no update can occur)
     let Some(redemption_rate) = redemption_rate else {
         return Ok(None);
@@ -297,16 +361,32 @@ pub fn update_vault(
     // - Treasury shares (unclaimed)
     // - AMO shares (unclaimed)
     // - Reserve Pool
-    let payments = match status {
+    let (payments, fees) = match status {
         Status::CollateralYieldOnly(surplus) => {
             let treasury_fee = collateral_treasury_fee.get();
 
-            payments(surplus, treasury_fee.rate(), amo_allocation.rate())
+            let payments = payments(surplus, treasury_fee.rate(), amo_allocation.rate());
+
+            let fees = YieldFees {
+                collateral: payments.treasury_shares,
+                ..Default::default()
+            };
+
+            (payments, fees)
         }
 
         // In this case the treasury fee is always 100% (i.e. 1.0) because it implies there are no
         // collateral pool entrants.
-        Status::ReserveYieldOnly(surplus) => payments(surplus, Rate::one(), amo_allocation.rate()),
+        Status::ReserveYieldOnly(surplus) => {
+            let payments = payments(surplus, Rate::one(), amo_allocation.rate());
+
+            let fees = YieldFees {
+                reserve: payments.treasury_shares,
+                ..Default::default()
+            };
+
+            (payments, fees)
+        }
 
         // combine the payments arising from each set of surplus & fees together
         Status::Both {
@@ -316,8 +396,15 @@ pub fn update_vault(
             let cp_treasury_fee = collateral_treasury_fee.get();
             let rp_treasury_fee = reserve_treasury_fee.get();
 
-            payments(collateral, cp_treasury_fee.rate(), amo_allocation.rate())
-                + payments(reserve, rp_treasury_fee.rate(), amo_allocation.rate())
+            let cp_payments = payments(collateral, cp_treasury_fee.rate(), amo_allocation.rate());
+            let rp_payments = payments(reserve, rp_treasury_fee.rate(), amo_allocation.rate());
+
+            let fees = YieldFees {
+                collateral: cp_payments.treasury_shares,
+                reserve: rp_payments.treasury_shares,
+            };
+
+            (cp_payments + rp_payments, fees)
         }
     };
 
@@ -340,7 +427,91 @@ pub fn update_vault(
     // Step 7: increase the sum payment ratio by total debt paymebt / collateral pool quota
     let vault = increase_vault_spr(vault, total_debt_payment);
 
-    Ok(Some(vault))
+    Ok(Some((vault, fees)))
+}
+
+// Returns true if the value of the pool's shares is no more than `tolerance` below its quota
+fn share_pool_loss_tolerated(
+    pool: SharesPool,
+    redemption_rate: RedemptionRate,
+    tolerance: LossTolerance,
+) -> bool {
+    let DepositValue(pool_shares_value) = redemption_rate.shares_to_deposits(pool.shares);
+
+    let Some(loss) = pool
+        .quota
+        .checked_sub(pool_shares_value)
+        .filter(|loss| *loss > 0)
+    else {
+        return true;
+    };
+
+    Rate::from_ratio(loss, pool.quota).expect("checked: quota > loss > 0") <= tolerance.rate()
+}
+
+/// Returns true if any loss in the value of the vault's collateral & reserve shares is within the `tolerance`
+pub fn loss_within_tolerance(
+    vault: &Vault,
+    redemption_rate: RedemptionRate,
+    tolerance: LossTolerance,
+) -> bool {
+    share_pool_loss_tolerated(vault.collateral_pool, redemption_rate, tolerance)
+        && share_pool_loss_tolerated(vault.reserve_pool, redemption_rate, tolerance)
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum AcknowledgeLossError {
+    #[error("no loss to acknowledge")]
+    NoLoss,
+    #[error("cannot acknowledge a total loss of collateral")]
+    TotalCollateralLoss,
+}
+
+/// Rebase the vault's collateral & reserve balances to the current value of their shares, realizing any loss.
+/// The collateral loss is borne by every position in proportion to its collateral,
+/// applied to each position on its next update via the collateral factor.
+pub fn acknowledge_loss(
+    mut vault: Vault,
+    redemption_rate: RedemptionRate,
+) -> Result<Vault, AcknowledgeLossError> {
+    let DepositValue(collateral_value) =
+        redemption_rate.shares_to_deposits(vault.collateral_pool.shares);
+
+    let DepositValue(reserve_value) = redemption_rate.shares_to_deposits(vault.reserve_pool.shares);
+
+    let collateral_loss = collateral_value < vault.collateral_pool.quota;
+
+    let reserve_loss = reserve_value < vault.reserve_pool.quota;
+
+    if !collateral_loss && !reserve_loss {
+        return Err(AcknowledgeLossError::NoLoss);
+    }
+
+    if collateral_loss {
+        // positions cannot be rescaled from a zero collateral factor
+        if collateral_value == 0 {
+            return Err(AcknowledgeLossError::TotalCollateralLoss);
+        }
+
+        let ratio = FixedU256::from_u128(collateral_value)
+            .checked_div(FixedU256::from_u128(vault.collateral_pool.quota))
+            .expect("checked: collateral quota > collateral value");
+
+        vault.collateral_factor = vault
+            .collateral_factor
+            .fixed_u256()
+            .checked_mul(ratio)
+            .map(CollateralFactor)
+            .expect("always: ratio < 1");
+
+        vault.collateral_pool.quota = collateral_value;
+    }
+
+    if reserve_loss {
+        vault.reserve_pool.quota = reserve_value;
+    }
+
+    Ok(vault)
 }
 
 pub fn repay(cdp: Cdp, amount: Debt) -> Cdp {
@@ -373,27 +544,101 @@ pub fn update_cdp(vault: &Vault, cdp: Cdp) -> Cdp {
     let cdp_spr = cdp.spr.fixed_u256();
 
     // check if CDP is already up-to-date
-    if vault_spr == cdp_spr {
+    if vault_spr == cdp_spr && vault.collateral_factor == cdp.collateral_factor {
         return cdp;
     }
 
-    let diff = vault_spr
-        .checked_sub(cdp_spr)
-        .expect("always: vault spr >= account spr");
-
-    let debt_payment = diff
-        .checked_mul(FixedU256::from_u128(cdp.collateral))
-        .expect("never: account debt payment overflow")
-        .floor();
+    let debt_payment = sum_payment(cdp.spr, vault.spr, cdp.collateral, cdp.collateral_factor);
 
     let cdp = repay(cdp, debt_payment);
 
+    let collateral = rescale_collateral(
+        cdp.collateral,
+        cdp.collateral_factor,
+        vault.collateral_factor,
+    );
+
     Cdp {
+        collateral,
         spr: vault.spr,
+        collateral_factor: vault.collateral_factor,
         ..cdp
     }
 }
 
+/// Returns the debt payment accrued by `collateral` recorded at `collateral_factor`
+/// as the sum payment ratio rose `from` one ratio `to` another
+pub fn sum_payment(
+    from: SumPaymentRatio,
+    to: SumPaymentRatio,
+    collateral: Collateral,
+    collateral_factor: CollateralFactor,
+) -> Debt {
+    let diff = to
+        .fixed_u256()
+        .checked_sub(from.fixed_u256())
+        .expect("always: sum payment ratio only increases");
+
+    // the sum payment ratio is in terms of collateral prior to any acknowledged losses
+    if collateral_factor.is_one() {
+        diff.checked_mul_u128(collateral)
+    } else {
+        diff.checked_div(collateral_factor.fixed_u256())
+            .and_then(|diff| diff.checked_mul_u128(collateral))
+    }
+    .expect("never: debt payment overflow")
+}
+
+// Rescale `collateral` recorded at the `from` factor to the `to` factor, bearing any losses acknowledged in between
+fn rescale_collateral(
+    collateral: Collateral,
+    from: CollateralFactor,
+    to: CollateralFactor,
+) -> Collateral {
+    if from == to {
+        return collateral;
+    }
+
+    to.fixed_u256()
+        .checked_div(from.fixed_u256())
+        .and_then(|ratio| ratio.checked_mul_u128(collateral))
+        .expect("always: collateral factor only decreases & is never zero")
+}
+
+/// Returns the `collateral` valued for LTV purposes, applying the strategy's redemption rate if it reports one.
+/// Collateral is otherwise valued mark-to-market, as it is for yield skimming.
+pub fn ltv_collateral(
+    collateral: Collateral,
+    strategy_redemption_rate: Option<FixedU256>,
+) -> Collateral {
+    let Some(rate) = strategy_redemption_rate else {
+        return collateral;
+    };
+
+    rate.checked_mul_u128(collateral)
+        .expect("collateral valued at the strategy redemption rate will not overflow 128 bits")
+}
+
+/// The loan-to-value ratio of a position with `collateral` valued for LTV purposes and `debt`.
+/// Returns `None` if there is debt against no collateral, i.e. the LTV is infinite.
+pub fn ltv(collateral: Collateral, debt: Debt) -> Option<Rate> {
+    if debt == 0 {
+        return Rate::from_ratio(0, 1);
+    }
+
+    Rate::from_ratio(debt, collateral)
+}
+
+/// The most debt that can be added to a position with `collateral` valued for LTV purposes and `debt`
+/// without exceeding the `max_ltv`
+pub fn max_borrowable(collateral: Collateral, debt: Debt, max_ltv: MaxLtv) -> u128 {
+    max_ltv
+        .rate()
+        .apply_u128(collateral)
+        .expect("always: max ltv <= 100%")
+        .saturating_sub(debt)
+}
+
 pub struct Advance {
     /// The updated CDP
     pub cdp: Cdp,
@@ -407,6 +652,7 @@ pub fn advance(
     cdp: Cdp,
     amount: Debt,
     max_ltv: impl Lazy<MaxLtv>,
+    strategy_redemption_rate: impl Lazy<Option<FixedU256>>,
     advance_fee: impl Lazy<Option<AdvanceFee>>,
 ) -> Option<Advance> {
     // check if amount falls wholely within available credit
@@ -428,23 +674,23 @@ pub fn advance(
 
     let debt = cdp.debt.checked_add(debt_increase)?;
 
+    let collateral = ltv_collateral(cdp.collateral, strategy_redemption_rate.get());
+
     // reject anything over 100% LTV
-    if cdp.collateral < debt {
+    if collateral < debt {
         return None;
     }
 
-    // calculate max debt balance allowed based on collateral and Max LTV
-    let max_debt = max_ltv
-        .get()
-        .rate()
-        .apply_u128(cdp.collateral)
-        .expect("always: max ltv <= 100%");
+    let borrowable = max_borrowable(collateral, cdp.debt, max_ltv.get());
 
     // reject anything over Max LTV
-    if debt > max_debt {
+    if debt_increase > borrowable {
         return None;
     }
 
+    // the max debt balance allowed based on collateral and Max LTV
+    let max_debt = safe_add!(cdp.debt, borrowable);
+
     // check if there is to be an advance fee applied
     let Some(advance_fee) = advance_fee.get() else {
         // nothing left to do if not
@@ -501,7 +747,12 @@ pub fn advance(
     })
 }
 
-fn withdraw_cdp_collateral(cdp: Cdp, max_ltv: MaxLtv, amount: Collateral) -> Option<Cdp> {
+fn withdraw_cdp_collateral(
+    cdp: Cdp,
+    max_ltv: MaxLtv,
+    strategy_redemption_rate: Option<FixedU256>,
+    amount: Collateral,
+) -> Option<Cdp> {
     // reject if amount greater than deposited collateral
     if amount > cdp.collateral {
         return None;
@@ -517,12 +768,15 @@ fn withdraw_cdp_collateral(cdp: Cdp, max_ltv: MaxLtv, amount: Collateral) -> Opt
         return Some(Cdp { collateral, ..cdp });
     }
 
+    let remaining_ltv_collateral = ltv_collateral(collateral, strategy_redemption_rate);
+
     // reject if 100% LTV exceeded
-    if collateral < cdp.debt {
+    if remaining_ltv_collateral < cdp.debt {
         return None;
     }
 
-    let proposed_ltv = Rate::from_ratio(cdp.debt, collateral).expect("checked: collateral > 0");
+    let proposed_ltv =
+        ltv(remaining_ltv_collateral, cdp.debt).expect("checked: remaining ltv collateral > 0");
 
     // reject if max LTV exceeded
     if proposed_ltv > max_ltv.rate() {
@@ -532,12 +786,54 @@ fn withdraw_cdp_collateral(cdp: Cdp, max_ltv: MaxLtv, amount: Collateral) -> Opt
     Some(Cdp { collateral, ..cdp })
 }
 
+/// The most collateral that can be withdrawn from the CDP without exceeding the max LTV
+pub fn max_withdrawable_collateral(
+    cdp: &Cdp,
+    max_ltv: MaxLtv,
+    strategy_redemption_rate: Option<FixedU256>,
+) -> Collateral {
+    if cdp.debt == 0 {
+        return cdp.collateral;
+    }
+
+    // withdrawing more only ever raises the LTV, so search for the largest amount still allowed
+    let (mut lo, mut hi) = (0, cdp.collateral);
+
+    while lo < hi {
+        let mid = lo + (hi - lo).div_ceil(2);
+
+        if withdraw_cdp_collateral(cdp.clone(), max_ltv, strategy_redemption_rate, mid).is_some() {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+
+    lo
+}
+
 fn withdraw_vault_collateral(
     mut vault: Vault,
     redemption_rate: RedemptionRate,
     amount: Collateral,
 ) -> Option<(Vault, SharesAmount)> {
-    let SharesAmount(shares) = redemption_rate.checked_deposits_to_shares(DepositValue(amount))?;
+    let DepositValue(pool_value) = redemption_rate.shares_to_deposits(vault.collateral_pool.shares);
+
+    let SharesAmount(mut shares) = if pool_value < vault.collateral_pool.quota {
+        // while at a (tolerated) loss, collateral is redeemed for its pro-rata share of the pool,
+        // so that the loss is borne by every position in proportion to its collateral
+        let shares = U256::from(vault.collateral_pool.shares.0)
+            .checked_mul_div(amount, vault.collateral_pool.quota)?;
+
+        SharesAmount(u128::try_from(shares).ok()?)
+    } else {
+        redemption_rate.checked_deposits_to_shares(DepositValue(amount))?
+    };
+
+    // the last of the pool's collateral takes any rounding residue in shares with it
+    if amount == vault.collateral_pool.quota && shares <= vault.collateral_pool.shares.0 {
+        shares = vault.collateral_pool.shares.0;
+    }
 
     vault.collateral_pool.shares = vault
         .collateral_pool
@@ -600,9 +896,10 @@ pub fn withdraw_collateral(
     cdp: Cdp,
     amount: Collateral,
     max_ltv: MaxLtv,
+    strategy_redemption_rate: Option<FixedU256>,
     redemption_rate: Option<RedemptionRate>,
 ) -> Result<(Vault, Cdp, SharesAmount), WithdrawCollateralError> {
-    let cdp = withdraw_cdp_collateral(cdp, max_ltv, amount)
+    let cdp = withdraw_cdp_collateral(cdp, max_ltv, strategy_redemption_rate, amount)
         .ok_or(WithdrawCollateralError::NotEnoughCollateral)?;
 
     let redemption_rate = redemption_rate.ok_or(LossError)?;
@@ -639,11 +936,12 @@ pub fn self_liquidate(
         return Err(SelfLiquidateError::NothingToLiquidate);
     }
 
+    // the position remains settled up to the current sum payment ratio
     let zeroed_cdp = Cdp {
         collateral: 0,
         debt: 0,
         credit: 0,
-        spr: SumPaymentRatio::zero(),
+        ..cdp
     };
 
     let redemption_rate = redemption_rate.ok_or(LossError)?;
@@ -772,6 +1070,63 @@ pub fn redeem_reserves(
     Ok((vault, shares))
 }
 
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum SweepDustError {
+    #[error(transparent)]
+    VaultLoss(#[from] LossError),
+    #[error("no dust to sweep")]
+    NoDust,
+    #[error("the collateral pool cannot spare the shares backing the dust")]
+    UnbackedDust,
+}
+
+/// Move the collateral pool balance not attributed to any position, i.e. in excess of the `total_account_collateral`,
+/// into the reserve pool along with the shares backing it.
+pub fn sweep_dust(
+    mut vault: Vault,
+    total_account_collateral: Collateral,
+    redemption_rate: Option<RedemptionRate>,
+) -> Result<Vault, SweepDustError> {
+    let dust = vault
+        .collateral_pool
+        .quota
+        .checked_sub(total_account_collateral)
+        .filter(|dust| *dust > 0)
+        .ok_or(SweepDustError::NoDust)?;
+
+    let redemption_rate = redemption_rate.ok_or(LossError)?;
+
+    // the swept shares are rounded up so that they fully back the dust credited to the reserves,
+    // without any attributed collateral the whole pool is swept
+    let SharesAmount(shares) = if total_account_collateral == 0 {
+        vault.collateral_pool.shares
+    } else {
+        redemption_rate
+            .checked_deposits_to_shares_ceil(DepositValue(dust))
+            .ok_or(LossError)?
+    };
+
+    vault.collateral_pool.shares = vault
+        .collateral_pool
+        .shares
+        .0
+        .checked_sub(shares)
+        .map(SharesAmount)
+        .ok_or(SweepDustError::UnbackedDust)?;
+
+    vault.collateral_pool.quota = total_account_collateral;
+
+    // the attributed collateral must remain fully backed by the shares left in the pool
+    let DepositValue(remaining_value) =
+        redemption_rate.shares_to_deposits(vault.collateral_pool.shares);
+
+    if remaining_value < total_account_collateral {
+        return Err(SweepDustError::UnbackedDust);
+    }
+
+    Ok(add_vault_reserves(vault, dust, SharesAmount(shares)))
+}
+
 pub fn deposit_collateral(
     vault: Vault,
     mut cdp: Cdp,
@@ -789,19 +1144,37 @@ pub fn deposit_collateral(
 #[error("nothing to claim")]
 pub struct NothingToClaimError;
 
-pub fn claim_treasury_shares(vault: Vault) -> Result<(Vault, SharesAmount), NothingToClaimError> {
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ClaimTreasurySharesError {
+    #[error(transparent)]
+    NothingToClaim(#[from] NothingToClaimError),
+    #[error("not enough treasury shares")]
+    NotEnoughTreasuryShares,
+}
+
+/// Claim `amount` of the treasury shares, or all of them if `None`
+pub fn claim_treasury_shares(
+    vault: Vault,
+    amount: Option<SharesAmount>,
+) -> Result<(Vault, SharesAmount), ClaimTreasurySharesError> {
     let SharesAmount(shares) = vault.treasury_shares;
 
-    if shares == 0 {
-        return Err(NothingToClaimError);
+    let SharesAmount(claim) = amount.unwrap_or(SharesAmount(shares));
+
+    if shares == 0 || claim == 0 {
+        return Err(NothingToClaimError.into());
     }
 
+    let remaining = shares
+        .checked_sub(claim)
+        .ok_or(ClaimTreasurySharesError::NotEnoughTreasuryShares)?;
+
     let vault = Vault {
-        treasury_shares: SharesAmount(0),
+        treasury_shares: SharesAmount(remaining),
         ..vault
     };
 
-    Ok((vault, SharesAmount(shares)))
+    Ok((vault, SharesAmount(claim)))
 }
 
 pub fn claim_amo_shares(vault: Vault) -> Result<(Vault, SharesAmount), NothingToClaimError> {
@@ -818,3 +1191,40 @@ pub fn claim_amo_shares(vault: Vault) -> Result<(Vault, SharesAmount), NothingTo
 
     Ok((vault, SharesAmount(shares)))
 }
+
+/// Return AMO proceeds to the vault's depositors, split per the AMO allocation:
+/// - the allocated portion is added to the reserves and paid out as a reduction in debt
+/// - the remainder is credited to the collateral pool's shares, accruing as collateral yield on the next evaluation
+///
+/// If there is no collateral in the vault, the proceeds remain in the reserves.
+pub fn return_amo_proceeds(
+    vault: Vault,
+    amount: Collateral,
+    SharesAmount(shares): SharesAmount,
+    amo_allocation: AmoAllocation,
+) -> Vault {
+    if vault.collateral_pool.quota == 0 {
+        return add_vault_reserves(vault, amount, SharesAmount(shares));
+    }
+
+    let reserve_amount = amo_allocation
+        .rate()
+        .apply_u128(amount)
+        .expect("always: amo allocation <= 100%");
+
+    let reserve_shares = amo_allocation
+        .rate()
+        .apply_u128(shares)
+        .expect("always: amo allocation <= 100%");
+
+    let collateral_shares = shares
+        .checked_sub(reserve_shares)
+        .expect("always: reserve shares <= returned shares");
+
+    let mut vault = add_vault_reserves(vault, reserve_amount, SharesAmount(reserve_shares));
+
+    vault.collateral_pool.shares =
+        SharesAmount(safe_add!(vault.collateral_pool.shares.0, collateral_shares));
+
+    increase_vault_spr(vault, reserve_amount)
+}
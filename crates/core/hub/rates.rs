@@ -70,3 +70,97 @@ bps_rate!(
     max = percent!(100),
     default = 0 // bps: 0.0%
 );
+
+bps_rate!(
+    RedeemFee,
+    max = percent!(100),
+    default = 0 // bps: 0.0%
+);
+
+bps_rate!(
+    LossTolerance,
+    max = percent!(10),
+    default = 0 // bps: 0.0%
+);
+
+/// A redeem fee charged on redemptions taking at least `threshold` of the reserves
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct RedeemFeeTier {
+    threshold_bps: u32,
+    threshold: crate::Rate,
+    fee: RedeemFee,
+}
+
+impl RedeemFeeTier {
+    pub fn threshold_bps(self) -> u32 {
+        self.threshold_bps
+    }
+
+    pub fn fee(self) -> RedeemFee {
+        self.fee
+    }
+}
+
+/// Redeem fee tiers ordered by ascending reserve share threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct RedeemFeeSchedule(Vec<RedeemFeeTier>);
+
+impl RedeemFeeSchedule {
+    /// Returns `Some(schedule)` for non-empty `(threshold_bps, fee_bps)` tiers with strictly increasing thresholds
+    /// and non-decreasing fees, all within 100%, otherwise `None`
+    pub fn new(tiers: &[(u32, u32)]) -> Option<Self> {
+        if tiers.is_empty() {
+            return None;
+        }
+
+        let mut schedule = Vec::with_capacity(tiers.len());
+
+        for &(threshold_bps, fee_bps) in tiers {
+            if threshold_bps > percent!(100) {
+                return None;
+            }
+
+            let fee = RedeemFee::new(fee_bps)?;
+
+            if let Some(prev) = schedule.last().copied() {
+                let RedeemFeeTier {
+                    threshold_bps: prev_threshold_bps,
+                    fee: prev_fee,
+                    ..
+                } = prev;
+
+                if threshold_bps <= prev_threshold_bps || fee_bps < prev_fee.raw() {
+                    return None;
+                }
+            }
+
+            let threshold = crate::Rate::from_ratio(threshold_bps.into(), 10_000).unwrap();
+
+            schedule.push(RedeemFeeTier {
+                threshold_bps,
+                threshold,
+                fee,
+            });
+        }
+
+        Some(Self(schedule))
+    }
+
+    pub fn tiers(&self) -> &[RedeemFeeTier] {
+        &self.0
+    }
+
+    /// Returns the fee of the highest tier whose threshold is met by `amount` as a share of `reserves`, if any
+    pub(crate) fn fee(&self, amount: u128, reserves: u128) -> Option<RedeemFee> {
+        // redeeming from empty reserves fails regardless, treat it as draining them
+        let share = crate::Rate::from_ratio(amount, reserves).unwrap_or_else(crate::Rate::one);
+
+        self.0
+            .iter()
+            .rev()
+            .find(|tier| share >= tier.threshold)
+            .map(|tier| tier.fee)
+    }
+}
@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, HashMap},
+};
 
 use num::FixedU256;
 use test_utils::prelude::*;
@@ -9,10 +12,14 @@ use super::*;
 
 const ALREADY_REGISTERED_VAULT: &str = "already_registered_vault";
 const SENDER: &str = "sender";
+const OPERATOR: &str = "operator";
 const SYNTHETIC: &str = "synthetic";
 const EIGHT_DECIMAL_SYNTHETIC: &str = "eight_decimal_synthetic";
+const SIX_DECIMAL_SHARES_VAULT: &str = "six_decimal_shares_vault";
+const NEXT_MAJOR_INTERFACE_VAULT: &str = "next_major_interface_vault";
 const VAULT: &str = "vault";
 const VAULT_DEPOSIT_ASSET: &str = "vault_deposit_asset";
+const VAULT_SECONDARY_DEPOSIT_ASSET: &str = "vault_secondary_deposit_asset";
 const VAULT_SHARES_ASSET: &str = "vault_shares_asset";
 
 const fn shares_amount(n: u128) -> SharesAmount {
@@ -24,12 +31,31 @@ struct VaultMeta {
     deposits_enabled: bool,
     advance_enabled: bool,
     advance_fee_oracle: Option<Oracle>,
+    fixed_advance_fee: Option<AdvanceFee>,
     advance_fee_recipient: Option<Recipient>,
+    advance_fee_oracle_epoch: OracleEpoch,
+    oracle_fee_cache_secs: Option<CacheWindowSecs>,
+    cached_oracle_fees: BTreeMap<String, CachedAdvanceFee>,
     amo: Option<Amo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amo_allocation: Option<AmoAllocation>,
     deposit_proxy: Option<Proxy>,
     advance_proxy: Option<Proxy>,
     mint_proxy: Option<Proxy>,
     redeem_proxy: Option<Proxy>,
+    min_collateral: Option<Collateral>,
+    min_debt: Option<Debt>,
+    account_debt_cap: Option<Debt>,
+    reserve_floor: Option<Collateral>,
+    redeem_fee_schedule: Option<RedeemFeeSchedule>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    loss_tolerance: Option<LossTolerance>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    loss_socialisation_enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_deposit: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    collateral_price_oracle: Option<CollateralPriceOracle>,
 }
 
 #[derive(Debug, Default, serde::Serialize)]
@@ -38,6 +64,11 @@ struct User {
     debt: u128,
     credit: u128,
     spr: Option<SumPaymentRatio>,
+    position_seq: Option<PositionSeq>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    operator: Option<PositionOperator>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    collateral_factor: Option<CollateralFactor>,
 }
 
 #[derive(Debug, Default, serde::Serialize)]
@@ -49,7 +80,13 @@ struct Balances {
     reserve_balance: u128,
     treasury_shares: u128,
     amo_shares: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amo_shares_returned: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amo_deposits_returned: Option<u128>,
     spr: Option<SumPaymentRatio>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    collateral_factor: Option<CollateralFactor>,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -63,9 +100,57 @@ struct Vault {
 struct World {
     vaults: HashMap<String, Vault>,
     treasury: Option<Treasury>,
+    guardian: Option<Guardian>,
+    global_paused: bool,
     oracle_advance_fee: Option<AdvanceFee>,
+    oracle_queries: Cell<usize>,
+    now: Now,
     total_deposits: TotalDepositsValue,
     total_issued_shares: TotalSharesIssued,
+    strategy_redemption_rate: Option<FixedU256>,
+    collateral_price: Option<CollateralPrice>,
+}
+
+#[test]
+fn positions_ltv() {
+    let ltvs: Vec<_> = [
+        (1_000, 0),
+        (1_000, 250),
+        (1_000, 1_000),
+        (1_000, 1_500),
+        (0, 0),
+        (0, 1),
+    ]
+    .into_iter()
+    .map(|(collateral, debt)| {
+        ltv(collateral, debt).map(|rate| (rate.bps().unwrap(), rate.fixed_u256().to_string()))
+    })
+    .collect();
+
+    check(
+        ltvs,
+        expect![[r#"
+            [
+              Some((0, "0.0")),
+              Some((2500, "0.25")),
+              Some((10000, "1.0")),
+              Some((15000, "1.5")),
+              Some((0, "0.0")),
+              None,
+            ]"#]],
+    );
+}
+
+#[test]
+fn positions_max_borrowable() {
+    let max_ltv = MaxLtv::new(5_000).unwrap();
+
+    assert_eq!(max_borrowable(1_000, 0, max_ltv), 500);
+    assert_eq!(max_borrowable(1_000, 200, max_ltv), 300);
+    assert_eq!(max_borrowable(1_000, 500, max_ltv), 0);
+    // already over the max LTV
+    assert_eq!(max_borrowable(1_000, 600, max_ltv), 0);
+    assert_eq!(max_borrowable(0, 0, max_ltv), 0);
 }
 
 #[test]
@@ -84,6 +169,7 @@ fn positions_update_vault_regression_1() {
                 treasury_shares: SharesAmount(0),
                 amo_shares: SharesAmount(0),
                 spr: SumPaymentRatio::zero(),
+                collateral_factor: CollateralFactor::one(),
             },
             RedemptionRate::new(
                 TotalSharesIssued(270_000_000_000_000_000_000_000),
@@ -193,6 +279,88 @@ fn deposit_zero_errs() {
     )
 }
 
+#[test]
+fn deposit_below_min_deposit_errs() {
+    let world = World::default().handle_cmds(cmds![
+        VaultCmd::Register {
+            vault: VAULT.into(),
+            synthetic: SYNTHETIC.into()
+        },
+        VaultCmd::SetDepositsEnabled {
+            vault: VAULT.into(),
+            enabled: true
+        },
+        VaultCmd::SetMinDeposit {
+            vault: VAULT.into(),
+            amount: 1000
+        }
+    ]);
+
+    let deposit = |amount| {
+        world.hub().deposit(
+            VAULT.into(),
+            SENDER.into(),
+            VAULT_DEPOSIT_ASSET.into(),
+            DepositAmount(amount),
+            SENDER.into(),
+        )
+    };
+
+    check_err(deposit(999).unwrap_err(), expect!["deposit below minimum"]);
+
+    assert!(deposit(1000).is_ok());
+    assert!(deposit(1001).is_ok());
+}
+
+#[test]
+fn secondary_deposit_min_deposit_applies_to_value() {
+    let world = World::with_vault(1_000)
+        .with_deposits_enabled()
+        .with_advance_fee_recipient("treasury")
+        .with_position(SENDER, 1_000, 400)
+        .total_deposits(1_100)
+        .handle_cmds(cmds![VaultCmd::SetMinDeposit {
+            vault: VAULT.into(),
+            amount: 150
+        }]);
+
+    let deposit = |amount| {
+        world.hub().deposit(
+            VAULT.into(),
+            SENDER.into(),
+            VAULT_SECONDARY_DEPOSIT_ASSET.into(),
+            DepositAmount(amount),
+            SENDER.into(),
+        )
+    };
+
+    // each secondary asset unit is worth 1.5 deposit asset units
+    check_err(deposit(99).unwrap_err(), expect!["deposit below minimum"]);
+
+    assert!(deposit(100).is_ok());
+}
+
+#[test]
+fn set_min_deposit_never_below_one() {
+    check(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_min_deposit(AdminRole::mock(), VAULT.into(), 0)
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetMinDeposit(
+                vault: "vault",
+                amount: 1,
+              )),
+            ]"#]],
+    )
+}
+
 #[test]
 fn deposit_invalid_asset_errs() {
     check_err(
@@ -303,6 +471,42 @@ fn deposit() {
     )
 }
 
+#[test]
+fn deposit_secondary_asset() {
+    check(
+        World::default()
+            .handle_cmds(cmds![
+                VaultCmd::Register {
+                    vault: VAULT.into(),
+                    synthetic: SYNTHETIC.into()
+                },
+                VaultCmd::SetDepositsEnabled {
+                    vault: VAULT.into(),
+                    enabled: true
+                }
+            ])
+            .hub()
+            .deposit(
+                VAULT.into(),
+                SENDER.into(),
+                VAULT_SECONDARY_DEPOSIT_ASSET.into(),
+                DepositAmount(1000),
+                SENDER.into(),
+            )
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(Deposit(
+                vault: "vault",
+                asset: "vault_secondary_deposit_asset",
+                amount: (1000),
+                callback_recipient: "sender",
+                callback_reason: Deposit,
+              )),
+            ]"#]],
+    )
+}
+
 #[test]
 fn deposit_after_share_value_increase_sender_has_position() {
     check(
@@ -362,6 +566,13 @@ fn deposit_after_share_value_increase_sender_has_position() {
               BalanceSheet(SetOverallSumPaymentRatio(
                 vault: "vault",
                 spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
+              )),
+              Event(YieldFeeAccrued(
+                vault: "vault",
+                treasury_shares: (9090909090909090908),
+                collateral_fee: (9090909090909090908),
+                reserve_fee: (0),
               )),
               BalanceSheet(SetAccountCredit(
                 vault: "vault",
@@ -372,6 +583,7 @@ fn deposit_after_share_value_increase_sender_has_position() {
                 vault: "vault",
                 account: "sender",
                 spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
               )),
               Vault(Deposit(
                 vault: "vault",
@@ -443,11 +655,19 @@ fn deposit_after_share_value_increase_sender_with_no_position() {
               BalanceSheet(SetOverallSumPaymentRatio(
                 vault: "vault",
                 spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
+              )),
+              Event(YieldFeeAccrued(
+                vault: "vault",
+                treasury_shares: (9090909090909090908),
+                collateral_fee: (9090909090909090908),
+                reserve_fee: (0),
               )),
               BalanceSheet(SetAccountSumPaymentRatio(
                 vault: "vault",
                 account: "sender",
                 spr: (("0.08999999999999999999999999999999")),
+                payment: 0,
               )),
               Vault(Deposit(
                 vault: "vault",
@@ -460,6 +680,241 @@ fn deposit_after_share_value_increase_sender_with_no_position() {
     )
 }
 
+#[test]
+fn absorb_shares_invalid_asset_errs() {
+    check_err(
+        World::default()
+            .handle_cmds(cmds![
+                VaultCmd::Register {
+                    vault: VAULT.into(),
+                    synthetic: SYNTHETIC.into()
+                },
+                VaultCmd::SetDepositsEnabled {
+                    vault: VAULT.into(),
+                    enabled: true
+                }
+            ])
+            .total_deposits(1_000)
+            .total_shares_issued(shares_amount(1_000))
+            .hub()
+            .absorb_shares(
+                VAULT.into(),
+                SENDER.into(),
+                VAULT_DEPOSIT_ASSET.into(),
+                shares_amount(100),
+            )
+            .unwrap_err(),
+        expect!["invalid shares asset"],
+    )
+}
+
+#[test]
+fn absorb_shares() {
+    check(
+        World::default()
+            .handle_cmds(cmds![
+                VaultCmd::Register {
+                    vault: VAULT.into(),
+                    synthetic: SYNTHETIC.into()
+                },
+                VaultCmd::SetDepositsEnabled {
+                    vault: VAULT.into(),
+                    enabled: true
+                },
+                BalanceSheetCmd::SetCollateralBalance {
+                    vault: VAULT.into(),
+                    balance: 1_000
+                },
+                BalanceSheetCmd::SetCollateralShares {
+                    vault: VAULT.into(),
+                    shares: shares_amount(1_000)
+                }
+            ])
+            // shares minted directly by the vault are included in the total issued
+            .total_deposits(1_500)
+            .total_shares_issued(shares_amount(1_500))
+            .hub()
+            .absorb_shares(
+                VAULT.into(),
+                SENDER.into(),
+                VAULT_SHARES_ASSET.into(),
+                shares_amount(500),
+            )
+            .unwrap(),
+        expect![[r#"
+            [
+              BalanceSheet(SetCollateralShares(
+                vault: "vault",
+                shares: (1500000000000000000000),
+              )),
+              BalanceSheet(SetCollateralBalance(
+                vault: "vault",
+                balance: 1500,
+              )),
+              BalanceSheet(SetAccountCollateral(
+                vault: "vault",
+                account: "sender",
+                collateral: 500,
+              )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
+            ]"#]],
+    )
+}
+
+#[test]
+fn absorb_shares_below_min_deposit_errs() {
+    // each share is worth 2 deposits
+    let world = World::default()
+        .handle_cmds(cmds![
+            VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            },
+            VaultCmd::SetDepositsEnabled {
+                vault: VAULT.into(),
+                enabled: true
+            },
+            VaultCmd::SetMinDeposit {
+                vault: VAULT.into(),
+                amount: 1000
+            }
+        ])
+        .total_deposits(2_000)
+        .total_shares_issued(shares_amount(1_000));
+
+    let absorb = |shares| {
+        world.hub().absorb_shares(
+            VAULT.into(),
+            SENDER.into(),
+            VAULT_SHARES_ASSET.into(),
+            shares_amount(shares),
+        )
+    };
+
+    check_err(absorb(499).unwrap_err(), expect!["deposit below minimum"]);
+
+    assert!(absorb(500).is_ok());
+}
+
+#[test]
+fn absorb_shares_when_disabled_errs() {
+    check_err(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .total_deposits(1_000)
+            .total_shares_issued(shares_amount(1_000))
+            .hub()
+            .absorb_shares(
+                VAULT.into(),
+                SENDER.into(),
+                VAULT_SHARES_ASSET.into(),
+                shares_amount(100),
+            )
+            .unwrap_err(),
+        expect!["deposits disabled"],
+    )
+}
+
+#[test]
+fn absorb_shares_while_loss_detected_errs() {
+    check_err(
+        World::default()
+            .handle_cmds(cmds![
+                VaultCmd::Register {
+                    vault: VAULT.into(),
+                    synthetic: SYNTHETIC.into()
+                },
+                VaultCmd::SetDepositsEnabled {
+                    vault: VAULT.into(),
+                    enabled: true
+                },
+                BalanceSheetCmd::SetCollateralBalance {
+                    vault: VAULT.into(),
+                    balance: 1_000
+                },
+                BalanceSheetCmd::SetCollateralShares {
+                    vault: VAULT.into(),
+                    shares: shares_amount(1_000)
+                }
+            ])
+            .total_deposits(1_499)
+            .total_shares_issued(shares_amount(1_500))
+            .hub()
+            .absorb_shares(
+                VAULT.into(),
+                SENDER.into(),
+                VAULT_SHARES_ASSET.into(),
+                shares_amount(500),
+            )
+            .unwrap_err(),
+        expect!["vault shares have suffered a loss in value"],
+    )
+}
+
+#[test]
+fn absorb_shares_valued_at_share_price() {
+    // each share is worth 2 deposits
+    let world = World::default()
+        .handle_cmds(cmds![
+            VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            },
+            VaultCmd::SetDepositsEnabled {
+                vault: VAULT.into(),
+                enabled: true
+            }
+        ])
+        .total_deposits(2_000)
+        .total_shares_issued(shares_amount(1_000));
+
+    let cmds = world
+        .hub()
+        .absorb_shares(
+            VAULT.into(),
+            SENDER.into(),
+            VAULT_SHARES_ASSET.into(),
+            shares_amount(500),
+        )
+        .unwrap();
+
+    check(
+        &cmds,
+        expect![[r#"
+        [
+          BalanceSheet(SetCollateralShares(
+            vault: "vault",
+            shares: (500000000000000000000),
+          )),
+          BalanceSheet(SetCollateralBalance(
+            vault: "vault",
+            balance: 1000,
+          )),
+          BalanceSheet(SetAccountCollateral(
+            vault: "vault",
+            account: "sender",
+            collateral: 1000,
+          )),
+          BalanceSheet(SetAccountPositionSeq(
+            vault: "vault",
+            account: "sender",
+            seq: 1,
+          )),
+        ]"#]],
+    );
+
+    let world = world.handle_cmds(cmds);
+
+    assert_eq!(stored_cdp(&world).collateral, 1_000);
+}
+
 #[test]
 fn advance_unregistered_vault_errs() {
     check_err(
@@ -658,7 +1113,12 @@ fn advance_debt() {
                 account: "sender",
                 debt: 500,
               )),
-              Mint(Mint(
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
+              Mint(Mint(
                 synthetic: "synthetic",
                 amount: 300,
                 recipient: "sender",
@@ -715,6 +1175,11 @@ fn advance_non_max_debt_with_fixed_advance_fee() {
                 account: "sender",
                 debt: 400499,
               )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
               Mint(Mint(
                 synthetic: "synthetic",
                 amount: 200000,
@@ -777,6 +1242,11 @@ fn advance_max_debt_with_fixed_advance_fee() {
                 account: "sender",
                 debt: 500000,
               )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
               Mint(Mint(
                 synthetic: "synthetic",
                 amount: 299251,
@@ -844,6 +1314,251 @@ fn advance_non_max_debt_with_advance_fee_oracle() {
                 account: "sender",
                 debt: 401999,
               )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
+              Mint(Mint(
+                synthetic: "synthetic",
+                amount: 200000,
+                recipient: "sender",
+              )),
+              Mint(Mint(
+                synthetic: "synthetic",
+                amount: 1999,
+                recipient: "treasury",
+              )),
+            ]"#]],
+    );
+}
+
+#[test]
+fn advance_with_oracle_fee_cache_records_oracle_fee() {
+    let world = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 0)
+        .with_advance_fee_recipient("treasury")
+        .with_advance_fee_oracle("advance_fee_oracle")
+        .with_oracle_fee_cache_secs(600)
+        .advance_fee_oracle_rate(AdvanceFee::new(100).unwrap())
+        .now(1_000);
+
+    check(
+        world
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 200_000, SENDER.into())
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetCachedOracleFee(
+                vault: "vault",
+                recipient: "sender",
+                cached: (
+                  fee: Some((
+                    bps: 100,
+                    rate: (("0.00999999999999999999999999999999")),
+                  )),
+                  oracle_epoch: 0,
+                  timestamp: 1000,
+                ),
+              )),
+              BalanceSheet(SetAccountDebt(
+                vault: "vault",
+                account: "sender",
+                debt: 201999,
+              )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
+              Mint(Mint(
+                synthetic: "synthetic",
+                amount: 200000,
+                recipient: "sender",
+              )),
+              Mint(Mint(
+                synthetic: "synthetic",
+                amount: 1999,
+                recipient: "treasury",
+              )),
+            ]"#]],
+    );
+
+    assert_eq!(world.oracle_queries.get(), 1);
+}
+
+#[test]
+fn advance_with_fresh_cached_oracle_fee_skips_query() {
+    let world = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 0)
+        .with_advance_fee_recipient("treasury")
+        .with_advance_fee_oracle("advance_fee_oracle")
+        .with_oracle_fee_cache_secs(600)
+        .advance_fee_oracle_rate(AdvanceFee::new(100).unwrap())
+        .handle_cmds(cmds![VaultCmd::SetCachedOracleFee {
+            vault: VAULT.into(),
+            recipient: SENDER.into(),
+            cached: CachedAdvanceFee {
+                fee: AdvanceFee::new(50),
+                oracle_epoch: 0,
+                timestamp: 1_000,
+            },
+        }])
+        .now(1_599);
+
+    check(
+        world
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 200_000, SENDER.into())
+            .unwrap(),
+        expect![[r#"
+            [
+              BalanceSheet(SetAccountDebt(
+                vault: "vault",
+                account: "sender",
+                debt: 200999,
+              )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
+              Mint(Mint(
+                synthetic: "synthetic",
+                amount: 200000,
+                recipient: "sender",
+              )),
+              Mint(Mint(
+                synthetic: "synthetic",
+                amount: 999,
+                recipient: "treasury",
+              )),
+            ]"#]],
+    );
+
+    assert_eq!(world.oracle_queries.get(), 0);
+}
+
+#[test]
+fn advance_with_expired_cached_oracle_fee_queries_oracle() {
+    let world = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 0)
+        .with_advance_fee_recipient("treasury")
+        .with_advance_fee_oracle("advance_fee_oracle")
+        .with_oracle_fee_cache_secs(600)
+        .advance_fee_oracle_rate(AdvanceFee::new(100).unwrap())
+        .handle_cmds(cmds![VaultCmd::SetCachedOracleFee {
+            vault: VAULT.into(),
+            recipient: SENDER.into(),
+            cached: CachedAdvanceFee {
+                fee: AdvanceFee::new(50),
+                oracle_epoch: 0,
+                timestamp: 1_000,
+            },
+        }])
+        .now(1_600);
+
+    check(
+        world
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 200_000, SENDER.into())
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetCachedOracleFee(
+                vault: "vault",
+                recipient: "sender",
+                cached: (
+                  fee: Some((
+                    bps: 100,
+                    rate: (("0.00999999999999999999999999999999")),
+                  )),
+                  oracle_epoch: 0,
+                  timestamp: 1600,
+                ),
+              )),
+              BalanceSheet(SetAccountDebt(
+                vault: "vault",
+                account: "sender",
+                debt: 201999,
+              )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
+              Mint(Mint(
+                synthetic: "synthetic",
+                amount: 200000,
+                recipient: "sender",
+              )),
+              Mint(Mint(
+                synthetic: "synthetic",
+                amount: 1999,
+                recipient: "treasury",
+              )),
+            ]"#]],
+    );
+
+    assert_eq!(world.oracle_queries.get(), 1);
+}
+
+#[test]
+fn advance_after_oracle_change_ignores_cached_oracle_fee() {
+    let mut world = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 0)
+        .with_advance_fee_recipient("treasury")
+        .with_advance_fee_oracle("advance_fee_oracle")
+        .with_oracle_fee_cache_secs(600)
+        .advance_fee_oracle_rate(AdvanceFee::new(100).unwrap())
+        .handle_cmds(cmds![VaultCmd::SetCachedOracleFee {
+            vault: VAULT.into(),
+            recipient: SENDER.into(),
+            cached: CachedAdvanceFee {
+                fee: AdvanceFee::new(50),
+                oracle_epoch: 0,
+                timestamp: 1_000,
+            },
+        }])
+        .now(1_001);
+
+    let cmds = world
+        .configure()
+        .set_advance_fee_oracle(AdminRole::mock(), VAULT.into(), "new_oracle".into())
+        .unwrap();
+
+    world = world.handle_cmds(cmds);
+
+    check(
+        world
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 200_000, SENDER.into())
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetCachedOracleFee(
+                vault: "vault",
+                recipient: "sender",
+                cached: (
+                  fee: Some((
+                    bps: 100,
+                    rate: (("0.00999999999999999999999999999999")),
+                  )),
+                  oracle_epoch: 1,
+                  timestamp: 1001,
+                ),
+              )),
+              BalanceSheet(SetAccountDebt(
+                vault: "vault",
+                account: "sender",
+                debt: 201999,
+              )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
               Mint(Mint(
                 synthetic: "synthetic",
                 amount: 200000,
@@ -856,6 +1571,150 @@ fn advance_non_max_debt_with_advance_fee_oracle() {
               )),
             ]"#]],
     );
+
+    assert_eq!(world.oracle_queries.get(), 1);
+}
+
+#[test]
+fn advance_fees_batches_uncached_oracle_queries() {
+    let world = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 0)
+        .with_advance_fee_recipient("treasury")
+        .with_advance_fee_oracle("advance_fee_oracle")
+        .with_oracle_fee_cache_secs(600)
+        .advance_fee_oracle_rate(AdvanceFee::new(100).unwrap())
+        .handle_cmds(cmds![VaultCmd::SetCachedOracleFee {
+            vault: VAULT.into(),
+            recipient: "cached".into(),
+            cached: CachedAdvanceFee {
+                fee: AdvanceFee::new(50),
+                oracle_epoch: 0,
+                timestamp: 1_000,
+            },
+        }])
+        .now(1_000);
+
+    let hub = hub(&world, &world, &world);
+
+    let (fees, cmds) = hub.resolve_advance_fees(
+        &VAULT.into(),
+        &["first".into(), "cached".into(), "second".into()],
+    );
+
+    assert_eq!(
+        fees.into_iter().map(AdvanceFee::raw).collect::<Vec<_>>(),
+        [100, 50, 100]
+    );
+
+    assert_eq!(cmds.len(), 2);
+
+    assert_eq!(world.oracle_queries.get(), 1);
+}
+
+#[test]
+fn advance_fees_query() {
+    let world = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 0)
+        .with_advance_fee_recipient("treasury")
+        .with_advance_fee_oracle("advance_fee_oracle")
+        .with_oracle_fee_cache_secs(600)
+        .advance_fee_oracle_rate(AdvanceFee::new(100).unwrap())
+        .now(1_000);
+
+    let fees = world
+        .hub()
+        .advance_fees(VAULT.into(), vec!["first".into(), "second".into()])
+        .unwrap();
+
+    assert_eq!(
+        fees.into_iter().map(AdvanceFee::raw).collect::<Vec<_>>(),
+        [100, 100]
+    );
+
+    assert_eq!(world.oracle_queries.get(), 1);
+
+    assert!(matches!(
+        world
+            .hub()
+            .advance_fees("unregistered".into(), vec!["first".into()]),
+        Err(Error::VaultNotRegistered)
+    ));
+}
+
+#[test]
+fn prune_oracle_fee_cache_removes_expired_entries() {
+    let cached = |timestamp, oracle_epoch| CachedAdvanceFee {
+        fee: AdvanceFee::new(50),
+        oracle_epoch,
+        timestamp,
+    };
+
+    let world = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 0)
+        .with_advance_fee_recipient("treasury")
+        .with_advance_fee_oracle("advance_fee_oracle")
+        .with_oracle_fee_cache_secs(600)
+        .advance_fee_oracle_rate(AdvanceFee::new(100).unwrap())
+        .handle_cmds(cmds![
+            VaultCmd::SetCachedOracleFee {
+                vault: VAULT.into(),
+                recipient: "expired".into(),
+                cached: cached(400, 0),
+            },
+            VaultCmd::SetCachedOracleFee {
+                vault: VAULT.into(),
+                recipient: "fresh".into(),
+                cached: cached(1_000, 0),
+            },
+            VaultCmd::SetCachedOracleFee {
+                vault: VAULT.into(),
+                recipient: "previous_oracle".into(),
+                cached: cached(1_000, 1),
+            },
+        ])
+        .now(1_001);
+
+    check(
+        world
+            .hub()
+            .prune_oracle_fee_cache(VAULT.into(), None, 10)
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(RemoveCachedOracleFee(
+                vault: "vault",
+                recipient: "expired",
+              )),
+              Vault(RemoveCachedOracleFee(
+                vault: "vault",
+                recipient: "previous_oracle",
+              )),
+            ]"#]],
+    );
+
+    // the page following "expired" holds only the fresh entry
+    check(
+        world
+            .hub()
+            .prune_oracle_fee_cache(VAULT.into(), Some("expired".into()), 1)
+            .unwrap(),
+        expect!["[]"],
+    );
+
+    // disabling the cache expires every entry
+    let world = world.handle_cmds(cmds![VaultCmd::SetOracleFeeCacheSecs {
+        vault: VAULT.into(),
+        secs: None,
+    }]);
+
+    assert_eq!(
+        world
+            .hub()
+            .prune_oracle_fee_cache(VAULT.into(), None, 10)
+            .unwrap()
+            .len(),
+        3
+    );
 }
 
 #[test]
@@ -904,6 +1763,11 @@ fn advance_all_credit() {
                 account: "sender",
                 credit: 0,
               )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
               Mint(Mint(
                 synthetic: "synthetic",
                 amount: 89,
@@ -973,6 +1837,11 @@ fn advance_from_credit_with_fee() {
                 account: "sender",
                 credit: 0,
               )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
               Mint(Mint(
                 synthetic: "synthetic",
                 amount: 489,
@@ -1215,6 +2084,13 @@ fn repay_underlying_after_share_value_increase() {
               BalanceSheet(SetOverallSumPaymentRatio(
                 vault: "vault",
                 spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
+              )),
+              Event(YieldFeeAccrued(
+                vault: "vault",
+                treasury_shares: (9090909090909090908),
+                collateral_fee: (9090909090909090908),
+                reserve_fee: (0),
               )),
               BalanceSheet(SetAccountDebt(
                 vault: "vault",
@@ -1225,6 +2101,7 @@ fn repay_underlying_after_share_value_increase() {
                 vault: "vault",
                 account: "sender",
                 spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
               )),
               Vault(Deposit(
                 vault: "vault",
@@ -1376,6 +2253,11 @@ fn repay_synthetic() {
                   account: "sender",
                   debt: 0,
                 )),
+                BalanceSheet(SetAccountPositionSeq(
+                  vault: "vault",
+                  account: "sender",
+                  seq: 1,
+                )),
                 Mint(Burn(
                   synthetic: "synthetic",
                   amount: 500,
@@ -1441,6 +2323,12 @@ fn repay_synthetic_after_vault_shares_value_increase() {
                   vault: "vault",
                   account: "sender",
                   spr: (("0.08999999999999999999999999999999")),
+                  payment: 89,
+                )),
+                BalanceSheet(SetAccountPositionSeq(
+                  vault: "vault",
+                  account: "sender",
+                  seq: 1,
                 )),
                 BalanceSheet(SetCollateralShares(
                   vault: "vault",
@@ -1461,6 +2349,13 @@ fn repay_synthetic_after_vault_shares_value_increase() {
                 BalanceSheet(SetOverallSumPaymentRatio(
                   vault: "vault",
                   spr: (("0.08999999999999999999999999999999")),
+                  payment: 89,
+                )),
+                Event(YieldFeeAccrued(
+                  vault: "vault",
+                  treasury_shares: (9090909090909090908),
+                  collateral_fee: (9090909090909090908),
+                  reserve_fee: (0),
                 )),
                 Mint(Burn(
                   synthetic: "synthetic",
@@ -1593,30 +2488,295 @@ fn withdraw_collateral_exceeding_max_ltv_errs() {
 }
 
 #[test]
-fn withdraw_collateral() {
-    check(
-        World::default()
-            .handle_cmds(cmds![
-                VaultCmd::Register {
-                    vault: VAULT.into(),
-                    synthetic: SYNTHETIC.into()
-                },
-                BalanceSheetCmd::SetCollateralBalance {
-                    vault: VAULT.into(),
-                    balance: 1_000
-                },
-                BalanceSheetCmd::SetCollateralShares {
-                    vault: VAULT.into(),
-                    shares: shares_amount(1_000)
-                },
-                BalanceSheetCmd::SetAccountCollateral {
-                    vault: VAULT.into(),
-                    account: SENDER.into(),
-                    collateral: 1_000
-                },
-                BalanceSheetCmd::SetAccountDebt {
-                    vault: VAULT.into(),
-                    account: SENDER.into(),
+fn advance_ltv_uses_strategy_redemption_rate_below_mark_to_market() {
+    // mark-to-market values the collateral at 1,000, the redemption rate at 750
+    let world = World::with_vault(1_000)
+        .with_position(SENDER, 1_000, 0)
+        .strategy_redemption_rate(3, 4);
+
+    // 50% of the mark-to-market value is over the max LTV
+    check_err(
+        world
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 500, SENDER.into())
+            .unwrap_err(),
+        expect!["not enough collateral"],
+    );
+
+    check_err(
+        world
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 376, SENDER.into())
+            .unwrap_err(),
+        expect!["not enough collateral"],
+    );
+
+    world
+        .hub()
+        .advance(VAULT.into(), SENDER.into(), 375, SENDER.into())
+        .unwrap();
+}
+
+#[test]
+fn advance_ltv_uses_strategy_redemption_rate_above_mark_to_market() {
+    // mark-to-market values the collateral at 1,000, the redemption rate at 1,250
+    let world = World::with_vault(1_000)
+        .with_position(SENDER, 1_000, 0)
+        .strategy_redemption_rate(5, 4);
+
+    // over 50% of the mark-to-market value is allowed
+    world
+        .hub()
+        .advance(VAULT.into(), SENDER.into(), 625, SENDER.into())
+        .unwrap();
+
+    check_err(
+        world
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 626, SENDER.into())
+            .unwrap_err(),
+        expect!["not enough collateral"],
+    );
+}
+
+#[test]
+fn withdraw_collateral_ltv_uses_strategy_redemption_rate() {
+    // without a strategy redemption rate the withdrawal stays within the max LTV
+    World::with_vault(1_000)
+        .with_position(SENDER, 1_000, 400)
+        .hub()
+        .withdraw_collateral(VAULT.into(), SENDER.into(), 200)
+        .unwrap();
+
+    // at the redemption rate the remaining 800 collateral is only valued at 600
+    check_err(
+        World::with_vault(1_000)
+            .with_position(SENDER, 1_000, 400)
+            .strategy_redemption_rate(3, 4)
+            .hub()
+            .withdraw_collateral(VAULT.into(), SENDER.into(), 200)
+            .unwrap_err(),
+        expect!["not enough collateral"],
+    );
+}
+
+#[test]
+fn set_collateral_price_oracle() {
+    check(
+        World::with_vault(1_000)
+            .with_position(SENDER, 1_000, 0)
+            .configure()
+            .set_collateral_price_oracle(
+                AdminRole::mock(),
+                VAULT.into(),
+                Some(CollateralPriceOracle {
+                    oracle: "oracle".into(),
+                    max_age_secs: 600,
+                }),
+            )
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetCollateralPriceOracle(
+                vault: "vault",
+                oracle: Some((
+                  oracle: "oracle",
+                  max_age_secs: 600,
+                )),
+              )),
+            ]"#]],
+    );
+
+    check_err(
+        World::default()
+            .configure()
+            .set_collateral_price_oracle(AdminRole::mock(), VAULT.into(), None)
+            .unwrap_err(),
+        expect!["vault not registered"],
+    );
+}
+
+#[test]
+fn withdrawable_collateral_valued_at_collateral_price() {
+    let max_withdrawable = |world: World| {
+        world
+            .hub()
+            .withdraw_all_collateral(VAULT.into(), SENDER.into())
+            .unwrap()
+            .cdp
+            .collateral
+            .abs_diff(1_000)
+    };
+
+    // at the share price, 800 collateral covers the 400 debt at the max LTV
+    assert_eq!(
+        max_withdrawable(World::with_vault(1_000).with_position(SENDER, 1_000, 400)),
+        200
+    );
+
+    // at a 20% discount, 1,000 collateral is only worth 800, leaving nothing to withdraw
+    check_err(
+        World::with_vault(1_000)
+            .with_position(SENDER, 1_000, 400)
+            .with_collateral_price_oracle("oracle", 600)
+            .collateral_price(4, 5, 0)
+            .hub()
+            .withdraw_all_collateral(VAULT.into(), SENDER.into())
+            .unwrap_err(),
+        expect!["not enough collateral"],
+    );
+
+    // at a 10% discount, 900 collateral valued at 810 is needed
+    assert_eq!(
+        max_withdrawable(
+            World::with_vault(1_000)
+                .with_position(SENDER, 1_000, 400)
+                .with_collateral_price_oracle("oracle", 600)
+                .collateral_price(9, 10, 0)
+        ),
+        111
+    );
+
+    // the oracle price discounts the strategy redemption rate, at a 1.25 rate & a 10% discount 712 collateral valued at 801 is needed
+    assert_eq!(
+        max_withdrawable(
+            World::with_vault(1_000)
+                .with_position(SENDER, 1_000, 400)
+                .with_collateral_price_oracle("oracle", 600)
+                .strategy_redemption_rate(5, 4)
+                .collateral_price(9, 10, 0)
+        ),
+        288
+    );
+
+    // at a 0.75 rate & a 10% discount, 1,000 collateral is only worth 675
+    check_err(
+        World::with_vault(1_000)
+            .with_position(SENDER, 1_000, 400)
+            .with_collateral_price_oracle("oracle", 600)
+            .strategy_redemption_rate(3, 4)
+            .collateral_price(9, 10, 0)
+            .hub()
+            .withdraw_all_collateral(VAULT.into(), SENDER.into())
+            .unwrap_err(),
+        expect!["not enough collateral"],
+    );
+
+    check_err(
+        World::with_vault(1_000)
+            .with_position(SENDER, 1_000, 400)
+            .with_collateral_price_oracle("oracle", 600)
+            .collateral_price(9, 10, 0)
+            .hub()
+            .withdraw_collateral(VAULT.into(), SENDER.into(), 112)
+            .unwrap_err(),
+        expect!["not enough collateral"],
+    );
+}
+
+#[test]
+fn advance_blocked_while_collateral_price_stale() {
+    let world = World::with_vault(1_000)
+        .with_position(SENDER, 1_000, 0)
+        .with_collateral_price_oracle("oracle", 600)
+        .collateral_price(3, 4, 1_000)
+        .now(1_600);
+
+    // at a 25% discount the 1,000 collateral supports 375 at the 50% max LTV
+    check_err(
+        world
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 376, SENDER.into())
+            .unwrap_err(),
+        expect!["not enough collateral"],
+    );
+
+    world
+        .hub()
+        .advance(VAULT.into(), SENDER.into(), 375, SENDER.into())
+        .unwrap();
+
+    check_err(
+        world
+            .now(1_601)
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 100, SENDER.into())
+            .unwrap_err(),
+        expect!["collateral price stale"],
+    );
+}
+
+#[test]
+fn withdraw_blocked_while_collateral_price_stale() {
+    let world = |now| {
+        World::with_vault(1_000)
+            .with_position(SENDER, 1_000, 300)
+            .with_collateral_price_oracle("oracle", 600)
+            .collateral_price(3, 4, 1_000)
+            .now(now)
+    };
+
+    // at the last price, 800 collateral valued at 600 covers the 300 debt
+    world(1_600)
+        .hub()
+        .withdraw_collateral(VAULT.into(), SENDER.into(), 200)
+        .unwrap();
+
+    // the debt can not be shown to stay within the max LTV at a stale price
+    let world = world(1_601);
+
+    check_err(
+        world
+            .hub()
+            .withdraw_collateral(VAULT.into(), SENDER.into(), 200)
+            .unwrap_err(),
+        expect!["collateral price stale"],
+    );
+
+    check_err(
+        world
+            .hub()
+            .withdraw_all_collateral(VAULT.into(), SENDER.into())
+            .unwrap_err(),
+        expect!["collateral price stale"],
+    );
+
+    // without debt the collateral is never valued, so it can be withdrawn at any time
+    World::with_vault(1_000)
+        .with_position(SENDER, 1_000, 0)
+        .with_collateral_price_oracle("oracle", 600)
+        .collateral_price(3, 4, 1_000)
+        .now(1_601)
+        .hub()
+        .withdraw_all_collateral(VAULT.into(), SENDER.into())
+        .unwrap();
+}
+
+#[test]
+fn withdraw_collateral() {
+    check(
+        World::default()
+            .handle_cmds(cmds![
+                VaultCmd::Register {
+                    vault: VAULT.into(),
+                    synthetic: SYNTHETIC.into()
+                },
+                BalanceSheetCmd::SetCollateralBalance {
+                    vault: VAULT.into(),
+                    balance: 1_000
+                },
+                BalanceSheetCmd::SetCollateralShares {
+                    vault: VAULT.into(),
+                    shares: shares_amount(1_000)
+                },
+                BalanceSheetCmd::SetAccountCollateral {
+                    vault: VAULT.into(),
+                    account: SENDER.into(),
+                    collateral: 1_000
+                },
+                BalanceSheetCmd::SetAccountDebt {
+                    vault: VAULT.into(),
+                    account: SENDER.into(),
                     debt: 400
                 }
             ])
@@ -1641,6 +2801,11 @@ fn withdraw_collateral() {
                   account: "sender",
                   collateral: 800,
                 )),
+                BalanceSheet(SetAccountPositionSeq(
+                  vault: "vault",
+                  account: "sender",
+                  seq: 1,
+                )),
                 Vault(Redeem(
                   vault: "vault",
                   shares: "vault_shares_asset",
@@ -1717,6 +2882,13 @@ fn withdraw_collateral_after_vault_shares_value_increase() {
                 BalanceSheet(SetOverallSumPaymentRatio(
                   vault: "vault",
                   spr: (("0.08999999999999999999999999999999")),
+                  payment: 89,
+                )),
+                Event(YieldFeeAccrued(
+                  vault: "vault",
+                  treasury_shares: (9090909090909090908),
+                  collateral_fee: (9090909090909090908),
+                  reserve_fee: (0),
                 )),
                 BalanceSheet(SetAccountCollateral(
                   vault: "vault",
@@ -1732,6 +2904,12 @@ fn withdraw_collateral_after_vault_shares_value_increase() {
                   vault: "vault",
                   account: "sender",
                   spr: (("0.08999999999999999999999999999999")),
+                  payment: 89,
+                )),
+                BalanceSheet(SetAccountPositionSeq(
+                  vault: "vault",
+                  account: "sender",
+                  seq: 1,
                 )),
                 Vault(Redeem(
                   vault: "vault",
@@ -1751,82 +2929,223 @@ fn withdraw_collateral_after_vault_shares_value_increase() {
 }
 
 #[test]
-fn self_liquidate_unregistered_vault_errs() {
-    check_err(
-        World::default()
-            .hub()
-            .self_liquidate_position("does_not_exist".into(), SENDER.into())
-            .unwrap_err(),
-        expect!["vault not registered"],
-    )
-}
+fn withdraw_all_collateral_with_debt() {
+    let world = World::with_vault(1_000).with_position(SENDER, 1_000, 400);
+
+    let response = world
+        .hub()
+        .withdraw_all_collateral(VAULT.into(), SENDER.into())
+        .unwrap();
+
+    // the remaining collateral is at exactly the max LTV
+    check(
+        &response.cdp,
+        expect![[r#"
+            (
+              collateral: 800,
+              debt: 400,
+              credit: 0,
+              spr: (("0.0")),
+            )"#]],
+    );
+
+    let world = world.handle_cmds(response.cmds);
 
-#[test]
-fn self_liquidate_without_a_position_errs() {
     check_err(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
-                vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
+        world
             .hub()
-            .self_liquidate_position(VAULT.into(), SENDER.into())
+            .withdraw_all_collateral(VAULT.into(), SENDER.into())
             .unwrap_err(),
-        expect!["nothing to liquidate"],
-    )
+        expect!["not enough collateral"],
+    );
 }
 
 #[test]
-fn self_liquidated_while_loss_detected_errs() {
-    check_err(
-        World::default()
-            .handle_cmds(cmds![
-                VaultCmd::Register {
-                    vault: VAULT.into(),
-                    synthetic: SYNTHETIC.into()
-                },
-                BalanceSheetCmd::SetCollateralBalance {
-                    vault: VAULT.into(),
-                    balance: 1_000
-                },
-                BalanceSheetCmd::SetCollateralShares {
-                    vault: VAULT.into(),
-                    shares: shares_amount(1_000)
-                },
-                BalanceSheetCmd::SetAccountCollateral {
-                    vault: VAULT.into(),
-                    account: SENDER.into(),
-                    collateral: 1_000
-                },
-                BalanceSheetCmd::SetAccountDebt {
-                    vault: VAULT.into(),
-                    account: SENDER.into(),
-                    debt: 500
-                }
-            ])
-            .total_deposits(999)
-            .total_shares_issued(shares_amount(1_000))
-            .hub()
-            .self_liquidate_position(VAULT.into(), SENDER.into())
-            .unwrap_err(),
-        expect!["vault shares have suffered a loss in value"],
-    )
+fn withdraw_all_collateral_ltv_uses_strategy_redemption_rate() {
+    // at the redemption rate the 1,000 collateral is valued at 750, so only 200 is free over the 400 debt
+    let response = World::with_vault(1_000)
+        .with_position(SENDER, 1_000, 300)
+        .strategy_redemption_rate(3, 4)
+        .hub()
+        .withdraw_all_collateral(VAULT.into(), SENDER.into())
+        .unwrap();
+
+    assert_eq!(response.cdp.collateral, 800);
 }
 
 #[test]
-fn self_liquidate_credit_position() {
+fn withdraw_all_collateral_without_debt_empties_position() {
+    // the collateral shares have grown in value since they were deposited
+    let world = World::with_vault(1_000)
+        .with_position(SENDER, 1_000, 0)
+        .total_deposits(1_100)
+        .total_shares_issued(shares_amount(1_000));
+
+    let response = world
+        .hub()
+        .withdraw_all_collateral(VAULT.into(), SENDER.into())
+        .unwrap();
+
     check(
-        World::default()
-            .handle_cmds(cmds![
-                VaultCmd::Register {
-                    vault: VAULT.into(),
-                    synthetic: SYNTHETIC.into()
-                },
-                BalanceSheetCmd::SetCollateralBalance {
-                    vault: VAULT.into(),
-                    balance: 1_000
-                },
-                BalanceSheetCmd::SetCollateralShares {
+        &response.cmds,
+        expect![[r#"
+            [
+              BalanceSheet(SetCollateralShares(
+                vault: "vault",
+                shares: (0),
+              )),
+              BalanceSheet(SetCollateralBalance(
+                vault: "vault",
+                balance: 0,
+              )),
+              BalanceSheet(SetReserveShares(
+                vault: "vault",
+                shares: (81818181818181818182),
+              )),
+              BalanceSheet(SetReserveBalance(
+                vault: "vault",
+                balance: 90,
+              )),
+              BalanceSheet(SetTreasuryShares(
+                vault: "vault",
+                shares: (9090909090909090908),
+              )),
+              BalanceSheet(SetOverallSumPaymentRatio(
+                vault: "vault",
+                spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
+              )),
+              Event(YieldFeeAccrued(
+                vault: "vault",
+                treasury_shares: (9090909090909090908),
+                collateral_fee: (9090909090909090908),
+                reserve_fee: (0),
+              )),
+              BalanceSheet(SetAccountCollateral(
+                vault: "vault",
+                account: "sender",
+                collateral: 0,
+              )),
+              BalanceSheet(SetAccountCredit(
+                vault: "vault",
+                account: "sender",
+                credit: 89,
+              )),
+              BalanceSheet(SetAccountSumPaymentRatio(
+                vault: "vault",
+                account: "sender",
+                spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
+              )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
+              Vault(Redeem(
+                vault: "vault",
+                shares: "vault_shares_asset",
+                amount: (909090909090909090910),
+                recipient: "sender",
+              )),
+            ]"#]],
+    );
+
+    let world = world.handle_cmds(response.cmds);
+
+    let position = world.hub().evaluate(VAULT.into(), SENDER.into()).unwrap();
+
+    assert_eq!(position.cdp.collateral, 0);
+}
+
+#[test]
+fn withdraw_all_collateral_unregistered_vault_errs() {
+    check_err(
+        World::default()
+            .hub()
+            .withdraw_all_collateral(VAULT.into(), SENDER.into())
+            .unwrap_err(),
+        expect!["vault not registered"],
+    );
+}
+
+#[test]
+fn self_liquidate_unregistered_vault_errs() {
+    check_err(
+        World::default()
+            .hub()
+            .self_liquidate_position("does_not_exist".into(), SENDER.into())
+            .unwrap_err(),
+        expect!["vault not registered"],
+    )
+}
+
+#[test]
+fn self_liquidate_without_a_position_errs() {
+    check_err(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .hub()
+            .self_liquidate_position(VAULT.into(), SENDER.into())
+            .unwrap_err(),
+        expect!["nothing to liquidate"],
+    )
+}
+
+#[test]
+fn self_liquidated_while_loss_detected_errs() {
+    check_err(
+        World::default()
+            .handle_cmds(cmds![
+                VaultCmd::Register {
+                    vault: VAULT.into(),
+                    synthetic: SYNTHETIC.into()
+                },
+                BalanceSheetCmd::SetCollateralBalance {
+                    vault: VAULT.into(),
+                    balance: 1_000
+                },
+                BalanceSheetCmd::SetCollateralShares {
+                    vault: VAULT.into(),
+                    shares: shares_amount(1_000)
+                },
+                BalanceSheetCmd::SetAccountCollateral {
+                    vault: VAULT.into(),
+                    account: SENDER.into(),
+                    collateral: 1_000
+                },
+                BalanceSheetCmd::SetAccountDebt {
+                    vault: VAULT.into(),
+                    account: SENDER.into(),
+                    debt: 500
+                }
+            ])
+            .total_deposits(999)
+            .total_shares_issued(shares_amount(1_000))
+            .hub()
+            .self_liquidate_position(VAULT.into(), SENDER.into())
+            .unwrap_err(),
+        expect!["vault shares have suffered a loss in value"],
+    )
+}
+
+#[test]
+fn self_liquidate_credit_position() {
+    check(
+        World::default()
+            .handle_cmds(cmds![
+                VaultCmd::Register {
+                    vault: VAULT.into(),
+                    synthetic: SYNTHETIC.into()
+                },
+                BalanceSheetCmd::SetCollateralBalance {
+                    vault: VAULT.into(),
+                    balance: 1_000
+                },
+                BalanceSheetCmd::SetCollateralShares {
                     vault: VAULT.into(),
                     shares: shares_amount(1_000)
                 },
@@ -1845,7 +3164,7 @@ fn self_liquidate_credit_position() {
             [
               BalanceSheet(SetCollateralShares(
                 vault: "vault",
-                shares: (1),
+                shares: (0),
               )),
               BalanceSheet(SetCollateralBalance(
                 vault: "vault",
@@ -1866,12 +3185,30 @@ fn self_liquidate_credit_position() {
               BalanceSheet(SetOverallSumPaymentRatio(
                 vault: "vault",
                 spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
+              )),
+              Event(YieldFeeAccrued(
+                vault: "vault",
+                treasury_shares: (9090909090909090908),
+                collateral_fee: (9090909090909090908),
+                reserve_fee: (0),
               )),
               BalanceSheet(SetAccountCollateral(
                 vault: "vault",
                 account: "sender",
                 collateral: 0,
               )),
+              BalanceSheet(SetAccountSumPaymentRatio(
+                vault: "vault",
+                account: "sender",
+                spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
+              )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
               Mint(Mint(
                 synthetic: "synthetic",
                 amount: 89,
@@ -1880,7 +3217,7 @@ fn self_liquidate_credit_position() {
               Vault(Redeem(
                 vault: "vault",
                 shares: "vault_shares_asset",
-                amount: (909090909090909090909),
+                amount: (909090909090909090910),
                 recipient: "sender",
               )),
             ]"#]],
@@ -1948,6 +3285,11 @@ fn self_liquidate_debt_position() {
                 account: "sender",
                 debt: 0,
               )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
               Vault(Redeem(
                 vault: "vault",
                 shares: "vault_shares_asset",
@@ -1995,7 +3337,7 @@ fn self_liquidate_debt_position_after_vault_shares_increase() {
             [
               BalanceSheet(SetCollateralShares(
                 vault: "vault",
-                shares: (2),
+                shares: (0),
               )),
               BalanceSheet(SetCollateralBalance(
                 vault: "vault",
@@ -2016,6 +3358,13 @@ fn self_liquidate_debt_position_after_vault_shares_increase() {
               BalanceSheet(SetOverallSumPaymentRatio(
                 vault: "vault",
                 spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
+              )),
+              Event(YieldFeeAccrued(
+                vault: "vault",
+                treasury_shares: (9090909090909090908),
+                collateral_fee: (9090909090909090908),
+                reserve_fee: (0),
               )),
               BalanceSheet(SetAccountCollateral(
                 vault: "vault",
@@ -2027,10 +3376,21 @@ fn self_liquidate_debt_position_after_vault_shares_increase() {
                 account: "sender",
                 debt: 0,
               )),
+              BalanceSheet(SetAccountSumPaymentRatio(
+                vault: "vault",
+                account: "sender",
+                spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
+              )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
               Vault(Redeem(
                 vault: "vault",
                 shares: "vault_shares_asset",
-                amount: (535454545454545454545),
+                amount: (535454545454545454547),
                 recipient: "sender",
               )),
             ]"#]],
@@ -2217,6 +3577,13 @@ fn convert_credit() {
                 BalanceSheet(SetOverallSumPaymentRatio(
                   vault: "vault",
                   spr: (("0.08999999999999999999999999999999")),
+                  payment: 89,
+                )),
+                Event(YieldFeeAccrued(
+                  vault: "vault",
+                  treasury_shares: (9090909090909090908),
+                  collateral_fee: (9090909090909090908),
+                  reserve_fee: (0),
                 )),
                 BalanceSheet(SetAccountCollateral(
                   vault: "vault",
@@ -2227,6 +3594,12 @@ fn convert_credit() {
                   vault: "vault",
                   account: "sender",
                   spr: (("0.08999999999999999999999999999999")),
+                  payment: 89,
+                )),
+                BalanceSheet(SetAccountPositionSeq(
+                  vault: "vault",
+                  account: "sender",
+                  seq: 1,
                 )),
               ],
               cdp: (
@@ -2252,6 +3625,7 @@ fn convert_credit() {
                   debt: 0,
                   credit: 0,
                   spr: Some((("0.08999999999999999999999999999999"))),
+                  position_seq: Some(1),
                 ),
               },
               collateral_shares: 990000000000000000000,
@@ -2283,24 +3657,7 @@ fn convert_credit() {
 }
 
 #[test]
-fn redeem_synthetic_unregistered_vault_errs() {
-    check_err(
-        World::default()
-            .hub()
-            .redeem_synthetic(
-                "does_not_exist".into(),
-                SENDER.into(),
-                SYNTHETIC.into(),
-                1_000,
-                SENDER.into(),
-            )
-            .unwrap_err(),
-        expect!["vault not registered"],
-    )
-}
-
-#[test]
-fn redeem_zero_synthetic_assets_errs() {
+fn convert_all_zero_credit_errs() {
     check_err(
         World::default()
             .handle_cmds(cmds![VaultCmd::Register {
@@ -2308,52 +3665,191 @@ fn redeem_zero_synthetic_assets_errs() {
                 synthetic: SYNTHETIC.into()
             }])
             .hub()
-            .redeem_synthetic(
-                VAULT.into(),
-                SENDER.into(),
-                SYNTHETIC.into(),
-                0,
-                SENDER.into(),
-            )
+            .convert_all_credit(VAULT.into(), SENDER.into())
             .unwrap_err(),
-        expect!["cannot redeem zero"],
+        expect!["nothing to convert"],
     )
 }
 
 #[test]
-fn redeem_invalid_synthetic_asset_errs() {
-    check_err(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
+fn convert_all_credit() {
+    let world = World::default()
+        .total_deposits(1_100)
+        .total_shares_issued(shares_amount(1_000))
+        .handle_cmds(cmds![
+            VaultCmd::Register {
                 vault: VAULT.into(),
                 synthetic: SYNTHETIC.into()
-            }])
-            .hub()
-            .redeem_synthetic(
-                VAULT.into(),
-                SENDER.into(),
-                "unknown_asset".into(),
-                1_000,
-                SENDER.into(),
-            )
-            .unwrap_err(),
-        expect!["invalid synthetic asset"],
-    )
+            },
+            BalanceSheetCmd::SetCollateralBalance {
+                vault: VAULT.into(),
+                balance: 1_000
+            },
+            BalanceSheetCmd::SetCollateralShares {
+                vault: VAULT.into(),
+                shares: shares_amount(1_000)
+            },
+            BalanceSheetCmd::SetAccountCollateral {
+                vault: VAULT.into(),
+                account: SENDER.into(),
+                collateral: 1_000
+            }
+        ]);
+
+    let response = hub(&world, &world, &world)
+        .convert_all_credit(VAULT.into(), SENDER.into())
+        .unwrap();
+
+    check(
+        &response.cdp,
+        expect![[r#"
+        (
+          collateral: 1088,
+          debt: 0,
+          credit: 0,
+          spr: (("0.08999999999999999999999999999999")),
+        )"#]],
+    );
 }
 
 #[test]
-fn redeem_not_from_configured_proxy_errs() {
-    check_err(
-        World::default()
-            .handle_cmds(cmds![
-                VaultCmd::Register {
-                    vault: VAULT.into(),
-                    synthetic: SYNTHETIC.into()
-                },
-                VaultCmd::SetRedeemProxy {
-                    vault: VAULT.into(),
-                    proxy: Some("redeem_proxy".into())
-                }
+fn convert_all_credit_limited_by_reserves() {
+    let world = World::default()
+        .total_deposits(1_100)
+        .total_shares_issued(shares_amount(1_000))
+        .handle_cmds(cmds![
+            VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            },
+            BalanceSheetCmd::SetCollateralBalance {
+                vault: VAULT.into(),
+                balance: 1_000
+            },
+            BalanceSheetCmd::SetCollateralShares {
+                vault: VAULT.into(),
+                shares: shares_amount(1_000)
+            },
+            BalanceSheetCmd::SetAccountCollateral {
+                vault: VAULT.into(),
+                account: SENDER.into(),
+                collateral: 1_000
+            }
+        ]);
+
+    let cmds = hub(&world, &world, &world)
+        .redeem_synthetic(
+            VAULT.into(),
+            SENDER.into(),
+            SYNTHETIC.into(),
+            50,
+            SENDER.into(),
+        )
+        .unwrap();
+
+    let world = world.handle_cmds(cmds);
+
+    let response = world
+        .hub()
+        .convert_all_credit(VAULT.into(), SENDER.into())
+        .unwrap();
+
+    check(
+        &response.cdp,
+        expect![[r#"
+        (
+          collateral: 1039,
+          debt: 0,
+          credit: 49,
+          spr: (("0.08999999999999999999999999999999")),
+        )"#]],
+    );
+
+    let world = world.handle_cmds(response.cmds);
+
+    let vault = world.vaults.get(VAULT).unwrap();
+
+    check(
+        (
+            vault.balances.reserve_balance,
+            vault.balances.reserve_shares,
+        ),
+        expect!["(0, 1)"],
+    );
+}
+
+#[test]
+fn redeem_synthetic_unregistered_vault_errs() {
+    check_err(
+        World::default()
+            .hub()
+            .redeem_synthetic(
+                "does_not_exist".into(),
+                SENDER.into(),
+                SYNTHETIC.into(),
+                1_000,
+                SENDER.into(),
+            )
+            .unwrap_err(),
+        expect!["vault not registered"],
+    )
+}
+
+#[test]
+fn redeem_zero_synthetic_assets_errs() {
+    check_err(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .hub()
+            .redeem_synthetic(
+                VAULT.into(),
+                SENDER.into(),
+                SYNTHETIC.into(),
+                0,
+                SENDER.into(),
+            )
+            .unwrap_err(),
+        expect!["cannot redeem zero"],
+    )
+}
+
+#[test]
+fn redeem_invalid_synthetic_asset_errs() {
+    check_err(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .hub()
+            .redeem_synthetic(
+                VAULT.into(),
+                SENDER.into(),
+                "unknown_asset".into(),
+                1_000,
+                SENDER.into(),
+            )
+            .unwrap_err(),
+        expect!["invalid synthetic asset"],
+    )
+}
+
+#[test]
+fn redeem_not_from_configured_proxy_errs() {
+    check_err(
+        World::default()
+            .handle_cmds(cmds![
+                VaultCmd::Register {
+                    vault: VAULT.into(),
+                    synthetic: SYNTHETIC.into()
+                },
+                VaultCmd::SetRedeemProxy {
+                    vault: VAULT.into(),
+                    proxy: Some("redeem_proxy".into())
+                }
             ])
             .hub()
             .redeem_synthetic(
@@ -2492,6 +3988,13 @@ fn redeem_synthetic() {
               BalanceSheet(SetOverallSumPaymentRatio(
                 vault: "vault",
                 spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
+              )),
+              Event(YieldFeeAccrued(
+                vault: "vault",
+                treasury_shares: (9090909090909090908),
+                collateral_fee: (9090909090909090908),
+                reserve_fee: (0),
               )),
               BalanceSheet(SetAccountDebt(
                 vault: "vault",
@@ -2502,6 +4005,7 @@ fn redeem_synthetic() {
                 vault: "vault",
                 account: "sender",
                 spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
               )),
               Vault(Redeem(
                 vault: "vault",
@@ -2517,54 +4021,267 @@ fn redeem_synthetic() {
     );
 }
 
+// Vault with 90 in reserves, charging 10% on redemptions of any size & 50% on those taking at least half the reserves
 #[test]
-fn mint_synthetic_unregistered_vault_errs() {
-    check_err(
-        World::default()
+fn redeem_synthetic_small_redemption_charged_low_tier_fee() {
+    // 20 burnt at a 10% fee, 18 redeemed from the 90 in reserves
+    check(
+        World::with_vault(1_000)
+            .with_position(SENDER, 1_000, 500)
+            .with_redeem_fee_schedule(&[(0, 1_000), (5_000, 5_000)])
+            .total_deposits(1_100)
             .hub()
-            .mint_synthetic(
-                "does_not_exist".into(),
+            .redeem_synthetic(
+                VAULT.into(),
                 SENDER.into(),
-                VAULT_DEPOSIT_ASSET.into(),
-                DepositAmount(1000),
+                SYNTHETIC.into(),
+                20,
                 SENDER.into(),
             )
-            .unwrap_err(),
-        expect!["vault not registered"],
-    )
+            .unwrap(),
+        expect![[r#"
+            [
+              BalanceSheet(SetCollateralShares(
+                vault: "vault",
+                shares: (909090909090909090910),
+              )),
+              BalanceSheet(SetReserveShares(
+                vault: "vault",
+                shares: (64545454545454545455),
+              )),
+              BalanceSheet(SetReserveBalance(
+                vault: "vault",
+                balance: 71,
+              )),
+              BalanceSheet(SetTreasuryShares(
+                vault: "vault",
+                shares: (9090909090909090908),
+              )),
+              BalanceSheet(SetOverallSumPaymentRatio(
+                vault: "vault",
+                spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
+              )),
+              Event(YieldFeeAccrued(
+                vault: "vault",
+                treasury_shares: (9090909090909090908),
+                collateral_fee: (9090909090909090908),
+                reserve_fee: (0),
+              )),
+              BalanceSheet(SetAccountDebt(
+                vault: "vault",
+                account: "sender",
+                debt: 411,
+              )),
+              BalanceSheet(SetAccountSumPaymentRatio(
+                vault: "vault",
+                account: "sender",
+                spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
+              )),
+              Vault(Redeem(
+                vault: "vault",
+                shares: "vault_shares_asset",
+                amount: (17272727272727272727),
+                recipient: "sender",
+              )),
+              Mint(Burn(
+                synthetic: "synthetic",
+                amount: 20,
+              )),
+            ]"#]],
+    );
 }
 
 #[test]
-fn mint_zero_synthetic_assets_errs() {
-    check_err(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
-                vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
+fn redeem_synthetic_reserve_draining_redemption_charged_high_tier_fee() {
+    // 89 burnt at a 50% fee, 45 redeemed from the 90 in reserves
+    check(
+        World::with_vault(1_000)
+            .with_position(SENDER, 1_000, 500)
+            .with_redeem_fee_schedule(&[(0, 1_000), (5_000, 5_000)])
+            .total_deposits(1_100)
             .hub()
-            .mint_synthetic(
+            .redeem_synthetic(
                 VAULT.into(),
                 SENDER.into(),
-                VAULT_DEPOSIT_ASSET.into(),
-                DepositAmount(0),
+                SYNTHETIC.into(),
+                89,
                 SENDER.into(),
             )
-            .unwrap_err(),
-        expect!["cannot mint zero"],
-    )
+            .unwrap(),
+        expect![[r#"
+            [
+              BalanceSheet(SetCollateralShares(
+                vault: "vault",
+                shares: (909090909090909090910),
+              )),
+              BalanceSheet(SetReserveShares(
+                vault: "vault",
+                shares: (40909090909090909092),
+              )),
+              BalanceSheet(SetReserveBalance(
+                vault: "vault",
+                balance: 45,
+              )),
+              BalanceSheet(SetTreasuryShares(
+                vault: "vault",
+                shares: (9090909090909090908),
+              )),
+              BalanceSheet(SetOverallSumPaymentRatio(
+                vault: "vault",
+                spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
+              )),
+              Event(YieldFeeAccrued(
+                vault: "vault",
+                treasury_shares: (9090909090909090908),
+                collateral_fee: (9090909090909090908),
+                reserve_fee: (0),
+              )),
+              BalanceSheet(SetAccountDebt(
+                vault: "vault",
+                account: "sender",
+                debt: 411,
+              )),
+              BalanceSheet(SetAccountSumPaymentRatio(
+                vault: "vault",
+                account: "sender",
+                spr: (("0.08999999999999999999999999999999")),
+                payment: 89,
+              )),
+              Vault(Redeem(
+                vault: "vault",
+                shares: "vault_shares_asset",
+                amount: (40909090909090909090),
+                recipient: "sender",
+              )),
+              Mint(Burn(
+                synthetic: "synthetic",
+                amount: 89,
+              )),
+            ]"#]],
+    );
 }
 
 #[test]
-fn mint_not_from_configured_proxy_errs() {
-    check_err(
+fn set_redeem_fee_schedule_invalid_tiers_errs() {
+    let world = World::default().handle_cmds(cmds![VaultCmd::Register {
+        vault: VAULT.into(),
+        synthetic: SYNTHETIC.into()
+    }]);
+
+    for tiers in [
+        vec![],
+        // thresholds not increasing
+        vec![(5_000, 100), (5_000, 200)],
+        // fees decreasing
+        vec![(0, 200), (5_000, 100)],
+        // threshold over 100%
+        vec![(10_001, 100)],
+        // fee over 100%
+        vec![(0, 10_001)],
+    ] {
+        check_err(
+            world
+                .configure()
+                .set_redeem_fee_schedule(AdminRole::mock(), VAULT.into(), Some(tiers))
+                .unwrap_err(),
+            expect!["invalid redeem fee schedule"],
+        );
+    }
+}
+
+#[test]
+fn set_redeem_fee_schedule() {
+    check(
         World::default()
-            .handle_cmds(cmds![
-                VaultCmd::Register {
-                    vault: VAULT.into(),
-                    synthetic: SYNTHETIC.into()
-                },
-                VaultCmd::SetMintProxy {
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_redeem_fee_schedule(
+                AdminRole::mock(),
+                VAULT.into(),
+                Some(vec![(0, 10), (5_000, 100)]),
+            )
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetRedeemFeeSchedule(
+                vault: "vault",
+                schedule: Some(([
+                  (
+                    threshold_bps: 0,
+                    threshold: (("0.0")),
+                    fee: (
+                      bps: 10,
+                      rate: (("0.00099999999999999999999999999999")),
+                    ),
+                  ),
+                  (
+                    threshold_bps: 5000,
+                    threshold: (("0.5")),
+                    fee: (
+                      bps: 100,
+                      rate: (("0.00999999999999999999999999999999")),
+                    ),
+                  ),
+                ])),
+              )),
+            ]"#]],
+    );
+}
+
+#[test]
+fn mint_synthetic_unregistered_vault_errs() {
+    check_err(
+        World::default()
+            .hub()
+            .mint_synthetic(
+                "does_not_exist".into(),
+                SENDER.into(),
+                VAULT_DEPOSIT_ASSET.into(),
+                DepositAmount(1000),
+                SENDER.into(),
+            )
+            .unwrap_err(),
+        expect!["vault not registered"],
+    )
+}
+
+#[test]
+fn mint_zero_synthetic_assets_errs() {
+    check_err(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .hub()
+            .mint_synthetic(
+                VAULT.into(),
+                SENDER.into(),
+                VAULT_DEPOSIT_ASSET.into(),
+                DepositAmount(0),
+                SENDER.into(),
+            )
+            .unwrap_err(),
+        expect!["cannot mint zero"],
+    )
+}
+
+#[test]
+fn mint_not_from_configured_proxy_errs() {
+    check_err(
+        World::default()
+            .handle_cmds(cmds![
+                VaultCmd::Register {
+                    vault: VAULT.into(),
+                    synthetic: SYNTHETIC.into()
+                },
+                VaultCmd::SetMintProxy {
                     vault: VAULT.into(),
                     proxy: Some("mint_proxy".into())
                 }
@@ -2735,10 +4452,86 @@ fn vault_deposit_callback_after_deposit() {
                 account: "sender",
                 collateral: 1000,
               )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
             ]"#]],
     )
 }
 
+#[test]
+fn position_seq_increments_per_account() {
+    let world = World::default()
+        .handle_cmds(cmds![
+            VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            },
+            VaultCmd::SetAdvanceEnabled {
+                vault: VAULT.into(),
+                enabled: true
+            }
+        ])
+        .total_deposits(1_000)
+        .total_shares_issued(shares_amount(1_000));
+
+    let position_seq = |world: &World, account: &str| {
+        world.account_position_seq(&VAULT.into(), &account.to_owned().into())
+    };
+
+    let cmds = world
+        .hub()
+        .vault_deposit_callback(
+            VAULT.into(),
+            SENDER.into(),
+            VaultDepositReason::Deposit,
+            shares_amount(1_000),
+            DepositValue(1_000),
+        )
+        .unwrap();
+
+    let world = world.handle_cmds(cmds);
+
+    assert_eq!(position_seq(&world, SENDER), Some(1));
+
+    let cmds = world
+        .hub()
+        .advance(VAULT.into(), SENDER.into(), 300, SENDER.into())
+        .unwrap();
+
+    let world = world.handle_cmds(cmds);
+
+    assert_eq!(position_seq(&world, SENDER), Some(2));
+
+    let PositionResponse { cmds, .. } = world
+        .hub()
+        .repay_synthetic(VAULT.into(), SENDER.into(), SYNTHETIC.into(), 100)
+        .unwrap();
+
+    let world = world.handle_cmds(cmds);
+
+    assert_eq!(position_seq(&world, SENDER), Some(3));
+    assert_eq!(position_seq(&world, "other"), None);
+
+    let cmds = world
+        .hub()
+        .vault_deposit_callback(
+            VAULT.into(),
+            "other".into(),
+            VaultDepositReason::Deposit,
+            shares_amount(1_000),
+            DepositValue(1_000),
+        )
+        .unwrap();
+
+    let world = world.handle_cmds(cmds);
+
+    assert_eq!(position_seq(&world, "other"), Some(1));
+    assert_eq!(position_seq(&world, SENDER), Some(3));
+}
+
 #[test]
 fn vault_deposit_callback_after_repay_underlying() {
     check(
@@ -2771,6 +4564,11 @@ fn vault_deposit_callback_after_repay_underlying() {
                 account: "sender",
                 credit: 1000,
               )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
             ]"#]],
     )
 }
@@ -2816,7 +4614,7 @@ fn claim_treasury_shares_unregistered_vault_errs() {
     check_err(
         World::default()
             .hub()
-            .claim_treasury_shares("does_not_exist".into(), SENDER.into())
+            .claim_treasury_shares("does_not_exist".into(), SENDER.into(), None)
             .unwrap_err(),
         expect!["vault not registered"],
     )
@@ -2831,7 +4629,7 @@ fn claim_treasury_shares_when_no_treasury_set_errs() {
                 synthetic: SYNTHETIC.into()
             }])
             .hub()
-            .claim_treasury_shares(VAULT.into(), SENDER.into())
+            .claim_treasury_shares(VAULT.into(), SENDER.into(), None)
             .unwrap_err(),
         expect!["no treasury set"],
     )
@@ -2851,12 +4649,62 @@ fn claim_treasury_shares_when_not_the_treasury_errs() {
                 }
             ])
             .hub()
-            .claim_treasury_shares(VAULT.into(), SENDER.into())
+            .claim_treasury_shares(VAULT.into(), SENDER.into(), None)
             .unwrap_err(),
         expect!["unauthorized"],
     )
 }
 
+#[test]
+fn set_global_pause_authorization() {
+    let world = World::default().handle_cmds(cmds![BalanceSheetCmd::SetGuardian {
+        guardian: "guardian".into()
+    }]);
+
+    check_err(
+        set_global_pause(&world, None, SENDER.into(), true).unwrap_err(),
+        expect!["unauthorized"],
+    );
+
+    check(
+        set_global_pause(&world, None, "guardian".into(), true).unwrap(),
+        expect![[r#"
+            [
+              BalanceSheet(SetGlobalPause(
+                paused: true,
+              )),
+            ]"#]],
+    );
+
+    check(
+        set_global_pause(&world, Some(AdminRole::mock()), SENDER.into(), false).unwrap(),
+        expect![[r#"
+            [
+              BalanceSheet(SetGlobalPause(
+                paused: false,
+              )),
+            ]"#]],
+    );
+}
+
+#[test]
+fn ensure_not_paused_errs_while_paused() {
+    let mut world = World::default();
+
+    ensure_not_paused(&world).unwrap();
+
+    world = world.handle_cmds(cmds![BalanceSheetCmd::SetGlobalPause { paused: true }]);
+
+    check_err(
+        ensure_not_paused(&world).unwrap_err(),
+        expect!["protocol paused"],
+    );
+
+    world = world.handle_cmds(cmds![BalanceSheetCmd::SetGlobalPause { paused: false }]);
+
+    ensure_not_paused(&world).unwrap();
+}
+
 #[test]
 fn claim_treasury_shares_when_nothing_to_claim_errs() {
     check_err(
@@ -2871,7 +4719,7 @@ fn claim_treasury_shares_when_nothing_to_claim_errs() {
                 }
             ])
             .hub()
-            .claim_treasury_shares(VAULT.into(), "treasury".into())
+            .claim_treasury_shares(VAULT.into(), "treasury".into(), None)
             .unwrap_err(),
         expect!["nothing to claim"],
     )
@@ -2895,7 +4743,53 @@ fn claim_treasury_shares() {
                 }
             ])
             .hub()
-            .claim_treasury_shares(VAULT.into(), "treasury".into())
+            .claim_treasury_shares(VAULT.into(), "treasury".into(), None)
+            .unwrap(),
+        expect![[r#"
+            [
+              BalanceSheet(SetTreasuryShares(
+                vault: "vault",
+                shares: (0),
+              )),
+              BalanceSheet(SendShares(
+                shares: "vault_shares_asset",
+                amount: (10000000000000000000),
+                recipient: "treasury",
+              )),
+            ]"#]],
+    )
+}
+
+#[test]
+fn claim_treasury_shares_partial() {
+    check(
+        World::with_vault(0)
+            .with_treasury_shares("treasury", shares_amount(10))
+            .hub()
+            .claim_treasury_shares(VAULT.into(), "treasury".into(), Some(shares_amount(4)))
+            .unwrap(),
+        expect![[r#"
+            [
+              BalanceSheet(SetTreasuryShares(
+                vault: "vault",
+                shares: (6000000000000000000),
+              )),
+              BalanceSheet(SendShares(
+                shares: "vault_shares_asset",
+                amount: (4000000000000000000),
+                recipient: "treasury",
+              )),
+            ]"#]],
+    )
+}
+
+#[test]
+fn claim_treasury_shares_full_amount() {
+    check(
+        World::with_vault(0)
+            .with_treasury_shares("treasury", shares_amount(10))
+            .hub()
+            .claim_treasury_shares(VAULT.into(), "treasury".into(), Some(shares_amount(10)))
             .unwrap(),
         expect![[r#"
             [
@@ -2912,6 +4806,18 @@ fn claim_treasury_shares() {
     )
 }
 
+#[test]
+fn claim_treasury_shares_over_claim_errs() {
+    check_err(
+        World::with_vault(0)
+            .with_treasury_shares("treasury", shares_amount(10))
+            .hub()
+            .claim_treasury_shares(VAULT.into(), "treasury".into(), Some(shares_amount(11)))
+            .unwrap_err(),
+        expect!["not enough treasury shares"],
+    )
+}
+
 #[test]
 fn claim_amo_shares_unregistered_vault_errs() {
     check_err(
@@ -3017,486 +4923,613 @@ fn claim_amo_shares() {
 }
 
 #[test]
-fn evaluate_vault_loss_errs() {
+fn return_amo_proceeds_when_not_the_amo_errs() {
     check_err(
-        World::default()
-            .handle_cmds(cmds![
-                VaultCmd::Register {
-                    vault: VAULT.into(),
-                    synthetic: SYNTHETIC.into()
-                },
-                BalanceSheetCmd::SetCollateralBalance {
-                    vault: VAULT.into(),
-                    balance: 1_000
-                },
-                BalanceSheetCmd::SetCollateralShares {
-                    vault: VAULT.into(),
-                    shares: shares_amount(1_000)
-                },
-                BalanceSheetCmd::SetAccountCollateral {
-                    vault: VAULT.into(),
-                    account: SENDER.into(),
-                    collateral: 1_000
-                },
-                BalanceSheetCmd::SetAccountDebt {
-                    vault: VAULT.into(),
-                    account: SENDER.into(),
-                    debt: 500
-                }
-            ])
-            .total_deposits(999)
+        World::with_vault(0)
+            .with_amo("amo", AmoAllocation::new(4_000).unwrap())
+            .total_deposits(1_000)
             .total_shares_issued(shares_amount(1_000))
+            .with_deposit(SENDER, 1_000)
             .hub()
-            .evaluate(VAULT.into(), SENDER.into())
+            .return_amo_proceeds(
+                VAULT.into(),
+                SENDER.into(),
+                VAULT_SHARES_ASSET.into(),
+                shares_amount(100).0,
+            )
             .unwrap_err(),
-        expect!["vault shares have suffered a loss in value"],
+        expect!["unauthorized"],
     )
 }
 
 #[test]
-fn evaluate_empty_overall_position() {
-    check(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
-                vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
+fn return_amo_proceeds_invalid_asset_errs() {
+    check_err(
+        World::with_vault(0)
+            .with_amo("amo", AmoAllocation::new(4_000).unwrap())
+            .total_deposits(1_000)
+            .total_shares_issued(shares_amount(1_000))
+            .with_deposit(SENDER, 1_000)
             .hub()
-            .evaluate(VAULT.into(), SENDER.into())
-            .unwrap(),
-        expect![[r#"
-            (
-              cmds: [],
-              cdp: (
-                collateral: 0,
-                debt: 0,
-                credit: 0,
-                spr: (("0.0")),
-              ),
-            )"#]],
+            .return_amo_proceeds(VAULT.into(), "amo".into(), "invalid".into(), 100)
+            .unwrap_err(),
+        expect!["invalid deposit asset"],
     )
 }
 
 #[test]
-fn evaluate_open_position_without_change() {
+fn return_amo_proceeds_as_shares() {
+    let world = World::with_vault(0)
+        .with_amo("amo", AmoAllocation::new(4_000).unwrap())
+        .total_deposits(1_000)
+        .total_shares_issued(shares_amount(1_000))
+        .with_deposit(SENDER, 1_000);
+
+    let cmds = world
+        .hub()
+        .return_amo_proceeds(
+            VAULT.into(),
+            "amo".into(),
+            VAULT_SHARES_ASSET.into(),
+            shares_amount(100).0,
+        )
+        .unwrap();
+
     check(
-        World::default()
-            .total_deposits(1_000)
-            .total_shares_issued(shares_amount(1_000))
-            .handle_cmds(cmds![
-                VaultCmd::Register {
-                    vault: VAULT.into(),
-                    synthetic: SYNTHETIC.into()
-                },
-                BalanceSheetCmd::SetCollateralBalance {
-                    vault: VAULT.into(),
-                    balance: 1_000
-                },
-                BalanceSheetCmd::SetCollateralShares {
-                    vault: VAULT.into(),
-                    shares: shares_amount(1_000)
-                },
-                BalanceSheetCmd::SetAccountCollateral {
-                    vault: VAULT.into(),
-                    account: SENDER.into(),
-                    collateral: 1_000
-                },
-                BalanceSheetCmd::SetAccountDebt {
-                    vault: VAULT.into(),
-                    account: SENDER.into(),
-                    debt: 500
-                }
-            ])
-            .hub()
-            .evaluate(VAULT.into(), SENDER.into())
-            .unwrap(),
+        &cmds,
+        expect![[r#"
+            [
+              BalanceSheet(SetCollateralShares(
+                vault: "vault",
+                shares: (1060000000000000000001),
+              )),
+              BalanceSheet(SetReserveShares(
+                vault: "vault",
+                shares: (39999999999999999999),
+              )),
+              BalanceSheet(SetReserveBalance(
+                vault: "vault",
+                balance: 39,
+              )),
+              BalanceSheet(SetOverallSumPaymentRatio(
+                vault: "vault",
+                spr: (("0.03899999999999999999999999999999")),
+                payment: 38,
+              )),
+              BalanceSheet(SetAmoSharesReturned(
+                vault: "vault",
+                shares: (100000000000000000000),
+              )),
+            ]"#]],
+    );
+
+    let world = world.handle_cmds(cmds);
+
+    let PositionResponse { cdp, .. } = world.hub().evaluate(VAULT.into(), SENDER.into()).unwrap();
+
+    check(
+        cdp,
         expect![[r#"
             (
-              cmds: [],
-              cdp: (
-                collateral: 1000,
-                debt: 500,
-                credit: 0,
-                spr: (("0.0")),
-              ),
+              collateral: 1000,
+              debt: 0,
+              credit: 91,
+              spr: (("0.09199999999999999999999999999999")),
             )"#]],
+    );
+}
+
+#[test]
+fn return_amo_proceeds_as_deposit_asset() {
+    check(
+        World::with_vault(0)
+            .with_amo("amo", AmoAllocation::new(4_000).unwrap())
+            .total_deposits(1_000)
+            .total_shares_issued(shares_amount(1_000))
+            .with_deposit(SENDER, 1_000)
+            .hub()
+            .return_amo_proceeds(VAULT.into(), "amo".into(), VAULT_DEPOSIT_ASSET.into(), 100)
+            .unwrap(),
+        expect![[r#"
+            [
+              BalanceSheet(SetAmoDepositsReturned(
+                vault: "vault",
+                amount: (100),
+              )),
+              Vault(Deposit(
+                vault: "vault",
+                asset: "vault_deposit_asset",
+                amount: (100),
+                callback_recipient: "amo",
+                callback_reason: AmoProceeds,
+              )),
+            ]"#]],
     )
 }
 
 #[test]
-fn evaluate_open_position_with_debt_after_shares_value_increase() {
-    let world = World::default()
-        .total_deposits(1_100)
+fn vault_deposit_callback_after_amo_proceeds() {
+    let world = World::with_vault(0)
+        .with_amo("amo", AmoAllocation::new(4_000).unwrap())
+        .total_deposits(1_000)
         .total_shares_issued(shares_amount(1_000))
-        .handle_cmds(cmds![
-            VaultCmd::Register {
-                vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            },
-            BalanceSheetCmd::SetCollateralBalance {
-                vault: VAULT.into(),
-                balance: 1_000
-            },
-            BalanceSheetCmd::SetCollateralShares {
-                vault: VAULT.into(),
-                shares: shares_amount(1_000)
-            },
-            BalanceSheetCmd::SetAccountCollateral {
-                vault: VAULT.into(),
-                account: SENDER.into(),
-                collateral: 1_000
-            },
-            BalanceSheetCmd::SetAccountDebt {
-                vault: VAULT.into(),
-                account: SENDER.into(),
-                debt: 500
-            }
-        ]);
+        .with_deposit(SENDER, 1_000);
 
-    let response = hub(&world, &world, &world)
-        .evaluate(VAULT.into(), SENDER.into())
+    let cmds = world
+        .hub()
+        .vault_deposit_callback(
+            VAULT.into(),
+            "amo".into(),
+            VaultDepositReason::AmoProceeds,
+            shares_amount(100),
+            DepositValue(100),
+        )
         .unwrap();
 
     check(
-        &response,
+        &cmds,
         expect![[r#"
-            (
-              cmds: [
-                BalanceSheet(SetCollateralShares(
-                  vault: "vault",
-                  shares: (909090909090909090910),
-                )),
-                BalanceSheet(SetReserveShares(
-                  vault: "vault",
-                  shares: (81818181818181818182),
-                )),
-                BalanceSheet(SetReserveBalance(
-                  vault: "vault",
-                  balance: 90,
-                )),
-                BalanceSheet(SetTreasuryShares(
-                  vault: "vault",
-                  shares: (9090909090909090908),
-                )),
-                BalanceSheet(SetOverallSumPaymentRatio(
-                  vault: "vault",
-                  spr: (("0.08999999999999999999999999999999")),
-                )),
-                BalanceSheet(SetAccountDebt(
-                  vault: "vault",
-                  account: "sender",
-                  debt: 411,
-                )),
-                BalanceSheet(SetAccountSumPaymentRatio(
-                  vault: "vault",
-                  account: "sender",
-                  spr: (("0.08999999999999999999999999999999")),
-                )),
-              ],
-              cdp: (
-                collateral: 1000,
-                debt: 411,
-                credit: 0,
-                spr: (("0.08999999999999999999999999999999")),
-              ),
-            )"#]],
+            [
+              BalanceSheet(SetCollateralShares(
+                vault: "vault",
+                shares: (1060000000000000000001),
+              )),
+              BalanceSheet(SetReserveShares(
+                vault: "vault",
+                shares: (39999999999999999999),
+              )),
+              BalanceSheet(SetReserveBalance(
+                vault: "vault",
+                balance: 39,
+              )),
+              BalanceSheet(SetOverallSumPaymentRatio(
+                vault: "vault",
+                spr: (("0.03899999999999999999999999999999")),
+                payment: 38,
+              )),
+            ]"#]],
     );
 
-    let world = world.handle_cmds(response.cmds);
+    let world = world.handle_cmds(cmds);
 
-    let vault = world.vaults.get(VAULT).unwrap();
+    let PositionResponse { cdp, .. } = world.hub().evaluate(VAULT.into(), SENDER.into()).unwrap();
 
     check(
-        &vault.balances,
+        cdp,
         expect![[r#"
             (
-              users: {
-                "sender": (
-                  collateral: 1000,
-                  debt: 411,
-                  credit: 0,
-                  spr: Some((("0.08999999999999999999999999999999"))),
-                ),
-              },
-              collateral_shares: 909090909090909090910,
-              collateral_balance: 1000,
-              reserve_shares: 81818181818181818182,
-              reserve_balance: 90,
-              treasury_shares: 9090909090909090908,
-              amo_shares: 0,
-              spr: Some((("0.08999999999999999999999999999999"))),
+              collateral: 1000,
+              debt: 0,
+              credit: 91,
+              spr: (("0.09199999999999999999999999999999")),
             )"#]],
     );
+}
 
-    check(
-        vault.balances.collateral_shares
-            + vault.balances.reserve_shares
-            + vault.balances.treasury_shares
-            + vault.balances.amo_shares,
-        expect!["1000000000000000000000"],
+#[test]
+fn set_position_operator_unregistered_vault_errs() {
+    check_err(
+        World::default()
+            .hub()
+            .set_position_operator(
+                VAULT.into(),
+                SENDER.into(),
+                Some(OPERATOR.into()),
+                None,
+                false,
+            )
+            .unwrap_err(),
+        expect!["vault not registered"],
     );
+}
 
+#[test]
+fn operator_advance() {
     check(
-        FixedU256::from_u128(vault.balances.collateral_shares)
-            .checked_div(FixedU256::from_u128(world.total_issued_shares.0))
-            .unwrap()
-            .checked_mul(FixedU256::from_u128(world.total_deposits.0))
-            .unwrap()
-            .floor(),
-        expect!["1000"],
+        World::with_vault(1_000)
+            .with_position(SENDER, 1_000, 100)
+            .with_position_operator(Some(300), false)
+            .hub()
+            .operator_advance(VAULT.into(), OPERATOR.into(), SENDER.into(), 200)
+            .unwrap(),
+        expect![[r#"
+            [
+              BalanceSheet(SetAccountDebt(
+                vault: "vault",
+                account: "sender",
+                debt: 300,
+              )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
+              Mint(Mint(
+                synthetic: "synthetic",
+                amount: 200,
+                recipient: "sender",
+              )),
+              BalanceSheet(SetPositionOperator(
+                vault: "vault",
+                account: "sender",
+                operator: Some((
+                  operator: "operator",
+                  max_advance: Some(300),
+                  allow_withdraw: false,
+                  advanced: 200,
+                )),
+              )),
+            ]"#]],
     );
 }
 
 #[test]
-fn evaluate_open_position_without_debt_after_shares_value_increase() {
-    let world = World::default()
-        .total_deposits(1_100)
-        .total_shares_issued(shares_amount(1_000))
-        .handle_cmds(cmds![
-            VaultCmd::Register {
-                vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            },
-            BalanceSheetCmd::SetCollateralBalance {
-                vault: VAULT.into(),
-                balance: 1_000
-            },
-            BalanceSheetCmd::SetCollateralShares {
-                vault: VAULT.into(),
-                shares: shares_amount(1_000)
-            },
-            BalanceSheetCmd::SetAccountCollateral {
-                vault: VAULT.into(),
-                account: SENDER.into(),
-                collateral: 1_000
-            }
-        ]);
+fn operator_advance_limit_is_cumulative() {
+    let mut world = World::with_vault(1_000)
+        .with_position(SENDER, 1_000, 100)
+        .with_position_operator(Some(300), false);
+
+    let cmds = world
+        .hub()
+        .operator_advance(VAULT.into(), OPERATOR.into(), SENDER.into(), 200)
+        .unwrap();
 
-    let response = hub(&world, &world, &world)
-        .evaluate(VAULT.into(), SENDER.into())
+    world = world.handle_cmds(cmds);
+
+    check_err(
+        world
+            .hub()
+            .operator_advance(VAULT.into(), OPERATOR.into(), SENDER.into(), 101)
+            .unwrap_err(),
+        expect!["operator advance limit exceeded"],
+    );
+
+    world
+        .hub()
+        .operator_advance(VAULT.into(), OPERATOR.into(), SENDER.into(), 100)
         .unwrap();
 
-    check(
-        &response,
-        expect![[r#"
-            (
-              cmds: [
-                BalanceSheet(SetCollateralShares(
-                  vault: "vault",
-                  shares: (909090909090909090910),
-                )),
-                BalanceSheet(SetReserveShares(
-                  vault: "vault",
-                  shares: (81818181818181818182),
-                )),
-                BalanceSheet(SetReserveBalance(
-                  vault: "vault",
-                  balance: 90,
-                )),
-                BalanceSheet(SetTreasuryShares(
-                  vault: "vault",
-                  shares: (9090909090909090908),
-                )),
-                BalanceSheet(SetOverallSumPaymentRatio(
-                  vault: "vault",
-                  spr: (("0.08999999999999999999999999999999")),
-                )),
-                BalanceSheet(SetAccountCredit(
-                  vault: "vault",
-                  account: "sender",
-                  credit: 89,
-                )),
-                BalanceSheet(SetAccountSumPaymentRatio(
-                  vault: "vault",
-                  account: "sender",
-                  spr: (("0.08999999999999999999999999999999")),
-                )),
-              ],
-              cdp: (
-                collateral: 1000,
-                debt: 0,
-                credit: 89,
-                spr: (("0.08999999999999999999999999999999")),
-              ),
-            )"#]],
-    );
-
-    let world = world.handle_cmds(response.cmds);
-
-    let vault = world.vaults.get(VAULT).unwrap();
+    // the owner is not bound by the operator's limit
+    world
+        .hub()
+        .advance(VAULT.into(), SENDER.into(), 200, SENDER.into())
+        .unwrap();
+}
 
-    check(
-        &vault.balances,
-        expect![[r#"
-            (
-              users: {
-                "sender": (
-                  collateral: 1000,
-                  debt: 0,
-                  credit: 89,
-                  spr: Some((("0.08999999999999999999999999999999"))),
-                ),
-              },
-              collateral_shares: 909090909090909090910,
-              collateral_balance: 1000,
-              reserve_shares: 81818181818181818182,
-              reserve_balance: 90,
-              treasury_shares: 9090909090909090908,
-              amo_shares: 0,
-              spr: Some((("0.08999999999999999999999999999999"))),
-            )"#]],
-    );
+#[test]
+fn operator_advance_without_limit() {
+    World::with_vault(1_000)
+        .with_position(SENDER, 1_000, 100)
+        .with_position_operator(None, false)
+        .hub()
+        .operator_advance(VAULT.into(), OPERATOR.into(), SENDER.into(), 400)
+        .unwrap();
+}
 
-    check(
-        vault.balances.collateral_shares
-            + vault.balances.reserve_shares
-            + vault.balances.treasury_shares
-            + vault.balances.amo_shares,
-        expect!["1000000000000000000000"],
+#[test]
+fn operator_advance_from_non_operator_errs() {
+    check_err(
+        World::with_vault(1_000)
+            .with_position(SENDER, 1_000, 100)
+            .with_position_operator(None, true)
+            .hub()
+            .operator_advance(VAULT.into(), "not_operator".into(), SENDER.into(), 100)
+            .unwrap_err(),
+        expect!["unauthorized"],
     );
 
-    check(
-        FixedU256::from_u128(vault.balances.collateral_shares)
-            .checked_div(FixedU256::from_u128(world.total_issued_shares.0))
-            .unwrap()
-            .checked_mul(FixedU256::from_u128(world.total_deposits.0))
-            .unwrap()
-            .floor(),
-        expect!["1000"],
+    // the grant only covers the owner's position
+    check_err(
+        World::with_vault(1_000)
+            .with_position(SENDER, 1_000, 100)
+            .with_position_operator(None, true)
+            .hub()
+            .operator_advance(VAULT.into(), OPERATOR.into(), "other".into(), 100)
+            .unwrap_err(),
+        expect!["unauthorized"],
     );
 }
 
 #[test]
-fn evaluate_open_position_with_repaid_debt_after_shares_value_increase() {
-    let world = World::default()
-        .total_deposits(1_100)
-        .total_shares_issued(shares_amount(1_000))
-        .handle_cmds(cmds![
-            VaultCmd::Register {
-                vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            },
-            BalanceSheetCmd::SetCollateralBalance {
-                vault: VAULT.into(),
-                balance: 1_000
-            },
-            BalanceSheetCmd::SetCollateralShares {
-                vault: VAULT.into(),
-                shares: shares_amount(1_000)
-            },
-            BalanceSheetCmd::SetAccountCollateral {
-                vault: VAULT.into(),
-                account: SENDER.into(),
-                collateral: 1_000
-            },
-            BalanceSheetCmd::SetAccountDebt {
-                vault: VAULT.into(),
-                account: SENDER.into(),
-                debt: 50
-            }
-        ]);
+fn operator_advance_with_advance_proxy_errs() {
+    let world = World::with_vault(1_000)
+        .with_position(SENDER, 1_000, 100)
+        .with_position_operator(None, false)
+        .handle_cmds(cmds![VaultCmd::SetAdvanceProxy {
+            vault: VAULT.into(),
+            proxy: Some("proxy".into())
+        }]);
 
-    let response = hub(&world, &world, &world)
-        .evaluate(VAULT.into(), SENDER.into())
-        .unwrap();
+    check_err(
+        world
+            .hub()
+            .operator_advance(VAULT.into(), OPERATOR.into(), SENDER.into(), 100)
+            .unwrap_err(),
+        expect!["unauthorized"],
+    );
+}
 
+#[test]
+fn operator_repay_synthetic() {
     check(
-        &response,
+        World::with_vault(1_000)
+            .with_position(SENDER, 1_000, 100)
+            .with_position_operator(None, false)
+            .hub()
+            .operator_repay_synthetic(
+                VAULT.into(),
+                OPERATOR.into(),
+                SENDER.into(),
+                SYNTHETIC.into(),
+                100,
+            )
+            .unwrap(),
         expect![[r#"
             (
               cmds: [
-                BalanceSheet(SetCollateralShares(
-                  vault: "vault",
-                  shares: (909090909090909090910),
-                )),
-                BalanceSheet(SetReserveShares(
-                  vault: "vault",
-                  shares: (81818181818181818182),
-                )),
-                BalanceSheet(SetReserveBalance(
-                  vault: "vault",
-                  balance: 90,
-                )),
-                BalanceSheet(SetTreasuryShares(
-                  vault: "vault",
-                  shares: (9090909090909090908),
-                )),
-                BalanceSheet(SetOverallSumPaymentRatio(
-                  vault: "vault",
-                  spr: (("0.08999999999999999999999999999999")),
-                )),
                 BalanceSheet(SetAccountDebt(
                   vault: "vault",
                   account: "sender",
                   debt: 0,
                 )),
-                BalanceSheet(SetAccountCredit(
+                BalanceSheet(SetAccountPositionSeq(
                   vault: "vault",
                   account: "sender",
-                  credit: 39,
+                  seq: 1,
                 )),
-                BalanceSheet(SetAccountSumPaymentRatio(
-                  vault: "vault",
-                  account: "sender",
-                  spr: (("0.08999999999999999999999999999999")),
+                Mint(Burn(
+                  synthetic: "synthetic",
+                  amount: 100,
                 )),
               ],
               cdp: (
                 collateral: 1000,
                 debt: 0,
-                credit: 39,
-                spr: (("0.08999999999999999999999999999999")),
+                credit: 0,
+                spr: (("0.0")),
               ),
             )"#]],
     );
+}
 
-    let world = world.handle_cmds(response.cmds);
-
-    let vault = world.vaults.get(VAULT).unwrap();
+#[test]
+fn operator_withdraw_collateral_not_allowed_errs() {
+    check_err(
+        World::with_vault(1_000)
+            .with_position(SENDER, 1_000, 100)
+            .with_position_operator(None, false)
+            .hub()
+            .operator_withdraw_collateral(VAULT.into(), OPERATOR.into(), SENDER.into(), 100)
+            .unwrap_err(),
+        expect!["operator not allowed to withdraw"],
+    );
+}
 
+#[test]
+fn operator_withdraw_collateral() {
     check(
-        &vault.balances,
+        World::with_vault(1_000)
+            .with_position(SENDER, 1_000, 100)
+            .with_position_operator(None, true)
+            .hub()
+            .operator_withdraw_collateral(VAULT.into(), OPERATOR.into(), SENDER.into(), 100)
+            .unwrap()
+            .cmds,
         expect![[r#"
-            (
-              users: {
-                "sender": (
-                  collateral: 1000,
-                  debt: 0,
-                  credit: 39,
-                  spr: Some((("0.08999999999999999999999999999999"))),
-                ),
-              },
-              collateral_shares: 909090909090909090910,
-              collateral_balance: 1000,
-              reserve_shares: 81818181818181818182,
-              reserve_balance: 90,
-              treasury_shares: 9090909090909090908,
-              amo_shares: 0,
-              spr: Some((("0.08999999999999999999999999999999"))),
-            )"#]],
+            [
+              BalanceSheet(SetCollateralShares(
+                vault: "vault",
+                shares: (900000000000000000000),
+              )),
+              BalanceSheet(SetCollateralBalance(
+                vault: "vault",
+                balance: 900,
+              )),
+              BalanceSheet(SetAccountCollateral(
+                vault: "vault",
+                account: "sender",
+                collateral: 900,
+              )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
+              Vault(Redeem(
+                vault: "vault",
+                shares: "vault_shares_asset",
+                amount: (100000000000000000000),
+                recipient: "sender",
+              )),
+            ]"#]],
     );
+}
 
-    check(
-        vault.balances.collateral_shares
-            + vault.balances.reserve_shares
-            + vault.balances.treasury_shares
-            + vault.balances.amo_shares,
-        expect!["1000000000000000000000"],
+#[test]
+fn revoked_position_operator_errs() {
+    let mut world = World::with_vault(1_000)
+        .with_position(SENDER, 1_000, 100)
+        .with_position_operator(None, true);
+
+    let cmds = world
+        .hub()
+        .set_position_operator(VAULT.into(), SENDER.into(), None, None, false)
+        .unwrap();
+
+    world = world.handle_cmds(cmds);
+
+    check_err(
+        world
+            .hub()
+            .operator_advance(VAULT.into(), OPERATOR.into(), SENDER.into(), 100)
+            .unwrap_err(),
+        expect!["unauthorized"],
     );
 
-    check(
-        FixedU256::from_u128(vault.balances.collateral_shares)
-            .checked_div(FixedU256::from_u128(world.total_issued_shares.0))
-            .unwrap()
-            .checked_mul(FixedU256::from_u128(world.total_deposits.0))
-            .unwrap()
-            .floor(),
-        expect!["1000"],
+    check_err(
+        world
+            .hub()
+            .operator_repay_synthetic(
+                VAULT.into(),
+                OPERATOR.into(),
+                SENDER.into(),
+                SYNTHETIC.into(),
+                100,
+            )
+            .unwrap_err(),
+        expect!["unauthorized"],
     );
-}
 
-#[test]
-fn evaluate_open_position_with_debt_no_change_after_previous_shares_value_increase() {
+    check_err(
+        world
+            .hub()
+            .operator_withdraw_collateral(VAULT.into(), OPERATOR.into(), SENDER.into(), 100)
+            .unwrap_err(),
+        expect!["unauthorized"],
+    );
+}
+
+#[test]
+fn regranting_position_operator_resets_advanced() {
+    let mut world = World::with_vault(1_000)
+        .with_position(SENDER, 1_000, 100)
+        .with_position_operator(Some(100), false);
+
+    let cmds = world
+        .hub()
+        .operator_advance(VAULT.into(), OPERATOR.into(), SENDER.into(), 100)
+        .unwrap();
+
+    world = world.handle_cmds(cmds);
+
+    let cmds = world
+        .hub()
+        .set_position_operator(
+            VAULT.into(),
+            SENDER.into(),
+            Some(OPERATOR.into()),
+            Some(100),
+            false,
+        )
+        .unwrap();
+
+    world = world.handle_cmds(cmds);
+
+    world
+        .hub()
+        .operator_advance(VAULT.into(), OPERATOR.into(), SENDER.into(), 100)
+        .unwrap();
+}
+
+#[test]
+fn evaluate_vault_loss_errs() {
+    check_err(
+        World::default()
+            .handle_cmds(cmds![
+                VaultCmd::Register {
+                    vault: VAULT.into(),
+                    synthetic: SYNTHETIC.into()
+                },
+                BalanceSheetCmd::SetCollateralBalance {
+                    vault: VAULT.into(),
+                    balance: 1_000
+                },
+                BalanceSheetCmd::SetCollateralShares {
+                    vault: VAULT.into(),
+                    shares: shares_amount(1_000)
+                },
+                BalanceSheetCmd::SetAccountCollateral {
+                    vault: VAULT.into(),
+                    account: SENDER.into(),
+                    collateral: 1_000
+                },
+                BalanceSheetCmd::SetAccountDebt {
+                    vault: VAULT.into(),
+                    account: SENDER.into(),
+                    debt: 500
+                }
+            ])
+            .total_deposits(999)
+            .total_shares_issued(shares_amount(1_000))
+            .hub()
+            .evaluate(VAULT.into(), SENDER.into())
+            .unwrap_err(),
+        expect!["vault shares have suffered a loss in value"],
+    )
+}
+
+#[test]
+fn evaluate_empty_overall_position() {
+    check(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .hub()
+            .evaluate(VAULT.into(), SENDER.into())
+            .unwrap(),
+        expect![[r#"
+            (
+              cmds: [],
+              cdp: (
+                collateral: 0,
+                debt: 0,
+                credit: 0,
+                spr: (("0.0")),
+              ),
+            )"#]],
+    )
+}
+
+#[test]
+fn evaluate_open_position_without_change() {
+    check(
+        World::default()
+            .total_deposits(1_000)
+            .total_shares_issued(shares_amount(1_000))
+            .handle_cmds(cmds![
+                VaultCmd::Register {
+                    vault: VAULT.into(),
+                    synthetic: SYNTHETIC.into()
+                },
+                BalanceSheetCmd::SetCollateralBalance {
+                    vault: VAULT.into(),
+                    balance: 1_000
+                },
+                BalanceSheetCmd::SetCollateralShares {
+                    vault: VAULT.into(),
+                    shares: shares_amount(1_000)
+                },
+                BalanceSheetCmd::SetAccountCollateral {
+                    vault: VAULT.into(),
+                    account: SENDER.into(),
+                    collateral: 1_000
+                },
+                BalanceSheetCmd::SetAccountDebt {
+                    vault: VAULT.into(),
+                    account: SENDER.into(),
+                    debt: 500
+                }
+            ])
+            .hub()
+            .evaluate(VAULT.into(), SENDER.into())
+            .unwrap(),
+        expect![[r#"
+            (
+              cmds: [],
+              cdp: (
+                collateral: 1000,
+                debt: 500,
+                credit: 0,
+                spr: (("0.0")),
+              ),
+            )"#]],
+    )
+}
+
+#[test]
+fn evaluate_open_position_with_debt_after_shares_value_increase() {
     let world = World::default()
         .total_deposits(1_100)
         .total_shares_issued(shares_amount(1_000))
@@ -3530,14 +5563,49 @@ fn evaluate_open_position_with_debt_no_change_after_previous_shares_value_increa
         .unwrap();
 
     check(
-        world
-            .handle_cmds(response.cmds)
-            .hub()
-            .evaluate(VAULT.into(), SENDER.into())
-            .unwrap(),
+        &response,
         expect![[r#"
             (
-              cmds: [],
+              cmds: [
+                BalanceSheet(SetCollateralShares(
+                  vault: "vault",
+                  shares: (909090909090909090910),
+                )),
+                BalanceSheet(SetReserveShares(
+                  vault: "vault",
+                  shares: (81818181818181818182),
+                )),
+                BalanceSheet(SetReserveBalance(
+                  vault: "vault",
+                  balance: 90,
+                )),
+                BalanceSheet(SetTreasuryShares(
+                  vault: "vault",
+                  shares: (9090909090909090908),
+                )),
+                BalanceSheet(SetOverallSumPaymentRatio(
+                  vault: "vault",
+                  spr: (("0.08999999999999999999999999999999")),
+                  payment: 89,
+                )),
+                Event(YieldFeeAccrued(
+                  vault: "vault",
+                  treasury_shares: (9090909090909090908),
+                  collateral_fee: (9090909090909090908),
+                  reserve_fee: (0),
+                )),
+                BalanceSheet(SetAccountDebt(
+                  vault: "vault",
+                  account: "sender",
+                  debt: 411,
+                )),
+                BalanceSheet(SetAccountSumPaymentRatio(
+                  vault: "vault",
+                  account: "sender",
+                  spr: (("0.08999999999999999999999999999999")),
+                  payment: 89,
+                )),
+              ],
               cdp: (
                 collateral: 1000,
                 debt: 411,
@@ -3546,10 +5614,55 @@ fn evaluate_open_position_with_debt_no_change_after_previous_shares_value_increa
               ),
             )"#]],
     );
+
+    let world = world.handle_cmds(response.cmds);
+
+    let vault = world.vaults.get(VAULT).unwrap();
+
+    check(
+        &vault.balances,
+        expect![[r#"
+            (
+              users: {
+                "sender": (
+                  collateral: 1000,
+                  debt: 411,
+                  credit: 0,
+                  spr: Some((("0.08999999999999999999999999999999"))),
+                  position_seq: None,
+                ),
+              },
+              collateral_shares: 909090909090909090910,
+              collateral_balance: 1000,
+              reserve_shares: 81818181818181818182,
+              reserve_balance: 90,
+              treasury_shares: 9090909090909090908,
+              amo_shares: 0,
+              spr: Some((("0.08999999999999999999999999999999"))),
+            )"#]],
+    );
+
+    check(
+        vault.balances.collateral_shares
+            + vault.balances.reserve_shares
+            + vault.balances.treasury_shares
+            + vault.balances.amo_shares,
+        expect!["1000000000000000000000"],
+    );
+
+    check(
+        FixedU256::from_u128(vault.balances.collateral_shares)
+            .checked_div(FixedU256::from_u128(world.total_issued_shares.0))
+            .unwrap()
+            .checked_mul(FixedU256::from_u128(world.total_deposits.0))
+            .unwrap()
+            .floor(),
+        expect!["1000"],
+    );
 }
 
 #[test]
-fn evaluate_sender_without_position_after_another_shares_value_increase() {
+fn evaluate_open_position_without_debt_after_shares_value_increase() {
     let world = World::default()
         .total_deposits(1_100)
         .total_shares_issued(shares_amount(1_000))
@@ -3570,11 +5683,6 @@ fn evaluate_sender_without_position_after_another_shares_value_increase() {
                 vault: VAULT.into(),
                 account: SENDER.into(),
                 collateral: 1_000
-            },
-            BalanceSheetCmd::SetAccountDebt {
-                vault: VAULT.into(),
-                account: SENDER.into(),
-                debt: 500
             }
         ]);
 
@@ -3583,191 +5691,1773 @@ fn evaluate_sender_without_position_after_another_shares_value_increase() {
         .unwrap();
 
     check(
-        world
-            .handle_cmds(response.cmds)
-            .total_deposits(1_200)
-            .hub()
-            .evaluate(VAULT.into(), "someone_else".into())
-            .unwrap(),
+        &response,
         expect![[r#"
             (
               cmds: [
                 BalanceSheet(SetCollateralShares(
                   vault: "vault",
-                  shares: (834090909090909090910),
+                  shares: (909090909090909090910),
                 )),
                 BalanceSheet(SetReserveShares(
                   vault: "vault",
-                  shares: (142651515151515151517),
+                  shares: (81818181818181818182),
                 )),
                 BalanceSheet(SetReserveBalance(
                   vault: "vault",
-                  balance: 171,
+                  balance: 90,
                 )),
                 BalanceSheet(SetTreasuryShares(
                   vault: "vault",
-                  shares: (23257575757575757573),
+                  shares: (9090909090909090908),
                 )),
                 BalanceSheet(SetOverallSumPaymentRatio(
                   vault: "vault",
-                  spr: (("0.17099999999999999999999999999999")),
+                  spr: (("0.08999999999999999999999999999999")),
+                  payment: 89,
+                )),
+                Event(YieldFeeAccrued(
+                  vault: "vault",
+                  treasury_shares: (9090909090909090908),
+                  collateral_fee: (9090909090909090908),
+                  reserve_fee: (0),
+                )),
+                BalanceSheet(SetAccountCredit(
+                  vault: "vault",
+                  account: "sender",
+                  credit: 89,
                 )),
                 BalanceSheet(SetAccountSumPaymentRatio(
                   vault: "vault",
-                  account: "someone_else",
-                  spr: (("0.17099999999999999999999999999999")),
+                  account: "sender",
+                  spr: (("0.08999999999999999999999999999999")),
+                  payment: 89,
                 )),
               ],
               cdp: (
-                collateral: 0,
+                collateral: 1000,
                 debt: 0,
-                credit: 0,
-                spr: (("0.17099999999999999999999999999999")),
-              ),
+                credit: 89,
+                spr: (("0.08999999999999999999999999999999")),
+              ),
+            )"#]],
+    );
+
+    let world = world.handle_cmds(response.cmds);
+
+    let vault = world.vaults.get(VAULT).unwrap();
+
+    check(
+        &vault.balances,
+        expect![[r#"
+            (
+              users: {
+                "sender": (
+                  collateral: 1000,
+                  debt: 0,
+                  credit: 89,
+                  spr: Some((("0.08999999999999999999999999999999"))),
+                  position_seq: None,
+                ),
+              },
+              collateral_shares: 909090909090909090910,
+              collateral_balance: 1000,
+              reserve_shares: 81818181818181818182,
+              reserve_balance: 90,
+              treasury_shares: 9090909090909090908,
+              amo_shares: 0,
+              spr: Some((("0.08999999999999999999999999999999"))),
+            )"#]],
+    );
+
+    check(
+        vault.balances.collateral_shares
+            + vault.balances.reserve_shares
+            + vault.balances.treasury_shares
+            + vault.balances.amo_shares,
+        expect!["1000000000000000000000"],
+    );
+
+    check(
+        FixedU256::from_u128(vault.balances.collateral_shares)
+            .checked_div(FixedU256::from_u128(world.total_issued_shares.0))
+            .unwrap()
+            .checked_mul(FixedU256::from_u128(world.total_deposits.0))
+            .unwrap()
+            .floor(),
+        expect!["1000"],
+    );
+}
+
+#[test]
+fn evaluate_open_position_with_repaid_debt_after_shares_value_increase() {
+    let world = World::default()
+        .total_deposits(1_100)
+        .total_shares_issued(shares_amount(1_000))
+        .handle_cmds(cmds![
+            VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            },
+            BalanceSheetCmd::SetCollateralBalance {
+                vault: VAULT.into(),
+                balance: 1_000
+            },
+            BalanceSheetCmd::SetCollateralShares {
+                vault: VAULT.into(),
+                shares: shares_amount(1_000)
+            },
+            BalanceSheetCmd::SetAccountCollateral {
+                vault: VAULT.into(),
+                account: SENDER.into(),
+                collateral: 1_000
+            },
+            BalanceSheetCmd::SetAccountDebt {
+                vault: VAULT.into(),
+                account: SENDER.into(),
+                debt: 50
+            }
+        ]);
+
+    let response = hub(&world, &world, &world)
+        .evaluate(VAULT.into(), SENDER.into())
+        .unwrap();
+
+    check(
+        &response,
+        expect![[r#"
+            (
+              cmds: [
+                BalanceSheet(SetCollateralShares(
+                  vault: "vault",
+                  shares: (909090909090909090910),
+                )),
+                BalanceSheet(SetReserveShares(
+                  vault: "vault",
+                  shares: (81818181818181818182),
+                )),
+                BalanceSheet(SetReserveBalance(
+                  vault: "vault",
+                  balance: 90,
+                )),
+                BalanceSheet(SetTreasuryShares(
+                  vault: "vault",
+                  shares: (9090909090909090908),
+                )),
+                BalanceSheet(SetOverallSumPaymentRatio(
+                  vault: "vault",
+                  spr: (("0.08999999999999999999999999999999")),
+                  payment: 89,
+                )),
+                Event(YieldFeeAccrued(
+                  vault: "vault",
+                  treasury_shares: (9090909090909090908),
+                  collateral_fee: (9090909090909090908),
+                  reserve_fee: (0),
+                )),
+                BalanceSheet(SetAccountDebt(
+                  vault: "vault",
+                  account: "sender",
+                  debt: 0,
+                )),
+                BalanceSheet(SetAccountCredit(
+                  vault: "vault",
+                  account: "sender",
+                  credit: 39,
+                )),
+                BalanceSheet(SetAccountSumPaymentRatio(
+                  vault: "vault",
+                  account: "sender",
+                  spr: (("0.08999999999999999999999999999999")),
+                  payment: 89,
+                )),
+              ],
+              cdp: (
+                collateral: 1000,
+                debt: 0,
+                credit: 39,
+                spr: (("0.08999999999999999999999999999999")),
+              ),
+            )"#]],
+    );
+
+    let world = world.handle_cmds(response.cmds);
+
+    let vault = world.vaults.get(VAULT).unwrap();
+
+    check(
+        &vault.balances,
+        expect![[r#"
+            (
+              users: {
+                "sender": (
+                  collateral: 1000,
+                  debt: 0,
+                  credit: 39,
+                  spr: Some((("0.08999999999999999999999999999999"))),
+                  position_seq: None,
+                ),
+              },
+              collateral_shares: 909090909090909090910,
+              collateral_balance: 1000,
+              reserve_shares: 81818181818181818182,
+              reserve_balance: 90,
+              treasury_shares: 9090909090909090908,
+              amo_shares: 0,
+              spr: Some((("0.08999999999999999999999999999999"))),
+            )"#]],
+    );
+
+    check(
+        vault.balances.collateral_shares
+            + vault.balances.reserve_shares
+            + vault.balances.treasury_shares
+            + vault.balances.amo_shares,
+        expect!["1000000000000000000000"],
+    );
+
+    check(
+        FixedU256::from_u128(vault.balances.collateral_shares)
+            .checked_div(FixedU256::from_u128(world.total_issued_shares.0))
+            .unwrap()
+            .checked_mul(FixedU256::from_u128(world.total_deposits.0))
+            .unwrap()
+            .floor(),
+        expect!["1000"],
+    );
+}
+
+#[test]
+fn evaluate_open_position_with_debt_no_change_after_previous_shares_value_increase() {
+    let world = World::default()
+        .total_deposits(1_100)
+        .total_shares_issued(shares_amount(1_000))
+        .handle_cmds(cmds![
+            VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            },
+            BalanceSheetCmd::SetCollateralBalance {
+                vault: VAULT.into(),
+                balance: 1_000
+            },
+            BalanceSheetCmd::SetCollateralShares {
+                vault: VAULT.into(),
+                shares: shares_amount(1_000)
+            },
+            BalanceSheetCmd::SetAccountCollateral {
+                vault: VAULT.into(),
+                account: SENDER.into(),
+                collateral: 1_000
+            },
+            BalanceSheetCmd::SetAccountDebt {
+                vault: VAULT.into(),
+                account: SENDER.into(),
+                debt: 500
+            }
+        ]);
+
+    let response = hub(&world, &world, &world)
+        .evaluate(VAULT.into(), SENDER.into())
+        .unwrap();
+
+    check(
+        world
+            .handle_cmds(response.cmds)
+            .hub()
+            .evaluate(VAULT.into(), SENDER.into())
+            .unwrap(),
+        expect![[r#"
+            (
+              cmds: [],
+              cdp: (
+                collateral: 1000,
+                debt: 411,
+                credit: 0,
+                spr: (("0.08999999999999999999999999999999")),
+              ),
             )"#]],
     );
 }
 
 #[test]
-fn evaluate_sender_with_position_after_shares_increase_acknowledged_by_somebody_else() {
-    let world = World::default()
-        .handle_cmds(cmds![
-            VaultCmd::Register {
+fn evaluate_sender_without_position_after_another_shares_value_increase() {
+    let world = World::default()
+        .total_deposits(1_100)
+        .total_shares_issued(shares_amount(1_000))
+        .handle_cmds(cmds![
+            VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            },
+            BalanceSheetCmd::SetCollateralBalance {
+                vault: VAULT.into(),
+                balance: 1_000
+            },
+            BalanceSheetCmd::SetCollateralShares {
+                vault: VAULT.into(),
+                shares: shares_amount(1_000)
+            },
+            BalanceSheetCmd::SetAccountCollateral {
+                vault: VAULT.into(),
+                account: SENDER.into(),
+                collateral: 1_000
+            },
+            BalanceSheetCmd::SetAccountDebt {
+                vault: VAULT.into(),
+                account: SENDER.into(),
+                debt: 500
+            }
+        ]);
+
+    let response = hub(&world, &world, &world)
+        .evaluate(VAULT.into(), SENDER.into())
+        .unwrap();
+
+    check(
+        world
+            .handle_cmds(response.cmds)
+            .total_deposits(1_200)
+            .hub()
+            .evaluate(VAULT.into(), "someone_else".into())
+            .unwrap(),
+        expect![[r#"
+            (
+              cmds: [
+                BalanceSheet(SetCollateralShares(
+                  vault: "vault",
+                  shares: (834090909090909090910),
+                )),
+                BalanceSheet(SetReserveShares(
+                  vault: "vault",
+                  shares: (142651515151515151517),
+                )),
+                BalanceSheet(SetReserveBalance(
+                  vault: "vault",
+                  balance: 171,
+                )),
+                BalanceSheet(SetTreasuryShares(
+                  vault: "vault",
+                  shares: (23257575757575757573),
+                )),
+                BalanceSheet(SetOverallSumPaymentRatio(
+                  vault: "vault",
+                  spr: (("0.17099999999999999999999999999999")),
+                  payment: 80,
+                )),
+                Event(YieldFeeAccrued(
+                  vault: "vault",
+                  treasury_shares: (23257575757575757573),
+                  collateral_fee: (7499999999999999999),
+                  reserve_fee: (6666666666666666666),
+                )),
+                BalanceSheet(SetAccountSumPaymentRatio(
+                  vault: "vault",
+                  account: "someone_else",
+                  spr: (("0.17099999999999999999999999999999")),
+                  payment: 0,
+                )),
+              ],
+              cdp: (
+                collateral: 0,
+                debt: 0,
+                credit: 0,
+                spr: (("0.17099999999999999999999999999999")),
+              ),
+            )"#]],
+    );
+}
+
+#[test]
+fn evaluate_sender_with_position_after_shares_increase_acknowledged_by_somebody_else() {
+    let world = World::default()
+        .handle_cmds(cmds![
+            VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            },
+            BalanceSheetCmd::SetCollateralBalance {
+                vault: VAULT.into(),
+                balance: 1_000
+            },
+            BalanceSheetCmd::SetCollateralShares {
+                vault: VAULT.into(),
+                shares: shares_amount(1_000)
+            },
+            BalanceSheetCmd::SetAccountCollateral {
+                vault: VAULT.into(),
+                account: SENDER.into(),
+                collateral: 1_000
+            },
+            BalanceSheetCmd::SetAccountDebt {
+                vault: VAULT.into(),
+                account: SENDER.into(),
+                debt: 500
+            }
+        ])
+        .total_deposits(1_100)
+        .total_shares_issued(shares_amount(1_000));
+
+    let response = hub(&world, &world, &world)
+        .evaluate(VAULT.into(), "someone_else".into())
+        .unwrap();
+
+    check(
+        world
+            .handle_cmds(response.cmds)
+            .hub()
+            .evaluate(VAULT.into(), SENDER.into())
+            .unwrap(),
+        expect![[r#"
+            (
+              cmds: [
+                BalanceSheet(SetAccountDebt(
+                  vault: "vault",
+                  account: "sender",
+                  debt: 411,
+                )),
+                BalanceSheet(SetAccountSumPaymentRatio(
+                  vault: "vault",
+                  account: "sender",
+                  spr: (("0.08999999999999999999999999999999")),
+                  payment: 89,
+                )),
+              ],
+              cdp: (
+                collateral: 1000,
+                debt: 411,
+                credit: 0,
+                spr: (("0.08999999999999999999999999999999")),
+              ),
+            )"#]],
+    );
+}
+
+#[test]
+fn register_already_registered_vault_errs() {
+    check_err(
+        World::default()
+            .configure()
+            .register_vault(
+                AdminRole::mock(),
+                ALREADY_REGISTERED_VAULT.into(),
+                SYNTHETIC.into(),
+            )
+            .unwrap_err(),
+        expect!["vault already registered"],
+    )
+}
+
+#[test]
+fn register_vault_non_existing_synthetic_errs() {
+    check_err(
+        World::default()
+            .configure()
+            .register_vault(AdminRole::mock(), VAULT.into(), "does_not_exist".into())
+            .unwrap_err(),
+        expect!["synthetic not found"],
+    )
+}
+
+#[test]
+fn register_vault_mismatching_synthetic_decimals_errs() {
+    check_err(
+        World::default()
+            .configure()
+            .register_vault(
+                AdminRole::mock(),
+                VAULT.into(),
+                EIGHT_DECIMAL_SYNTHETIC.into(),
+            )
+            .unwrap_err(),
+        expect!["decimals mismatch"],
+    )
+}
+
+#[test]
+fn register_vault_mismatching_shares_decimals_errs() {
+    check_err(
+        World::default()
+            .configure()
+            .register_vault(
+                AdminRole::mock(),
+                SIX_DECIMAL_SHARES_VAULT.into(),
+                SYNTHETIC.into(),
+            )
+            .unwrap_err(),
+        expect!["shares decimals mismatch"],
+    )
+}
+
+#[test]
+fn register_vault_unsupported_interface_version_errs() {
+    check_err(
+        World::default()
+            .configure()
+            .register_vault(
+                AdminRole::mock(),
+                NEXT_MAJOR_INTERFACE_VAULT.into(),
+                SYNTHETIC.into(),
+            )
+            .unwrap_err(),
+        expect!["unsupported vault interface version"],
+    )
+}
+
+#[test]
+fn interface_major_version_parses_semver() {
+    assert_eq!(interface_major_version("1.2.3"), Some(1));
+    assert_eq!(interface_major_version("2"), Some(2));
+    assert_eq!(interface_major_version("v1.0.0"), None);
+    assert_eq!(interface_major_version(""), None);
+}
+
+#[test]
+fn register_vault() {
+    check(
+        World::default()
+            .configure()
+            .register_vault(AdminRole::mock(), VAULT.into(), SYNTHETIC.into())
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(Register(
+                vault: "vault",
+                synthetic: "synthetic",
+              )),
+              Vault(SetInterfaceVersion(
+                vault: "vault",
+                version: "1.0.0",
+                features: [],
+              )),
+              BalanceSheet(TrackTotalCollateral(
+                vault: "vault",
+              )),
+            ]"#]],
+    )
+}
+
+#[test]
+fn deregister_empty_vault() {
+    let world = World::default().handle_cmds(cmds![VaultCmd::Register {
+        vault: VAULT.into(),
+        synthetic: SYNTHETIC.into()
+    }]);
+
+    let cmds = deregister_vault(&world, &world, AdminRole::mock(), VAULT.into()).unwrap();
+
+    check(
+        &cmds,
+        expect![[r#"
+            [
+              Vault(SetAdvanceFeeOracleEpoch(
+                vault: "vault",
+                epoch: 1,
+              )),
+              Vault(Deregister(
+                vault: "vault",
+              )),
+            ]"#]],
+    );
+
+    let world = world.handle_cmds(cmds);
+
+    assert!(!world.is_registered(&VAULT.into()));
+
+    check_err(
+        deregister_vault(&world, &world, AdminRole::mock(), VAULT.into()).unwrap_err(),
+        expect!["vault not registered"],
+    );
+}
+
+#[test]
+fn deregister_vault_with_collateral_errs() {
+    let world = World::default().handle_cmds(cmds![
+        VaultCmd::Register {
+            vault: VAULT.into(),
+            synthetic: SYNTHETIC.into()
+        },
+        BalanceSheetCmd::SetCollateralBalance {
+            vault: VAULT.into(),
+            balance: 1_000
+        },
+        BalanceSheetCmd::SetCollateralShares {
+            vault: VAULT.into(),
+            shares: shares_amount(1_000)
+        },
+        BalanceSheetCmd::SetAccountCollateral {
+            vault: VAULT.into(),
+            account: SENDER.into(),
+            collateral: 1_000
+        }
+    ]);
+
+    check_err(
+        deregister_vault(&world, &world, AdminRole::mock(), VAULT.into()).unwrap_err(),
+        expect!["vault not empty"],
+    );
+}
+
+#[test]
+fn deregister_vault_with_debt_reserves_or_unclaimed_shares_errs() {
+    let registered = || {
+        World::default().handle_cmds(cmds![VaultCmd::Register {
+            vault: VAULT.into(),
+            synthetic: SYNTHETIC.into()
+        }])
+    };
+
+    let world = registered().handle_cmds(cmds![BalanceSheetCmd::SetAccountDebt {
+        vault: VAULT.into(),
+        account: SENDER.into(),
+        debt: 1
+    }]);
+
+    check_err(
+        deregister_vault(&world, &world, AdminRole::mock(), VAULT.into()).unwrap_err(),
+        expect!["vault not empty"],
+    );
+
+    let world = registered().handle_cmds(cmds![BalanceSheetCmd::SetReserveBalance {
+        vault: VAULT.into(),
+        balance: 1
+    }]);
+
+    check_err(
+        deregister_vault(&world, &world, AdminRole::mock(), VAULT.into()).unwrap_err(),
+        expect!["vault not empty"],
+    );
+
+    let world = registered().handle_cmds(cmds![BalanceSheetCmd::SetTreasuryShares {
+        vault: VAULT.into(),
+        shares: SharesAmount(1)
+    }]);
+
+    check_err(
+        deregister_vault(&world, &world, AdminRole::mock(), VAULT.into()).unwrap_err(),
+        expect!["vault not empty"],
+    );
+
+    let world = registered().handle_cmds(cmds![BalanceSheetCmd::SetAmoShares {
+        vault: VAULT.into(),
+        shares: SharesAmount(1)
+    }]);
+
+    check_err(
+        deregister_vault(&world, &world, AdminRole::mock(), VAULT.into()).unwrap_err(),
+        expect!["vault not empty"],
+    );
+}
+
+#[test]
+fn set_loss_tolerance() {
+    check(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_loss_tolerance(AdminRole::mock(), VAULT.into(), 100)
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetLossTolerance(
+                vault: "vault",
+                tolerance: (
+                  bps: 100,
+                  rate: (("0.00999999999999999999999999999999")),
+                ),
+              )),
+            ]"#]],
+    );
+
+    check_err(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_loss_tolerance(AdminRole::mock(), VAULT.into(), 1_001)
+            .unwrap_err(),
+        expect!["invalid rate"],
+    );
+
+    check_err(
+        World::default()
+            .configure()
+            .set_loss_tolerance(AdminRole::mock(), VAULT.into(), 100)
+            .unwrap_err(),
+        expect!["vault not registered"],
+    );
+}
+
+#[test]
+fn set_loss_socialisation_enabled() {
+    check(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_loss_socialisation_enabled(AdminRole::mock(), VAULT.into(), true)
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetLossSocialisationEnabled(
+                vault: "vault",
+                enabled: true,
+              )),
+            ]"#]],
+    );
+
+    check_err(
+        World::default()
+            .configure()
+            .set_loss_socialisation_enabled(AdminRole::mock(), VAULT.into(), true)
+            .unwrap_err(),
+        expect!["vault not registered"],
+    );
+}
+
+// Sender holds 600 collateral & 200 debt, other holds 400 collateral, while the vault shares have lost 1% in value
+#[test]
+fn withdraw_collateral_within_loss_tolerance() {
+    check(
+        World::with_vault(1_000)
+            .with_deposits_enabled()
+            .with_reserve(100)
+            .with_position(SENDER, 600, 200)
+            .with_position("other", 400, 0)
+            .with_loss_tolerance(100)
+            .total_deposits(1_089)
+            .hub()
+            .withdraw_collateral(VAULT.into(), SENDER.into(), 100)
+            .unwrap(),
+        expect![[r#"
+            (
+              cmds: [
+                BalanceSheet(SetCollateralShares(
+                  vault: "vault",
+                  shares: (900000000000000000000),
+                )),
+                BalanceSheet(SetCollateralBalance(
+                  vault: "vault",
+                  balance: 900,
+                )),
+                BalanceSheet(SetAccountCollateral(
+                  vault: "vault",
+                  account: "sender",
+                  collateral: 500,
+                )),
+                BalanceSheet(SetAccountPositionSeq(
+                  vault: "vault",
+                  account: "sender",
+                  seq: 1,
+                )),
+                Vault(Redeem(
+                  vault: "vault",
+                  shares: "vault_shares_asset",
+                  amount: (100000000000000000000),
+                  recipient: "sender",
+                )),
+              ],
+              cdp: (
+                collateral: 500,
+                debt: 200,
+                credit: 0,
+                spr: (("0.0")),
+              ),
+            )"#]],
+    );
+}
+
+#[test]
+fn repay_within_loss_tolerance() {
+    let world = World::with_vault(1_000)
+        .with_deposits_enabled()
+        .with_reserve(100)
+        .with_position(SENDER, 600, 200)
+        .with_position("other", 400, 0)
+        .with_loss_tolerance(100)
+        .total_deposits(1_089);
+
+    check(
+        world
+            .hub()
+            .repay_synthetic(VAULT.into(), SENDER.into(), SYNTHETIC.into(), 100)
+            .unwrap(),
+        expect![[r#"
+            (
+              cmds: [
+                BalanceSheet(SetAccountDebt(
+                  vault: "vault",
+                  account: "sender",
+                  debt: 100,
+                )),
+                BalanceSheet(SetAccountPositionSeq(
+                  vault: "vault",
+                  account: "sender",
+                  seq: 1,
+                )),
+                Mint(Burn(
+                  synthetic: "synthetic",
+                  amount: 100,
+                )),
+              ],
+              cdp: (
+                collateral: 600,
+                debt: 100,
+                credit: 0,
+                spr: (("0.0")),
+              ),
+            )"#]],
+    );
+
+    check(
+        world
+            .hub()
+            .repay_underlying(
+                VAULT.into(),
+                SENDER.into(),
+                VAULT_DEPOSIT_ASSET.into(),
+                DepositAmount(100),
+            )
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(Deposit(
+                vault: "vault",
+                asset: "vault_deposit_asset",
+                amount: (100),
+                callback_recipient: "sender",
+                callback_reason: RepayUnderlying,
+              )),
+            ]"#]],
+    );
+
+    assert!(world
+        .hub()
+        .withdraw_all_collateral(VAULT.into(), "other".into())
+        .is_ok());
+}
+
+#[test]
+fn deposit_and_advance_within_loss_tolerance_errs() {
+    let world = World::with_vault(1_000)
+        .with_deposits_enabled()
+        .with_reserve(100)
+        .with_position(SENDER, 600, 200)
+        .with_position("other", 400, 0)
+        .with_loss_tolerance(100)
+        .total_deposits(1_089);
+
+    check_err(
+        world
+            .hub()
+            .deposit(
+                VAULT.into(),
+                SENDER.into(),
+                VAULT_DEPOSIT_ASSET.into(),
+                DepositAmount(1_000),
+                SENDER.into(),
+            )
+            .unwrap_err(),
+        expect!["vault shares have suffered a loss in value"],
+    );
+
+    check_err(
+        world
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 100, SENDER.into())
+            .unwrap_err(),
+        expect!["vault shares have suffered a loss in value"],
+    );
+}
+
+#[test]
+fn advance_with_socialised_loss_within_tolerance() {
+    // the 1% loss is borne by all collateral holders, the sender's 600 collateral becoming 593 after rounding
+    check(
+        World::with_vault(1_000)
+            .with_deposits_enabled()
+            .with_reserve(100)
+            .with_position(SENDER, 600, 200)
+            .with_position("other", 400, 0)
+            .with_loss_tolerance(100)
+            .with_loss_socialisation_enabled()
+            .total_deposits(1_089)
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 50, SENDER.into())
+            .unwrap(),
+        expect![[r#"
+            [
+              BalanceSheet(SetAccountCollateral(
+                vault: "vault",
+                account: "sender",
+                collateral: 593,
+              )),
+              BalanceSheet(SetAccountDebt(
+                vault: "vault",
+                account: "sender",
+                debt: 250,
+              )),
+              BalanceSheet(SetAccountCollateralFactor(
+                vault: "vault",
+                account: "sender",
+                factor: (("0.98999999999999999999999999999999")),
+              )),
+              BalanceSheet(SetAccountPositionSeq(
+                vault: "vault",
+                account: "sender",
+                seq: 1,
+              )),
+              BalanceSheet(SetCollateralBalance(
+                vault: "vault",
+                balance: 990,
+              )),
+              BalanceSheet(SetReserveBalance(
+                vault: "vault",
+                balance: 99,
+              )),
+              BalanceSheet(SetCollateralFactor(
+                vault: "vault",
+                factor: (("0.98999999999999999999999999999999")),
+              )),
+              Mint(Mint(
+                synthetic: "synthetic",
+                amount: 50,
+                recipient: "sender",
+              )),
+            ]"#]],
+    );
+}
+
+#[test]
+fn deposit_and_advance_with_socialised_loss_beyond_tolerance_errs() {
+    let world = World::with_vault(1_000)
+        .with_deposits_enabled()
+        .with_reserve(100)
+        .with_position(SENDER, 600, 200)
+        .with_position("other", 400, 0)
+        .with_loss_tolerance(50)
+        .with_loss_socialisation_enabled()
+        .total_deposits(1_089);
+
+    check_err(
+        world
+            .hub()
+            .deposit(
+                VAULT.into(),
+                SENDER.into(),
+                VAULT_DEPOSIT_ASSET.into(),
+                DepositAmount(1_000),
+                SENDER.into(),
+            )
+            .unwrap_err(),
+        expect!["vault shares have suffered a loss in value"],
+    );
+
+    check_err(
+        world
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 100, SENDER.into())
+            .unwrap_err(),
+        expect!["vault shares have suffered a loss in value"],
+    );
+}
+
+#[test]
+fn withdraw_and_repay_beyond_loss_tolerance_errs() {
+    let world = World::with_vault(1_000)
+        .with_deposits_enabled()
+        .with_reserve(100)
+        .with_position(SENDER, 600, 200)
+        .with_position("other", 400, 0)
+        .with_loss_tolerance(50)
+        .total_deposits(1_089);
+
+    let errs = [
+        world
+            .hub()
+            .withdraw_collateral(VAULT.into(), SENDER.into(), 100)
+            .map(drop),
+        world
+            .hub()
+            .withdraw_all_collateral(VAULT.into(), "other".into())
+            .map(drop),
+        world
+            .hub()
+            .repay_synthetic(VAULT.into(), SENDER.into(), SYNTHETIC.into(), 100)
+            .map(drop),
+        world
+            .hub()
+            .repay_underlying(
+                VAULT.into(),
+                SENDER.into(),
+                VAULT_DEPOSIT_ASSET.into(),
+                DepositAmount(100),
+            )
+            .map(drop),
+    ];
+
+    for err in errs {
+        check_err(
+            err.unwrap_err(),
+            expect!["vault shares have suffered a loss in value"],
+        );
+    }
+}
+
+#[test]
+fn acknowledge_loss() {
+    let world = World::with_vault(1_000)
+        .with_deposits_enabled()
+        .with_reserve(100)
+        .with_position(SENDER, 600, 200)
+        .with_position("other", 400, 0)
+        .with_loss_tolerance(0)
+        .total_deposits(1_089);
+
+    let cmds = super::acknowledge_loss(&world, &world, AdminRole::mock(), VAULT.into()).unwrap();
+
+    check(
+        &cmds,
+        expect![[r#"
+        [
+          BalanceSheet(SetCollateralBalance(
+            vault: "vault",
+            balance: 990,
+          )),
+          BalanceSheet(SetReserveBalance(
+            vault: "vault",
+            balance: 99,
+          )),
+          BalanceSheet(SetCollateralFactor(
+            vault: "vault",
+            factor: (("0.98999999999999999999999999999999")),
+          )),
+        ]"#]],
+    );
+
+    let world = world.handle_cmds(cmds);
+
+    // each position bears the collateral loss in proportion to its collateral
+    check(
+        world.hub().evaluate(VAULT.into(), SENDER.into()).unwrap(),
+        expect![[r#"
+            (
+              cmds: [
+                BalanceSheet(SetAccountCollateral(
+                  vault: "vault",
+                  account: "sender",
+                  collateral: 593,
+                )),
+                BalanceSheet(SetAccountCollateralFactor(
+                  vault: "vault",
+                  account: "sender",
+                  factor: (("0.98999999999999999999999999999999")),
+                )),
+              ],
+              cdp: (
+                collateral: 593,
+                debt: 200,
+                credit: 0,
+                spr: (("0.0")),
+                collateral_factor: (("0.98999999999999999999999999999999")),
+              ),
+            )"#]],
+    );
+
+    check(
+        world.hub().evaluate(VAULT.into(), "other".into()).unwrap(),
+        expect![[r#"
+            (
+              cmds: [
+                BalanceSheet(SetAccountCollateral(
+                  vault: "vault",
+                  account: "other",
+                  collateral: 395,
+                )),
+                BalanceSheet(SetAccountCollateralFactor(
+                  vault: "vault",
+                  account: "other",
+                  factor: (("0.98999999999999999999999999999999")),
+                )),
+              ],
+              cdp: (
+                collateral: 395,
+                debt: 0,
+                credit: 0,
+                spr: (("0.0")),
+                collateral_factor: (("0.98999999999999999999999999999999")),
+              ),
+            )"#]],
+    );
+
+    // deposits & advances resume
+    assert!(world
+        .hub()
+        .advance(VAULT.into(), SENDER.into(), 50, SENDER.into())
+        .is_ok());
+
+    assert!(world
+        .hub()
+        .deposit(
+            VAULT.into(),
+            SENDER.into(),
+            VAULT_DEPOSIT_ASSET.into(),
+            DepositAmount(1_000),
+            SENDER.into(),
+        )
+        .is_ok());
+
+    check_err(
+        super::acknowledge_loss(&world, &world, AdminRole::mock(), VAULT.into()).unwrap_err(),
+        expect!["no loss to acknowledge"],
+    );
+}
+
+#[test]
+fn yield_after_acknowledged_loss() {
+    let world = World::with_vault(1_000)
+        .with_deposits_enabled()
+        .with_reserve(100)
+        .with_position(SENDER, 600, 200)
+        .with_position("other", 400, 0)
+        .with_loss_tolerance(0)
+        .total_deposits(1_089);
+
+    let cmds = super::acknowledge_loss(&world, &world, AdminRole::mock(), VAULT.into()).unwrap();
+
+    // the shares recover their value: 990 collateral & 99 reserves are now worth 1,000 & 100
+    let world = world.handle_cmds(cmds).total_deposits(1_100);
+
+    // the sender holds 60% of the collateral, so receives 60% of the collateral yield
+    check(
+        world.hub().evaluate(VAULT.into(), SENDER.into()).unwrap(),
+        expect![[r#"
+            (
+              cmds: [
+                BalanceSheet(SetCollateralShares(
+                  vault: "vault",
+                  shares: (990000000000000000000),
+                )),
+                BalanceSheet(SetReserveShares(
+                  vault: "vault",
+                  shares: (108000000000000000001),
+                )),
+                BalanceSheet(SetReserveBalance(
+                  vault: "vault",
+                  balance: 108,
+                )),
+                BalanceSheet(SetTreasuryShares(
+                  vault: "vault",
+                  shares: (1999999999999999999),
+                )),
+                BalanceSheet(SetOverallSumPaymentRatio(
+                  vault: "vault",
+                  spr: (("0.00899999999999999999999999999999")),
+                  payment: 8,
+                )),
+                Event(YieldFeeAccrued(
+                  vault: "vault",
+                  treasury_shares: (1999999999999999999),
+                  collateral_fee: (999999999999999999),
+                  reserve_fee: (1000000000000000000),
+                )),
+                BalanceSheet(SetAccountCollateral(
+                  vault: "vault",
+                  account: "sender",
+                  collateral: 593,
+                )),
+                BalanceSheet(SetAccountDebt(
+                  vault: "vault",
+                  account: "sender",
+                  debt: 195,
+                )),
+                BalanceSheet(SetAccountSumPaymentRatio(
+                  vault: "vault",
+                  account: "sender",
+                  spr: (("0.00899999999999999999999999999999")),
+                  payment: 5,
+                )),
+                BalanceSheet(SetAccountCollateralFactor(
+                  vault: "vault",
+                  account: "sender",
+                  factor: (("0.98999999999999999999999999999999")),
+                )),
+              ],
+              cdp: (
+                collateral: 593,
+                debt: 195,
+                credit: 0,
+                spr: (("0.00899999999999999999999999999999")),
+                collateral_factor: (("0.98999999999999999999999999999999")),
+              ),
+            )"#]],
+    );
+}
+
+#[test]
+fn yield_fee_accrued_matches_treasury_share_increase() {
+    // both pools accrue yield twice, so the second accrual adds to existing treasury shares
+    let mut world = World::with_vault(1_000)
+        .with_deposits_enabled()
+        .with_reserve(100)
+        .with_position(SENDER, 600, 200)
+        .with_position("other", 400, 0)
+        .with_loss_tolerance(0)
+        .total_deposits(1_089);
+
+    for total_deposits in [1_210, 1_331] {
+        world = world.total_deposits(total_deposits);
+
+        let prev_treasury_shares = world.treasury_shares(&VAULT.into()).unwrap_or_default();
+
+        let PositionResponse { cmds, .. } =
+            world.hub().evaluate(VAULT.into(), SENDER.into()).unwrap();
+
+        let set_treasury_shares = cmds
+            .iter()
+            .find_map(|cmd| match cmd {
+                Cmd::BalanceSheet(BalanceSheetCmd::SetTreasuryShares { shares, .. }) => {
+                    Some(*shares)
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        let Some(Cmd::Event(BalanceSheetEvent::YieldFeeAccrued {
+            treasury_shares,
+            collateral_fee,
+            reserve_fee,
+            ..
+        })) = cmds
+            .iter()
+            .find(|cmd| matches!(cmd, Cmd::Event(_)))
+            .cloned()
+        else {
+            panic!("yield fee accrued event emitted");
+        };
+
+        assert!(collateral_fee.0 > 0);
+        assert!(reserve_fee.0 > 0);
+        assert_eq!(treasury_shares, set_treasury_shares);
+        assert_eq!(
+            collateral_fee.0 + reserve_fee.0,
+            set_treasury_shares.0 - prev_treasury_shares.0
+        );
+
+        world = world.handle_cmds(cmds);
+    }
+}
+
+#[test]
+fn sweep_dust() {
+    let world = World::with_vault(1_000)
+        .with_deposits_enabled()
+        .with_reserve(100)
+        .with_position(SENDER, 600, 200)
+        .with_position("other", 400, 0)
+        .with_loss_tolerance(0)
+        .total_deposits(1_089);
+
+    check_err(
+        super::sweep_dust(&world, &world, AdminRole::mock(), VAULT.into()).unwrap_err(),
+        expect!["vault shares have suffered a loss in value"],
+    );
+
+    let cmds = super::acknowledge_loss(&world, &world, AdminRole::mock(), VAULT.into()).unwrap();
+
+    let world = world.handle_cmds(cmds);
+
+    // positions are yet to be rescaled, so all of the collateral is still attributed
+    check_err(
+        super::sweep_dust(&world, &world, AdminRole::mock(), VAULT.into()).unwrap_err(),
+        expect!["no dust to sweep"],
+    );
+
+    let mut world = world;
+
+    for account in [SENDER, "other"] {
+        let PositionResponse { cmds, .. } =
+            world.hub().evaluate(VAULT.into(), account.into()).unwrap();
+
+        world = world.handle_cmds(cmds);
+    }
+
+    // rescaled positions total 593 + 395 = 988 collateral, leaving 2 of the 990 balance as dust,
+    // but the rebased pool's shares are worth exactly its balance, so none can be spared to back the dust
+    check_err(
+        super::sweep_dust(&world, &world, AdminRole::mock(), VAULT.into()).unwrap_err(),
+        expect!["the collateral pool cannot spare the shares backing the dust"],
+    );
+
+    // once the shares have grown in value, the reserves are credited with exactly the dust & the shares backing it
+    let world = world.total_deposits(1_090);
+
+    let cmds = super::sweep_dust(&world, &world, AdminRole::mock(), VAULT.into()).unwrap();
+
+    check(
+        &cmds,
+        expect![[r#"
+            [
+              BalanceSheet(SetCollateralShares(
+                vault: "vault",
+                shares: (997981651376146788990),
+              )),
+              BalanceSheet(SetCollateralBalance(
+                vault: "vault",
+                balance: 988,
+              )),
+              BalanceSheet(SetReserveShares(
+                vault: "vault",
+                shares: (102018348623853211010),
+              )),
+              BalanceSheet(SetReserveBalance(
+                vault: "vault",
+                balance: 101,
+              )),
+            ]"#]],
+    );
+
+    let world = world.handle_cmds(cmds);
+
+    check_err(
+        super::sweep_dust(&world, &world, AdminRole::mock(), VAULT.into()).unwrap_err(),
+        expect!["no dust to sweep"],
+    );
+}
+
+#[test]
+fn set_treasury() {
+    check(
+        World::default()
+            .configure()
+            .set_treasury(AdminRole::mock(), "treasury".into())
+            .unwrap(),
+        expect![[r#"
+            [
+              BalanceSheet(SetTreasury(
+                treasury: "treasury",
+              )),
+            ]"#]],
+    )
+}
+
+#[test]
+fn set_deposit_enabled() {
+    check(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_deposit_enabled(AdminRole::mock(), VAULT.into(), true)
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetDepositsEnabled(
+                vault: "vault",
+                enabled: true,
+              )),
+            ]"#]],
+    );
+
+    check(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_deposit_enabled(AdminRole::mock(), VAULT.into(), false)
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetDepositsEnabled(
+                vault: "vault",
+                enabled: false,
+              )),
+            ]"#]],
+    );
+
+    check_err(
+        World::default()
+            .configure()
+            .set_deposit_enabled(AdminRole::mock(), VAULT.into(), false)
+            .unwrap_err(),
+        expect!["vault not registered"],
+    );
+}
+
+#[test]
+fn set_advance_enabled() {
+    check(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_advance_enabled(AdminRole::mock(), VAULT.into(), true)
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetAdvanceEnabled(
+                vault: "vault",
+                enabled: true,
+              )),
+            ]"#]],
+    );
+
+    check(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_advance_enabled(AdminRole::mock(), VAULT.into(), false)
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetAdvanceEnabled(
+                vault: "vault",
+                enabled: false,
+              )),
+            ]"#]],
+    );
+
+    check_err(
+        World::default()
+            .configure()
+            .set_advance_enabled(AdminRole::mock(), VAULT.into(), false)
+            .unwrap_err(),
+        expect!["vault not registered"],
+    );
+}
+
+#[test]
+fn set_max_ltv() {
+    check(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_max_ltv(AdminRole::mock(), VAULT.into(), 5_000)
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetMaxLtv(
+                vault: "vault",
+                max_ltv: (
+                  bps: 5000,
+                  rate: (("0.5")),
+                ),
+              )),
+            ]"#]],
+    );
+
+    check_err(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_max_ltv(AdminRole::mock(), VAULT.into(), 10_001)
+            .unwrap_err(),
+        expect!["invalid rate"],
+    );
+
+    check_err(
+        World::default()
+            .configure()
+            .set_max_ltv(AdminRole::mock(), VAULT.into(), 1_000)
+            .unwrap_err(),
+        expect!["vault not registered"],
+    );
+}
+
+#[test]
+fn set_collateral_yield_fee() {
+    check(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_collateral_yield_fee(AdminRole::mock(), VAULT.into(), 1_000)
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetCollateralYieldFee(
+                vault: "vault",
+                fee: (
+                  bps: 1000,
+                  rate: (("0.09999999999999999999999999999999")),
+                ),
+              )),
+            ]"#]],
+    );
+
+    check_err(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_collateral_yield_fee(AdminRole::mock(), VAULT.into(), 10_001)
+            .unwrap_err(),
+        expect!["invalid rate"],
+    );
+
+    check_err(
+        World::default()
+            .configure()
+            .set_collateral_yield_fee(AdminRole::mock(), VAULT.into(), 1_000)
+            .unwrap_err(),
+        expect!["vault not registered"],
+    );
+}
+
+#[test]
+fn set_reserve_yield_fee() {
+    check(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_reserve_yield_fee(AdminRole::mock(), VAULT.into(), 1_000)
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetReserveYieldFee(
+                vault: "vault",
+                fee: (
+                  bps: 1000,
+                  rate: (("0.09999999999999999999999999999999")),
+                ),
+              )),
+            ]"#]],
+    );
+
+    check_err(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_reserve_yield_fee(AdminRole::mock(), VAULT.into(), 10_001)
+            .unwrap_err(),
+        expect!["invalid rate"],
+    );
+
+    check_err(
+        World::default()
+            .configure()
+            .set_reserve_yield_fee(AdminRole::mock(), VAULT.into(), 1_000)
+            .unwrap_err(),
+        expect!["vault not registered"],
+    );
+}
+
+#[test]
+fn set_advance_fee_recipient() {
+    check(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
                 vault: VAULT.into(),
                 synthetic: SYNTHETIC.into()
-            },
-            BalanceSheetCmd::SetCollateralBalance {
-                vault: VAULT.into(),
-                balance: 1_000
-            },
-            BalanceSheetCmd::SetCollateralShares {
-                vault: VAULT.into(),
-                shares: shares_amount(1_000)
-            },
-            BalanceSheetCmd::SetAccountCollateral {
-                vault: VAULT.into(),
-                account: SENDER.into(),
-                collateral: 1_000
-            },
-            BalanceSheetCmd::SetAccountDebt {
-                vault: VAULT.into(),
-                account: SENDER.into(),
-                debt: 500
-            }
-        ])
-        .total_deposits(1_100)
-        .total_shares_issued(shares_amount(1_000));
+            }])
+            .configure()
+            .set_advance_fee_recipient(AdminRole::mock(), VAULT.into(), "treasury".into())
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetAdvanceFeeRecipient(
+                vault: "vault",
+                recipient: "treasury",
+              )),
+            ]"#]],
+    );
 
-    let response = hub(&world, &world, &world)
-        .evaluate(VAULT.into(), "someone_else".into())
-        .unwrap();
+    check_err(
+        World::default()
+            .configure()
+            .set_advance_fee_recipient(AdminRole::mock(), VAULT.into(), "treasury".into())
+            .unwrap_err(),
+        expect!["vault not registered"],
+    );
+}
 
+#[test]
+fn set_fixed_advance_fee() {
     check(
-        world
-            .handle_cmds(response.cmds)
-            .hub()
-            .evaluate(VAULT.into(), SENDER.into())
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_fixed_advance_fee(AdminRole::mock(), VAULT.into(), 50)
             .unwrap(),
         expect![[r#"
-            (
-              cmds: [
-                BalanceSheet(SetAccountDebt(
-                  vault: "vault",
-                  account: "sender",
-                  debt: 411,
-                )),
-                BalanceSheet(SetAccountSumPaymentRatio(
-                  vault: "vault",
-                  account: "sender",
-                  spr: (("0.08999999999999999999999999999999")),
-                )),
-              ],
-              cdp: (
-                collateral: 1000,
-                debt: 411,
-                credit: 0,
-                spr: (("0.08999999999999999999999999999999")),
-              ),
-            )"#]],
+            [
+              Vault(SetFixedAdvanceFee(
+                vault: "vault",
+                fee: (
+                  bps: 50,
+                  rate: (("0.00499999999999999999999999999999")),
+                ),
+              )),
+            ]"#]],
     );
-}
 
-#[test]
-fn register_already_registered_vault_errs() {
     check_err(
         World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
             .configure()
-            .register_vault(
-                AdminRole::mock(),
-                ALREADY_REGISTERED_VAULT.into(),
-                SYNTHETIC.into(),
-            )
+            .set_fixed_advance_fee(AdminRole::mock(), VAULT.into(), 10_001)
             .unwrap_err(),
-        expect!["vault already registered"],
-    )
-}
+        expect!["invalid rate"],
+    );
 
-#[test]
-fn register_vault_non_existing_synthetic_errs() {
     check_err(
         World::default()
             .configure()
-            .register_vault(AdminRole::mock(), VAULT.into(), "does_not_exist".into())
+            .set_fixed_advance_fee(AdminRole::mock(), VAULT.into(), 1_000)
             .unwrap_err(),
-        expect!["synthetic not found"],
-    )
+        expect!["vault not registered"],
+    );
 }
 
 #[test]
-fn register_vault_mismatching_synthetic_decimals_errs() {
+fn set_advance_fee_oracle() {
+    check(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_advance_fee_oracle(AdminRole::mock(), VAULT.into(), "oracle".into())
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetAdvanceFeeOracle(
+                vault: "vault",
+                oracle: "oracle",
+              )),
+              Vault(SetAdvanceFeeOracleEpoch(
+                vault: "vault",
+                epoch: 1,
+              )),
+            ]"#]],
+    );
+
     check_err(
         World::default()
             .configure()
-            .register_vault(
-                AdminRole::mock(),
-                VAULT.into(),
-                EIGHT_DECIMAL_SYNTHETIC.into(),
-            )
+            .set_amo(AdminRole::mock(), VAULT.into(), "amo".into())
             .unwrap_err(),
-        expect!["decimals mismatch"],
-    )
+        expect!["vault not registered"],
+    );
 }
 
 #[test]
-fn register_vault() {
+fn set_oracle_fee_cache_secs() {
     check(
         World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
             .configure()
-            .register_vault(AdminRole::mock(), VAULT.into(), SYNTHETIC.into())
+            .set_oracle_fee_cache_secs(AdminRole::mock(), VAULT.into(), Some(0))
             .unwrap(),
         expect![[r#"
             [
-              Vault(Register(
+              Vault(SetOracleFeeCacheSecs(
                 vault: "vault",
-                synthetic: "synthetic",
+                secs: None,
               )),
             ]"#]],
-    )
+    );
+
+    check_err(
+        World::default()
+            .configure()
+            .set_oracle_fee_cache_secs(AdminRole::mock(), VAULT.into(), Some(600))
+            .unwrap_err(),
+        expect!["vault not registered"],
+    );
 }
 
 #[test]
-fn set_treasury() {
+fn set_min_collateral() {
+    check(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .set_min_collateral(AdminRole::mock(), VAULT.into(), Some(100))
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetMinCollateral(
+                vault: "vault",
+                min: Some(100),
+              )),
+            ]"#]],
+    );
+
     check(
         World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
             .configure()
-            .set_treasury(AdminRole::mock(), "treasury".into())
+            .set_min_collateral(AdminRole::mock(), VAULT.into(), Some(0))
             .unwrap(),
         expect![[r#"
             [
-              BalanceSheet(SetTreasury(
-                treasury: "treasury",
+              Vault(SetMinCollateral(
+                vault: "vault",
+                min: None,
               )),
             ]"#]],
-    )
+    );
+
+    check_err(
+        World::default()
+            .configure()
+            .set_min_collateral(AdminRole::mock(), VAULT.into(), Some(100))
+            .unwrap_err(),
+        expect!["vault not registered"],
+    );
 }
 
 #[test]
-fn set_deposit_enabled() {
+fn set_min_debt() {
     check(
         World::default()
             .handle_cmds(cmds![VaultCmd::Register {
@@ -3775,17 +7465,99 @@ fn set_deposit_enabled() {
                 synthetic: SYNTHETIC.into()
             }])
             .configure()
-            .set_deposit_enabled(AdminRole::mock(), VAULT.into(), true)
+            .set_min_debt(AdminRole::mock(), VAULT.into(), Some(100))
             .unwrap(),
         expect![[r#"
             [
-              Vault(SetDepositsEnabled(
+              Vault(SetMinDebt(
                 vault: "vault",
-                enabled: true,
+                min: Some(100),
               )),
             ]"#]],
     );
 
+    check_err(
+        World::default()
+            .configure()
+            .set_min_debt(AdminRole::mock(), VAULT.into(), Some(100))
+            .unwrap_err(),
+        expect!["vault not registered"],
+    );
+}
+
+// Account holds 500 collateral & 100 debt against a vault requiring at least 200 of each
+#[test]
+fn deposit_below_min_collateral_errs() {
+    let hub = |world: &World, value| {
+        world.hub().vault_deposit_callback(
+            VAULT.into(),
+            "other".into(),
+            VaultDepositReason::Deposit,
+            shares_amount(value),
+            DepositValue(value),
+        )
+    };
+
+    let world = World::with_vault(1_000)
+        .with_min_position(200, 200)
+        .with_position(SENDER, 500, 100);
+
+    check_err(
+        hub(&world, 199).unwrap_err(),
+        expect!["position collateral below minimum"],
+    );
+
+    assert!(hub(&world, 200).is_ok());
+}
+
+#[test]
+fn withdraw_leaving_collateral_below_min_errs() {
+    let world = World::with_vault(1_000)
+        .with_min_position(200, 200)
+        .with_position(SENDER, 500, 0);
+
+    check_err(
+        world
+            .hub()
+            .withdraw_collateral(VAULT.into(), SENDER.into(), 301)
+            .unwrap_err(),
+        expect!["position collateral below minimum"],
+    );
+
+    assert!(world
+        .hub()
+        .withdraw_collateral(VAULT.into(), SENDER.into(), 300)
+        .is_ok());
+
+    // withdrawing everything is always allowed
+    assert!(world
+        .hub()
+        .withdraw_collateral(VAULT.into(), SENDER.into(), 500)
+        .is_ok());
+}
+
+#[test]
+fn advance_leaving_debt_below_min_errs() {
+    let world = World::with_vault(1_000)
+        .with_min_position(200, 200)
+        .with_position(SENDER, 500, 0);
+
+    check_err(
+        world
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 199, SENDER.into())
+            .unwrap_err(),
+        expect!["position debt below minimum"],
+    );
+
+    assert!(world
+        .hub()
+        .advance(VAULT.into(), SENDER.into(), 200, SENDER.into())
+        .is_ok());
+}
+
+#[test]
+fn set_account_debt_cap() {
     check(
         World::default()
             .handle_cmds(cmds![VaultCmd::Register {
@@ -3793,13 +7565,13 @@ fn set_deposit_enabled() {
                 synthetic: SYNTHETIC.into()
             }])
             .configure()
-            .set_deposit_enabled(AdminRole::mock(), VAULT.into(), false)
+            .set_account_debt_cap(AdminRole::mock(), VAULT.into(), Some(300))
             .unwrap(),
         expect![[r#"
             [
-              Vault(SetDepositsEnabled(
+              Vault(SetAccountDebtCap(
                 vault: "vault",
-                enabled: false,
+                cap: Some(300),
               )),
             ]"#]],
     );
@@ -3807,14 +7579,75 @@ fn set_deposit_enabled() {
     check_err(
         World::default()
             .configure()
-            .set_deposit_enabled(AdminRole::mock(), VAULT.into(), false)
+            .set_account_debt_cap(AdminRole::mock(), VAULT.into(), Some(300))
             .unwrap_err(),
         expect!["vault not registered"],
     );
 }
 
 #[test]
-fn set_advance_enabled() {
+fn advance_up_to_account_debt_cap() {
+    // 500 collateral & 100 debt, capped at 200 debt per account
+    let world = World::with_vault(1_000)
+        .with_min_position(200, 200)
+        .with_position(SENDER, 500, 100)
+        .handle_cmds(cmds![VaultCmd::SetAccountDebtCap {
+            vault: VAULT.into(),
+            cap: Some(200)
+        }]);
+
+    check_err(
+        world
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 101, SENDER.into())
+            .unwrap_err(),
+        expect!["account debt cap exceeded"],
+    );
+
+    let cmds = world
+        .hub()
+        .advance(VAULT.into(), SENDER.into(), 100, SENDER.into())
+        .unwrap();
+
+    let world = world.handle_cmds(cmds);
+
+    assert_eq!(world.account_debt(&VAULT.into(), &SENDER.into()), Some(200));
+
+    check_err(
+        world
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 1, SENDER.into())
+            .unwrap_err(),
+        expect!["account debt cap exceeded"],
+    );
+}
+
+#[test]
+fn position_below_min_can_exit_fully() {
+    // the account was opened before the minimums were set: 150 collateral & 100 debt
+    let world = World::with_vault(1_000)
+        .with_min_position(200, 200)
+        .with_position(SENDER, 150, 100);
+
+    let PositionResponse { cmds, cdp } = world
+        .hub()
+        .repay_synthetic(VAULT.into(), SENDER.into(), SYNTHETIC.into(), 100)
+        .unwrap();
+
+    assert_eq!(cdp.debt, 0);
+
+    let world = world.handle_cmds(cmds);
+
+    let PositionResponse { cdp, .. } = world
+        .hub()
+        .withdraw_collateral(VAULT.into(), SENDER.into(), 150)
+        .unwrap();
+
+    assert_eq!(cdp.collateral, 0);
+}
+
+#[test]
+fn set_reserve_floor() {
     check(
         World::default()
             .handle_cmds(cmds![VaultCmd::Register {
@@ -3822,13 +7655,13 @@ fn set_advance_enabled() {
                 synthetic: SYNTHETIC.into()
             }])
             .configure()
-            .set_advance_enabled(AdminRole::mock(), VAULT.into(), true)
+            .set_reserve_floor(AdminRole::mock(), VAULT.into(), Some(100))
             .unwrap(),
         expect![[r#"
             [
-              Vault(SetAdvanceEnabled(
+              Vault(SetReserveFloor(
                 vault: "vault",
-                enabled: true,
+                floor: Some(100),
               )),
             ]"#]],
     );
@@ -3840,13 +7673,13 @@ fn set_advance_enabled() {
                 synthetic: SYNTHETIC.into()
             }])
             .configure()
-            .set_advance_enabled(AdminRole::mock(), VAULT.into(), false)
+            .set_reserve_floor(AdminRole::mock(), VAULT.into(), Some(0))
             .unwrap(),
         expect![[r#"
             [
-              Vault(SetAdvanceEnabled(
+              Vault(SetReserveFloor(
                 vault: "vault",
-                enabled: false,
+                floor: None,
               )),
             ]"#]],
     );
@@ -3854,244 +7687,546 @@ fn set_advance_enabled() {
     check_err(
         World::default()
             .configure()
-            .set_advance_enabled(AdminRole::mock(), VAULT.into(), false)
+            .set_reserve_floor(AdminRole::mock(), VAULT.into(), Some(100))
             .unwrap_err(),
         expect!["vault not registered"],
     );
 }
 
+// Account holds 500 collateral against a vault with 100 in reserves
 #[test]
-fn set_max_ltv() {
-    check(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
-                vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
-            .configure()
-            .set_max_ltv(AdminRole::mock(), VAULT.into(), 5_000)
-            .unwrap(),
-        expect![[r#"
-            [
-              Vault(SetMaxLtv(
-                vault: "vault",
-                max_ltv: (
-                  bps: 5000,
-                  rate: (("0.5")),
-                ),
-              )),
-            ]"#]],
-    );
+fn advance_with_reserves_above_floor() {
+    for floor in [None, Some(50), Some(100)] {
+        assert!(World::with_vault(900)
+            .with_reserve(100)
+            .with_position(SENDER, 500, 0)
+            .with_reserve_floor(floor)
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 100, SENDER.into())
+            .is_ok());
+    }
+}
 
+#[test]
+fn advance_with_reserves_below_floor_errs() {
     check_err(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
-                vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
-            .configure()
-            .set_max_ltv(AdminRole::mock(), VAULT.into(), 10_001)
+        World::with_vault(900)
+            .with_reserve(100)
+            .with_position(SENDER, 500, 0)
+            .with_reserve_floor(Some(101))
+            .hub()
+            .advance(VAULT.into(), SENDER.into(), 100, SENDER.into())
             .unwrap_err(),
-        expect!["invalid rate"],
+        expect!["reserve below floor"],
     );
+}
 
-    check_err(
-        World::default()
-            .configure()
-            .set_max_ltv(AdminRole::mock(), VAULT.into(), 1_000)
-            .unwrap_err(),
-        expect!["vault not registered"],
+// Account holds 1,000 collateral & 400 debt while the vault shares have increased in value
+fn stored_cdp(world: &World) -> Cdp {
+    hub(world, world, world).current_cdp(&VAULT.into(), &SENDER.into())
+}
+
+fn minted(cmds: &[Cmd], recipient: &str) -> Option<Debt> {
+    cmds.iter().find_map(|cmd| match cmd {
+        Cmd::Mint(MintCmd::Mint {
+            amount,
+            recipient: r,
+            ..
+        }) if r.as_str() == recipient => Some(*amount),
+        _ => None,
+    })
+}
+
+#[test]
+fn simulate_deposit_agrees_with_deposit() {
+    let world = World::with_vault(1_000)
+        .with_deposits_enabled()
+        .with_advance_fee_recipient("treasury")
+        .with_position(SENDER, 1_000, 400)
+        .total_deposits(1_100);
+
+    let outcome = world
+        .hub()
+        .simulate_deposit(
+            VAULT.into(),
+            SENDER.into(),
+            VAULT_DEPOSIT_ASSET.into(),
+            DepositAmount(500),
+            SENDER.into(),
+        )
+        .unwrap();
+
+    let deposit_cmds = world
+        .hub()
+        .deposit(
+            VAULT.into(),
+            SENDER.into(),
+            VAULT_DEPOSIT_ASSET.into(),
+            DepositAmount(500),
+            SENDER.into(),
+        )
+        .unwrap();
+
+    let world = world.handle_cmds(deposit_cmds);
+
+    let callback_cmds = world
+        .hub()
+        .vault_deposit_callback(
+            VAULT.into(),
+            SENDER.into(),
+            VaultDepositReason::Deposit,
+            shares_amount(455),
+            DepositValue(500),
+        )
+        .unwrap();
+
+    let world = world.handle_cmds(callback_cmds);
+
+    assert_eq!(outcome.collateral, 500);
+    assert_eq!(outcome.cdp, stored_cdp(&world));
+
+    check(
+        outcome,
+        expect![[r#"
+            (
+              cdp: (
+                collateral: 1500,
+                debt: 311,
+                credit: 0,
+                spr: (("0.08999999999999999999999999999999")),
+              ),
+              collateral: 500,
+            )"#]],
     );
 }
 
 #[test]
-fn set_collateral_yield_fee() {
+fn simulate_advance_agrees_with_advance() {
+    let world = World::with_vault(1_000)
+        .with_deposits_enabled()
+        .with_advance_fee_recipient("treasury")
+        .with_position(SENDER, 1_000, 400)
+        .total_deposits(1_100);
+
+    let outcome = world
+        .hub()
+        .simulate_advance(VAULT.into(), 100, SENDER.into())
+        .unwrap();
+
+    let cmds = world
+        .hub()
+        .advance(VAULT.into(), SENDER.into(), 100, SENDER.into())
+        .unwrap();
+
+    assert_eq!(Some(outcome.amount), minted(&cmds, SENDER));
+    assert_eq!(outcome.fee, minted(&cmds, "treasury"));
+    assert_eq!(outcome.cdp, stored_cdp(&world.handle_cmds(cmds)));
+
     check(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
-                vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
-            .configure()
-            .set_collateral_yield_fee(AdminRole::mock(), VAULT.into(), 1_000)
-            .unwrap(),
+        outcome,
         expect![[r#"
-            [
-              Vault(SetCollateralYieldFee(
-                vault: "vault",
-                fee: (
-                  bps: 1000,
-                  rate: (("0.09999999999999999999999999999999")),
-                ),
-              )),
-            ]"#]],
+        (
+          cdp: (
+            collateral: 1000,
+            debt: 411,
+            credit: 0,
+            spr: (("0.08999999999999999999999999999999")),
+          ),
+          amount: 100,
+          fee: Some(0),
+        )"#]],
+    );
+}
+
+#[test]
+fn simulate_withdraw_agrees_with_withdraw() {
+    let world = World::with_vault(1_000)
+        .with_deposits_enabled()
+        .with_advance_fee_recipient("treasury")
+        .with_position(SENDER, 1_000, 400)
+        .total_deposits(1_100);
+
+    let outcome = world
+        .hub()
+        .simulate_withdraw(VAULT.into(), SENDER.into(), 250)
+        .unwrap();
+
+    let PositionResponse { cmds, cdp } = world
+        .hub()
+        .withdraw_collateral(VAULT.into(), SENDER.into(), 250)
+        .unwrap();
+
+    let redeemed = cmds.iter().find_map(|cmd| match cmd {
+        Cmd::Vault(VaultCmd::Redeem { amount, .. }) => Some(*amount),
+        _ => None,
+    });
+
+    assert_eq!(Some(outcome.shares), redeemed);
+    assert_eq!(outcome.cdp, cdp);
+    assert_eq!(outcome.cdp, stored_cdp(&world.handle_cmds(cmds)));
+
+    check(
+        outcome,
+        expect![[r#"
+        (
+          cdp: (
+            collateral: 750,
+            debt: 311,
+            credit: 0,
+            spr: (("0.08999999999999999999999999999999")),
+          ),
+          shares: (227272727272727272727),
+        )"#]],
+    );
+}
+
+#[test]
+fn simulate_errs_agree_with_execute() {
+    let world = World::with_vault(1_000)
+        .with_min_position(200, 200)
+        .with_position(SENDER, 500, 0);
+
+    check_err(
+        world
+            .hub()
+            .simulate_advance(VAULT.into(), 199, SENDER.into())
+            .unwrap_err(),
+        expect!["position debt below minimum"],
     );
 
     check_err(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
-                vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
-            .configure()
-            .set_collateral_yield_fee(AdminRole::mock(), VAULT.into(), 10_001)
+        world
+            .hub()
+            .simulate_withdraw(VAULT.into(), SENDER.into(), 301)
             .unwrap_err(),
-        expect!["invalid rate"],
+        expect!["position collateral below minimum"],
+    );
+
+    check_err(
+        World::with_vault(900)
+            .with_reserve(100)
+            .with_position(SENDER, 500, 0)
+            .with_reserve_floor(Some(101))
+            .hub()
+            .simulate_advance(VAULT.into(), 100, SENDER.into())
+            .unwrap_err(),
+        expect!["reserve below floor"],
     );
 
     check_err(
         World::default()
-            .configure()
-            .set_collateral_yield_fee(AdminRole::mock(), VAULT.into(), 1_000)
+            .hub()
+            .simulate_deposit(
+                VAULT.into(),
+                SENDER.into(),
+                VAULT_DEPOSIT_ASSET.into(),
+                DepositAmount(100),
+                SENDER.into(),
+            )
             .unwrap_err(),
         expect!["vault not registered"],
     );
+
+    check_err(
+        World::with_vault(1_000)
+            .with_deposits_enabled()
+            .with_advance_fee_recipient("treasury")
+            .with_position(SENDER, 1_000, 400)
+            .total_deposits(1_100)
+            .total_deposits(900)
+            .hub()
+            .simulate_withdraw(VAULT.into(), SENDER.into(), 100)
+            .unwrap_err(),
+        expect!["vault shares have suffered a loss in value"],
+    );
 }
 
 #[test]
-fn set_reserve_yield_fee() {
-    check(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
+fn simulate_deposit_applies_deposit_checks() {
+    let world = || {
+        World::with_vault(1_000)
+            .with_deposits_enabled()
+            .with_advance_fee_recipient("treasury")
+            .with_position(SENDER, 1_000, 400)
+            .total_deposits(1_100)
+    };
+
+    let simulate = |world: World, asset: &str, amount: u128| {
+        world.hub().simulate_deposit(
+            VAULT.into(),
+            SENDER.into(),
+            asset.into(),
+            DepositAmount(amount),
+            SENDER.into(),
+        )
+    };
+
+    // a secondary asset is valued at its conversion rate
+    let outcome = simulate(world(), VAULT_SECONDARY_DEPOSIT_ASSET, 100).unwrap();
+
+    assert_eq!(outcome.collateral, 150);
+
+    check_err(
+        simulate(world(), "unknown", 100).unwrap_err(),
+        expect!["invalid deposit asset"],
+    );
+
+    check_err(
+        simulate(world(), VAULT_DEPOSIT_ASSET, 0).unwrap_err(),
+        expect!["cannot deposit zero"],
+    );
+
+    check_err(
+        simulate(
+            world().handle_cmds(cmds![VaultCmd::SetMinDeposit {
                 vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
-            .configure()
-            .set_reserve_yield_fee(AdminRole::mock(), VAULT.into(), 1_000)
-            .unwrap(),
-        expect![[r#"
-            [
-              Vault(SetReserveYieldFee(
-                vault: "vault",
-                fee: (
-                  bps: 1000,
-                  rate: (("0.09999999999999999999999999999999")),
-                ),
-              )),
-            ]"#]],
+                amount: 101,
+            }]),
+            VAULT_DEPOSIT_ASSET,
+            100,
+        )
+        .unwrap_err(),
+        expect!["deposit below minimum"],
     );
 
     check_err(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
+        simulate(
+            world().handle_cmds(cmds![VaultCmd::SetDepositProxy {
                 vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
-            .configure()
-            .set_reserve_yield_fee(AdminRole::mock(), VAULT.into(), 10_001)
-            .unwrap_err(),
-        expect!["invalid rate"],
+                proxy: Some("proxy".into()),
+            }]),
+            VAULT_DEPOSIT_ASSET,
+            100,
+        )
+        .unwrap_err(),
+        expect!["unauthorized"],
+    );
+}
+
+#[track_caller]
+fn assert_effective_fee_agrees_with_advance(world: &World) -> EffectiveAdvanceFee {
+    let effective = world
+        .hub()
+        .effective_advance_fee(VAULT.into(), SENDER.into(), 100_000)
+        .unwrap();
+
+    let cmds = world
+        .hub()
+        .advance(VAULT.into(), SENDER.into(), 100_000, SENDER.into())
+        .unwrap();
+
+    assert_eq!(
+        effective.amount,
+        minted(&cmds, "treasury").unwrap_or_default()
     );
 
-    check_err(
-        World::default()
-            .configure()
-            .set_reserve_yield_fee(AdminRole::mock(), VAULT.into(), 1_000)
-            .unwrap_err(),
-        expect!["vault not registered"],
+    effective
+}
+
+#[test]
+fn effective_advance_fee_fixed() {
+    let world = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 0)
+        .with_fixed_advance_fee(AdvanceFee::new(50).unwrap())
+        .with_advance_fee_recipient("treasury");
+
+    check(
+        assert_effective_fee_agrees_with_advance(&world),
+        expect![[r#"
+            (
+              source: Fixed,
+              fee: (
+                bps: 50,
+                rate: (("0.00499999999999999999999999999999")),
+              ),
+              amount: 499,
+            )"#]],
     );
 }
 
 #[test]
-fn set_advance_fee_recipient() {
+fn effective_advance_fee_oracle() {
+    let world = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 0)
+        .with_fixed_advance_fee(AdvanceFee::new(50).unwrap())
+        .with_advance_fee_recipient("treasury")
+        .with_advance_fee_oracle("advance_fee_oracle")
+        .advance_fee_oracle_rate(AdvanceFee::new(100).unwrap());
+
     check(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
-                vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
-            .configure()
-            .set_advance_fee_recipient(AdminRole::mock(), VAULT.into(), "treasury".into())
-            .unwrap(),
+        assert_effective_fee_agrees_with_advance(&world),
         expect![[r#"
-            [
-              Vault(SetAdvanceFeeRecipient(
-                vault: "vault",
-                recipient: "treasury",
-              )),
-            ]"#]],
+            (
+              source: Oracle,
+              fee: (
+                bps: 100,
+                rate: (("0.00999999999999999999999999999999")),
+              ),
+              amount: 999,
+            )"#]],
     );
+}
 
-    check_err(
-        World::default()
-            .configure()
-            .set_advance_fee_recipient(AdminRole::mock(), VAULT.into(), "treasury".into())
-            .unwrap_err(),
-        expect!["vault not registered"],
+#[test]
+fn effective_advance_fee_falls_back_to_fixed_without_oracle_fee() {
+    let world = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 0)
+        .with_fixed_advance_fee(AdvanceFee::new(50).unwrap())
+        .with_advance_fee_recipient("treasury")
+        .with_advance_fee_oracle("advance_fee_oracle");
+
+    check(
+        assert_effective_fee_agrees_with_advance(&world),
+        expect![[r#"
+            (
+              source: Fixed,
+              fee: (
+                bps: 50,
+                rate: (("0.00499999999999999999999999999999")),
+              ),
+              amount: 499,
+            )"#]],
     );
 }
 
 #[test]
-fn set_fixed_advance_fee() {
+fn effective_advance_fee_zero_without_fee_recipient() {
+    let world = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 0)
+        .with_fixed_advance_fee(AdvanceFee::new(50).unwrap())
+        .with_advance_fee_oracle("advance_fee_oracle")
+        .advance_fee_oracle_rate(AdvanceFee::new(100).unwrap());
+
     check(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
-                vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
-            .configure()
-            .set_fixed_advance_fee(AdminRole::mock(), VAULT.into(), 50)
-            .unwrap(),
+        assert_effective_fee_agrees_with_advance(&world),
         expect![[r#"
-            [
-              Vault(SetFixedAdvanceFee(
-                vault: "vault",
-                fee: (
-                  bps: 50,
-                  rate: (("0.00499999999999999999999999999999")),
-                ),
-              )),
-            ]"#]],
+            (
+              source: None,
+              fee: (
+                bps: 0,
+                rate: (("0.0")),
+              ),
+              amount: 0,
+            )"#]],
     );
 
-    check_err(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
+    assert_eq!(world.oracle_queries.get(), 0);
+}
+
+#[test]
+fn effective_advance_fee_does_not_query_fresh_cached_fee() {
+    let world = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 0)
+        .with_fixed_advance_fee(AdvanceFee::new(50).unwrap())
+        .with_advance_fee_recipient("treasury")
+        .with_advance_fee_oracle("advance_fee_oracle")
+        .advance_fee_oracle_rate(AdvanceFee::new(100).unwrap())
+        .handle_cmds(cmds![
+            VaultCmd::SetOracleFeeCacheSecs {
                 vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
-            .configure()
-            .set_fixed_advance_fee(AdminRole::mock(), VAULT.into(), 10_001)
-            .unwrap_err(),
-        expect!["invalid rate"],
-    );
+                secs: Some(600),
+            },
+            VaultCmd::SetCachedOracleFee {
+                vault: VAULT.into(),
+                recipient: SENDER.into(),
+                cached: CachedAdvanceFee {
+                    fee: Some(AdvanceFee::new(200).unwrap()),
+                    oracle_epoch: 0,
+                    timestamp: 0,
+                },
+            }
+        ]);
+
+    let effective = world
+        .hub()
+        .effective_advance_fee(VAULT.into(), SENDER.into(), 100_000)
+        .unwrap();
+
+    assert_eq!(effective.source, AdvanceFeeSource::Oracle);
+    assert_eq!(effective.fee.raw(), 200);
+    assert_eq!(effective.amount, 1_999);
+    assert_eq!(world.oracle_queries.get(), 0);
+}
 
+#[test]
+fn effective_advance_fee_unregistered_vault_errs() {
     check_err(
         World::default()
-            .configure()
-            .set_fixed_advance_fee(AdminRole::mock(), VAULT.into(), 1_000)
+            .hub()
+            .effective_advance_fee(VAULT.into(), SENDER.into(), 100_000)
             .unwrap_err(),
         expect!["vault not registered"],
     );
 }
 
 #[test]
-fn set_advance_fee_oracle() {
+fn effective_advance_fee_matches_advance_non_max_debt_with_fixed_advance_fee() {
+    let effective = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 200_000)
+        .with_advance_fee_recipient("treasury")
+        .hub()
+        .effective_advance_fee(VAULT.into(), SENDER.into(), 200_000)
+        .unwrap();
+
+    // see `advance_non_max_debt_with_fixed_advance_fee`
+    assert_eq!(effective.source, AdvanceFeeSource::Fixed);
+    assert_eq!(effective.amount, 499);
+}
+
+#[test]
+fn effective_advance_fee_matches_advance_non_max_debt_with_advance_fee_oracle() {
+    let effective = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 200_000)
+        .with_advance_fee_recipient("treasury")
+        .with_advance_fee_oracle("advance_fee_oracle")
+        .advance_fee_oracle_rate(AdvanceFee::new(100).unwrap())
+        .hub()
+        .effective_advance_fee(VAULT.into(), SENDER.into(), 200_000)
+        .unwrap();
+
+    // see `advance_non_max_debt_with_advance_fee_oracle`
+    assert_eq!(effective.source, AdvanceFeeSource::Oracle);
+    assert_eq!(effective.amount, 1_999);
+}
+
+#[test]
+fn effective_advance_fee_only_charges_debt_increase() {
+    let world = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 0)
+        .with_fixed_advance_fee(AdvanceFee::new(50).unwrap())
+        .with_advance_fee_recipient("treasury")
+        .handle_cmds(cmds![BalanceSheetCmd::SetAccountCredit {
+            vault: VAULT.into(),
+            account: SENDER.into(),
+            credit: 40_000
+        }]);
+
+    // 60_000 of the 100_000 advanced is new debt
     check(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
-                vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
-            .configure()
-            .set_advance_fee_oracle(AdminRole::mock(), VAULT.into(), "oracle".into())
-            .unwrap(),
+        assert_effective_fee_agrees_with_advance(&world),
         expect![[r#"
-            [
-              Vault(SetAdvanceFeeOracle(
-                vault: "vault",
-                oracle: "oracle",
-              )),
-            ]"#]],
+            (
+              source: Fixed,
+              fee: (
+                bps: 50,
+                rate: (("0.00499999999999999999999999999999")),
+              ),
+              amount: 299,
+            )"#]],
     );
 
-    check_err(
-        World::default()
-            .configure()
-            .set_amo(AdminRole::mock(), VAULT.into(), "amo".into())
-            .unwrap_err(),
-        expect!["vault not registered"],
-    );
+    let world = World::with_vault(1_000_000)
+        .with_position(SENDER, 1_000_000, 0)
+        .with_fixed_advance_fee(AdvanceFee::new(50).unwrap())
+        .with_advance_fee_recipient("treasury")
+        .handle_cmds(cmds![BalanceSheetCmd::SetAccountCredit {
+            vault: VAULT.into(),
+            account: SENDER.into(),
+            credit: 100_000
+        }]);
+
+    // drawn wholly from credit
+    assert_eq!(assert_effective_fee_agrees_with_advance(&world).amount, 0);
 }
 
 #[test]
@@ -4319,72 +8454,293 @@ fn remove_redeem_proxy() {
             ]"#]],
     );
 
-    check_err(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
+    check_err(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .remove_redeem_proxy(AdminRole::mock(), VAULT.into())
+            .unwrap_err(),
+        expect!["no proxy set"],
+    );
+}
+
+#[test]
+fn remove_mint_proxy() {
+    check(
+        World::default()
+            .handle_cmds(cmds![
+                VaultCmd::Register {
+                    vault: VAULT.into(),
+                    synthetic: SYNTHETIC.into()
+                },
+                VaultCmd::SetMintProxy {
+                    vault: VAULT.into(),
+                    proxy: Some("mint_proxy".into())
+                }
+            ])
+            .configure()
+            .remove_mint_proxy(AdminRole::mock(), VAULT.into())
+            .unwrap(),
+        expect![[r#"
+            [
+              Vault(SetMintProxy(
+                vault: "vault",
+                proxy: None,
+              )),
+            ]"#]],
+    );
+
+    check_err(
+        World::default()
+            .handle_cmds(cmds![VaultCmd::Register {
+                vault: VAULT.into(),
+                synthetic: SYNTHETIC.into()
+            }])
+            .configure()
+            .remove_mint_proxy(AdminRole::mock(), VAULT.into())
+            .unwrap_err(),
+        expect!["no proxy set"],
+    );
+}
+
+impl World {
+    fn total_deposits(mut self, deposits: u128) -> Self {
+        self.total_deposits = TotalDepositsValue(deposits);
+        self
+    }
+
+    fn total_shares_issued(mut self, SharesAmount(shares): SharesAmount) -> Self {
+        self.total_issued_shares = TotalSharesIssued(shares);
+        self
+    }
+
+    fn strategy_redemption_rate(mut self, numerator: u128, denominator: u128) -> Self {
+        self.strategy_redemption_rate =
+            FixedU256::from_u128(numerator).checked_div(FixedU256::from_u128(denominator));
+        self
+    }
+
+    fn collateral_price(mut self, numerator: u128, denominator: u128, updated_at: Now) -> Self {
+        self.collateral_price = Some(CollateralPrice {
+            price: FixedU256::from_u128(numerator)
+                .checked_div(FixedU256::from_u128(denominator))
+                .unwrap(),
+            updated_at,
+        });
+        self
+    }
+
+    fn advance_fee_oracle_rate(mut self, fee: AdvanceFee) -> Self {
+        self.oracle_advance_fee = Some(fee);
+        self
+    }
+
+    fn now(mut self, now: Now) -> Self {
+        self.now = now;
+        self
+    }
+
+    // A registered vault with advances enabled, holding `balance` collateral at one share per unit
+    fn with_vault(balance: u128) -> Self {
+        World::default()
+            .handle_cmds(cmds![
+                VaultCmd::Register {
+                    vault: VAULT.into(),
+                    synthetic: SYNTHETIC.into()
+                },
+                VaultCmd::SetAdvanceEnabled {
+                    vault: VAULT.into(),
+                    enabled: true
+                },
+                BalanceSheetCmd::SetCollateralBalance {
+                    vault: VAULT.into(),
+                    balance
+                },
+                BalanceSheetCmd::SetCollateralShares {
+                    vault: VAULT.into(),
+                    shares: shares_amount(balance)
+                }
+            ])
+            .total_deposits(balance)
+            .total_shares_issued(shares_amount(balance))
+    }
+
+    fn with_position(self, account: &str, collateral: Collateral, debt: Debt) -> Self {
+        self.handle_cmds(cmds![
+            BalanceSheetCmd::SetAccountCollateral {
+                vault: VAULT.into(),
+                account: account.into(),
+                collateral
+            },
+            BalanceSheetCmd::SetAccountDebt {
+                vault: VAULT.into(),
+                account: account.into(),
+                debt
+            }
+        ])
+    }
+
+    fn with_deposits_enabled(self) -> Self {
+        self.handle_cmds(cmds![VaultCmd::SetDepositsEnabled {
+            vault: VAULT.into(),
+            enabled: true
+        }])
+    }
+
+    fn with_advance_fee_recipient(self, recipient: &str) -> Self {
+        self.handle_cmds(cmds![VaultCmd::SetAdvanceFeeRecipient {
+            vault: VAULT.into(),
+            recipient: recipient.into(),
+        }])
+    }
+
+    fn with_advance_fee_oracle(self, oracle: &str) -> Self {
+        self.handle_cmds(cmds![VaultCmd::SetAdvanceFeeOracle {
+            vault: VAULT.into(),
+            oracle: oracle.into(),
+        }])
+    }
+
+    fn with_fixed_advance_fee(self, fee: AdvanceFee) -> Self {
+        self.handle_cmds(cmds![VaultCmd::SetFixedAdvanceFee {
+            vault: VAULT.into(),
+            fee,
+        }])
+    }
+
+    fn with_oracle_fee_cache_secs(self, secs: CacheWindowSecs) -> Self {
+        self.handle_cmds(cmds![VaultCmd::SetOracleFeeCacheSecs {
+            vault: VAULT.into(),
+            secs: Some(secs),
+        }])
+    }
+
+    // Grants the operator control of the sender's position, with nothing advanced yet
+    fn with_position_operator(self, max_advance: Option<Debt>, allow_withdraw: bool) -> Self {
+        self.handle_cmds(cmds![BalanceSheetCmd::SetPositionOperator {
+            vault: VAULT.into(),
+            account: SENDER.into(),
+            operator: Some(PositionOperator {
+                operator: OPERATOR.into(),
+                max_advance,
+                allow_withdraw,
+                advanced: 0,
+            }),
+        }])
+    }
+
+    fn with_amo(self, amo: &str, allocation: AmoAllocation) -> Self {
+        self.handle_cmds(cmds![
+            VaultCmd::SetAmo {
+                vault: VAULT.into(),
+                amo: amo.into()
+            },
+            VaultCmd::SetAmoAllocation {
+                vault: VAULT.into(),
+                allocation
+            }
+        ])
+    }
+
+    // Applies the hub's response to a deposit of `amount` at one share per unit
+    fn with_deposit(self, account: &str, amount: u128) -> Self {
+        let cmds = self
+            .hub()
+            .vault_deposit_callback(
+                VAULT.into(),
+                account.into(),
+                VaultDepositReason::Deposit,
+                shares_amount(amount),
+                DepositValue(amount),
+            )
+            .unwrap();
+
+        self.handle_cmds(cmds)
+    }
+
+    fn with_collateral_price_oracle(self, oracle: &str, max_age_secs: u64) -> Self {
+        self.handle_cmds(cmds![VaultCmd::SetCollateralPriceOracle {
+            vault: VAULT.into(),
+            oracle: Some(CollateralPriceOracle {
+                oracle: oracle.into(),
+                max_age_secs,
+            }),
+        }])
+    }
+
+    fn with_min_position(self, collateral: Collateral, debt: Debt) -> Self {
+        self.handle_cmds(cmds![
+            VaultCmd::SetMinCollateral {
                 vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
-            .configure()
-            .remove_redeem_proxy(AdminRole::mock(), VAULT.into())
-            .unwrap_err(),
-        expect!["no proxy set"],
-    );
-}
+                min: Some(collateral)
+            },
+            VaultCmd::SetMinDebt {
+                vault: VAULT.into(),
+                min: Some(debt)
+            }
+        ])
+    }
 
-#[test]
-fn remove_mint_proxy() {
-    check(
-        World::default()
-            .handle_cmds(cmds![
-                VaultCmd::Register {
-                    vault: VAULT.into(),
-                    synthetic: SYNTHETIC.into()
-                },
-                VaultCmd::SetMintProxy {
-                    vault: VAULT.into(),
-                    proxy: Some("mint_proxy".into())
-                }
-            ])
-            .configure()
-            .remove_mint_proxy(AdminRole::mock(), VAULT.into())
-            .unwrap(),
-        expect![[r#"
-            [
-              Vault(SetMintProxy(
-                vault: "vault",
-                proxy: None,
-              )),
-            ]"#]],
-    );
+    // Adds `balance` to the reserve at one share per unit
+    fn with_reserve(self, balance: u128) -> Self {
+        let TotalDepositsValue(deposits) = self.total_deposits;
+        let TotalSharesIssued(shares) = self.total_issued_shares;
 
-    check_err(
-        World::default()
-            .handle_cmds(cmds![VaultCmd::Register {
+        self.handle_cmds(cmds![
+            BalanceSheetCmd::SetReserveBalance {
                 vault: VAULT.into(),
-                synthetic: SYNTHETIC.into()
-            }])
-            .configure()
-            .remove_mint_proxy(AdminRole::mock(), VAULT.into())
-            .unwrap_err(),
-        expect!["no proxy set"],
-    );
-}
+                balance
+            },
+            BalanceSheetCmd::SetReserveShares {
+                vault: VAULT.into(),
+                shares: shares_amount(balance)
+            }
+        ])
+        .total_deposits(deposits + balance)
+        .total_shares_issued(SharesAmount(shares + shares_amount(balance).0))
+    }
 
-impl World {
-    fn total_deposits(mut self, deposits: u128) -> Self {
-        self.total_deposits = TotalDepositsValue(deposits);
-        self
+    fn with_treasury_shares(self, treasury: &str, shares: SharesAmount) -> Self {
+        self.handle_cmds(cmds![
+            BalanceSheetCmd::SetTreasury {
+                treasury: treasury.into()
+            },
+            BalanceSheetCmd::SetTreasuryShares {
+                vault: VAULT.into(),
+                shares
+            }
+        ])
     }
 
-    fn total_shares_issued(mut self, SharesAmount(shares): SharesAmount) -> Self {
-        self.total_issued_shares = TotalSharesIssued(shares);
-        self
+    fn with_redeem_fee_schedule(self, tiers: &[(u32, u32)]) -> Self {
+        self.handle_cmds(cmds![VaultCmd::SetRedeemFeeSchedule {
+            vault: VAULT.into(),
+            schedule: RedeemFeeSchedule::new(tiers)
+        }])
     }
 
-    fn advance_fee_oracle_rate(mut self, fee: AdvanceFee) -> Self {
-        self.oracle_advance_fee = Some(fee);
-        self
+    fn with_loss_tolerance(self, tolerance_bps: u32) -> Self {
+        self.handle_cmds(cmds![VaultCmd::SetLossTolerance {
+            vault: VAULT.into(),
+            tolerance: LossTolerance::new(tolerance_bps).unwrap()
+        }])
+    }
+
+    fn with_loss_socialisation_enabled(self) -> Self {
+        self.handle_cmds(cmds![VaultCmd::SetLossSocialisationEnabled {
+            vault: VAULT.into(),
+            enabled: true
+        }])
+    }
+
+    fn with_reserve_floor(self, floor: Option<Collateral>) -> Self {
+        self.handle_cmds(cmds![VaultCmd::SetReserveFloor {
+            vault: VAULT.into(),
+            floor
+        }])
     }
 
     fn configure(&self) -> impl ConfigureHub + '_ {
@@ -4416,6 +8772,9 @@ impl World {
                         },
                     );
                 }
+                VaultCmd::Deregister { vault } => {
+                    self.vaults.remove(vault.as_str());
+                }
                 VaultCmd::SetDepositsEnabled { vault, enabled } => {
                     self.vault_meta_mut(vault).deposits_enabled = enabled
                 }
@@ -4425,9 +8784,38 @@ impl World {
                 VaultCmd::SetAdvanceFeeOracle { vault, oracle } => {
                     self.vault_meta_mut(vault).advance_fee_oracle = Some(oracle);
                 }
+                VaultCmd::SetFixedAdvanceFee { vault, fee } => {
+                    self.vault_meta_mut(vault).fixed_advance_fee = Some(fee);
+                }
+                VaultCmd::SetAdvanceFeeOracleEpoch { vault, epoch } => {
+                    self.vault_meta_mut(vault).advance_fee_oracle_epoch = epoch;
+                }
+                VaultCmd::SetOracleFeeCacheSecs { vault, secs } => {
+                    self.vault_meta_mut(vault).oracle_fee_cache_secs = secs;
+                }
+                VaultCmd::SetCollateralPriceOracle { vault, oracle } => {
+                    self.vault_meta_mut(vault).collateral_price_oracle = oracle;
+                }
+                VaultCmd::SetCachedOracleFee {
+                    vault,
+                    recipient,
+                    cached,
+                } => {
+                    self.vault_meta_mut(vault)
+                        .cached_oracle_fees
+                        .insert(recipient.into_string(), cached);
+                }
+                VaultCmd::RemoveCachedOracleFee { vault, recipient } => {
+                    self.vault_meta_mut(vault)
+                        .cached_oracle_fees
+                        .remove(recipient.as_str());
+                }
                 VaultCmd::SetAmo { vault, amo } => {
                     self.vault_meta_mut(vault).amo = Some(amo);
                 }
+                VaultCmd::SetAmoAllocation { vault, allocation } => {
+                    self.vault_meta_mut(vault).amo_allocation = Some(allocation)
+                }
                 VaultCmd::SetDepositProxy { vault, proxy } => {
                     self.vault_meta_mut(vault).deposit_proxy = proxy
                 }
@@ -4440,6 +8828,28 @@ impl World {
                 VaultCmd::SetMintProxy { vault, proxy } => {
                     self.vault_meta_mut(vault).mint_proxy = proxy
                 }
+                VaultCmd::SetMinCollateral { vault, min } => {
+                    self.vault_meta_mut(vault).min_collateral = min
+                }
+                VaultCmd::SetMinDebt { vault, min } => self.vault_meta_mut(vault).min_debt = min,
+                VaultCmd::SetAccountDebtCap { vault, cap } => {
+                    self.vault_meta_mut(vault).account_debt_cap = cap
+                }
+                VaultCmd::SetReserveFloor { vault, floor } => {
+                    self.vault_meta_mut(vault).reserve_floor = floor
+                }
+                VaultCmd::SetRedeemFeeSchedule { vault, schedule } => {
+                    self.vault_meta_mut(vault).redeem_fee_schedule = schedule
+                }
+                VaultCmd::SetLossTolerance { vault, tolerance } => {
+                    self.vault_meta_mut(vault).loss_tolerance = Some(tolerance)
+                }
+                VaultCmd::SetLossSocialisationEnabled { vault, enabled } => {
+                    self.vault_meta_mut(vault).loss_socialisation_enabled = enabled
+                }
+                VaultCmd::SetMinDeposit { vault, amount } => {
+                    self.vault_meta_mut(vault).min_deposit = Some(amount)
+                }
                 VaultCmd::SetAdvanceFeeRecipient { vault, recipient } => {
                     self.vault_meta_mut(vault).advance_fee_recipient = Some(recipient)
                 }
@@ -4447,6 +8857,8 @@ impl World {
             },
             Cmd::BalanceSheet(c) => match c {
                 BalanceSheetCmd::SetTreasury { treasury } => self.treasury = Some(treasury),
+                BalanceSheetCmd::SetGuardian { guardian } => self.guardian = Some(guardian),
+                BalanceSheetCmd::SetGlobalPause { paused } => self.global_paused = paused,
                 BalanceSheetCmd::SetCollateralShares {
                     vault,
                     shares: SharesAmount(shares),
@@ -4469,9 +8881,22 @@ impl World {
                     vault,
                     shares: SharesAmount(shares),
                 } => self.balances_mut(vault).amo_shares = shares,
-                BalanceSheetCmd::SetOverallSumPaymentRatio { vault, spr } => {
+                BalanceSheetCmd::SetAmoSharesReturned {
+                    vault,
+                    shares: SharesAmount(shares),
+                } => self.balances_mut(vault).amo_shares_returned = Some(shares),
+                BalanceSheetCmd::SetAmoDepositsReturned {
+                    vault,
+                    amount: DepositAmount(amount),
+                } => self.balances_mut(vault).amo_deposits_returned = Some(amount),
+                BalanceSheetCmd::SetOverallSumPaymentRatio { vault, spr, .. } => {
                     self.balances_mut(vault).spr = Some(spr)
                 }
+                BalanceSheetCmd::SetCollateralFactor { vault, factor } => {
+                    self.balances_mut(vault).collateral_factor = Some(factor)
+                }
+                // the total is always derived from the recorded account collateral
+                BalanceSheetCmd::TrackTotalCollateral { .. } => {}
                 BalanceSheetCmd::SetAccountCollateral {
                     vault,
                     account,
@@ -4509,6 +8934,7 @@ impl World {
                     vault,
                     account,
                     spr,
+                    ..
                 } => {
                     self.balances_mut(vault)
                         .users
@@ -4516,6 +8942,39 @@ impl World {
                         .or_default()
                         .spr = Some(spr)
                 }
+                BalanceSheetCmd::SetAccountCollateralFactor {
+                    vault,
+                    account,
+                    factor,
+                } => {
+                    self.balances_mut(vault)
+                        .users
+                        .entry(account.into())
+                        .or_default()
+                        .collateral_factor = Some(factor)
+                }
+                BalanceSheetCmd::SetAccountPositionSeq {
+                    vault,
+                    account,
+                    seq,
+                } => {
+                    self.balances_mut(vault)
+                        .users
+                        .entry(account.into())
+                        .or_default()
+                        .position_seq = Some(seq)
+                }
+                BalanceSheetCmd::SetPositionOperator {
+                    vault,
+                    account,
+                    operator,
+                } => {
+                    self.balances_mut(vault)
+                        .users
+                        .entry(account.into())
+                        .or_default()
+                        .operator = operator
+                }
                 _ => {}
             },
             _ => {}
@@ -4533,11 +8992,32 @@ impl World {
 impl VaultRegistry for World {
     fn underlying_asset_decimals(&self, vault: &VaultId) -> Option<Decimals> {
         match vault.as_str() {
-            VAULT => Some(6),
+            VAULT | SIX_DECIMAL_SHARES_VAULT | NEXT_MAJOR_INTERFACE_VAULT => Some(6),
+            _ => None,
+        }
+    }
+
+    fn shares_decimals(&self, vault: &VaultId) -> Option<Decimals> {
+        match vault.as_str() {
+            VAULT | NEXT_MAJOR_INTERFACE_VAULT => Some(SHARES_DECIMAL_PLACES),
+            SIX_DECIMAL_SHARES_VAULT => Some(6),
             _ => None,
         }
     }
 
+    fn interface(&self, vault: &VaultId) -> Option<VaultInterface> {
+        let version = match vault.as_str() {
+            VAULT | SIX_DECIMAL_SHARES_VAULT => "1.0.0",
+            NEXT_MAJOR_INTERFACE_VAULT => "2.0.0",
+            _ => return None,
+        };
+
+        Some(VaultInterface {
+            version: version.to_owned(),
+            features: vec![],
+        })
+    }
+
     fn is_registered(&self, vault: &VaultId) -> bool {
         if vault.as_str() == ALREADY_REGISTERED_VAULT {
             return true;
@@ -4574,8 +9054,10 @@ impl VaultRegistry for World {
         None
     }
 
-    fn fixed_advance_fee(&self, _: &VaultId) -> Option<AdvanceFee> {
-        None
+    fn fixed_advance_fee(&self, vault: &VaultId) -> Option<AdvanceFee> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.meta.fixed_advance_fee)
     }
 
     fn advance_fee_recipient(&self, vault: &VaultId) -> Option<Recipient> {
@@ -4590,14 +9072,61 @@ impl VaultRegistry for World {
             .and_then(|v| v.meta.advance_fee_oracle.clone())
     }
 
+    fn advance_fee_oracle_epoch(&self, vault: &VaultId) -> OracleEpoch {
+        self.vaults
+            .get(vault.as_str())
+            .map_or(0, |v| v.meta.advance_fee_oracle_epoch)
+    }
+
+    fn oracle_fee_cache_secs(&self, vault: &VaultId) -> Option<CacheWindowSecs> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.meta.oracle_fee_cache_secs)
+    }
+
+    fn cached_oracle_fee(
+        &self,
+        vault: &VaultId,
+        recipient: &Recipient,
+    ) -> Option<CachedAdvanceFee> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.meta.cached_oracle_fees.get(recipient.as_str()))
+            .copied()
+    }
+
+    fn cached_oracle_fee_recipients(
+        &self,
+        vault: &VaultId,
+        start_after: Option<&Recipient>,
+        limit: usize,
+    ) -> Vec<Recipient> {
+        self.vaults[vault.as_str()]
+            .meta
+            .cached_oracle_fees
+            .keys()
+            .filter(|recipient| start_after.is_none_or(|start| recipient.as_str() > start.as_str()))
+            .take(limit)
+            .map(|recipient| Recipient::from(recipient.clone()))
+            .collect()
+    }
+
+    fn collateral_price_oracle(&self, vault: &VaultId) -> Option<CollateralPriceOracle> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.meta.collateral_price_oracle.clone())
+    }
+
     fn amo(&self, vault: &VaultId) -> Option<Amo> {
         self.vaults
             .get(vault.as_str())
             .and_then(|v| v.meta.amo.clone())
     }
 
-    fn amo_allocation(&self, _: &VaultId) -> Option<AmoAllocation> {
-        None
+    fn amo_allocation(&self, vault: &VaultId) -> Option<AmoAllocation> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.meta.amo_allocation)
     }
 
     fn deposit_proxy(&self, vault: &VaultId) -> Option<Proxy> {
@@ -4624,12 +9153,72 @@ impl VaultRegistry for World {
             .and_then(|v| v.meta.mint_proxy.clone())
     }
 
+    fn min_collateral(&self, vault: &VaultId) -> Option<Collateral> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.meta.min_collateral)
+    }
+
+    fn min_debt(&self, vault: &VaultId) -> Option<Debt> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.meta.min_debt)
+    }
+
+    fn account_debt_cap(&self, vault: &VaultId) -> Option<Debt> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.meta.account_debt_cap)
+    }
+
+    fn reserve_floor(&self, vault: &VaultId) -> Option<Collateral> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.meta.reserve_floor)
+    }
+
+    fn redeem_fee_schedule(&self, vault: &VaultId) -> Option<RedeemFeeSchedule> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.meta.redeem_fee_schedule.clone())
+    }
+
+    fn loss_tolerance(&self, vault: &VaultId) -> Option<LossTolerance> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.meta.loss_tolerance)
+    }
+
+    fn loss_socialisation_enabled(&self, vault: &VaultId) -> bool {
+        self.vaults
+            .get(vault.as_str())
+            .is_some_and(|v| v.meta.loss_socialisation_enabled)
+    }
+
+    fn min_deposit(&self, vault: &VaultId) -> Option<u128> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.meta.min_deposit)
+    }
+
     fn deposit_asset(&self, vault: &VaultId) -> Asset {
         assert_eq!(vault.as_str(), VAULT);
 
         VAULT_DEPOSIT_ASSET.into()
     }
 
+    fn convert_secondary_deposit(
+        &self,
+        vault: &VaultId,
+        asset: &Asset,
+        DepositAmount(amount): DepositAmount,
+    ) -> Option<DepositAmount> {
+        assert_eq!(vault.as_str(), VAULT);
+
+        // the secondary asset converts at 1.5
+        (asset.as_str() == VAULT_SECONDARY_DEPOSIT_ASSET).then(|| DepositAmount(amount * 3 / 2))
+    }
+
     fn shares_asset(&self, vault: &VaultId) -> Asset {
         assert_eq!(vault.as_str(), VAULT);
 
@@ -4654,6 +9243,16 @@ impl VaultRegistry for World {
 
         self.total_deposits
     }
+
+    fn strategy_redemption_rate(&self, vault: &VaultId) -> Option<FixedU256> {
+        assert_eq!(vault.as_str(), VAULT);
+
+        self.strategy_redemption_rate
+    }
+
+    fn collateral_price(&self, _: &Oracle) -> CollateralPrice {
+        self.collateral_price.expect("collateral price set")
+    }
 }
 
 impl SyntheticMint for World {
@@ -4671,6 +9270,14 @@ impl BalanceSheet for World {
         self.treasury.clone()
     }
 
+    fn guardian(&self) -> Option<Guardian> {
+        self.guardian.clone()
+    }
+
+    fn global_paused(&self) -> bool {
+        self.global_paused
+    }
+
     fn collateral_shares(&self, vault: &VaultId) -> Option<SharesAmount> {
         self.vaults
             .get(vault.as_str())
@@ -4707,10 +9314,42 @@ impl BalanceSheet for World {
             .map(|v| SharesAmount(v.balances.amo_shares))
     }
 
+    fn amo_shares_returned(&self, vault: &VaultId) -> Option<SharesAmount> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.balances.amo_shares_returned)
+            .map(SharesAmount)
+    }
+
+    fn amo_deposits_returned(&self, vault: &VaultId) -> Option<DepositAmount> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.balances.amo_deposits_returned)
+            .map(DepositAmount)
+    }
+
     fn overall_sum_payment_ratio(&self, vault: &VaultId) -> Option<SumPaymentRatio> {
         self.vaults.get(vault.as_str()).and_then(|v| v.balances.spr)
     }
 
+    fn collateral_factor(&self, vault: &VaultId) -> Option<CollateralFactor> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.balances.collateral_factor)
+    }
+
+    fn total_debt(&self, vault: &VaultId) -> Option<Debt> {
+        self.vaults
+            .get(vault.as_str())
+            .map(|v| v.balances.users.values().map(|u| u.debt).sum())
+    }
+
+    fn total_collateral(&self, vault: &VaultId) -> Option<Collateral> {
+        self.vaults
+            .get(vault.as_str())
+            .map(|v| v.balances.users.values().map(|u| u.collateral).sum())
+    }
+
     fn account_collateral(&self, vault: &VaultId, account: &Account) -> Option<Collateral> {
         self.vaults
             .get(vault.as_str())
@@ -4742,10 +9381,45 @@ impl BalanceSheet for World {
             .and_then(|v| v.balances.users.get(account.as_str()))
             .and_then(|u| u.spr)
     }
+
+    fn account_collateral_factor(
+        &self,
+        vault: &VaultId,
+        account: &Account,
+    ) -> Option<CollateralFactor> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.balances.users.get(account.as_str()))
+            .and_then(|u| u.collateral_factor)
+    }
+
+    fn account_position_seq(&self, vault: &VaultId, account: &Account) -> Option<PositionSeq> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.balances.users.get(account.as_str()))
+            .and_then(|u| u.position_seq)
+    }
+
+    fn position_operator(&self, vault: &VaultId, account: &Account) -> Option<PositionOperator> {
+        self.vaults
+            .get(vault.as_str())
+            .and_then(|v| v.balances.users.get(account.as_str()))
+            .and_then(|u| u.operator.clone())
+    }
 }
 
 impl AdvanceFeeOracle for World {
+    fn now(&self) -> Now {
+        self.now
+    }
+
     fn advance_fee(&self, _: &Oracle, _: &Recipient) -> Option<AdvanceFee> {
+        self.oracle_queries.set(self.oracle_queries.get() + 1);
         self.oracle_advance_fee
     }
+
+    fn fees_for(&self, _: &Oracle, recipients: Vec<Recipient>) -> Vec<Option<AdvanceFee>> {
+        self.oracle_queries.set(self.oracle_queries.get() + 1);
+        vec![self.oracle_advance_fee; recipients.len()]
+    }
 }
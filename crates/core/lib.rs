@@ -3,7 +3,7 @@ pub mod hub;
 pub mod mint;
 pub mod vault;
 
-use num::FixedU256;
+use num::{FixedU256, Rounding};
 
 #[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Deref)]
 #[deref(forward)]
@@ -48,9 +48,10 @@ impl From<&Identifier> for String {
 #[error("unauthorized")]
 pub struct UnauthorizedError;
 
+/// A non-negative ratio
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(test, derive(serde::Serialize))]
-struct Rate(FixedU256);
+pub struct Rate(FixedU256);
 
 impl Rate {
     fn one() -> Self {
@@ -64,15 +65,59 @@ impl Rate {
     }
 
     fn apply_u128(self, x: u128) -> Option<u128> {
+        self.0.checked_mul_u128(x)
+    }
+
+    /// Apply the rate to `x` rounding away from zero, `None` on overflow
+    pub fn apply_ceil(self, x: u128) -> Option<u128> {
+        self.0.mul_u128_with_rounding(x, Rounding::Ceil)
+    }
+
+    /// Apply the rate to `x` rounding to the nearest integer, halves to even, `None` on overflow
+    pub fn apply_round(self, x: u128) -> Option<u128> {
+        self.0.mul_u128_with_rounding(x, Rounding::HalfEven)
+    }
+
+    pub fn fixed_u256(self) -> FixedU256 {
         self.0
-            .checked_mul(FixedU256::from_u128(x))
-            .map(FixedU256::floor)
+    }
+
+    /// The rate in basis points, rounded down
+    pub fn bps(self) -> Option<u128> {
+        self.apply_u128(10_000)
     }
 }
 
 // convenience macro to create a Vec<Cmd> from different command types
-macro_rules! cmds {
-    ($($cmd:expr),+) => { vec![$($cmd.into()),*] };
-}
+pub(crate) use amulet_prelude::into_cmds as cmds;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rate_rounding() {
+        let third = Rate::from_ratio(1, 3).unwrap();
+        let half = Rate::from_ratio(1, 2).unwrap();
+        let three_halves = Rate::from_ratio(3, 2).unwrap();
 
-pub(crate) use cmds;
+        for (rate, x, floor, ceil, round) in [
+            (third, 100, 33, 34, 33),
+            (Rate::from_ratio(2, 3).unwrap(), 100, 66, 67, 67),
+            (half, 101, 50, 51, 50),
+            (half, 103, 51, 52, 52),
+            (three_halves, 5, 7, 8, 8),
+            (Rate::one(), 100, 100, 100, 100),
+            (Rate::from_ratio(0, 1).unwrap(), 100, 0, 0, 0),
+            (third, 0, 0, 0, 0),
+        ] {
+            assert_eq!(rate.apply_u128(x), Some(floor));
+            assert_eq!(rate.apply_ceil(x), Some(ceil));
+            assert_eq!(rate.apply_round(x), Some(round));
+        }
+
+        assert_eq!(three_halves.apply_ceil(u128::MAX), None);
+        assert_eq!(three_halves.apply_round(u128::MAX), None);
+        assert_eq!(Rate::one().apply_ceil(u128::MAX), Some(u128::MAX));
+    }
+}
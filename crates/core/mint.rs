@@ -4,6 +4,25 @@ pub type Minter = Identifier;
 pub type Synthetic = Identifier;
 pub type SyntheticAmount = u128;
 
+/// The bounds on the length of a ticker, well within the 44 character limit the token factory places on subdenoms
+pub const MIN_TICKER_LEN: usize = 3;
+pub const MAX_TICKER_LEN: usize = 32;
+
+/// Why a ticker cannot be used as a token factory subdenom
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TickerError {
+    #[error(
+        "ticker must be between {MIN_TICKER_LEN} and {MAX_TICKER_LEN} characters long, got {0}"
+    )]
+    Length(usize),
+
+    #[error("ticker must start with a letter")]
+    LeadingNonLetter,
+
+    #[error("ticker contains '{0}', only ascii letters and digits are allowed")]
+    IllegalCharacter(char),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Ticker {
     lowercase: std::rc::Rc<String>,
@@ -29,6 +48,25 @@ impl Ticker {
     pub fn display(&self) -> &str {
         self.original.as_str()
     }
+
+    /// Checks the ticker is within the length limits, starts with a letter & is otherwise alphanumeric
+    pub fn validate(&self) -> Result<(), TickerError> {
+        let ticker = self.display();
+
+        if let Some(c) = ticker.chars().find(|c| !c.is_ascii_alphanumeric()) {
+            return Err(TickerError::IllegalCharacter(c));
+        }
+
+        if !(MIN_TICKER_LEN..=MAX_TICKER_LEN).contains(&ticker.len()) {
+            return Err(TickerError::Length(ticker.len()));
+        }
+
+        if !ticker.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return Err(TickerError::LeadingNonLetter);
+        }
+
+        Ok(())
+    }
 }
 
 impl From<Ticker> for String {
@@ -53,6 +91,19 @@ pub enum Error {
 
     #[error("synthetic not found")]
     SyntheticNotFound,
+
+    #[error("invalid ticker: {0}")]
+    InvalidTicker(#[from] TickerError),
+}
+
+/// The mutable display metadata of a synthetic, the ticker the base denom derives from & the decimals used for value math
+/// are fixed at creation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntheticMetadata {
+    pub ticker: Ticker,
+    pub display_ticker: Ticker,
+    pub display_decimals: Decimals,
+    pub description: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -71,8 +122,20 @@ pub enum MintCmd {
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ConfigCmd {
-    CreateSynthetic { ticker: Ticker, decimals: Decimals },
-    Whitelist { minter: Minter, enabled: bool },
+    CreateSynthetic {
+        ticker: Ticker,
+        decimals: Decimals,
+    },
+    Whitelist {
+        synthetic: Synthetic,
+        minter: Minter,
+        enabled: bool,
+    },
+    UpdateMetadata {
+        synthetic: Synthetic,
+        prev: SyntheticMetadata,
+        next: SyntheticMetadata,
+    },
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -87,8 +150,11 @@ pub trait Repository {
     /// Returns true if the synthetic exists
     fn synthetic_exists(&self, synthetic: &Synthetic) -> bool;
 
-    /// Returns Some(true | false) if the minter whitelist status has been set, otherwise None
-    fn is_whitelisted(&self, minter: &Minter) -> Option<bool>;
+    /// Returns Some(true | false) if the minter whitelist status has been set for the synthetic, otherwise None
+    fn is_whitelisted(&self, synthetic: &Synthetic, minter: &Minter) -> Option<bool>;
+
+    /// Returns Some(metadata) if the synthetic exists, otherwise None
+    fn synthetic_metadata(&self, synthetic: &Synthetic) -> Option<SyntheticMetadata>;
 }
 
 pub trait Mint {
@@ -100,14 +166,26 @@ pub trait Mint {
         decimals: Decimals,
     ) -> Result<Cmd, Error>;
 
-    /// Set a minter as whitelisted or not - requires the admin role
+    /// Set a minter as whitelisted for a synthetic or not - requires the admin role
     fn set_whitelisted(
         &self,
         admin_role: AdminRole,
+        synthetic: Synthetic,
         minter: Minter,
         whitelisted: bool,
     ) -> Result<Cmd, Error>;
 
+    /// Update any of the display metadata of a synthetic, the base denom is unaffected by a display ticker change
+    /// - requires the admin role
+    fn update_synthetic_metadata(
+        &self,
+        admin_role: AdminRole,
+        synthetic: Synthetic,
+        display_ticker: Option<Ticker>,
+        display_decimals: Option<Decimals>,
+        description: Option<String>,
+    ) -> Result<Cmd, Error>;
+
     /// Mint an amount of synthetics to a recipient
     fn mint(
         &self,
@@ -117,13 +195,18 @@ pub trait Mint {
         recipient: Recipient,
     ) -> Result<Cmd, Error>;
 
-    /// Burn an amount of synthetics, anyone can do this
-    fn burn(&self, synthetic: Synthetic, amount: SyntheticAmount) -> Result<Cmd, Error>;
+    /// Burn an amount of synthetics - requires the burner to be whitelisted for the synthetic
+    fn burn(
+        &self,
+        burner: Minter,
+        synthetic: Synthetic,
+        amount: SyntheticAmount,
+    ) -> Result<Cmd, Error>;
 }
 
 pub struct MintImpl<'a>(&'a dyn Repository);
 
-pub fn mint(repository: &dyn Repository) -> MintImpl {
+pub fn mint(repository: &dyn Repository) -> MintImpl<'_> {
     MintImpl(repository)
 }
 
@@ -134,6 +217,8 @@ impl<'a> Mint for MintImpl<'a> {
         ticker: Ticker,
         decimals: Decimals,
     ) -> Result<Cmd, Error> {
+        ticker.validate()?;
+
         if self.0.ticker_exists(&ticker) {
             return Err(Error::TickerAlreadyExists);
         }
@@ -144,16 +229,61 @@ impl<'a> Mint for MintImpl<'a> {
     fn set_whitelisted(
         &self,
         _: AdminRole,
+        synthetic: Synthetic,
         minter: Minter,
         whitelisted: bool,
     ) -> Result<Cmd, Error> {
+        if !self.0.synthetic_exists(&synthetic) {
+            return Err(Error::SyntheticNotFound);
+        }
+
         Ok(ConfigCmd::Whitelist {
+            synthetic,
             minter,
             enabled: whitelisted,
         }
         .into())
     }
 
+    fn update_synthetic_metadata(
+        &self,
+        _: AdminRole,
+        synthetic: Synthetic,
+        display_ticker: Option<Ticker>,
+        display_decimals: Option<Decimals>,
+        description: Option<String>,
+    ) -> Result<Cmd, Error> {
+        let prev = self
+            .0
+            .synthetic_metadata(&synthetic)
+            .ok_or(Error::SyntheticNotFound)?;
+
+        if let Some(display_ticker) = &display_ticker {
+            display_ticker.validate()?;
+
+            // displaying the ticker of another synthetic would be misleading
+            if display_ticker.as_str() != prev.ticker.as_str()
+                && self.0.ticker_exists(display_ticker)
+            {
+                return Err(Error::TickerAlreadyExists);
+            }
+        }
+
+        let next = SyntheticMetadata {
+            ticker: prev.ticker.clone(),
+            display_ticker: display_ticker.unwrap_or_else(|| prev.display_ticker.clone()),
+            display_decimals: display_decimals.unwrap_or(prev.display_decimals),
+            description: description.unwrap_or_else(|| prev.description.clone()),
+        };
+
+        Ok(ConfigCmd::UpdateMetadata {
+            synthetic,
+            prev,
+            next,
+        }
+        .into())
+    }
+
     fn mint(
         &self,
         minter: Minter,
@@ -165,7 +295,7 @@ impl<'a> Mint for MintImpl<'a> {
             return Err(Error::SyntheticNotFound);
         }
 
-        if !self.0.is_whitelisted(&minter).unwrap_or(false) {
+        if !self.0.is_whitelisted(&synthetic, &minter).unwrap_or(false) {
             return Err(UnauthorizedError.into());
         }
 
@@ -177,11 +307,20 @@ impl<'a> Mint for MintImpl<'a> {
         .into())
     }
 
-    fn burn(&self, synthetic: Synthetic, amount: SyntheticAmount) -> Result<Cmd, Error> {
+    fn burn(
+        &self,
+        burner: Minter,
+        synthetic: Synthetic,
+        amount: SyntheticAmount,
+    ) -> Result<Cmd, Error> {
         if !self.0.synthetic_exists(&synthetic) {
             return Err(Error::SyntheticNotFound);
         }
 
+        if !self.0.is_whitelisted(&synthetic, &burner).unwrap_or(false) {
+            return Err(UnauthorizedError.into());
+        }
+
         Ok(MintCmd::Burn { synthetic, amount }.into())
     }
 }
@@ -200,7 +339,7 @@ impl From<ConfigCmd> for Cmd {
 
 #[cfg(test)]
 mod test {
-    use std::collections::HashSet;
+    use std::collections::{HashMap, HashSet};
     use test_utils::prelude::*;
 
     use super::{mint as make_mint, *};
@@ -209,14 +348,15 @@ mod test {
     struct Context {
         tickers: HashSet<String>,
         synthetic: HashSet<String>,
-        whitelist: HashSet<String>,
+        whitelist: HashSet<(String, String)>,
+        metadata: HashMap<String, SyntheticMetadata>,
     }
 
     impl Context {
         fn handle_cmd(&mut self, cmd: Cmd) {
             match cmd {
                 Cmd::Config(config_cmd) => match config_cmd {
-                    ConfigCmd::CreateSynthetic { ticker, .. } => {
+                    ConfigCmd::CreateSynthetic { ticker, decimals } => {
                         if self.tickers.contains(ticker.as_str())
                             || self.synthetic.contains(ticker.as_str())
                         {
@@ -224,16 +364,37 @@ mod test {
                         }
 
                         self.tickers.insert(ticker.clone().into_string());
-                        self.synthetic.insert(ticker.into_string());
+                        self.synthetic.insert(ticker.clone().into_string());
+                        self.metadata.insert(
+                            ticker.clone().into_string(),
+                            SyntheticMetadata {
+                                display_ticker: ticker.clone(),
+                                ticker,
+                                display_decimals: decimals,
+                                description: String::new(),
+                            },
+                        );
                     }
 
-                    ConfigCmd::Whitelist { minter, enabled } => {
+                    ConfigCmd::Whitelist {
+                        synthetic,
+                        minter,
+                        enabled,
+                    } => {
+                        let key = (synthetic.into_string(), minter.into_string());
+
                         if enabled {
-                            self.whitelist.insert(minter.into_string());
+                            self.whitelist.insert(key);
                         } else {
-                            self.whitelist.remove(minter.as_str());
+                            self.whitelist.remove(&key);
                         }
                     }
+
+                    ConfigCmd::UpdateMetadata {
+                        synthetic, next, ..
+                    } => {
+                        self.metadata.insert(synthetic.into_string(), next);
+                    }
                 },
                 Cmd::Mint(mint_cmd) => match mint_cmd {
                     MintCmd::Mint { synthetic, .. } | MintCmd::Burn { synthetic, .. } => {
@@ -258,8 +419,14 @@ mod test {
             self.synthetic.contains(synthetic.as_str())
         }
 
-        fn is_whitelisted(&self, minter: &Minter) -> Option<bool> {
-            self.whitelist.contains(minter.as_str()).then_some(true)
+        fn is_whitelisted(&self, synthetic: &Synthetic, minter: &Minter) -> Option<bool> {
+            self.whitelist
+                .contains(&(synthetic.to_string(), minter.to_string()))
+                .then_some(true)
+        }
+
+        fn synthetic_metadata(&self, synthetic: &Synthetic) -> Option<SyntheticMetadata> {
+            self.metadata.get(synthetic.as_str()).cloned()
         }
     }
 
@@ -279,6 +446,14 @@ mod test {
         "booasset".to_owned().into()
     }
 
+    fn other_asset_ticker() -> Ticker {
+        "amOTHER".to_owned().into()
+    }
+
+    fn other_asset() -> Synthetic {
+        "amother".to_owned().into()
+    }
+
     fn whitelisted_minter() -> Minter {
         "minter".to_owned().into()
     }
@@ -296,8 +471,26 @@ mod test {
             ConfigCmd::CreateSynthetic { ticker, decimals }.into()
         }
 
-        fn set_whitelisted(minter: Minter, enabled: bool) -> Self {
-            ConfigCmd::Whitelist { minter, enabled }.into()
+        fn set_whitelisted(synthetic: Synthetic, minter: Minter, enabled: bool) -> Self {
+            ConfigCmd::Whitelist {
+                synthetic,
+                minter,
+                enabled,
+            }
+            .into()
+        }
+
+        fn update_metadata(
+            synthetic: Synthetic,
+            prev: SyntheticMetadata,
+            next: SyntheticMetadata,
+        ) -> Self {
+            ConfigCmd::UpdateMetadata {
+                synthetic,
+                prev,
+                next,
+            }
+            .into()
         }
 
         fn mint(synthetic: Synthetic, amount: SyntheticAmount, recipient: Recipient) -> Self {
@@ -323,7 +516,8 @@ mod test {
     fn ctx() -> Context {
         Context::default()
             .after_cmd(Cmd::create_synthetic(am_asset_ticker(), 6))
-            .after_cmd(Cmd::set_whitelisted(whitelisted_minter(), true))
+            .after_cmd(Cmd::create_synthetic(other_asset_ticker(), 6))
+            .after_cmd(Cmd::set_whitelisted(am_asset(), whitelisted_minter(), true))
     }
 
     #[rstest]
@@ -333,6 +527,16 @@ mod test {
         Ok(Cmd::create_synthetic(phantom_asset_ticker(), 6))
     )]
     #[case::ticker_taken(am_asset_ticker(), 6, Err(Error::TickerAlreadyExists))]
+    #[case::illegal_character(
+        "am/ASSET".to_owned().into(),
+        6,
+        Err(Error::InvalidTicker(TickerError::IllegalCharacter('/')))
+    )]
+    #[case::over_length(
+        "am".repeat(17).into(),
+        6,
+        Err(Error::InvalidTicker(TickerError::Length(34)))
+    )]
     fn create_synthetic(
         admin_role: AdminRole,
         mut ctx: Context,
@@ -351,23 +555,32 @@ mod test {
 
     #[rstest]
     #[case::set_true(
+        am_asset(),
         whitelisted_minter(),
         true,
-        Ok(Cmd::set_whitelisted(whitelisted_minter(), true))
+        Ok(Cmd::set_whitelisted(am_asset(), whitelisted_minter(), true))
     )]
     #[case::set_false(
+        am_asset(),
         non_whitelisted_minter(),
         false,
-        Ok(Cmd::set_whitelisted(non_whitelisted_minter(), false))
+        Ok(Cmd::set_whitelisted(am_asset(), non_whitelisted_minter(), false))
+    )]
+    #[case::non_existing_synthetic(
+        phantom_asset(),
+        whitelisted_minter(),
+        true,
+        Err(Error::SyntheticNotFound)
     )]
     fn set_whitelisted(
         admin_role: AdminRole,
         mut ctx: Context,
+        #[case] synthetic: Synthetic,
         #[case] minter: Minter,
         #[case] enabled: bool,
         #[case] expected: Result<Cmd, Error>,
     ) {
-        let actual = make_mint(&ctx).set_whitelisted(admin_role, minter, enabled);
+        let actual = make_mint(&ctx).set_whitelisted(admin_role, synthetic, minter, enabled);
 
         assert_eq!(actual, expected);
 
@@ -376,6 +589,155 @@ mod test {
         }
     }
 
+    fn am_asset_metadata(
+        display_ticker: &str,
+        display_decimals: Decimals,
+        description: &str,
+    ) -> SyntheticMetadata {
+        SyntheticMetadata {
+            ticker: am_asset_ticker(),
+            display_ticker: display_ticker.to_owned().into(),
+            display_decimals,
+            description: description.to_owned(),
+        }
+    }
+
+    #[rstest]
+    #[case::ticker_only(
+        am_asset(),
+        Some("AMASSET"),
+        None,
+        None,
+        Ok(Cmd::update_metadata(
+            am_asset(),
+            am_asset_metadata("amASSET", 6, ""),
+            am_asset_metadata("AMASSET", 6, "")
+        ))
+    )]
+    #[case::display_decimals_only(
+        am_asset(),
+        None,
+        Some(18),
+        None,
+        Ok(Cmd::update_metadata(
+            am_asset(),
+            am_asset_metadata("amASSET", 6, ""),
+            am_asset_metadata("amASSET", 18, "")
+        ))
+    )]
+    #[case::description_only(
+        am_asset(),
+        None,
+        None,
+        Some("Amulet ASSET"),
+        Ok(Cmd::update_metadata(
+            am_asset(),
+            am_asset_metadata("amASSET", 6, ""),
+            am_asset_metadata("amASSET", 6, "Amulet ASSET")
+        ))
+    )]
+    #[case::all_fields(
+        am_asset(),
+        Some("AmAsset"),
+        Some(8),
+        Some("Amulet ASSET"),
+        Ok(Cmd::update_metadata(
+            am_asset(),
+            am_asset_metadata("amASSET", 6, ""),
+            am_asset_metadata("AmAsset", 8, "Amulet ASSET")
+        ))
+    )]
+    #[case::ticker_typo(
+        am_asset(),
+        Some("amASSETS"),
+        None,
+        None,
+        Ok(Cmd::update_metadata(
+            am_asset(),
+            am_asset_metadata("amASSET", 6, ""),
+            am_asset_metadata("amASSETS", 6, "")
+        ))
+    )]
+    #[case::ticker_taken_by_other_synthetic(
+        am_asset(),
+        Some("amOTHER"),
+        None,
+        None,
+        Err(Error::TickerAlreadyExists)
+    )]
+    #[case::invalid_ticker_charset(
+        am_asset(),
+        Some("am-ASSET"),
+        None,
+        None,
+        Err(Error::InvalidTicker(TickerError::IllegalCharacter('-')))
+    )]
+    #[case::invalid_ticker_length(
+        am_asset(),
+        Some("am"),
+        None,
+        None,
+        Err(Error::InvalidTicker(TickerError::Length(2)))
+    )]
+    #[case::non_existing_synthetic(
+        phantom_asset(),
+        None,
+        Some(18),
+        None,
+        Err(Error::SyntheticNotFound)
+    )]
+    fn update_synthetic_metadata(
+        admin_role: AdminRole,
+        mut ctx: Context,
+        #[case] synthetic: Synthetic,
+        #[case] ticker: Option<&str>,
+        #[case] display_decimals: Option<Decimals>,
+        #[case] description: Option<&str>,
+        #[case] expected: Result<Cmd, Error>,
+    ) {
+        let actual = make_mint(&ctx).update_synthetic_metadata(
+            admin_role,
+            synthetic,
+            ticker.map(Ticker::new),
+            display_decimals,
+            description.map(str::to_owned),
+        );
+
+        assert_eq!(actual, expected);
+
+        if let Ok(cmd) = actual {
+            ctx.handle_cmd(cmd)
+        }
+    }
+
+    #[test]
+    fn partial_metadata_updates_merge_with_previous() {
+        let mut ctx = ctx();
+
+        for (ticker, display_decimals, description) in [
+            (None, None, Some("Amulet ASSET")),
+            (None, Some(18), None),
+            (Some("AMASSET"), None, None),
+        ] {
+            let cmd = make_mint(&ctx)
+                .update_synthetic_metadata(
+                    admin_role(),
+                    am_asset(),
+                    ticker.map(Ticker::new),
+                    display_decimals,
+                    description.map(str::to_owned),
+                )
+                .unwrap();
+
+            ctx.handle_cmd(cmd);
+        }
+
+        assert_eq!(
+            ctx.synthetic_metadata(&am_asset()),
+            Some(am_asset_metadata("AMASSET", 18, "Amulet ASSET"))
+        );
+    }
+
     #[rstest]
     #[case::whitelisted_minter_existing_synthetic(
         whitelisted_minter(),
@@ -398,6 +760,13 @@ mod test {
         mint_recipient(),
         Err(UnauthorizedError.into())
     )]
+    #[case::minter_whitelisted_for_other_synthetic(
+        whitelisted_minter(),
+        other_asset(),
+        1_000_000,
+        mint_recipient(),
+        Err(UnauthorizedError.into())
+    )]
     fn mint(
         mut ctx: Context,
         #[case] minter: Minter,
@@ -416,15 +785,38 @@ mod test {
     }
 
     #[rstest]
-    #[case::existing_synthetic(am_asset(), 1_000_000, Ok(Cmd::burn(am_asset(), 1_000_000)))]
-    #[case::non_existing_synthetic(phantom_asset(), 1_000_000, Err(Error::SyntheticNotFound))]
+    #[case::existing_synthetic(
+        whitelisted_minter(),
+        am_asset(),
+        1_000_000,
+        Ok(Cmd::burn(am_asset(), 1_000_000))
+    )]
+    #[case::non_existing_synthetic(
+        whitelisted_minter(),
+        phantom_asset(),
+        1_000_000,
+        Err(Error::SyntheticNotFound)
+    )]
+    #[case::non_whitelisted_burner(
+        non_whitelisted_minter(),
+        am_asset(),
+        1_000_000,
+        Err(UnauthorizedError.into())
+    )]
+    #[case::burner_whitelisted_for_other_synthetic(
+        whitelisted_minter(),
+        other_asset(),
+        1_000_000,
+        Err(UnauthorizedError.into())
+    )]
     fn burn(
         mut ctx: Context,
+        #[case] burner: Minter,
         #[case] synthetic: Synthetic,
         #[case] amount: SyntheticAmount,
         #[case] expected: Result<Cmd, Error>,
     ) {
-        let actual = make_mint(&ctx).burn(synthetic, amount);
+        let actual = make_mint(&ctx).burn(burner, synthetic, amount);
 
         assert_eq!(actual, expected);
 
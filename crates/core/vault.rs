@@ -1,4 +1,4 @@
-use num::U256;
+use num::{FixedU256, U256};
 
 use crate::{cmds, Asset, Decimals, Rate, Recipient};
 
@@ -80,6 +80,11 @@ pub trait SharesMint {
 
     /// Returns asset that the strategy issues as shares
     fn shares_asset(&self) -> Asset;
+
+    /// Returns the decimals of the shares asset
+    fn shares_decimals(&self) -> Decimals {
+        SHARES_DECIMAL_PLACES
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -88,6 +93,10 @@ pub enum StrategyCmd {
     /// Deposit an `amount` of deposit assets into the strategy
     Deposit { amount: DepositAmount },
 
+    /// Deposit an `amount` of an accepted secondary `asset` into the strategy.
+    /// The raw balance is recorded as-is, it is up to the strategy to handle it, e.g. convert it into the deposit asset.
+    DepositSecondary { asset: Asset, amount: DepositAmount },
+
     /// Unbond an amount of deposited assets in equal `value` to the underlying asset
     /// NOTE: It is assumed that when an unbond is started, that the reported `total_deposits_value` by the strategy does
     /// *NOT* include the unbonded amount of deposited assets.
@@ -98,6 +107,10 @@ pub enum StrategyCmd {
         amount: ClaimAmount,
         recipient: Recipient,
     },
+
+    /// Collect an unbond fee of `value` retained from a redemption entering the pending `batch`.
+    /// The fee is unbonded as part of the batch, but is not claimable by the redeemer.
+    CollectUnbondFee { batch: BatchId, value: DepositValue },
 }
 
 pub trait Strategy {
@@ -107,6 +120,14 @@ pub trait Strategy {
     /// Returns asset that the strategy can accept for deposits
     fn deposit_asset(&self) -> Asset;
 
+    /// Returns the `amount` of an accepted secondary `asset` converted into an amount of the deposit asset,
+    /// or `None` if the strategy does not accept the `asset` for deposits.
+    fn convert_secondary_deposit(
+        &self,
+        asset: &Asset,
+        amount: DepositAmount,
+    ) -> Option<DepositAmount>;
+
     /// Returns the decimals used by the underlying asset
     fn underlying_asset_decimals(&self) -> Decimals;
 
@@ -119,6 +140,17 @@ pub trait Strategy {
     /// Returns the `UnbondReadyStatus::Later(_)` with an optional start hint if unbonding is not yet possible,
     /// otherwise `UnbondReadyStatus::Ready { amount, epoch }`.
     fn unbond(&self, value: DepositValue) -> UnbondReadyStatus;
+
+    /// Returns the portion of a redemption's unbond `value` to be retained as an unbond fee.
+    fn unbond_fee(&self, value: DepositValue) -> DepositValue;
+
+    /// Returns the amount of unbonded assets received by the strategy that are yet to be claimed,
+    /// or `None` if unbonded assets are always received by the end of a batch's epoch.
+    fn available_to_claim(&self) -> Option<ClaimAmount>;
+
+    /// Returns the on-chain redemption rate of the strategy's deposits relative to their mark-to-market value
+    /// (i.e. redemption value / `total_deposits_value`), or `None` if the deposits are only valued mark-to-market.
+    fn redemption_rate(&self) -> Option<FixedU256>;
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -245,6 +277,19 @@ impl RedemptionRate {
             .map(SharesAmount)
     }
 
+    /// As [Self::checked_deposits_to_shares], rounding up rather than down
+    pub fn checked_deposits_to_shares_ceil(
+        &self,
+        DepositValue(deposit_amount): DepositValue,
+    ) -> Option<SharesAmount> {
+        U256::from(deposit_amount)
+            .checked_mul(U256::from(self.total_shares_issued.0))?
+            .checked_add(U256::from(self.total_deposits_value.0 - 1))?
+            .checked_div(U256::from(self.total_deposits_value.0))
+            .and_then(|shares_u256| shares_u256.try_into().ok())
+            .map(SharesAmount)
+    }
+
     fn overflow_panic(self, shares_or_deposits: &str, amount: u128) -> ! {
         panic!(
             "overflow converting {amount} to {shares_or_deposits}. total_shares_issued = {}, total_deposit_value = {}", 
@@ -271,6 +316,30 @@ pub fn pending_batch_id(unbonding_log: &dyn UnbondingLog) -> BatchId {
         .map_or(0, |batch_id| batch_id + 1)
 }
 
+/// Returns the `recipient`'s share of the amount claimable from a committed `batch`,
+/// or `None` if the recipient did not enter the batch or it has not been committed
+pub fn batch_claim_amount(
+    unbonding_log: &dyn UnbondingLog,
+    recipient: &str,
+    batch: BatchId,
+) -> Option<ClaimAmount> {
+    let ((recipient_unbonded, total_unbonded), total_claimable) = unbonding_log
+        .unbonded_value_in_batch(recipient, batch)
+        .zip(unbonding_log.batch_unbond_value(batch))
+        .zip(unbonding_log.batch_claimable_amount(batch))?;
+
+    let DepositValue(recipient_unbonded) = recipient_unbonded;
+    let DepositValue(total_unbonded) = total_unbonded;
+    let ClaimAmount(total_claimable) = total_claimable;
+
+    let claim_amount = Rate::from_ratio(recipient_unbonded, total_unbonded)
+        .expect("unbonded non-zero amount")
+        .apply_u128(total_claimable)
+        .expect("recipient unbonded <= total unbonded");
+
+    Some(ClaimAmount(claim_amount))
+}
+
 /// Offsets the total deposit value reported by the strategy by the value of any deposits pending unbonding
 pub fn offset_total_deposits_value(
     strategy: &dyn Strategy,
@@ -365,6 +434,14 @@ pub enum Error {
     UnbondNotReady,
 }
 
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct ClaimAllResponse {
+    pub cmds: Vec<Cmd>,
+    /// The batches claimed in ascending order along with the amount claimed from each
+    pub claimed: Vec<(BatchId, ClaimAmount)>,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct DepositResponse {
@@ -375,6 +452,16 @@ pub struct DepositResponse {
     pub total_deposits_value: TotalDepositsValue,
 }
 
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct PerformanceFeeResponse {
+    pub cmds: Vec<Cmd>,
+    /// The shares minted to the fee recipient
+    pub fee_shares: SharesAmount,
+    /// The high-water mark of deposit value per share to record, `None` if there is none yet
+    pub high_water_mark: Option<FixedU256>,
+}
+
 pub trait Vault {
     fn deposit(
         &self,
@@ -398,7 +485,24 @@ pub trait Vault {
 
     fn claim(&self, recipient: Recipient) -> Result<Vec<Cmd>, Error>;
 
+    /// Claim up to `max_batches` of the recipient's matured batches in a single send.
+    /// Batches whose unbonded assets have not yet been received by the strategy are left unclaimed.
+    fn claim_all(
+        &self,
+        recipient: Recipient,
+        max_batches: usize,
+    ) -> Result<ClaimAllResponse, Error>;
+
     fn start_unbond(&self) -> Result<Vec<Cmd>, Error>;
+
+    /// Charge a `fee_bps` cut of the increase in deposit value per share above the `high_water_mark`,
+    /// minted as shares to the `recipient`. The mark only ever rises, so recovering from a loss is not charged.
+    fn take_performance_fee(
+        &self,
+        fee_bps: u32,
+        recipient: Recipient,
+        high_water_mark: Option<FixedU256>,
+    ) -> PerformanceFeeResponse;
 }
 
 pub struct VaultImpl<'a> {
@@ -511,23 +615,10 @@ impl<'a> ClaimableBatchIter<'a> {
             .next_entered_batch(self.recipient, batch_id)
             .filter(|next_batch_id| *next_batch_id <= highest_id);
 
-        let ((recipient_unbonded, total_unbonded), total_claimable) = self
-            .unbonding_log
-            .unbonded_value_in_batch(self.recipient, batch_id)
-            .zip(self.unbonding_log.batch_unbond_value(batch_id))
-            .zip(self.unbonding_log.batch_claimable_amount(batch_id))
+        let claim_amount = batch_claim_amount(self.unbonding_log, self.recipient, batch_id)
             .expect("batch has been entered by recipient and committed");
 
-        let DepositValue(recipient_unbonded) = recipient_unbonded;
-        let DepositValue(total_unbonded) = total_unbonded;
-        let ClaimAmount(total_claimable) = total_claimable;
-
-        let claim_amount = Rate::from_ratio(recipient_unbonded, total_unbonded)
-            .expect("unbonded non-zero amount")
-            .apply_u128(total_claimable)
-            .expect("recipient unbonded <= total unbonded");
-
-        Some((ClaimAmount(claim_amount), batch_id))
+        Some((claim_amount, batch_id))
     }
 }
 
@@ -556,24 +647,42 @@ impl<'a> Vault for VaultImpl<'a> {
             return Err(Error::CannotDepositZero);
         }
 
-        if deposit_asset != self.strategy.deposit_asset() {
-            return Err(Error::InvalidDepositAsset);
+        // Deposits of an accepted secondary asset are credited with the equivalent amount of the deposit asset
+        let (deposit_equivalent, deposit_cmd) = if deposit_asset == self.strategy.deposit_asset() {
+            (
+                DepositAmount(deposit_amount),
+                StrategyCmd::Deposit {
+                    amount: DepositAmount(deposit_amount),
+                },
+            )
+        } else {
+            let deposit_equivalent = self
+                .strategy
+                .convert_secondary_deposit(&deposit_asset, DepositAmount(deposit_amount))
+                .ok_or(Error::InvalidDepositAsset)?;
+
+            (
+                deposit_equivalent,
+                StrategyCmd::DepositSecondary {
+                    asset: deposit_asset,
+                    amount: DepositAmount(deposit_amount),
+                },
+            )
+        };
+
+        if deposit_equivalent.0 == 0 {
+            return Err(Error::DepositTooSmall);
         }
 
         let TotalDepositsValue(previous_total_deposits_value) = self.offset_total_deposits_value();
 
         // Value the deposit in terms of the underlying strategy token
-        let DepositValue(deposit_value) =
-            self.strategy.deposit_value(DepositAmount(deposit_amount));
+        let DepositValue(deposit_value) = self.strategy.deposit_value(deposit_equivalent);
 
         let total_deposits_value = previous_total_deposits_value
             .checked_add(deposit_value)
             .ok_or(Error::DepositTooLarge)?;
 
-        let deposit_cmd = StrategyCmd::Deposit {
-            amount: DepositAmount(deposit_amount),
-        };
-
         let TotalSharesIssued(total_shares_issued) = self.mint.total_shares_issued();
 
         let Some(redemption_rate) = RedemptionRate::new(
@@ -612,10 +721,12 @@ impl<'a> Vault for VaultImpl<'a> {
             );
 
             // initial share issuance == inital deposit amount normalized to Self::SHARE_DECIMALS
-            let scaler = 10u128.pow(SHARES_DECIMAL_PLACES - underlying_asset_decimals);
+            let scaler = U256::decimal_scale(SHARES_DECIMAL_PLACES - underlying_asset_decimals)
+                .expect("always: decimals difference <= shares decimal places");
 
-            let mint_shares = total_deposits_value
+            let mint_shares = U256::from(total_deposits_value)
                 .checked_mul(scaler)
+                .and_then(|shares| u128::try_from(shares).ok())
                 .ok_or(Error::DepositTooLarge)?;
 
             return Ok(DepositResponse {
@@ -721,6 +832,14 @@ impl<'a> Vault for VaultImpl<'a> {
             .filter(|DepositValue(value)| *value > 0)
             .ok_or(Error::RedemptionTooSmall)?;
 
+        let DepositValue(unbond_fee) = self.strategy.unbond_fee(DepositValue(unbond_value));
+
+        // the fee is unbonded as part of the batch, the recipient is only entitled to the remainder
+        let net_unbond_value = unbond_value
+            .checked_sub(unbond_fee)
+            .filter(|value| *value > 0)
+            .ok_or(Error::RedemptionTooSmall)?;
+
         let pending_batch_id = self.pending_batch_id();
 
         let DepositValue(total_unbond_value) = self
@@ -738,7 +857,7 @@ impl<'a> Vault for VaultImpl<'a> {
             .unwrap_or_default();
 
         let recipient_unbond_value = recipient_unbond_value
-            .checked_add(unbond_value)
+            .checked_add(net_unbond_value)
             .expect("always: recipient unbond value <= total deposit value <= u128::MAX");
 
         let mut cmds: Vec<Cmd> = cmds![
@@ -756,6 +875,13 @@ impl<'a> Vault for VaultImpl<'a> {
             }
         ];
 
+        if unbond_fee > 0 {
+            cmds.add_cmd(StrategyCmd::CollectUnbondFee {
+                batch: pending_batch_id,
+                value: DepositValue(unbond_fee),
+            });
+        }
+
         match self.unbonding_log.last_entered_batch(&recipient) {
             Some(batch_id) if batch_id != pending_batch_id => {
                 cmds.add_cmd(UnbondingLogSet::LastEnteredBatch {
@@ -842,6 +968,49 @@ impl<'a> Vault for VaultImpl<'a> {
         ])
     }
 
+    fn claim_all(
+        &self,
+        recipient: Recipient,
+        max_batches: usize,
+    ) -> Result<ClaimAllResponse, Error> {
+        let mut total_claimable_amount = 0u128;
+        let mut claimed = vec![];
+
+        let available_to_claim = self.strategy.available_to_claim();
+
+        let iter = ClaimableBatchIter::new(&recipient, self.unbonding_log, self.strategy)
+            .take(max_batches);
+
+        for (ClaimAmount(amount), id) in iter {
+            let Some(total) = total_claimable_amount.checked_add(amount) else {
+                break;
+            };
+
+            // batches are claimed in order, so stop at the first batch that cannot be covered yet
+            if available_to_claim.is_some_and(|ClaimAmount(available)| total > available) {
+                break;
+            }
+
+            total_claimable_amount = total;
+            claimed.push((id, ClaimAmount(amount)));
+        }
+
+        let &(last_claimed_id, _) = claimed.last().ok_or(Error::NothingToClaim)?;
+
+        let cmds = cmds![
+            UnbondingLogSet::LastClaimedBatch {
+                recipient: recipient.clone(),
+                batch: last_claimed_id
+            },
+            StrategyCmd::SendClaimed {
+                amount: ClaimAmount(total_claimable_amount),
+                recipient
+            }
+        ];
+
+        Ok(ClaimAllResponse { cmds, claimed })
+    }
+
     fn start_unbond(&self) -> Result<Vec<Cmd>, Error> {
         let pending_batch_id = self.pending_batch_id();
 
@@ -870,6 +1039,92 @@ impl<'a> Vault for VaultImpl<'a> {
             }
         ])
     }
+
+    fn take_performance_fee(
+        &self,
+        fee_bps: u32,
+        recipient: Recipient,
+        high_water_mark: Option<FixedU256>,
+    ) -> PerformanceFeeResponse {
+        let TotalDepositsValue(total_deposits_value) = self.offset_total_deposits_value();
+
+        let TotalSharesIssued(total_shares_issued) = self.mint.total_shares_issued();
+
+        let no_fee = PerformanceFeeResponse {
+            cmds: vec![],
+            fee_shares: SharesAmount(0),
+            high_water_mark,
+        };
+
+        if total_shares_issued == 0 {
+            return no_fee;
+        }
+
+        let Some(rate) = FixedU256::from_u128(total_deposits_value)
+            .checked_div(FixedU256::from_u128(total_shares_issued))
+        else {
+            return no_fee;
+        };
+
+        // the first observation sets the mark, only the gains after it are charged
+        let Some(mark) = high_water_mark else {
+            return PerformanceFeeResponse {
+                high_water_mark: Some(rate),
+                ..no_fee
+            };
+        };
+
+        if rate <= mark {
+            return no_fee;
+        }
+
+        let gain = mark
+            .checked_mul_u128(total_shares_issued)
+            .map_or(0, |mark_value| {
+                total_deposits_value.saturating_sub(mark_value)
+            });
+
+        let Some(fee_value) = U256::from(gain)
+            .checked_mul_div(fee_bps.into(), 10_000)
+            .and_then(|fee_value| u128::try_from(fee_value).ok())
+        else {
+            return no_fee;
+        };
+
+        // the fee shares are worth the fee value once issued:
+        // fee_shares * total_deposits_value / (total_shares_issued + fee_shares) == fee_value
+        let fee_shares = total_deposits_value
+            .checked_sub(fee_value)
+            .filter(|remaining_value| *remaining_value != 0)
+            .and_then(|remaining_value| {
+                U256::from(fee_value).checked_mul_div(total_shares_issued, remaining_value)
+            })
+            .and_then(|fee_shares| u128::try_from(fee_shares).ok())
+            .unwrap_or_default();
+
+        let post_fee_rate = total_shares_issued
+            .checked_add(fee_shares)
+            .and_then(|total_shares| {
+                FixedU256::from_u128(total_deposits_value)
+                    .checked_div(FixedU256::from_u128(total_shares))
+            })
+            .unwrap_or(mark);
+
+        let cmds = if fee_shares == 0 {
+            vec![]
+        } else {
+            cmds![MintCmd::Mint {
+                amount: SharesAmount(fee_shares),
+                recipient,
+            }]
+        };
+
+        PerformanceFeeResponse {
+            cmds,
+            fee_shares: SharesAmount(fee_shares),
+            high_water_mark: Some(mark.max(post_fee_rate)),
+        }
+    }
 }
 
 impl From<MintCmd> for Cmd {
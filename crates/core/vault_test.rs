@@ -9,6 +9,7 @@ const BOB: &str = "bob";
 const ALICE: &str = "alice";
 const DEPOSIT_ASSET: &str = "deposit_asset";
 const SHARES_ASSET: &str = "shares_asset";
+const SECONDARY_ASSET: &str = "secondary_asset";
 
 enum UnbondMode {
     Ready,
@@ -41,6 +42,9 @@ struct World {
     total_deposits: u128,
     total_shares: u128,
     underlying_redemption_rate: FixedU256,
+    secondary_conversion_rate: FixedU256,
+    available_to_claim: Option<u128>,
+    unbond_fee_bps: u128,
     unbond_mode: UnbondMode,
     unbonding_batches: BTreeMap<BatchId, WholeBatch>,
     recipient_batches: HashMap<String, RecipientEntry>,
@@ -54,6 +58,9 @@ impl Default for World {
             total_deposits: 0,
             total_shares: 0,
             underlying_redemption_rate: FixedU256::from_u128(1),
+            secondary_conversion_rate: FixedU256::from_u128(1),
+            available_to_claim: None,
+            unbond_fee_bps: 0,
             unbond_mode: UnbondMode::Ready,
             unbonding_batches: BTreeMap::default(),
             recipient_batches: HashMap::default(),
@@ -89,6 +96,23 @@ impl World {
         self
     }
 
+    fn secondary_conversion_rate(mut self, numer: u128, denom: u128) -> Self {
+        self.secondary_conversion_rate = FixedU256::from_u128(numer)
+            .checked_div(FixedU256::from_u128(denom))
+            .unwrap();
+        self
+    }
+
+    fn available_to_claim(mut self, v: u128) -> Self {
+        self.available_to_claim = Some(v);
+        self
+    }
+
+    fn unbond_fee_bps(mut self, v: u128) -> Self {
+        self.unbond_fee_bps = v;
+        self
+    }
+
     fn unbond_later(mut self) -> Self {
         self.unbond_mode = UnbondMode::Later;
         self
@@ -263,6 +287,18 @@ fn deposit_after_total_loss_errs() {
     )
 }
 
+#[test]
+fn deposit_secondary_asset_too_small_errs() {
+    check_err(
+        World::default()
+            .secondary_conversion_rate(1, 2)
+            .vault()
+            .deposit(SECONDARY_ASSET.into(), DepositAmount(1), BOB.into())
+            .unwrap_err(),
+        expect!["deposit too small"],
+    )
+}
+
 #[test]
 fn initial_deposit() {
     check(
@@ -373,6 +409,36 @@ fn regular_deposit_with_underlying_redemption_rate_gt_1() {
     )
 }
 
+#[test]
+fn regular_deposit_with_secondary_asset() {
+    check(
+        World::default()
+            .total_deposits(1_000)
+            .total_shares(shares_amount(1_000))
+            .secondary_conversion_rate(3, 2)
+            .vault()
+            .deposit(SECONDARY_ASSET.into(), DepositAmount(100), BOB.into())
+            .unwrap(),
+        expect![[r#"
+            (
+              cmds: [
+                Strategy(DepositSecondary(
+                  asset: "secondary_asset",
+                  amount: (100),
+                )),
+                Mint(Mint(
+                  amount: (150000000000000000000),
+                  recipient: "bob",
+                )),
+              ],
+              deposit_value: (150),
+              issued_shares: (150000000000000000000),
+              total_shares_issued: (1150000000000000000000),
+              total_deposits_value: (1150),
+            )"#]],
+    )
+}
+
 #[test]
 fn donate_zero_errs() {
     check_err(
@@ -516,6 +582,73 @@ fn redeem_first_time_unbond_ready() {
     )
 }
 
+#[test]
+fn redeem_with_unbond_fee() {
+    check(
+        World::default()
+            .total_deposits(1_000)
+            .total_shares(shares_amount(1_000))
+            .unbond_fee_bps(100)
+            .unbond_later()
+            .vault()
+            .redeem(
+                SHARES_ASSET.into(),
+                SharesAmount(shares_amount(100)),
+                BOB.into(),
+            )
+            .unwrap(),
+        expect![[r#"
+            [
+              UnbondingLog(BatchTotalUnbondValue(
+                batch: 0,
+                value: (100),
+              )),
+              UnbondingLog(UnbondedValueInBatch(
+                recipient: "bob",
+                batch: 0,
+                value: (99),
+              )),
+              Mint(Burn(
+                amount: (100000000000000000000),
+              )),
+              Strategy(CollectUnbondFee(
+                batch: 0,
+                value: (1),
+              )),
+              UnbondingLog(LastEnteredBatch(
+                recipient: "bob",
+                batch: 0,
+              )),
+              UnbondingLog(FirstEnteredBatch(
+                recipient: "bob",
+                batch: 0,
+              )),
+              UnbondingLog(BatchHint(
+                batch: 0,
+                hint: 1,
+              )),
+            ]"#]],
+    )
+}
+
+#[test]
+fn redeem_with_unbond_fee_leaving_nothing_errs() {
+    check_err(
+        World::default()
+            .total_deposits(1_000)
+            .total_shares(shares_amount(1_000))
+            .unbond_fee_bps(10_000)
+            .vault()
+            .redeem(
+                SHARES_ASSET.into(),
+                SharesAmount(shares_amount(100)),
+                BOB.into(),
+            )
+            .unwrap_err(),
+        expect!["redemption too small"],
+    )
+}
+
 #[test]
 fn redeem_first_time_unbond_ready_underlying_redemption_rate_gt_1() {
     check(
@@ -843,6 +976,42 @@ fn claim() {
     )
 }
 
+#[test]
+fn claim_with_unbond_fee_pays_out_net() {
+    let world = World::default()
+        .total_deposits(10_000)
+        .total_shares(shares_amount(10_000))
+        .unbond_fee_bps(100);
+
+    let cmds = vault(&world, &world, &world)
+        .redeem(
+            SHARES_ASSET.into(),
+            SharesAmount(shares_amount(1_000)),
+            BOB.into(),
+        )
+        .unwrap();
+
+    check(
+        world
+            .handle_cmds(cmds)
+            .now(2)
+            .vault()
+            .claim(BOB.into())
+            .unwrap(),
+        expect![[r#"
+            [
+              UnbondingLog(LastClaimedBatch(
+                recipient: "bob",
+                batch: 0,
+              )),
+              Strategy(SendClaimed(
+                amount: (989),
+                recipient: "bob",
+              )),
+            ]"#]],
+    )
+}
+
 #[test]
 fn claim_multiple() {
     let world = World::default()
@@ -893,6 +1062,140 @@ fn claim_multiple() {
     )
 }
 
+fn world_with_three_matured_batches() -> World {
+    let mut world = World::default()
+        .total_deposits(1_000)
+        .total_shares(shares_amount(1_000));
+
+    for (now, shares) in [(2, 100), (3, 200), (4, 300)] {
+        let cmds = vault(&world, &world, &world)
+            .redeem(
+                SHARES_ASSET.into(),
+                SharesAmount(shares_amount(shares)),
+                BOB.into(),
+            )
+            .unwrap();
+
+        world = world.handle_cmds(cmds).now(now);
+    }
+
+    world.now(5)
+}
+
+#[test]
+fn claim_all_without_matured_batches_errs() {
+    check_err(
+        World::default()
+            .total_deposits(1_000)
+            .total_shares(shares_amount(1_000))
+            .vault()
+            .claim_all(BOB.into(), 10)
+            .unwrap_err(),
+        expect!["nothing to claim"],
+    )
+}
+
+#[test]
+fn claim_all() {
+    check(
+        world_with_three_matured_batches()
+            .vault()
+            .claim_all(BOB.into(), 10)
+            .unwrap(),
+        expect![[r#"
+            (
+              cmds: [
+                UnbondingLog(LastClaimedBatch(
+                  recipient: "bob",
+                  batch: 2,
+                )),
+                Strategy(SendClaimed(
+                  amount: (600),
+                  recipient: "bob",
+                )),
+              ],
+              claimed: [
+                (0, (100)),
+                (1, (200)),
+                (2, (300)),
+              ],
+            )"#]],
+    )
+}
+
+#[test]
+fn claim_all_bounded_by_max_batches() {
+    let world = world_with_three_matured_batches();
+
+    let response = world.vault().claim_all(BOB.into(), 2).unwrap();
+
+    check(
+        &response,
+        expect![[r#"
+        (
+          cmds: [
+            UnbondingLog(LastClaimedBatch(
+              recipient: "bob",
+              batch: 1,
+            )),
+            Strategy(SendClaimed(
+              amount: (300),
+              recipient: "bob",
+            )),
+          ],
+          claimed: [
+            (0, (100)),
+            (1, (200)),
+          ],
+        )"#]],
+    );
+
+    let world = world.handle_cmds(response.cmds);
+
+    check(
+        world.vault().claim_all(BOB.into(), 2).unwrap().claimed,
+        expect![[r#"
+            [
+              (2, (300)),
+            ]"#]],
+    );
+}
+
+#[test]
+fn claim_all_skips_batches_not_yet_received() {
+    let world = world_with_three_matured_batches().available_to_claim(350);
+
+    check(
+        world.vault().claim_all(BOB.into(), 10).unwrap(),
+        expect![[r#"
+            (
+              cmds: [
+                UnbondingLog(LastClaimedBatch(
+                  recipient: "bob",
+                  batch: 1,
+                )),
+                Strategy(SendClaimed(
+                  amount: (300),
+                  recipient: "bob",
+                )),
+              ],
+              claimed: [
+                (0, (100)),
+                (1, (200)),
+              ],
+            )"#]],
+    );
+
+    check_err(
+        world
+            .available_to_claim(50)
+            .vault()
+            .claim_all(BOB.into(), 10)
+            .unwrap_err(),
+        expect!["nothing to claim"],
+    );
+}
+
 #[test]
 fn start_unbond_with_empty_ready_pending_batch_errs() {
     check_err(
@@ -970,6 +1273,99 @@ fn start_unbond() {
     )
 }
 
+fn take_performance_fee(
+    world: World,
+    fee_bps: u32,
+    high_water_mark: Option<FixedU256>,
+) -> (World, u128, Option<FixedU256>) {
+    let PerformanceFeeResponse {
+        cmds,
+        fee_shares: SharesAmount(fee_shares),
+        high_water_mark,
+    } = world
+        .vault()
+        .take_performance_fee(fee_bps, ALICE.into(), high_water_mark);
+
+    (world.handle_cmds(cmds), fee_shares, high_water_mark)
+}
+
+#[test]
+fn performance_fee_charged_above_high_water_mark_only() {
+    let world = World::default().total_deposits(1_000).total_shares(1_000);
+
+    // the first observation only sets the mark
+    let (world, fee_shares, mark) = take_performance_fee(world, 1_000, None);
+
+    check(
+        (fee_shares, mark.map(|mark| mark.to_string())),
+        expect![[r#"(0, Some("1.0"))"#]],
+    );
+
+    // a 100 gain is charged 10%, minted as shares worth up to 10 of the deposits
+    let (world, fee_shares, mark) = take_performance_fee(world.total_deposits(1_100), 1_000, mark);
+
+    check(
+        (
+            fee_shares,
+            world.total_shares,
+            mark.map(|mark| mark.to_string()),
+        ),
+        expect![[r#"(9, 1009, Some("1.0901883052527254707631318136769"))"#]],
+    );
+
+    // a loss leaves the mark in place
+    let (world, fee_shares, mark) = take_performance_fee(world.total_deposits(900), 1_000, mark);
+
+    check(
+        (fee_shares, mark.map(|mark| mark.to_string())),
+        expect![[r#"(0, Some("1.0901883052527254707631318136769"))"#]],
+    );
+
+    // recovering the loss back to the mark is not charged
+    let (world, fee_shares, mark) = take_performance_fee(world.total_deposits(1_100), 1_000, mark);
+
+    check(
+        (fee_shares, mark.map(|mark| mark.to_string())),
+        expect![[r#"(0, Some("1.0901883052527254707631318136769"))"#]],
+    );
+
+    // only the gain beyond the mark is charged
+    let (world, fee_shares, mark) = take_performance_fee(world.total_deposits(1_210), 1_000, mark);
+
+    check(
+        (
+            fee_shares,
+            world.total_shares,
+            mark.map(|mark| mark.to_string()),
+        ),
+        expect![[r#"(9, 1018, Some("1.18860510805500982318271119842829"))"#]],
+    );
+}
+
+#[test]
+fn performance_fee_without_shares_or_bps() {
+    // nothing to value without shares
+    let (_, fee_shares, mark) = take_performance_fee(World::default(), 1_000, None);
+
+    check((fee_shares, mark), expect!["(0, None)"]);
+
+    // a zero fee still raises the mark
+    let world = World::default().total_deposits(1_000).total_shares(1_000);
+
+    let (world, _, mark) = take_performance_fee(world, 0, None);
+
+    let (world, fee_shares, mark) = take_performance_fee(world.total_deposits(1_500), 0, mark);
+
+    check(
+        (
+            fee_shares,
+            world.total_shares,
+            mark.map(|mark| mark.to_string()),
+        ),
+        expect![[r#"(0, 1000, Some("1.5"))"#]],
+    );
+}
+
 impl serde::Serialize for crate::Identifier {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -988,6 +1384,24 @@ impl Strategy for World {
         DEPOSIT_ASSET.into()
     }
 
+    fn convert_secondary_deposit(
+        &self,
+        asset: &Asset,
+        DepositAmount(amount): DepositAmount,
+    ) -> Option<DepositAmount> {
+        if asset.as_str() != SECONDARY_ASSET {
+            return None;
+        }
+
+        let amount = self
+            .secondary_conversion_rate
+            .checked_mul(FixedU256::from_u128(amount))
+            .unwrap()
+            .floor();
+
+        Some(DepositAmount(amount))
+    }
+
     fn underlying_asset_decimals(&self) -> Decimals {
         6
     }
@@ -1027,6 +1441,18 @@ impl Strategy for World {
             UnbondMode::Later => UnbondReadyStatus::Later(Some(1)),
         }
     }
+
+    fn unbond_fee(&self, DepositValue(value): DepositValue) -> DepositValue {
+        DepositValue(value * self.unbond_fee_bps / 10_000)
+    }
+
+    fn available_to_claim(&self) -> Option<ClaimAmount> {
+        self.available_to_claim.map(ClaimAmount)
+    }
+
+    fn redemption_rate(&self) -> Option<FixedU256> {
+        None
+    }
 }
 
 impl UnbondingLog for World {
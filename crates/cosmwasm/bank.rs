@@ -0,0 +1,260 @@
+use std::collections::{BTreeMap, HashMap};
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{BankMsg, Coin, CosmosMsg, Event, ReplyOn, SubMsg, Uint128};
+
+use crate::events::{EventBuilder, Key, Kind};
+
+#[cw_serde]
+pub struct UnaccountedBalancesResponse {
+    /// The amount of each denom held by the contract in excess of the funds it tracks
+    pub balances: Vec<Coin>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SweepError {
+    #[error("no unaccounted balance of {denom} to sweep")]
+    NothingToSweep { denom: String },
+}
+
+/// Returns the recipient & amount of a send that can be merged with others:
+/// a `BankMsg::Send` without a reply or gas limit
+fn plain_send<Msg>(msg: &SubMsg<Msg>) -> Option<(&str, &[Coin])> {
+    match msg {
+        SubMsg {
+            msg: CosmosMsg::Bank(BankMsg::Send { to_address, amount }),
+            reply_on: ReplyOn::Never,
+            gas_limit: None,
+            ..
+        } => Some((to_address, amount)),
+        _ => None,
+    }
+}
+
+/// Coalesce the plain bank sends in `msgs` into at most one send per recipient, summing the amounts per denom.
+/// Each coalesced send takes the place of the recipient's last send so that no funds leave earlier than before,
+/// with its coins ordered by denom. All other messages keep their relative order.
+pub fn coalesce_sends<Msg>(msgs: Vec<SubMsg<Msg>>) -> Vec<SubMsg<Msg>> {
+    let mut totals: HashMap<String, BTreeMap<String, Uint128>> = HashMap::new();
+    let mut last_send: HashMap<String, usize> = HashMap::new();
+
+    for (idx, msg) in msgs.iter().enumerate() {
+        let Some((recipient, amount)) = plain_send(msg) else {
+            continue;
+        };
+
+        let denom_totals = totals.entry(recipient.to_owned()).or_default();
+
+        for coin in amount {
+            *denom_totals.entry(coin.denom.clone()).or_default() += coin.amount;
+        }
+
+        last_send.insert(recipient.to_owned(), idx);
+    }
+
+    let mut coalesced = Vec::with_capacity(msgs.len());
+
+    for (idx, msg) in msgs.into_iter().enumerate() {
+        let Some((recipient, _)) = plain_send(&msg) else {
+            coalesced.push(msg);
+            continue;
+        };
+
+        if last_send[recipient] != idx {
+            continue;
+        }
+
+        let to_address = recipient.to_owned();
+
+        let amount: Vec<Coin> = totals
+            .remove(&to_address)
+            .expect("always: recorded for every plain send")
+            .into_iter()
+            .filter(|(_, amount)| !amount.is_zero())
+            .map(|(denom, amount)| Coin { denom, amount })
+            .collect();
+
+        if amount.is_empty() {
+            continue;
+        }
+
+        coalesced.push(SubMsg::new(BankMsg::Send { to_address, amount }));
+    }
+
+    coalesced
+}
+
+/// Returns the amount of each of the contract's `balances` in excess of the `tracked` funds of the same denom,
+/// omitting denoms without a surplus. A denom without any tracked funds is entirely unaccounted for.
+pub fn unaccounted_balances(balances: Vec<Coin>, tracked: &[Coin]) -> Vec<Coin> {
+    balances
+        .into_iter()
+        .filter_map(|Coin { denom, amount }| {
+            let tracked: Uint128 = tracked
+                .iter()
+                .filter(|coin| coin.denom == denom)
+                .map(|coin| coin.amount)
+                .sum();
+
+            let amount = amount.saturating_sub(tracked);
+
+            (!amount.is_zero()).then_some(Coin { denom, amount })
+        })
+        .collect()
+}
+
+/// Returns a send of the entire `unaccounted` balance of `denom` to the `recipient`,
+/// along with the event recording the sweep so that monitoring can alert on it
+pub fn sweep_unaccounted(
+    unaccounted: Vec<Coin>,
+    denom: &str,
+    recipient: String,
+) -> Result<(BankMsg, Event), SweepError> {
+    let surplus = unaccounted
+        .into_iter()
+        .find(|coin| coin.denom == denom)
+        .ok_or_else(|| SweepError::NothingToSweep {
+            denom: denom.to_owned(),
+        })?;
+
+    let event = EventBuilder::new(Kind::UnaccountedSwept)
+        .attr(Key::Denom, &surplus.denom)
+        .amount(surplus.amount)
+        .recipient(&recipient)
+        .event();
+
+    let sweep = BankMsg::Send {
+        to_address: recipient,
+        amount: vec![surplus],
+    };
+
+    Ok((sweep, event))
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{coin, coins, Empty, WasmMsg};
+
+    use super::*;
+
+    fn send(recipient: &str, amount: Vec<Coin>) -> SubMsg<Empty> {
+        SubMsg::new(BankMsg::Send {
+            to_address: recipient.to_owned(),
+            amount,
+        })
+    }
+
+    fn execute(contract: &str) -> SubMsg<Empty> {
+        SubMsg::new(WasmMsg::Execute {
+            contract_addr: contract.to_owned(),
+            msg: Default::default(),
+            funds: vec![],
+        })
+    }
+
+    #[test]
+    fn sums_sends_to_the_same_recipient() {
+        let msgs = vec![
+            send("bob", coins(100, "uatom")),
+            send("alice", coins(50, "uatom")),
+            send("bob", vec![coin(25, "untrn"), coin(200, "uatom")]),
+        ];
+
+        assert_eq!(
+            coalesce_sends(msgs),
+            vec![
+                send("alice", coins(50, "uatom")),
+                send("bob", vec![coin(300, "uatom"), coin(25, "untrn")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_other_messages_in_order() {
+        let msgs = vec![
+            send("bob", coins(100, "uatom")),
+            execute("vault"),
+            send("bob", coins(100, "uatom")),
+            execute("hub"),
+        ];
+
+        assert_eq!(
+            coalesce_sends(msgs),
+            vec![
+                execute("vault"),
+                send("bob", coins(200, "uatom")),
+                execute("hub"),
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_sends_with_gas_limits_untouched() {
+        let with_gas_limit = send("bob", coins(100, "uatom")).with_gas_limit(100_000);
+
+        let msgs = vec![
+            with_gas_limit.clone(),
+            send("bob", coins(100, "uatom")),
+            send("bob", coins(100, "uatom")),
+        ];
+
+        assert_eq!(
+            coalesce_sends(msgs),
+            vec![with_gas_limit, send("bob", coins(200, "uatom"))]
+        );
+    }
+
+    #[test]
+    fn drops_zero_amounts() {
+        let msgs = vec![
+            send("bob", coins(0, "uatom")),
+            send("alice", vec![coin(0, "uatom"), coin(10, "untrn")]),
+        ];
+
+        assert_eq!(
+            coalesce_sends(msgs),
+            vec![send("alice", coins(10, "untrn"))]
+        );
+    }
+
+    #[test]
+    fn unaccounted_balances_exclude_tracked_funds() {
+        let balances = vec![coin(150, "uatom"), coin(100, "untrn"), coin(5, "uosmo")];
+
+        let tracked = vec![
+            coin(100, "uatom"),
+            coin(20, "uatom"),
+            coin(200, "untrn"),
+            coin(50, "ustars"),
+        ];
+
+        assert_eq!(
+            unaccounted_balances(balances, &tracked),
+            vec![coin(30, "uatom"), coin(5, "uosmo")]
+        );
+    }
+
+    #[test]
+    fn sweep_limited_to_unaccounted_balance() {
+        let unaccounted = vec![coin(30, "uatom")];
+
+        assert_eq!(
+            sweep_unaccounted(unaccounted.clone(), "uatom", "treasury".to_owned()).unwrap(),
+            (
+                BankMsg::Send {
+                    to_address: "treasury".to_owned(),
+                    amount: coins(30, "uatom"),
+                },
+                Event::new("unaccounted_swept")
+                    .add_attribute("denom", "uatom")
+                    .add_attribute("amount", "30")
+                    .add_attribute("recipient", "treasury"),
+            )
+        );
+
+        assert!(matches!(
+            sweep_unaccounted(unaccounted, "untrn", "treasury".to_owned()),
+            Err(SweepError::NothingToSweep { .. })
+        ));
+    }
+}
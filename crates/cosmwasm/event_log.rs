@@ -0,0 +1,381 @@
+use cosmwasm_schema::{
+    cw_serde,
+    serde::{de::DeserializeOwned, Serialize},
+};
+use cosmwasm_std::{from_json, to_json_string, StdError, Storage};
+
+use crate::StorageExt as _;
+
+/// The number of entries retained if no capacity has been configured
+pub const DEFAULT_CAPACITY: u64 = 1_000;
+
+/// The number of entries returned by a query if no limit is provided
+pub const DEFAULT_QUERY_LIMIT: u32 = 30;
+
+/// The maximum number of entries returned by a query
+pub const MAX_QUERY_LIMIT: u32 = 100;
+
+/// The number of entries evicted by an append beyond those it appends,
+/// draining any excess left by a reduced capacity over subsequent appends
+pub const EXCESS_EVICTIONS_PER_APPEND: u64 = 10;
+
+pub type EntryId = u64;
+
+#[rustfmt::skip]
+mod key {
+    use crate::MapKey;
+
+    macro_rules! key {
+        ($k:literal) => {
+            concat!("event_log::", $k)
+        };
+    }
+
+    macro_rules! map_key {
+        ($k:literal) => {
+            crate::MapKey::new(key!($k))
+        };
+    }
+
+    pub const CAPACITY     : MapKey = map_key!("capacity");
+    pub const FIRST_ID     : MapKey = map_key!("first_id");
+    pub const NEXT_ID      : MapKey = map_key!("next_id");
+    pub const ENTRY_EVENT  : MapKey = map_key!("entry_event");
+    pub const ENTRY_HEIGHT : MapKey = map_key!("entry_height");
+}
+
+#[cw_serde]
+pub struct EventLogEntry {
+    /// The monotonically increasing ID of the entry
+    pub id: EntryId,
+    /// The block height at which the event was appended
+    pub height: u64,
+    /// The JSON serialized event
+    pub event: String,
+}
+
+impl EventLogEntry {
+    /// Deserialize the entry's event
+    pub fn parse_event<E: DeserializeOwned>(&self) -> Result<E, StdError> {
+        from_json(&self.event)
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct EventLogResponse {
+    /// The retained entries in ascending order of ID
+    pub entries: Vec<EventLogEntry>,
+}
+
+/// A named event log, stored separately from any other log in the same contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventLog {
+    name: &'static str,
+    default_capacity: u64,
+}
+
+impl EventLog {
+    /// An event log retaining [DEFAULT_CAPACITY] entries until a capacity is configured
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            default_capacity: DEFAULT_CAPACITY,
+        }
+    }
+
+    /// An event log retaining `default_capacity` entries until a capacity is configured
+    pub const fn with_default_capacity(name: &'static str, default_capacity: u64) -> Self {
+        assert!(default_capacity > 0, "event log capacity must be non-zero");
+
+        Self {
+            name,
+            default_capacity,
+        }
+    }
+
+    /// Returns the maximum number of entries retained by the event log
+    pub fn capacity(self, storage: &dyn Storage) -> u64 {
+        storage
+            .u64_at(key::CAPACITY.with(self.name))
+            .unwrap_or(self.default_capacity)
+    }
+
+    /// Set the maximum number of entries retained by the event log.
+    /// If the capacity is reduced, the oldest entries are no longer queryable & are evicted gradually on subsequent appends.
+    /// Panics if `capacity` is zero.
+    pub fn set_capacity(self, storage: &mut dyn Storage, capacity: u64) {
+        assert!(capacity > 0, "event log capacity must be non-zero");
+
+        storage.set_u64(key::CAPACITY.with(self.name), capacity);
+    }
+
+    fn first_id(self, storage: &dyn Storage) -> EntryId {
+        storage
+            .u64_at(key::FIRST_ID.with(self.name))
+            .unwrap_or_default()
+    }
+
+    fn next_id(self, storage: &dyn Storage) -> EntryId {
+        storage
+            .u64_at(key::NEXT_ID.with(self.name))
+            .unwrap_or_default()
+    }
+
+    // The ID of the oldest entry within the capacity, entries before it may remain stored until evicted
+    fn first_retained_id(self, storage: &dyn Storage) -> EntryId {
+        self.first_id(storage)
+            .max(self.next_id(storage).saturating_sub(self.capacity(storage)))
+    }
+
+    /// Append the `events` emitted at block `height` to the log in order,
+    /// evicting the oldest entries once the capacity has been reached.
+    /// At most [EXCESS_EVICTIONS_PER_APPEND] entries more than those appended are evicted, bounding the cost of an append.
+    pub fn append<E: Serialize>(
+        self,
+        storage: &mut dyn Storage,
+        height: u64,
+        events: &[E],
+    ) -> Result<(), StdError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let mut next_id = self.next_id(storage);
+
+        for event in events {
+            let event = to_json_string(event)?;
+
+            storage.set_string(key::ENTRY_EVENT.multi([&self.name, &next_id]), &event);
+            storage.set_u64(key::ENTRY_HEIGHT.multi([&self.name, &next_id]), height);
+
+            next_id += 1;
+        }
+
+        storage.set_u64(key::NEXT_ID.with(self.name), next_id);
+
+        let mut first_id = self.first_id(storage);
+
+        let evict_until = next_id
+            .saturating_sub(self.capacity(storage))
+            .min(first_id + events.len() as u64 + EXCESS_EVICTIONS_PER_APPEND);
+
+        while first_id < evict_until {
+            storage.remove(key::ENTRY_EVENT.multi([&self.name, &first_id]).as_bytes());
+            storage.remove(key::ENTRY_HEIGHT.multi([&self.name, &first_id]).as_bytes());
+
+            first_id += 1;
+        }
+
+        storage.set_u64(key::FIRST_ID.with(self.name), first_id);
+
+        Ok(())
+    }
+
+    /// Returns up to `limit` retained entries with an ID greater than `start_after`, in ascending order
+    pub fn query(
+        self,
+        storage: &dyn Storage,
+        start_after: Option<EntryId>,
+        limit: Option<u32>,
+    ) -> EventLogResponse {
+        let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as u64;
+
+        let first_id = self.first_retained_id(storage);
+
+        let start = start_after.map_or(first_id, |id| first_id.max(id.saturating_add(1)));
+
+        let end = self.next_id(storage).min(start.saturating_add(limit));
+
+        let entries = (start..end)
+            .map(|id| EventLogEntry {
+                id,
+                height: storage
+                    .u64_at(key::ENTRY_HEIGHT.multi([&self.name, &id]))
+                    .expect("always: height stored for retained entries"),
+                event: storage
+                    .string_at(key::ENTRY_EVENT.multi([&self.name, &id]))
+                    .expect("always: event stored for retained entries"),
+            })
+            .collect();
+
+        EventLogResponse { entries }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::testing::MockStorage;
+    use test_utils::prelude::*;
+
+    use super::*;
+
+    const LOG: EventLog = EventLog::new("test");
+
+    #[cw_serde]
+    enum Event {
+        Slashed { amount: u128 },
+        Unbonded { amount: u128 },
+    }
+
+    fn ids(response: &EventLogResponse) -> Vec<EntryId> {
+        response.entries.iter().map(|entry| entry.id).collect()
+    }
+
+    fn stored(storage: &dyn Storage, id: EntryId) -> bool {
+        storage
+            .string_at(key::ENTRY_EVENT.multi([&LOG.name, &id]))
+            .is_some()
+    }
+
+    #[test]
+    fn append_preserves_order() {
+        let mut storage = MockStorage::default();
+
+        LOG.append(
+            &mut storage,
+            10,
+            &[Event::Slashed { amount: 1 }, Event::Unbonded { amount: 2 }],
+        )
+        .unwrap();
+
+        LOG.append(&mut storage, 11, &[Event::Unbonded { amount: 3 }])
+            .unwrap();
+
+        check(
+            LOG.query(&storage, None, None),
+            expect![[r#"
+                (
+                  entries: [
+                    (
+                      id: 0,
+                      height: 10,
+                      event: "{\"slashed\":{\"amount\":\"1\"}}",
+                    ),
+                    (
+                      id: 1,
+                      height: 10,
+                      event: "{\"unbonded\":{\"amount\":\"2\"}}",
+                    ),
+                    (
+                      id: 2,
+                      height: 11,
+                      event: "{\"unbonded\":{\"amount\":\"3\"}}",
+                    ),
+                  ],
+                )"#]],
+        );
+    }
+
+    #[test]
+    fn query_pagination() {
+        let mut storage = MockStorage::default();
+
+        let events: Vec<_> = (0..5).map(|amount| Event::Slashed { amount }).collect();
+
+        LOG.append(&mut storage, 1, &events).unwrap();
+
+        let page = LOG.query(&storage, None, Some(2));
+        assert_eq!(ids(&page), [0, 1]);
+
+        let page = LOG.query(&storage, page.entries.last().map(|e| e.id), Some(2));
+        assert_eq!(ids(&page), [2, 3]);
+
+        let page = LOG.query(&storage, page.entries.last().map(|e| e.id), Some(2));
+        assert_eq!(ids(&page), [4]);
+
+        let page = LOG.query(&storage, page.entries.last().map(|e| e.id), Some(2));
+        assert!(page.entries.is_empty());
+
+        let page = LOG.query(&storage, None, Some(MAX_QUERY_LIMIT + 1));
+        assert_eq!(ids(&page), [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn ring_buffer_evicts_oldest_entries() {
+        let mut storage = MockStorage::default();
+
+        LOG.set_capacity(&mut storage, 3);
+
+        for amount in 0..5 {
+            LOG.append(&mut storage, amount as u64, &[Event::Unbonded { amount }])
+                .unwrap();
+        }
+
+        assert_eq!(ids(&LOG.query(&storage, None, None)), [2, 3, 4]);
+
+        // start_after an evicted entry resumes from the oldest retained entry
+        assert_eq!(ids(&LOG.query(&storage, Some(0), None)), [2, 3, 4]);
+
+        LOG.set_capacity(&mut storage, 2);
+
+        LOG.append(&mut storage, 5, &[Event::Unbonded { amount: 5 }])
+            .unwrap();
+
+        assert_eq!(ids(&LOG.query(&storage, None, None)), [4, 5]);
+    }
+
+    #[test]
+    fn shrinking_capacity_evicts_gradually() {
+        let mut storage = MockStorage::default();
+
+        let events: Vec<_> = (0..50).map(|amount| Event::Unbonded { amount }).collect();
+
+        LOG.append(&mut storage, 1, &events).unwrap();
+
+        LOG.set_capacity(&mut storage, 2);
+
+        // the excess entries are no longer queryable, before any are evicted
+        assert_eq!(ids(&LOG.query(&storage, None, None)), [48, 49]);
+
+        LOG.append(&mut storage, 2, &[Event::Slashed { amount: 50 }])
+            .unwrap();
+
+        assert_eq!(ids(&LOG.query(&storage, None, None)), [49, 50]);
+
+        // only the appended entry & the excess allowance are evicted
+        assert!(!stored(&storage, EXCESS_EVICTIONS_PER_APPEND));
+        assert!(stored(&storage, EXCESS_EVICTIONS_PER_APPEND + 1));
+
+        for height in 3..8 {
+            LOG.append(&mut storage, height, &[Event::Slashed { amount: 0 }])
+                .unwrap();
+        }
+
+        // drained once every excess entry is evicted
+        assert!((0..54).all(|id| !stored(&storage, id)));
+        assert_eq!(LOG.first_id(&storage), 54);
+        assert_eq!(ids(&LOG.query(&storage, None, None)), [54, 55]);
+    }
+
+    #[test]
+    fn logs_are_independent() {
+        const OTHER: EventLog = EventLog::with_default_capacity("other", 1);
+
+        let mut storage = MockStorage::default();
+
+        LOG.append(&mut storage, 1, &[Event::Slashed { amount: 1 }])
+            .unwrap();
+
+        OTHER
+            .append(
+                &mut storage,
+                2,
+                &[Event::Unbonded { amount: 2 }, Event::Unbonded { amount: 3 }],
+            )
+            .unwrap();
+
+        let events = |log: EventLog| -> Vec<(EntryId, Event)> {
+            log.query(&storage, None, None)
+                .entries
+                .iter()
+                .map(|entry| (entry.id, entry.parse_event().unwrap()))
+                .collect()
+        };
+
+        assert_eq!(events(LOG), [(0, Event::Slashed { amount: 1 })]);
+
+        // the other log's capacity of 1 only retains its latest entry
+        assert_eq!(events(OTHER), [(1, Event::Unbonded { amount: 3 })]);
+    }
+}
@@ -0,0 +1,226 @@
+use cosmwasm_std::{Addr, Attribute, Event, Uint128};
+use num::FixedU256;
+use strum::IntoStaticStr;
+
+use amulet_core::Identifier;
+
+/// Response attribute keys, rendered in snake case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum Key {
+    Kind,
+    Vault,
+    Account,
+    Recipient,
+    Amount,
+    Operator,
+    Reason,
+    MintedShares,
+    DepositValue,
+    RedeemShares,
+    CollateralShares,
+    CollateralBalance,
+    ReserveShares,
+    ReserveBalance,
+    TreasuryShares,
+    CollateralFee,
+    ReserveFee,
+    AmoShares,
+    AmoSharesReturned,
+    AmoDepositsReturned,
+    Spr,
+    AccountCollateral,
+    AccountDebt,
+    AccountCredit,
+    PositionSeq,
+    UnbondStartHint,
+    Flag,
+    Enabled,
+    Paused,
+    Sender,
+    Denom,
+    OldDisplayTicker,
+    NewDisplayTicker,
+    OldDisplayDecimals,
+    NewDisplayDecimals,
+    OldDescription,
+    NewDescription,
+}
+
+impl Key {
+    pub fn as_str(self) -> &'static str {
+        self.into()
+    }
+}
+
+/// The kinds of event built, rendered in snake case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum Kind {
+    /// A user message, rendered as the message's own kind
+    User(&'static str),
+    Batch,
+    VaultDepositCallback,
+    VaultFlagChanged,
+    GlobalPauseChanged,
+    SyntheticMetadataUpdated,
+    YieldFeeAccrued,
+    UnaccountedSwept,
+}
+
+impl Kind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::User(kind) => kind,
+            kind => kind.into(),
+        }
+    }
+}
+
+/// A value that can be rendered as an attribute value
+pub trait AttributeValue {
+    fn render(&self) -> String;
+}
+
+macro_rules! impl_attribute_value {
+    ($($t:ty),+) => {
+        $(
+            impl AttributeValue for $t {
+                fn render(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )+
+    };
+}
+
+impl_attribute_value!(Identifier, Uint128, FixedU256, Addr, String, str, u128, u64, u32, bool);
+
+impl<T: AttributeValue + ?Sized> AttributeValue for &T {
+    fn render(&self) -> String {
+        (**self).render()
+    }
+}
+
+/// Builds response attributes or events with keys checked at compile time, e.g.
+/// `EventBuilder::new(Kind::Batch).account(&sender).render()`
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EventBuilder {
+    kind: Option<Kind>,
+    attrs: Vec<Attribute>,
+}
+
+impl EventBuilder {
+    /// Start building the attributes of a `kind` of event
+    pub fn new(kind: Kind) -> Self {
+        Self {
+            kind: Some(kind),
+            attrs: vec![],
+        }
+    }
+
+    pub fn attr(mut self, key: Key, value: impl AttributeValue) -> Self {
+        self.attrs
+            .push(Attribute::new(key.as_str(), value.render()));
+        self
+    }
+
+    pub fn vault(self, vault: impl AttributeValue) -> Self {
+        self.attr(Key::Vault, vault)
+    }
+
+    pub fn account(self, account: impl AttributeValue) -> Self {
+        self.attr(Key::Account, account)
+    }
+
+    pub fn recipient(self, recipient: impl AttributeValue) -> Self {
+        self.attr(Key::Recipient, recipient)
+    }
+
+    pub fn amount(self, amount: impl AttributeValue) -> Self {
+        self.attr(Key::Amount, amount)
+    }
+
+    pub fn operator(self, operator: impl AttributeValue) -> Self {
+        self.attr(Key::Operator, operator)
+    }
+
+    /// Returns the attributes in the order they were added, led by the kind if any
+    pub fn render(self) -> Vec<Attribute> {
+        let kind = self
+            .kind
+            .map(|kind| Attribute::new(Key::Kind.as_str(), kind.as_str()));
+
+        kind.into_iter().chain(self.attrs).collect()
+    }
+
+    /// Returns an event typed by the kind, carrying the attributes in the order they were added
+    pub fn event(self) -> Event {
+        let kind = self.kind.expect("always: events are built with a kind");
+
+        Event::new(kind.as_str()).add_attributes(self.attrs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pairs(attrs: Vec<Attribute>) -> Vec<(String, String)> {
+        attrs
+            .into_iter()
+            .map(|attr| (attr.key, attr.value))
+            .collect()
+    }
+
+    #[test]
+    fn renders_keys_in_snake_case() {
+        let attrs = EventBuilder::new(Kind::User("deposit"))
+            .vault(Identifier::from("vault"))
+            .account(Addr::unchecked("bob"))
+            .amount(Uint128::new(1_000))
+            .attr(Key::Spr, FixedU256::from_u128(1))
+            .attr(Key::PositionSeq, 7u64)
+            .render();
+
+        assert_eq!(
+            pairs(attrs),
+            [
+                ("kind", "deposit"),
+                ("vault", "vault"),
+                ("account", "bob"),
+                ("amount", "1000"),
+                ("spr", "1.0"),
+                ("position_seq", "7"),
+            ]
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+        );
+    }
+
+    #[test]
+    fn renders_no_kind_by_default() {
+        let attrs = EventBuilder::default()
+            .attr(Key::RedeemShares, 10u128)
+            .render();
+
+        assert_eq!(
+            pairs(attrs),
+            [("redeem_shares".to_owned(), "10".to_owned())]
+        );
+    }
+
+    #[test]
+    fn renders_kind_as_event_type() {
+        let event = EventBuilder::new(Kind::GlobalPauseChanged)
+            .attr(Key::Paused, true)
+            .attr(Key::Sender, Addr::unchecked("guardian"))
+            .event();
+
+        assert_eq!(event.ty, "global_pause_changed");
+
+        assert_eq!(
+            pairs(event.attributes),
+            [("paused", "true"), ("sender", "guardian")].map(|(k, v)| (k.to_owned(), v.to_owned()))
+        );
+    }
+}
@@ -3,10 +3,12 @@ pub mod balance_sheet;
 pub mod synthetic_mint;
 pub mod vault_registry;
 
+use std::collections::BTreeMap;
+
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{
-    from_json, to_json_binary, Addr, Api, Binary, Env, MessageInfo, Reply, Response, StdError,
-    Storage, Uint128,
+    from_json, to_json_binary, Addr, Api, Binary, Coin, Env, Event, MessageInfo, QuerierWrapper,
+    Reply, Response, StdError, Storage, Uint128,
 };
 use cw_utils::{one_coin, parse_reply_execute_data, ParseReplyError, PaymentError};
 use strum::IntoStaticStr;
@@ -14,10 +16,14 @@ use strum::IntoStaticStr;
 use amulet_core::{
     admin::Repository as AdminRepository,
     hub::{
-        configure, hub, positions::update_cdp, Account, AdvanceFeeOracle as CoreAdvanceFeeOracle,
-        BalanceSheet as CoreBalanceSheet, BalanceSheetCmd, Cdp, Cmd, ConfigureHub,
-        Error as CoreHubError, Hub, ProxyConfig, SyntheticMint as CoreSyntheticMint, VaultCmd,
-        VaultDepositReason, VaultId, VaultRegistry as CoreVaultRegistry,
+        acknowledge_loss, configure, deregister_vault, ensure_not_paused, hub,
+        positions::update_cdp, set_global_pause, sweep_dust, Account,
+        AdvanceFeeOracle as CoreAdvanceFeeOracle, AdvanceFeeSource as CoreAdvanceFeeSource,
+        AdvanceOutcome, BalanceSheet as CoreBalanceSheet, BalanceSheetCmd, BalanceSheetEvent, Cdp,
+        Cmd, CollateralPriceOracle as CoreCollateralPriceOracle, ConfigureHub, DepositOutcome,
+        EffectiveAdvanceFee, Error as CoreHubError, Hub, PositionLtv, ProxyConfig,
+        SyntheticMint as CoreSyntheticMint, VaultCmd, VaultDepositReason, VaultId,
+        VaultRegistry as CoreVaultRegistry, WithdrawOutcome, DEFAULT_MIN_DEPOSIT,
     },
     vault::{DepositAmount, DepositValue, SharesAmount},
     Identifier,
@@ -25,13 +31,19 @@ use amulet_core::{
 
 use crate::{
     admin::{get_admin_role, Error as AdminError},
-    vault::DepositResponse as VaultDepositResponse,
+    bank::{self, SweepError, UnaccountedBalancesResponse},
+    events::{EventBuilder, Key, Kind},
+    mint::{Metadata as SyntheticMetadata, QueryMsg as MintQueryMsg, WhitelistedResponse},
+    vault::{
+        DepositResponse as VaultDepositResponse, PendingUnbondingResponse,
+        QueryMsg as VaultQueryMsg, StateResponse, Timestamp, UnderlyingAssetDecimalsResponse,
+    },
 };
 
 use self::{
     balance_sheet::StorageExt as _,
     synthetic_mint::StorageExt as _,
-    vault_registry::{StorageExt as _, DEPOSIT_REPLY_ID, MINT_REPLY_ID, REPAY_UNDERLYING_REPLY_ID},
+    vault_registry::{StorageExt as _, VaultCallback},
 };
 
 pub use self::{
@@ -53,6 +65,24 @@ pub enum Error {
     Admin(#[from] AdminError),
     #[error(transparent)]
     Reply(#[from] ParseReplyError),
+    #[error(transparent)]
+    Batch(#[from] BatchError),
+    #[error(transparent)]
+    Sweep(#[from] SweepError),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BatchError {
+    #[error("empty batch")]
+    Empty,
+    #[error("batches cannot be nested")]
+    Nested,
+    #[error("only the last action in a batch may await a vault deposit: {kind}")]
+    DepositBeforeEnd { kind: &'static str },
+    #[error("at most one action in a batch may take the attached funds")]
+    MultipleFundedActions,
+    #[error("attached funds are not taken by any action in the batch")]
+    UnusedFunds,
 }
 
 #[cw_serde]
@@ -64,8 +94,12 @@ pub enum AdminMsg {
         /// The synthetic to be associated with the vault
         synthetic: String,
     },
+    /// Deregister a vault, only possible once it holds no collateral, debt or reserves
+    DeregisterVault { vault: String },
     /// Set the treasury
     SetTreasury { address: String },
+    /// Set the guardian, authorised to set the global pause alongside the admin
+    SetGuardian { address: String },
     /// Set the enabled status of deposits for the vault
     SetDepositsEnabled { vault: String, enabled: bool },
     /// Set the enabled status of advance for the vault
@@ -82,6 +116,42 @@ pub enum AdminMsg {
     SetFixedAdvanceFee { vault: String, bps: u32 },
     /// Set the advance fee oracle for the vault
     SetAdvanceFeeOracle { vault: String, oracle: String },
+    /// Set the window in seconds for which an oracle advance fee is cached per recipient, `None` to disable
+    SetOracleFeeCacheSecs { vault: String, secs: Option<u64> },
+    /// Set the oracle pricing the vault's collateral for LTV purposes, `None` to value it at the share price
+    SetCollateralPriceOracle {
+        vault: String,
+        oracle: Option<CollateralPriceOracle>,
+    },
+    /// Set the minimum non-zero collateral a position may hold in the vault, `None` to disable
+    SetMinCollateral { vault: String, min: Option<Uint128> },
+    /// Set the minimum non-zero debt a position may hold in the vault, `None` to disable
+    SetMinDebt { vault: String, min: Option<Uint128> },
+    /// Set the maximum debt any single position may hold in the vault, `None` to disable
+    SetAccountDebtCap { vault: String, cap: Option<Uint128> },
+    /// Set the reserve balance below which advances are blocked, `None` to disable
+    SetReserveFloor {
+        vault: String,
+        floor: Option<Uint128>,
+    },
+    /// Set the redeem fee tiers for the vault, `None` to disable
+    SetRedeemFeeSchedule {
+        vault: String,
+        tiers: Option<Vec<RedeemFeeTier>>,
+    },
+    /// Set the loss in share value tolerated for withdrawals & repayments in the vault
+    SetLossTolerance { vault: String, bps: u32 },
+    /// Set whether a loss in share value within the loss tolerance is socialised across the vault's collateral holders,
+    /// keeping the vault operational
+    SetLossSocialisationEnabled { vault: String, enabled: bool },
+    /// Set the minimum deposit asset amount accepted by the vault, never less than one
+    SetMinDeposit { vault: String, amount: Uint128 },
+    /// Acknowledge a loss in the vault's share value, distributing it across all positions
+    AcknowledgeLoss { vault: String },
+    /// Sweep collateral dust not attributed to any position into the vault's reserves
+    SweepDust { vault: String },
+    /// Send the hub's entire balance of `denom` in excess of the vault shares it holds to the `recipient`
+    SweepUnaccounted { denom: String, recipient: String },
     /// Set the 'Automatic Market Operator' (AMO) for the vault
     SetAmo { vault: String, amo: String },
     /// Set the AMO allocation to be used for the vault
@@ -121,6 +191,8 @@ pub enum UserMsg {
     /// Deposit native token into a vault on behalf of another (proxied deposit)
     #[strum(to_string = "deposit")]
     DepositOnBehalf { vault: String, behalf_of: String },
+    /// Deposit vault shares held outside of the hub as collateral
+    AbsorbShares { vault: String },
     /// Repay debt against a vault using the underlying token
     RepayUnderlying { vault: String },
     /// Repay debt against a vault using the synthetic token
@@ -136,10 +208,14 @@ pub enum UserMsg {
     },
     /// Request to withdraw funds from a vault
     Withdraw { vault: String, amount: Uint128 },
+    /// Request to withdraw as much collateral from a vault as the position allows, emptying it if there is no debt
+    WithdrawAll { vault: String },
     /// Request to self-liquidate a vault position
     SelfLiquidate { vault: String },
     /// Request to convert a `vault` position's credit into collateral
     ConvertCredit { vault: String, amount: Uint128 },
+    /// Request to convert as much of a `vault` position's credit into collateral as the reserves allow
+    ConvertAllCredit { vault: String },
     /// Redeem synthetics for reserve holdings
     Redeem { vault: String },
     /// Redeem synthetics for reserve holdings on behalf of another (proxied mint)
@@ -150,10 +226,53 @@ pub enum UserMsg {
     /// Mint synthetics for depositing native token into a vault on behalf of another (proxied mint)
     #[strum(to_string = "mint")]
     MintOnBehalf { vault: String, behalf_of: String },
-    /// Request to claim the treasury's accumulated `vault` shares
-    ClaimTreasury { vault: String },
+    /// Request to claim the treasury's accumulated `vault` shares, up to `amount` if set
+    ClaimTreasury {
+        vault: String,
+        amount: Option<Uint128>,
+    },
     /// Request to claim the AMO's accumulated `vault` shares
     ClaimAmo { vault: String },
+    /// Return AMO proceeds to the `vault` depositors, sent as either vault shares or the deposit asset
+    ReturnAmoProceeds { vault: String },
+    /// Authorise an `operator` to manage the sender's `vault` position, replacing any existing operator.
+    /// `None` revokes the existing operator.
+    SetPositionOperator {
+        vault: String,
+        operator: Option<String>,
+        /// The cumulative amount the operator may advance, unlimited if `None`
+        max_advance: Option<Uint128>,
+        /// Whether the operator may withdraw collateral (to the owner)
+        allow_withdraw: bool,
+    },
+    /// Request an advance against the `owner`'s vault deposit as their operator, minted to the owner
+    #[strum(to_string = "advance")]
+    AdvanceAsOperator {
+        vault: String,
+        amount: Uint128,
+        owner: String,
+    },
+    /// Repay the `owner`'s debt against a vault as their operator using the synthetic token
+    #[strum(to_string = "repay_synthetic")]
+    RepaySyntheticAsOperator { vault: String, owner: String },
+    /// Request to withdraw funds from the `owner`'s vault position as their operator, paid to the owner
+    #[strum(to_string = "withdraw")]
+    WithdrawAsOperator {
+        vault: String,
+        amount: Uint128,
+        owner: String,
+    },
+    /// Remove the expired advance fee oracle cache entries of a `vault` among up to `limit` entries
+    /// following `start_after`, `limit` is capped at [MAX_ORACLE_FEE_CACHE_PRUNE_LIMIT]
+    PruneOracleFeeCache {
+        vault: String,
+        start_after: Option<String>,
+        limit: u32,
+    },
+    /// Execute each action in sequence as the sender, aborting every action if any fails.
+    /// At most one action may take the attached funds & an action awaiting a vault deposit,
+    /// which only completes in the reply, must be the last.
+    Batch { actions: Vec<UserMsg> },
 }
 
 impl UserMsg {
@@ -162,6 +281,99 @@ impl UserMsg {
         // relies on deriving strum::IntoStaticStr
         self.into()
     }
+
+    /// True if the message takes the attached funds
+    pub fn takes_funds(&self) -> bool {
+        matches!(
+            self,
+            Self::Deposit { .. }
+                | Self::DepositOnBehalf { .. }
+                | Self::AbsorbShares { .. }
+                | Self::RepayUnderlying { .. }
+                | Self::RepaySynthetic { .. }
+                | Self::RepaySyntheticAsOperator { .. }
+                | Self::Redeem { .. }
+                | Self::RedeemOnBehalf { .. }
+                | Self::Mint { .. }
+                | Self::MintOnBehalf { .. }
+                | Self::ReturnAmoProceeds { .. }
+        )
+    }
+
+    /// True if the message deposits into the vault, only completing once the deposit reply is handled
+    pub fn awaits_deposit(&self) -> bool {
+        matches!(
+            self,
+            Self::Deposit { .. }
+                | Self::DepositOnBehalf { .. }
+                | Self::RepayUnderlying { .. }
+                | Self::Mint { .. }
+                | Self::MintOnBehalf { .. }
+                | Self::ReturnAmoProceeds { .. }
+        )
+    }
+}
+
+/// Split a batch into its actions, each paired with the info it executes with:
+/// the batch sender & the attached funds for the single action that takes them, no funds otherwise.
+pub fn batch_actions(
+    info: MessageInfo,
+    actions: Vec<UserMsg>,
+) -> Result<Vec<(MessageInfo, UserMsg)>, Error> {
+    let Some(last) = actions.len().checked_sub(1) else {
+        return Err(BatchError::Empty.into());
+    };
+
+    let mut funds_taken = false;
+
+    for (idx, action) in actions.iter().enumerate() {
+        if matches!(action, UserMsg::Batch { .. }) {
+            return Err(BatchError::Nested.into());
+        }
+
+        if action.awaits_deposit() && idx != last {
+            return Err(BatchError::DepositBeforeEnd {
+                kind: action.kind(),
+            }
+            .into());
+        }
+
+        if action.takes_funds() {
+            if funds_taken {
+                return Err(BatchError::MultipleFundedActions.into());
+            }
+
+            funds_taken = true;
+        }
+    }
+
+    if !funds_taken && !info.funds.is_empty() {
+        return Err(BatchError::UnusedFunds.into());
+    }
+
+    Ok(actions
+        .into_iter()
+        .map(|action| {
+            let funds = if action.takes_funds() {
+                info.funds.clone()
+            } else {
+                vec![]
+            };
+
+            let info = MessageInfo {
+                sender: info.sender.clone(),
+                funds,
+            };
+
+            (info, action)
+        })
+        .collect())
+}
+
+#[cw_serde]
+pub enum GuardianMsg {
+    /// Halt (or resume) all user entry points across every vault, callable by the guardian or the admin
+    SetGlobalPause { paused: bool },
 }
 
 #[cw_serde]
@@ -169,6 +381,7 @@ impl UserMsg {
 pub enum ExecuteMsg {
     Admin(AdminMsg),
     User(UserMsg),
+    Guardian(GuardianMsg),
 }
 
 #[cw_serde]
@@ -185,6 +398,24 @@ pub struct PositionResponse {
     /// Whether or not there was a vault loss detected.
     /// If `true` the other fields will be based on the last stored overall SPR for the vault.
     pub vault_loss_detected: bool,
+    /// The sequence number of the latest mutation of the position, zero if it has never been mutated
+    pub position_seq: u64,
+}
+
+#[cw_serde]
+pub struct CollateralPriceOracle {
+    /// The address of the oracle contract
+    pub address: String,
+    /// The age in seconds beyond which a price is stale, blocking advances & withdrawals against debt
+    pub max_age_secs: u64,
+}
+
+#[cw_serde]
+pub struct RedeemFeeTier {
+    /// The share of the reserves in basis points a redemption must take for the fee to apply
+    pub threshold_bps: u32,
+    /// The fee in basis points charged on the redeemed synthetics
+    pub fee_bps: u32,
 }
 
 #[cw_serde]
@@ -215,6 +446,26 @@ pub struct VaultMetadata {
     pub advance_fee_recipient: Option<String>,
     /// The advance fee rate oracle assigned to the vault, if any
     pub advance_fee_oracle: Option<String>,
+    /// The window in seconds for which oracle advance fees are cached per recipient, if any
+    pub oracle_fee_cache_secs: Option<u64>,
+    /// The oracle pricing the collateral for LTV purposes, if any, otherwise it is valued at the share price
+    pub collateral_price_oracle: Option<CollateralPriceOracle>,
+    /// The minimum non-zero collateral a position may hold, if any
+    pub min_collateral: Option<Uint128>,
+    /// The minimum non-zero debt a position may hold, if any
+    pub min_debt: Option<Uint128>,
+    /// The maximum debt any single position may hold, if any
+    pub account_debt_cap: Option<Uint128>,
+    /// The reserve balance below which advances are blocked, if any
+    pub reserve_floor: Option<Uint128>,
+    /// The redeem fee tiers, if any
+    pub redeem_fee_schedule: Option<Vec<RedeemFeeTier>>,
+    /// The loss in share value tolerated for withdrawals & repayments in basis points
+    pub loss_tolerance_bps: u32,
+    /// Whether a loss in share value within the loss tolerance is socialised across the collateral holders
+    pub loss_socialisation_enabled: bool,
+    /// The minimum deposit asset amount accepted by the vault
+    pub min_deposit: Uint128,
     /// The total amount of deposited collateral
     pub collateral_balance: Uint128,
     /// The amount of vault shares representing deposited collateral
@@ -231,6 +482,10 @@ pub struct VaultMetadata {
     pub amo_allocation: u32,
     /// The amount of shares claimable by the AMO
     pub amo_shares: Uint128,
+    /// The cumulative amount of vault shares returned by the AMO as proceeds
+    pub amo_shares_returned: Uint128,
+    /// The cumulative amount of deposit asset returned by the AMO as proceeds
+    pub amo_deposits_returned: Uint128,
     /// The on-going sum of payments over collateral, if any
     pub sum_payment_ratio: Option<SumPaymentRatio>,
     /// The address of the deposit proxy, if any
@@ -241,6 +496,10 @@ pub struct VaultMetadata {
     pub mint_proxy: Option<String>,
     /// The address of the redeem proxy, if any
     pub redeem_proxy: Option<String>,
+    /// The vault interface version reported at registration, if any
+    pub interface_version: Option<String>,
+    /// The optional vault interface features reported at registration
+    pub interface_features: Vec<String>,
 }
 
 #[cw_serde]
@@ -248,18 +507,172 @@ pub struct ListVaultsResponse {
     pub vaults: Vec<VaultMetadata>,
 }
 
+#[cw_serde]
+pub struct ProtocolSummaryResponse {
+    /// The total amount of deposited collateral across all vaults
+    pub total_collateral: Uint128,
+    /// The total amount of outstanding debt across all vaults
+    pub total_debt: Uint128,
+    /// The total amount of credit across all vaults
+    pub total_credit: Uint128,
+    /// The total amount of assets in reserve across all vaults
+    pub total_reserves: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulateDepositResponse {
+    /// The amount of collateral credited to the position
+    pub deposited: Uint128,
+    /// The resulting amount of collateral in the position
+    pub collateral: Uint128,
+    /// The resulting amount of debt in the position
+    pub debt: Uint128,
+    /// The resulting amount of credit in the position
+    pub credit: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulateAdvanceResponse {
+    /// The amount of synthetics minted to the recipient
+    pub amount: Uint128,
+    /// The amount of synthetics minted to the advance fee recipient
+    pub fee: Uint128,
+    /// The resulting amount of collateral in the position
+    pub collateral: Uint128,
+    /// The resulting amount of debt in the position
+    pub debt: Uint128,
+    /// The resulting amount of credit in the position
+    pub credit: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulateWithdrawResponse {
+    /// The amount of vault shares redeemed
+    pub redeem_shares: Uint128,
+    /// The resulting amount of collateral in the position
+    pub collateral: Uint128,
+    /// The resulting amount of debt in the position
+    pub debt: Uint128,
+    /// The resulting amount of credit in the position
+    pub credit: Uint128,
+}
+
+#[cw_serde]
+pub enum AdvanceFeeSource {
+    /// No advance fee recipient is set, so no fee is charged
+    None,
+    /// The vault's fixed advance fee
+    Fixed,
+    /// The vault's advance fee oracle
+    Oracle,
+}
+
+#[cw_serde]
+pub struct AdvanceFeeResponse {
+    /// Where the fee was resolved from
+    pub source: AdvanceFeeSource,
+    /// The fee rate in basis points
+    pub bps: u32,
+    /// The fee charged on the requested amount of new debt
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct AdvanceFeesResponse {
+    /// The fee rates in basis points, in the same order as the requested recipients
+    pub bps: Vec<u32>,
+}
+
+#[cw_serde]
+pub struct LtvResponse {
+    /// The current LTV in basis points, `None` if the position has debt against no collateral (infinite LTV)
+    pub ltv_bps: Option<Uint128>,
+    /// The current LTV as a decimal, `None` if the position has debt against no collateral (infinite LTV)
+    pub ltv: Option<String>,
+    /// The vault's configured max LTV in basis points
+    pub max_ltv_bps: u32,
+}
+
+#[cw_serde]
+pub struct AccountVaultPosition {
+    /// Address/Id of the vault
+    pub vault: String,
+    /// The synthetic asset associated with the vault
+    pub synthetic: String,
+    /// Whether or not the vault state could not be queried.
+    /// If `true` the position figures are the last stored ones rather than evaluated.
+    pub unavailable: bool,
+    /// The account's position in the vault
+    pub position: PositionResponse,
+}
+
+#[cw_serde]
+pub struct SyntheticTotals {
+    /// The synthetic asset the totals are denominated in
+    pub synthetic: String,
+    /// The total amount of collateral across the synthetic's vaults
+    pub collateral: Uint128,
+    /// The total amount of debt across the synthetic's vaults
+    pub debt: Uint128,
+    /// The total amount of credit across the synthetic's vaults
+    pub credit: Uint128,
+}
+
+#[cw_serde]
+pub struct AccountOverviewResponse {
+    /// The account's positions in every vault where it has any collateral, debt or credit
+    pub positions: Vec<AccountVaultPosition>,
+    /// The position totals across vaults per synthetic, ordered by synthetic
+    pub totals: Vec<SyntheticTotals>,
+    /// Whether or not there are registered vaults beyond the [MAX_ACCOUNT_OVERVIEW_VAULTS] that were not visited
+    pub truncated: bool,
+    /// The `start_after` to continue the overview from when truncated, the last vault visited
+    pub next_start_after: Option<String>,
+}
+
 #[cw_serde]
 pub struct TreasuryResponse {
     /// The address authorised to claim treasury allocations
     pub treasury: Option<String>,
 }
 
+#[cw_serde]
+pub struct GlobalPauseResponse {
+    /// Whether or not all user entry points are halted
+    pub paused: bool,
+    /// The address authorised to set the global pause alongside the admin, if any
+    pub guardian: Option<String>,
+}
+
 #[cw_serde]
 pub struct MintResponse {
     /// The fixed address of the mint
     pub mint: String,
 }
 
+#[cw_serde]
+pub struct HealthcheckResult {
+    /// The name of the check
+    pub check: String,
+    /// Whether or not the check passed
+    pub passed: bool,
+    /// The reason for the failure, if the check failed
+    pub reason: Option<String>,
+}
+
+#[cw_serde]
+pub struct VaultHealthcheck {
+    /// Address/Id of the vault
+    pub vault: String,
+    /// The results of each check performed against the vault
+    pub checks: Vec<HealthcheckResult>,
+}
+
+#[cw_serde]
+pub struct HealthcheckResponse {
+    pub vaults: Vec<VaultHealthcheck>,
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
@@ -269,10 +682,130 @@ pub enum QueryMsg {
     VaultMetadata { vault: String },
     #[returns(PositionResponse)]
     Position { account: String, vault: String },
+    /// Aggregate collateral, debt, credit & reserves across all registered vaults
+    #[returns(ProtocolSummaryResponse)]
+    ProtocolSummary {},
+    /// Preview the sender depositing an `amount` of the `asset` into the position of `behalf_of`, or their own,
+    /// applying the same checks as a deposit & valuing it in the vault's deposit asset
+    #[returns(SimulateDepositResponse)]
+    SimulateDeposit {
+        vault: String,
+        sender: String,
+        asset: String,
+        amount: Uint128,
+        behalf_of: Option<String>,
+    },
+    /// Preview advancing `amount` against the account's position
+    #[returns(SimulateAdvanceResponse)]
+    SimulateAdvance {
+        vault: String,
+        account: String,
+        amount: Uint128,
+    },
+    /// Preview withdrawing `amount` of collateral from the account's position
+    #[returns(SimulateWithdrawResponse)]
+    SimulateWithdraw {
+        vault: String,
+        account: String,
+        amount: Uint128,
+    },
+    /// The advance fee the account would be charged on advancing `amount`, credit use is not charged
+    #[returns(AdvanceFeeResponse)]
+    AdvanceFee {
+        vault: String,
+        account: String,
+        amount: Uint128,
+    },
+    /// The advance fees charged to each of the `recipients` on new debt, in the same order as given
+    #[returns(AdvanceFeesResponse)]
+    AdvanceFees {
+        vault: String,
+        recipients: Vec<String>,
+    },
+    /// The account's current LTV, with collateral valued the same way as when advancing
+    #[returns(LtvResponse)]
+    Ltv { vault: String, account: String },
+    /// The account's positions across the registered vaults following `start_after`,
+    /// up to [MAX_ACCOUNT_OVERVIEW_VAULTS] vaults are visited
+    #[returns(AccountOverviewResponse)]
+    AccountOverview {
+        account: String,
+        start_after: Option<String>,
+    },
     #[returns(TreasuryResponse)]
     Treasury {},
+    #[returns(GlobalPauseResponse)]
+    GlobalPause {},
     #[returns(MintResponse)]
     Mint {},
+    /// Validate the wiring between the hub, the registered vaults and the mint
+    #[returns(HealthcheckResponse)]
+    Healthcheck {},
+    /// The hub's balance of each denom in excess of the vault shares it holds
+    #[returns(UnaccountedBalancesResponse)]
+    UnaccountedBalances {},
+}
+
+impl From<DepositOutcome> for SimulateDepositResponse {
+    fn from(outcome: DepositOutcome) -> Self {
+        Self {
+            deposited: outcome.collateral.into(),
+            collateral: outcome.cdp.collateral.into(),
+            debt: outcome.cdp.debt.into(),
+            credit: outcome.cdp.credit.into(),
+        }
+    }
+}
+
+impl From<AdvanceOutcome> for SimulateAdvanceResponse {
+    fn from(outcome: AdvanceOutcome) -> Self {
+        Self {
+            amount: outcome.amount.into(),
+            fee: outcome.fee.unwrap_or_default().into(),
+            collateral: outcome.cdp.collateral.into(),
+            debt: outcome.cdp.debt.into(),
+            credit: outcome.cdp.credit.into(),
+        }
+    }
+}
+
+impl From<EffectiveAdvanceFee> for AdvanceFeeResponse {
+    fn from(effective: EffectiveAdvanceFee) -> Self {
+        Self {
+            source: match effective.source {
+                CoreAdvanceFeeSource::None => AdvanceFeeSource::None,
+                CoreAdvanceFeeSource::Fixed => AdvanceFeeSource::Fixed,
+                CoreAdvanceFeeSource::Oracle => AdvanceFeeSource::Oracle,
+            },
+            bps: effective.fee.raw(),
+            amount: effective.amount.into(),
+        }
+    }
+}
+
+impl From<PositionLtv> for LtvResponse {
+    fn from(position_ltv: PositionLtv) -> Self {
+        Self {
+            ltv_bps: position_ltv.ltv.map(|ltv| {
+                ltv.bps()
+                    .expect("ltv in bps will not overflow 128 bits")
+                    .into()
+            }),
+            ltv: position_ltv.ltv.map(|ltv| ltv.fixed_u256().to_string()),
+            max_ltv_bps: position_ltv.max_ltv.raw(),
+        }
+    }
+}
+
+impl From<WithdrawOutcome> for SimulateWithdrawResponse {
+    fn from(outcome: WithdrawOutcome) -> Self {
+        Self {
+            redeem_shares: outcome.shares.0.into(),
+            collateral: outcome.cdp.collateral.into(),
+            debt: outcome.cdp.debt.into(),
+            credit: outcome.cdp.credit.into(),
+        }
+    }
 }
 
 impl From<Cdp> for PositionResponse {
@@ -283,6 +816,7 @@ impl From<Cdp> for PositionResponse {
             credit: cdp.credit.into(),
             sum_payment_ratio: cdp.spr.fixed_u256().to_string(),
             vault_loss_detected: false,
+            position_seq: 0,
         }
     }
 }
@@ -323,22 +857,37 @@ fn handle_advance(
     Ok(cmds)
 }
 
-fn handle_redeem(
+fn handle_redeem<Msg>(
     hub: &dyn Hub,
+    querier: QuerierWrapper,
     info: MessageInfo,
     vault: String,
     recipient: String,
+    response: &mut Response<Msg>,
 ) -> Result<Vec<Cmd>, Error> {
     let coin = one_coin(&info)?;
 
     let cmds = hub.redeem_synthetic(
-        vault.into(),
+        vault.clone().into(),
         info.sender.into_string().into(),
         coin.denom.into(),
         coin.amount.u128(),
         recipient.into(),
     )?;
 
+    // the redeemed reserve shares join the vault's pending unbonding batch on the recipient's behalf,
+    // claimable from the vault once the batch unbonds - the batch start is only known as a hint
+    let pending: PendingUnbondingResponse =
+        querier.query_wasm_smart(&vault, &VaultQueryMsg::PendingUnbonding { address: None })?;
+
+    if let Some(Timestamp(start_hint)) = pending.start_hint {
+        response.attributes.extend(
+            EventBuilder::default()
+                .attr(Key::UnbondStartHint, start_hint)
+                .render(),
+        );
+    }
+
     Ok(cmds)
 }
 
@@ -361,18 +910,75 @@ fn handle_mint(
     Ok(cmds)
 }
 
+/// Vault flags that can be toggled by the admin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum VaultFlag {
+    DepositsEnabled,
+    AdvanceEnabled,
+    LossSocialisationEnabled,
+}
+
+/// Events emitted by admin handlers, so that monitoring can alert on them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdminEvent {
+    /// A vault flag was set, emitted even if the value is unchanged so the audit trail is complete
+    VaultFlagChanged {
+        vault: String,
+        flag: VaultFlag,
+        enabled: bool,
+    },
+    /// The global pause was set
+    GlobalPauseChanged { paused: bool, sender: String },
+}
+
+impl From<AdminEvent> for Event {
+    fn from(event: AdminEvent) -> Self {
+        match event {
+            AdminEvent::VaultFlagChanged {
+                vault,
+                flag,
+                enabled,
+            } => EventBuilder::new(Kind::VaultFlagChanged)
+                .vault(vault)
+                .attr(Key::Flag, <&'static str>::from(flag))
+                .attr(Key::Enabled, enabled)
+                .event(),
+            AdminEvent::GlobalPauseChanged { paused, sender } => {
+                EventBuilder::new(Kind::GlobalPauseChanged)
+                    .attr(Key::Paused, paused)
+                    .attr(Key::Sender, sender)
+                    .event()
+            }
+        }
+    }
+}
+
 pub fn handle_admin_msg<Msg>(
-    api: &dyn Api,
-    admin_repository: &dyn AdminRepository,
-    vaults: &dyn CoreVaultRegistry,
-    mint: &dyn CoreSyntheticMint,
+    ctx: Ctx,
     info: MessageInfo,
     msg: AdminMsg,
 ) -> Result<(Vec<Cmd>, Response<Msg>), Error> {
+    let Ctx {
+        api,
+        storage,
+        querier,
+        env,
+        admin_repository,
+        vaults,
+        mint,
+        balance_sheet,
+        ..
+    } = ctx;
+
     let admin_role = get_admin_role(admin_repository, &info)?;
 
     let config = configure(vaults, mint);
 
+    let mut event = None;
+
+    let mut response = Response::default();
+
     let cmds = match msg {
         AdminMsg::RegisterVault { vault, synthetic } => {
             api.addr_validate(&vault)?;
@@ -380,18 +986,44 @@ pub fn handle_admin_msg<Msg>(
             config.register_vault(admin_role, vault.into(), synthetic.into())?
         }
 
+        AdminMsg::DeregisterVault { vault } => {
+            deregister_vault(vaults, balance_sheet, admin_role, vault.into())?
+        }
+
         AdminMsg::SetTreasury { address } => {
             api.addr_validate(&address)?;
 
             config.set_treasury(admin_role, address.into())?
         }
 
+        AdminMsg::SetGuardian { address } => {
+            api.addr_validate(&address)?;
+
+            config.set_guardian(admin_role, address.into())?
+        }
+
         AdminMsg::SetDepositsEnabled { vault, enabled } => {
-            config.set_deposit_enabled(admin_role, vault.into(), enabled)?
+            let cmds = config.set_deposit_enabled(admin_role, vault.clone().into(), enabled)?;
+
+            event = Some(AdminEvent::VaultFlagChanged {
+                vault,
+                flag: VaultFlag::DepositsEnabled,
+                enabled,
+            });
+
+            cmds
         }
 
         AdminMsg::SetAdvanceEnabled { vault, enabled } => {
-            config.set_advance_enabled(admin_role, vault.into(), enabled)?
+            let cmds = config.set_advance_enabled(admin_role, vault.clone().into(), enabled)?;
+
+            event = Some(AdminEvent::VaultFlagChanged {
+                vault,
+                flag: VaultFlag::AdvanceEnabled,
+                enabled,
+            });
+
+            cmds
         }
 
         AdminMsg::SetMaxLtv { vault, bps } => config.set_max_ltv(admin_role, vault.into(), bps)?,
@@ -420,6 +1052,96 @@ pub fn handle_admin_msg<Msg>(
             config.set_advance_fee_oracle(admin_role, vault.into(), oracle.into())?
         }
 
+        AdminMsg::SetOracleFeeCacheSecs { vault, secs } => {
+            config.set_oracle_fee_cache_secs(admin_role, vault.into(), secs)?
+        }
+
+        AdminMsg::SetCollateralPriceOracle { vault, oracle } => {
+            let oracle = oracle
+                .map(
+                    |CollateralPriceOracle {
+                         address,
+                         max_age_secs,
+                     }| {
+                        api.addr_validate(&address)?;
+
+                        Ok::<_, StdError>(CoreCollateralPriceOracle {
+                            oracle: address.into(),
+                            max_age_secs,
+                        })
+                    },
+                )
+                .transpose()?;
+
+            config.set_collateral_price_oracle(admin_role, vault.into(), oracle)?
+        }
+
+        AdminMsg::SetMinCollateral { vault, min } => {
+            config.set_min_collateral(admin_role, vault.into(), min.map(|min| min.u128()))?
+        }
+
+        AdminMsg::SetMinDebt { vault, min } => {
+            config.set_min_debt(admin_role, vault.into(), min.map(|min| min.u128()))?
+        }
+
+        AdminMsg::SetAccountDebtCap { vault, cap } => {
+            config.set_account_debt_cap(admin_role, vault.into(), cap.map(|cap| cap.u128()))?
+        }
+        AdminMsg::SetReserveFloor { vault, floor } => {
+            config.set_reserve_floor(admin_role, vault.into(), floor.map(|floor| floor.u128()))?
+        }
+        AdminMsg::SetRedeemFeeSchedule { vault, tiers } => config.set_redeem_fee_schedule(
+            admin_role,
+            vault.into(),
+            tiers.map(|tiers| {
+                tiers
+                    .into_iter()
+                    .map(|tier| (tier.threshold_bps, tier.fee_bps))
+                    .collect()
+            }),
+        )?,
+
+        AdminMsg::SetLossTolerance { vault, bps } => {
+            config.set_loss_tolerance(admin_role, vault.into(), bps)?
+        }
+
+        AdminMsg::SetLossSocialisationEnabled { vault, enabled } => {
+            let cmds =
+                config.set_loss_socialisation_enabled(admin_role, vault.clone().into(), enabled)?;
+
+            event = Some(AdminEvent::VaultFlagChanged {
+                vault,
+                flag: VaultFlag::LossSocialisationEnabled,
+                enabled,
+            });
+
+            cmds
+        }
+
+        AdminMsg::SetMinDeposit { vault, amount } => {
+            config.set_min_deposit(admin_role, vault.into(), amount.u128())?
+        }
+
+        AdminMsg::AcknowledgeLoss { vault } => {
+            acknowledge_loss(vaults, balance_sheet, admin_role, vault.into())?
+        }
+
+        AdminMsg::SweepDust { vault } => {
+            sweep_dust(vaults, balance_sheet, admin_role, vault.into())?
+        }
+
+        AdminMsg::SweepUnaccounted { denom, recipient } => {
+            api.addr_validate(&recipient)?;
+
+            let unaccounted = unaccounted_balances(storage, querier, env, vaults, balance_sheet)?;
+
+            let (sweep, swept) = bank::sweep_unaccounted(unaccounted, &denom, recipient)?;
+
+            response = response.add_message(sweep).add_event(swept);
+
+            vec![]
+        }
+
         AdminMsg::SetAmo { vault, amo } => {
             api.addr_validate(&amo)?;
 
@@ -476,103 +1198,107 @@ pub fn handle_admin_msg<Msg>(
         }
     };
 
-    Ok((cmds, Response::default()))
-}
-
-struct AttrsBuilder<'a, Msg>(&'a mut Response<Msg>);
-
-impl<'a, Msg> AttrsBuilder<'a, Msg> {
-    fn add_attr(&mut self, k: &str, v: impl ToString) -> &mut Self {
-        self.0.attributes.push((k, v.to_string()).into());
-        self
-    }
-
-    fn add_kind(&mut self, kind: &str) -> &mut Self {
-        self.add_attr("kind", kind)
-    }
-
-    fn add_vault(&mut self, vault: impl Into<String>) -> &mut Self {
-        self.add_attr("vault", vault.into())
-    }
-
-    fn add_account(&mut self, account: impl Into<String>) -> &mut Self {
-        self.add_attr("account", account.into())
-    }
-
-    fn add_recipient(&mut self, recipient: impl Into<String>) -> &mut Self {
-        self.add_attr("recipient", recipient.into())
-    }
-
-    fn add_amount(&mut self, amount: impl ToString) -> &mut Self {
-        self.add_attr("amount", amount)
-    }
+    Ok((cmds, response.add_events(event.map(Event::from))))
 }
 
 fn add_user_msg_attrs<Msg>(msg: &UserMsg, info: &MessageInfo, response: &mut Response<Msg>) {
-    let mut attrs = AttrsBuilder(response);
+    let attrs = EventBuilder::new(Kind::User(msg.kind()));
 
-    attrs.add_kind(msg.kind());
-
-    match msg {
+    let attrs = match msg {
         UserMsg::Evaluate { vault }
         | UserMsg::Deposit { vault }
+        | UserMsg::AbsorbShares { vault }
         | UserMsg::RepayUnderlying { vault }
         | UserMsg::RepaySynthetic { vault }
         | UserMsg::SelfLiquidate { vault }
         | UserMsg::Advance { vault, .. }
         | UserMsg::Withdraw { vault, .. }
-        | UserMsg::ConvertCredit { vault, .. } => attrs.add_vault(vault).add_account(&info.sender),
+        | UserMsg::WithdrawAll { vault }
+        | UserMsg::ConvertCredit { vault, .. }
+        | UserMsg::ConvertAllCredit { vault } => attrs.vault(vault).account(&info.sender),
 
         UserMsg::DepositOnBehalf { vault, behalf_of }
         | UserMsg::AdvanceOnBehalf {
             vault, behalf_of, ..
-        } => attrs.add_vault(vault).add_account(behalf_of),
+        } => attrs.vault(vault).account(behalf_of),
 
         UserMsg::Redeem { vault } | UserMsg::Mint { vault } => {
-            attrs.add_vault(vault).add_recipient(&info.sender)
+            attrs.vault(vault).recipient(&info.sender)
         }
 
         UserMsg::RedeemOnBehalf { vault, behalf_of }
-        | UserMsg::MintOnBehalf { vault, behalf_of } => {
-            attrs.add_vault(vault).add_recipient(behalf_of)
+        | UserMsg::MintOnBehalf { vault, behalf_of } => attrs.vault(vault).recipient(behalf_of),
+
+        UserMsg::ClaimTreasury { vault, .. }
+        | UserMsg::ClaimAmo { vault }
+        | UserMsg::ReturnAmoProceeds { vault }
+        | UserMsg::PruneOracleFeeCache { vault, .. } => attrs.vault(vault),
+
+        UserMsg::SetPositionOperator {
+            vault, operator, ..
+        } => {
+            let attrs = attrs.vault(vault).account(&info.sender);
+
+            match operator {
+                Some(operator) => attrs.operator(operator),
+                None => attrs,
+            }
+        }
+
+        UserMsg::AdvanceAsOperator { vault, owner, .. }
+        | UserMsg::RepaySyntheticAsOperator { vault, owner }
+        | UserMsg::WithdrawAsOperator { vault, owner, .. } => {
+            attrs.vault(vault).account(owner).operator(&info.sender)
         }
 
-        UserMsg::ClaimTreasury { vault } | UserMsg::ClaimAmo { vault } => attrs.add_vault(vault),
+        UserMsg::Batch { .. } => attrs.account(&info.sender),
     };
 
-    match msg {
+    let attrs = match msg {
         UserMsg::Advance { amount, .. }
         | UserMsg::AdvanceOnBehalf { amount, .. }
+        | UserMsg::AdvanceAsOperator { amount, .. }
         | UserMsg::Withdraw { amount, .. }
-        | UserMsg::ConvertCredit { amount, .. } => {
-            attrs.add_amount(*amount);
-        }
+        | UserMsg::WithdrawAsOperator { amount, .. }
+        | UserMsg::ConvertCredit { amount, .. } => attrs.amount(amount),
+
+        UserMsg::ClaimTreasury {
+            amount: Some(amount),
+            ..
+        } => attrs.amount(amount),
 
         UserMsg::Deposit { .. }
         | UserMsg::DepositOnBehalf { .. }
+        | UserMsg::AbsorbShares { .. }
         | UserMsg::RepayUnderlying { .. }
         | UserMsg::RepaySynthetic { .. }
+        | UserMsg::RepaySyntheticAsOperator { .. }
         | UserMsg::Redeem { .. }
         | UserMsg::RedeemOnBehalf { .. }
         | UserMsg::Mint { .. }
-        | UserMsg::MintOnBehalf { .. } => {
-            if let Some(coin) = info.funds.first() {
-                attrs.add_amount(coin.amount);
-            }
-        }
+        | UserMsg::MintOnBehalf { .. }
+        | UserMsg::ReturnAmoProceeds { .. } => match info.funds.first() {
+            Some(coin) => attrs.amount(coin.amount),
+            None => attrs,
+        },
 
-        _ => {}
-    }
+        _ => attrs,
+    };
+
+    response.attributes.extend(attrs.render());
 }
 
 pub fn handle_user_msg<Msg>(
     api: &dyn Api,
+    querier: QuerierWrapper,
     vaults: &dyn CoreVaultRegistry,
     balance_sheet: &dyn CoreBalanceSheet,
     advance_fee_oracle: &dyn CoreAdvanceFeeOracle,
     info: MessageInfo,
     msg: UserMsg,
 ) -> Result<(Vec<Cmd>, Response<Msg>), Error> {
+    ensure_not_paused(balance_sheet)?;
+
     let hub = hub(vaults, balance_sheet, advance_fee_oracle);
 
     let mut response = Response::default();
@@ -597,6 +1323,17 @@ pub fn handle_user_msg<Msg>(
             handle_deposit(&hub, info, vault, behalf_of)?
         }
 
+        UserMsg::AbsorbShares { vault } => {
+            let coin = one_coin(&info)?;
+
+            hub.absorb_shares(
+                vault.into(),
+                info.sender.into_string().into(),
+                coin.denom.into(),
+                SharesAmount(coin.amount.u128()),
+            )?
+        }
+
         UserMsg::RepayUnderlying { vault } => {
             let coin = one_coin(&info)?;
 
@@ -647,6 +1384,13 @@ pub fn handle_user_msg<Msg>(
             response.cmds
         }
 
+        UserMsg::WithdrawAll { vault } => {
+            let response =
+                hub.withdraw_all_collateral(vault.into(), info.sender.into_string().into())?;
+
+            response.cmds
+        }
+
         UserMsg::SelfLiquidate { vault } => {
             hub.self_liquidate_position(vault.into(), info.sender.into_string().into())?
         }
@@ -661,16 +1405,23 @@ pub fn handle_user_msg<Msg>(
             response.cmds
         }
 
+        UserMsg::ConvertAllCredit { vault } => {
+            let response =
+                hub.convert_all_credit(vault.into(), info.sender.into_string().into())?;
+
+            response.cmds
+        }
+
         UserMsg::Redeem { vault } => {
             let recipient = info.sender.clone().into_string();
 
-            handle_redeem(&hub, info, vault, recipient)?
+            handle_redeem(&hub, querier, info, vault, recipient, &mut response)?
         }
 
         UserMsg::RedeemOnBehalf { vault, behalf_of } => {
             api.addr_validate(&behalf_of)?;
 
-            handle_redeem(&hub, info, vault, behalf_of)?
+            handle_redeem(&hub, querier, info, vault, behalf_of, &mut response)?
         }
 
         UserMsg::Mint { vault } => {
@@ -685,20 +1436,137 @@ pub fn handle_user_msg<Msg>(
             handle_mint(&hub, info, vault, behalf_of)?
         }
 
-        UserMsg::ClaimTreasury { vault } => {
-            hub.claim_treasury_shares(vault.into(), info.sender.into_string().into())?
-        }
+        UserMsg::ClaimTreasury { vault, amount } => hub.claim_treasury_shares(
+            vault.into(),
+            info.sender.into_string().into(),
+            amount.map(|amount| SharesAmount(amount.u128())),
+        )?,
 
         UserMsg::ClaimAmo { vault } => {
             hub.claim_amo_shares(vault.into(), info.sender.into_string().into())?
         }
+
+        UserMsg::PruneOracleFeeCache {
+            vault,
+            start_after,
+            limit,
+        } => hub.prune_oracle_fee_cache(
+            vault.into(),
+            start_after.map(Into::into),
+            limit.min(MAX_ORACLE_FEE_CACHE_PRUNE_LIMIT) as usize,
+        )?,
+
+        UserMsg::ReturnAmoProceeds { vault } => {
+            let coin = one_coin(&info)?;
+
+            hub.return_amo_proceeds(
+                vault.into(),
+                info.sender.into_string().into(),
+                coin.denom.into(),
+                coin.amount.u128(),
+            )?
+        }
+
+        UserMsg::SetPositionOperator {
+            vault,
+            operator,
+            max_advance,
+            allow_withdraw,
+        } => {
+            let operator = operator
+                .map(|operator| api.addr_validate(&operator))
+                .transpose()?;
+
+            hub.set_position_operator(
+                vault.into(),
+                info.sender.into_string().into(),
+                operator.map(|operator| operator.into_string().into()),
+                max_advance.map(|max| max.u128()),
+                allow_withdraw,
+            )?
+        }
+
+        UserMsg::AdvanceAsOperator {
+            vault,
+            amount,
+            owner,
+        } => {
+            api.addr_validate(&owner)?;
+
+            hub.operator_advance(
+                vault.into(),
+                info.sender.into_string().into(),
+                owner.into(),
+                amount.u128(),
+            )?
+        }
+
+        UserMsg::RepaySyntheticAsOperator { vault, owner } => {
+            api.addr_validate(&owner)?;
+
+            let coin = one_coin(&info)?;
+
+            let response = hub.operator_repay_synthetic(
+                vault.into(),
+                info.sender.into_string().into(),
+                owner.into(),
+                coin.denom.into(),
+                coin.amount.u128(),
+            )?;
+
+            response.cmds
+        }
+
+        UserMsg::WithdrawAsOperator {
+            vault,
+            amount,
+            owner,
+        } => {
+            api.addr_validate(&owner)?;
+
+            let response = hub.operator_withdraw_collateral(
+                vault.into(),
+                info.sender.into_string().into(),
+                owner.into(),
+                amount.u128(),
+            )?;
+
+            response.cmds
+        }
+
+        // each action must see the state left by the previous, so the contract executes them one by one
+        UserMsg::Batch { .. } => return Err(BatchError::Nested.into()),
     };
 
     Ok((cmds, response))
 }
 
+pub fn handle_guardian_msg<Msg>(
+    admin_repository: &dyn AdminRepository,
+    balance_sheet: &dyn CoreBalanceSheet,
+    info: MessageInfo,
+    msg: GuardianMsg,
+) -> Result<(Vec<Cmd>, Response<Msg>), Error> {
+    let admin_role = get_admin_role(admin_repository, &info).ok();
+
+    let sender = info.sender.into_string();
+
+    let (cmds, event) = match msg {
+        GuardianMsg::SetGlobalPause { paused } => {
+            let cmds = set_global_pause(balance_sheet, admin_role, sender.clone().into(), paused)?;
+
+            (cmds, AdminEvent::GlobalPauseChanged { paused, sender })
+        }
+    };
+
+    Ok((cmds, Response::default().add_event(Event::from(event))))
+}
+
 pub struct Ctx<'a> {
     pub api: &'a dyn Api,
+    pub storage: &'a dyn Storage,
+    pub querier: QuerierWrapper<'a>,
+    pub env: &'a Env,
     pub vaults: &'a dyn CoreVaultRegistry,
     pub admin_repository: &'a dyn AdminRepository,
     pub mint: &'a dyn CoreSyntheticMint,
@@ -712,23 +1580,21 @@ pub fn handle_execute_msg<Msg>(
     msg: ExecuteMsg,
 ) -> Result<(Vec<Cmd>, Response<Msg>), Error> {
     match msg {
-        ExecuteMsg::Admin(admin_msg) => handle_admin_msg(
-            ctx.api,
-            ctx.admin_repository,
-            ctx.vaults,
-            ctx.mint,
-            info,
-            admin_msg,
-        ),
+        ExecuteMsg::Admin(admin_msg) => handle_admin_msg(ctx, info, admin_msg),
 
         ExecuteMsg::User(user_msg) => handle_user_msg(
             ctx.api,
+            ctx.querier,
             ctx.vaults,
             ctx.balance_sheet,
             ctx.advance_fee_oracle,
             info,
             user_msg,
         ),
+
+        ExecuteMsg::Guardian(guardian_msg) => {
+            handle_guardian_msg(ctx.admin_repository, ctx.balance_sheet, info, guardian_msg)
+        }
     }
 }
 
@@ -739,16 +1605,13 @@ pub fn handle_reply<Msg>(
     advance_fee_oracle: &dyn CoreAdvanceFeeOracle,
     reply: Reply,
 ) -> Result<(Vec<Cmd>, Response<Msg>), Error> {
-    let reason = match reply.id {
-        DEPOSIT_REPLY_ID => VaultDepositReason::Deposit,
-        REPAY_UNDERLYING_REPLY_ID => VaultDepositReason::RepayUnderlying,
-        MINT_REPLY_ID => VaultDepositReason::Mint,
-        _ => panic!("unexpected reply"),
-    };
-
-    let vault = storage.vault_callback_vault();
-
-    let recipient = storage.vault_callback_recipient();
+    let VaultCallback {
+        vault,
+        recipient,
+        reason,
+    } = storage
+        .vault_callback(reply.id)
+        .unwrap_or_else(|| panic!("unexpected reply: {}", reply.id));
 
     let reply_data = parse_reply_execute_data(reply)?
         .data
@@ -758,22 +1621,22 @@ pub fn handle_reply<Msg>(
 
     let mut response = Response::default();
 
-    let mut attrs = AttrsBuilder(&mut response);
-
-    attrs
-        .add_kind("vault_deposit_callback")
-        .add_attr(
-            "reason",
+    let attrs = EventBuilder::new(Kind::VaultDepositCallback)
+        .attr(
+            Key::Reason,
             match reason {
                 VaultDepositReason::Deposit => "deposit",
                 VaultDepositReason::RepayUnderlying => "repay_underlying",
                 VaultDepositReason::Mint => "mint",
+                VaultDepositReason::AmoProceeds => "amo_proceeds",
             },
         )
-        .add_vault(&vault)
-        .add_recipient(&recipient)
-        .add_attr("minted_shares", vault_response.minted_shares.u128())
-        .add_attr("deposit_value", vault_response.deposit_value.u128());
+        .vault(&vault)
+        .recipient(&recipient)
+        .attr(Key::MintedShares, vault_response.minted_shares)
+        .attr(Key::DepositValue, vault_response.deposit_value);
+
+    response.attributes.extend(attrs.render());
 
     let cmds = hub(vaults, balance_sheet, advance_fee_oracle).vault_deposit_callback(
         vault.into(),
@@ -817,6 +1680,46 @@ fn vault_metadata(
 
     let advance_fee_oracle = vaults.advance_fee_oracle(&vault).map(Into::into);
 
+    let oracle_fee_cache_secs = vaults.oracle_fee_cache_secs(&vault);
+
+    let collateral_price_oracle = vaults.collateral_price_oracle(&vault).map(
+        |CoreCollateralPriceOracle {
+             oracle,
+             max_age_secs,
+         }| CollateralPriceOracle {
+            address: oracle.into_string(),
+            max_age_secs,
+        },
+    );
+
+    let min_collateral = vaults.min_collateral(&vault).map(Uint128::new);
+
+    let min_debt = vaults.min_debt(&vault).map(Uint128::new);
+
+    let account_debt_cap = vaults.account_debt_cap(&vault).map(Uint128::new);
+
+    let reserve_floor = vaults.reserve_floor(&vault).map(Uint128::new);
+
+    let redeem_fee_schedule = vaults.redeem_fee_schedule(&vault).map(|schedule| {
+        schedule
+            .tiers()
+            .iter()
+            .map(|tier| RedeemFeeTier {
+                threshold_bps: tier.threshold_bps(),
+                fee_bps: tier.fee().raw(),
+            })
+            .collect()
+    });
+
+    let loss_tolerance_bps = vaults.loss_tolerance(&vault).unwrap_or_default().raw();
+
+    let loss_socialisation_enabled = vaults.loss_socialisation_enabled(&vault);
+
+    let min_deposit = vaults
+        .min_deposit(&vault)
+        .unwrap_or(DEFAULT_MIN_DEPOSIT)
+        .into();
+
     let collateral_balance = balance_sheet
         .collateral_balance(&vault)
         .unwrap_or_default()
@@ -855,6 +1758,18 @@ fn vault_metadata(
         .0
         .into();
 
+    let amo_shares_returned = balance_sheet
+        .amo_shares_returned(&vault)
+        .unwrap_or_default()
+        .0
+        .into();
+
+    let amo_deposits_returned = balance_sheet
+        .amo_deposits_returned(&vault)
+        .unwrap_or_default()
+        .0
+        .into();
+
     let sum_payment_ratio = balance_sheet.overall_sum_payment_ratio(&vault).map(|spr| {
         let timestamp = storage
             .overall_spr_timestamp(&vault)
@@ -873,6 +1788,10 @@ fn vault_metadata(
 
     let redeem_proxy = vaults.redeem_proxy(&vault).map(Identifier::into_string);
 
+    let interface_version = storage.vault_interface_version(&vault);
+
+    let interface_features = storage.vault_interface_features(&vault);
+
     Ok(VaultMetadata {
         vault: vault.into_string(),
         synthetic: synthetic.into_string(),
@@ -884,6 +1803,16 @@ fn vault_metadata(
         fixed_advance_fee_bps,
         advance_fee_recipient,
         advance_fee_oracle,
+        oracle_fee_cache_secs,
+        collateral_price_oracle,
+        min_collateral,
+        min_debt,
+        account_debt_cap,
+        reserve_floor,
+        redeem_fee_schedule,
+        loss_tolerance_bps,
+        loss_socialisation_enabled,
+        min_deposit,
         collateral_balance,
         collateral_shares,
         reserve_balance,
@@ -892,14 +1821,53 @@ fn vault_metadata(
         amo,
         amo_allocation,
         amo_shares,
+        amo_shares_returned,
+        amo_deposits_returned,
         sum_payment_ratio,
         deposit_proxy,
         advance_proxy,
         redeem_proxy,
         mint_proxy,
+        interface_version,
+        interface_features,
     })
 }
 
+/// Returns the hub's balance of each denom in excess of the vault shares it holds
+/// for positions, reserves, the treasury & the AMO across all registered vaults
+fn unaccounted_balances(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    env: &Env,
+    vaults: &dyn CoreVaultRegistry,
+    balance_sheet: &dyn CoreBalanceSheet,
+) -> Result<Vec<Coin>, StdError> {
+    let tracked: Vec<Coin> = (0..storage.vault_count())
+        .map(|i| {
+            let vault: VaultId = storage
+                .vault_address(i)
+                .expect("always: vault address set for i where i < vault count")
+                .into();
+
+            let shares: Uint128 = [
+                balance_sheet.collateral_shares(&vault).unwrap_or_default(),
+                balance_sheet.reserve_shares(&vault).unwrap_or_default(),
+                balance_sheet.treasury_shares(&vault).unwrap_or_default(),
+                balance_sheet.amo_shares(&vault).unwrap_or_default(),
+            ]
+            .into_iter()
+            .map(|SharesAmount(shares)| Uint128::new(shares))
+            .sum();
+
+            Coin::new(shares.u128(), vaults.shares_asset(&vault))
+        })
+        .collect();
+
+    let balances = querier.query_all_balances(&env.contract.address)?;
+
+    Ok(bank::unaccounted_balances(balances, &tracked))
+}
+
 fn list_vaults(
     storage: &dyn Storage,
     vaults: &dyn CoreVaultRegistry,
@@ -920,6 +1888,160 @@ fn list_vaults(
     Ok(all_vaults)
 }
 
+fn protocol_summary(
+    storage: &dyn Storage,
+    balance_sheet: &dyn CoreBalanceSheet,
+) -> ProtocolSummaryResponse {
+    let mut summary = ProtocolSummaryResponse {
+        total_collateral: Uint128::zero(),
+        total_debt: Uint128::zero(),
+        total_credit: Uint128::zero(),
+        total_reserves: Uint128::zero(),
+    };
+
+    for i in 0..storage.vault_count() {
+        let vault: VaultId = storage
+            .vault_address(i)
+            .expect("always: vault address set for i where i < vault count")
+            .into();
+
+        summary.total_collateral +=
+            Uint128::new(balance_sheet.collateral_balance(&vault).unwrap_or_default());
+
+        summary.total_debt += Uint128::new(storage.total_debt(&vault));
+
+        summary.total_credit += Uint128::new(storage.total_credit(&vault));
+
+        summary.total_reserves +=
+            Uint128::new(balance_sheet.reserve_balance(&vault).unwrap_or_default());
+    }
+
+    summary
+}
+
+impl HealthcheckResult {
+    fn pass(check: &str) -> Self {
+        Self {
+            check: check.to_owned(),
+            passed: true,
+            reason: None,
+        }
+    }
+
+    fn fail(check: &str, reason: impl ToString) -> Self {
+        Self {
+            check: check.to_owned(),
+            passed: false,
+            reason: Some(reason.to_string()),
+        }
+    }
+
+    fn from_result<T>(check: &str, result: &Result<T, StdError>) -> Self {
+        match result {
+            Ok(_) => Self::pass(check),
+            Err(err) => Self::fail(check, err),
+        }
+    }
+}
+
+fn vault_healthcheck(
+    vaults: &dyn CoreVaultRegistry,
+    querier: QuerierWrapper,
+    hub_address: &str,
+    mint_address: &str,
+    vault: VaultId,
+) -> VaultHealthcheck {
+    let mut checks = vec![];
+
+    let vault_state: Result<StateResponse, StdError> =
+        querier.query_wasm_smart(vault.as_str(), &VaultQueryMsg::State {});
+
+    checks.push(HealthcheckResult::from_result("vault_state", &vault_state));
+
+    let synthetic: Result<SyntheticMetadata, StdError> = querier.query_wasm_smart(
+        mint_address,
+        &MintQueryMsg::Synthetic {
+            denom: vaults.synthetic_asset(&vault).into_string(),
+        },
+    );
+
+    checks.push(HealthcheckResult::from_result(
+        "synthetic_exists",
+        &synthetic,
+    ));
+
+    let whitelisted: Result<WhitelistedResponse, StdError> = querier.query_wasm_smart(
+        mint_address,
+        &MintQueryMsg::Whitelisted {
+            denom: vaults.synthetic_asset(&vault).into_string(),
+            minter: hub_address.to_owned(),
+        },
+    );
+
+    checks.push(match whitelisted {
+        Ok(WhitelistedResponse { whitelisted: true }) => HealthcheckResult::pass("hub_whitelisted"),
+        Ok(WhitelistedResponse { whitelisted: false }) => {
+            HealthcheckResult::fail("hub_whitelisted", "hub is not a whitelisted minter")
+        }
+        Err(err) => HealthcheckResult::fail("hub_whitelisted", err),
+    });
+
+    let underlying_decimals: Result<UnderlyingAssetDecimalsResponse, StdError> =
+        querier.query_wasm_smart(vault.as_str(), &VaultQueryMsg::UnderlyingAssetDecimals {});
+
+    checks.push(match (underlying_decimals, synthetic) {
+        (Ok(underlying), Ok(synthetic)) if underlying.decimals == synthetic.decimals => {
+            HealthcheckResult::pass("decimals_match")
+        }
+        (Ok(underlying), Ok(synthetic)) => HealthcheckResult::fail(
+            "decimals_match",
+            format!(
+                "vault underlying asset decimals ({}) do not match synthetic decimals ({})",
+                underlying.decimals, synthetic.decimals
+            ),
+        ),
+        (Err(err), _) => HealthcheckResult::fail(
+            "decimals_match",
+            format!("vault underlying asset decimals unavailable: {err}"),
+        ),
+        (_, Err(err)) => HealthcheckResult::fail(
+            "decimals_match",
+            format!("synthetic decimals unavailable: {err}"),
+        ),
+    });
+
+    VaultHealthcheck {
+        vault: vault.into_string(),
+        checks,
+    }
+}
+
+fn healthcheck(
+    storage: &dyn Storage,
+    vaults: &dyn CoreVaultRegistry,
+    querier: QuerierWrapper,
+    env: &Env,
+) -> Vec<VaultHealthcheck> {
+    let mint_address = storage.mint_address();
+
+    (0..storage.vault_count())
+        .map(|i| {
+            storage
+                .vault_address(i)
+                .expect("always: vault address set for i where i < vault count")
+        })
+        .map(|vault| {
+            vault_healthcheck(
+                vaults,
+                querier,
+                env.contract.address.as_str(),
+                &mint_address,
+                vault.into(),
+            )
+        })
+        .collect()
+}
+
 fn position(
     vaults: &dyn CoreVaultRegistry,
     balance_sheet: &dyn CoreBalanceSheet,
@@ -929,11 +2051,19 @@ fn position(
 ) -> Result<PositionResponse, Error> {
     let hub = hub(vaults, balance_sheet, advance_fee_oracle);
 
+    let position_seq = balance_sheet
+        .account_position_seq(&vault, &account)
+        .unwrap_or_default();
+
     match hub.evaluate(vault.clone(), account.clone()) {
-        Ok(response) => Ok(PositionResponse::from(response.cdp)),
+        Ok(response) => Ok(PositionResponse {
+            position_seq,
+            ..response.cdp.into()
+        }),
 
         Err(CoreHubError::SharesValueLoss(_)) => Ok(PositionResponse {
             vault_loss_detected: true,
+            position_seq,
             // update the stored CDP using stored vault
             ..update_cdp(
                 &hub.current_vault_position(&vault),
@@ -946,9 +2076,119 @@ fn position(
     }
 }
 
+/// The maximum number of registered vaults visited by an account overview query
+pub const MAX_ACCOUNT_OVERVIEW_VAULTS: u32 = 50;
+
+/// The maximum number of oracle fee cache entries visited by a single prune
+pub const MAX_ORACLE_FEE_CACHE_PRUNE_LIMIT: u32 = 100;
+
+fn account_overview(
+    storage: &dyn Storage,
+    vaults: &VaultRegistry,
+    balance_sheet: &dyn CoreBalanceSheet,
+    advance_fee_oracle: &dyn CoreAdvanceFeeOracle,
+    account: Account,
+    start_after: Option<String>,
+) -> Result<AccountOverviewResponse, Error> {
+    let hub = hub(vaults, balance_sheet, advance_fee_oracle);
+
+    let vault_count = storage.vault_count();
+
+    let start = match start_after {
+        Some(start_after) => {
+            (0..vault_count)
+                .find(|&i| storage.vault_address(i).as_deref() == Some(start_after.as_str()))
+                .ok_or(CoreHubError::VaultNotRegistered)?
+                + 1
+        }
+        None => 0,
+    };
+
+    let end = vault_count.min(start.saturating_add(MAX_ACCOUNT_OVERVIEW_VAULTS));
+
+    let mut positions = vec![];
+
+    let mut totals = BTreeMap::<String, SyntheticTotals>::new();
+
+    let mut last_visited = None;
+
+    for i in start..end {
+        let vault: VaultId = storage
+            .vault_address(i)
+            .expect("always: vault address set for i where i < vault count")
+            .into();
+
+        last_visited = Some(vault.clone());
+
+        let stored_cdp = hub.current_cdp(&vault, &account);
+
+        if stored_cdp.collateral == 0 && stored_cdp.debt == 0 && stored_cdp.credit == 0 {
+            continue;
+        }
+
+        // evaluating the position panics if the vault cannot be queried,
+        // a successful state query is cached by the registry for the evaluation
+        let (unavailable, position) = match vaults.vault_state(&vault) {
+            Ok(_) => (
+                false,
+                position(
+                    vaults,
+                    balance_sheet,
+                    advance_fee_oracle,
+                    vault.clone(),
+                    account.clone(),
+                )?,
+            ),
+
+            Err(_) => (
+                true,
+                PositionResponse {
+                    position_seq: balance_sheet
+                        .account_position_seq(&vault, &account)
+                        .unwrap_or_default(),
+                    ..stored_cdp.into()
+                },
+            ),
+        };
+
+        let synthetic = vaults.synthetic_asset(&vault).into_string();
+
+        let synthetic_totals = totals
+            .entry(synthetic.clone())
+            .or_insert_with(|| SyntheticTotals {
+                synthetic: synthetic.clone(),
+                collateral: Uint128::zero(),
+                debt: Uint128::zero(),
+                credit: Uint128::zero(),
+            });
+
+        synthetic_totals.collateral += position.collateral;
+        synthetic_totals.debt += position.debt;
+        synthetic_totals.credit += position.credit;
+
+        positions.push(AccountVaultPosition {
+            vault: vault.into_string(),
+            synthetic,
+            unavailable,
+            position,
+        });
+    }
+
+    let truncated = end < vault_count;
+
+    Ok(AccountOverviewResponse {
+        positions,
+        totals: totals.into_values().collect(),
+        truncated,
+        next_start_after: last_visited.filter(|_| truncated).map(VaultId::into_string),
+    })
+}
+
 pub fn handle_query_msg(
     storage: &dyn Storage,
-    vaults: &dyn CoreVaultRegistry,
+    querier: QuerierWrapper,
+    env: &Env,
+    vaults: &VaultRegistry,
     balance_sheet: &dyn CoreBalanceSheet,
     advance_fee_oracle: &dyn CoreAdvanceFeeOracle,
     msg: QueryMsg,
@@ -971,63 +2211,185 @@ pub fn handle_query_msg(
         )
         .and_then(|position| to_json_binary(&position).map_err(Error::from))?,
 
+        QueryMsg::ProtocolSummary {} => to_json_binary(&protocol_summary(storage, balance_sheet))?,
+
+        QueryMsg::SimulateDeposit {
+            vault,
+            sender,
+            asset,
+            amount,
+            behalf_of,
+        } => hub(vaults, balance_sheet, advance_fee_oracle)
+            .simulate_deposit(
+                vault.into(),
+                sender.clone().into(),
+                asset.into(),
+                DepositAmount(amount.u128()),
+                behalf_of.unwrap_or(sender).into(),
+            )
+            .map(SimulateDepositResponse::from)
+            .map_err(Error::from)
+            .and_then(|res| to_json_binary(&res).map_err(Error::from))?,
+
+        QueryMsg::SimulateAdvance {
+            vault,
+            account,
+            amount,
+        } => hub(vaults, balance_sheet, advance_fee_oracle)
+            .simulate_advance(vault.into(), amount.u128(), account.into())
+            .map(SimulateAdvanceResponse::from)
+            .map_err(Error::from)
+            .and_then(|res| to_json_binary(&res).map_err(Error::from))?,
+
+        QueryMsg::SimulateWithdraw {
+            vault,
+            account,
+            amount,
+        } => hub(vaults, balance_sheet, advance_fee_oracle)
+            .simulate_withdraw(vault.into(), account.into(), amount.u128())
+            .map(SimulateWithdrawResponse::from)
+            .map_err(Error::from)
+            .and_then(|res| to_json_binary(&res).map_err(Error::from))?,
+
+        QueryMsg::AdvanceFee {
+            vault,
+            account,
+            amount,
+        } => hub(vaults, balance_sheet, advance_fee_oracle)
+            .effective_advance_fee(vault.into(), account.into(), amount.u128())
+            .map(AdvanceFeeResponse::from)
+            .map_err(Error::from)
+            .and_then(|res| to_json_binary(&res).map_err(Error::from))?,
+
+        QueryMsg::AdvanceFees { vault, recipients } => {
+            hub(vaults, balance_sheet, advance_fee_oracle)
+                .advance_fees(
+                    vault.into(),
+                    recipients.into_iter().map(Into::into).collect(),
+                )
+                .map(|fees| AdvanceFeesResponse {
+                    bps: fees.into_iter().map(|fee| fee.raw()).collect(),
+                })
+                .map_err(Error::from)
+                .and_then(|res| to_json_binary(&res).map_err(Error::from))?
+        }
+
+        QueryMsg::Ltv { vault, account } => hub(vaults, balance_sheet, advance_fee_oracle)
+            .position_ltv(vault.into(), account.into())
+            .map(LtvResponse::from)
+            .map_err(Error::from)
+            .and_then(|res| to_json_binary(&res).map_err(Error::from))?,
+
+        QueryMsg::AccountOverview {
+            account,
+            start_after,
+        } => account_overview(
+            storage,
+            vaults,
+            balance_sheet,
+            advance_fee_oracle,
+            account.into(),
+            start_after,
+        )
+        .and_then(|overview| to_json_binary(&overview).map_err(Error::from))?,
+
         QueryMsg::Treasury {} => to_json_binary(&TreasuryResponse {
             treasury: balance_sheet.treasury().map(Into::into),
         })?,
 
+        QueryMsg::GlobalPause {} => to_json_binary(&GlobalPauseResponse {
+            paused: balance_sheet.global_paused(),
+            guardian: balance_sheet.guardian().map(Into::into),
+        })?,
+
         QueryMsg::Mint {} => to_json_binary(&MintResponse {
             mint: storage.mint_address(),
         })?,
+
+        QueryMsg::Healthcheck {} => to_json_binary(&HealthcheckResponse {
+            vaults: healthcheck(storage, vaults, querier, env),
+        })?,
+
+        QueryMsg::UnaccountedBalances {} => to_json_binary(&UnaccountedBalancesResponse {
+            balances: unaccounted_balances(storage, querier, env, vaults, balance_sheet)?,
+        })?,
     };
 
     Ok(binary)
 }
 
 fn add_cmd_attrs<Msg>(cmd: &Cmd, response: &mut Response<Msg>) {
-    let mut attrs = AttrsBuilder(response);
+    let attrs = EventBuilder::default();
 
-    match cmd {
+    let attrs = match cmd {
         Cmd::Vault(VaultCmd::Redeem {
             amount: SharesAmount(amount),
             ..
-        }) => attrs.add_attr("redeem_shares", amount),
+        }) => attrs.attr(Key::RedeemShares, amount),
         Cmd::BalanceSheet(cmd) => match cmd {
             BalanceSheetCmd::SetCollateralShares {
                 shares: SharesAmount(amount),
                 ..
-            } => attrs.add_attr("collateral_shares", amount),
+            } => attrs.attr(Key::CollateralShares, amount),
             BalanceSheetCmd::SetCollateralBalance { balance, .. } => {
-                attrs.add_attr("collateral_balance", balance)
+                attrs.attr(Key::CollateralBalance, balance)
             }
             BalanceSheetCmd::SetReserveShares {
                 shares: SharesAmount(amount),
                 ..
-            } => attrs.add_attr("reserve_shares", amount),
+            } => attrs.attr(Key::ReserveShares, amount),
             BalanceSheetCmd::SetReserveBalance { balance, .. } => {
-                attrs.add_attr("reserve_balance", balance)
+                attrs.attr(Key::ReserveBalance, balance)
             }
             BalanceSheetCmd::SetTreasuryShares {
                 shares: SharesAmount(amount),
                 ..
-            } => attrs.add_attr("treasury_shares", amount),
+            } => attrs.attr(Key::TreasuryShares, amount),
             BalanceSheetCmd::SetAmoShares {
                 shares: SharesAmount(amount),
                 ..
-            } => attrs.add_attr("amo_shares", amount),
+            } => attrs.attr(Key::AmoShares, amount),
+            BalanceSheetCmd::SetAmoSharesReturned {
+                shares: SharesAmount(amount),
+                ..
+            } => attrs.attr(Key::AmoSharesReturned, amount),
+            BalanceSheetCmd::SetAmoDepositsReturned {
+                amount: DepositAmount(amount),
+                ..
+            } => attrs.attr(Key::AmoDepositsReturned, amount),
             BalanceSheetCmd::SetOverallSumPaymentRatio { spr, .. } => {
-                attrs.add_attr("spr", spr.fixed_u256())
+                attrs.attr(Key::Spr, spr.fixed_u256())
             }
             BalanceSheetCmd::SetAccountCollateral { collateral, .. } => {
-                attrs.add_attr("account_collateral", collateral)
+                attrs.attr(Key::AccountCollateral, collateral)
             }
-            BalanceSheetCmd::SetAccountDebt { debt, .. } => attrs.add_attr("account_debt", debt),
+            BalanceSheetCmd::SetAccountDebt { debt, .. } => attrs.attr(Key::AccountDebt, debt),
             BalanceSheetCmd::SetAccountCredit { credit, .. } => {
-                attrs.add_attr("account_credit", credit)
+                attrs.attr(Key::AccountCredit, credit)
             }
-            _ => &mut attrs,
+            BalanceSheetCmd::SetAccountPositionSeq { seq, .. } => attrs.attr(Key::PositionSeq, seq),
+            _ => attrs,
         },
-        _ => &mut attrs,
+        _ => attrs,
     };
+
+    response.attributes.extend(attrs.render());
+}
+
+fn balance_sheet_event(event: BalanceSheetEvent) -> Event {
+    match event {
+        BalanceSheetEvent::YieldFeeAccrued {
+            vault,
+            treasury_shares: SharesAmount(treasury_shares),
+            collateral_fee: SharesAmount(collateral_fee),
+            reserve_fee: SharesAmount(reserve_fee),
+        } => EventBuilder::new(Kind::YieldFeeAccrued)
+            .vault(vault)
+            .attr(Key::TreasuryShares, treasury_shares)
+            .attr(Key::CollateralFee, collateral_fee)
+            .attr(Key::ReserveFee, reserve_fee)
+            .event(),
+    }
 }
 
 pub fn handle_hub_cmd<Msg>(
@@ -1056,6 +2418,10 @@ pub fn handle_hub_cmd<Msg>(
                 response.messages.push(sub_msg);
             }
         }
+
+        Cmd::Event(event) => {
+            response.events.push(balance_sheet_event(event));
+        }
     }
 
     Ok(())
@@ -1073,6 +2439,12 @@ impl From<AdminMsg> for ExecuteMsg {
     }
 }
 
+impl From<GuardianMsg> for ExecuteMsg {
+    fn from(v: GuardianMsg) -> Self {
+        Self::Guardian(v)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{from_json, to_json_string};
@@ -1,13 +1,15 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{CustomQuery, QuerierWrapper};
+use cosmwasm_std::{CustomQuery, Env, QuerierWrapper};
 
 use amulet_core::{
     hub::{AdvanceFee, AdvanceFeeOracle as CoreAdvanceFeeOracle, Oracle},
+    vault::Now,
     Recipient,
 };
 
 pub struct AdvanceFeeOracle<'a> {
     querier: QuerierWrapper<'a>,
+    env: &'a Env,
 }
 
 #[cw_serde]
@@ -20,15 +22,31 @@ pub struct AdvanceFeeResponse {
     fee: Option<u32>,
 }
 
+#[cw_serde]
+pub struct AdvanceFeesQuery {
+    recipients: Vec<String>,
+}
+
+#[cw_serde]
+pub struct AdvanceFeesResponse {
+    /// The fees in the same order as the requested recipients
+    fees: Vec<Option<u32>>,
+}
+
 impl<'a> AdvanceFeeOracle<'a> {
-    pub fn new(querier: QuerierWrapper<'a, impl CustomQuery>) -> Self {
+    pub fn new(querier: QuerierWrapper<'a, impl CustomQuery>, env: &'a Env) -> Self {
         Self {
             querier: querier.into_empty(),
+            env,
         }
     }
 }
 
 impl<'a> CoreAdvanceFeeOracle for AdvanceFeeOracle<'a> {
+    fn now(&self) -> Now {
+        self.env.block.time.seconds()
+    }
+
     fn advance_fee(&self, oracle: &Oracle, recipient: &Recipient) -> Option<AdvanceFee> {
         let response: AdvanceFeeResponse = match self.querier.query_wasm_smart(
             oracle,
@@ -42,4 +60,30 @@ impl<'a> CoreAdvanceFeeOracle for AdvanceFeeOracle<'a> {
 
         response.fee.and_then(AdvanceFee::new)
     }
+
+    fn fees_for(&self, oracle: &Oracle, recipients: Vec<Recipient>) -> Vec<Option<AdvanceFee>> {
+        let recipient_count = recipients.len();
+
+        let response: AdvanceFeesResponse = match self.querier.query_wasm_smart(
+            oracle,
+            &AdvanceFeesQuery {
+                recipients: recipients.into_iter().map(Recipient::into_string).collect(),
+            },
+        ) {
+            Ok(res) => res,
+            Err(err) => panic!("unexpected error querying advance fee oracle: {err}"),
+        };
+
+        assert_eq!(
+            response.fees.len(),
+            recipient_count,
+            "advance fee oracle must respond with one fee per recipient"
+        );
+
+        response
+            .fees
+            .into_iter()
+            .map(|fee| fee.and_then(AdvanceFee::new))
+            .collect()
+    }
 }
@@ -1,14 +1,15 @@
-use cosmwasm_std::{coins, BankMsg, Env, Storage, SubMsg};
+use cosmwasm_std::{coins, BankMsg, Env, Order, StdError, Storage, SubMsg};
 
 use amulet_core::{
     hub::{
-        Account, AmoShares, BalanceSheet as CoreBalanceSheet, BalanceSheetCmd, Collateral, Credit,
-        Debt, SumPaymentRatio, Treasury, TreasuryShares, VaultId,
+        positions::sum_payment, Account, AmoShares, BalanceSheet as CoreBalanceSheet,
+        BalanceSheetCmd, Collateral, CollateralFactor, Credit, Debt, Guardian, PositionOperator,
+        PositionSeq, SumPaymentRatio, Treasury, TreasuryShares, VaultId,
     },
-    vault::SharesAmount,
+    vault::{DepositAmount, SharesAmount},
 };
 
-use crate::StorageExt as _;
+use crate::{hub::vault_registry::StorageExt as _, MapKey, StorageExt as _};
 
 pub struct BalanceSheet<'a>(&'a dyn Storage);
 
@@ -35,20 +36,35 @@ mod key {
     }
 
     pub const TREASURY                  : &str   = key!("treasury");
+    pub const GUARDIAN                  : &str   = key!("guardian");
+    pub const GLOBAL_PAUSED             : &str   = key!("global_paused");
     pub const COLLATERAL_SHARES         : MapKey = map_key!("collateral_shares");
     pub const COLLATERAL_BALANCE        : MapKey = map_key!("collateral_balance");
     pub const RESERVE_SHARES            : MapKey = map_key!("reserve_shares");
     pub const RESERVE_BALANCE           : MapKey = map_key!("reserve_balance");
     pub const TREASURY_SHARES           : MapKey = map_key!("treasury_shares");
     pub const AMO_SHARES                : MapKey = map_key!("amo_shares");
+    pub const AMO_SHARES_RETURNED       : MapKey = map_key!("amo_shares_returned");
+    pub const AMO_DEPOSITS_RETURNED     : MapKey = map_key!("amo_deposits_returned");
     pub const OVERALL_SUM_PAYMENT_RATIO : MapKey = map_key!("overall_sum_payment_ratio");
+    pub const COLLATERAL_FACTOR         : MapKey = map_key!("collateral_factor");
     pub const ACCOUNT_COLLATERAL        : MapKey = map_key!("account_collateral");
     pub const ACCOUNT_DEBT              : MapKey = map_key!("account_debt");
     pub const ACCOUNT_CREDIT            : MapKey = map_key!("account_credit");
     pub const ACCOUNT_SUM_PAYMENT_RATIO : MapKey = map_key!("account_sum_payment_ratio");
+    pub const ACCOUNT_COLLATERAL_FACTOR : MapKey = map_key!("account_collateral_factor");
+    pub const ACCOUNT_POSITION_SEQ      : MapKey = map_key!("account_position_seq");
+    pub const POSITION_OPERATOR         : MapKey = map_key!("position_operator");
+    pub const TOTAL_DEBT                : MapKey = map_key!("total_debt");
+    pub const TOTAL_CREDIT              : MapKey = map_key!("total_credit");
+    pub const PENDING_DEBT_PAYMENT      : MapKey = map_key!("pending_debt_payment");
+    pub const TOTAL_COLLATERAL          : MapKey = map_key!("total_collateral");
 }
 
 const TIMESTAMP: &str = "timestamp";
+const MAX_ADVANCE: &str = "max_advance";
+const ALLOW_WITHDRAW: &str = "allow_withdraw";
+const ADVANCED: &str = "advanced";
 
 pub trait StorageExt: Storage {
     fn set_overall_spr_timestamp(&mut self, vault: &str, timestamp: u64) {
@@ -61,6 +77,166 @@ pub trait StorageExt: Storage {
     fn overall_spr_timestamp(&self, vault: &str) -> Option<u64> {
         self.u64_at(key::OVERALL_SUM_PAYMENT_RATIO.multi([&vault, &TIMESTAMP]))
     }
+
+    /// The debt payment accrued across the vault's positions that is yet to be settled in each position
+    fn pending_debt_payment(&self, vault: &str) -> Debt {
+        self.u128_at(key::PENDING_DEBT_PAYMENT.with(vault))
+            .unwrap_or_default()
+    }
+
+    /// The sum of all account debts recorded against the vault, less any pending debt payment
+    fn total_debt(&self, vault: &str) -> Debt {
+        let (total_debt, _) = totals(self, vault);
+
+        total_debt
+    }
+
+    /// The sum of all account credit recorded against the vault, plus any pending debt payment in excess of the debt
+    fn total_credit(&self, vault: &str) -> Credit {
+        let (_, total_credit) = totals(self, vault);
+
+        total_credit
+    }
+}
+
+// The vault's total debt & credit as of the current sum payment ratio.
+// Pending payments are applied to the recorded debt first, with any excess becoming credit,
+// so the split is approximate until every position has been settled.
+fn totals<S>(storage: &S, vault: &str) -> (Debt, Credit)
+where
+    S: Storage + ?Sized,
+{
+    let recorded_debt = storage
+        .u128_at(key::TOTAL_DEBT.with(vault))
+        .unwrap_or_default();
+
+    let recorded_credit = storage
+        .u128_at(key::TOTAL_CREDIT.with(vault))
+        .unwrap_or_default();
+
+    let pending_payment = storage.pending_debt_payment(vault);
+
+    let debt_payment = recorded_debt.min(pending_payment);
+
+    (
+        recorded_debt - debt_payment,
+        recorded_credit.saturating_add(pending_payment - debt_payment),
+    )
+}
+
+// The accounts with an entry in the per-account `map` for the vault
+fn accounts(storage: &dyn Storage, map: MapKey, vault: &str) -> Vec<Account> {
+    let prefix = map.with(vault);
+
+    // the first key after every key with the prefix, replacing its ':' terminator with ';'
+    let mut end = prefix.clone().into_bytes();
+    *end.last_mut().expect("always: keys are ':' terminated") = b';';
+
+    storage
+        .range_keys(Some(prefix.as_bytes()), Some(&end), Order::Ascending)
+        .map(|key| {
+            std::str::from_utf8(&key[prefix.len()..])
+                .expect("always: keys are valid utf-8")
+                .strip_suffix(':')
+                .expect("always: map keys are ':' terminated")
+                .to_owned()
+                .into()
+        })
+        .collect()
+}
+
+/// Remove the balances & totals recorded against a deregistered vault.
+/// The sum payment ratio & collateral factor are kept, any remaining account positions are recorded against them.
+pub fn remove_vault(storage: &mut dyn Storage, vault: &str) {
+    for map in [
+        key::COLLATERAL_SHARES,
+        key::COLLATERAL_BALANCE,
+        key::RESERVE_SHARES,
+        key::RESERVE_BALANCE,
+        key::TREASURY_SHARES,
+        key::AMO_SHARES,
+        key::AMO_SHARES_RETURNED,
+        key::AMO_DEPOSITS_RETURNED,
+        key::TOTAL_DEBT,
+        key::TOTAL_CREDIT,
+        key::PENDING_DEBT_PAYMENT,
+        key::TOTAL_COLLATERAL,
+    ] {
+        storage.remove(map.with(vault).as_bytes());
+    }
+}
+
+/// State migration recording each vault's total debt & credit from its existing positions,
+/// along with the debt payment pending in those positions
+pub fn backfill_debt_credit_totals(storage: &mut dyn Storage) -> Result<(), StdError> {
+    for i in 0..storage.vault_count() {
+        let vault: VaultId = storage
+            .vault_address(i)
+            .expect("always: vault address set for i where i < vault count")
+            .into();
+
+        let balance_sheet = BalanceSheet::new(storage);
+
+        let total_debt = accounts(storage, key::ACCOUNT_DEBT, &vault)
+            .iter()
+            .filter_map(|account| balance_sheet.account_debt(&vault, account))
+            .fold(0u128, u128::saturating_add);
+
+        let total_credit = accounts(storage, key::ACCOUNT_CREDIT, &vault)
+            .iter()
+            .filter_map(|account| balance_sheet.account_credit(&vault, account))
+            .fold(0u128, u128::saturating_add);
+
+        let vault_spr = balance_sheet
+            .overall_sum_payment_ratio(&vault)
+            .unwrap_or(SumPaymentRatio::zero());
+
+        let pending_payment = accounts(storage, key::ACCOUNT_COLLATERAL, &vault)
+            .iter()
+            .map(|account| {
+                sum_payment(
+                    balance_sheet
+                        .account_sum_payment_ratio(&vault, account)
+                        .unwrap_or(SumPaymentRatio::zero()),
+                    vault_spr,
+                    balance_sheet
+                        .account_collateral(&vault, account)
+                        .unwrap_or_default(),
+                    balance_sheet
+                        .account_collateral_factor(&vault, account)
+                        .unwrap_or_default(),
+                )
+            })
+            .fold(0u128, u128::saturating_add);
+
+        storage.set_u128(key::TOTAL_DEBT.with(&vault), total_debt);
+        storage.set_u128(key::TOTAL_CREDIT.with(&vault), total_credit);
+        storage.set_u128(key::PENDING_DEBT_PAYMENT.with(&vault), pending_payment);
+    }
+
+    Ok(())
+}
+
+/// State migration recording each vault's total collateral from its existing positions,
+/// for vaults registered before total collateral tracking began
+pub fn backfill_total_collateral(storage: &mut dyn Storage) -> Result<(), StdError> {
+    for i in 0..storage.vault_count() {
+        let vault: VaultId = storage
+            .vault_address(i)
+            .expect("always: vault address set for i where i < vault count")
+            .into();
+
+        let balance_sheet = BalanceSheet::new(storage);
+
+        let total_collateral = accounts(storage, key::ACCOUNT_COLLATERAL, &vault)
+            .iter()
+            .filter_map(|account| balance_sheet.account_collateral(&vault, account))
+            .fold(0u128, u128::saturating_add);
+
+        storage.set_u128(key::TOTAL_COLLATERAL.with(&vault), total_collateral);
+    }
+
+    Ok(())
 }
 
 impl<T> StorageExt for T where T: Storage + ?Sized {}
@@ -70,6 +246,14 @@ impl<'a> CoreBalanceSheet for BalanceSheet<'a> {
         self.0.string_at(key::TREASURY).map(Into::into)
     }
 
+    fn guardian(&self) -> Option<Guardian> {
+        self.0.string_at(key::GUARDIAN).map(Into::into)
+    }
+
+    fn global_paused(&self) -> bool {
+        self.0.bool_at(key::GLOBAL_PAUSED).unwrap_or_default()
+    }
+
     fn collateral_shares(&self, vault: &VaultId) -> Option<SharesAmount> {
         self.0
             .u128_at(key::COLLATERAL_SHARES.with(vault))
@@ -102,12 +286,40 @@ impl<'a> CoreBalanceSheet for BalanceSheet<'a> {
             .map(SharesAmount)
     }
 
+    fn amo_shares_returned(&self, vault: &VaultId) -> Option<SharesAmount> {
+        self.0
+            .u128_at(key::AMO_SHARES_RETURNED.with(vault))
+            .map(SharesAmount)
+    }
+
+    fn amo_deposits_returned(&self, vault: &VaultId) -> Option<DepositAmount> {
+        self.0
+            .u128_at(key::AMO_DEPOSITS_RETURNED.with(vault))
+            .map(DepositAmount)
+    }
+
     fn overall_sum_payment_ratio(&self, vault: &VaultId) -> Option<SumPaymentRatio> {
         self.0
             .u256_at(key::OVERALL_SUM_PAYMENT_RATIO.with(vault))
             .map(SumPaymentRatio::raw)
     }
 
+    fn collateral_factor(&self, vault: &VaultId) -> Option<CollateralFactor> {
+        self.0
+            .u256_at(key::COLLATERAL_FACTOR.with(vault))
+            .map(CollateralFactor::raw)
+    }
+
+    fn total_debt(&self, vault: &VaultId) -> Option<Debt> {
+        self.0.u128_at(key::TOTAL_DEBT.with(vault))?;
+
+        Some(StorageExt::total_debt(self.0, vault))
+    }
+
+    fn total_collateral(&self, vault: &VaultId) -> Option<Collateral> {
+        self.0.u128_at(key::TOTAL_COLLATERAL.with(vault))
+    }
+
     fn account_collateral(&self, vault: &VaultId, account: &Account) -> Option<Collateral> {
         self.0
             .u128_at(key::ACCOUNT_COLLATERAL.multi([vault, account]))
@@ -130,6 +342,44 @@ impl<'a> CoreBalanceSheet for BalanceSheet<'a> {
             .u256_at(key::ACCOUNT_SUM_PAYMENT_RATIO.multi([vault, account]))
             .map(SumPaymentRatio::raw)
     }
+
+    fn account_collateral_factor(
+        &self,
+        vault: &VaultId,
+        account: &Account,
+    ) -> Option<CollateralFactor> {
+        self.0
+            .u256_at(key::ACCOUNT_COLLATERAL_FACTOR.multi([vault, account]))
+            .map(CollateralFactor::raw)
+    }
+
+    fn account_position_seq(&self, vault: &VaultId, account: &Account) -> Option<PositionSeq> {
+        self.0
+            .u64_at(key::ACCOUNT_POSITION_SEQ.multi([vault, account]))
+    }
+
+    fn position_operator(&self, vault: &VaultId, account: &Account) -> Option<PositionOperator> {
+        let operator = self
+            .0
+            .string_at(key::POSITION_OPERATOR.multi([vault, account]))?;
+
+        Some(PositionOperator {
+            operator: operator.into(),
+            max_advance: self.0.u128_at(key::POSITION_OPERATOR.multi([
+                vault,
+                account,
+                &MAX_ADVANCE,
+            ])),
+            allow_withdraw: self
+                .0
+                .bool_at(key::POSITION_OPERATOR.multi([vault, account, &ALLOW_WITHDRAW]))
+                .unwrap_or_default(),
+            advanced: self
+                .0
+                .u128_at(key::POSITION_OPERATOR.multi([vault, account, &ADVANCED]))
+                .unwrap_or_default(),
+        })
+    }
 }
 
 pub fn handle_cmd<Msg>(
@@ -140,6 +390,10 @@ pub fn handle_cmd<Msg>(
     match cmd {
         BalanceSheetCmd::SetTreasury { treasury } => storage.set_string(key::TREASURY, &treasury),
 
+        BalanceSheetCmd::SetGuardian { guardian } => storage.set_string(key::GUARDIAN, &guardian),
+
+        BalanceSheetCmd::SetGlobalPause { paused } => storage.set_bool(key::GLOBAL_PAUSED, paused),
+
         BalanceSheetCmd::SetCollateralShares {
             vault,
             shares: SharesAmount(shares),
@@ -168,41 +422,160 @@ pub fn handle_cmd<Msg>(
             shares: SharesAmount(shares),
         } => storage.set_u128(key::AMO_SHARES.with(vault), shares),
 
-        BalanceSheetCmd::SetOverallSumPaymentRatio { vault, spr } => {
+        BalanceSheetCmd::SetAmoSharesReturned {
+            vault,
+            shares: SharesAmount(shares),
+        } => storage.set_u128(key::AMO_SHARES_RETURNED.with(vault), shares),
+
+        BalanceSheetCmd::SetAmoDepositsReturned {
+            vault,
+            amount: DepositAmount(amount),
+        } => storage.set_u128(key::AMO_DEPOSITS_RETURNED.with(vault), amount),
+
+        BalanceSheetCmd::SetOverallSumPaymentRatio {
+            vault,
+            spr,
+            payment,
+        } => {
+            let pending_payment = storage.pending_debt_payment(&vault).saturating_add(payment);
+
+            storage.set_u128(key::PENDING_DEBT_PAYMENT.with(&vault), pending_payment);
             storage.set_u256(key::OVERALL_SUM_PAYMENT_RATIO.with(&vault), spr.into_raw());
             storage.set_overall_spr_timestamp(&vault, env.block.time.seconds());
         }
 
+        BalanceSheetCmd::SetCollateralFactor { vault, factor } => {
+            storage.set_u256(key::COLLATERAL_FACTOR.with(vault), factor.into_raw())
+        }
+
+        BalanceSheetCmd::TrackTotalCollateral { vault } => {
+            storage.set_u128(key::TOTAL_COLLATERAL.with(vault), 0)
+        }
+
         BalanceSheetCmd::SetAccountCollateral {
             vault,
             account,
             collateral,
-        } => storage.set_u128(
-            key::ACCOUNT_COLLATERAL.multi([&vault, &account]),
-            collateral,
-        ),
+        } => {
+            let key = key::ACCOUNT_COLLATERAL.multi([&vault, &account]);
+
+            // only vaults registered since total tracking began have a complete total to maintain
+            if let Some(total) = storage.u128_at(key::TOTAL_COLLATERAL.with(&vault)) {
+                let prev = storage.u128_at(&key).unwrap_or_default();
+
+                let total = total.saturating_sub(prev) + collateral;
+
+                storage.set_u128(key::TOTAL_COLLATERAL.with(&vault), total);
+            }
+
+            storage.set_u128(key, collateral);
+        }
 
         BalanceSheetCmd::SetAccountDebt {
             vault,
             account,
             debt,
-        } => storage.set_u128(key::ACCOUNT_DEBT.multi([&vault, &account]), debt),
+        } => {
+            let key = key::ACCOUNT_DEBT.multi([&vault, &account]);
+
+            let prev = storage.u128_at(&key).unwrap_or_default();
+
+            // saturate: accounts recorded prior to total tracking are not included in the total
+            let total = storage
+                .u128_at(key::TOTAL_DEBT.with(&vault))
+                .unwrap_or_default()
+                .saturating_sub(prev)
+                + debt;
+
+            storage.set_u128(key::TOTAL_DEBT.with(&vault), total);
+            storage.set_u128(key, debt);
+        }
 
         BalanceSheetCmd::SetAccountCredit {
             vault,
             account,
             credit,
-        } => storage.set_u128(key::ACCOUNT_CREDIT.multi([&vault, &account]), credit),
+        } => {
+            let key = key::ACCOUNT_CREDIT.multi([&vault, &account]);
+
+            let prev = storage.u128_at(&key).unwrap_or_default();
+
+            // saturate: accounts recorded prior to total tracking are not included in the total
+            let total = storage
+                .u128_at(key::TOTAL_CREDIT.with(&vault))
+                .unwrap_or_default()
+                .saturating_sub(prev)
+                + credit;
+
+            storage.set_u128(key::TOTAL_CREDIT.with(&vault), total);
+            storage.set_u128(key, credit);
+        }
 
         BalanceSheetCmd::SetAccountSumPaymentRatio {
             vault,
             account,
             spr,
+            payment,
+        } => {
+            // saturate: rounding may settle marginally more than was recorded as pending
+            let pending_payment = storage.pending_debt_payment(&vault).saturating_sub(payment);
+
+            storage.set_u128(key::PENDING_DEBT_PAYMENT.with(&vault), pending_payment);
+            storage.set_u256(
+                key::ACCOUNT_SUM_PAYMENT_RATIO.multi([&vault, &account]),
+                spr.into_raw(),
+            );
+        }
+
+        BalanceSheetCmd::SetAccountCollateralFactor {
+            vault,
+            account,
+            factor,
         } => storage.set_u256(
-            key::ACCOUNT_SUM_PAYMENT_RATIO.multi([&vault, &account]),
-            spr.into_raw(),
+            key::ACCOUNT_COLLATERAL_FACTOR.multi([&vault, &account]),
+            factor.into_raw(),
         ),
 
+        BalanceSheetCmd::SetAccountPositionSeq {
+            vault,
+            account,
+            seq,
+        } => storage.set_u64(key::ACCOUNT_POSITION_SEQ.multi([&vault, &account]), seq),
+
+        BalanceSheetCmd::SetPositionOperator {
+            vault,
+            account,
+            operator,
+        } => {
+            let operator_key = key::POSITION_OPERATOR.multi([&vault, &account]);
+            let max_advance_key = key::POSITION_OPERATOR.multi([&vault, &account, &MAX_ADVANCE]);
+            let allow_withdraw_key =
+                key::POSITION_OPERATOR.multi([&vault, &account, &ALLOW_WITHDRAW]);
+            let advanced_key = key::POSITION_OPERATOR.multi([&vault, &account, &ADVANCED]);
+
+            let Some(operator) = operator else {
+                for key in [
+                    operator_key,
+                    max_advance_key,
+                    allow_withdraw_key,
+                    advanced_key,
+                ] {
+                    storage.remove(key.as_bytes());
+                }
+
+                return None;
+            };
+
+            storage.set_string(&operator_key, &operator.operator);
+            storage.set_bool(&allow_withdraw_key, operator.allow_withdraw);
+            storage.set_u128(&advanced_key, operator.advanced);
+
+            match operator.max_advance {
+                Some(max_advance) => storage.set_u128(&max_advance_key, max_advance),
+                None => storage.remove(max_advance_key.as_bytes()),
+            }
+        }
+
         BalanceSheetCmd::SendShares {
             shares,
             amount: SharesAmount(amount),
@@ -1,32 +1,93 @@
+use std::{cell::RefCell, collections::BTreeMap};
+
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    coins, to_json_binary, CustomQuery, QuerierWrapper, StdError, Storage, SubMsg, WasmMsg,
+    coins, to_json_binary, CustomQuery, Decimal, Order, QuerierWrapper, StdError, Storage, SubMsg,
+    WasmMsg,
 };
 
 use amulet_core::{
     hub::{
-        AdvanceFee, Amo, AmoAllocation, CollateralYieldFee, MaxLtv, Oracle, Proxy, ReserveYieldFee,
-        VaultCmd, VaultDepositReason, VaultId, VaultRegistry as CoreVaultRegistry,
+        AdvanceFee, Amo, AmoAllocation, CacheWindowSecs, CachedAdvanceFee, Collateral,
+        CollateralPrice, CollateralPriceOracle, CollateralYieldFee, Debt, LossTolerance, MaxLtv,
+        Oracle, OracleEpoch, Proxy, RedeemFeeSchedule, ReserveYieldFee, VaultCmd,
+        VaultDepositReason, VaultId, VaultInterface, VaultRegistry as CoreVaultRegistry,
     },
     mint::Synthetic,
     vault::{DepositAmount, SharesAmount, TotalDepositsValue, TotalSharesIssued},
     Asset, Decimals, Recipient,
 };
+use num::FixedU256;
 
 use crate::{
+    hub::balance_sheet,
+    reentrancy,
     vault::{
-        DepositAssetResponse, ExecuteMsg as VaultExecuteMsg, QueryMsg as VaultQueryMsg,
-        SharesAssetResponse, StateResponse, UnderlyingAssetDecimalsResponse,
+        accepted_assets::{ConversionOracleQueryMsg, ConversionRate, ConversionRateResponse},
+        AcceptedDepositAssetsResponse, DepositAssetResponse, ExecuteMsg as VaultExecuteMsg,
+        InterfaceVersionResponse, QueryMsg as VaultQueryMsg, SharesAssetResponse,
+        SharesDecimalsResponse, StateResponse, UnderlyingAssetDecimalsResponse,
     },
     StorageExt as _,
 };
 
+// The kinds of vault deposit callback, recorded in the callback context rather than the reply ID,
+// which is allocated per callback so that several deposits may be pending in a single transaction
 pub const DEPOSIT_REPLY_ID: u64 = VaultDepositReason::Deposit as u64;
 pub const REPAY_UNDERLYING_REPLY_ID: u64 = VaultDepositReason::RepayUnderlying as u64;
 pub const MINT_REPLY_ID: u64 = VaultDepositReason::Mint as u64;
+pub const AMO_PROCEEDS_REPLY_ID: u64 = VaultDepositReason::AmoProceeds as u64;
+
+/// The context of a pending vault deposit, handled on reply
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultCallback {
+    pub vault: String,
+    pub recipient: String,
+    pub reason: VaultDepositReason,
+}
+
+fn callback_kind(reason: VaultDepositReason) -> u64 {
+    match reason {
+        VaultDepositReason::Deposit => DEPOSIT_REPLY_ID,
+        VaultDepositReason::RepayUnderlying => REPAY_UNDERLYING_REPLY_ID,
+        VaultDepositReason::Mint => MINT_REPLY_ID,
+        VaultDepositReason::AmoProceeds => AMO_PROCEEDS_REPLY_ID,
+    }
+}
+
+fn callback_reason(kind: u64) -> VaultDepositReason {
+    match kind {
+        DEPOSIT_REPLY_ID => VaultDepositReason::Deposit,
+        REPAY_UNDERLYING_REPLY_ID => VaultDepositReason::RepayUnderlying,
+        MINT_REPLY_ID => VaultDepositReason::Mint,
+        AMO_PROCEEDS_REPLY_ID => VaultDepositReason::AmoProceeds,
+        _ => panic!("unexpected vault callback kind: {kind}"),
+    }
+}
+
+/// Returns true if the query error is a vault failing to parse a query variant it does not support
+fn is_unsupported_query(err: &StdError) -> bool {
+    matches!(err, StdError::GenericErr { msg, .. } if msg.contains("unknown variant"))
+}
+
+#[cw_serde]
+pub enum CollateralPriceQueryMsg {
+    CollateralPrice {},
+}
+
+#[cw_serde]
+pub struct CollateralPriceResponse {
+    /// The value of the collateral relative to its redemption value, e.g. 0.95 at a 5% discount
+    pub price: Decimal,
+    /// The time the price was last updated in seconds
+    pub updated_at: u64,
+}
 
 pub struct VaultRegistry<'a> {
     storage: &'a dyn Storage,
     querier: QuerierWrapper<'a>,
+    // a vault's state cannot change while an entry point is being handled, so it is queried once
+    states: RefCell<BTreeMap<String, StateResponse>>,
 }
 
 impl<'a> VaultRegistry<'a> {
@@ -34,8 +95,26 @@ impl<'a> VaultRegistry<'a> {
         Self {
             storage,
             querier: querier.into_empty(),
+            states: RefCell::default(),
         }
     }
+
+    /// The vault's state, only queried the first time it is requested
+    pub fn vault_state(&self, vault: &VaultId) -> Result<StateResponse, StdError> {
+        if let Some(state) = self.states.borrow().get(vault.as_str()) {
+            return Ok(state.clone());
+        }
+
+        let state: StateResponse = self
+            .querier
+            .query_wasm_smart(vault.as_str(), &VaultQueryMsg::State {})?;
+
+        self.states
+            .borrow_mut()
+            .insert(vault.as_str().to_owned(), state.clone());
+
+        Ok(state)
+    }
 }
 
 #[rustfmt::skip]
@@ -63,16 +142,38 @@ mod key {
     pub const FIXED_ADVANCE_FEE        : MapKey = map_key!("fixed_advance_fee");
     pub const ADVANCE_FEE_RECIPIENT    : MapKey = map_key!("advance_fee_recipient");
     pub const ADVANCE_FEE_ORACLE       : MapKey = map_key!("advance_fee_oracle");
+    pub const ADVANCE_FEE_ORACLE_EPOCH : MapKey = map_key!("advance_fee_oracle_epoch");
+    pub const ORACLE_FEE_CACHE_SECS    : MapKey = map_key!("oracle_fee_cache_secs");
+    pub const CACHED_ORACLE_FEE        : MapKey = map_key!("cached_oracle_fee");
+    pub const CACHED_ORACLE_FEE_EPOCH  : MapKey = map_key!("cached_oracle_fee_epoch");
+    pub const CACHED_ORACLE_FEE_TIME   : MapKey = map_key!("cached_oracle_fee_time");
+    pub const COLLATERAL_PRICE_ORACLE  : MapKey = map_key!("collateral_price_oracle");
+    pub const COLLATERAL_PRICE_MAX_AGE : MapKey = map_key!("collateral_price_max_age");
     pub const AMO                      : MapKey = map_key!("amo");
     pub const AMO_ALLOCATION           : MapKey = map_key!("amo_allocation");
     pub const DEPOSIT_PROXY            : MapKey = map_key!("deposit_proxy");
     pub const ADVANCE_PROXY            : MapKey = map_key!("advance_proxy");
     pub const REDEEM_PROXY             : MapKey = map_key!("redeem_proxy");
     pub const MINT_PROXY               : MapKey = map_key!("mint_proxy");
+    pub const MIN_COLLATERAL           : MapKey = map_key!("min_collateral");
+    pub const MIN_DEBT                 : MapKey = map_key!("min_debt");
+    pub const ACCOUNT_DEBT_CAP         : MapKey = map_key!("account_debt_cap");
+    pub const RESERVE_FLOOR            : MapKey = map_key!("reserve_floor");
+    pub const LOSS_TOLERANCE           : MapKey = map_key!("loss_tolerance");
+    pub const LOSS_SOCIALISATION       : MapKey = map_key!("loss_socialisation");
+    pub const MIN_DEPOSIT              : MapKey = map_key!("min_deposit");
     pub const VAULT_ADDRESS            : MapKey = map_key!("vault_address");
+    pub const INTERFACE_VERSION        : MapKey = map_key!("interface_version");
+    pub const INTERFACE_FEATURES       : MapKey = map_key!("interface_features");
+    pub const REDEEM_FEE_TIER_COUNT    : MapKey = map_key!("redeem_fee_tier_count");
+    pub const REDEEM_FEE_TIER_THRESHOLD: MapKey = map_key!("redeem_fee_tier_threshold");
+    pub const REDEEM_FEE_TIER_FEE      : MapKey = map_key!("redeem_fee_tier_fee");
     pub const VAULT_COUNT              : &str   = key!("vault_count");
-    pub const VAULT_CALLBACK_VAULT     : &str   = key!("vault_callback_vault");
-    pub const VAULT_CALLBACK_RECIPIENT : &str   = key!("vault_callback_recipient");
+    pub const VAULT_CALLBACK_COUNT     : &str   = key!("vault_callback_count");
+    pub const VAULT_CALLBACK_PENDING   : &str   = key!("vault_callback_pending");
+    pub const VAULT_CALLBACK_VAULT     : MapKey = map_key!("vault_callback_vault");
+    pub const VAULT_CALLBACK_RECIPIENT : MapKey = map_key!("vault_callback_recipient");
+    pub const VAULT_CALLBACK_KIND      : MapKey = map_key!("vault_callback_kind");
 }
 
 pub trait StorageExt: Storage {
@@ -84,14 +185,45 @@ pub trait StorageExt: Storage {
         self.string_at(key::VAULT_ADDRESS.with(index))
     }
 
-    fn vault_callback_vault(&self) -> String {
-        self.string_at(key::VAULT_CALLBACK_VAULT)
-            .expect("always: set before vault msg issued")
+    fn vault_interface_version(&self, vault: &str) -> Option<String> {
+        self.string_at(key::INTERFACE_VERSION.with(vault))
+    }
+
+    /// The optional interface features reported by the vault at registration
+    fn vault_interface_features(&self, vault: &str) -> Vec<String> {
+        self.string_at(key::INTERFACE_FEATURES.with(vault))
+            .map(|features| {
+                features
+                    .split(',')
+                    .filter(|feature| !feature.is_empty())
+                    .map(str::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The number of vault callbacks ever issued, the ID of the latest
+    fn vault_callback_count(&self) -> u64 {
+        self.u64_at(key::VAULT_CALLBACK_COUNT).unwrap_or_default()
+    }
+
+    /// The number of vault callbacks awaiting a reply
+    fn pending_vault_callback_count(&self) -> u64 {
+        self.u64_at(key::VAULT_CALLBACK_PENDING).unwrap_or_default()
     }
 
-    fn vault_callback_recipient(&self) -> String {
-        self.string_at(key::VAULT_CALLBACK_RECIPIENT)
-            .expect("always: set before vault msg issued")
+    fn vault_callback(&self, id: u64) -> Option<VaultCallback> {
+        let kind = self.u64_at(key::VAULT_CALLBACK_KIND.with(id))?;
+
+        Some(VaultCallback {
+            vault: self
+                .string_at(key::VAULT_CALLBACK_VAULT.with(id))
+                .expect("always: set with the callback kind"),
+            recipient: self
+                .string_at(key::VAULT_CALLBACK_RECIPIENT.with(id))
+                .expect("always: set with the callback kind"),
+            reason: callback_reason(kind),
+        })
     }
 
     fn add_vault_address(&mut self, address: &str) {
@@ -102,12 +234,62 @@ pub trait StorageExt: Storage {
         self.set_u32(key::VAULT_COUNT, count + 1);
     }
 
-    fn set_vault_callback_vault(&mut self, vault: &str) {
-        self.set_string(key::VAULT_CALLBACK_VAULT, vault)
+    /// Remove the vault address, moving the last address into its slot
+    fn remove_vault_address(&mut self, address: &str) {
+        let count = self.vault_count();
+
+        let Some(index) = (0..count).find(|&i| self.vault_address(i).as_deref() == Some(address))
+        else {
+            return;
+        };
+
+        let last = count - 1;
+
+        if index != last {
+            let last_address = self
+                .vault_address(last)
+                .expect("always: vault address set for i where i < vault count");
+
+            self.set_string(key::VAULT_ADDRESS.with(index), &last_address);
+        }
+
+        self.remove(key::VAULT_ADDRESS.with(last).as_bytes());
+
+        self.set_u32(key::VAULT_COUNT, last);
+    }
+
+    /// Record the callback context under a newly allocated reply ID, which is returned
+    fn push_vault_callback(&mut self, callback: &VaultCallback) -> u64 {
+        let id = self.vault_callback_count() + 1;
+
+        self.set_u64(key::VAULT_CALLBACK_COUNT, id);
+        self.set_string(key::VAULT_CALLBACK_VAULT.with(id), &callback.vault);
+        self.set_string(key::VAULT_CALLBACK_RECIPIENT.with(id), &callback.recipient);
+        self.set_u64(
+            key::VAULT_CALLBACK_KIND.with(id),
+            callback_kind(callback.reason),
+        );
+
+        let pending = self.pending_vault_callback_count();
+
+        self.set_u64(key::VAULT_CALLBACK_PENDING, pending + 1);
+
+        id
     }
 
-    fn set_vault_callback_recipient(&mut self, recipient: &str) {
-        self.set_string(key::VAULT_CALLBACK_RECIPIENT, recipient)
+    /// Remove the callback context once its reply has been handled
+    fn remove_vault_callback(&mut self, id: u64) {
+        if self.u64_at(key::VAULT_CALLBACK_KIND.with(id)).is_none() {
+            return;
+        }
+
+        self.remove(key::VAULT_CALLBACK_VAULT.with(id).as_bytes());
+        self.remove(key::VAULT_CALLBACK_RECIPIENT.with(id).as_bytes());
+        self.remove(key::VAULT_CALLBACK_KIND.with(id).as_bytes());
+
+        let pending = self.pending_vault_callback_count();
+
+        self.set_u64(key::VAULT_CALLBACK_PENDING, pending.saturating_sub(1));
     }
 }
 
@@ -127,6 +309,37 @@ impl<'a> CoreVaultRegistry for VaultRegistry<'a> {
         Some(response.decimals)
     }
 
+    fn shares_decimals(&self, vault: &VaultId) -> Option<Decimals> {
+        let response: SharesDecimalsResponse = self
+            .querier
+            .query_wasm_smart(vault.clone(), &VaultQueryMsg::SharesDecimals {})
+            .map_err(|err| match err {
+                err @ StdError::NotFound { .. } => err,
+                err => panic!("{vault}: {err}"),
+            })
+            .ok()?;
+
+        Some(response.decimals)
+    }
+
+    fn interface(&self, vault: &VaultId) -> Option<VaultInterface> {
+        let response: InterfaceVersionResponse = self
+            .querier
+            .query_wasm_smart(vault.clone(), &VaultQueryMsg::InterfaceVersion {})
+            .map_err(|err| match err {
+                err @ StdError::NotFound { .. } => err,
+                // vaults predating interface versioning fail to parse the query
+                err if is_unsupported_query(&err) => err,
+                err => panic!("{vault}: {err}"),
+            })
+            .ok()?;
+
+        Some(VaultInterface {
+            version: response.version,
+            features: response.features,
+        })
+    }
+
     fn is_registered(&self, vault: &VaultId) -> bool {
         self.storage
             .get(key::SYNTHETICS.with(vault).as_bytes())
@@ -181,6 +394,80 @@ impl<'a> CoreVaultRegistry for VaultRegistry<'a> {
             .map(Oracle::from)
     }
 
+    fn advance_fee_oracle_epoch(&self, vault: &VaultId) -> OracleEpoch {
+        self.storage
+            .u64_at(key::ADVANCE_FEE_ORACLE_EPOCH.with(vault))
+            .unwrap_or_default()
+    }
+
+    fn oracle_fee_cache_secs(&self, vault: &VaultId) -> Option<CacheWindowSecs> {
+        self.storage.u64_at(key::ORACLE_FEE_CACHE_SECS.with(vault))
+    }
+
+    fn cached_oracle_fee(
+        &self,
+        vault: &VaultId,
+        recipient: &Recipient,
+    ) -> Option<CachedAdvanceFee> {
+        let timestamp = self
+            .storage
+            .u64_at(key::CACHED_ORACLE_FEE_TIME.multi([vault, recipient]))?;
+
+        let oracle_epoch = self
+            .storage
+            .u64_at(key::CACHED_ORACLE_FEE_EPOCH.multi([vault, recipient]))
+            .expect("always: set alongside the cached oracle fee timestamp");
+
+        let fee = self
+            .storage
+            .u32_at(key::CACHED_ORACLE_FEE.multi([vault, recipient]))
+            .and_then(AdvanceFee::new);
+
+        Some(CachedAdvanceFee {
+            fee,
+            oracle_epoch,
+            timestamp,
+        })
+    }
+
+    fn cached_oracle_fee_recipients(
+        &self,
+        vault: &VaultId,
+        start_after: Option<&Recipient>,
+        limit: usize,
+    ) -> Vec<Recipient> {
+        let prefix = key::CACHED_ORACLE_FEE_TIME.with(vault);
+
+        // keys are ':' terminated, so the next key after `start_after` is its key followed by a null byte
+        let start = match start_after {
+            Some(recipient) => {
+                let mut start = key::CACHED_ORACLE_FEE_TIME
+                    .multi([vault, recipient])
+                    .into_bytes();
+                start.push(0);
+                start
+            }
+            None => prefix.clone().into_bytes(),
+        };
+
+        // the first key after every key with the prefix, replacing its ':' terminator with ';'
+        let mut end = prefix.clone().into_bytes();
+        *end.last_mut().expect("always: keys are ':' terminated") = b';';
+
+        self.storage
+            .range_keys(Some(&start), Some(&end), Order::Ascending)
+            .take(limit)
+            .map(|key| {
+                std::str::from_utf8(&key[prefix.len()..])
+                    .expect("always: keys are valid utf-8")
+                    .strip_suffix(':')
+                    .expect("always: map keys are ':' terminated")
+                    .to_owned()
+                    .into()
+            })
+            .collect()
+    }
+
     fn amo(&self, vault: &VaultId) -> Option<Amo> {
         self.storage.string_at(key::AMO.with(vault)).map(Amo::from)
     }
@@ -215,6 +502,64 @@ impl<'a> CoreVaultRegistry for VaultRegistry<'a> {
             .map(Proxy::from)
     }
 
+    fn min_collateral(&self, vault: &VaultId) -> Option<Collateral> {
+        self.storage.u128_at(key::MIN_COLLATERAL.with(vault))
+    }
+
+    fn min_debt(&self, vault: &VaultId) -> Option<Debt> {
+        self.storage.u128_at(key::MIN_DEBT.with(vault))
+    }
+
+    fn account_debt_cap(&self, vault: &VaultId) -> Option<Debt> {
+        self.storage.u128_at(key::ACCOUNT_DEBT_CAP.with(vault))
+    }
+
+    fn reserve_floor(&self, vault: &VaultId) -> Option<Collateral> {
+        self.storage.u128_at(key::RESERVE_FLOOR.with(vault))
+    }
+
+    fn loss_tolerance(&self, vault: &VaultId) -> Option<LossTolerance> {
+        self.storage
+            .u32_at(key::LOSS_TOLERANCE.with(vault))
+            .and_then(LossTolerance::new)
+    }
+
+    fn loss_socialisation_enabled(&self, vault: &VaultId) -> bool {
+        self.storage
+            .bool_at(key::LOSS_SOCIALISATION.with(vault))
+            .unwrap_or_default()
+    }
+
+    fn min_deposit(&self, vault: &VaultId) -> Option<u128> {
+        self.storage.u128_at(key::MIN_DEPOSIT.with(vault))
+    }
+
+    fn redeem_fee_schedule(&self, vault: &VaultId) -> Option<RedeemFeeSchedule> {
+        let tier_count = self
+            .storage
+            .u32_at(key::REDEEM_FEE_TIER_COUNT.with(vault))?;
+
+        let tiers: Vec<_> = (0..tier_count)
+            .map(|idx| {
+                let threshold = self
+                    .storage
+                    .u32_at(key::REDEEM_FEE_TIER_THRESHOLD.multi([vault, &idx]))
+                    .expect("always: stored with tier count");
+
+                let fee = self
+                    .storage
+                    .u32_at(key::REDEEM_FEE_TIER_FEE.multi([vault, &idx]))
+                    .expect("always: stored with tier count");
+
+                (threshold, fee)
+            })
+            .collect();
+
+        let schedule = RedeemFeeSchedule::new(&tiers).expect("always: validated before storing");
+
+        Some(schedule)
+    }
+
     fn deposit_asset(&self, vault: &VaultId) -> Asset {
         let response: DepositAssetResponse = match self
             .querier
@@ -227,6 +572,50 @@ impl<'a> CoreVaultRegistry for VaultRegistry<'a> {
         response.denom.into()
     }
 
+    fn convert_secondary_deposit(
+        &self,
+        vault: &VaultId,
+        asset: &Asset,
+        DepositAmount(amount): DepositAmount,
+    ) -> Option<DepositAmount> {
+        // vaults that predate secondary deposit assets do not support the query, so only accept their deposit asset
+        let Ok(response) = self
+            .querier
+            .query_wasm_smart::<AcceptedDepositAssetsResponse>(
+                vault.clone(),
+                &VaultQueryMsg::AcceptedDepositAssets {},
+            )
+        else {
+            return None;
+        };
+
+        let accepted = response
+            .accepted
+            .into_iter()
+            .find(|accepted| accepted.denom == asset.as_str())?;
+
+        let rate = match self.querier.query_wasm_smart::<ConversionRateResponse>(
+            &accepted.conversion,
+            &ConversionOracleQueryMsg::ConversionRate {
+                denom: accepted.denom.clone(),
+            },
+        ) {
+            Ok(ConversionRateResponse { rate }) => rate,
+            Err(err) => panic!(
+                "conversion rate query failed: {err} - {}",
+                accepted.conversion
+            ),
+        };
+
+        let converted = ConversionRate {
+            denom: accepted.denom,
+            rate,
+        }
+        .to_primary(amount);
+
+        Some(DepositAmount(converted))
+    }
+
     fn shares_asset(&self, vault: &VaultId) -> Asset {
         let response: SharesAssetResponse = match self
             .querier
@@ -247,10 +636,7 @@ impl<'a> CoreVaultRegistry for VaultRegistry<'a> {
     }
 
     fn total_shares_issued(&self, vault: &VaultId) -> TotalSharesIssued {
-        let response: StateResponse = match self
-            .querier
-            .query_wasm_smart(vault.clone(), &VaultQueryMsg::State {})
-        {
+        let response = match self.vault_state(vault) {
             Ok(response) => response,
             Err(err) => panic!("state query failed: {err} - {vault}"),
         };
@@ -259,16 +645,60 @@ impl<'a> CoreVaultRegistry for VaultRegistry<'a> {
     }
 
     fn total_deposits_value(&self, vault: &VaultId) -> TotalDepositsValue {
-        let response: StateResponse = match self
-            .querier
-            .query_wasm_smart(vault.clone(), &VaultQueryMsg::State {})
-        {
+        let response = match self.vault_state(vault) {
             Ok(response) => response,
             Err(err) => panic!("state query failed: {err} - {vault}"),
         };
 
         TotalDepositsValue(response.total_deposits.u128())
     }
+
+    fn strategy_redemption_rate(&self, vault: &VaultId) -> Option<FixedU256> {
+        let response = match self.vault_state(vault) {
+            Ok(response) => response,
+            Err(err) => panic!("state query failed: {err} - {vault}"),
+        };
+
+        let redemption_value = response.redemption_value?;
+
+        FixedU256::from_u128(redemption_value.u128())
+            .checked_div(FixedU256::from_u128(response.total_deposits.u128()))
+    }
+
+    fn collateral_price_oracle(&self, vault: &VaultId) -> Option<CollateralPriceOracle> {
+        let oracle = self
+            .storage
+            .string_at(key::COLLATERAL_PRICE_ORACLE.with(vault))?;
+
+        let max_age_secs = self
+            .storage
+            .u64_at(key::COLLATERAL_PRICE_MAX_AGE.with(vault))
+            .expect("always: set alongside the collateral price oracle");
+
+        Some(CollateralPriceOracle {
+            oracle: oracle.into(),
+            max_age_secs,
+        })
+    }
+
+    fn collateral_price(&self, oracle: &Oracle) -> CollateralPrice {
+        let response: CollateralPriceResponse = match self
+            .querier
+            .query_wasm_smart(oracle.clone(), &CollateralPriceQueryMsg::CollateralPrice {})
+        {
+            Ok(response) => response,
+            Err(err) => panic!("collateral price query failed: {err} - {oracle}"),
+        };
+
+        let price = FixedU256::from_u128(response.price.atomics().u128())
+            .checked_div(FixedU256::from_u128(10u128.pow(Decimal::DECIMAL_PLACES)))
+            .expect("always: non-zero divisor");
+
+        CollateralPrice {
+            price,
+            updated_at: response.updated_at,
+        }
+    }
 }
 
 pub fn handle_cmd<Msg>(storage: &mut dyn Storage, cmd: VaultCmd) -> Option<SubMsg<Msg>> {
@@ -278,6 +708,62 @@ pub fn handle_cmd<Msg>(storage: &mut dyn Storage, cmd: VaultCmd) -> Option<SubMs
             storage.set_string(key::SYNTHETICS.with(vault), &synthetic);
         }
 
+        VaultCmd::Deregister { vault } => {
+            storage.remove_vault_address(&vault);
+
+            // a re-registered vault starts from the default configuration, as when first registered
+            for map in [
+                key::SYNTHETICS,
+                key::DEPOSITS_ENABLED,
+                key::ADVANCE_ENABLED,
+                key::MAX_LTV,
+                key::COLLATERAL_YIELD_FEE,
+                key::RESERVE_YIELD_FEE,
+                key::FIXED_ADVANCE_FEE,
+                key::ADVANCE_FEE_RECIPIENT,
+                key::ADVANCE_FEE_ORACLE,
+                key::ORACLE_FEE_CACHE_SECS,
+                key::COLLATERAL_PRICE_ORACLE,
+                key::COLLATERAL_PRICE_MAX_AGE,
+                key::AMO,
+                key::AMO_ALLOCATION,
+                key::DEPOSIT_PROXY,
+                key::ADVANCE_PROXY,
+                key::REDEEM_PROXY,
+                key::MINT_PROXY,
+                key::MIN_COLLATERAL,
+                key::MIN_DEBT,
+                key::ACCOUNT_DEBT_CAP,
+                key::RESERVE_FLOOR,
+                key::LOSS_TOLERANCE,
+                key::LOSS_SOCIALISATION,
+                key::MIN_DEPOSIT,
+                key::INTERFACE_VERSION,
+                key::INTERFACE_FEATURES,
+            ] {
+                storage.remove(map.with(&vault).as_bytes());
+            }
+
+            let tier_count = storage
+                .u32_at(key::REDEEM_FEE_TIER_COUNT.with(&vault))
+                .unwrap_or_default();
+
+            for idx in 0..tier_count {
+                storage.remove(
+                    key::REDEEM_FEE_TIER_THRESHOLD
+                        .multi([&vault, &idx])
+                        .as_bytes(),
+                );
+                storage.remove(key::REDEEM_FEE_TIER_FEE.multi([&vault, &idx]).as_bytes());
+            }
+
+            storage.remove(key::REDEEM_FEE_TIER_COUNT.with(&vault).as_bytes());
+
+            // the oracle epoch & cached oracle fees are kept, the cached fees were invalidated by bumping the epoch
+
+            balance_sheet::remove_vault(storage, &vault);
+        }
+
         VaultCmd::SetDepositsEnabled { vault, enabled } => {
             storage.set_bool(key::DEPOSITS_ENABLED.with(vault), enabled);
         }
@@ -310,6 +796,74 @@ pub fn handle_cmd<Msg>(storage: &mut dyn Storage, cmd: VaultCmd) -> Option<SubMs
             storage.set_string(key::ADVANCE_FEE_ORACLE.with(vault), &oracle);
         }
 
+        VaultCmd::SetAdvanceFeeOracleEpoch { vault, epoch } => {
+            storage.set_u64(key::ADVANCE_FEE_ORACLE_EPOCH.with(vault), epoch);
+        }
+
+        VaultCmd::SetOracleFeeCacheSecs { vault, secs } => {
+            if let Some(secs) = secs {
+                storage.set_u64(key::ORACLE_FEE_CACHE_SECS.with(vault), secs);
+            } else {
+                storage.remove(key::ORACLE_FEE_CACHE_SECS.with(vault).as_bytes())
+            }
+        }
+
+        VaultCmd::SetCollateralPriceOracle { vault, oracle } => {
+            if let Some(CollateralPriceOracle {
+                oracle,
+                max_age_secs,
+            }) = oracle
+            {
+                storage.set_string(key::COLLATERAL_PRICE_ORACLE.with(&vault), &oracle);
+                storage.set_u64(key::COLLATERAL_PRICE_MAX_AGE.with(vault), max_age_secs);
+            } else {
+                storage.remove(key::COLLATERAL_PRICE_ORACLE.with(&vault).as_bytes());
+                storage.remove(key::COLLATERAL_PRICE_MAX_AGE.with(vault).as_bytes());
+            }
+        }
+
+        VaultCmd::SetCachedOracleFee {
+            vault,
+            recipient,
+            cached,
+        } => {
+            let fee_key = key::CACHED_ORACLE_FEE.multi([&vault, &recipient]);
+
+            if let Some(fee) = cached.fee {
+                storage.set_u32(&fee_key, fee.raw());
+            } else {
+                storage.remove(fee_key.as_bytes())
+            }
+
+            storage.set_u64(
+                key::CACHED_ORACLE_FEE_EPOCH.multi([&vault, &recipient]),
+                cached.oracle_epoch,
+            );
+
+            storage.set_u64(
+                key::CACHED_ORACLE_FEE_TIME.multi([&vault, &recipient]),
+                cached.timestamp,
+            );
+        }
+
+        VaultCmd::RemoveCachedOracleFee { vault, recipient } => {
+            storage.remove(
+                key::CACHED_ORACLE_FEE
+                    .multi([&vault, &recipient])
+                    .as_bytes(),
+            );
+            storage.remove(
+                key::CACHED_ORACLE_FEE_EPOCH
+                    .multi([&vault, &recipient])
+                    .as_bytes(),
+            );
+            storage.remove(
+                key::CACHED_ORACLE_FEE_TIME
+                    .multi([&vault, &recipient])
+                    .as_bytes(),
+            );
+        }
+
         VaultCmd::SetAmo { vault, amo } => {
             storage.set_string(key::AMO.with(vault), &amo);
         }
@@ -350,6 +904,95 @@ pub fn handle_cmd<Msg>(storage: &mut dyn Storage, cmd: VaultCmd) -> Option<SubMs
             }
         }
 
+        VaultCmd::SetMinCollateral { vault, min } => {
+            if let Some(min) = min {
+                storage.set_u128(key::MIN_COLLATERAL.with(vault), min);
+            } else {
+                storage.remove(key::MIN_COLLATERAL.with(vault).as_bytes())
+            }
+        }
+
+        VaultCmd::SetMinDebt { vault, min } => {
+            if let Some(min) = min {
+                storage.set_u128(key::MIN_DEBT.with(vault), min);
+            } else {
+                storage.remove(key::MIN_DEBT.with(vault).as_bytes())
+            }
+        }
+
+        VaultCmd::SetAccountDebtCap { vault, cap } => {
+            if let Some(cap) = cap {
+                storage.set_u128(key::ACCOUNT_DEBT_CAP.with(vault), cap);
+            } else {
+                storage.remove(key::ACCOUNT_DEBT_CAP.with(vault).as_bytes())
+            }
+        }
+
+        VaultCmd::SetReserveFloor { vault, floor } => {
+            if let Some(floor) = floor {
+                storage.set_u128(key::RESERVE_FLOOR.with(vault), floor);
+            } else {
+                storage.remove(key::RESERVE_FLOOR.with(vault).as_bytes())
+            }
+        }
+
+        VaultCmd::SetLossTolerance { vault, tolerance } => {
+            storage.set_u32(key::LOSS_TOLERANCE.with(vault), tolerance.raw());
+        }
+
+        VaultCmd::SetLossSocialisationEnabled { vault, enabled } => {
+            storage.set_bool(key::LOSS_SOCIALISATION.with(vault), enabled);
+        }
+
+        VaultCmd::SetMinDeposit { vault, amount } => {
+            storage.set_u128(key::MIN_DEPOSIT.with(vault), amount);
+        }
+
+        VaultCmd::SetRedeemFeeSchedule { vault, schedule } => {
+            let prev_tier_count = storage
+                .u32_at(key::REDEEM_FEE_TIER_COUNT.with(&vault))
+                .unwrap_or_default();
+
+            for idx in 0..prev_tier_count {
+                storage.remove(
+                    key::REDEEM_FEE_TIER_THRESHOLD
+                        .multi([&vault, &idx])
+                        .as_bytes(),
+                );
+                storage.remove(key::REDEEM_FEE_TIER_FEE.multi([&vault, &idx]).as_bytes());
+            }
+
+            let Some(schedule) = schedule else {
+                storage.remove(key::REDEEM_FEE_TIER_COUNT.with(&vault).as_bytes());
+                return None;
+            };
+
+            for (idx, tier) in (0u32..).zip(schedule.tiers()) {
+                storage.set_u32(
+                    key::REDEEM_FEE_TIER_THRESHOLD.multi([&vault, &idx]),
+                    tier.threshold_bps(),
+                );
+                storage.set_u32(
+                    key::REDEEM_FEE_TIER_FEE.multi([&vault, &idx]),
+                    tier.fee().raw(),
+                );
+            }
+
+            storage.set_u32(
+                key::REDEEM_FEE_TIER_COUNT.with(&vault),
+                schedule.tiers().len() as u32,
+            );
+        }
+
+        VaultCmd::SetInterfaceVersion {
+            vault,
+            version,
+            features,
+        } => {
+            storage.set_string(key::INTERFACE_VERSION.with(&vault), &version);
+            storage.set_string(key::INTERFACE_FEATURES.with(vault), &features.join(","));
+        }
+
         VaultCmd::Deposit {
             vault,
             asset,
@@ -357,9 +1000,15 @@ pub fn handle_cmd<Msg>(storage: &mut dyn Storage, cmd: VaultCmd) -> Option<SubMs
             callback_recipient,
             callback_reason,
         } => {
-            // cache callback details
-            storage.set_vault_callback_vault(&vault);
-            storage.set_vault_callback_recipient(&callback_recipient);
+            // cache callback details under a reply ID unique to this deposit
+            let reply_id = storage.push_vault_callback(&VaultCallback {
+                vault: vault.as_str().to_owned(),
+                recipient: callback_recipient.as_str().to_owned(),
+                reason: callback_reason,
+            });
+
+            // the vault may not re-enter the hub before the callback has been handled
+            reentrancy::lock(storage);
 
             let msg = WasmMsg::Execute {
                 contract_addr: vault.into_string(),
@@ -368,12 +1017,6 @@ pub fn handle_cmd<Msg>(storage: &mut dyn Storage, cmd: VaultCmd) -> Option<SubMs
                 funds: coins(amount, asset),
             };
 
-            let reply_id = match callback_reason {
-                VaultDepositReason::Deposit => DEPOSIT_REPLY_ID,
-                VaultDepositReason::RepayUnderlying => REPAY_UNDERLYING_REPLY_ID,
-                VaultDepositReason::Mint => MINT_REPLY_ID,
-            };
-
             return Some(SubMsg::reply_on_success(msg, reply_id));
         }
 
@@ -1,9 +1,14 @@
 use cosmwasm_schema::cw_serde;
 
 pub mod admin;
+pub mod bank;
+pub mod event_log;
+pub mod events;
 pub mod hub;
 pub mod mint;
 pub mod query;
+pub mod reentrancy;
+pub mod state_version;
 pub mod storage;
 pub mod strategy;
 pub mod vault;
@@ -1,13 +1,14 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{
-    to_json_binary, Api, Binary, CosmosMsg, MessageInfo, StdError, Storage, SubMsg, Uint128,
+    to_json_binary, Addr, Api, Binary, Coin, CosmosMsg, CustomQuery, Event, MessageInfo, Order,
+    QuerierWrapper, StdError, Storage, SubMsg, Uint128,
 };
 
 use amulet_core::{
     admin::Repository as AdminRepository,
     mint::{
         mint, Cmd, ConfigCmd, Error as CoreMintError, Mint, MintCmd, Minter,
-        Repository as CoreMintRepository, Synthetic, SyntheticAmount, Ticker,
+        Repository as CoreMintRepository, Synthetic, SyntheticAmount, SyntheticMetadata, Ticker,
     },
     Decimals, Recipient,
 };
@@ -15,8 +16,9 @@ use cw_utils::{one_coin, PaymentError};
 
 use crate::{
     admin::{get_admin_role, Error as AdminError},
+    events::{EventBuilder, Key, Kind},
     query::page_bounds,
-    StorageExt,
+    MapKey, StorageExt,
 };
 
 pub trait TokenFactory<Msg> {
@@ -24,7 +26,14 @@ pub trait TokenFactory<Msg> {
 
     fn create(&self, ticker: Ticker) -> CosmosMsg<Msg>;
 
-    fn set_metadata(&self, ticker: &Ticker, decimals: Decimals) -> CosmosMsg<Msg>;
+    /// Set the denom metadata of the synthetic created with the `ticker`, displayed as the `display_ticker`
+    fn set_metadata(
+        &self,
+        ticker: &Ticker,
+        display_ticker: &Ticker,
+        decimals: Decimals,
+        description: &str,
+    ) -> CosmosMsg<Msg>;
 
     fn mint(
         &self,
@@ -34,6 +43,8 @@ pub trait TokenFactory<Msg> {
     ) -> CosmosMsg<Msg>;
 
     fn burn(&self, denom: Synthetic, amount: SyntheticAmount) -> CosmosMsg<Msg>;
+
+    fn set_before_send_hook(&self, ticker: &Ticker, contract_addr: &str) -> CosmosMsg<Msg>;
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -46,6 +57,8 @@ pub enum Error {
     Payment(#[from] PaymentError),
     #[error(transparent)]
     Admin(#[from] AdminError),
+    #[error("denom creation fee required: {amount} {denom}")]
+    DenomCreationFeeRequired { amount: Uint128, denom: String },
 }
 
 #[cw_serde]
@@ -57,8 +70,20 @@ pub enum ExecuteMsg {
         /// The number of decimals the synthetic uses
         decimals: u32,
     },
-    /// Set minter's whitelist status
-    SetWhitelisted { minter: String, whitelisted: bool },
+    /// Set minter's whitelist status for the synthetic with the given `denom`
+    SetWhitelistedMinter {
+        denom: String,
+        minter: String,
+        enabled: bool,
+    },
+    /// Update the display metadata of the synthetic with the given `denom`, omitted fields are unchanged.
+    /// The ticker the denom derives from & the decimals used for value math cannot change.
+    UpdateSyntheticMetadata {
+        denom: String,
+        display_ticker: Option<String>,
+        display_decimals: Option<u32>,
+        description: Option<String>,
+    },
     /// Mint an amount of synthetics to a recipient's address
     Mint {
         synthetic: String,
@@ -74,11 +99,29 @@ pub struct WhitelistedResponse {
     pub whitelisted: bool,
 }
 
+#[cw_serde]
+pub struct WhitelistedMintersResponse {
+    pub minters: Vec<String>,
+}
+
+#[cw_serde]
+pub struct WhitelistedDenomsResponse {
+    pub denoms: Vec<String>,
+}
+
 #[cw_serde]
 pub struct Metadata {
     pub denom: String,
     pub ticker: String,
     pub decimals: u32,
+    /// The ticker as displayed, in its original case unless since updated
+    #[serde(default)]
+    pub display_ticker: String,
+    /// The exponent of the display denom unit
+    #[serde(default)]
+    pub display_decimals: u32,
+    #[serde(default)]
+    pub description: String,
 }
 
 #[cw_serde]
@@ -90,9 +133,15 @@ pub struct AllAssetsResponse {
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
-    /// Query whether the `minter` address is whitelisted to mint assets
+    /// Query whether the `minter` address is whitelisted to mint the synthetic with the given `denom`
     #[returns(WhitelistedResponse)]
-    Whitelisted { minter: String },
+    Whitelisted { denom: String, minter: String },
+    /// All the minters whitelisted to mint the synthetic with the given `denom`
+    #[returns(WhitelistedMintersResponse)]
+    WhitelistedMinters { denom: String },
+    /// All the synthetic denoms the `minter` address is whitelisted to mint
+    #[returns(WhitelistedDenomsResponse)]
+    WhitelistedDenoms { minter: String },
     /// Query the metadata for the synthetic with the given `denom`
     #[returns(Metadata)]
     Synthetic { denom: String },
@@ -122,15 +171,33 @@ pub fn handle_execute_msg(
             mint.create_synthetic(admin_role, ticker.into(), decimals)?
         }
 
-        ExecuteMsg::SetWhitelisted {
+        ExecuteMsg::SetWhitelistedMinter {
+            denom,
             minter,
-            whitelisted,
+            enabled,
         } => {
             api.addr_validate(&minter)?;
 
             let admin_role = get_admin_role(admin_repository, &info)?;
 
-            mint.set_whitelisted(admin_role, minter.into(), whitelisted)?
+            mint.set_whitelisted(admin_role, denom.into(), minter.into(), enabled)?
+        }
+
+        ExecuteMsg::UpdateSyntheticMetadata {
+            denom,
+            display_ticker,
+            display_decimals,
+            description,
+        } => {
+            let admin_role = get_admin_role(admin_repository, &info)?;
+
+            mint.update_synthetic_metadata(
+                admin_role,
+                denom.into(),
+                display_ticker.map(Ticker::new),
+                display_decimals,
+                description,
+            )?
         }
 
         ExecuteMsg::Mint {
@@ -151,7 +218,11 @@ pub fn handle_execute_msg(
         ExecuteMsg::Burn {} => {
             let coin = one_coin(&info)?;
 
-            mint.burn(coin.denom.into(), coin.amount.u128())?
+            mint.burn(
+                info.sender.into_string().into(),
+                coin.denom.into(),
+                coin.amount.u128(),
+            )?
         }
     };
 
@@ -160,28 +231,24 @@ pub fn handle_execute_msg(
 
 pub fn handle_query_msg(storage: &dyn Storage, msg: QueryMsg) -> Result<Binary, StdError> {
     match msg {
-        QueryMsg::Whitelisted { minter } => {
+        QueryMsg::Whitelisted { denom, minter } => {
             let whitelisted = Repository(storage)
-                .is_whitelisted(&minter.into())
+                .is_whitelisted(&denom.into(), &minter.into())
                 .unwrap_or_default();
 
             to_json_binary(&WhitelistedResponse { whitelisted })
         }
 
-        QueryMsg::Synthetic { denom: synthetic } => {
-            let ticker = storage
-                .string_at(key::TICKER.with(&synthetic))
-                .ok_or(StdError::not_found("synthetic"))?;
+        QueryMsg::WhitelistedMinters { denom } => to_json_binary(&WhitelistedMintersResponse {
+            minters: DENOM_MINTERS.list(storage, &denom),
+        }),
 
-            let decimals = storage
-                .u32_at(key::DECIMALS.with(&synthetic))
-                .ok_or(StdError::not_found("synthetic"))?;
+        QueryMsg::WhitelistedDenoms { minter } => to_json_binary(&WhitelistedDenomsResponse {
+            denoms: MINTER_DENOMS.list(storage, &minter),
+        }),
 
-            to_json_binary(&Metadata {
-                denom: synthetic,
-                ticker,
-                decimals,
-            })
+        QueryMsg::Synthetic { denom } => {
+            to_json_binary(&metadata(storage, denom).ok_or(StdError::not_found("synthetic"))?)
         }
 
         QueryMsg::AllAssets { page, limit } => {
@@ -197,19 +264,7 @@ pub fn handle_query_msg(storage: &dyn Storage, msg: QueryMsg) -> Result<Binary,
                     .string_at(key::SYNTHETIC.with(idx))
                     .expect("always: set during denom creation");
 
-                let ticker = storage
-                    .string_at(key::TICKER.with(&denom))
-                    .expect("always: set during denom creation");
-
-                let decimals = storage
-                    .u32_at(key::DECIMALS.with(&denom))
-                    .expect("always: set during denom creation");
-
-                assets.push(Metadata {
-                    denom,
-                    ticker,
-                    decimals,
-                });
+                assets.push(metadata(storage, denom).expect("always: set during denom creation"));
             }
 
             to_json_binary(&AllAssetsResponse {
@@ -220,6 +275,34 @@ pub fn handle_query_msg(storage: &dyn Storage, msg: QueryMsg) -> Result<Binary,
     }
 }
 
+fn metadata(storage: &dyn Storage, denom: String) -> Option<Metadata> {
+    let ticker = storage.string_at(key::TICKER.with(&denom))?;
+
+    let decimals = storage.u32_at(key::DECIMALS.with(&denom))?;
+
+    // synthetics created before display metadata was stored are displayed with their lowercase ticker
+    let display_ticker = storage
+        .string_at(key::DISPLAY_TICKER.with(&denom))
+        .unwrap_or_else(|| ticker.clone());
+
+    let display_decimals = storage
+        .u32_at(key::DISPLAY_DECIMALS.with(&denom))
+        .unwrap_or(decimals);
+
+    let description = storage
+        .string_at(key::DESCRIPTION.with(&denom))
+        .unwrap_or_default();
+
+    Some(Metadata {
+        denom,
+        ticker,
+        decimals,
+        display_ticker,
+        display_decimals,
+        description,
+    })
+}
+
 pub struct Repository<'a>(&'a dyn Storage);
 
 impl<'a> Repository<'a> {
@@ -231,7 +314,7 @@ impl<'a> Repository<'a> {
 #[rustfmt::skip]
 mod key {
     use crate::MapKey;
-    
+
     macro_rules! key {
         ($k:literal) => {
             concat!("mint::", $k)
@@ -244,11 +327,147 @@ mod key {
         };
     }
 
-    pub const COUNT     : &str   = key!("count");
-    pub const SYNTHETIC : MapKey = map_key!("synthetic");
-    pub const TICKER    : MapKey = map_key!("ticker");
-    pub const DECIMALS  : MapKey = map_key!("decimals");
-    pub const WHITELIST : MapKey = map_key!("whitelist");
+    pub const COUNT                    : &str   = key!("count");
+    pub const SYNTHETIC                : MapKey = map_key!("synthetic");
+    pub const TICKER                   : MapKey = map_key!("ticker");
+    pub const DECIMALS                 : MapKey = map_key!("decimals");
+    pub const DISPLAY_TICKER           : MapKey = map_key!("display_ticker");
+    pub const DISPLAY_DECIMALS         : MapKey = map_key!("display_decimals");
+    pub const DESCRIPTION              : MapKey = map_key!("description");
+    pub const DENOM_MINTER             : MapKey = map_key!("denom_minter");
+    pub const DENOM_MINTER_COUNT       : MapKey = map_key!("denom_minter_count");
+    pub const DENOM_MINTER_INDEX       : MapKey = map_key!("denom_minter_index");
+    pub const MINTER_DENOM             : MapKey = map_key!("minter_denom");
+    pub const MINTER_DENOM_COUNT       : MapKey = map_key!("minter_denom_count");
+    pub const MINTER_DENOM_INDEX       : MapKey = map_key!("minter_denom_index");
+    // global minter whitelist, superseded by the per-denom whitelists
+    pub const LEGACY_WHITELIST         : &str   = key!("whitelist");
+    pub const LEGACY_WHITELIST_END     : &str   = key!("whitelisu");
+}
+
+/// An unordered set of values belonging to an owner that can be listed,
+/// removal swaps the last value into the removed value's position
+struct IndexedSet {
+    value: MapKey,
+    count: MapKey,
+    index: MapKey,
+}
+
+const DENOM_MINTERS: IndexedSet = IndexedSet {
+    value: key::DENOM_MINTER,
+    count: key::DENOM_MINTER_COUNT,
+    index: key::DENOM_MINTER_INDEX,
+};
+
+const MINTER_DENOMS: IndexedSet = IndexedSet {
+    value: key::MINTER_DENOM,
+    count: key::MINTER_DENOM_COUNT,
+    index: key::MINTER_DENOM_INDEX,
+};
+
+impl IndexedSet {
+    fn value_key(&self, owner: &str, idx: u32) -> String {
+        self.value.multi([&owner as &dyn ToString, &idx])
+    }
+    fn contains(&self, storage: &dyn Storage, owner: &str, value: &str) -> bool {
+        storage.has_key(self.index.multi([&owner, &value]))
+    }
+
+    fn list(&self, storage: &dyn Storage, owner: &str) -> Vec<String> {
+        let count = storage.u32_at(self.count.with(owner)).unwrap_or_default();
+
+        (0..count)
+            .map(|idx| {
+                storage
+                    .string_at(self.value_key(owner, idx))
+                    .expect("always: set for idx < count")
+            })
+            .collect()
+    }
+
+    fn insert(&self, storage: &mut dyn Storage, owner: &str, value: &str) {
+        if self.contains(storage, owner, value) {
+            return;
+        }
+
+        let count = storage.u32_at(self.count.with(owner)).unwrap_or_default();
+
+        storage.set_string(self.value_key(owner, count), value);
+        storage.set_u32(self.index.multi([&owner, &value]), count);
+        storage.set_u32(self.count.with(owner), count + 1);
+    }
+
+    fn remove(&self, storage: &mut dyn Storage, owner: &str, value: &str) {
+        let Some(idx) = storage.u32_at(self.index.multi([&owner, &value])) else {
+            return;
+        };
+
+        let last_idx = storage
+            .u32_at(self.count.with(owner))
+            .expect("always: set when there is at least one value")
+            - 1;
+
+        if idx != last_idx {
+            let last_value = storage
+                .string_at(self.value_key(owner, last_idx))
+                .expect("always: set for idx < count");
+
+            storage.set_string(self.value_key(owner, idx), &last_value);
+            storage.set_u32(self.index.multi([&owner, &last_value]), idx);
+        }
+
+        storage.remove(self.value_key(owner, last_idx).as_bytes());
+        storage.remove(self.index.multi([&owner, &value]).as_bytes());
+        storage.set_u32(self.count.with(owner), last_idx);
+    }
+}
+
+fn set_whitelisted(storage: &mut dyn Storage, denom: &str, minter: &str, enabled: bool) {
+    if enabled {
+        DENOM_MINTERS.insert(storage, denom, minter);
+        MINTER_DENOMS.insert(storage, minter, denom);
+    } else {
+        DENOM_MINTERS.remove(storage, denom, minter);
+        MINTER_DENOMS.remove(storage, minter, denom);
+    }
+}
+
+/// State migration mapping every minter on the legacy global whitelist onto each existing synthetic,
+/// removing the legacy entries
+pub fn migrate_global_whitelist(storage: &mut dyn Storage) -> Result<(), StdError> {
+    let legacy_keys: Vec<Vec<u8>> = storage
+        .range_keys(
+            Some(key::LEGACY_WHITELIST.as_bytes()),
+            Some(key::LEGACY_WHITELIST_END.as_bytes()),
+            Order::Ascending,
+        )
+        .collect();
+
+    let synthetic_count = storage.u32_at(key::COUNT).unwrap_or_default();
+
+    let denoms: Vec<String> = (0..synthetic_count)
+        .map(|idx| {
+            storage
+                .string_at(key::SYNTHETIC.with(idx))
+                .expect("always: set during denom creation")
+        })
+        .collect();
+
+    for legacy_key in legacy_keys {
+        let minter = std::str::from_utf8(&legacy_key[key::LEGACY_WHITELIST.len()..])
+            .expect("always: keys are valid utf-8")
+            .strip_suffix(':')
+            .expect("always: map keys are ':' terminated")
+            .to_owned();
+
+        for denom in &denoms {
+            set_whitelisted(storage, denom, &minter, true);
+        }
+
+        storage.remove(&legacy_key);
+    }
+
+    Ok(())
 }
 
 impl<'a> CoreMintRepository for Repository<'a> {
@@ -260,11 +479,69 @@ impl<'a> CoreMintRepository for Repository<'a> {
         self.0.has_key(key::TICKER.with(synthetic))
     }
 
-    fn is_whitelisted(&self, minter: &Minter) -> Option<bool> {
-        self.0.bool_at(key::WHITELIST.with(minter))
+    fn is_whitelisted(&self, synthetic: &Synthetic, minter: &Minter) -> Option<bool> {
+        DENOM_MINTERS
+            .contains(self.0, synthetic, minter)
+            .then_some(true)
+    }
+
+    fn synthetic_metadata(&self, synthetic: &Synthetic) -> Option<SyntheticMetadata> {
+        let metadata = metadata(self.0, synthetic.to_string())?;
+
+        Some(SyntheticMetadata {
+            ticker: metadata.ticker.into(),
+            display_ticker: metadata.display_ticker.into(),
+            display_decimals: metadata.display_decimals,
+            description: metadata.description,
+        })
     }
 }
 
+/// Ensure the contract balance covers the token factory `fee` charged for creating a denom.
+/// Any funds attached to the create message are already included in the balance.
+pub fn check_denom_creation_fee<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    contract: &Addr,
+    fee: &[Coin],
+) -> Result<(), Error> {
+    for coin in fee {
+        let balance = querier.query_balance(contract, &coin.denom)?;
+
+        if balance.amount < coin.amount {
+            return Err(Error::DenomCreationFeeRequired {
+                amount: coin.amount,
+                denom: coin.denom.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// The event describing a command, if any, recording the previous & updated values of a metadata update
+pub fn cmd_event(cmd: &Cmd) -> Option<Event> {
+    let Cmd::Config(ConfigCmd::UpdateMetadata {
+        synthetic,
+        prev,
+        next,
+    }) = cmd
+    else {
+        return None;
+    };
+
+    let event = EventBuilder::new(Kind::SyntheticMetadataUpdated)
+        .attr(Key::Denom, synthetic.as_str())
+        .attr(Key::OldDisplayTicker, prev.display_ticker.display())
+        .attr(Key::NewDisplayTicker, next.display_ticker.display())
+        .attr(Key::OldDisplayDecimals, prev.display_decimals)
+        .attr(Key::NewDisplayDecimals, next.display_decimals)
+        .attr(Key::OldDescription, prev.description.as_str())
+        .attr(Key::NewDescription, next.description.as_str())
+        .event();
+
+    Some(event)
+}
+
 pub fn handle_cmd<Msg>(
     storage: &mut dyn Storage,
     token_factory: impl TokenFactory<Msg>,
@@ -287,22 +564,48 @@ pub fn handle_cmd<Msg>(
 
                 storage.set_u32(key::DECIMALS.with(&denom), decimals);
 
-                let set_metadata_msg = SubMsg::new(token_factory.set_metadata(&ticker, decimals));
+                storage.set_string(key::DISPLAY_TICKER.with(&denom), ticker.display());
+
+                let set_metadata_msg =
+                    SubMsg::new(token_factory.set_metadata(&ticker, &ticker, decimals, ""));
 
                 let create_denom_msg = SubMsg::new(token_factory.create(ticker));
 
                 vec![create_denom_msg, set_metadata_msg]
             }
 
-            ConfigCmd::Whitelist { minter, enabled } => {
-                if enabled {
-                    storage.set_bool(key::WHITELIST.with(minter), enabled);
-                } else {
-                    storage.remove(key::WHITELIST.with(minter).as_bytes())
-                }
+            ConfigCmd::Whitelist {
+                synthetic,
+                minter,
+                enabled,
+            } => {
+                set_whitelisted(storage, &synthetic, &minter, enabled);
 
                 vec![]
             }
+
+            ConfigCmd::UpdateMetadata {
+                synthetic, next, ..
+            } => {
+                storage.set_string(
+                    key::DISPLAY_TICKER.with(&synthetic),
+                    next.display_ticker.display(),
+                );
+
+                storage.set_u32(
+                    key::DISPLAY_DECIMALS.with(&synthetic),
+                    next.display_decimals,
+                );
+
+                storage.set_string(key::DESCRIPTION.with(&synthetic), &next.description);
+
+                vec![SubMsg::new(token_factory.set_metadata(
+                    &next.ticker,
+                    &next.display_ticker,
+                    next.display_decimals,
+                    &next.description,
+                ))]
+            }
         },
 
         Cmd::Mint(mint_cmd) => match mint_cmd {
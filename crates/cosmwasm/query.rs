@@ -1,5 +1,8 @@
 pub const DEFAULT_PAGE_LIMIT: u32 = 10;
 
+/// The most entries a cursor paginated query returns, whatever the requested limit
+pub const MAX_PAGE_LIMIT: u32 = 100;
+
 /// Determine the page bounds to be used in a paginated query.
 /// Returns `Some((start, end))` or `None` if the proposed start is out of bounds.
 /// If no `limit` is provided but a `page` is, the `DEFAULT_PAGE_LIMIT` is used.
@@ -0,0 +1,48 @@
+//! An opt-in guard against re-entrant executes while a submessage reply is pending.
+//!
+//! A contract locks the guard when dispatching a submessage it expects a reply for,
+//! rejects any execute that arrives while locked & unlocks the guard in its reply handler.
+use cosmwasm_std::Storage;
+
+use crate::StorageExt as _;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("reentrancy detected")]
+    ReentrancyDetected,
+}
+
+#[rustfmt::skip]
+mod key {
+    macro_rules! key {
+        ($k:literal) => {
+            concat!("reentrancy::", $k)
+        };
+    }
+
+    pub const LOCKED : &str = key!("locked");
+}
+
+/// Returns true if a reply is pending
+pub fn is_locked(storage: &dyn Storage) -> bool {
+    storage.bool_at(key::LOCKED).unwrap_or_default()
+}
+
+/// Lock the guard until the pending reply is handled
+pub fn lock(storage: &mut dyn Storage) {
+    storage.set_bool(key::LOCKED, true);
+}
+
+/// Unlock the guard once the pending reply has been handled
+pub fn unlock(storage: &mut dyn Storage) {
+    storage.remove(key::LOCKED.as_bytes());
+}
+
+/// Returns an error if a reply is pending
+pub fn ensure_unlocked(storage: &dyn Storage) -> Result<(), Error> {
+    if is_locked(storage) {
+        return Err(Error::ReentrancyDetected);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,209 @@
+//! A version header for contract state, so that a migration can detect which state layout it is upgrading from.
+//!
+//! Each contract declares its own ordered list of [`MigrationStep`]s, records the resulting version on
+//! instantiation & calls [`migrate_state`] with the same list when migrated.
+//! State written before the header existed has no stored version & is treated as version 0.
+use std::fmt;
+
+use cosmwasm_std::{DepsMut, StdError, Storage};
+
+use crate::StorageExt as _;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("stored state version {stored} is newer than the current version {current}")]
+    UnsupportedVersion {
+        stored: StorageVersion,
+        current: StorageVersion,
+    },
+
+    #[error(transparent)]
+    CosmWasm(#[from] StdError),
+}
+
+#[rustfmt::skip]
+mod key {
+    macro_rules! key {
+        ($k:literal) => {
+            concat!("state_version::", $k)
+        };
+    }
+
+    pub const VERSION : &str = key!("version");
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StorageVersion(pub u32);
+
+impl fmt::Display for StorageVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+/// Upgrades state by a single version
+pub type MigrationStep = fn(&mut dyn Storage) -> Result<(), StdError>;
+
+/// The version of the state layout written by a release with the given migrations,
+/// where the step at index `n` upgrades state from version `n` to `n + 1`
+pub const fn current_version(migrations: &[MigrationStep]) -> StorageVersion {
+    StorageVersion(migrations.len() as u32)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateMigration {
+    pub from: StorageVersion,
+    pub to: StorageVersion,
+}
+
+/// Returns the stored state version, version 0 if none has been stored
+pub fn stored_version(storage: &dyn Storage) -> StorageVersion {
+    StorageVersion(storage.u32_at(key::VERSION).unwrap_or_default())
+}
+
+/// Record freshly instantiated state as being at the current version of the contract's migrations
+pub fn init(storage: &mut dyn Storage, migrations: &[MigrationStep]) {
+    storage.set_u32(key::VERSION, current_version(migrations).0);
+}
+
+/// Apply any of the contract's step migrations the stored state has not yet been through, in order,
+/// then record the state as being at the current version
+pub fn migrate_state(deps: DepsMut, migrations: &[MigrationStep]) -> Result<StateMigration, Error> {
+    apply_migrations(deps.storage, migrations)
+}
+
+fn apply_migrations(
+    storage: &mut dyn Storage,
+    steps: &[MigrationStep],
+) -> Result<StateMigration, Error> {
+    let from = stored_version(storage);
+
+    let to = current_version(steps);
+
+    if from > to {
+        return Err(Error::UnsupportedVersion {
+            stored: from,
+            current: to,
+        });
+    }
+
+    for step in &steps[from.0 as usize..] {
+        step(storage)?;
+    }
+
+    storage.set_u32(key::VERSION, to.0);
+
+    Ok(StateMigration { from, to })
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::testing::MockStorage;
+    use test_utils::prelude::*;
+
+    use super::*;
+
+    const STEPS: &[MigrationStep] = &[
+        // v0 -> v1: rename a key
+        |storage| {
+            let value = storage
+                .get(b"legacy")
+                .ok_or_else(|| StdError::not_found("legacy"))?;
+
+            storage.remove(b"legacy");
+            storage.set(b"renamed", &value);
+
+            Ok(())
+        },
+        // v1 -> v2: add a key with a default value
+        |storage| {
+            storage.set_bool("flag", true);
+
+            Ok(())
+        },
+    ];
+
+    #[test]
+    fn unset_version_migrates_from_v0_through_all_steps() {
+        let mut storage = MockStorage::default();
+
+        storage.set(b"legacy", b"value");
+
+        assert_eq!(stored_version(&storage), StorageVersion(0));
+
+        let migration = apply_migrations(&mut storage, STEPS).unwrap();
+
+        assert_eq!(
+            migration,
+            StateMigration {
+                from: StorageVersion(0),
+                to: StorageVersion(2),
+            }
+        );
+        assert_eq!(stored_version(&storage), StorageVersion(2));
+        assert_eq!(storage.get(b"legacy"), None);
+        assert_eq!(
+            storage.get(b"renamed").as_deref(),
+            Some(b"value".as_slice())
+        );
+        assert_eq!(storage.bool_at("flag"), Some(true));
+    }
+
+    #[test]
+    fn only_pending_steps_are_applied() {
+        let mut storage = MockStorage::default();
+
+        // the first step would fail if applied again, as the legacy key is gone
+        storage.set_u32(key::VERSION, 1);
+
+        let migration = apply_migrations(&mut storage, STEPS).unwrap();
+
+        assert_eq!(migration.from, StorageVersion(1));
+        assert_eq!(stored_version(&storage), StorageVersion(2));
+        assert_eq!(storage.bool_at("flag"), Some(true));
+
+        // migrating again is a no-op
+        storage.remove(b"flag");
+
+        let migration = apply_migrations(&mut storage, STEPS).unwrap();
+
+        assert_eq!(migration.from, migration.to);
+        assert_eq!(storage.bool_at("flag"), None);
+    }
+
+    #[test]
+    fn failed_step_errs() {
+        let mut storage = MockStorage::default();
+
+        check_err(
+            apply_migrations(&mut storage, STEPS).unwrap_err(),
+            expect!["legacy not found"],
+        );
+    }
+
+    #[test]
+    fn newer_stored_version_errs() {
+        let mut storage = MockStorage::default();
+
+        storage.set_u32(key::VERSION, 3);
+
+        check_err(
+            apply_migrations(&mut storage, STEPS).unwrap_err(),
+            expect!["stored state version v3 is newer than the current version v2"],
+        );
+    }
+
+    #[test]
+    fn init_records_current_version() {
+        let mut storage = MockStorage::default();
+
+        init(&mut storage, STEPS);
+
+        assert_eq!(stored_version(&storage), current_version(STEPS));
+
+        let migration = apply_migrations(&mut storage, STEPS).unwrap();
+
+        assert_eq!(migration.from, current_version(STEPS));
+        assert_eq!(migration.to, current_version(STEPS));
+    }
+}
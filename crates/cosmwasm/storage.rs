@@ -128,6 +128,7 @@ pub trait StorageExt: Storage {
 
 impl<T> StorageExt for T where T: Storage + ?Sized {}
 
+#[derive(Clone, Copy)]
 pub struct MapKey(&'static str);
 
 impl MapKey {
@@ -25,3 +25,53 @@ pub mod generic_lst {
         }
     }
 }
+
+pub mod weights {
+    use cosmwasm_schema::cw_serde;
+    use num::{FixedU256, Rounding};
+
+    const HUNDRED_PERCENT_BPS: u128 = 10_000;
+
+    #[cw_serde]
+    pub struct SlotWeight {
+        /// The weight as a decimal fraction of 1.0
+        pub weight: String,
+        /// The weight in basis points, rounded to the nearest
+        pub bps: u32,
+    }
+
+    #[cw_serde]
+    pub struct WeightsResponse {
+        /// The weight of each validator slot, in slot order
+        pub weights: Vec<SlotWeight>,
+    }
+
+    impl WeightsResponse {
+        /// Panics if any weight is greater than 1.0
+        pub fn new(weights: impl IntoIterator<Item = FixedU256>) -> Self {
+            let weights = weights
+                .into_iter()
+                .map(|weight| {
+                    assert!(weight.le_u128(1), "weight <= 1.0");
+
+                    let bps = weight
+                        .mul_u128_with_rounding(HUNDRED_PERCENT_BPS, Rounding::HalfEven)
+                        .and_then(|bps| u32::try_from(bps).ok())
+                        .expect("always: weight <= 1.0");
+
+                    SlotWeight {
+                        weight: weight.to_string(),
+                        bps,
+                    }
+                })
+                .collect();
+
+            Self { weights }
+        }
+
+        /// The slot weights in basis points, as accepted when setting weights
+        pub fn bps(&self) -> Vec<u32> {
+            self.weights.iter().map(|slot| slot.bps).collect()
+        }
+    }
+}
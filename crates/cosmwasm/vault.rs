@@ -1,13 +1,19 @@
+pub mod accepted_assets;
+pub mod claims;
 pub mod mint;
+pub mod redemption_rate;
+pub mod shares_transfer;
 pub mod unbonding_log;
 
+use std::fmt;
+
 use amulet_core::{
     vault::{
-        offset_total_deposits_value, pending_batch_id, vault, BatchId, ClaimAmount,
-        ClaimableBatchIter, Cmd, DepositAmount, DepositResponse as CoreDepositResponse,
-        DepositValue, Error as CoreVaultError, MintCmd, SharesAmount, SharesMint as CoreSharesMint,
-        Strategy, StrategyCmd, UnbondEpoch, UnbondingLog as CoreUnbondingLog, UnbondingLogSet,
-        Vault,
+        batch_claim_amount, offset_total_deposits_value, pending_batch_id, vault, BatchId,
+        ClaimAllResponse, ClaimAmount, ClaimableBatchIter, Cmd, DepositAmount,
+        DepositResponse as CoreDepositResponse, DepositValue, Error as CoreVaultError, MintCmd,
+        SharesAmount, SharesMint as CoreSharesMint, Strategy, StrategyCmd, UnbondEpoch,
+        UnbondingLog as CoreUnbondingLog, UnbondingLogSet, Vault,
     },
     Decimals,
 };
@@ -18,13 +24,40 @@ use cosmwasm_std::{
 use cw_utils::{nonpayable, one_coin, PaymentError};
 use strum::IntoStaticStr;
 
-use self::unbonding_log::StorageExt as _;
+use crate::query::{DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT};
+
+use self::{
+    accepted_assets::{AcceptedAsset, StorageExt as _},
+    unbonding_log::StorageExt as _,
+};
 
 pub use self::{
     mint::{handle_cmd as handle_mint_cmd, init_msg as init_mint_msg, SharesMint},
+    shares_transfer::{
+        handle_sudo_msg as handle_shares_transfer_sudo_msg, restrict_msg as restrict_shares_msg,
+        SudoMsg as SharesTransferSudoMsg,
+    },
     unbonding_log::{handle_cmd as handle_unbonding_log_cmd, UnbondingLog},
 };
 
+/// The maximum number of batches claimed by a single [ExecuteMsg::ClaimAll], capping the gas used
+pub const MAX_BATCHES_PER_CLAIM: usize = 30;
+
+/// The semver version of the shared vault interface, bumping the major version on breaking changes
+pub const INTERFACE_VERSION: &str = "1.0.0";
+
+/// Optional capabilities a vault may report in an [InterfaceVersionResponse]
+pub mod feature {
+    /// Accepts [super::ExecuteMsg::Donate]
+    pub const DONATE: &str = "donate";
+    /// Accepts [super::ExecuteMsg::Redeem] on behalf of another recipient
+    pub const REDEEM_ON_BEHALF: &str = "redeem_on_behalf";
+    /// Accepts [super::ExecuteMsg::ClaimAll]
+    pub const CLAIM_ALL: &str = "claim_all";
+    /// Answers [super::QueryMsg::UnbondSchedule]
+    pub const UNBOND_SCHEDULE: &str = "unbond_schedule";
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error(transparent)]
@@ -66,6 +99,10 @@ pub enum ExecuteMsg {
 
     /// Claim any unclaimed unbonded underlying tokens belonging to the sender
     Claim {},
+
+    /// Claim the unbonded underlying tokens of up to [MAX_BATCHES_PER_CLAIM] matured batches belonging to the sender,
+    /// listing each batch claimed in the response attributes
+    ClaimAll {},
 }
 
 impl ExecuteMsg {
@@ -76,6 +113,40 @@ impl ExecuteMsg {
     }
 }
 
+/// A point in time, in seconds since the unix epoch, serialized as a plain integer
+#[cw_serde]
+#[derive(Copy, Eq, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct Timestamp(pub u64);
+
+impl Timestamp {
+    /// The timestamp `duration` after this one
+    pub fn plus(self, duration: DurationSecs) -> Timestamp {
+        self.0
+            .checked_add(duration.0)
+            .map(Timestamp)
+            .expect("always: timestamps are far from overflowing")
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A length of time in seconds, serialized as a plain integer
+#[cw_serde]
+#[derive(Copy, Eq, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct DurationSecs(pub u64);
+
+impl fmt::Display for DurationSecs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 #[cw_serde]
 pub struct UnbondingStatus {
     pub amount: Uint128,
@@ -86,7 +157,7 @@ pub struct UnbondingStatus {
 #[cw_serde]
 pub struct PendingUnbondingResponse {
     pub amount: Uint128,
-    pub start_hint: Option<u64>,
+    pub start_hint: Option<Timestamp>,
 }
 
 #[cw_serde]
@@ -96,12 +167,43 @@ pub struct ActiveUnbondingsResponse {
     pub unbondings: Vec<UnbondingStatus>,
 }
 
+#[cw_serde]
+pub enum UnbondScheduleStatus {
+    /// Entered into the pending batch, which has yet to be committed to unbonding
+    Pending,
+    /// Committed to unbonding on the host chain
+    Active,
+    /// Finished unbonding and awaiting a claim
+    Matured,
+}
+
+#[cw_serde]
+pub struct UnbondScheduleEntry {
+    pub batch_id: u64,
+    /// The amount the account is due from the batch, valued at the time of redemption while it is pending
+    pub amount: Uint128,
+    /// When the batch started unbonding, or the start hint of the pending batch if one was given
+    pub unbond_started_at: Option<u64>,
+    /// When the batch will be claimable, unknown until it is committed
+    pub estimated_claimable_at: Option<u64>,
+    pub status: UnbondScheduleStatus,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct UnbondScheduleResponse {
+    /// The account's unclaimed entries in ascending batch order, which is also the order they become claimable
+    pub entries: Vec<UnbondScheduleEntry>,
+}
+
 #[cw_serde]
 pub struct StateResponse {
     /// Total amount of deposits in the vault
     pub total_deposits: Uint128,
     /// Total number of issued shares
     pub total_issued_shares: Uint128,
+    /// Total deposits valued at the strategy's redemption rate, if it reports one distinct from mark-to-market
+    pub redemption_value: Option<Uint128>,
 }
 
 #[cw_serde]
@@ -115,16 +217,37 @@ pub struct UnderlyingAssetDecimalsResponse {
     pub decimals: Decimals,
 }
 
+#[cw_serde]
+pub struct SharesDecimalsResponse {
+    pub decimals: Decimals,
+}
+
 #[cw_serde]
 pub struct DepositAssetResponse {
     pub denom: String,
 }
 
+#[cw_serde]
+pub struct AcceptedDepositAssetsResponse {
+    /// The primary deposit asset
+    pub primary: String,
+    /// The secondary assets accepted for deposit, converted into the primary asset at deposit time
+    pub accepted: Vec<AcceptedAsset>,
+}
+
 #[cw_serde]
 pub struct SharesAssetResponse {
     pub denom: String,
 }
 
+#[cw_serde]
+pub struct InterfaceVersionResponse {
+    /// The semver version of the vault interface implemented
+    pub version: String,
+    /// The optional features supported by the vault
+    pub features: Vec<String>,
+}
+
 #[cw_serde]
 pub struct UnbondingLogMetadata {
     pub last_committed_batch_id: Option<BatchId>,
@@ -160,6 +283,16 @@ pub enum QueryMsg {
     #[returns(ClaimableResponse)]
     Claimable { address: String },
 
+    /// Returns every batch the account has redeemed into that is yet to be claimed, whether pending, active or matured.
+    /// Only batches after `start_after`, the last batch of a previous page, are included,
+    /// up to `limit` entries or `amulet_cw::query::DEFAULT_PAGE_LIMIT`, capped at `amulet_cw::query::MAX_PAGE_LIMIT`
+    #[returns(UnbondScheduleResponse)]
+    UnbondSchedule {
+        account: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
     /// Returns the underlying asset decimals that the vault deposit value is denominated with
     #[returns(UnderlyingAssetDecimalsResponse)]
     UnderlyingAssetDecimals {},
@@ -168,9 +301,21 @@ pub enum QueryMsg {
     #[returns(DepositAssetResponse)]
     DepositAsset {},
 
+    /// Returns all the assets that the vault accepts for deposit
+    #[returns(AcceptedDepositAssetsResponse)]
+    AcceptedDepositAssets {},
+
     /// Returns the shares asset issued by the vault
     #[returns(SharesAssetResponse)]
     SharesAsset {},
+
+    /// Returns the decimals of the shares asset issued by the vault
+    #[returns(SharesDecimalsResponse)]
+    SharesDecimals {},
+
+    /// Returns the vault interface version & the optional features supported
+    #[returns(InterfaceVersionResponse)]
+    InterfaceVersion {},
 }
 
 fn handle_vault_deposit<Msg>(
@@ -247,6 +392,27 @@ fn handle_vault_claim(info: MessageInfo, vault: &dyn Vault) -> Result<Vec<Cmd>,
     Ok(cmds)
 }
 
+fn handle_vault_claim_all<Msg>(
+    info: MessageInfo,
+    vault: &dyn Vault,
+    response: &mut Response<Msg>,
+) -> Result<Vec<Cmd>, Error> {
+    nonpayable(&info)?;
+
+    let ClaimAllResponse { cmds, claimed } =
+        vault.claim_all(info.sender.into_string().into(), MAX_BATCHES_PER_CLAIM)?;
+
+    let mut attrs = AttrsBuilder(response);
+
+    for (batch, ClaimAmount(amount)) in claimed {
+        attrs
+            .add_attr("claimed_batch", batch)
+            .add_attr("claimed_batch_amount", amount);
+    }
+
+    Ok(cmds)
+}
+
 struct AttrsBuilder<'a, Msg>(&'a mut Response<Msg>);
 
 impl<'a, Msg> AttrsBuilder<'a, Msg> {
@@ -274,7 +440,9 @@ fn add_msg_attrs<Msg>(msg: &ExecuteMsg, info: &MessageInfo, response: &mut Respo
     attrs.add_kind(msg.kind());
 
     match msg {
-        ExecuteMsg::Deposit {} | ExecuteMsg::Claim {} => attrs.add_recipient(info.sender.as_str()),
+        ExecuteMsg::Deposit {} | ExecuteMsg::Claim {} | ExecuteMsg::ClaimAll {} => {
+            attrs.add_recipient(info.sender.as_str())
+        }
         ExecuteMsg::Donate {} => attrs.add_attr("donor", &info.sender),
         ExecuteMsg::Redeem { recipient } => attrs.add_recipient(recipient),
         _ => &mut attrs,
@@ -303,9 +471,17 @@ fn add_cmd_attrs<Msg>(cmds: &[Cmd], response: &mut Response<Msg>) {
                     amount: ClaimAmount(amount),
                     ..
                 } => attrs.add_amount(amount),
+                StrategyCmd::DepositSecondary {
+                    asset,
+                    amount: DepositAmount(amount),
+                } => attrs.add_attr("secondary_asset", asset).add_amount(amount),
                 StrategyCmd::Unbond {
                     value: DepositValue(value),
                 } => attrs.add_attr("unbond_value", value),
+                StrategyCmd::CollectUnbondFee {
+                    value: DepositValue(value),
+                    ..
+                } => attrs.add_attr("unbond_fee", value),
             },
             Cmd::UnbondingLog(cmd) => match cmd {
                 UnbondingLogSet::LastCommittedBatchId(batch) => {
@@ -366,6 +542,7 @@ pub fn handle_execute_msg<Msg>(
         ExecuteMsg::Redeem { recipient } => handle_vault_redemption(info, &vault, recipient)?,
         ExecuteMsg::StartUnbond {} => handle_vault_start_unbond(info, &vault)?,
         ExecuteMsg::Claim {} => handle_vault_claim(info, &vault)?,
+        ExecuteMsg::ClaimAll {} => handle_vault_claim_all(info, &vault, &mut response)?,
     };
 
     add_cmd_attrs(&cmds, &mut response);
@@ -393,7 +570,9 @@ pub fn pending_unbonding(
             .into()
     };
 
-    let start_hint = unbonding_log.pending_batch_hint(pending_batch_id);
+    let start_hint = unbonding_log
+        .pending_batch_hint(pending_batch_id)
+        .map(Timestamp);
 
     Ok(PendingUnbondingResponse { amount, start_hint })
 }
@@ -508,12 +687,87 @@ pub fn all_active_unbondings(
     ActiveUnbondingsResponse { unbondings }
 }
 
+pub fn account_unbond_schedule(
+    unbonding_log: &dyn CoreUnbondingLog,
+    strategy: &dyn Strategy,
+    account: &str,
+    start_after: Option<BatchId>,
+    limit: Option<u32>,
+) -> UnbondScheduleResponse {
+    let first_unclaimed_batch = match unbonding_log.last_claimed_batch(account) {
+        Some(last_claimed_batch) => unbonding_log.next_entered_batch(account, last_claimed_batch),
+        None => unbonding_log.first_entered_batch(account),
+    };
+
+    // resume from the batch entered after the previous page's last, rather than walking up to it
+    let first_batch = match (first_unclaimed_batch, start_after) {
+        (Some(first_unclaimed_batch), Some(start_after))
+            if start_after >= first_unclaimed_batch =>
+        {
+            unbonding_log.next_entered_batch(account, start_after)
+        }
+        (first_unclaimed_batch, _) => first_unclaimed_batch,
+    };
+
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+
+    let pending_batch_id = pending_batch_id(unbonding_log);
+
+    let now = strategy.now();
+
+    let entries = std::iter::successors(first_batch, |&batch_id| {
+        unbonding_log.next_entered_batch(account, batch_id)
+    })
+    .take(limit as usize)
+    .map(|batch_id| {
+        if batch_id >= pending_batch_id {
+            let amount = unbonding_log
+                .unbonded_value_in_batch(account, batch_id)
+                .expect("always: non-zero amount unbonded in entered batch")
+                .0
+                .into();
+
+            return UnbondScheduleEntry {
+                batch_id,
+                amount,
+                unbond_started_at: unbonding_log.pending_batch_hint(batch_id),
+                estimated_claimable_at: None,
+                status: UnbondScheduleStatus::Pending,
+            };
+        }
+
+        let UnbondEpoch { start, end } = unbonding_log
+            .committed_batch_epoch(batch_id)
+            .expect("always: epoch set for a committed batch");
+
+        let ClaimAmount(amount) = batch_claim_amount(unbonding_log, account, batch_id)
+            .expect("always: batch entered by account and committed");
+
+        UnbondScheduleEntry {
+            batch_id,
+            amount: amount.into(),
+            unbond_started_at: Some(start),
+            estimated_claimable_at: Some(end),
+            status: if end > now {
+                UnbondScheduleStatus::Active
+            } else {
+                UnbondScheduleStatus::Matured
+            },
+        }
+    })
+    .collect();
+
+    UnbondScheduleResponse { entries }
+}
+
+/// Handles the shared vault queries, reporting the optional `features` the vault contract declares it supports
 pub fn handle_query_msg(
     storage: &dyn Storage,
     strategy: &dyn Strategy,
     unbonding_log: &dyn CoreUnbondingLog,
     mint: &dyn CoreSharesMint,
     env: &Env,
+    features: &[&str],
     msg: QueryMsg,
 ) -> Result<Binary, StdError> {
     match msg {
@@ -522,9 +776,14 @@ pub fn handle_query_msg(
 
             let total_deposits = offset_total_deposits_value(strategy, unbonding_log);
 
+            let redemption_value = strategy
+                .redemption_rate()
+                .and_then(|rate| rate.checked_mul_u128(total_deposits.0));
+
             to_json_binary(&StateResponse {
                 total_deposits: total_deposits.0.into(),
                 total_issued_shares: total_shares_issue.0.into(),
+                redemption_value: redemption_value.map(Uint128::new),
             })
         }
 
@@ -560,6 +819,18 @@ pub fn handle_query_msg(
             to_json_binary(&ClaimableResponse { amount })
         }
 
+        QueryMsg::UnbondSchedule {
+            account,
+            start_after,
+            limit,
+        } => to_json_binary(&account_unbond_schedule(
+            unbonding_log,
+            strategy,
+            &account,
+            start_after,
+            limit,
+        )),
+
         QueryMsg::UnderlyingAssetDecimals {} => to_json_binary(&UnderlyingAssetDecimalsResponse {
             decimals: strategy.underlying_asset_decimals(),
         }),
@@ -568,9 +839,23 @@ pub fn handle_query_msg(
             denom: strategy.deposit_asset().into_string(),
         }),
 
+        QueryMsg::AcceptedDepositAssets {} => to_json_binary(&AcceptedDepositAssetsResponse {
+            primary: strategy.deposit_asset().into_string(),
+            accepted: storage.accepted_assets(),
+        }),
+
         QueryMsg::SharesAsset {} => to_json_binary(&SharesAssetResponse {
             denom: mint.shares_asset().into_string(),
         }),
+
+        QueryMsg::SharesDecimals {} => to_json_binary(&SharesDecimalsResponse {
+            decimals: mint.shares_decimals(),
+        }),
+
+        QueryMsg::InterfaceVersion {} => to_json_binary(&InterfaceVersionResponse {
+            version: INTERFACE_VERSION.to_owned(),
+            features: features.iter().copied().map(str::to_owned).collect(),
+        }),
     }
 }
 
@@ -591,6 +876,7 @@ mod test {
         env: &'a Env,
         deposits: u128,
         unbonds: u64,
+        accepted_assets: Option<&'a accepted_assets::AcceptedAssets>,
     }
 
     impl<'a> CoreStategy for MockStrategy<'a> {
@@ -602,6 +888,14 @@ mod test {
             DEPOSIT_ASSET.to_owned().into()
         }
 
+        fn convert_secondary_deposit(
+            &self,
+            asset: &amulet_core::Asset,
+            amount: DepositAmount,
+        ) -> Option<DepositAmount> {
+            self.accepted_assets?.convert(asset, amount)
+        }
+
         fn underlying_asset_decimals(&self) -> Decimals {
             6
         }
@@ -626,6 +920,18 @@ mod test {
                 },
             }
         }
+
+        fn unbond_fee(&self, _value: DepositValue) -> DepositValue {
+            DepositValue(0)
+        }
+
+        fn available_to_claim(&self) -> Option<ClaimAmount> {
+            None
+        }
+
+        fn redemption_rate(&self) -> Option<num::FixedU256> {
+            None
+        }
     }
 
     struct MockTokenFactory;
@@ -667,6 +973,7 @@ mod test {
             env: &env,
             deposits: 0,
             unbonds: 0,
+            accepted_assets: None,
         };
 
         let (cmds, _) = handle_execute_msg::<Empty>(
@@ -735,6 +1042,7 @@ mod test {
             &unbonding_log::UnbondingLog::new(&deps.storage),
             &mint::SharesMint::new(&deps.storage, &env),
             &env,
+            &[],
             QueryMsg::ActiveUnbondings {
                 address: None,
                 limit: None,
@@ -775,6 +1083,7 @@ mod test {
             &unbonding_log::UnbondingLog::new(&deps.storage),
             &mint::SharesMint::new(&deps.storage, &env),
             &env,
+            &[],
             QueryMsg::ActiveUnbondings {
                 address: Some("bob".to_owned()),
                 limit: Some(4),
@@ -810,6 +1119,294 @@ mod test {
         )
     }
 
+    #[test]
+    fn unbond_schedule_query() {
+        let mut deps = testing::mock_dependencies();
+
+        let env = testing::mock_env();
+
+        let now = env.block.time.seconds();
+
+        let strategy = MockStrategy {
+            env: &env,
+            deposits: 0,
+            unbonds: 0,
+            accepted_assets: None,
+        };
+
+        let bob = || -> amulet_core::Recipient { "bob".to_owned().into() };
+
+        // batch 0 is claimed, 1 has matured, 2 is still unbonding & 3 is pending
+        let mut sets = vec![
+            UnbondingLogSet::LastCommittedBatchId(2),
+            UnbondingLogSet::BatchHint {
+                batch: 3,
+                hint: now + 10,
+            },
+            UnbondingLogSet::FirstEnteredBatch {
+                recipient: bob(),
+                batch: 0,
+            },
+            UnbondingLogSet::LastEnteredBatch {
+                recipient: bob(),
+                batch: 3,
+            },
+            UnbondingLogSet::LastClaimedBatch {
+                recipient: bob(),
+                batch: 0,
+            },
+        ];
+
+        for (batch, (start, end)) in [(now - 3, now - 2), (now - 2, now), (now - 1, now + 1)]
+            .into_iter()
+            .enumerate()
+        {
+            let batch = batch as BatchId;
+
+            sets.extend([
+                UnbondingLogSet::BatchEpoch {
+                    batch,
+                    epoch: UnbondEpoch { start, end },
+                },
+                // bob has a quarter of each batch, which lost 5% while unbonding
+                UnbondingLogSet::BatchTotalUnbondValue {
+                    batch,
+                    value: DepositValue(400),
+                },
+                UnbondingLogSet::BatchClaimableAmount {
+                    batch,
+                    amount: ClaimAmount(380),
+                },
+            ]);
+        }
+
+        for batch in 0..=3 {
+            sets.push(UnbondingLogSet::UnbondedValueInBatch {
+                recipient: bob(),
+                batch,
+                value: DepositValue(100),
+            });
+
+            if batch > 0 {
+                sets.push(UnbondingLogSet::NextEnteredBatch {
+                    recipient: bob(),
+                    previous: batch - 1,
+                    next: batch,
+                });
+            }
+        }
+
+        for set in sets {
+            unbonding_log::handle_cmd(&mut deps.storage, set);
+        }
+
+        let unbond_schedule = |start_after, limit| -> UnbondScheduleResponse {
+            handle_query_msg(
+                &deps.storage,
+                &strategy,
+                &unbonding_log::UnbondingLog::new(&deps.storage),
+                &mint::SharesMint::new(&deps.storage, &env),
+                &env,
+                &[],
+                QueryMsg::UnbondSchedule {
+                    account: "bob".to_owned(),
+                    start_after,
+                    limit,
+                },
+            )
+            .and_then(cosmwasm_std::from_json)
+            .unwrap()
+        };
+
+        check(
+            unbond_schedule(None, None),
+            expect![[r#"
+            (
+              entries: [
+                (
+                  batch_id: 1,
+                  amount: "95",
+                  unbond_started_at: Some(1571797417),
+                  estimated_claimable_at: Some(1571797419),
+                  status: matured,
+                ),
+                (
+                  batch_id: 2,
+                  amount: "95",
+                  unbond_started_at: Some(1571797418),
+                  estimated_claimable_at: Some(1571797420),
+                  status: active,
+                ),
+                (
+                  batch_id: 3,
+                  amount: "100",
+                  unbond_started_at: Some(1571797429),
+                  estimated_claimable_at: None,
+                  status: pending,
+                ),
+              ],
+            )"#]],
+        );
+
+        check(
+            unbond_schedule(Some(1), Some(1)),
+            expect![[r#"
+            (
+              entries: [
+                (
+                  batch_id: 2,
+                  amount: "95",
+                  unbond_started_at: Some(1571797418),
+                  estimated_claimable_at: Some(1571797420),
+                  status: active,
+                ),
+              ],
+            )"#]],
+        );
+
+        assert_eq!(
+            unbond_schedule(Some(3), None),
+            UnbondScheduleResponse::default()
+        );
+
+        // starting after a claimed batch resumes from the first unclaimed one
+        assert_eq!(unbond_schedule(Some(0), None), unbond_schedule(None, None));
+
+        // an excessive limit is capped rather than rejected
+        assert_eq!(
+            unbond_schedule(None, Some(u32::MAX)),
+            unbond_schedule(None, None)
+        );
+    }
+
+    #[test]
+    fn deposit_secondary_asset() {
+        use accepted_assets::{
+            AcceptedAssets, ConversionOracleQueryMsg, ConversionRateResponse, StorageExt as _,
+        };
+
+        const SECONDARY_ASSET: &str = "secondary_asset";
+        const CONVERSION_ORACLE: &str = "conversion_oracle";
+
+        let mut deps = testing::mock_dependencies();
+
+        deps.querier.update_wasm(|query| {
+            let cosmwasm_std::WasmQuery::Smart { msg, contract_addr } = query else {
+                panic!("unexpected wasm query: {query:?}");
+            };
+
+            assert_eq!(contract_addr, CONVERSION_ORACLE);
+
+            let ConversionOracleQueryMsg::ConversionRate { denom } =
+                cosmwasm_std::from_json(msg).unwrap();
+
+            assert_eq!(denom, SECONDARY_ASSET);
+
+            let rate = cosmwasm_std::Decimal::from_ratio(3u128, 2u128);
+
+            cosmwasm_std::SystemResult::Ok(cosmwasm_std::ContractResult::Ok(
+                to_json_binary(&ConversionRateResponse { rate }).unwrap(),
+            ))
+        });
+
+        deps.storage
+            .set_accepted_asset(SECONDARY_ASSET, CONVERSION_ORACLE);
+
+        let env = testing::mock_env();
+
+        let accepted_assets = AcceptedAssets::query(&deps.storage, deps.as_ref().querier).unwrap();
+
+        let strategy = MockStrategy {
+            env: &env,
+            deposits: 0,
+            unbonds: 0,
+            accepted_assets: Some(&accepted_assets),
+        };
+
+        let (cmds, response) = handle_execute_msg::<Empty>(
+            &strategy,
+            &unbonding_log::UnbondingLog::new(&deps.storage),
+            &mint::SharesMint::new(&deps.storage, &env),
+            testing::mock_info("account", &coins(1_000_000, SECONDARY_ASSET)),
+            ExecuteMsg::Deposit {},
+        )
+        .unwrap();
+
+        check(
+            cosmwasm_std::from_json::<DepositResponse>(response.data.unwrap()).unwrap(),
+            expect![[r#"
+                (
+                  total_shares_issued: "1500000000000000000",
+                  total_deposits_value: "1500000",
+                  minted_shares: "1500000000000000000",
+                  deposit_value: "1500000",
+                )"#]],
+        );
+
+        let err = handle_execute_msg::<Empty>(
+            &strategy,
+            &unbonding_log::UnbondingLog::new(&deps.storage),
+            &mint::SharesMint::new(&deps.storage, &env),
+            testing::mock_info("account", &coins(1_000_000, "unknown_asset")),
+            ExecuteMsg::Deposit {},
+        )
+        .unwrap_err();
+
+        check_err(err, expect!["invalid deposit asset"]);
+
+        for cmd in cmds {
+            if let Cmd::Strategy(StrategyCmd::DepositSecondary { asset, amount }) = cmd {
+                accepted_assets::handle_deposit_secondary(&mut deps.storage, &asset, amount);
+            }
+        }
+
+        assert_eq!(deps.storage.secondary_balance(SECONDARY_ASSET), 1_000_000);
+
+        let accepted_deposit_assets: AcceptedDepositAssetsResponse = handle_query_msg(
+            &deps.storage,
+            &MockStrategy {
+                env: &env,
+                deposits: 0,
+                unbonds: 0,
+                accepted_assets: None,
+            },
+            &unbonding_log::UnbondingLog::new(&deps.storage),
+            &mint::SharesMint::new(&deps.storage, &env),
+            &env,
+            &[],
+            QueryMsg::AcceptedDepositAssets {},
+        )
+        .and_then(cosmwasm_std::from_json)
+        .unwrap();
+
+        check(
+            accepted_deposit_assets,
+            expect![[r#"
+            (
+              primary: "deposit_asset",
+              accepted: [
+                (
+                  denom: "secondary_asset",
+                  conversion: "conversion_oracle",
+                ),
+              ],
+            )"#]],
+        );
+
+        // an asset still held by the vault could no longer be valued
+        assert!(matches!(
+            deps.storage.remove_accepted_asset(SECONDARY_ASSET),
+            Err(accepted_assets::AcceptedAssetError::BalanceOutstanding { .. })
+        ));
+
+        deps.storage.set_secondary_balance(SECONDARY_ASSET, 0);
+
+        deps.storage.remove_accepted_asset(SECONDARY_ASSET).unwrap();
+
+        assert!(deps.storage.accepted_assets().is_empty());
+        assert_eq!(deps.storage.conversion_oracle(SECONDARY_ASSET), None);
+    }
+
     impl TokenFactory<Empty> for MockTokenFactory {
         fn denom(&self, ticker: &amulet_core::mint::Ticker) -> String {
             format!("factory/mint/{}", ticker.as_str())
@@ -822,7 +1419,9 @@ mod test {
         fn set_metadata(
             &self,
             _ticker: &amulet_core::mint::Ticker,
+            _display_ticker: &amulet_core::mint::Ticker,
             _decimals: Decimals,
+            _description: &str,
         ) -> cosmwasm_std::CosmosMsg<Empty> {
             cosmwasm_std::CosmosMsg::Custom(Empty {})
         }
@@ -843,5 +1442,13 @@ mod test {
         ) -> cosmwasm_std::CosmosMsg<Empty> {
             cosmwasm_std::CosmosMsg::Custom(Empty {})
         }
+
+        fn set_before_send_hook(
+            &self,
+            _ticker: &amulet_core::mint::Ticker,
+            _contract_addr: &str,
+        ) -> cosmwasm_std::CosmosMsg<Empty> {
+            cosmwasm_std::CosmosMsg::Custom(Empty {})
+        }
     }
 }
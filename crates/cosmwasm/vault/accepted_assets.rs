@@ -0,0 +1,264 @@
+use amulet_core::{vault::DepositAmount, Asset};
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{CustomQuery, Decimal, Fraction, QuerierWrapper, StdError, Storage, Uint128};
+
+use crate::StorageExt as _;
+
+#[rustfmt::skip]
+mod key {
+    use crate::MapKey;
+
+    macro_rules! key {
+        ($k:literal) => {
+            concat!("vault_accepted_assets::", $k)
+        };
+    }
+
+    macro_rules! map_key {
+        ($k:literal) => {
+            crate::MapKey::new(key!($k))
+        };
+    }
+
+    pub const ACCEPTED_ASSET_COUNT : &str   = key!("accepted_asset_count");
+    pub const ACCEPTED_ASSET_DENOM : MapKey = map_key!("accepted_asset_denom");
+    pub const CONVERSION_ORACLE    : MapKey = map_key!("conversion_oracle");
+    pub const SECONDARY_BALANCE    : MapKey = map_key!("secondary_balance");
+}
+
+/// A secondary asset accepted for deposit alongside the vault's primary deposit asset
+#[cw_serde]
+pub struct AcceptedAsset {
+    /// The denom of the secondary asset
+    pub denom: String,
+    /// The oracle used to convert the secondary asset into the primary deposit asset
+    pub conversion: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AcceptedAssetError {
+    #[error("{denom} is not an accepted deposit asset")]
+    NotAccepted { denom: String },
+
+    #[error("{denom} is still held by the vault")]
+    BalanceOutstanding { denom: String },
+}
+
+#[cw_serde]
+pub struct ConversionRateResponse {
+    /// The amount of the primary deposit asset equal in value to one unit of the secondary asset
+    pub rate: Decimal,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum ConversionOracleQueryMsg {
+    /// Returns the rate at which the `denom` converts into the vault's primary deposit asset
+    #[returns(ConversionRateResponse)]
+    ConversionRate { denom: String },
+}
+
+pub trait StorageExt: Storage {
+    /// Returns all the accepted secondary assets
+    fn accepted_assets(&self) -> Vec<AcceptedAsset> {
+        let count = self.u32_at(key::ACCEPTED_ASSET_COUNT).unwrap_or_default();
+
+        (0..count)
+            .map(|idx| {
+                let denom = self
+                    .string_at(key::ACCEPTED_ASSET_DENOM.with(idx))
+                    .expect("always: denom stored for every index < count");
+
+                let conversion = self
+                    .conversion_oracle(&denom)
+                    .expect("always: conversion oracle stored for every accepted asset");
+
+                AcceptedAsset { denom, conversion }
+            })
+            .collect()
+    }
+
+    /// Returns the conversion oracle of the `denom` if it is an accepted secondary asset
+    fn conversion_oracle(&self, denom: &str) -> Option<String> {
+        self.string_at(key::CONVERSION_ORACLE.with(denom))
+    }
+
+    /// Returns the raw balance of secondary asset `denom` deposited into the vault
+    fn secondary_balance(&self, denom: &str) -> u128 {
+        self.u128_at(key::SECONDARY_BALANCE.with(denom))
+            .unwrap_or_default()
+    }
+
+    /// Accept the `denom` as a secondary deposit asset using the `conversion` oracle,
+    /// replacing the conversion oracle if the `denom` is already accepted
+    fn set_accepted_asset(&mut self, denom: &str, conversion: &str) {
+        if self.conversion_oracle(denom).is_none() {
+            let count = self.u32_at(key::ACCEPTED_ASSET_COUNT).unwrap_or_default();
+
+            self.set_string(key::ACCEPTED_ASSET_DENOM.with(count), denom);
+
+            self.set_u32(key::ACCEPTED_ASSET_COUNT, count + 1);
+        }
+
+        self.set_string(key::CONVERSION_ORACLE.with(denom), conversion);
+    }
+
+    /// Stop accepting the `denom` as a secondary deposit asset.
+    /// Only possible once none of the `denom` is held by the vault, as it could no longer be valued.
+    fn remove_accepted_asset(&mut self, denom: &str) -> Result<(), AcceptedAssetError> {
+        if self.conversion_oracle(denom).is_none() {
+            return Err(AcceptedAssetError::NotAccepted {
+                denom: denom.to_owned(),
+            });
+        }
+
+        if self.secondary_balance(denom) != 0 {
+            return Err(AcceptedAssetError::BalanceOutstanding {
+                denom: denom.to_owned(),
+            });
+        }
+
+        let count = self
+            .u32_at(key::ACCEPTED_ASSET_COUNT)
+            .expect("always: count stored if an asset is accepted");
+
+        let idx = (0..count)
+            .find(|idx| {
+                self.string_at(key::ACCEPTED_ASSET_DENOM.with(idx))
+                    .is_some_and(|d| d == denom)
+            })
+            .expect("always: accepted asset has an index");
+
+        let last_idx = count - 1;
+
+        // swap the last denom into the removed slot
+        if idx != last_idx {
+            let last_denom = self
+                .string_at(key::ACCEPTED_ASSET_DENOM.with(last_idx))
+                .expect("always: denom stored for every index < count");
+
+            self.set_string(key::ACCEPTED_ASSET_DENOM.with(idx), &last_denom);
+        }
+
+        self.remove(key::ACCEPTED_ASSET_DENOM.with(last_idx).as_bytes());
+        self.remove(key::CONVERSION_ORACLE.with(denom).as_bytes());
+        self.set_u32(key::ACCEPTED_ASSET_COUNT, last_idx);
+
+        Ok(())
+    }
+
+    fn set_secondary_balance(&mut self, denom: &str, balance: u128) {
+        self.set_u128(key::SECONDARY_BALANCE.with(denom), balance);
+    }
+}
+
+impl<T> StorageExt for T where T: Storage + ?Sized {}
+
+/// The rate at which an accepted secondary asset converts into the primary deposit asset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionRate {
+    pub denom: String,
+    /// The amount of the primary deposit asset equal in value to one unit of the secondary asset
+    pub rate: Decimal,
+}
+
+fn apply_ratio(amount: u128, numerator: Uint128, denominator: Uint128) -> u128 {
+    let Ok(result) = Uint128::new(amount).checked_multiply_ratio(numerator, denominator) else {
+        panic!("converting {amount} at {numerator}/{denominator} overflowed");
+    };
+
+    result.u128()
+}
+
+impl ConversionRate {
+    /// Returns the `amount` of the secondary asset converted into the primary deposit asset, rounded down
+    pub fn to_primary(&self, amount: u128) -> u128 {
+        apply_ratio(amount, self.rate.numerator(), self.rate.denominator())
+    }
+
+    /// Returns the `amount` of the primary deposit asset converted into the secondary asset, rounded down
+    pub fn to_secondary(&self, amount: u128) -> u128 {
+        if self.rate.is_zero() {
+            return 0;
+        }
+
+        apply_ratio(amount, self.rate.denominator(), self.rate.numerator())
+    }
+}
+
+/// Converts deposits of accepted secondary assets at the rates reported by their conversion oracles
+#[derive(Debug, Clone, Default)]
+pub struct AcceptedAssets {
+    rates: Vec<ConversionRate>,
+}
+
+impl AcceptedAssets {
+    /// Query the current conversion rate of every accepted secondary asset
+    pub fn query(
+        storage: &dyn Storage,
+        querier: QuerierWrapper<impl CustomQuery>,
+    ) -> Result<Self, StdError> {
+        let querier = querier.into_empty();
+
+        let rates = storage
+            .accepted_assets()
+            .into_iter()
+            .map(|AcceptedAsset { denom, conversion }| {
+                let ConversionRateResponse { rate } = querier.query_wasm_smart(
+                    conversion,
+                    &ConversionOracleQueryMsg::ConversionRate {
+                        denom: denom.clone(),
+                    },
+                )?;
+
+                Ok(ConversionRate { denom, rate })
+            })
+            .collect::<Result<_, StdError>>()?;
+
+        Ok(Self { rates })
+    }
+
+    /// Returns the conversion rates of the accepted secondary assets
+    pub fn rates(&self) -> &[ConversionRate] {
+        &self.rates
+    }
+
+    /// Returns the `amount` of the secondary `asset` converted into the primary deposit asset,
+    /// or `None` if the `asset` is not accepted for deposit
+    pub fn convert(
+        &self,
+        asset: &Asset,
+        DepositAmount(amount): DepositAmount,
+    ) -> Option<DepositAmount> {
+        self.rates
+            .iter()
+            .find(|rate| rate.denom == asset.as_str())
+            .map(|rate| DepositAmount(rate.to_primary(amount)))
+    }
+
+    /// Returns the vault's secondary balances converted into the primary deposit asset
+    pub fn total_converted_balance(&self, storage: &dyn Storage) -> u128 {
+        self.rates
+            .iter()
+            .map(|rate| rate.to_primary(storage.secondary_balance(&rate.denom)))
+            .fold(0u128, |total, converted| {
+                total
+                    .checked_add(converted)
+                    .expect("converted secondary balances should never overflow")
+            })
+    }
+}
+
+/// Record a deposit of an `amount` of the secondary `asset`
+pub fn handle_deposit_secondary(
+    storage: &mut dyn Storage,
+    asset: &Asset,
+    DepositAmount(amount): DepositAmount,
+) {
+    let balance = storage
+        .secondary_balance(asset)
+        .checked_add(amount)
+        .expect("secondary balance should never overflow");
+
+    storage.set_secondary_balance(asset, balance);
+}
@@ -0,0 +1,311 @@
+//! Balances owed to an account that become claimable once the unbonding batch they were added to matures.
+//!
+//! Amounts are added against an account & batch, the batch is matured once its unbonding completes &
+//! the account then claims every matured amount at once. All arithmetic is checked, returning an
+//! [`Error`] rather than saturating so that accounting errors are surfaced instead of hidden.
+use amulet_core::vault::BatchId;
+use cosmwasm_std::{Order, Storage};
+
+use crate::StorageExt as _;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("claimable balance overflow")]
+    Overflow,
+    #[error("batch {0} already matured")]
+    AlreadyMatured(BatchId),
+    #[error("nothing to claim")]
+    NothingToClaim,
+}
+
+#[rustfmt::skip]
+mod key {
+    use crate::MapKey;
+
+    macro_rules! key {
+        ($k:literal) => {
+            concat!("claims::", $k)
+        };
+    }
+
+    macro_rules! map_key {
+        ($k:literal) => {
+            crate::MapKey::new(key!($k))
+        };
+    }
+
+    pub const CLAIMABLE_IN_BATCH : MapKey = map_key!("claimable_in_batch");
+    pub const UNCLAIMED_TOTAL    : MapKey = map_key!("unclaimed_total");
+    pub const MATURED_BATCH      : MapKey = map_key!("matured_batch");
+}
+
+pub trait StorageExt: Storage {
+    /// The amount added for the account in the batch that is yet to be claimed
+    fn claimable_in_batch(&self, account: &str, batch: BatchId) -> u128 {
+        self.u128_at(key::CLAIMABLE_IN_BATCH.multi([&account, &batch]))
+            .unwrap_or_default()
+    }
+
+    /// The total amount added for the account that is yet to be claimed, matured or not
+    fn unclaimed_total(&self, account: &str) -> u128 {
+        self.u128_at(key::UNCLAIMED_TOTAL.with(account))
+            .unwrap_or_default()
+    }
+
+    fn is_matured(&self, batch: BatchId) -> bool {
+        self.bool_at(key::MATURED_BATCH.with(batch))
+            .unwrap_or_default()
+    }
+
+    /// The account's unclaimed amounts in batch order, whether matured or not
+    fn unclaimed_batches(&self, account: &str) -> Vec<(BatchId, u128)> {
+        let prefix = key::CLAIMABLE_IN_BATCH.with(account);
+
+        // the first key after every key with the prefix, replacing its ':' terminator with ';'
+        let mut end = prefix.clone().into_bytes();
+        *end.last_mut().expect("always: keys are ':' terminated") = b';';
+
+        // batch IDs are keyed as decimal strings, so the key order is not the batch order
+        let mut batches: Vec<_> = self
+            .range_keys(Some(prefix.as_bytes()), Some(&end), Order::Ascending)
+            .map(|key| {
+                std::str::from_utf8(&key[prefix.len()..])
+                    .expect("always: keys are valid utf-8")
+                    .strip_suffix(':')
+                    .and_then(|batch| batch.parse().ok())
+                    .expect("always: batch IDs are ':' terminated integers")
+            })
+            .map(|batch| (batch, self.claimable_in_batch(account, batch)))
+            .collect();
+
+        batches.sort_unstable_by_key(|(batch, _)| *batch);
+
+        batches
+    }
+
+    /// The total amount the account could claim now
+    fn claimable(&self, account: &str) -> Result<u128, Error> {
+        self.unclaimed_batches(account)
+            .into_iter()
+            .filter(|(batch, _)| self.is_matured(*batch))
+            .try_fold(0u128, |total, (_, amount)| {
+                total.checked_add(amount).ok_or(Error::Overflow)
+            })
+    }
+
+    /// Add `amount` to the account's balance in the batch, the batch must not have matured yet
+    fn add_claimable(&mut self, account: &str, batch: BatchId, amount: u128) -> Result<(), Error> {
+        if self.is_matured(batch) {
+            return Err(Error::AlreadyMatured(batch));
+        }
+
+        let in_batch = self
+            .claimable_in_batch(account, batch)
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+
+        let unclaimed_total = self
+            .unclaimed_total(account)
+            .checked_add(amount)
+            .ok_or(Error::Overflow)?;
+
+        self.set_u128(key::CLAIMABLE_IN_BATCH.multi([&account, &batch]), in_batch);
+        self.set_u128(key::UNCLAIMED_TOTAL.with(account), unclaimed_total);
+
+        Ok(())
+    }
+
+    /// Mark the batch as matured, making any amounts added in it claimable
+    fn mature(&mut self, batch: BatchId) -> Result<(), Error> {
+        if self.is_matured(batch) {
+            return Err(Error::AlreadyMatured(batch));
+        }
+
+        self.set_bool(key::MATURED_BATCH.with(batch), true);
+
+        Ok(())
+    }
+
+    /// Claim the account's balance in every matured batch, returning the total amount claimed
+    fn claim(&mut self, account: &str) -> Result<u128, Error> {
+        let unclaimed_batches = self.unclaimed_batches(account);
+
+        let mut claimed = 0u128;
+
+        for (batch, amount) in unclaimed_batches {
+            if !self.is_matured(batch) {
+                continue;
+            }
+
+            claimed = claimed.checked_add(amount).ok_or(Error::Overflow)?;
+
+            self.remove(key::CLAIMABLE_IN_BATCH.multi([&account, &batch]).as_bytes());
+        }
+
+        if claimed == 0 {
+            return Err(Error::NothingToClaim);
+        }
+
+        let unclaimed_total = self
+            .unclaimed_total(account)
+            .checked_sub(claimed)
+            .ok_or(Error::Overflow)?;
+
+        self.set_u128(key::UNCLAIMED_TOTAL.with(account), unclaimed_total);
+
+        Ok(claimed)
+    }
+}
+
+impl<T> StorageExt for T where T: Storage + ?Sized {}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::testing::MockStorage;
+
+    use super::*;
+
+    const ALICE: &str = "alice";
+    const BOB: &str = "bob";
+
+    #[test]
+    fn claim_before_mature_errs() {
+        let mut storage = MockStorage::new();
+
+        storage.add_claimable(ALICE, 0, 100).unwrap();
+
+        assert_eq!(storage.claimable(ALICE), Ok(0));
+        assert_eq!(storage.claim(ALICE), Err(Error::NothingToClaim));
+        assert_eq!(storage.unclaimed_total(ALICE), 100);
+    }
+
+    #[test]
+    fn claim_without_balance_errs() {
+        let mut storage = MockStorage::new();
+
+        assert_eq!(storage.claim(ALICE), Err(Error::NothingToClaim));
+
+        storage.mature(0).unwrap();
+
+        assert_eq!(storage.claim(ALICE), Err(Error::NothingToClaim));
+    }
+
+    #[test]
+    fn double_mature_errs() {
+        let mut storage = MockStorage::new();
+
+        storage.mature(0).unwrap();
+
+        assert_eq!(storage.mature(0), Err(Error::AlreadyMatured(0)));
+    }
+
+    #[test]
+    fn add_to_matured_batch_errs() {
+        let mut storage = MockStorage::new();
+
+        storage.mature(0).unwrap();
+
+        assert_eq!(
+            storage.add_claimable(ALICE, 0, 100),
+            Err(Error::AlreadyMatured(0))
+        );
+    }
+
+    #[test]
+    fn add_overflow_errs() {
+        let mut storage = MockStorage::new();
+
+        storage.add_claimable(ALICE, 0, u128::MAX).unwrap();
+
+        // overflows the batch balance
+        assert_eq!(storage.add_claimable(ALICE, 0, 1), Err(Error::Overflow));
+
+        // overflows the unclaimed total
+        assert_eq!(storage.add_claimable(ALICE, 1, 1), Err(Error::Overflow));
+
+        // nothing was written by the failed adds
+        assert_eq!(storage.unclaimed_batches(ALICE), vec![(0, u128::MAX)]);
+        assert_eq!(storage.unclaimed_total(ALICE), u128::MAX);
+    }
+
+    #[test]
+    fn claim_once_matured() {
+        let mut storage = MockStorage::new();
+
+        storage.add_claimable(ALICE, 0, 100).unwrap();
+        storage.add_claimable(ALICE, 0, 50).unwrap();
+        storage.mature(0).unwrap();
+
+        assert_eq!(storage.claimable(ALICE), Ok(150));
+        assert_eq!(storage.claim(ALICE), Ok(150));
+        assert_eq!(storage.unclaimed_total(ALICE), 0);
+        assert_eq!(storage.unclaimed_batches(ALICE), vec![]);
+
+        // claims are only paid once
+        assert_eq!(storage.claim(ALICE), Err(Error::NothingToClaim));
+    }
+
+    #[test]
+    fn multiple_batches_per_account() {
+        let mut storage = MockStorage::new();
+
+        storage.add_claimable(ALICE, 0, 100).unwrap();
+        storage.add_claimable(ALICE, 2, 200).unwrap();
+        storage.add_claimable(ALICE, 3, 300).unwrap();
+
+        assert_eq!(
+            storage.unclaimed_batches(ALICE),
+            vec![(0, 100), (2, 200), (3, 300)]
+        );
+
+        // maturity need not be in order
+        storage.mature(0).unwrap();
+        storage.mature(3).unwrap();
+
+        assert_eq!(storage.claim(ALICE), Ok(400));
+        assert_eq!(storage.unclaimed_batches(ALICE), vec![(2, 200)]);
+        assert_eq!(storage.unclaimed_total(ALICE), 200);
+
+        // further amounts added to later batches
+        storage.add_claimable(ALICE, 4, 400).unwrap();
+        storage.mature(2).unwrap();
+
+        assert_eq!(storage.claim(ALICE), Ok(200));
+        assert_eq!(storage.unclaimed_batches(ALICE), vec![(4, 400)]);
+
+        storage.mature(4).unwrap();
+
+        assert_eq!(storage.claim(ALICE), Ok(400));
+        assert_eq!(storage.unclaimed_total(ALICE), 0);
+    }
+
+    #[test]
+    fn unclaimed_batches_in_batch_order() {
+        let mut storage = MockStorage::new();
+
+        for batch in [10, 2, 100, 9] {
+            storage.add_claimable(ALICE, batch, 1).unwrap();
+        }
+
+        assert_eq!(
+            storage.unclaimed_batches(ALICE),
+            vec![(2, 1), (9, 1), (10, 1), (100, 1)]
+        );
+    }
+
+    #[test]
+    fn accounts_claim_independently() {
+        let mut storage = MockStorage::new();
+
+        storage.add_claimable(ALICE, 0, 100).unwrap();
+        storage.add_claimable(BOB, 0, 10).unwrap();
+        storage.add_claimable(BOB, 1, 20).unwrap();
+        storage.mature(0).unwrap();
+
+        assert_eq!(storage.claim(ALICE), Ok(100));
+        assert_eq!(storage.claimable(BOB), Ok(10));
+        assert_eq!(storage.claim(BOB), Ok(10));
+        assert_eq!(storage.unclaimed_batches(BOB), vec![(1, 20)]);
+        assert_eq!(storage.unclaimed_total(BOB), 20);
+    }
+}
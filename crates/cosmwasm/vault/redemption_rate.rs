@@ -0,0 +1,348 @@
+//! Share redemption rate observations, so that integrations can price shares with a time-weighted average
+//! rather than the spot rate, which jumps whenever rewards are accounted for.
+//!
+//! Observations are recorded by the vault when its rate settles, e.g. on completing a reconcile,
+//! and only the most recent [MAX_OBSERVATIONS] are retained in the vault's redemption rate event log.
+use amulet_core::{
+    vault::{
+        offset_total_deposits_value, SharesMint, Strategy, TotalDepositsValue, TotalSharesIssued,
+        UnbondingLog,
+    },
+    Decimals,
+};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Storage, Uint256};
+use num::{FixedU256, U256};
+
+use crate::event_log::{EventLog, MAX_QUERY_LIMIT};
+
+/// The number of observations retained
+pub const MAX_OBSERVATIONS: u64 = 64;
+
+const OBSERVATIONS: EventLog = EventLog::with_default_capacity("redemption_rate", MAX_OBSERVATIONS);
+
+// An observation as appended to the event log, the rate being the raw fixed point value
+#[cw_serde]
+struct RecordedObservation {
+    timestamp: u64,
+    rate: Uint256,
+}
+
+#[cw_serde]
+pub struct RedemptionRateResponse {
+    /// The current deposit value of a single share, `None` if no shares have been issued
+    pub spot: Option<String>,
+    /// The time-weighted average of the observed rates over the requested window,
+    /// `None` if not requested or no rates have been observed
+    pub twap: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observation {
+    pub timestamp: u64,
+    pub rate: FixedU256,
+}
+
+/// Returns the deposit value of a single share, adjusted for the decimals of each,
+/// or `None` if no shares have been issued
+pub fn spot_rate(
+    TotalDepositsValue(total_deposits): TotalDepositsValue,
+    TotalSharesIssued(total_shares): TotalSharesIssued,
+    deposit_decimals: Decimals,
+    shares_decimals: Decimals,
+) -> Option<FixedU256> {
+    if total_shares == 0 {
+        return None;
+    }
+
+    let scale = |decimals: Decimals| 10u128.checked_pow(decimals).map(FixedU256::from_u128);
+
+    let mut deposits = FixedU256::from_u128(total_deposits);
+
+    let mut shares = FixedU256::from_u128(total_shares);
+
+    // scale before dividing so that no precision is lost
+    if shares_decimals >= deposit_decimals {
+        deposits = deposits.checked_mul(scale(shares_decimals - deposit_decimals)?)?;
+    } else {
+        shares = shares.checked_mul(scale(deposit_decimals - shares_decimals)?)?;
+    }
+
+    deposits.checked_div(shares)
+}
+
+/// Returns the current redemption rate of the vault, see [spot_rate]
+pub fn current_rate(
+    strategy: &dyn Strategy,
+    unbonding_log: &dyn UnbondingLog,
+    mint: &dyn SharesMint,
+) -> Option<FixedU256> {
+    spot_rate(
+        offset_total_deposits_value(strategy, unbonding_log),
+        mint.total_shares_issued(),
+        strategy.underlying_asset_decimals(),
+        mint.shares_decimals(),
+    )
+}
+
+/// Record the rate observed at block `height` & `timestamp`,
+/// evicting the oldest observation once [MAX_OBSERVATIONS] are retained
+pub fn record(storage: &mut dyn Storage, height: u64, timestamp: u64, rate: FixedU256) {
+    let observation = RecordedObservation {
+        timestamp,
+        rate: Uint256::from_be_bytes(rate.into_raw().to_be_bytes()),
+    };
+
+    OBSERVATIONS
+        .append(storage, height, &[observation])
+        .expect("always: observation serializes");
+}
+
+/// Returns the retained observations, oldest first
+pub fn observations(storage: &dyn Storage) -> Vec<Observation> {
+    let mut observations = vec![];
+
+    let mut start_after = None;
+
+    loop {
+        let entries = OBSERVATIONS
+            .query(storage, start_after, Some(MAX_QUERY_LIMIT))
+            .entries;
+
+        let Some(last) = entries.last() else {
+            return observations;
+        };
+
+        start_after = Some(last.id);
+
+        observations.extend(entries.iter().map(|entry| {
+            let RecordedObservation { timestamp, rate } = entry
+                .parse_event()
+                .expect("always: observation stored for retained entries");
+
+            Observation {
+                timestamp,
+                rate: FixedU256::raw(U256::from_be_bytes(rate.to_be_bytes())),
+            }
+        }));
+    }
+}
+
+/// Returns the time-weighted average rate over the `window_secs` up to `now`, where each observed rate
+/// holds until the next observation. Any part of the window before the first retained observation is excluded.
+/// If the window covers no time, the rate in effect at `now` is returned.
+/// Returns `None` if no rates have been observed by `now`.
+pub fn twap(storage: &dyn Storage, now: u64, window_secs: u64) -> Option<FixedU256> {
+    let observations: Vec<_> = observations(storage)
+        .into_iter()
+        .filter(|observation| observation.timestamp <= now)
+        .collect();
+
+    let latest = observations.last()?.rate;
+
+    let window_start = now.saturating_sub(window_secs);
+
+    let mut weighted_sum = FixedU256::zero();
+
+    let mut total_secs = 0u64;
+
+    for (idx, observation) in observations.iter().enumerate() {
+        let held_until = observations.get(idx + 1).map_or(now, |next| next.timestamp);
+
+        let held_secs = held_until.saturating_sub(observation.timestamp.max(window_start));
+
+        if held_secs == 0 {
+            continue;
+        }
+
+        weighted_sum = observation
+            .rate
+            .checked_mul(FixedU256::from_u128(held_secs.into()))
+            .and_then(|weighted| weighted_sum.checked_add(weighted))
+            .expect("always: rate * seconds < u128::MAX");
+
+        total_secs += held_secs;
+    }
+
+    if total_secs == 0 {
+        return Some(latest);
+    }
+
+    weighted_sum.checked_div(FixedU256::from_u128(total_secs.into()))
+}
+
+/// Build the response for a redemption rate query, with the average over `twap_secs` if requested
+pub fn query(
+    storage: &dyn Storage,
+    spot: Option<FixedU256>,
+    now: u64,
+    twap_secs: Option<u64>,
+) -> RedemptionRateResponse {
+    RedemptionRateResponse {
+        spot: spot.as_ref().map(ToString::to_string),
+        twap: twap_secs
+            .and_then(|window_secs| twap(storage, now, window_secs))
+            .as_ref()
+            .map(ToString::to_string),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::testing::MockStorage;
+    use test_utils::prelude::*;
+
+    use super::*;
+
+    fn rate(numer: u128, denom: u128) -> FixedU256 {
+        FixedU256::from_u128(numer)
+            .checked_div(FixedU256::from_u128(denom))
+            .unwrap()
+    }
+
+    #[test]
+    fn spot_rate_adjusts_for_decimals() {
+        let spot = |deposits, shares, deposit_decimals, shares_decimals| {
+            spot_rate(
+                TotalDepositsValue(deposits),
+                TotalSharesIssued(shares),
+                deposit_decimals,
+                shares_decimals,
+            )
+            .map(|rate| rate.to_string())
+        };
+
+        check(
+            vec![
+                spot(0, 0, 6, 18),
+                spot(1_000, 0, 6, 18),
+                spot(0, 1_000, 6, 18),
+                spot(1_000_000, 10u128.pow(18), 6, 18),
+                spot(1_050_000, 10u128.pow(18), 6, 18),
+                spot(3, 2, 6, 6),
+                spot(10u128.pow(18), 1_000_000, 18, 6),
+            ],
+            expect![[r#"
+                [
+                  None,
+                  None,
+                  Some("0.0"),
+                  Some("1.0"),
+                  Some("1.04999999999999999999999999999999"),
+                  Some("1.5"),
+                  Some("1.0"),
+                ]"#]],
+        );
+    }
+
+    #[test]
+    fn twap_of_recorded_observations() {
+        let mut storage = MockStorage::default();
+
+        assert_eq!(twap(&storage, 1_000, 100), None);
+
+        record(&mut storage, 1, 100, rate(1, 1));
+        record(&mut storage, 1, 200, rate(11, 10));
+        record(&mut storage, 1, 300, rate(12, 10));
+
+        check(
+            observations(&storage)
+                .iter()
+                .map(|o| (o.timestamp, o.rate.to_string()))
+                .collect::<Vec<_>>(),
+            expect![[r#"
+                [
+                  (100, "1.0"),
+                  (200, "1.09999999999999999999999999999999"),
+                  (300, "1.19999999999999999999999999999999"),
+                ]"#]],
+        );
+
+        let twap = |now, window_secs| twap(&storage, now, window_secs).unwrap().to_string();
+
+        check(
+            vec![
+                // only the latest rate is in effect over the window
+                twap(400, 100),
+                // 100s at 1.1 & 100s at 1.2
+                twap(400, 200),
+                // 100s at 1.0, 100s at 1.1 & 100s at 1.2
+                twap(400, 300),
+                // the window before the first observation is excluded
+                twap(400, 10_000),
+                // 100s at 1.0 & 50s at 1.1
+                twap(250, 200),
+                // observations after `now` are ignored
+                twap(150, 100),
+                // a window covering no time takes the rate in effect
+                twap(300, 0),
+                twap(250, 0),
+            ],
+            expect![[r#"
+                [
+                  "1.19999999999999999999999999999999",
+                  "1.14999999999999999999999999999999",
+                  "1.09999999999999999999999999999999",
+                  "1.09999999999999999999999999999999",
+                  "1.03333333333333333333333333333333",
+                  "1.0",
+                  "1.19999999999999999999999999999999",
+                  "1.09999999999999999999999999999999",
+                ]"#]],
+        );
+    }
+
+    #[test]
+    fn observation_history_is_bounded() {
+        let mut storage = MockStorage::default();
+
+        for n in 0..MAX_OBSERVATIONS + 10 {
+            record(&mut storage, 1, n * 10, FixedU256::from_u128(n.into()));
+        }
+
+        let observations = observations(&storage);
+
+        assert_eq!(observations.len() as u64, MAX_OBSERVATIONS);
+        assert_eq!(observations[0].timestamp, 100);
+        assert_eq!(
+            observations.last().unwrap().timestamp,
+            (MAX_OBSERVATIONS + 9) * 10
+        );
+    }
+
+    #[test]
+    fn query_response() {
+        let mut storage = MockStorage::default();
+
+        // empty vault
+        check(
+            query(&storage, None, 1_000, Some(100)),
+            expect![[r#"
+                (
+                  spot: None,
+                  twap: None,
+                )"#]],
+        );
+
+        record(&mut storage, 1, 100, rate(1, 1));
+        record(&mut storage, 1, 200, rate(3, 2));
+
+        check(
+            query(&storage, Some(rate(3, 2)), 300, None),
+            expect![[r#"
+                (
+                  spot: Some("1.5"),
+                  twap: None,
+                )"#]],
+        );
+
+        check(
+            query(&storage, Some(rate(3, 2)), 300, Some(200)),
+            expect![[r#"
+                (
+                  spot: Some("1.5"),
+                  twap: Some("1.25"),
+                )"#]],
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Coin, CosmosMsg, Env, Storage};
+
+use crate::{mint::TokenFactory, StorageExt as _};
+
+use super::mint::SHARES_DENOM;
+
+#[rustfmt::skip]
+mod key {
+    macro_rules! key {
+        ($k:literal) => {
+            concat!("vault_shares_transfer::", $k)
+        };
+    }
+
+    pub const RESTRICTED : &str = key!("restricted");
+    pub const HUB        : &str = key!("hub");
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("shares transfers are restricted to the hub and vault")]
+    TransferRestricted,
+}
+
+/// Sudo messages sent by the token factory module for the shares denom
+#[cw_serde]
+pub enum SudoMsg {
+    /// Invoked before any transfer of the shares denom, rejecting it if it returns an error
+    BlockBeforeSend {
+        from: String,
+        to: String,
+        amount: Coin,
+    },
+}
+
+pub trait StorageExt: Storage {
+    /// Returns true if shares may only be transferred to or from the hub and vault
+    fn shares_restricted(&self) -> bool {
+        self.bool_at(key::RESTRICTED).unwrap_or_default()
+    }
+
+    /// Returns the hub permitted to hold restricted shares
+    fn shares_hub(&self) -> Option<String> {
+        self.string_at(key::HUB)
+    }
+
+    fn set_shares_restricted(&mut self, restricted: bool) {
+        self.set_bool(key::RESTRICTED, restricted);
+    }
+
+    fn set_shares_hub(&mut self, hub: &str) {
+        self.set_string(key::HUB, hub);
+    }
+}
+
+impl<T> StorageExt for T where T: Storage + ?Sized {}
+
+/// Restrict shares transfers to the `hub` and the vault itself,
+/// returning the message registering the vault as the shares before-send hook
+pub fn restrict_msg<Msg>(
+    storage: &mut dyn Storage,
+    factory: impl TokenFactory<Msg>,
+    env: &Env,
+    hub: &Addr,
+) -> CosmosMsg<Msg> {
+    storage.set_shares_restricted(true);
+
+    storage.set_shares_hub(hub.as_str());
+
+    factory.set_before_send_hook(
+        &SHARES_DENOM.to_owned().into(),
+        env.contract.address.as_str(),
+    )
+}
+
+pub fn handle_sudo_msg(storage: &dyn Storage, env: &Env, msg: SudoMsg) -> Result<(), Error> {
+    match msg {
+        SudoMsg::BlockBeforeSend { from, to, .. } => {
+            if !storage.shares_restricted() {
+                return Ok(());
+            }
+
+            let hub = storage.shares_hub();
+
+            let is_permitted =
+                |addr: &str| addr == env.contract.address.as_str() || hub.as_deref() == Some(addr);
+
+            if is_permitted(&from) || is_permitted(&to) {
+                return Ok(());
+            }
+
+            Err(Error::TransferRestricted)
+        }
+    }
+}
@@ -0,0 +1,8 @@
+//! JSON fixtures for every execute, query & migrate message accepted by the Amulet contracts.
+//!
+//! The tests deserialize each fixture into the contract's message type & check that it
+//! serializes back to the same JSON, so that an accidental rename of a variant, field or nested
+//! shared type is caught before it breaks deployed integrations. Every variant in a message's
+//! schema must have a fixture, so new messages cannot be added without one.
+#[cfg(test)]
+mod test;
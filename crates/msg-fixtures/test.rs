@@ -0,0 +1,176 @@
+use std::collections::BTreeSet;
+
+use cosmwasm_schema::{schema_for, schemars::JsonSchema};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+use amulet_cw::MigrateMsg;
+
+macro_rules! fixture {
+    ($contract:literal, $msg:literal) => {
+        include_str!(concat!("fixtures/", $contract, "/", $msg, ".json"))
+    };
+}
+
+fn parse_fixtures(fixtures: &str) -> Vec<Value> {
+    serde_json::from_str(fixtures).expect("fixture file is a JSON array of messages")
+}
+
+fn resolve<'a>(schema: &'a Value, definitions: &'a Map<String, Value>) -> &'a Value {
+    let Some(reference) = schema.get("$ref").and_then(Value::as_str) else {
+        return schema;
+    };
+
+    let name = reference
+        .strip_prefix("#/definitions/")
+        .expect("only local definition references are used");
+
+    resolve(&definitions[name], definitions)
+}
+
+/// Collect the variant names of an externally tagged enum schema, descending through the
+/// nested enums of untagged wrappers
+fn collect_variants(
+    schema: &Value,
+    definitions: &Map<String, Value>,
+    variants: &mut BTreeSet<String>,
+) {
+    let schema = resolve(schema, definitions);
+
+    for key in ["anyOf", "oneOf"] {
+        for sub_schema in schema
+            .get(key)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            collect_variants(sub_schema, definitions, variants);
+        }
+    }
+
+    // unit variants
+    for name in schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        variants.insert(name.as_str().expect("unit variant name").to_owned());
+    }
+
+    // struct & newtype variants
+    if let Some([name]) = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+    {
+        variants.insert(name.as_str().expect("variant name").to_owned());
+    }
+}
+
+fn variant_name(msg: &Value) -> String {
+    match msg {
+        Value::String(name) => name.clone(),
+        Value::Object(fields) if fields.len() == 1 => fields.keys().next().unwrap().clone(),
+        _ => panic!("fixture is not an enum variant: {msg}"),
+    }
+}
+
+fn round_trip<T>(msg: &Value)
+where
+    T: DeserializeOwned + Serialize,
+{
+    let decoded: T = serde_json::from_value(msg.clone())
+        .unwrap_or_else(|err| panic!("failed to deserialize {msg}: {err}"));
+
+    let encoded = serde_json::to_value(&decoded).unwrap();
+
+    assert_eq!(&encoded, msg, "message did not round-trip");
+}
+
+/// Round-trip every fixture & check that every variant of `T` has a fixture
+fn check_enum_fixtures<T>(fixtures: &str)
+where
+    T: DeserializeOwned + Serialize + JsonSchema,
+{
+    let fixtures = parse_fixtures(fixtures);
+
+    let mut fixture_variants = BTreeSet::new();
+
+    for msg in &fixtures {
+        round_trip::<T>(msg);
+
+        assert!(
+            fixture_variants.insert(variant_name(msg)),
+            "duplicate fixture for {msg}"
+        );
+    }
+
+    let schema = serde_json::to_value(schema_for!(T)).unwrap();
+
+    let definitions = schema
+        .get("definitions")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut schema_variants = BTreeSet::new();
+
+    collect_variants(&schema, &definitions, &mut schema_variants);
+
+    let missing: Vec<_> = schema_variants.difference(&fixture_variants).collect();
+
+    let unknown: Vec<_> = fixture_variants.difference(&schema_variants).collect();
+
+    assert!(
+        missing.is_empty() && unknown.is_empty(),
+        "fixtures out of sync with {}: missing {missing:?}, unknown {unknown:?}",
+        std::any::type_name::<T>(),
+    );
+}
+
+fn check_migrate_fixtures(fixtures: &str) {
+    let fixtures = parse_fixtures(fixtures);
+
+    assert!(!fixtures.is_empty(), "missing migrate fixture");
+
+    for msg in &fixtures {
+        round_trip::<MigrateMsg>(msg);
+    }
+}
+
+#[test]
+fn hub() {
+    use amulet_hub::msg::{ExecuteMsg, QueryMsg};
+
+    check_enum_fixtures::<ExecuteMsg>(fixture!("hub", "execute"));
+    check_enum_fixtures::<QueryMsg>(fixture!("hub", "query"));
+    check_migrate_fixtures(fixture!("hub", "migrate"));
+}
+
+#[test]
+fn mint() {
+    use amulet_mint::msg::{ExecuteMsg, QueryMsg};
+
+    check_enum_fixtures::<ExecuteMsg>(fixture!("mint", "execute"));
+    check_enum_fixtures::<QueryMsg>(fixture!("mint", "query"));
+    check_migrate_fixtures(fixture!("mint", "migrate"));
+}
+
+#[test]
+fn generic_lst() {
+    use amulet_generic_lst::msg::{ExecuteMsg, QueryMsg};
+
+    check_enum_fixtures::<ExecuteMsg>(fixture!("generic-lst", "execute"));
+    check_enum_fixtures::<QueryMsg>(fixture!("generic-lst", "query"));
+    check_migrate_fixtures(fixture!("generic-lst", "migrate"));
+}
+
+#[test]
+fn remote_pos() {
+    use amulet_remote_pos::msg::{ExecuteMsg, QueryMsg};
+
+    check_enum_fixtures::<ExecuteMsg>(fixture!("remote-pos", "execute"));
+    check_enum_fixtures::<QueryMsg>(fixture!("remote-pos", "query"));
+    check_migrate_fixtures(fixture!("remote-pos", "migrate"));
+}
@@ -0,0 +1,171 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{from_json, Coin, CosmosMsg, StdError};
+use neutron_sdk::bindings::msg::NeutronMsg;
+
+/// The prefix of every ICA controller port id
+pub const PORT_ID_PREFIX: &str = "icacontroller";
+
+#[derive(Debug, thiserror::Error)]
+pub enum OpenAckError {
+    #[error("port id {0} is not an ICA controller port")]
+    InvalidPortId(String),
+
+    #[error("invalid counterparty version: {0}")]
+    InvalidVersion(#[from] StdError),
+}
+
+/// The port id of the ICA registered by `contract` under `account_key`, as assigned by the interchaintxs module
+pub fn port_id(contract: &str, account_key: &str) -> String {
+    format!("{PORT_ID_PREFIX}-{contract}.{account_key}")
+}
+
+/// The account key of an ICA controller port id, the inverse of [`port_id`]
+pub fn account_key(port_id: &str) -> Option<&str> {
+    let (_, account_key) = port_id
+        .strip_prefix(PORT_ID_PREFIX)?
+        .strip_prefix('-')?
+        .split_once('.')?;
+
+    (!account_key.is_empty()).then_some(account_key)
+}
+
+/// Register an ICA under `account_key` on `connection_id`, attaching the `register_fee`
+/// (see [`crate::query::QuerierExt::interchain_account_register_fee`])
+pub fn register_ica(
+    connection_id: impl Into<String>,
+    account_key: impl Into<String>,
+    register_fee: Coin,
+) -> CosmosMsg<NeutronMsg> {
+    NeutronMsg::RegisterInterchainAccount {
+        connection_id: connection_id.into(),
+        interchain_account_id: account_key.into(),
+        register_fee: Some(vec![register_fee]),
+    }
+    .into()
+}
+
+/// The counterparty version of a channel open ack for an ICA
+#[cw_serde]
+pub struct OpenAckVersion {
+    pub version: String,
+    pub controller_connection_id: String,
+    pub host_connection_id: String,
+    pub address: String,
+    pub encoding: String,
+    pub tx_type: String,
+}
+
+/// An ICA registration confirmed by a channel open ack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredIca {
+    pub account_key: String,
+    pub address: String,
+    pub controller_connection_id: String,
+    pub host_connection_id: String,
+}
+
+/// Parse the `port_id` & `counterparty_version` of an `OpenAck` sudo callback
+pub fn parse_open_ack(
+    port_id: &str,
+    counterparty_version: &str,
+) -> Result<RegisteredIca, OpenAckError> {
+    let account_key = account_key(port_id)
+        .ok_or_else(|| OpenAckError::InvalidPortId(port_id.to_owned()))?
+        .to_owned();
+
+    let OpenAckVersion {
+        address,
+        controller_connection_id,
+        host_connection_id,
+        ..
+    } = from_json(counterparty_version)?;
+
+    Ok(RegisteredIca {
+        account_key,
+        address,
+        controller_connection_id,
+        host_connection_id,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::coin;
+    use test_utils::prelude::*;
+
+    use super::*;
+
+    const CONTRACT: &str = "neutron1kmfmrq6yrxr4nv6tfwv3sycvl6ffy5uqpyrnp4ujv6uq9n0fghsq3dz7su";
+
+    // the port id & counterparty version of an ICA channel open ack as delivered by neutron
+    const PORT_ID: &str =
+        "icacontroller-neutron1kmfmrq6yrxr4nv6tfwv3sycvl6ffy5uqpyrnp4ujv6uq9n0fghsq3dz7su.main";
+
+    const COUNTERPARTY_VERSION: &str = r#"{"version":"ics27-1","controller_connection_id":"connection-42","host_connection_id":"connection-7","address":"cosmos1x7xs6ncdvc3jmhdppusv5hklvv8ft94zh33hkfmhytdn79xh8k4qhc4l5e","encoding":"proto3","tx_type":"sdk_multi_msg"}"#;
+
+    #[test]
+    fn port_id_round_trips_account_key() {
+        let port_id = port_id(CONTRACT, "main");
+
+        assert_eq!(port_id, PORT_ID);
+        assert_eq!(account_key(&port_id), Some("main"));
+    }
+
+    #[test]
+    fn account_key_requires_controller_port() {
+        for port_id in [
+            "transfer",
+            "icahost",
+            "icacontroller",
+            "icacontroller-neutron1contract",
+            "icacontroller-neutron1contract.",
+            "icacontrollerneutron1contract.main",
+        ] {
+            assert_eq!(account_key(port_id), None, "{port_id}");
+        }
+    }
+
+    #[test]
+    fn register_ica_encoding() {
+        let msg = register_ica("connection-42", "rewards", coin(1_000_000, "untrn"));
+
+        check(
+            msg,
+            expect![[r#"
+            custom(register_interchain_account(
+              connection_id: "connection-42",
+              interchain_account_id: "rewards",
+              register_fee: Some([
+                (
+                  denom: "untrn",
+                  amount: "1000000",
+                ),
+              ]),
+            ))"#]],
+        );
+    }
+
+    #[test]
+    fn parse_captured_open_ack() {
+        assert_eq!(
+            parse_open_ack(PORT_ID, COUNTERPARTY_VERSION).unwrap(),
+            RegisteredIca {
+                account_key: "main".to_owned(),
+                address: "cosmos1x7xs6ncdvc3jmhdppusv5hklvv8ft94zh33hkfmhytdn79xh8k4qhc4l5e"
+                    .to_owned(),
+                controller_connection_id: "connection-42".to_owned(),
+                host_connection_id: "connection-7".to_owned(),
+            }
+        );
+
+        assert!(matches!(
+            parse_open_ack("transfer", COUNTERPARTY_VERSION),
+            Err(OpenAckError::InvalidPortId(_))
+        ));
+
+        assert!(matches!(
+            parse_open_ack(PORT_ID, r#"{"version":"ics27-1"}"#),
+            Err(OpenAckError::InvalidVersion(_))
+        ));
+    }
+}
@@ -1,5 +1,7 @@
+use cosmwasm_std::Coin;
 use neutron_sdk::bindings::msg::IbcFee;
 
+pub mod ica;
 pub mod token_factory;
 
 pub mod query {
@@ -37,12 +39,27 @@ pub mod query {
         pub params: IcqParams,
     }
 
+    #[cw_serde]
+    pub struct TokenFactoryParams {
+        pub denom_creation_fee: Vec<Coin>,
+        pub denom_creation_gas_consume: Option<Uint64>,
+        pub fee_collector_address: Option<String>,
+    }
+
+    #[cw_serde]
+    pub struct QueryTokenFactoryParamsResponse {
+        pub params: TokenFactoryParams,
+    }
+
     pub trait QuerierExt {
         fn interchain_tx_max_msg_count(&self) -> Result<usize, StdError>;
 
         fn interchain_account_register_fee(&self) -> Result<Coin, StdError>;
 
         fn interchain_query_deposit(&self) -> Result<Coin, StdError>;
+
+        /// The fee charged to the sender for creating a denom, queried from the token factory params at `path`
+        fn denom_creation_fee(&self, path: &str) -> Result<Vec<Coin>, StdError>;
     }
 
     impl<'a, C: CustomQuery> QuerierExt for QuerierWrapper<'a, C> {
@@ -83,21 +100,86 @@ pub mod query {
 
             Ok(coin)
         }
+
+        fn denom_creation_fee(&self, path: &str) -> Result<Vec<Coin>, StdError> {
+            let res: QueryTokenFactoryParamsResponse = self.query(&QueryRequest::Stargate {
+                path: path.to_owned(),
+                data: Binary(vec![]),
+            })?;
+
+            Ok(res.params.denom_creation_fee)
+        }
     }
 }
 
 pub static IBC_FEE_DENOM: &str = "untrn";
 
+/// The components of an IBC fee, each denominated in [`IBC_FEE_DENOM`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IbcFeeBreakdown {
+    pub ack: u128,
+    pub timeout: u128,
+    /// Neutron requires the receive fee to be empty, so this is expected to be zero
+    pub recv: u128,
+}
+
+impl IbcFeeBreakdown {
+    pub fn total(&self) -> u128 {
+        self.ack + self.timeout + self.recv
+    }
+}
+
 pub trait IbcFeeExt {
-    fn total_fee_per_tx(&self) -> u128;
+    fn fee_breakdown(&self) -> IbcFeeBreakdown;
+
+    fn total_fee_per_tx(&self) -> u128 {
+        self.fee_breakdown().total()
+    }
+}
+
+fn ibc_fee_denom_amount(coins: &[Coin]) -> u128 {
+    coins
+        .iter()
+        .filter_map(|c| (c.denom == IBC_FEE_DENOM).then_some(c.amount.u128()))
+        .sum()
 }
 
 impl IbcFeeExt for IbcFee {
-    fn total_fee_per_tx(&self) -> u128 {
-        self.timeout_fee
-            .iter()
-            .chain(self.ack_fee.iter())
-            .filter_map(|c| (c.denom == IBC_FEE_DENOM).then_some(c.amount.u128()))
-            .sum()
+    fn fee_breakdown(&self) -> IbcFeeBreakdown {
+        IbcFeeBreakdown {
+            ack: ibc_fee_denom_amount(&self.ack_fee),
+            timeout: ibc_fee_denom_amount(&self.timeout_fee),
+            recv: ibc_fee_denom_amount(&self.recv_fee),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::coins;
+
+    use super::*;
+
+    #[test]
+    fn fee_breakdown_reports_each_component() {
+        let fee = IbcFee {
+            recv_fee: vec![],
+            ack_fee: [coins(1_000, IBC_FEE_DENOM), coins(5, "uatom")].concat(),
+            timeout_fee: coins(250, IBC_FEE_DENOM),
+        };
+
+        let breakdown = fee.fee_breakdown();
+
+        assert_eq!(
+            breakdown,
+            IbcFeeBreakdown {
+                ack: 1_000,
+                timeout: 250,
+                recv: 0,
+            }
+        );
+
+        assert_eq!(breakdown.total(), 1_250);
+        assert_eq!(fee.total_fee_per_tx(), 1_250);
     }
 }
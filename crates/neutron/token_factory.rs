@@ -3,49 +3,150 @@ use amulet_core::{
     Decimals, Recipient,
 };
 use amulet_cw::mint::TokenFactory as CwTokenFactory;
-use cosmwasm_std::{CosmosMsg, DenomUnit, Env};
+use cosmos_sdk_proto::cosmos::{
+    bank::v1beta1::{DenomUnit as ProtoDenomUnit, Metadata as ProtoMetadata},
+    base::v1beta1::Coin as ProtoCoin,
+};
+use cosmwasm_std::{Coin, CosmosMsg, CustomQuery, DenomUnit, Env, QuerierWrapper, StdError};
 use neutron_sdk::bindings::msg::NeutronMsg;
+use prost::Name;
+
+use crate::query::QuerierExt as _;
+
+/// The token factory module that messages are built for
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Flavour {
+    /// Neutron's `tokenfactory` module, via the Neutron custom message bindings
+    #[default]
+    Neutron,
+    /// The upstream `cosmos.tokenfactory.v1beta1` module, via stargate messages,
+    /// for vanilla SDK chains with the tokenfactory module enabled
+    CosmosSdk,
+}
+
+impl Flavour {
+    /// The stargate query path of the token factory module params
+    pub fn params_query_path(self) -> &'static str {
+        match self {
+            Flavour::Neutron => "/osmosis.tokenfactory.v1beta1.Query/Params",
+            Flavour::CosmosSdk => "/cosmos.tokenfactory.v1beta1.Query/Params",
+        }
+    }
+}
 
-pub struct TokenFactory<'a>(&'a Env);
+pub struct TokenFactory<'a> {
+    env: &'a Env,
+    flavour: Flavour,
+}
 
 impl<'a> TokenFactory<'a> {
     pub fn new(env: &'a Env) -> Self {
-        Self(env)
+        Self::with_flavour(env, Flavour::Neutron)
+    }
+
+    pub fn with_flavour(env: &'a Env, flavour: Flavour) -> Self {
+        Self { env, flavour }
+    }
+
+    /// The fee charged for creating a denom, drawn from the contract balance when the create message is executed
+    pub fn denom_creation_fee<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+    ) -> Result<Vec<Coin>, StdError> {
+        querier.denom_creation_fee(self.flavour.params_query_path())
+    }
+
+    fn sender(&self) -> String {
+        self.env.contract.address.to_string()
+    }
+
+    fn denom_units(
+        &self,
+        ticker: &Ticker,
+        display_ticker: &Ticker,
+        decimals: Decimals,
+    ) -> Vec<DenomUnit> {
+        vec![
+            DenomUnit {
+                denom: self.denom(ticker),
+                exponent: 0,
+                aliases: vec![],
+            },
+            DenomUnit {
+                denom: display_ticker.display().to_owned(),
+                exponent: decimals,
+                aliases: vec![],
+            },
+        ]
+    }
+}
+
+fn stargate<M: Name>(msg: M) -> CosmosMsg<NeutronMsg> {
+    CosmosMsg::Stargate {
+        type_url: M::type_url(),
+        value: msg.encode_to_vec().into(),
     }
 }
 
 impl<'a> CwTokenFactory<NeutronMsg> for TokenFactory<'a> {
     fn denom(&self, ticker: &Ticker) -> String {
-        format!("factory/{}/{}", self.0.contract.address, ticker.as_str())
+        format!("factory/{}/{}", self.env.contract.address, ticker.as_str())
     }
 
     fn create(&self, ticker: Ticker) -> CosmosMsg<NeutronMsg> {
-        NeutronMsg::submit_create_denom(ticker).into()
-    }
-
-    fn set_metadata(&self, ticker: &Ticker, decimals: Decimals) -> CosmosMsg<NeutronMsg> {
-        NeutronMsg::SetDenomMetadata {
-            description: "".to_owned(),
-            denom_units: vec![
-                DenomUnit {
-                    denom: self.denom(ticker),
-                    exponent: 0,
-                    aliases: vec![],
-                },
-                DenomUnit {
-                    denom: ticker.display().to_owned(),
-                    exponent: decimals,
-                    aliases: vec![],
-                },
-            ],
-            base: self.denom(ticker),
-            display: ticker.display().to_owned(),
-            name: ticker.display().to_owned(),
-            symbol: ticker.display().to_owned(),
-            uri: String::new(),
-            uri_hash: String::new(),
+        match self.flavour {
+            Flavour::Neutron => NeutronMsg::submit_create_denom(ticker).into(),
+
+            Flavour::CosmosSdk => stargate(proto::MsgCreateDenom {
+                sender: self.sender(),
+                subdenom: ticker.as_str().to_owned(),
+            }),
+        }
+    }
+
+    fn set_metadata(
+        &self,
+        ticker: &Ticker,
+        display_ticker: &Ticker,
+        decimals: Decimals,
+        description: &str,
+    ) -> CosmosMsg<NeutronMsg> {
+        let denom_units = self.denom_units(ticker, display_ticker, decimals);
+
+        match self.flavour {
+            Flavour::Neutron => NeutronMsg::SetDenomMetadata {
+                description: description.to_owned(),
+                denom_units,
+                base: self.denom(ticker),
+                display: display_ticker.display().to_owned(),
+                name: display_ticker.display().to_owned(),
+                symbol: display_ticker.display().to_owned(),
+                uri: String::new(),
+                uri_hash: String::new(),
+            }
+            .into(),
+
+            Flavour::CosmosSdk => stargate(proto::MsgSetDenomMetadata {
+                sender: self.sender(),
+                metadata: Some(ProtoMetadata {
+                    description: description.to_owned(),
+                    denom_units: denom_units
+                        .into_iter()
+                        .map(|unit| ProtoDenomUnit {
+                            denom: unit.denom,
+                            exponent: unit.exponent,
+                            aliases: unit.aliases,
+                        })
+                        .collect(),
+                    base: self.denom(ticker),
+                    display: display_ticker.display().to_owned(),
+                    name: display_ticker.display().to_owned(),
+                    symbol: display_ticker.display().to_owned(),
+                    uri: String::new(),
+                    uri_hash: String::new(),
+                }),
+            }),
         }
-        .into()
     }
 
     fn mint(
@@ -54,14 +155,121 @@ impl<'a> CwTokenFactory<NeutronMsg> for TokenFactory<'a> {
         amount: SyntheticAmount,
         recipient: Recipient,
     ) -> CosmosMsg<NeutronMsg> {
-        NeutronMsg::submit_mint_tokens(synthetic, amount.into(), recipient).into()
+        match self.flavour {
+            Flavour::Neutron => {
+                NeutronMsg::submit_mint_tokens(synthetic, amount.into(), recipient).into()
+            }
+
+            Flavour::CosmosSdk => stargate(proto::MsgMint {
+                sender: self.sender(),
+                amount: Some(ProtoCoin {
+                    denom: synthetic.into_string(),
+                    amount: amount.to_string(),
+                }),
+                mint_to_address: recipient.into_string(),
+            }),
+        }
     }
 
     fn burn(&self, synthetic: Synthetic, amount: SyntheticAmount) -> CosmosMsg<NeutronMsg> {
-        NeutronMsg::submit_burn_tokens(synthetic, amount.into()).into()
+        match self.flavour {
+            Flavour::Neutron => NeutronMsg::submit_burn_tokens(synthetic, amount.into()).into(),
+
+            Flavour::CosmosSdk => stargate(proto::MsgBurn {
+                sender: self.sender(),
+                amount: Some(ProtoCoin {
+                    denom: synthetic.into_string(),
+                    amount: amount.to_string(),
+                }),
+                burn_from_address: self.sender(),
+            }),
+        }
+    }
+
+    fn set_before_send_hook(&self, ticker: &Ticker, contract_addr: &str) -> CosmosMsg<NeutronMsg> {
+        match self.flavour {
+            Flavour::Neutron => {
+                NeutronMsg::submit_set_before_send_hook(self.denom(ticker), contract_addr).into()
+            }
+
+            Flavour::CosmosSdk => stargate(proto::MsgSetBeforeSendHook {
+                sender: self.sender(),
+                denom: self.denom(ticker),
+                cosmwasm_address: contract_addr.to_owned(),
+            }),
+        }
     }
 }
 
+/// The `cosmos.tokenfactory.v1beta1` messages, which are not included in `cosmos-sdk-proto`
+mod proto {
+    use cosmos_sdk_proto::cosmos::{bank::v1beta1::Metadata, base::v1beta1::Coin};
+    use prost::Name;
+
+    const PACKAGE: &str = "cosmos.tokenfactory.v1beta1";
+
+    macro_rules! impl_name {
+        ($msg:ident) => {
+            impl Name for $msg {
+                const NAME: &'static str = stringify!($msg);
+                const PACKAGE: &'static str = PACKAGE;
+            }
+        };
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgCreateDenom {
+        #[prost(string, tag = "1")]
+        pub sender: String,
+        #[prost(string, tag = "2")]
+        pub subdenom: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgMint {
+        #[prost(string, tag = "1")]
+        pub sender: String,
+        #[prost(message, optional, tag = "2")]
+        pub amount: Option<Coin>,
+        #[prost(string, tag = "3")]
+        pub mint_to_address: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgBurn {
+        #[prost(string, tag = "1")]
+        pub sender: String,
+        #[prost(message, optional, tag = "2")]
+        pub amount: Option<Coin>,
+        #[prost(string, tag = "3")]
+        pub burn_from_address: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgSetDenomMetadata {
+        #[prost(string, tag = "1")]
+        pub sender: String,
+        #[prost(message, optional, tag = "2")]
+        pub metadata: Option<Metadata>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MsgSetBeforeSendHook {
+        #[prost(string, tag = "1")]
+        pub sender: String,
+        #[prost(string, tag = "2")]
+        pub denom: String,
+        #[prost(string, tag = "3")]
+        pub cosmwasm_address: String,
+    }
+
+    impl_name!(MsgCreateDenom);
+    impl_name!(MsgMint);
+    impl_name!(MsgBurn);
+    impl_name!(MsgSetDenomMetadata);
+    impl_name!(MsgSetBeforeSendHook);
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_std::testing::mock_env;
@@ -73,6 +281,17 @@ mod test {
         s.to_owned().into()
     }
 
+    // decode a stargate message, checking the type url
+    fn decode<M: Name + Default>(msg: CosmosMsg<NeutronMsg>) -> M {
+        let CosmosMsg::Stargate { type_url, value } = msg else {
+            panic!("expected a stargate msg, got {msg:?}");
+        };
+
+        assert_eq!(type_url, M::type_url());
+
+        M::decode(value.as_slice()).unwrap()
+    }
+
     #[test]
     fn denom() {
         check(
@@ -95,7 +314,12 @@ mod test {
     #[test]
     fn set_metadata() {
         check(
-            TokenFactory::new(&mock_env()).set_metadata(&ticker("amNTRN"), 6),
+            TokenFactory::new(&mock_env()).set_metadata(
+                &ticker("amNTRN"),
+                &ticker("amNTRN"),
+                6,
+                "",
+            ),
             expect![[r#"
                 custom(set_denom_metadata(
                   description: "",
@@ -153,4 +377,157 @@ mod test {
                 ))"#]],
         );
     }
+
+    #[test]
+    fn set_before_send_hook() {
+        check(
+            TokenFactory::new(&mock_env()).set_before_send_hook(&ticker("share"), "vault"),
+            expect![[r#"
+                custom(set_before_send_hook(
+                  denom: "factory/cosmos2contract/share",
+                  contract_addr: "vault",
+                ))"#]],
+        );
+    }
+
+    #[test]
+    fn cosmos_sdk_type_urls() {
+        assert_eq!(
+            proto::MsgCreateDenom::type_url(),
+            "/cosmos.tokenfactory.v1beta1.MsgCreateDenom"
+        );
+        assert_eq!(
+            proto::MsgSetBeforeSendHook::type_url(),
+            "/cosmos.tokenfactory.v1beta1.MsgSetBeforeSendHook"
+        );
+    }
+
+    #[test]
+    fn params_query_paths() {
+        assert_eq!(
+            Flavour::Neutron.params_query_path(),
+            "/osmosis.tokenfactory.v1beta1.Query/Params"
+        );
+        assert_eq!(
+            Flavour::CosmosSdk.params_query_path(),
+            "/cosmos.tokenfactory.v1beta1.Query/Params"
+        );
+    }
+
+    #[test]
+    fn cosmos_sdk_create() {
+        let env = mock_env();
+
+        let msg = TokenFactory::with_flavour(&env, Flavour::CosmosSdk).create(ticker("amNTRN"));
+
+        assert_eq!(
+            decode::<proto::MsgCreateDenom>(msg),
+            proto::MsgCreateDenom {
+                sender: "cosmos2contract".to_owned(),
+                subdenom: "amntrn".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn cosmos_sdk_set_metadata() {
+        let env = mock_env();
+
+        let msg = TokenFactory::with_flavour(&env, Flavour::CosmosSdk).set_metadata(
+            &ticker("amNTRN"),
+            &ticker("amNTRN"),
+            6,
+            "",
+        );
+
+        assert_eq!(
+            decode::<proto::MsgSetDenomMetadata>(msg),
+            proto::MsgSetDenomMetadata {
+                sender: "cosmos2contract".to_owned(),
+                metadata: Some(ProtoMetadata {
+                    description: String::new(),
+                    denom_units: vec![
+                        ProtoDenomUnit {
+                            denom: "factory/cosmos2contract/amntrn".to_owned(),
+                            exponent: 0,
+                            aliases: vec![],
+                        },
+                        ProtoDenomUnit {
+                            denom: "amNTRN".to_owned(),
+                            exponent: 6,
+                            aliases: vec![],
+                        },
+                    ],
+                    base: "factory/cosmos2contract/amntrn".to_owned(),
+                    display: "amNTRN".to_owned(),
+                    name: "amNTRN".to_owned(),
+                    symbol: "amNTRN".to_owned(),
+                    uri: String::new(),
+                    uri_hash: String::new(),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn cosmos_sdk_mint() {
+        let env = mock_env();
+
+        let msg = TokenFactory::with_flavour(&env, Flavour::CosmosSdk).mint(
+            "factory/cosmos2contract/amntrn".to_owned().into(),
+            1_000_000,
+            "bob".to_owned().into(),
+        );
+
+        assert_eq!(
+            decode::<proto::MsgMint>(msg),
+            proto::MsgMint {
+                sender: "cosmos2contract".to_owned(),
+                amount: Some(ProtoCoin {
+                    denom: "factory/cosmos2contract/amntrn".to_owned(),
+                    amount: "1000000".to_owned(),
+                }),
+                mint_to_address: "bob".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn cosmos_sdk_burn() {
+        let env = mock_env();
+
+        let msg = TokenFactory::with_flavour(&env, Flavour::CosmosSdk).burn(
+            "factory/cosmos2contract/amntrn".to_owned().into(),
+            1_000_000,
+        );
+
+        assert_eq!(
+            decode::<proto::MsgBurn>(msg),
+            proto::MsgBurn {
+                sender: "cosmos2contract".to_owned(),
+                amount: Some(ProtoCoin {
+                    denom: "factory/cosmos2contract/amntrn".to_owned(),
+                    amount: "1000000".to_owned(),
+                }),
+                burn_from_address: "cosmos2contract".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn cosmos_sdk_set_before_send_hook() {
+        let env = mock_env();
+
+        let msg = TokenFactory::with_flavour(&env, Flavour::CosmosSdk)
+            .set_before_send_hook(&ticker("share"), "vault");
+
+        assert_eq!(
+            decode::<proto::MsgSetBeforeSendHook>(msg),
+            proto::MsgSetBeforeSendHook {
+                sender: "cosmos2contract".to_owned(),
+                denom: "factory/cosmos2contract/share".to_owned(),
+                cosmwasm_address: "vault".to_owned(),
+            }
+        );
+    }
 }
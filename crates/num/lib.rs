@@ -1,4 +1,6 @@
 #[allow(clippy::assign_op_pattern)]
+// `construct_uint!` expands to a hand-rolled ceiling division
+#[allow(clippy::manual_div_ceil)]
 mod uint {
     uint::construct_uint! {
         pub struct U256(4);
@@ -36,6 +38,44 @@ impl U256 {
     }
 }
 
+macro_rules! impl_checked_pow_u32 {
+    ($($t:ident),+) => {
+        $(
+            impl $t {
+                /// Returns `Some(self^exp)` via square-and-multiply, or `None` on overflow.
+                /// Named apart from the generated `checked_pow`, which takes the exponent as a big integer.
+                pub fn checked_pow_u32(self, mut exp: u32) -> Option<Self> {
+                    let mut base = self;
+                    let mut acc = Self::one();
+
+                    while exp > 0 {
+                        if exp & 1 == 1 {
+                            acc = acc.checked_mul(base)?;
+                        }
+
+                        exp >>= 1;
+
+                        if exp > 0 {
+                            base = base.checked_mul(base)?;
+                        }
+                    }
+
+                    Some(acc)
+                }
+            }
+        )+
+    };
+}
+
+impl_checked_pow_u32![U256, U512];
+
+impl U256 {
+    /// Returns `Some(10^decimals)` if representable, i.e. `decimals <= 77`, otherwise `None`
+    pub fn decimal_scale(decimals: u32) -> Option<Self> {
+        Self::from(10u8).checked_pow_u32(decimals)
+    }
+}
+
 impl From<U256> for U512 {
     fn from(value: U256) -> Self {
         let mut u256_le_bytes = [0u8; 8 * 4];
@@ -74,6 +114,25 @@ impl TryFrom<U512> for U256 {
     }
 }
 
+/// How the fractional part of a fixed point product is rounded to an integer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round towards zero
+    Floor,
+    /// Round away from zero
+    Ceil,
+    /// Round to the nearest integer, halves round to the even neighbour (banker's rounding)
+    HalfEven,
+}
+
+/// Returns `(lhs - rhs, false)` if `rhs` <= `lhs`, otherwise `(0, true)`
+pub fn overflowing_sub_u128(lhs: u128, rhs: u128) -> (u128, bool) {
+    match lhs.checked_sub(rhs) {
+        Some(diff) => (diff, false),
+        None => (0, true),
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct FixedU256(U256);
 
@@ -104,6 +163,14 @@ impl FixedU256 {
         self.0.checked_sub(rhs.0).map(Self)
     }
 
+    /// Returns `(self - rhs, false)` if `rhs` <= `self`, otherwise `(0, true)`
+    pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+        match self.checked_sub(rhs) {
+            Some(diff) => (diff, false),
+            None => (Self::zero(), true),
+        }
+    }
+
     pub fn checked_mul(self, rhs: Self) -> Option<Self> {
         if self.0.is_zero() || rhs.0.is_zero() {
             return Some(Self(U256::zero()));
@@ -118,6 +185,45 @@ impl FixedU256 {
         ans.try_into().ok().map(Self)
     }
 
+    /// Returns `Some(floor(self * x))` if the result fits in a u128, otherwise `None`.
+    /// Equivalent to `self.checked_mul(FixedU256::from_u128(x)).floor()` without the intermediate shift.
+    pub fn checked_mul_u128(self, x: u128) -> Option<u128> {
+        if self.0.is_zero() || x == 0 {
+            return Some(0);
+        }
+
+        let ans = (U512::from(self.0) * U512::from(x)) >> Self::FRAC_BITS;
+
+        ans.try_into().ok()
+    }
+
+    /// Returns `Some(self * x)` rounded according to `mode` if the result fits in a u128, otherwise `None`.
+    pub fn mul_u128_with_rounding(self, x: u128, mode: Rounding) -> Option<u128> {
+        if self.0.is_zero() || x == 0 {
+            return Some(0);
+        }
+
+        let product = U512::from(self.0) * U512::from(x);
+
+        let floor: u128 = (product >> Self::FRAC_BITS).try_into().ok()?;
+
+        let fraction = product & ((U512::one() << Self::FRAC_BITS) - 1);
+
+        let half = U512::one() << (Self::FRAC_BITS - 1);
+
+        let round_up = match mode {
+            Rounding::Floor => false,
+            Rounding::Ceil => !fraction.is_zero(),
+            Rounding::HalfEven => fraction > half || (fraction == half && floor % 2 == 1),
+        };
+
+        if round_up {
+            floor.checked_add(1)
+        } else {
+            Some(floor)
+        }
+    }
+
     pub fn checked_div(self, rhs: Self) -> Option<Self> {
         if rhs.0.is_zero() {
             return None;
@@ -136,6 +242,31 @@ impl FixedU256 {
         ans.try_into().ok().map(Self)
     }
 
+    /// Returns `Some(self / rhs)` rounded up to the nearest representable value if `rhs` > 0, otherwise `None`
+    pub fn checked_div_ceil(self, rhs: Self) -> Option<Self> {
+        if rhs.0.is_zero() {
+            return None;
+        }
+
+        if self.0.is_zero() {
+            return Some(Self(U256::zero()));
+        }
+
+        let lhs = U512::from(self.0) << Self::FRAC_BITS;
+
+        let rhs = U512::from(rhs.0);
+
+        let (quotient, remainder) = lhs.div_mod(rhs);
+
+        let ans = if remainder.is_zero() {
+            quotient
+        } else {
+            quotient.checked_add(U512::one())?
+        };
+
+        ans.try_into().ok().map(Self)
+    }
+
     pub fn floor(self) -> u128 {
         (self.0 >> Self::FRAC_BITS)
             .try_into()
@@ -146,9 +277,62 @@ impl FixedU256 {
         Self(self.0.abs_diff(other.0))
     }
 
+    pub fn min(self, other: Self) -> Self {
+        if self <= other {
+            self
+        } else {
+            other
+        }
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        if self >= other {
+            self
+        } else {
+            other
+        }
+    }
+
+    /// Restricts `self` to the inclusive range `[lo, hi]`, which must not be inverted
+    pub fn clamp(self, lo: Self, hi: Self) -> Self {
+        debug_assert!(lo <= hi, "inverted clamp range: {lo} > {hi}");
+
+        self.max(lo).min(hi)
+    }
+
     pub const fn is_zero(&self) -> bool {
         self.0.is_zero()
     }
+
+    /// Compares against an integer by its integer part, breaking ties with any non-zero fractional part
+    fn cmp_u128(self, x: u128) -> std::cmp::Ordering {
+        let has_fraction = !(self.0 & ((U256::one() << Self::FRAC_BITS) - 1)).is_zero();
+
+        match self.floor().cmp(&x) {
+            std::cmp::Ordering::Equal if has_fraction => std::cmp::Ordering::Greater,
+            ordering => ordering,
+        }
+    }
+
+    pub fn gt_u128(self, x: u128) -> bool {
+        self.cmp_u128(x).is_gt()
+    }
+
+    pub fn ge_u128(self, x: u128) -> bool {
+        self.cmp_u128(x).is_ge()
+    }
+
+    pub fn lt_u128(self, x: u128) -> bool {
+        self.cmp_u128(x).is_lt()
+    }
+
+    pub fn le_u128(self, x: u128) -> bool {
+        self.cmp_u128(x).is_le()
+    }
+
+    pub fn eq_u128(self, x: u128) -> bool {
+        self.cmp_u128(x).is_eq()
+    }
 }
 
 impl serde::Serialize for FixedU256 {
@@ -217,6 +401,35 @@ mod test {
         );
     }
 
+    #[test]
+    fn checked_pow_u32() {
+        assert_eq!(U256::zero().checked_pow_u32(0), Some(U256::one()));
+        assert_eq!(
+            U256::from(2u8).checked_pow_u32(255),
+            Some(U256::one() << 255)
+        );
+        assert_eq!(U256::from(2u8).checked_pow_u32(256), None);
+        assert_eq!(
+            U512::from(2u8).checked_pow_u32(511),
+            Some(U512::one() << 511)
+        );
+        assert_eq!(U512::from(2u8).checked_pow_u32(512), None);
+    }
+
+    #[test]
+    fn decimal_scale() {
+        assert_eq!(
+            U256::decimal_scale(18),
+            Some(U256::from(1_000_000_000_000_000_000u128))
+        );
+        assert_eq!(U256::decimal_scale(38), Some(U256::from(10u128.pow(38))));
+        assert_eq!(
+            U256::decimal_scale(77).unwrap().to_string(),
+            format!("1{}", "0".repeat(77))
+        );
+        assert_eq!(U256::decimal_scale(78), None);
+    }
+
     #[test]
     fn u512_from_u256() {
         assert_eq!(U512::from(U256::zero()), U512::zero());
@@ -273,6 +486,101 @@ mod test {
         assert!(half.checked_sub(three_quarters).is_none());
     }
 
+    #[test]
+    fn fixed256_overflowing_sub() {
+        let zero = FixedU256::from_u128(0);
+        let one = FixedU256::from_u128(1);
+        let half = from_ratio(1, 2);
+        let quarter = from_ratio(1, 4);
+        let three_quarters = from_ratio(3, 4);
+
+        assert_eq!(one.overflowing_sub(half), (half, false));
+        assert_eq!(three_quarters.overflowing_sub(half), (quarter, false));
+
+        assert_eq!(half.overflowing_sub(half), (zero, false));
+        assert_eq!(zero.overflowing_sub(zero), (zero, false));
+
+        assert_eq!(half.overflowing_sub(three_quarters), (zero, true));
+        assert_eq!(
+            zero.overflowing_sub(FixedU256::raw(U256::one())),
+            (zero, true)
+        );
+    }
+
+    #[test]
+    fn u128_overflowing_sub() {
+        assert_eq!(overflowing_sub_u128(10, 3), (7, false));
+        assert_eq!(overflowing_sub_u128(u128::MAX, 1), (u128::MAX - 1, false));
+
+        assert_eq!(overflowing_sub_u128(10, 10), (0, false));
+        assert_eq!(overflowing_sub_u128(0, 0), (0, false));
+
+        assert_eq!(overflowing_sub_u128(3, 10), (0, true));
+        assert_eq!(overflowing_sub_u128(0, u128::MAX), (0, true));
+    }
+
+    #[test]
+    fn fixed256_min_max() {
+        let half = from_ratio(1, 2);
+        let third = from_ratio(1, 3);
+
+        assert_eq!(half.min(third), third);
+        assert_eq!(third.min(half), third);
+        assert_eq!(half.max(third), half);
+        assert_eq!(third.max(half), half);
+        assert_eq!(half.min(half), half);
+        assert_eq!(half.max(half), half);
+    }
+
+    #[test]
+    fn fixed256_clamp_rate_to_unit_range() {
+        let zero = FixedU256::zero();
+        let one = FixedU256::from_u128(1);
+        let rate = from_ratio(3, 4);
+
+        assert_eq!(rate.clamp(zero, one), rate);
+        assert_eq!(from_ratio(5, 4).clamp(zero, one), one);
+        assert_eq!(zero.clamp(zero, one), zero);
+        assert_eq!(one.clamp(zero, one), one);
+        assert_eq!(rate.clamp(one, one), one);
+        assert_eq!(zero.clamp(from_ratio(1, 4), one), from_ratio(1, 4));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "inverted clamp range")]
+    fn fixed256_clamp_inverted_range() {
+        FixedU256::zero().clamp(FixedU256::from_u128(1), FixedU256::zero());
+    }
+
+    #[test]
+    fn fixed256_compare_u128() {
+        let one_and_half = from_ratio(3, 2);
+
+        assert!(one_and_half.gt_u128(1));
+        assert!(one_and_half.ge_u128(1));
+        assert!(!one_and_half.lt_u128(1));
+        assert!(!one_and_half.le_u128(1));
+        assert!(!one_and_half.eq_u128(1));
+
+        assert!(!one_and_half.gt_u128(2));
+        assert!(!one_and_half.ge_u128(2));
+        assert!(one_and_half.lt_u128(2));
+        assert!(one_and_half.le_u128(2));
+        assert!(!one_and_half.eq_u128(2));
+
+        let two = FixedU256::from_u128(2);
+
+        assert!(!two.gt_u128(2));
+        assert!(two.ge_u128(2));
+        assert!(!two.lt_u128(2));
+        assert!(two.le_u128(2));
+        assert!(two.eq_u128(2));
+
+        assert!(FixedU256::zero().eq_u128(0));
+        assert!(FixedU256::raw(U256::MAX).gt_u128(u128::MAX));
+    }
+
     #[test]
     fn fixed256_checked_mul() {
         let zero = FixedU256::from_u128(0);
@@ -288,6 +596,55 @@ mod test {
         assert!(u128_max.checked_mul(u128_max).is_none());
     }
 
+    #[test]
+    fn fixed256_checked_mul_u128() {
+        let two_step = |lhs: FixedU256, x: u128| {
+            lhs.checked_mul(FixedU256::from_u128(x))
+                .map(FixedU256::floor)
+        };
+
+        let values = [
+            FixedU256::zero(),
+            FixedU256::from_u128(1),
+            FixedU256::from_u128(1_000_000),
+            FixedU256::raw(U256::one()),
+            from_ratio(1, 2),
+            from_ratio(1, 3),
+            from_ratio(2, 3),
+            from_ratio(1_100, 1_000),
+            from_ratio(999_999, 1_000_000),
+        ];
+
+        let xs = [
+            0,
+            1,
+            2,
+            3,
+            7,
+            1_000,
+            123_456_789,
+            u64::MAX as u128,
+            u128::MAX / 2,
+        ];
+
+        for lhs in values {
+            for x in xs {
+                assert_eq!(lhs.checked_mul_u128(x), two_step(lhs, x), "{lhs} * {x}");
+            }
+        }
+
+        assert_eq!(
+            FixedU256::from_u128(1).checked_mul_u128(u128::MAX),
+            Some(u128::MAX)
+        );
+        assert!(FixedU256::from_u128(2)
+            .checked_mul_u128(u128::MAX)
+            .is_none());
+        assert!(FixedU256::from_u128(u128::MAX)
+            .checked_mul_u128(u128::MAX)
+            .is_none());
+    }
+
     #[test]
     fn fixed256_checked_div() {
         let zero = FixedU256::from_u128(0);
@@ -304,6 +661,33 @@ mod test {
         assert!(zero.checked_div(zero).is_none());
     }
 
+    #[test]
+    fn fixed256_checked_div_ceil() {
+        let zero = FixedU256::from_u128(0);
+        let one = FixedU256::from_u128(1);
+        let two = FixedU256::from_u128(2);
+        let three = FixedU256::from_u128(3);
+        let u128_max = FixedU256::from_u128(u128::MAX);
+        let half = from_ratio(1, 2);
+
+        assert!(one.checked_div_ceil(three).unwrap() > one.checked_div(three).unwrap());
+        assert_eq!(
+            one.checked_div_ceil(three)
+                .unwrap()
+                .checked_sub(one.checked_div(three).unwrap())
+                .unwrap(),
+            FixedU256::raw(U256::one())
+        );
+
+        assert_eq!(one.checked_div_ceil(half), one.checked_div(half));
+        assert_eq!(one.checked_div_ceil(two), one.checked_div(two));
+        assert_eq!(u128_max.checked_div_ceil(one), u128_max.checked_div(one));
+        assert_eq!(zero.checked_div_ceil(three).unwrap(), zero);
+
+        assert!(u128_max.checked_div_ceil(zero).is_none());
+        assert!(zero.checked_div_ceil(zero).is_none());
+    }
+
     #[test]
     fn fixed256_display() {
         let numer = FixedU256::from_u128(1_500);
@@ -327,4 +711,63 @@ mod test {
 
         assert_eq!(x.to_string(), "1.0");
     }
+
+    #[test]
+    fn mul_u128_with_rounding() {
+        let third = from_ratio(1, 3);
+        let half = from_ratio(1, 2);
+        let one = FixedU256::from_u128(1);
+
+        for (x, n, floor, ceil, half_even) in [
+            (third, 100, 33, 34, 33),
+            (from_ratio(2, 3), 100, 66, 67, 67),
+            (half, 101, 50, 51, 50),
+            (half, 103, 51, 52, 52),
+            (half, 100, 50, 50, 50),
+            (one, 100, 100, 100, 100),
+            (FixedU256::zero(), 100, 0, 0, 0),
+            (third, 0, 0, 0, 0),
+        ] {
+            assert_eq!(x.mul_u128_with_rounding(n, Rounding::Floor), Some(floor));
+            assert_eq!(x.mul_u128_with_rounding(n, Rounding::Ceil), Some(ceil));
+            assert_eq!(
+                x.mul_u128_with_rounding(n, Rounding::HalfEven),
+                Some(half_even)
+            );
+            assert_eq!(x.checked_mul_u128(n), Some(floor));
+        }
+
+        let two = FixedU256::from_u128(2);
+
+        assert_eq!(two.mul_u128_with_rounding(u128::MAX, Rounding::Floor), None);
+        assert_eq!(
+            one.mul_u128_with_rounding(u128::MAX, Rounding::Ceil),
+            Some(u128::MAX)
+        );
+    }
+
+    #[test]
+    fn rounded_split_parts_sum_within_part_count_of_total() {
+        for parts in 1..=12u128 {
+            for total in [0, 1, 2, 7, 99, 1_000, 123_456_789, u64::MAX as u128] {
+                for mode in [Rounding::Floor, Rounding::Ceil, Rounding::HalfEven] {
+                    // uneven split: part i is weighted (i + 1) / sum(1..=parts)
+                    let denom = parts * (parts + 1) / 2;
+
+                    let sum: u128 = (1..=parts)
+                        .map(|numer| {
+                            from_ratio(numer, denom)
+                                .mul_u128_with_rounding(total, mode)
+                                .unwrap()
+                        })
+                        .sum();
+
+                    assert!(
+                        sum.abs_diff(total) <= parts,
+                        "{mode:?}: {parts} parts of {total} sum to {sum}"
+                    );
+                }
+            }
+        }
+    }
 }
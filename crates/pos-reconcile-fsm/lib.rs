@@ -1,16 +1,21 @@
+pub mod plan;
 pub mod types;
 
-use std::{collections::BTreeMap, num::NonZeroU128};
+use std::{borrow::Cow, collections::BTreeMap, num::NonZeroU128};
 
-use num::FixedU256;
+use num::{FixedU256, U256};
 use types::{
-    Account, CurrentHeight, DelegateStartSlot, Delegated, DelegationsReport, FeeBpsBlockIncrement,
-    FeeMetadata, FeePaymentCooldownBlocks, FeeRecipient, InflightDelegation, InflightDeposit,
-    InflightFeePayable, InflightRewardsReceivable, InflightUnbond, LastReconcileHeight, MaxFeeBps,
-    MaxMsgCount, MsgIssuedCount, MsgSuccessCount, Now, PendingDeposit, PendingUnbond, Phase,
-    ReconcilerFee, RedelegationSlot, RemoteBalance, RemoteBalanceReport, RewardsReceivable, State,
-    UnbondingTimeSecs, UndelegateStartSlot, UndelegatedBalanceReport, Validator, ValidatorSetSize,
-    ValidatorSetSlot, Weight, Weights,
+    Account, ArithmeticError, CurrentHeight, DelegateAuthzMsgs, DelegateSlotOrder,
+    DelegateStartSlot, Delegated, DelegationDriftTolerance, DelegationOrder, DelegationsReport,
+    FeeBpsBlockIncrement, FeeMetadata, FeePaymentCooldownBlocks, FeePayoutsEnabled, FeeRecipient,
+    InflightDelegation, InflightDeposit, InflightFeePayable, InflightRewardsReceivable,
+    InflightUnbond, LastReconcileHeight, MaxFeeBps, MaxMsgCount, MaxUnbondingEntries,
+    MaxUndelegateRetries, MsgIssuedCount, MsgSuccessCount, MsgTypeUrl, Now, PendingDeposit,
+    PendingUnbond, Phase, ReconcileScope, ReconcilerFee, RedelegationSlot, RemoteBalance,
+    RemoteBalanceReport, RewardsReceivable, SetupAccounts, State, UnbondingEntriesReport,
+    UnbondingTimeSecs, UndelegateDeferredSlots, UndelegateRetryCount, UndelegateStartSlot,
+    UndelegatedBalanceReport, Validator, ValidatorSetSize, ValidatorSetSlot, Weight, Weights,
+    WeightsError,
 };
 
 /// Access fixed config
@@ -21,13 +26,32 @@ pub trait Config {
 
     fn fee_payout_cooldown(&self) -> FeePaymentCooldownBlocks;
 
+    /// Whether reconciler fees are paid, when disabled the fee payable is always zero and all rewards are delegated
+    fn fee_payouts_enabled(&self) -> FeePayoutsEnabled;
+
     fn fee_bps_block_increment(&self) -> FeeBpsBlockIncrement;
 
     fn max_fee_bps(&self) -> MaxFeeBps;
 
     fn starting_weights(&self) -> Weights;
 
+    /// The delegation target weights set by an admin, if any.
+    /// Delegations are distributed so that the current weights converge towards the target.
+    fn target_weights(&self) -> Option<Weights>;
+
     fn validator_set_size(&self) -> ValidatorSetSize;
+
+    /// The number of times a failed undelegation is retried before the offending slot is skipped
+    fn max_undelegate_retries(&self) -> MaxUndelegateRetries;
+
+    /// The max concurrent unbonding entries per validator, slots at the limit are deferred from undelegations
+    fn max_unbonding_entries(&self) -> MaxUnbondingEntries;
+
+    /// The max difference between the recorded delegated amount and a delegations report that is corrected without being treated as a slashing
+    fn delegation_drift_tolerance(&self) -> DelegationDriftTolerance;
+
+    /// The order in which slots are funded, which decides the slots covered by the first batches of a partial delegation
+    fn delegation_order(&self) -> DelegationOrder;
 }
 
 /// Access mutable storage
@@ -36,6 +60,8 @@ pub trait Repository {
 
     fn delegate_start_slot(&self) -> DelegateStartSlot;
 
+    fn delegate_slot_order(&self) -> DelegateSlotOrder;
+
     fn inflight_delegation(&self) -> InflightDelegation;
 
     fn inflight_deposit(&self) -> InflightDeposit;
@@ -64,9 +90,16 @@ pub trait Repository {
 
     fn redelegate_to_validator(&self) -> Option<Validator>;
 
+    fn setup_accounts(&self) -> Option<SetupAccounts>;
+
+    fn undelegate_deferred_slots(&self) -> UndelegateDeferredSlots;
+
+    fn undelegate_retry_count(&self) -> UndelegateRetryCount;
+
     fn undelegate_start_slot(&self) -> UndelegateStartSlot;
 
-    fn weights(&self) -> Weights;
+    /// Borrowed where the weights are already held in memory, to avoid cloning them on every access
+    fn weights(&self) -> Cow<'_, Weights>;
 }
 
 /// Access current environment
@@ -86,17 +119,23 @@ pub trait Env {
     fn rewards_balance_report(&self) -> Option<RemoteBalanceReport>;
 
     fn undelegated_balance_report(&self) -> Option<UndelegatedBalanceReport>;
+
+    fn unbonding_entries_report(&self) -> Option<UnbondingEntriesReport>;
 }
 
 fn fee_metadata(config: &dyn Config, env: &dyn Env) -> FeeMetadata {
     FeeMetadata {
         fee_recipient: env.fee_recipient(),
         fee_payout_cooldown: config.fee_payout_cooldown(),
+        fee_payouts_enabled: config.fee_payouts_enabled(),
         fee_bps_block_increment: config.fee_bps_block_increment(),
         max_fee_bps: config.max_fee_bps(),
     }
 }
 
+/// The type URL of a bank send, authorised for the delegation account on behalf of the rewards account
+pub const MSG_SEND_TYPE_URL: &str = "/cosmos.bank.v1beta1.MsgSend";
+
 /// The types of message that can be issued in a single Authz exec message
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(test, derive(serde::Serialize))]
@@ -110,7 +149,10 @@ pub enum AuthzMsg {
 #[cfg_attr(test, derive(serde::Serialize))]
 pub enum TxMsg {
     SetRewardsWithdrawalAddress(Account, Account),
-    GrantAuthzSend(Account, Account),
+    /// Grant the grantee (second) authorisation to execute messages of the type on behalf of the granter (first)
+    GrantAuthz(Account, Account, MsgTypeUrl),
+    /// Revoke the grantee's (second) authorisation to execute messages of the type on behalf of the granter (first)
+    RevokeAuthz(Account, Account, MsgTypeUrl),
     TransferInUndelegated(u128),
     TransferOutPendingDeposit(u128),
     WithdrawRewards(ValidatorSetSlot),
@@ -153,6 +195,7 @@ pub enum Cmd {
     ClearRedelegationRequest,
     Delegated(Delegated),
     DelegateStartSlot(DelegateStartSlot),
+    DelegateSlotOrder(DelegateSlotOrder),
     InflightDelegation(InflightDelegation),
     InflightDeposit(InflightDeposit),
     InflightFeePayable(InflightFeePayable),
@@ -164,18 +207,15 @@ pub enum Cmd {
     PendingDeposit(PendingDeposit),
     PendingUnbond(PendingUnbond),
     Phase(Phase),
+    SetupAccounts(SetupAccounts),
     State(State),
+    UndelegateDeferredSlots(UndelegateDeferredSlots),
+    UndelegateRetryCount(UndelegateRetryCount),
     UndelegateStartSlot(UndelegateStartSlot),
     Weights(Weights),
 }
 
-macro_rules! set {
-    ($($item:expr),+) => {
-        vec![
-            $($item.into()),+
-        ]
-    };
-}
+use amulet_prelude::into_cmds as set;
 
 macro_rules! impl_cmd_from {
     ($($t:ident),+) => {
@@ -192,6 +232,7 @@ macro_rules! impl_cmd_from {
 impl_cmd_from![
     Delegated,
     DelegateStartSlot,
+    DelegateSlotOrder,
     InflightDeposit,
     InflightDelegation,
     InflightFeePayable,
@@ -203,7 +244,10 @@ impl_cmd_from![
     PendingDeposit,
     PendingUnbond,
     Phase,
+    SetupAccounts,
     State,
+    UndelegateDeferredSlots,
+    UndelegateRetryCount,
     UndelegateStartSlot,
     Weights
 ];
@@ -221,6 +265,56 @@ pub enum Event {
         slot: ValidatorSetSlot,
         validator: Validator,
     },
+    /// The adjusted weights were rejected and reset to the starting weights
+    WeightsReset(WeightsError),
+    /// The slot kept rejecting its undelegation past the max retries, so it was skipped
+    UndelegateSlotSkipped(ValidatorSetSlot),
+    /// The slot has reached the max unbonding entries, so it was deferred from the undelegation
+    UnbondingEntriesFull(ValidatorSetSlot),
+    /// The recorded delegations drifted from the delegations report within tolerance and were corrected.
+    /// The delta is the reported total minus the previously recorded total
+    DelegationDriftCorrected {
+        delta: i128,
+    },
+    /// The delegations report exceeded the recorded delegations beyond tolerance, the surplus was absorbed
+    DelegationSurplusAbsorbed(u128),
+    /// A full reconcile cycle completed at the current height, returning to the start
+    ReconcileCompleted {
+        height: u64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+#[cfg_attr(test, derive(serde::Serialize))]
+/// Why a reconcile was aborted without progressing
+pub enum AbortReason {
+    /// The delegation ICA has not been registered yet
+    #[display(fmt = "ica_not_registered")]
+    IcaNotRegistered,
+    /// The rewards ICA has not been registered yet
+    #[display(fmt = "missing_rewards_account")]
+    MissingRewardsAccount,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ReconcileError {
+    #[error("no validator slot weights")]
+    EmptyWeights,
+
+    #[error("validator slot weights add up to more than 1.0")]
+    WeightsExceedOne,
+
+    #[error("invalid validator slot weights: {0}")]
+    Weights(#[from] WeightsError),
+
+    #[error("validator slot {slot} out of range for {slot_count} slots")]
+    SlotOutOfRange { slot: usize, slot_count: usize },
+
+    #[error("reconcile invariant violated: {0}")]
+    InvariantViolated(&'static str),
+
+    #[error("inconsistent reconcile state: {0}")]
+    Arithmetic(#[from] ArithmeticError),
 }
 
 #[cfg_attr(test, derive(serde::Serialize))]
@@ -229,6 +323,12 @@ pub struct Response {
     pub events: Vec<Event>,
     pub tx_msgs: Option<TxMsgs>,
     pub tx_skip_count: usize,
+    /// Why the reconcile was aborted, if it was
+    pub abort_reason: Option<AbortReason>,
+    /// The number of phase transitions traversed, informational only
+    pub transitions_traversed: usize,
+    /// The number of events emitted, informational only
+    pub events_emitted: usize,
 }
 
 struct TxMsgBatcher {
@@ -283,11 +383,16 @@ trait EnvExt: Env {
 impl<T> EnvExt for T where T: Env + ?Sized {}
 
 pub trait Fsm {
-    fn reconcile(&self) -> Response;
+    fn reconcile(&self) -> Result<Response, ReconcileError>;
+
+    /// Start a cycle that only delegates accrued rewards (and withdraws the next rewards), skipping the
+    /// redelegate, undelegate & transfer phases. Returns `None` if a cycle is already underway, the accounts
+    /// require setting up or an undelegation is in-flight.
+    fn reconcile_rewards_only(&self) -> Result<Option<Response>, ReconcileError>;
 
     fn failed(&self) -> Response;
 
-    fn force_next(&self) -> Option<Response>;
+    fn force_next(&self) -> Result<Option<Response>, ReconcileError>;
 }
 
 #[derive(Clone, Copy)]
@@ -333,8 +438,71 @@ pub fn current_deposits(repo: &dyn Repository) -> u128 {
         .expect("always: pending unbond <= total deposits")
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ResizeError {
+    #[error("reconcile in progress")]
+    ReconcileInProgress,
+
+    #[error("redelegation in progress")]
+    RedelegationInProgress,
+
+    #[error("invalid validator slot map")]
+    InvalidSlotMap,
+
+    #[error("removed validator slot {0} still has delegations")]
+    RemovedSlotDelegated(usize),
+}
+
+/// Resizes the validator set, each new slot maps to the existing slot it retains or `None` if it is a new validator.
+/// New slots start with zero weight, retained slots keep their weight, rescaled pro-rata.
+/// Only allowed between reconcile cycles with nothing inflight, and every removed slot must have been fully undelegated first.
+pub fn resize_validator_set(
+    repo: &dyn Repository,
+    slot_map: &[Option<usize>],
+) -> Result<Vec<Cmd>, ResizeError> {
+    let quiescent = repo.phase().is_start_reconcile()
+        && repo.state().is_idle()
+        && repo.inflight_delegation().0 == 0
+        && repo.inflight_deposit().0 == 0
+        && repo.inflight_fee_payable().0 == 0
+        && repo.inflight_rewards_receivable().0 == 0
+        && repo.inflight_unbond().0 == 0;
+
+    if !quiescent {
+        return Err(ResizeError::ReconcileInProgress);
+    }
+
+    if repo.redelegation_slot().is_some() {
+        return Err(ResizeError::RedelegationInProgress);
+    }
+
+    let weights = repo.weights();
+
+    let resized = weights
+        .resize(slot_map)
+        .ok_or(ResizeError::InvalidSlotMap)?;
+
+    let Delegated(delegated) = repo.delegated();
+
+    for (slot, weight) in weights.as_slice().iter().enumerate() {
+        if !slot_map.contains(&Some(slot)) && weight.apply(delegated) > 0 {
+            return Err(ResizeError::RemovedSlotDelegated(slot));
+        }
+    }
+
+    Ok(vec![
+        Cmd::Weights(resized),
+        // the start slots are progress cursors within a phase, there is none to resume between cycles
+        Cmd::DelegateStartSlot(DelegateStartSlot::default()),
+        Cmd::DelegateSlotOrder(DelegateSlotOrder::default()),
+        Cmd::UndelegateStartSlot(UndelegateStartSlot::default()),
+        Cmd::UndelegateDeferredSlots(UndelegateDeferredSlots::default()),
+        Cmd::UndelegateRetryCount(UndelegateRetryCount::default()),
+    ])
+}
+
 enum TransitionKind {
-    Abort,
+    Abort(AbortReason),
     Next,
     Tx(TxMsgs),
 }
@@ -362,9 +530,9 @@ impl Transition {
         }
     }
 
-    const fn abort() -> Transition {
+    const fn abort(reason: AbortReason) -> Transition {
         Self {
-            kind: TransitionKind::Abort,
+            kind: TransitionKind::Abort(reason),
             cmds: vec![],
             events: vec![],
         }
@@ -381,6 +549,7 @@ struct Cache {
     clear_redelegation: bool,
     delegated: Option<Delegated>,
     delegate_start_slot: Option<DelegateStartSlot>,
+    delegate_slot_order: Option<DelegateSlotOrder>,
     inflight_delegation: Option<InflightDelegation>,
     inflight_deposit: Option<InflightDeposit>,
     inflight_fee_payable: Option<InflightFeePayable>,
@@ -391,6 +560,9 @@ struct Cache {
     msg_success_count: Option<MsgSuccessCount>,
     pending_deposit: Option<PendingDeposit>,
     pending_unbond: Option<PendingUnbond>,
+    setup_accounts: Option<SetupAccounts>,
+    undelegate_deferred_slots: Option<UndelegateDeferredSlots>,
+    undelegate_retry_count: Option<UndelegateRetryCount>,
     undelegate_start_slot: Option<UndelegateStartSlot>,
     weights: Option<Weights>,
 }
@@ -402,6 +574,7 @@ impl Cache {
                 .then_some(Cmd::ClearRedelegationRequest),
             self.delegated.map(Cmd::from),
             self.delegate_start_slot.map(Cmd::from),
+            self.delegate_slot_order.map(Cmd::from),
             self.inflight_delegation.map(Cmd::from),
             self.inflight_deposit.map(Cmd::from),
             self.inflight_fee_payable.map(Cmd::from),
@@ -412,6 +585,9 @@ impl Cache {
             self.msg_success_count.map(Cmd::from),
             self.pending_deposit.map(Cmd::from),
             self.pending_unbond.map(Cmd::from),
+            self.setup_accounts.map(Cmd::from),
+            self.undelegate_deferred_slots.map(Cmd::from),
+            self.undelegate_retry_count.map(Cmd::from),
             self.undelegate_start_slot.map(Cmd::from),
             self.weights.map(Cmd::from),
         ]
@@ -431,6 +607,7 @@ impl<'a> IntermediateRepo<'a> {
         match cmd {
             Cmd::ClearRedelegationRequest => self.cache.clear_redelegation = true,
             Cmd::DelegateStartSlot(v) => self.cache.delegate_start_slot = Some(v),
+            Cmd::DelegateSlotOrder(v) => self.cache.delegate_slot_order = Some(v),
             Cmd::Delegated(v) => self.cache.delegated = Some(v),
             Cmd::InflightDelegation(v) => self.cache.inflight_delegation = Some(v),
             Cmd::InflightDeposit(v) => self.cache.inflight_deposit = Some(v),
@@ -442,6 +619,9 @@ impl<'a> IntermediateRepo<'a> {
             Cmd::MsgSuccessCount(v) => self.cache.msg_success_count = Some(v),
             Cmd::PendingDeposit(v) => self.cache.pending_deposit = Some(v),
             Cmd::PendingUnbond(v) => self.cache.pending_unbond = Some(v),
+            Cmd::SetupAccounts(v) => self.cache.setup_accounts = Some(v),
+            Cmd::UndelegateDeferredSlots(v) => self.cache.undelegate_deferred_slots = Some(v),
+            Cmd::UndelegateRetryCount(v) => self.cache.undelegate_retry_count = Some(v),
             Cmd::UndelegateStartSlot(v) => self.cache.undelegate_start_slot = Some(v),
             Cmd::Weights(v) => self.cache.weights = Some(v),
             _ => panic!("unexpected cmd: {cmd:?}"),
@@ -462,6 +642,13 @@ impl<'a> Repository for IntermediateRepo<'a> {
             .unwrap_or_else(|| self.repo.delegate_start_slot())
     }
 
+    fn delegate_slot_order(&self) -> DelegateSlotOrder {
+        self.cache
+            .delegate_slot_order
+            .clone()
+            .unwrap_or_else(|| self.repo.delegate_slot_order())
+    }
+
     fn inflight_delegation(&self) -> InflightDelegation {
         self.cache
             .inflight_delegation
@@ -546,29 +733,62 @@ impl<'a> Repository for IntermediateRepo<'a> {
         self.repo.redelegate_to_validator()
     }
 
+    fn setup_accounts(&self) -> Option<SetupAccounts> {
+        self.cache
+            .setup_accounts
+            .clone()
+            .or_else(|| self.repo.setup_accounts())
+    }
+
+    fn undelegate_deferred_slots(&self) -> UndelegateDeferredSlots {
+        self.cache
+            .undelegate_deferred_slots
+            .clone()
+            .unwrap_or_else(|| self.repo.undelegate_deferred_slots())
+    }
+
+    fn undelegate_retry_count(&self) -> UndelegateRetryCount {
+        self.cache
+            .undelegate_retry_count
+            .unwrap_or_else(|| self.repo.undelegate_retry_count())
+    }
+
     fn undelegate_start_slot(&self) -> UndelegateStartSlot {
         self.cache
             .undelegate_start_slot
             .unwrap_or_else(|| self.repo.undelegate_start_slot())
     }
 
-    fn weights(&self) -> Weights {
+    fn weights(&self) -> Cow<'_, Weights> {
         self.cache
             .weights
-            .clone()
-            .unwrap_or_else(|| self.repo.weights())
+            .as_ref()
+            .map_or_else(|| self.repo.weights(), Cow::Borrowed)
     }
 }
 
-type Handler = fn(Context) -> Transition;
+// Adjusted weights within this tolerance of the stored weights are considered unchanged (~1e-33 per slot)
+const WEIGHTS_EPSILON: FixedU256 = FixedU256::raw(U256([1 << 20, 0, 0, 0]));
 
-fn start_setup_rewards_address(Context { env, .. }: Context) -> Transition {
-    let Some((delegation_account, rewards_account)) = env
-        .delegation_account_address()
-        .zip(env.rewards_account_address())
-    else {
-        // cannot continue until ICAs have been setup
-        return Transition::abort();
+// Only issue a weights update if the adjusted weights differ meaningfully from the current weights
+fn push_adjusted_weights(cmds: &mut Vec<Cmd>, current: &Weights, adjusted: Weights) {
+    if adjusted.approx_eq(current, WEIGHTS_EPSILON) {
+        return;
+    }
+
+    cmds.push(adjusted.into());
+}
+
+type Handler = fn(Context) -> Result<Transition, ReconcileError>;
+
+fn start_setup_rewards_address(Context { env, .. }: Context) -> Result<Transition, ReconcileError> {
+    // cannot continue until ICAs have been setup
+    let Some(delegation_account) = env.delegation_account_address() else {
+        return Ok(Transition::abort(AbortReason::IcaNotRegistered));
+    };
+
+    let Some(rewards_account) = env.rewards_account_address() else {
+        return Ok(Transition::abort(AbortReason::MissingRewardsAccount));
     };
 
     let tx_msgs = TxMsgs::single(TxMsg::SetRewardsWithdrawalAddress(
@@ -576,26 +796,74 @@ fn start_setup_rewards_address(Context { env, .. }: Context) -> Transition {
         rewards_account,
     ));
 
-    Transition::tx(tx_msgs, vec![])
+    Ok(Transition::tx(tx_msgs, vec![]))
 }
 
-fn on_setup_rewards_address_success(_: Context) -> Transition {
-    Transition::next(vec![])
+fn on_setup_rewards_address_success(_: Context) -> Result<Transition, ReconcileError> {
+    Ok(Transition::next(vec![]))
 }
 
-fn start_setup_authz(Context { env, .. }: Context) -> Transition {
+fn start_setup_authz(Context { env, .. }: Context) -> Result<Transition, ReconcileError> {
     let (delegation_account, rewards_account) = env
         .delegation_account_address()
         .zip(env.rewards_account_address())
         .expect("always: there must be delegation and rewards addresses to access this phase");
 
-    let tx_msgs = TxMsgs::single(TxMsg::GrantAuthzSend(rewards_account, delegation_account));
+    let tx_msgs = TxMsgs::single(TxMsg::GrantAuthz(
+        rewards_account,
+        delegation_account,
+        MSG_SEND_TYPE_URL.to_owned(),
+    ));
+
+    Ok(Transition::tx(tx_msgs, vec![]))
+}
 
-    Transition::tx(tx_msgs, vec![])
+fn on_setup_authz_success(Context { env, .. }: Context) -> Result<Transition, ReconcileError> {
+    let (delegation, rewards) = env
+        .delegation_account_address()
+        .zip(env.rewards_account_address())
+        .expect("always: there must be delegation and rewards addresses to access this phase");
+
+    Ok(Transition::next(set![SetupAccounts {
+        delegation,
+        rewards
+    }]))
 }
 
-fn on_setup_authz_success(_: Context) -> Transition {
-    Transition::next(vec![])
+/// Returns the phase to start from: the setup phases are re-entered at the start of a round
+/// if either account address differs from those in use when the setup phases last completed.
+/// Account addresses are adopted as-is if none have been recorded yet.
+fn check_setup_accounts(
+    Context { repo, env, .. }: Context,
+    phase: Phase,
+    intermediate_repo: &mut IntermediateRepo,
+) -> Phase {
+    if !phase.is_start_reconcile() {
+        return phase;
+    }
+
+    let Some((delegation, rewards)) = env
+        .delegation_account_address()
+        .zip(env.rewards_account_address())
+    else {
+        return phase;
+    };
+
+    let current = SetupAccounts {
+        delegation,
+        rewards,
+    };
+
+    match repo.setup_accounts() {
+        Some(persisted) if persisted != current => Phase::SetupRewardsAddress,
+
+        Some(_) => phase,
+
+        None => {
+            intermediate_repo.handle_cmd(current.into());
+            phase
+        }
+    }
 }
 
 struct Slashing {
@@ -611,13 +879,111 @@ struct Slashing {
     slashed_ratio: FixedU256,
 }
 
+struct Drift {
+    /// The weights matching the reported delegations
+    adjusted_weights: Weights,
+    /// The reported delegated amount
+    delegated: u128,
+    /// The reported delegated amount minus the recorded delegated amount
+    delta: i128,
+    /// Whether the difference is larger than the drift tolerance
+    exceeds_tolerance: bool,
+    /// The pending unbond amount, scaled down with the delegated amount on a loss
+    pending_unbond: u128,
+    /// The inflight unbond amount, scaled down with the delegated amount on a loss
+    inflight_unbond: u128,
+}
+
+// the weights matching the share of each slot in the reported delegations
+fn report_weights(delegations: &DelegationsReport) -> Weights {
+    let mut adjusted_weights = Vec::with_capacity(delegations.delegated_amounts_per_slot.len());
+
+    for &delegation in &delegations.delegated_amounts_per_slot {
+        let adjusted_weight =
+            Weight::checked_from_fraction(delegation, delegations.total_delegated)
+                .expect("always: delegation <= total delegation");
+
+        adjusted_weights.push(adjusted_weight);
+    }
+
+    Weights::new(&adjusted_weights).expect("always: one weight per slot & total weight == 1.0")
+}
+
+// determine whether the recorded delegations have drifted from the delegations report,
+// i.e. any surplus or a loss within the drift tolerance
+fn check_for_drift(
+    Delegated(delegated): Delegated,
+    PendingUnbond(pending_unbond): PendingUnbond,
+    InflightUnbond(inflight_unbond): InflightUnbond,
+    LastReconcileHeight(last_reconcile_height): LastReconcileHeight,
+    DelegationDriftTolerance(tolerance): DelegationDriftTolerance,
+    delegations: &DelegationsReport,
+) -> Option<Drift> {
+    if delegated == 0 || delegations.total_delegated == 0 {
+        return None;
+    }
+
+    // only continue if the delegations report is not stale
+    if delegations.height <= last_reconcile_height {
+        return None;
+    }
+
+    let difference = delegations.total_delegated.abs_diff(delegated);
+
+    if difference == 0 {
+        return None;
+    }
+
+    let exceeds_tolerance = difference > tolerance;
+
+    // losses beyond the tolerance are handled as a slashing
+    if exceeds_tolerance && delegations.total_delegated < delegated {
+        return None;
+    }
+
+    let delta = i128::try_from(difference).expect("always: delegated amounts fit within i128");
+
+    let delta = if delegations.total_delegated < delegated {
+        -delta
+    } else {
+        delta
+    };
+
+    // the unbonding amounts are drawn from the delegations, so they shrink in proportion to a loss
+    let (pending_unbond, inflight_unbond) = if delta < 0 {
+        let loss_ratio = FixedU256::from_u128(delegations.total_delegated)
+            .checked_div(FixedU256::from_u128(delegated))
+            .expect("checked: delegated > 0");
+
+        (
+            loss_ratio
+                .checked_mul_u128(pending_unbond)
+                .expect("always: loss ratio < 1.0"),
+            loss_ratio
+                .checked_mul_u128(inflight_unbond)
+                .expect("always: loss ratio < 1.0"),
+        )
+    } else {
+        (pending_unbond, inflight_unbond)
+    };
+
+    Some(Drift {
+        adjusted_weights: report_weights(delegations),
+        delegated: delegations.total_delegated,
+        delta,
+        exceeds_tolerance,
+        pending_unbond,
+        inflight_unbond,
+    })
+}
+
 // determine whether a slashing has occured
 fn check_for_slashing(
     Delegated(delegated): Delegated,
     PendingUnbond(pending_unbond): PendingUnbond,
     InflightUnbond(inflight_unbond): InflightUnbond,
     LastReconcileHeight(last_reconcile_height): LastReconcileHeight,
-    delegations: DelegationsReport,
+    delegations: &DelegationsReport,
 ) -> Option<Slashing> {
     if delegated == 0 {
         return None;
@@ -633,32 +999,19 @@ fn check_for_slashing(
         return None;
     }
 
-    let mut adjusted_weights = Vec::with_capacity(delegations.delegated_amounts_per_slot.len());
-
-    for delegation in delegations.delegated_amounts_per_slot {
-        let adjusted_weight =
-            Weight::checked_from_fraction(delegation, delegations.total_delegated)
-                .expect("always: delegation <= total delegation");
-
-        adjusted_weights.push(adjusted_weight);
-    }
-
-    let adjusted_weights =
-        Weights::new(&adjusted_weights).expect("always: one weight per slot & total weight == 1.0");
+    let adjusted_weights = report_weights(delegations);
 
     let slashed_ratio = FixedU256::from_u128(delegations.total_delegated)
         .checked_div(FixedU256::from_u128(delegated))
         .expect("checked: delegated > 0");
 
     let pending_unbond = slashed_ratio
-        .checked_mul(FixedU256::from_u128(pending_unbond))
-        .expect("always: slashed ratio < 1.0")
-        .floor();
+        .checked_mul_u128(pending_unbond)
+        .expect("always: slashed ratio < 1.0");
 
     let inflight_unbond = slashed_ratio
-        .checked_mul(FixedU256::from_u128(inflight_unbond))
-        .expect("always: slashed ratio < 1.0")
-        .floor();
+        .checked_mul_u128(inflight_unbond)
+        .expect("always: slashed ratio < 1.0");
 
     Some(Slashing {
         adjusted_weights,
@@ -669,23 +1022,62 @@ fn check_for_slashing(
     })
 }
 
-fn start_reconcile(Context { repo, env, .. }: Context) -> Transition {
+fn start_reconcile(
+    Context {
+        config, repo, env, ..
+    }: Context,
+) -> Result<Transition, ReconcileError> {
     let Some(last_reconcile_height) = repo.last_reconcile_height() else {
-        return Transition::next(vec![]);
+        return Ok(Transition::next(vec![]));
     };
 
     let Some(delegations) = env.delegations_report() else {
-        return Transition::next(vec![]);
+        return Ok(Transition::next(vec![]));
     };
 
+    if let Some(drift) = check_for_drift(
+        repo.delegated(),
+        repo.pending_unbond(),
+        repo.inflight_unbond(),
+        last_reconcile_height,
+        config.delegation_drift_tolerance(),
+        &delegations,
+    ) {
+        let mut cmds = set![drift.adjusted_weights, Delegated(drift.delegated)];
+
+        if drift.pending_unbond != repo.pending_unbond().0 {
+            cmds.push(PendingUnbond(drift.pending_unbond).into());
+        }
+
+        if drift.inflight_unbond != repo.inflight_unbond().0 {
+            cmds.push(InflightUnbond(drift.inflight_unbond).into());
+        }
+
+        let event = if drift.exceeds_tolerance {
+            Event::DelegationSurplusAbsorbed(drift.delta.unsigned_abs())
+        } else {
+            Event::DelegationDriftCorrected { delta: drift.delta }
+        };
+
+        return Ok(Transition::next(cmds).event(event));
+    }
+
     let Some(slashing) = check_for_slashing(
         repo.delegated(),
         repo.pending_unbond(),
         repo.inflight_unbond(),
         last_reconcile_height,
-        delegations,
+        &delegations,
     ) else {
-        return Transition::next(vec![]);
+        let mut cmds = vec![];
+
+        // the last slot undelegates the rounding remainder of each unbonding, so the per slot
+        // delegations can diverge from the weights while the total delegated is unchanged
+        if delegations.height > last_reconcile_height.0 && delegations.total_delegated > 0 {
+            push_adjusted_weights(&mut cmds, &repo.weights(), report_weights(&delegations));
+        }
+
+        return Ok(Transition::next(cmds));
     };
 
     let mut cmds = set![slashing.adjusted_weights, Delegated(slashing.delegated)];
@@ -698,35 +1090,39 @@ fn start_reconcile(Context { repo, env, .. }: Context) -> Transition {
         cmds.push(InflightUnbond(slashing.inflight_unbond).into());
     }
 
-    Transition::next(cmds).event(Event::SlashDetected(slashing.slashed_ratio))
+    Ok(Transition::next(cmds).event(Event::SlashDetected(slashing.slashed_ratio)))
 }
 
-fn start_redelegate(Context { repo, env, .. }: Context) -> Transition {
+fn start_redelegate(Context { repo, env, .. }: Context) -> Result<Transition, ReconcileError> {
     let Some(LastReconcileHeight(last_reconcile_height)) = repo.last_reconcile_height() else {
-        return Transition::next(vec![]);
+        return Ok(Transition::next(vec![]));
     };
 
     let Some(RedelegationSlot(ValidatorSetSlot(slot))) = repo.redelegation_slot() else {
-        return Transition::next(vec![]);
+        return Ok(Transition::next(vec![]));
     };
 
     let Some(delegations) = env.delegations_report() else {
-        return Transition::next(vec![]);
+        return Ok(Transition::next(vec![]));
     };
 
     // we can only use delegations after the previous reconciliation
     if delegations.height <= last_reconcile_height {
-        return Transition::next(vec![]);
+        return Ok(Transition::next(vec![]));
     }
 
     let to = repo
         .redelegate_to_validator()
         .expect("always: redelegation validator set when redelegation slot set");
 
-    let delegated_amount = delegations
-        .delegated_amounts_per_slot
-        .get(slot)
-        .expect("valid slot index");
+    let delegated_amount =
+        delegations
+            .delegated_amounts_per_slot
+            .get(slot)
+            .ok_or(ReconcileError::SlotOutOfRange {
+                slot,
+                slot_count: delegations.delegated_amounts_per_slot.len(),
+            })?;
 
     let tx_msgs = TxMsgs::single(TxMsg::Redelegate {
         slot: ValidatorSetSlot(slot),
@@ -734,10 +1130,10 @@ fn start_redelegate(Context { repo, env, .. }: Context) -> Transition {
         amount: *delegated_amount,
     });
 
-    Transition::tx(tx_msgs, vec![])
+    Ok(Transition::tx(tx_msgs, vec![]))
 }
 
-fn on_redelegate_success(Context { repo, .. }: Context) -> Transition {
+fn on_redelegate_success(Context { repo, .. }: Context) -> Result<Transition, ReconcileError> {
     let RedelegationSlot(slot) = repo
         .redelegation_slot()
         .expect("always: redelegation slot set for a redelegation to be successful");
@@ -746,44 +1142,40 @@ fn on_redelegate_success(Context { repo, .. }: Context) -> Transition {
         .redelegate_to_validator()
         .expect("always: redelegation validator set for a redelegation to be successful");
 
-    Transition::next(set![Cmd::ClearRedelegationRequest])
-        .event(Event::RedelegationSuccessful { slot, validator })
+    Ok(Transition::next(set![Cmd::ClearRedelegationRequest])
+        .event(Event::RedelegationSuccessful { slot, validator }))
 }
 
 // Do not retry on failure, clear request and move on
-fn on_redelegate_failure(_: Context) -> Transition {
-    Transition::next(set![Cmd::ClearRedelegationRequest])
+fn on_redelegate_failure(_: Context) -> Result<Transition, ReconcileError> {
+    Ok(Transition::next(set![Cmd::ClearRedelegationRequest]))
 }
 
 type Undelegation = (ValidatorSetSlot, NonZeroU128);
 
 // Normalize the weights so they add up to 1.0 if they do not already.
 // If all the given weights are 0, the scaled weights will all be: 1.0 / n weights.
-// Panics if the `weights` add up to > 1.0
-fn normalize_weights(weights: &[Weight]) -> Option<Weights> {
+// Errors if there are no `weights` or they add up to > 1.0
+fn normalize_weights(weights: &[Weight]) -> Result<Weights, ReconcileError> {
     if weights.is_empty() {
-        return None;
+        return Err(ReconcileError::EmptyWeights);
     }
 
     let total_weight = weights
         .iter()
         .copied()
         .map(Weight::into_fixed)
-        .reduce(|acc, w| {
-            acc.checked_add(w)
-                .expect("always: total weights should never overflow Q128.128")
-        })
-        .expect("checked: weights len > 0");
+        .try_fold(FixedU256::zero(), |acc, w| acc.checked_add(w))
+        .ok_or(ReconcileError::WeightsExceedOne)?;
 
     let one = FixedU256::from_u128(1);
 
-    assert!(
-        total_weight <= one,
-        "cannot scale weights that already add up to > 1.0"
-    );
+    if total_weight > one {
+        return Err(ReconcileError::WeightsExceedOne);
+    }
 
     if total_weight == one {
-        return Some(Weights::new_unchecked(weights.to_vec()));
+        return Ok(Weights::new_unchecked(weights.to_vec()));
     }
 
     if total_weight.is_zero() {
@@ -795,7 +1187,7 @@ fn normalize_weights(weights: &[Weight]) -> Option<Weights> {
 
         let equal_weights = vec![equal_weight; weights.len()];
 
-        return Some(Weights::new_unchecked(equal_weights));
+        return Ok(Weights::new_unchecked(equal_weights));
     }
 
     let scaled_weights = weights
@@ -810,7 +1202,17 @@ fn normalize_weights(weights: &[Weight]) -> Option<Weights> {
         })
         .collect();
 
-    Some(Weights::new_unchecked(scaled_weights))
+    Ok(Weights::new_unchecked(scaled_weights))
+}
+
+// Take the subset of slots starting at `start_slot_idx`
+fn slot_subset<T>(slots: &[T], start_slot_idx: usize) -> Result<&[T], ReconcileError> {
+    slots
+        .get(start_slot_idx..)
+        .ok_or(ReconcileError::SlotOutOfRange {
+            slot: start_slot_idx,
+            slot_count: slots.len(),
+        })
 }
 
 fn distribute_undelegations(
@@ -818,65 +1220,120 @@ fn distribute_undelegations(
     Delegated(delegated): Delegated,
     unbond_amount: u128,
     slot_offset: usize,
-) -> impl Iterator<Item = Undelegation> + '_ {
-    assert!(!weights.is_empty(), "cannot undelegate from 0 slots");
+) -> Result<impl Iterator<Item = Undelegation> + '_, ReconcileError> {
+    let scaled_weights = normalize_weights(weights)?;
+
+    // the last slot allocated a share of the unbond amount
+    let last_idx = scaled_weights
+        .as_slice()
+        .iter()
+        .rposition(|w| !w.into_fixed().is_zero());
 
-    normalize_weights(weights)
-        .expect("checked: weights len > 0")
+    let undelegations = scaled_weights
         .into_iter()
         .zip(weights)
-        .map(move |(scaled_w, original_w)| {
+        .enumerate()
+        .scan(0u128, move |allocated, (idx, (scaled_w, original_w))| {
+            // flooring every share can leave the unbond amount short, so the last share is the exact remainder
+            let share = if Some(idx) == last_idx {
+                unbond_amount.saturating_sub(*allocated)
+            } else {
+                scaled_w.apply(unbond_amount)
+            };
+
+            *allocated += share;
+
             // take the minimum of the total delegated amount to a slot and the scaled allocated unbond amount
-            original_w
-                .apply(delegated)
-                .min(scaled_w.apply(unbond_amount))
+            Some(original_w.apply(delegated).min(share))
         })
         .enumerate()
         // skip slots where the split amount is zero
         .filter_map(move |(idx, amount)| {
             Some(ValidatorSetSlot(idx + slot_offset)).zip(NonZeroU128::new(amount))
-        })
+        });
+
+    Ok(undelegations)
+}
+
+// Zero the weights of the deferred slots, so that undelegations are only distributed across the remaining slots
+fn undelegation_weights<'a>(
+    weights: &'a Weights,
+    UndelegateDeferredSlots(deferred_slots): &UndelegateDeferredSlots,
+) -> Cow<'a, [Weight]> {
+    if deferred_slots.is_empty() {
+        return Cow::Borrowed(weights.as_slice());
+    }
+
+    let mut weights = weights.as_slice().to_vec();
+
+    for ValidatorSetSlot(slot) in deferred_slots {
+        if let Some(w) = weights.get_mut(*slot) {
+            *w = Weight::zero();
+        }
+    }
+
+    Cow::Owned(weights)
+}
+
+// The slots from `start_slot_idx` onwards holding a delegation that have reached the max unbonding entries
+fn unbonding_entries_full_slots(
+    config: &dyn Config,
+    env: &dyn Env,
+    weights: &Weights,
+    start_slot_idx: usize,
+) -> UndelegateDeferredSlots {
+    let Some(UnbondingEntriesReport { entries_per_slot }) = env.unbonding_entries_report() else {
+        return UndelegateDeferredSlots::default();
+    };
+
+    let MaxUnbondingEntries(max_entries) = config.max_unbonding_entries();
+
+    let full_slots = weights
+        .as_slice()
+        .iter()
+        .zip(entries_per_slot)
+        .enumerate()
+        .skip(start_slot_idx)
+        .filter(|(_, (w, entries))| !w.into_fixed().is_zero() && *entries >= max_entries)
+        .map(|(slot, _)| ValidatorSetSlot(slot))
+        .collect();
+
+    UndelegateDeferredSlots(full_slots)
 }
 
 fn undelegate_tx_msgs(
     config: &dyn Config,
     repo: &dyn Repository,
+    deferred_slots: &UndelegateDeferredSlots,
     unbond_amount: u128,
-) -> Option<TxMsgs> {
+) -> Result<Option<TxMsgs>, ReconcileError> {
     let UndelegateStartSlot(start_slot_idx) = repo.undelegate_start_slot();
 
     let weights = repo.weights();
 
-    if start_slot_idx == 0 {
-        // no need to take a subset of slot weights
-        let undelegate_msgs = distribute_undelegations(
-            weights.as_slice(),
-            repo.delegated(),
-            unbond_amount,
-            start_slot_idx,
-        )
-        .map(|(slot, amount)| TxMsg::Undelegate(slot, amount.get()));
-
-        return TxMsgBatcher::new(config, repo).batch_msgs(undelegate_msgs);
-    }
+    let weights = undelegation_weights(&weights, deferred_slots);
 
-    let weights = &weights.as_slice()[start_slot_idx..];
+    // take a subset of the slots starting at the start slot index set in a previous round
+    let weights = slot_subset(&weights, start_slot_idx)?;
 
     let undelegate_msgs =
-        distribute_undelegations(weights, repo.delegated(), unbond_amount, start_slot_idx)
+        distribute_undelegations(weights, repo.delegated(), unbond_amount, start_slot_idx)?
             .map(|(slot, amount)| TxMsg::Undelegate(slot, amount.get()));
 
-    TxMsgBatcher::new(config, repo).batch_msgs(undelegate_msgs)
+    Ok(TxMsgBatcher::new(config, repo).batch_msgs(undelegate_msgs))
 }
 
+// Falls back to the `starting_weights` if everything has been undelegated or the adjusted weights are invalid,
+// returning the reason for rejecting the adjusted weights (if any)
 fn undelegate_adjust_weights(
     weights: &Weights,
     previous_delegated: u128,
     current_delegated: u128,
     undelegations: impl Iterator<Item = Undelegation>,
-) -> Option<Weights> {
+    starting_weights: impl FnOnce() -> Weights,
+) -> (Weights, Option<WeightsError>) {
     if current_delegated == 0 {
-        return None;
+        return (starting_weights(), None);
     }
 
     let undelegations: BTreeMap<_, _> = undelegations
@@ -900,10 +1357,10 @@ fn undelegate_adjust_weights(
         adjusted_weights.push(adjusted_w);
     }
 
-    Weights::new(&adjusted_weights)
+    Weights::new_lossy(&adjusted_weights, starting_weights)
 }
 
-fn start_undelegate(Context { repo, config, .. }: Context) -> Transition {
+fn start_undelegate(Context { repo, config, env }: Context) -> Result<Transition, ReconcileError> {
     let PendingUnbond(pending_unbond) = repo.pending_unbond();
     let Delegated(delegated) = repo.delegated();
 
@@ -912,7 +1369,7 @@ fn start_undelegate(Context { repo, config, .. }: Context) -> Transition {
     //
     // Only undelegating if pending unbond <= delegated is a simple way to ensure ordering.
     if pending_unbond > delegated || pending_unbond == 0 {
-        return Transition::next(vec![]);
+        return Ok(Transition::next(vec![]));
     }
 
     let InflightUnbond(inflight_unbond) = repo.inflight_unbond();
@@ -924,85 +1381,228 @@ fn start_undelegate(Context { repo, config, .. }: Context) -> Transition {
         pending_unbond
     };
 
-    let Some(tx_msgs) = undelegate_tx_msgs(config, repo, unbond_amount) else {
-        return Transition::next(vec![]);
+    let UndelegateStartSlot(start_slot_idx) = repo.undelegate_start_slot();
+
+    let weights = repo.weights();
+
+    // the remote chain rejects undelegations from slots at the max unbonding entries, so they sit this round out
+    let deferred_slots = unbonding_entries_full_slots(config, env, &weights, start_slot_idx);
+
+    let deferred_events: Vec<_> = deferred_slots
+        .0
+        .iter()
+        .copied()
+        .map(Event::UnbondingEntriesFull)
+        .collect();
+
+    // only the delegations held by the remaining slots can be undelegated, the rest stays pending
+    let unbond_amount = if deferred_slots.0.is_empty() {
+        unbond_amount
+    } else {
+        slot_subset(
+            &undelegation_weights(&weights, &deferred_slots),
+            start_slot_idx,
+        )?
+        .iter()
+        .map(|w| w.apply(delegated))
+        .sum::<u128>()
+        .min(unbond_amount)
+    };
+
+    let tx_msgs = if unbond_amount > 0 {
+        undelegate_tx_msgs(config, repo, &deferred_slots, unbond_amount)?
+    } else {
+        None
+    };
+
+    let Some(tx_msgs) = tx_msgs else {
+        return Ok(deferred_events
+            .into_iter()
+            .fold(Transition::next(vec![]), Transition::event));
     };
 
     let mut cmds = vec![];
 
-    if inflight_unbond == 0 {
+    if unbond_amount != inflight_unbond {
         cmds.push(InflightUnbond(unbond_amount).into());
     }
 
-    Transition::tx(tx_msgs, cmds)
+    if deferred_slots != repo.undelegate_deferred_slots() {
+        cmds.push(deferred_slots.into());
+    }
+
+    Ok(deferred_events
+        .into_iter()
+        .fold(Transition::tx(tx_msgs, cmds), Transition::event))
 }
 
-fn on_undelegate_success(Context { repo, config, .. }: Context) -> Transition {
+fn on_undelegate_success(
+    Context { repo, config, .. }: Context,
+) -> Result<Transition, ReconcileError> {
     let InflightUnbond(inflight_unbond) = repo.inflight_unbond();
 
-    if let Some(tx_msgs) = undelegate_tx_msgs(config, repo, inflight_unbond) {
-        return Transition::tx(tx_msgs, vec![]);
+    // a successful batch resets the retries available to the next one
+    let reset_retry_count = (repo.undelegate_retry_count() > UndelegateRetryCount(0))
+        .then(|| Cmd::from(UndelegateRetryCount(0)));
+
+    let deferred_slots = repo.undelegate_deferred_slots();
+
+    if let Some(tx_msgs) = undelegate_tx_msgs(config, repo, &deferred_slots, inflight_unbond)? {
+        return Ok(Transition::tx(
+            tx_msgs,
+            reset_retry_count.into_iter().collect(),
+        ));
     };
 
-    let PendingUnbond(pending_unbond) = repo.pending_unbond();
+    let pending_unbond = repo.pending_unbond().checked_sub(inflight_unbond)?;
 
     let Delegated(prev_delegated) = repo.delegated();
 
-    let delegated = prev_delegated - inflight_unbond;
+    let Delegated(delegated) = Delegated(prev_delegated).checked_sub(inflight_unbond)?;
 
-    let mut cmds = set![
-        PendingUnbond(pending_unbond - inflight_unbond),
-        InflightUnbond(0),
-        Delegated(delegated)
-    ];
+    let mut cmds = set![pending_unbond, InflightUnbond(0), Delegated(delegated)];
+
+    cmds.extend(reset_retry_count);
 
     let UndelegateStartSlot(start_slot_idx) = repo.undelegate_start_slot();
 
     if start_slot_idx > 0 {
         // reset starting slot to the first one
         cmds.push(UndelegateStartSlot(0).into());
+    }
+
+    if !deferred_slots.0.is_empty() {
+        cmds.push(UndelegateDeferredSlots::default().into());
+    }
 
+    // the undelegation was not spread in proportion to the weights, so they need adjusting
+    if start_slot_idx > 0 || !deferred_slots.0.is_empty() {
         let weights = repo.weights();
 
+        let undelegation_weights = undelegation_weights(&weights, &deferred_slots);
+
         let undelegations = distribute_undelegations(
-            &weights.as_slice()[start_slot_idx..],
+            slot_subset(&undelegation_weights, start_slot_idx)?,
             Delegated(prev_delegated),
             inflight_unbond,
             start_slot_idx,
-        );
+        )?;
 
-        let adjusted_weights =
-            undelegate_adjust_weights(&weights, prev_delegated, delegated, undelegations)
-                .unwrap_or_else(|| config.starting_weights());
+        let (adjusted_weights, weights_err) =
+            undelegate_adjust_weights(&weights, prev_delegated, delegated, undelegations, || {
+                config.starting_weights()
+            });
+
+        push_adjusted_weights(&mut cmds, &weights, adjusted_weights);
 
-        cmds.push(adjusted_weights.into())
+        if let Some(err) = weights_err {
+            return Ok(Transition::next(cmds)
+                .event(Event::WeightsReset(err))
+                .event(Event::UnbondStarted(inflight_unbond)));
+        }
     }
 
-    Transition::next(cmds).event(Event::UnbondStarted(inflight_unbond))
+    Ok(Transition::next(cmds).event(Event::UnbondStarted(inflight_unbond)))
 }
 
-fn retry_undelegate(Context { repo, config, .. }: Context) -> Transition {
+fn retry_undelegate(ctx: Context) -> Result<Transition, ReconcileError> {
+    let Context { repo, config, .. } = ctx;
+
+    let UndelegateRetryCount(retry_count) = repo.undelegate_retry_count();
+    let MaxUndelegateRetries(max_retries) = config.max_undelegate_retries();
+
+    // stop re-issuing once retries are exhausted, splitting the undelegation to skip the offending slot
+    if retry_count >= max_retries {
+        let (events, cmds) = undelegate_force_next(ctx)?;
+
+        return Ok(events
+            .into_iter()
+            .fold(Transition::next(cmds), Transition::event));
+    }
+
     let InflightUnbond(inflight_unbond) = repo.inflight_unbond();
 
-    let tx_msgs = undelegate_tx_msgs(config, repo, inflight_unbond)
-        .expect("always: messages to re-issue when retrying");
+    let tx_msgs = undelegate_tx_msgs(
+        config,
+        repo,
+        &repo.undelegate_deferred_slots(),
+        inflight_unbond,
+    )?
+    .expect("always: messages to re-issue when retrying");
 
-    Transition::tx(tx_msgs, vec![])
+    Ok(Transition::tx(
+        tx_msgs,
+        set![UndelegateRetryCount(retry_count + 1)],
+    ))
 }
 
-fn undelegate_force_next(Context { repo, config, .. }: Context) -> (Vec<Event>, Vec<Cmd>) {
+// The slot of the first undelegation in the failed batch, if the max retries are exhausted & there are slots after it
+fn undelegate_slot_to_skip(
+    config: &dyn Config,
+    repo: &dyn Repository,
+) -> Result<Option<ValidatorSetSlot>, ReconcileError> {
+    let UndelegateRetryCount(retry_count) = repo.undelegate_retry_count();
+    let MaxUndelegateRetries(max_retries) = config.max_undelegate_retries();
+
+    if retry_count < max_retries {
+        return Ok(None);
+    }
+
+    let UndelegateStartSlot(start_slot_idx) = repo.undelegate_start_slot();
+    let MsgSuccessCount(msg_success_count) = repo.msg_success_count();
+    let InflightUnbond(inflight_unbond) = repo.inflight_unbond();
+
+    let weights = repo.weights();
+
+    let offending_slot = distribute_undelegations(
+        slot_subset(
+            &undelegation_weights(&weights, &repo.undelegate_deferred_slots()),
+            start_slot_idx,
+        )?,
+        repo.delegated(),
+        inflight_unbond,
+        start_slot_idx,
+    )?
+    .nth(msg_success_count)
+    .map(|(slot, _)| slot);
+
+    // skipping the last slot would leave nowhere to undelegate the remainder from
+    Ok(offending_slot.filter(|ValidatorSetSlot(slot)| slot + 1 < weights.as_slice().len()))
+}
+
+fn undelegate_force_next(
+    Context { repo, config, .. }: Context,
+) -> Result<(Vec<Event>, Vec<Cmd>), ReconcileError> {
     let MsgSuccessCount(msg_success_count) = repo.msg_success_count();
     let UndelegateStartSlot(start_slot_idx) = repo.undelegate_start_slot();
 
+    let skip_slot = undelegate_slot_to_skip(config, repo)?;
+
+    // the retry count is per-phase, so is reset on moving to the next phase
+    let reset_retry_count = (repo.undelegate_retry_count() > UndelegateRetryCount(0))
+        .then(|| Cmd::from(UndelegateRetryCount(0)));
+
+    if let Some(ValidatorSetSlot(slot)) = skip_slot.filter(|_| msg_success_count == 0) {
+        // nothing was undelegated, start the remaining undelegation after the offending slot next time
+        return Ok((
+            vec![Event::UndelegateSlotSkipped(ValidatorSetSlot(slot))],
+            set![UndelegateStartSlot(slot + 1), UndelegateRetryCount(0)],
+        ));
+    }
+
     if msg_success_count == 0 {
         // was the last undelegation partial? i.e. forced next after a successful batch
         if start_slot_idx > 0 {
             // no change, try again next time
-            return (vec![], vec![]);
+            return Ok((vec![], reset_retry_count.into_iter().collect()));
         }
 
         // non-partial undelegation, clear inflight unbond
-        return (vec![], set![InflightUnbond(0)]);
+        let mut cmds = set![InflightUnbond(0)];
+
+        cmds.extend(reset_retry_count);
+
+        return Ok((vec![], cmds));
     }
 
     let InflightUnbond(inflight_unbond) = repo.inflight_unbond();
@@ -1012,11 +1612,11 @@ fn undelegate_force_next(Context { repo, config, .. }: Context) -> (Vec<Event>,
     let weights = repo.weights();
 
     let undelegations: Vec<_> = distribute_undelegations(
-        weights.as_slice(),
+        &undelegation_weights(&weights, &repo.undelegate_deferred_slots()),
         Delegated(prev_delegated),
         inflight_unbond,
         start_slot_idx,
-    )
+    )?
     .take(msg_success_count)
     .collect();
 
@@ -1026,99 +1626,132 @@ fn undelegate_force_next(Context { repo, config, .. }: Context) -> (Vec<Event>,
         .try_fold(0u128, |sum, (_, amount)| sum.checked_add(amount.get()))
         .expect("always: inflight unbond <= delegated <= u128::MAX");
 
-    let delegated = prev_delegated
-        .checked_sub(total_unbonded)
-        .expect("always: total unbonded <= inflight unbond <= delegated");
-
-    // Undelegation should start at the slot after the last successful undelegation
-    let undelegate_start_slot = undelegations
-        .last()
-        .map(|(ValidatorSetSlot(slot), _)| slot + 1)
-        .expect("always: undelegations length > 0 when msg success count > 0");
+    let Delegated(delegated) = Delegated(prev_delegated).checked_sub(total_unbonded)?;
+
+    // Undelegation should start at the slot after the last successful undelegation,
+    // or after the offending slot if it is being skipped
+    let undelegate_start_slot = skip_slot.map_or_else(
+        || {
+            undelegations
+                .last()
+                .map(|(ValidatorSetSlot(slot), _)| slot + 1)
+                .expect("always: undelegations length > 0 when msg success count > 0")
+        },
+        |ValidatorSetSlot(slot)| slot + 1,
+    );
 
-    let adjusted_weights = undelegate_adjust_weights(
+    let (adjusted_weights, weights_err) = undelegate_adjust_weights(
         &weights,
         prev_delegated,
         delegated,
         undelegations.into_iter(),
-    )
-    .unwrap_or_else(|| config.starting_weights());
+        || config.starting_weights(),
+    );
 
-    let cmds = set![
+    let mut cmds = set![
         Delegated(delegated),
-        PendingUnbond(pending_unbond - total_unbonded),
-        InflightUnbond(inflight_unbond - total_unbonded),
-        UndelegateStartSlot(undelegate_start_slot),
-        adjusted_weights
+        PendingUnbond(pending_unbond).checked_sub(total_unbonded)?,
+        InflightUnbond(inflight_unbond).checked_sub(total_unbonded)?,
+        UndelegateStartSlot(undelegate_start_slot)
     ];
 
-    let events = vec![Event::UnbondStarted(total_unbonded)];
+    cmds.extend(reset_retry_count);
+
+    push_adjusted_weights(&mut cmds, &weights, adjusted_weights);
+
+    let events = weights_err
+        .map(Event::WeightsReset)
+        .into_iter()
+        .chain([Event::UnbondStarted(total_unbonded)])
+        .chain(skip_slot.map(Event::UndelegateSlotSkipped))
+        .collect();
 
-    (events, cmds)
+    Ok((events, cmds))
 }
 
-fn start_transfer_undelegated(Context { repo, env, .. }: Context) -> Transition {
+fn start_transfer_undelegated(
+    Context { repo, env, .. }: Context,
+) -> Result<Transition, ReconcileError> {
     let Some(LastReconcileHeight(last_reconcile_height)) = repo.last_reconcile_height() else {
-        return Transition::next(vec![]);
+        return Ok(Transition::next(vec![]));
     };
 
     let Some(UndelegatedBalanceReport { remote_balance, .. }) = env.undelegated_balance_report()
     else {
-        return Transition::next(vec![]);
+        return Ok(Transition::next(vec![]));
     };
 
     if remote_balance.height <= last_reconcile_height {
-        return Transition::next(vec![]);
+        return Ok(Transition::next(vec![]));
     }
 
-    let RemoteBalance(amount) = remote_balance.amount;
-
-    if amount == 0 {
-        return Transition::next(vec![]);
+    if remote_balance.amount == RemoteBalance(0) {
+        return Ok(Transition::next(vec![]));
     };
 
     let InflightDeposit(inflight_deposit) = repo.inflight_deposit();
 
-    let amount = amount.checked_sub(inflight_deposit).expect(
-        "always: remote balance >= inflight deposit when report height > last reconcile height",
-    );
+    // the remote balance includes any inflight deposit when report height > last reconcile height
+    let RemoteBalance(amount) = remote_balance.amount.checked_sub(inflight_deposit)?;
 
     if amount == 0 {
-        return Transition::next(vec![]);
+        return Ok(Transition::next(vec![]));
     };
 
     let tx_msgs = TxMsgs::single(TxMsg::TransferInUndelegated(amount));
 
-    Transition::tx(tx_msgs, vec![])
+    Ok(Transition::tx(tx_msgs, vec![]))
+}
+
+fn on_transfer_undelegated_success(_: Context) -> Result<Transition, ReconcileError> {
+    Ok(Transition::next(vec![]).event(Event::UndelegatedAssetsTransferred))
 }
 
-fn on_transfer_undelegated_success(_: Context) -> Transition {
-    Transition::next(vec![]).event(Event::UndelegatedAssetsTransferred)
+fn transfer_undelegated_force_next(_: Context) -> Result<(Vec<Event>, Vec<Cmd>), ReconcileError> {
+    // nothing is tracked while the transfer is inflight, the undelegated assets remain in the remote balance
+    // & will be transferred in full next cycle once a fresh balance report is posted
+    Ok((vec![], vec![]))
 }
 
-fn start_transfer_pending_deposits(Context { repo, .. }: Context) -> Transition {
+fn start_transfer_pending_deposits(
+    Context { repo, .. }: Context,
+) -> Result<Transition, ReconcileError> {
     let PendingDeposit(pending_deposit) = repo.pending_deposit();
     let InflightDeposit(inflight_deposit) = repo.inflight_deposit();
 
     // Nothing to do if there are no pending deposits or if there are still inflight deposits to clear
     if pending_deposit == 0 || inflight_deposit > 0 {
-        return Transition::next(vec![]);
+        return Ok(Transition::next(vec![]));
     }
 
     let tx_msgs = TxMsgs::single(TxMsg::TransferOutPendingDeposit(pending_deposit));
 
-    let cmds = set![InflightDeposit(pending_deposit)];
+    let cmds = set![InflightDeposit::from(PendingDeposit(pending_deposit))];
 
-    Transition::tx(tx_msgs, cmds)
+    Ok(Transition::tx(tx_msgs, cmds))
 }
 
-fn on_transfer_pending_deposits_success(Context { repo, .. }: Context) -> Transition {
-    let PendingDeposit(pending_deposit) = repo.pending_deposit();
-
+fn on_transfer_pending_deposits_success(
+    Context { repo, .. }: Context,
+) -> Result<Transition, ReconcileError> {
     let InflightDeposit(inflight_deposit) = repo.inflight_deposit();
 
-    Transition::next(set![PendingDeposit(pending_deposit - inflight_deposit)])
-        .event(Event::DepositsTransferred(inflight_deposit))
+    let pending_deposit = repo.pending_deposit().checked_sub(inflight_deposit)?;
+
+    Ok(Transition::next(set![pending_deposit]).event(Event::DepositsTransferred(inflight_deposit)))
+}
+
+fn transfer_pending_deposits_force_next(
+    Context { repo, .. }: Context,
+) -> Result<(Vec<Event>, Vec<Cmd>), ReconcileError> {
+    // a transfer is only issued when nothing is inflight, so the inflight deposit is solely the failed transfer
+    if repo.inflight_deposit() == InflightDeposit(0) {
+        return Ok((vec![], vec![]));
+    }
+
+    // the pending deposit is only reduced on success, clearing the inflight deposit returns the amount to pending
+    // so it can be transferred again next cycle
+    Ok((vec![], set![InflightDeposit(0)]))
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -1132,29 +1765,29 @@ fn delegate_phase_balances(
     config: &dyn Config,
     repo: &dyn Repository,
     env: &dyn Env,
-) -> Option<DelegatePhaseBalances> {
-    let InflightDelegation(inflight_delegation) = repo.inflight_delegation();
+) -> Result<Option<DelegatePhaseBalances>, ArithmeticError> {
+    let inflight_delegation = repo.inflight_delegation();
 
-    if inflight_delegation != 0 {
-        return Some(DelegatePhaseBalances {
-            delegation: InflightDelegation(inflight_delegation),
+    if inflight_delegation.0 != 0 {
+        return Ok(Some(DelegatePhaseBalances {
+            delegation: inflight_delegation,
             rewards_receivable: repo.inflight_rewards_receivable(),
             fee_payable: repo.inflight_fee_payable(),
-        });
+        }));
     }
 
     let delegate_deposits_only = || {
-        let InflightDeposit(inflight_deposit) = repo.inflight_deposit();
+        let inflight_deposit = repo.inflight_deposit();
 
-        if inflight_deposit == 0 {
-            return None;
+        if inflight_deposit.0 == 0 {
+            return Ok(None);
         }
 
-        Some(DelegatePhaseBalances {
-            delegation: InflightDelegation(inflight_deposit),
+        Ok(Some(DelegatePhaseBalances {
+            delegation: inflight_deposit.into(),
             rewards_receivable: InflightRewardsReceivable(0),
             fee_payable: InflightFeePayable(0),
-        })
+        }))
     };
 
     let DelegateStartSlot(start_slot) = repo.delegate_start_slot();
@@ -1162,23 +1795,21 @@ fn delegate_phase_balances(
     // was the previous delegation a partial one?
     if start_slot > 0 {
         // delegations should be comprised of previously moved rewards and inflight deposits
-        let InflightDeposit(inflight_deposit) = repo.inflight_deposit();
         let InflightRewardsReceivable(rewards) = repo.inflight_rewards_receivable();
 
-        let inflight_delegation = inflight_deposit
-            .checked_add(rewards)
-            .expect("always: this inflight delegation < previous inflight delegation");
+        let inflight_delegation =
+            InflightDelegation::from(repo.inflight_deposit()).checked_add(rewards)?;
 
         assert!(
-            inflight_delegation > 0,
+            inflight_delegation.0 > 0,
             "inflight delegation > 0 when the previous delegation was partial"
         );
 
-        return Some(DelegatePhaseBalances {
-            delegation: InflightDelegation(inflight_delegation),
+        return Ok(Some(DelegatePhaseBalances {
+            delegation: inflight_delegation,
             rewards_receivable: InflightRewardsReceivable(rewards),
             fee_payable: InflightFeePayable(0),
-        });
+        }));
     }
 
     let Some(last_reconcile_height) = repo.last_reconcile_height() else {
@@ -1189,24 +1820,60 @@ fn delegate_phase_balances(
         return delegate_deposits_only();
     };
 
-    let InflightDeposit(inflight_deposit) = repo.inflight_deposit();
+    let deposit_delegation = InflightDelegation::from(repo.inflight_deposit());
 
     let Some(fee) = fee_metadata(config, env).fee_bps(last_reconcile_height, env.current_height())
     else {
-        return Some(DelegatePhaseBalances {
-            delegation: InflightDelegation(inflight_deposit + total_rewards.get()),
+        return Ok(Some(DelegatePhaseBalances {
+            delegation: deposit_delegation.checked_add(total_rewards.get())?,
             rewards_receivable: InflightRewardsReceivable(total_rewards.get()),
             fee_payable: InflightFeePayable(0),
-        });
+        }));
     };
 
     let (rewards_receivable, fee_payable) = fee.apply_to(total_rewards);
 
-    Some(DelegatePhaseBalances {
-        delegation: InflightDelegation(inflight_deposit + rewards_receivable.get()),
+    Ok(Some(DelegatePhaseBalances {
+        delegation: deposit_delegation.checked_add(rewards_receivable.get())?,
         rewards_receivable: InflightRewardsReceivable(rewards_receivable.get()),
         fee_payable: fee_payable.map_or(InflightFeePayable(0), |fee| InflightFeePayable(fee.get())),
-    })
+    }))
+}
+
+/// The Authz sends the delegate phase would bundle with its delegations given the current balances
+pub fn delegate_authz_msgs(
+    config: &dyn Config,
+    repo: &dyn Repository,
+    env: &dyn Env,
+) -> DelegateAuthzMsgs {
+    let InflightDelegation(inflight_delegation) = repo.inflight_delegation();
+
+    let DelegateStartSlot(start_slot) = repo.delegate_start_slot();
+
+    // the remainder of a partial delegation only carries the previously moved rewards, avoid
+    // asserting on the balances as this may be called ahead of the delegate phase
+    if inflight_delegation == 0 && start_slot > 0 {
+        let InflightRewardsReceivable(rewards_receivable) = repo.inflight_rewards_receivable();
+
+        return DelegateAuthzMsgs {
+            has_rewards: rewards_receivable != 0,
+            has_fee_payable: false,
+        };
+    }
+
+    // an inconsistent state is surfaced when the delegate phase runs, there is nothing to estimate
+    let Ok(Some(balances)) = delegate_phase_balances(config, repo, env) else {
+        return DelegateAuthzMsgs::default();
+    };
+
+    let InflightRewardsReceivable(rewards_receivable) = balances.rewards_receivable;
+
+    let InflightFeePayable(fee_payable) = balances.fee_payable;
+
+    DelegateAuthzMsgs {
+        has_rewards: rewards_receivable != 0,
+        has_fee_payable: fee_payable != 0 && env.fee_recipient().is_some(),
+    }
 }
 
 // Invert the weights and then normalize to 1.0
@@ -1248,66 +1915,259 @@ fn rebalance_weights(weights: Weights) -> Weights {
 
 type Delegation = (ValidatorSetSlot, NonZeroU128);
 
-// distribute delegations so that the weights trend towards equalisation, i.e. lower weighted slots receive more
+/// The target weights and the currently delegated amount used to steer delegations towards the target
+struct DelegationTarget {
+    weights: Vec<Weight>,
+    delegated: u128,
+}
+
+/// Returns the target weights if they cover the validator set, a target that does not is ignored
+fn covering_target_weights(config: &dyn Config) -> Option<Weights> {
+    let ValidatorSetSize(validator_set_size) = config.validator_set_size();
+
+    config
+        .target_weights()
+        .filter(|target_weights| target_weights.as_slice().len() == validator_set_size)
+}
+
+/// Returns the order the configured delegation order funds the slots in given their current weights,
+/// empty when funding in ascending slot index order
+fn configured_slot_order(config: &dyn Config, repo: &dyn Repository) -> DelegateSlotOrder {
+    if config.delegation_order() != DelegationOrder::MostUnderweightFirst {
+        return DelegateSlotOrder::default();
+    }
+
+    let weights = repo.weights();
+
+    let weights = weights.as_slice();
+
+    let mut slots: Vec<usize> = (0..weights.len()).collect();
+
+    match covering_target_weights(config) {
+        // a slot is further below its target if its weight less its target is lower,
+        // compared without subtracting as slots may be either side of their target
+        Some(target_weights) => {
+            let target_weights = target_weights.as_slice();
+
+            slots.sort_by(|&a, &b| {
+                let a_deficit = weights[a].into_raw() + target_weights[b].into_raw();
+                let b_deficit = weights[b].into_raw() + target_weights[a].into_raw();
+
+                a_deficit.cmp(&b_deficit)
+            });
+        }
+
+        None => slots.sort_by_key(|&slot| weights[slot].into_raw()),
+    }
+
+    DelegateSlotOrder(slots.into_iter().map(ValidatorSetSlot).collect())
+}
+
+/// The slots yet to be delegated to in the phase's slot order, with their current & target weights.
+/// The `DelegateStartSlot` is a position in this order rather than a slot index.
+struct DelegationSlots {
+    slots: Vec<usize>,
+    weights: Vec<Weight>,
+    target: Option<DelegationTarget>,
+}
+
+impl DelegationSlots {
+    fn new(
+        config: &dyn Config,
+        repo: &dyn Repository,
+        weights: &[Weight],
+        DelegateSlotOrder(slot_order): &DelegateSlotOrder,
+        start_slot_idx: usize,
+    ) -> Result<Self, ReconcileError> {
+        // an order that does not cover the slots is stale, fall back to slot index order
+        let slots: Vec<usize> = if slot_order.len() == weights.len() {
+            slot_order
+                .iter()
+                .map(|&ValidatorSetSlot(slot)| slot)
+                .collect()
+        } else {
+            (0..weights.len()).collect()
+        };
+
+        let slots = slot_subset(&slots, start_slot_idx)?.to_vec();
+
+        let slot_weights = |weights: &[Weight]| slots.iter().map(|&slot| weights[slot]).collect();
+
+        let Delegated(delegated) = repo.delegated();
+
+        Ok(Self {
+            weights: slot_weights(weights),
+            target: covering_target_weights(config).map(|target_weights| DelegationTarget {
+                weights: slot_weights(target_weights.as_slice()),
+                delegated,
+            }),
+            slots,
+        })
+    }
+
+    fn distribute(
+        &self,
+        total_delegation: u128,
+    ) -> Result<impl Iterator<Item = Delegation>, ReconcileError> {
+        distribute_delegations(
+            &self.weights,
+            self.target.as_ref(),
+            total_delegation,
+            self.slots.clone(),
+        )
+    }
+}
+
+// split the delegation so that each slot receives its shortfall relative to the target weights
+fn target_rebalance_weights(
+    weights: &[Weight],
+    target: &DelegationTarget,
+    total_delegation: u128,
+) -> Result<Weights, ReconcileError> {
+    let target_weights = normalize_weights(&target.weights)?;
+
+    let current_delegations: Vec<u128> =
+        weights.iter().map(|w| w.apply(target.delegated)).collect();
+
+    let total_after_delegation = current_delegations
+        .iter()
+        .try_fold(total_delegation, |sum, d| sum.checked_add(*d))
+        .expect("always: total delegated amount should never overflow u128");
+
+    let shortfalls: Vec<u128> = target_weights
+        .as_slice()
+        .iter()
+        .zip(&current_delegations)
+        .map(|(target_w, current)| {
+            target_w
+                .apply(total_after_delegation)
+                .saturating_sub(*current)
+        })
+        .collect();
+
+    let total_shortfall = shortfalls
+        .iter()
+        .try_fold(0u128, |sum, s| sum.checked_add(*s))
+        .expect("always: total shortfall <= total after delegation");
+
+    // all slots are at or above target, delegate according to the target itself
+    if total_shortfall == 0 {
+        return Ok(target_weights);
+    }
+
+    let rebalance_weights = shortfalls
+        .into_iter()
+        .map(|shortfall| {
+            Weight::checked_from_fraction(shortfall, total_shortfall)
+                .expect("always: shortfall <= total shortfall")
+        })
+        .collect();
+
+    Ok(Weights::new_unchecked(rebalance_weights))
+}
+
+// distribute delegations so that the weights trend towards the target weights if set,
+// otherwise towards equalisation, i.e. lower weighted slots receive more.
+// `slots` holds the validator set slot of each of the `weights`, in the order they are delegated to
 fn distribute_delegations(
     weights: &[Weight],
+    target: Option<&DelegationTarget>,
     total_delegation: u128,
-    slot_offset: usize,
-) -> impl Iterator<Item = Delegation> {
-    assert!(
-        !weights.is_empty(),
-        "cannot distribute delegations across 0 slots"
-    );
+    slots: Vec<usize>,
+) -> Result<impl Iterator<Item = Delegation>, ReconcileError> {
+    let rebalance_weights = match target {
+        Some(target) => target_rebalance_weights(weights, target, total_delegation)?,
+        None => rebalance_weights(normalize_weights(weights)?),
+    };
 
-    let scaled_weights = normalize_weights(weights).expect("checked: weights len > 0");
+    let rebalance_weights = rebalance_weights.as_slice();
 
-    let rebalance_weights = rebalance_weights(scaled_weights);
+    // round each share to the nearest unit (halves to even), so that no slot is systematically short-changed
+    let mut delegations: Vec<u128> = rebalance_weights
+        .iter()
+        .map(|w| w.apply_round(total_delegation))
+        .collect();
 
-    let mut total_allocated = 0u128;
-    let mut delegations = Vec::with_capacity(weights.len());
+    let total_allocated = delegations
+        .iter()
+        .try_fold(0u128, |total, delegation| total.checked_add(*delegation))
+        .expect(
+            "always: rounded shares sum to within half a unit per slot of the total delegation",
+        );
 
-    for weight in rebalance_weights.as_slice() {
-        let delegation = weight.apply(total_delegation);
+    // settle the rounding difference a unit at a time, highest weighted slots first,
+    // never moving a share beyond the floor or ceiling of its exact amount
+    let mut settle_order: Vec<usize> = (0..delegations.len()).collect();
 
-        total_allocated = total_allocated
-            .checked_add(delegation)
-            .expect("always: total allocated <= total delegation");
+    settle_order.sort_by_key(|&idx| std::cmp::Reverse(rebalance_weights[idx].into_fixed()));
 
-        delegations.push(delegation);
+    let mut unallocated = total_delegation.saturating_sub(total_allocated);
+    let mut overallocated = total_allocated.saturating_sub(total_delegation);
+
+    for idx in settle_order {
+        let weight = rebalance_weights[idx];
+
+        if unallocated > 0 && delegations[idx] < weight.apply_ceil(total_delegation) {
+            delegations[idx] += 1;
+            unallocated -= 1;
+        }
+
+        if overallocated > 0 && delegations[idx] > weight.apply(total_delegation) {
+            delegations[idx] -= 1;
+            overallocated -= 1;
+        }
     }
 
-    let unallocated = total_allocated.abs_diff(total_delegation);
+    // every unit over the total comes from a share rounded above its floor, and each of those gives a unit back
+    if overallocated > 0 {
+        return Err(ReconcileError::InvariantViolated(
+            "rounded delegation shares exceed the total delegation",
+        ));
+    }
 
-    // assign any unallocated delegation to the lowest weighted slot
-    let (lowest_weight_slot_idx, _) = weights
-        .iter()
-        .enumerate()
-        .min_by_key(|(_, w)| w.into_fixed())
-        .expect("checked: weights len > 0");
+    // assign anything still unallocated (weights short of 1.0) to the lowest weighted slot,
+    // or the most underweight slot if targeting
+    let (unallocated_slot_idx, _) = if target.is_some() {
+        rebalance_weights
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, w)| w.into_fixed())
+            .expect("checked: weights len > 0")
+    } else {
+        weights
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, w)| w.into_fixed())
+            .expect("checked: weights len > 0")
+    };
 
-    delegations[lowest_weight_slot_idx] = delegations[lowest_weight_slot_idx]
+    delegations[unallocated_slot_idx] = delegations[unallocated_slot_idx]
         .checked_add(unallocated)
         .expect("always: any slot allocation + unallocated <= total delegation");
 
-    delegations
+    let delegations = slots
         .into_iter()
-        // get the indexes of the slots
-        .enumerate()
+        .zip(delegations)
         // skip slots where the split amount is zero
-        .filter_map(move |(idx, amount)| {
-            NonZeroU128::new(amount).map(|amount| (ValidatorSetSlot(idx + slot_offset), amount))
-        })
+        .filter_map(|(slot, amount)| {
+            NonZeroU128::new(amount).map(|amount| (ValidatorSetSlot(slot), amount))
+        });
+
+    Ok(delegations)
 }
 
+/// Builds the delegate phase messages, always ordered as `[SendRewardsReceivable?, Delegate*, SendFee?]`.
+/// `delegate_force_next` relies on this ordering to derive how many delegations succeeded from the
+/// message success count, so it must not change without updating that accounting.
 fn delegate_phase_msgs(
-    weights: &[Weight],
+    slots: &DelegationSlots,
     balances: DelegatePhaseBalances,
-    slot_offset: usize,
     fee_recipient: Option<FeeRecipient>,
-) -> impl Iterator<Item = TxMsg> {
+) -> Result<impl Iterator<Item = TxMsg>, ReconcileError> {
     let InflightDelegation(inflight_delegation) = balances.delegation;
 
-    let delegate_msgs = distribute_delegations(weights, inflight_delegation, slot_offset)
+    let delegate_msgs = slots
+        .distribute(inflight_delegation)?
         // create undelegate msg
         .map(|(slot, amount)| TxMsg::Delegate(slot, amount.get()));
 
@@ -1326,10 +2186,12 @@ fn delegate_phase_msgs(
         .map(|(fee, recipient)| AuthzMsg::SendFee(recipient, fee))
         .map(|auth_z_msg| TxMsg::Authz(vec![auth_z_msg]));
 
-    send_rewards_receivable
+    let msgs = send_rewards_receivable
         .into_iter()
         .chain(delegate_msgs)
-        .chain(send_fee_msg)
+        .chain(send_fee_msg);
+
+    Ok(msgs)
 }
 
 fn delegate_tx_msgs(
@@ -1337,29 +2199,18 @@ fn delegate_tx_msgs(
     repo: &dyn Repository,
     env: &dyn Env,
     balances: DelegatePhaseBalances,
-) -> Option<TxMsgs> {
+    slot_order: &DelegateSlotOrder,
+) -> Result<Option<TxMsgs>, ReconcileError> {
     let DelegateStartSlot(start_slot_idx) = repo.delegate_start_slot();
 
     let weights = repo.weights();
 
-    if start_slot_idx == 0 {
-        // no need to take a subset of slot weights
-        let msgs = delegate_phase_msgs(
-            weights.as_slice(),
-            balances,
-            start_slot_idx,
-            env.fee_recipient(),
-        );
-
-        return TxMsgBatcher::new(config, repo).batch_msgs(msgs);
-    }
-
-    // take a subset of the slots starting at the start slot index set in a previous round
-    let weights = &weights.as_slice()[start_slot_idx..];
+    // take a subset of the slots starting at the start slot position set in a previous round
+    let slots = DelegationSlots::new(config, repo, weights.as_slice(), slot_order, start_slot_idx)?;
 
-    let msgs = delegate_phase_msgs(weights, balances, start_slot_idx, env.fee_recipient());
+    let msgs = delegate_phase_msgs(&slots, balances, env.fee_recipient())?;
 
-    TxMsgBatcher::new(config, repo).batch_msgs(msgs)
+    Ok(TxMsgBatcher::new(config, repo).batch_msgs(msgs))
 }
 
 fn delegate_adjust_weights(
@@ -1412,16 +2263,30 @@ fn try_withdraw_rewards(config: &dyn Config, repo: &dyn Repository) -> Transitio
     Transition::tx(tx_msgs, vec![])
 }
 
-fn start_delegate(Context { config, repo, env }: Context) -> Transition {
-    let Some(delegate_balances) = delegate_phase_balances(config, repo, env) else {
-        return try_withdraw_rewards(config, repo);
+fn start_delegate(Context { config, repo, env }: Context) -> Result<Transition, ReconcileError> {
+    let Some(delegate_balances) = delegate_phase_balances(config, repo, env)? else {
+        return Ok(try_withdraw_rewards(config, repo));
+    };
+
+    let DelegateStartSlot(start_slot) = repo.delegate_start_slot();
+
+    // the slot order is fixed for the phase, so a partial delegation resumes in the order it began in
+    // even though its successful delegations have since shifted the weights
+    let slot_order = if start_slot == 0 {
+        configured_slot_order(config, repo)
+    } else {
+        repo.delegate_slot_order()
     };
 
-    let tx_msgs = delegate_tx_msgs(config, repo, env, delegate_balances)
+    let tx_msgs = delegate_tx_msgs(config, repo, env, delegate_balances, &slot_order)?
         .expect("always: at least one message if there are delegatable balances");
 
     let mut cmds = vec![];
 
+    if slot_order != repo.delegate_slot_order() {
+        cmds.push(slot_order.into());
+    }
+
     if delegate_balances.delegation.0 != 0 {
         cmds.push(delegate_balances.delegation.into());
     }
@@ -1434,53 +2299,56 @@ fn start_delegate(Context { config, repo, env }: Context) -> Transition {
         cmds.push(delegate_balances.fee_payable.into());
     }
 
-    Transition::tx(tx_msgs, cmds)
+    Ok(Transition::tx(tx_msgs, cmds))
 }
 
-fn on_delegate_success(Context { config, repo, env }: Context) -> Transition {
-    let Some(delegate_balances) = delegate_phase_balances(config, repo, env) else {
-        return try_withdraw_rewards(config, repo);
+fn on_delegate_success(
+    Context { config, repo, env }: Context,
+) -> Result<Transition, ReconcileError> {
+    let Some(delegate_balances) = delegate_phase_balances(config, repo, env)? else {
+        return Ok(try_withdraw_rewards(config, repo));
     };
 
-    if let Some(tx_msgs) = delegate_tx_msgs(config, repo, env, delegate_balances) {
-        return Transition::tx(tx_msgs, vec![]);
+    let slot_order = repo.delegate_slot_order();
+
+    if let Some(tx_msgs) = delegate_tx_msgs(config, repo, env, delegate_balances, &slot_order)? {
+        return Ok(Transition::tx(tx_msgs, vec![]));
     }
 
     let Delegated(prev_delegated) = repo.delegated();
 
     let InflightDelegation(inflight_delegation) = delegate_balances.delegation;
 
-    let delegated = prev_delegated
-        .checked_add(inflight_delegation)
-        .expect("always: total delegated amount should never overflow u128");
+    let Delegated(delegated) = Delegated(prev_delegated).checked_add(inflight_delegation)?;
 
     let weights = repo.weights();
 
     let DelegateStartSlot(start_slot) = repo.delegate_start_slot();
 
-    let delegations = distribute_delegations(
-        &weights.as_slice()[start_slot..],
-        inflight_delegation,
-        start_slot,
-    );
+    let delegations =
+        DelegationSlots::new(config, repo, weights.as_slice(), &slot_order, start_slot)?
+            .distribute(inflight_delegation)?;
 
     let adjusted_weights =
         delegate_adjust_weights(&weights, prev_delegated, delegated, delegations);
 
-    let cmds = set![
+    let mut cmds = set![
         Delegated(delegated),
         InflightDelegation(0),
         InflightDeposit(0),
         InflightRewardsReceivable(0),
         InflightFeePayable(0),
-        DelegateStartSlot(0),
-        adjusted_weights
+        DelegateStartSlot(0)
     ];
 
-    Transition::next(cmds).event(Event::DelegationsIncreased(inflight_delegation))
+    push_adjusted_weights(&mut cmds, &weights, adjusted_weights);
+
+    Ok(Transition::next(cmds).event(Event::DelegationsIncreased(inflight_delegation)))
 }
 
-fn delegate_force_next(Context { repo, .. }: Context) -> (Vec<Event>, Vec<Cmd>) {
+fn delegate_force_next(
+    Context { config, repo, .. }: Context,
+) -> Result<(Vec<Event>, Vec<Cmd>), ReconcileError> {
     let MsgSuccessCount(msg_success_count) = repo.msg_success_count();
     let DelegateStartSlot(start_slot_idx) = repo.delegate_start_slot();
 
@@ -1488,11 +2356,11 @@ fn delegate_force_next(Context { repo, .. }: Context) -> (Vec<Event>, Vec<Cmd>)
         // was the last delegation partial? i.e. forced next after a successful batch
         if start_slot_idx > 0 {
             // no change, try again next time
-            return (vec![], vec![]);
+            return Ok((vec![], vec![]));
         }
 
         // no batches ever succeeded: clear inflight balances, try again next time as normal
-        return (
+        return Ok((
             vec![],
             set![
                 InflightDelegation(0),
@@ -1500,7 +2368,7 @@ fn delegate_force_next(Context { repo, .. }: Context) -> (Vec<Event>, Vec<Cmd>)
                 InflightRewardsReceivable(0),
                 InflightFeePayable(0)
             ],
-        );
+        ));
     }
 
     let InflightDelegation(inflight_delegation) = repo.inflight_delegation();
@@ -1510,65 +2378,97 @@ fn delegate_force_next(Context { repo, .. }: Context) -> (Vec<Event>, Vec<Cmd>)
     let weights = repo.weights();
 
     let delegate_msg_success_count = if start_slot_idx == 0 && previous_rewards > 0 {
-        // The first message is not a delegate msgs, but an authz bank send
+        // The first message is not a delegate msg, but an authz bank send (see `delegate_phase_msgs`)
         msg_success_count - 1
     } else {
         msg_success_count
     };
 
-    let delegations: Vec<_> =
-        distribute_delegations(weights.as_slice(), inflight_delegation, start_slot_idx)
-            .take(delegate_msg_success_count)
-            .collect();
+    let slots = DelegationSlots::new(
+        config,
+        repo,
+        weights.as_slice(),
+        &repo.delegate_slot_order(),
+        start_slot_idx,
+    )?;
+
+    let delegations: Vec<_> = slots
+        .distribute(inflight_delegation)?
+        .take(delegate_msg_success_count)
+        .collect();
 
     let successfully_delegated: u128 = delegations
         .iter()
         .try_fold(0u128, |sum, (_, amount)| sum.checked_add(amount.get()))
         .expect("always: successfully delegated < inflight delegation");
 
-    let delegated = prev_delegated
-        .checked_add(successfully_delegated)
-        .expect("always: total delegated amount should never overflow u128");
+    let Delegated(delegated) = Delegated(prev_delegated).checked_add(successfully_delegated)?;
 
-    // The delegation should recommence at the slot after the last successful undelegation
-    let delegate_start_slot = delegations
+    let (ValidatorSetSlot(last_delegated_slot), _) = delegations
         .last()
-        .map(|(ValidatorSetSlot(slot), _)| slot + 1)
         .expect("always: delegations length > 0 when msg success count > 0");
 
+    // The delegation should recommence at the position in the slot order after the last successful delegation
+    let delegate_start_slot = slots
+        .slots
+        .iter()
+        .position(|slot| slot == last_delegated_slot)
+        .map(|position| start_slot_idx + position + 1)
+        .expect("always: delegations are made to the slots in the slot order");
+
     let adjusted_weights =
         delegate_adjust_weights(&weights, prev_delegated, delegated, delegations.into_iter());
 
-    let InflightDeposit(mut inflight_deposit) = repo.inflight_deposit();
+    let mut inflight_deposit = repo.inflight_deposit();
 
     // draw down rewards first
     let remaining_rewards = previous_rewards.saturating_sub(successfully_delegated);
 
+    // inflight deposit == (inflight delegation - inflight rewards)
     if remaining_rewards == 0 {
-        inflight_deposit = inflight_deposit
-            .checked_sub(successfully_delegated.abs_diff(previous_rewards))
-            .expect("always: inflight deposit == (inflight delegation - inflight rewards)");
+        inflight_deposit =
+            inflight_deposit.checked_sub(successfully_delegated.abs_diff(previous_rewards))?;
     }
 
-    let cmds = set![
+    let mut cmds = set![
         Delegated(delegated),
         // Clear inflight delegation so it is recalculated on the next pass
         InflightDelegation(0),
-        InflightDeposit(inflight_deposit),
+        inflight_deposit,
         InflightRewardsReceivable(remaining_rewards),
         // Discard any fee payment
         InflightFeePayable(0),
-        DelegateStartSlot(delegate_start_slot),
-        adjusted_weights
+        DelegateStartSlot(delegate_start_slot)
     ];
 
+    push_adjusted_weights(&mut cmds, &weights, adjusted_weights);
+
     let events = vec![Event::DelegationsIncreased(successfully_delegated)];
 
-    (events, cmds)
+    Ok((events, cmds))
+}
+
+// The stored weights are relied upon by every delegation phase, reject them up front if malformed
+fn validate_weights(Context { config, repo, .. }: Context) -> Result<(), ReconcileError> {
+    let weights = repo.weights();
+
+    let weights = normalize_weights(weights.as_slice())?;
+
+    let ValidatorSetSize(validator_set_size) = config.validator_set_size();
+
+    weights.ensure_slot_count(validator_set_size)?;
+
+    Ok(())
+}
+
+// Phases out of the cycle's scope are passed over without issuing any txs
+fn skip_phase(_: Context) -> Result<Transition, ReconcileError> {
+    Ok(Transition::next(vec![]))
 }
 
-const fn handler(phase: Phase, state: State) -> Handler {
+const fn handler(scope: ReconcileScope, phase: Phase, state: State) -> Handler {
     match (phase, state) {
+        (_, State::Idle) if !scope.includes(phase) => skip_phase,
         (Phase::SetupRewardsAddress, State::Idle | State::Failed) => start_setup_rewards_address,
         (Phase::SetupRewardsAddress, State::Pending) => on_setup_rewards_address_success,
         (Phase::SetupAuthz, State::Idle | State::Failed) => start_setup_authz,
@@ -1597,14 +2497,20 @@ fn reconcile(
     mut state: State,
     mut intermediate_repo: IntermediateRepo,
     mut all_events: Vec<Event>,
-) -> Response {
+    scope: ReconcileScope,
+) -> Result<Response, ReconcileError> {
     let mut tx_skip_count = 0;
+    let mut transitions_traversed = 0;
+    let mut events_emitted = all_events.len();
 
     loop {
-        let Transition { kind, cmds, events } = handler(phase, state)(Context {
+        let Transition { kind, cmds, events } = handler(scope, phase, state)(Context {
             repo: &intermediate_repo,
             ..ctx
-        });
+        })?;
+
+        transitions_traversed += 1;
+        events_emitted += events.len();
 
         all_events.extend_from_slice(&events);
 
@@ -1617,10 +2523,13 @@ fn reconcile(
                 intermediate_repo.handle_cmd(MsgIssuedCount(0).into());
                 intermediate_repo.handle_cmd(MsgSuccessCount(0).into());
 
-                // Possible Txs skipped
-                if state.is_idle() {
-                    let phase_tx_count =
-                        phase.tx_count(ctx.config.validator_set_size(), ctx.config.max_msg_count());
+                // Possible Txs skipped, phases out of scope are not funded so are not counted
+                if state.is_idle() && scope.includes(phase) {
+                    let phase_tx_count = phase.tx_count(
+                        ctx.config.validator_set_size(),
+                        ctx.config.max_msg_count(),
+                        delegate_authz_msgs(ctx.config, &intermediate_repo, ctx.env),
+                    );
 
                     tx_skip_count += phase_tx_count;
                 }
@@ -1639,12 +2548,21 @@ fn reconcile(
                 cmds.push(Phase::StartReconcile.into());
                 cmds.push(State::Idle.into());
 
-                return Response {
+                all_events.push(Event::ReconcileCompleted {
+                    height: current_height,
+                });
+
+                events_emitted += 1;
+
+                return Ok(Response {
                     cmds,
                     events: all_events,
                     tx_msgs: None,
                     tx_skip_count,
-                };
+                    abort_reason: None,
+                    transitions_traversed,
+                    events_emitted,
+                });
             }
 
             TransitionKind::Tx(tx_msgs) => {
@@ -1655,34 +2573,44 @@ fn reconcile(
                 cmds.push(phase.into());
                 cmds.push(State::Pending.into());
 
-                return Response {
+                return Ok(Response {
                     cmds,
                     events: all_events,
                     tx_msgs: Some(tx_msgs),
                     tx_skip_count,
-                };
+                    abort_reason: None,
+                    transitions_traversed,
+                    events_emitted,
+                });
             }
 
-            TransitionKind::Abort => {
-                let tx_skip_count = phase.sequence_tx_count(
+            TransitionKind::Abort(reason) => {
+                let tx_skip_count = scope.sequence_tx_count(
+                    phase,
                     state,
                     ctx.config.validator_set_size(),
                     ctx.config.max_msg_count(),
+                    delegate_authz_msgs(ctx.config, &intermediate_repo, ctx.env),
                 );
 
-                return Response {
+                return Ok(Response {
                     cmds: vec![],
                     events: all_events,
                     tx_msgs: None,
                     tx_skip_count,
-                };
+                    abort_reason: Some(reason),
+                    transitions_traversed,
+                    events_emitted,
+                });
             }
         }
     }
 }
 
 impl<'a> Fsm for FsmImpl<'a> {
-    fn reconcile(&self) -> Response {
+    fn reconcile(&self) -> Result<Response, ReconcileError> {
+        validate_weights(self.ctx)?;
+
         let state = self.ctx.repo.state();
 
         let mut intermediate_repo = IntermediateRepo {
@@ -1699,13 +2627,51 @@ impl<'a> Fsm for FsmImpl<'a> {
                 .handle_cmd(MsgSuccessCount(msg_success_count + msg_issued_count).into());
         }
 
+        let phase = check_setup_accounts(self.ctx, self.ctx.repo.phase(), &mut intermediate_repo);
+
         reconcile(
             self.ctx,
-            self.ctx.repo.phase(),
+            phase,
             state,
             intermediate_repo,
             vec![],
+            ReconcileScope::Full,
+        )
+    }
+
+    fn reconcile_rewards_only(&self) -> Result<Option<Response>, ReconcileError> {
+        let phase = self.ctx.repo.phase();
+
+        if !phase.is_start_reconcile() || !self.ctx.repo.state().is_idle() {
+            return Ok(None);
+        }
+
+        let InflightUnbond(inflight_unbond) = self.ctx.repo.inflight_unbond();
+
+        if inflight_unbond > 0 {
+            return Ok(None);
+        }
+
+        validate_weights(self.ctx)?;
+
+        let mut intermediate_repo = IntermediateRepo {
+            repo: self.ctx.repo,
+            cache: Cache::default(),
+        };
+
+        if !check_setup_accounts(self.ctx, phase, &mut intermediate_repo).is_start_reconcile() {
+            return Ok(None);
+        }
+
+        reconcile(
+            self.ctx,
+            phase,
+            State::Idle,
+            intermediate_repo,
+            vec![],
+            ReconcileScope::RewardsOnly,
         )
+        .map(Some)
     }
 
     fn failed(&self) -> Response {
@@ -1720,20 +2686,27 @@ impl<'a> Fsm for FsmImpl<'a> {
             events: vec![],
             tx_msgs: None,
             tx_skip_count: 0,
+            abort_reason: None,
+            transitions_traversed: 0,
+            events_emitted: 0,
         }
     }
 
-    fn force_next(&self) -> Option<Response> {
+    fn force_next(&self) -> Result<Option<Response>, ReconcileError> {
         let phase = self.ctx.repo.phase();
 
         if !self.ctx.repo.state().is_failed() {
-            return None;
+            return Ok(None);
         }
 
-        let (events, mut cmds) = match phase {
-            Phase::Undelegate => undelegate_force_next(self.ctx),
-            Phase::Delegate => delegate_force_next(self.ctx),
-            _ => return None,
+        validate_weights(self.ctx)?;
+
+        let (mut events, mut cmds) = match phase {
+            Phase::Undelegate => undelegate_force_next(self.ctx)?,
+            Phase::TransferUndelegated => transfer_undelegated_force_next(self.ctx)?,
+            Phase::TransferPendingDeposits => transfer_pending_deposits_force_next(self.ctx)?,
+            Phase::Delegate => delegate_force_next(self.ctx)?,
+            _ => return Ok(None),
         };
 
         // ensure message counts are cleared
@@ -1747,12 +2720,21 @@ impl<'a> Fsm for FsmImpl<'a> {
             cmds.push(Phase::StartReconcile.into());
             cmds.push(State::Idle.into());
 
-            return Some(Response {
+            events.push(Event::ReconcileCompleted {
+                height: current_height,
+            });
+
+            let events_emitted = events.len();
+
+            return Ok(Some(Response {
                 cmds,
                 events,
                 tx_msgs: None,
                 tx_skip_count: 0,
-            });
+                abort_reason: None,
+                transitions_traversed: 0,
+                events_emitted,
+            }));
         };
 
         let mut intermediate_repo = IntermediateRepo {
@@ -1764,13 +2746,15 @@ impl<'a> Fsm for FsmImpl<'a> {
             intermediate_repo.handle_cmd(cmd);
         }
 
-        Some(reconcile(
+        reconcile(
             self.ctx,
             next_phase,
             State::Idle,
             intermediate_repo,
             events,
-        ))
+            ReconcileScope::Full,
+        )
+        .map(Some)
     }
 }
 
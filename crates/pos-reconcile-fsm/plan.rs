@@ -0,0 +1,477 @@
+//! Off-chain simulation of upcoming reconcile rounds.
+//!
+//! The state machine is applied repeatedly against an owned, in-memory repository seeded from a
+//! serializable [RepositorySnapshot], treating every issued tx as successful.
+//!
+//! ```
+//! use pos_reconcile_fsm::{
+//!     plan::{plan, EnvSnapshot, RepositorySnapshot},
+//!     types::{
+//!         DelegationDriftTolerance, DelegationOrder, FeeBpsBlockIncrement, FeePaymentCooldownBlocks,
+//!         FeePayoutsEnabled, MaxFeeBps, MaxMsgCount, MaxUnbondingEntries, MaxUndelegateRetries, Phase,
+//!         DurationSecs, UnbondingTimeSecs, ValidatorSetSize, Weight, Weights,
+//!     },
+//!     Config, TxMsg,
+//! };
+//!
+//! struct TwoValidators;
+//!
+//! impl Config for TwoValidators {
+//!     fn unbonding_time(&self) -> UnbondingTimeSecs {
+//!         UnbondingTimeSecs(DurationSecs(600))
+//!     }
+//!
+//!     fn max_msg_count(&self) -> MaxMsgCount {
+//!         MaxMsgCount(16)
+//!     }
+//!
+//!     fn fee_payout_cooldown(&self) -> FeePaymentCooldownBlocks {
+//!         FeePaymentCooldownBlocks(100)
+//!     }
+//!
+//!     fn fee_payouts_enabled(&self) -> FeePayoutsEnabled {
+//!         FeePayoutsEnabled(true)
+//!     }
+//!
+//!     fn fee_bps_block_increment(&self) -> FeeBpsBlockIncrement {
+//!         FeeBpsBlockIncrement(1)
+//!     }
+//!
+//!     fn max_fee_bps(&self) -> MaxFeeBps {
+//!         MaxFeeBps(200)
+//!     }
+//!
+//!     fn starting_weights(&self) -> Weights {
+//!         let half = Weight::checked_from_bps(5_000u32).unwrap();
+//!
+//!         Weights::new(&[half, half]).unwrap()
+//!     }
+//!
+//!     fn target_weights(&self) -> Option<Weights> {
+//!         None
+//!     }
+//!
+//!     fn validator_set_size(&self) -> ValidatorSetSize {
+//!         ValidatorSetSize(2)
+//!     }
+//!
+//!     fn max_undelegate_retries(&self) -> MaxUndelegateRetries {
+//!         MaxUndelegateRetries(3)
+//!     }
+//!
+//!     fn max_unbonding_entries(&self) -> MaxUnbondingEntries {
+//!         MaxUnbondingEntries(7)
+//!     }
+//!
+//!     fn delegation_drift_tolerance(&self) -> DelegationDriftTolerance {
+//!         DelegationDriftTolerance(3)
+//!     }
+//!
+//!     fn delegation_order(&self) -> DelegationOrder {
+//!         DelegationOrder::SlotIndex
+//!     }
+//! }
+//!
+//! // setup has already completed and 1,000 is waiting to be delegated
+//! let repo = RepositorySnapshot {
+//!     phase: Phase::TransferPendingDeposits,
+//!     pending_deposit: 1_000,
+//!     weights_bps: vec![5_000, 5_000],
+//!     ..Default::default()
+//! };
+//!
+//! let env = EnvSnapshot {
+//!     delegation_account_address: Some("delegation".to_owned()),
+//!     rewards_account_address: Some("rewards".to_owned()),
+//!     ..Default::default()
+//! };
+//!
+//! let responses = plan(&TwoValidators, repo, env, 2).unwrap();
+//!
+//! // first round transfers the pending deposit to the remote chain
+//! assert_eq!(
+//!     responses[0].tx_msgs.as_ref().unwrap().msgs,
+//!     vec![TxMsg::TransferOutPendingDeposit(1_000)]
+//! );
+//!
+//! // second round delegates it evenly across the validator set
+//! assert!(matches!(
+//!     responses[1].tx_msgs.as_ref().unwrap().msgs.as_slice(),
+//!     [TxMsg::Delegate(_, 500), TxMsg::Delegate(_, 500)]
+//! ));
+//! ```
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fsm,
+    types::{
+        Account, CurrentHeight, DelegateSlotOrder, DelegateStartSlot, Delegated, DelegationsReport,
+        FeeRecipient, InflightDelegation, InflightDeposit, InflightFeePayable,
+        InflightRewardsReceivable, InflightUnbond, LastReconcileHeight, MsgIssuedCount,
+        MsgSuccessCount, Now, PendingDeposit, PendingUnbond, Phase, RedelegationSlot,
+        RemoteBalance, RemoteBalanceReport, SetupAccounts, State, Timestamp,
+        UnbondingEntriesReport, UndelegateDeferredSlots, UndelegateRetryCount, UndelegateStartSlot,
+        UndelegatedBalanceReport, Validator, ValidatorSetSlot, Weight, Weights,
+    },
+    Cmd, Config, Env, Fsm as _, ReconcileError, Repository, Response,
+};
+
+/// A serializable snapshot of the mutable state read through [Repository]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RepositorySnapshot {
+    pub delegated: u128,
+    pub delegate_start_slot: usize,
+    /// The slots in the order the current delegate phase funds them, empty for slot index order
+    pub delegate_slot_order: Vec<usize>,
+    pub inflight_delegation: u128,
+    pub inflight_deposit: u128,
+    pub inflight_fee_payable: u128,
+    pub inflight_rewards_receivable: u128,
+    pub inflight_unbond: u128,
+    pub last_reconcile_height: Option<u64>,
+    pub msg_issued_count: usize,
+    pub msg_success_count: usize,
+    pub pending_deposit: u128,
+    pub pending_unbond: u128,
+    pub phase: Phase,
+    pub state: State,
+    pub redelegation_slot: Option<usize>,
+    pub redelegate_to_validator: Option<Validator>,
+    pub setup_accounts: Option<SetupAccounts>,
+    /// The slots left out of the current undelegation for having reached the max unbonding entries
+    pub undelegate_deferred_slots: Vec<usize>,
+    pub undelegate_retry_count: usize,
+    pub undelegate_start_slot: usize,
+    /// The current validator slot weights in basis points
+    pub weights_bps: Vec<u32>,
+}
+
+/// A serializable report of a remote balance at a given height
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BalanceSnapshot {
+    pub height: u64,
+    pub amount: u128,
+}
+
+/// A serializable report of the delegations made by the delegation account at a given height
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DelegationsSnapshot {
+    pub height: u64,
+    /// The amount delegated to each validator, in validator set order
+    pub delegated_amounts_per_slot: Vec<u128>,
+}
+
+/// A serializable snapshot of the environment read through [Env]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnvSnapshot {
+    pub current_height: u64,
+    pub now: Timestamp,
+    pub delegation_account_address: Option<Account>,
+    pub rewards_account_address: Option<Account>,
+    pub fee_recipient: Option<FeeRecipient>,
+    pub delegations: Option<DelegationsSnapshot>,
+    pub rewards_balance: Option<BalanceSnapshot>,
+    pub undelegated_balance: Option<BalanceSnapshot>,
+    /// The time the undelegated balance report was last updated
+    pub undelegated_balance_timestamp: Timestamp,
+    /// The number of unbonding entries in-flight for each validator, in validator set order
+    pub unbonding_entries_per_slot: Option<Vec<u64>>,
+}
+
+fn weights_from_bps(weights_bps: &[u32]) -> Result<Weights, ReconcileError> {
+    let weights = weights_bps
+        .iter()
+        .map(|bps| Weight::checked_from_bps(*bps).ok_or(ReconcileError::WeightsExceedOne))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Weights::new(&weights)?)
+}
+
+/// An owned, in-memory [Repository] that applies the [Cmd]s it is given
+#[derive(Debug, Clone)]
+pub struct MemoryRepository {
+    delegated: Delegated,
+    delegate_start_slot: DelegateStartSlot,
+    delegate_slot_order: DelegateSlotOrder,
+    inflight_delegation: InflightDelegation,
+    inflight_deposit: InflightDeposit,
+    inflight_fee_payable: InflightFeePayable,
+    inflight_rewards_receivable: InflightRewardsReceivable,
+    inflight_unbond: InflightUnbond,
+    last_reconcile_height: Option<LastReconcileHeight>,
+    msg_issued_count: MsgIssuedCount,
+    msg_success_count: MsgSuccessCount,
+    pending_deposit: PendingDeposit,
+    pending_unbond: PendingUnbond,
+    phase: Phase,
+    state: State,
+    redelegation_slot: Option<RedelegationSlot>,
+    redelegate_to_validator: Option<Validator>,
+    setup_accounts: Option<SetupAccounts>,
+    undelegate_deferred_slots: UndelegateDeferredSlots,
+    undelegate_retry_count: UndelegateRetryCount,
+    undelegate_start_slot: UndelegateStartSlot,
+    weights: Weights,
+}
+
+impl MemoryRepository {
+    pub fn from_snapshot(snapshot: RepositorySnapshot) -> Result<Self, ReconcileError> {
+        Ok(Self {
+            delegated: Delegated(snapshot.delegated),
+            delegate_start_slot: DelegateStartSlot(snapshot.delegate_start_slot),
+            delegate_slot_order: DelegateSlotOrder(
+                snapshot
+                    .delegate_slot_order
+                    .into_iter()
+                    .map(ValidatorSetSlot)
+                    .collect(),
+            ),
+            inflight_delegation: InflightDelegation(snapshot.inflight_delegation),
+            inflight_deposit: InflightDeposit(snapshot.inflight_deposit),
+            inflight_fee_payable: InflightFeePayable(snapshot.inflight_fee_payable),
+            inflight_rewards_receivable: InflightRewardsReceivable(
+                snapshot.inflight_rewards_receivable,
+            ),
+            inflight_unbond: InflightUnbond(snapshot.inflight_unbond),
+            last_reconcile_height: snapshot.last_reconcile_height.map(LastReconcileHeight),
+            msg_issued_count: MsgIssuedCount(snapshot.msg_issued_count),
+            msg_success_count: MsgSuccessCount(snapshot.msg_success_count),
+            pending_deposit: PendingDeposit(snapshot.pending_deposit),
+            pending_unbond: PendingUnbond(snapshot.pending_unbond),
+            phase: snapshot.phase,
+            state: snapshot.state,
+            redelegation_slot: snapshot
+                .redelegation_slot
+                .map(|slot| RedelegationSlot(ValidatorSetSlot(slot))),
+            redelegate_to_validator: snapshot.redelegate_to_validator,
+            setup_accounts: snapshot.setup_accounts,
+            undelegate_deferred_slots: UndelegateDeferredSlots(
+                snapshot
+                    .undelegate_deferred_slots
+                    .into_iter()
+                    .map(ValidatorSetSlot)
+                    .collect(),
+            ),
+            undelegate_retry_count: UndelegateRetryCount(snapshot.undelegate_retry_count),
+            undelegate_start_slot: UndelegateStartSlot(snapshot.undelegate_start_slot),
+            weights: weights_from_bps(&snapshot.weights_bps)?,
+        })
+    }
+
+    pub fn handle_cmd(&mut self, cmd: Cmd) {
+        match cmd {
+            Cmd::ClearRedelegationRequest => {
+                self.redelegation_slot = None;
+                self.redelegate_to_validator = None;
+            }
+            Cmd::Delegated(v) => self.delegated = v,
+            Cmd::DelegateStartSlot(v) => self.delegate_start_slot = v,
+            Cmd::DelegateSlotOrder(v) => self.delegate_slot_order = v,
+            Cmd::InflightDelegation(v) => self.inflight_delegation = v,
+            Cmd::InflightDeposit(v) => self.inflight_deposit = v,
+            Cmd::InflightFeePayable(v) => self.inflight_fee_payable = v,
+            Cmd::InflightRewardsReceivable(v) => self.inflight_rewards_receivable = v,
+            Cmd::InflightUnbond(v) => self.inflight_unbond = v,
+            Cmd::LastReconcileHeight(v) => self.last_reconcile_height = Some(v),
+            Cmd::MsgIssuedCount(v) => self.msg_issued_count = v,
+            Cmd::MsgSuccessCount(v) => self.msg_success_count = v,
+            Cmd::PendingDeposit(v) => self.pending_deposit = v,
+            Cmd::PendingUnbond(v) => self.pending_unbond = v,
+            Cmd::Phase(v) => self.phase = v,
+            Cmd::SetupAccounts(v) => self.setup_accounts = Some(v),
+            Cmd::State(v) => self.state = v,
+            Cmd::UndelegateDeferredSlots(v) => self.undelegate_deferred_slots = v,
+            Cmd::UndelegateRetryCount(v) => self.undelegate_retry_count = v,
+            Cmd::UndelegateStartSlot(v) => self.undelegate_start_slot = v,
+            Cmd::Weights(v) => self.weights = v,
+        }
+    }
+}
+
+impl Repository for MemoryRepository {
+    fn delegated(&self) -> Delegated {
+        self.delegated
+    }
+
+    fn delegate_start_slot(&self) -> DelegateStartSlot {
+        self.delegate_start_slot
+    }
+
+    fn delegate_slot_order(&self) -> DelegateSlotOrder {
+        self.delegate_slot_order.clone()
+    }
+
+    fn inflight_delegation(&self) -> InflightDelegation {
+        self.inflight_delegation
+    }
+
+    fn inflight_deposit(&self) -> InflightDeposit {
+        self.inflight_deposit
+    }
+
+    fn inflight_fee_payable(&self) -> InflightFeePayable {
+        self.inflight_fee_payable
+    }
+
+    fn inflight_rewards_receivable(&self) -> InflightRewardsReceivable {
+        self.inflight_rewards_receivable
+    }
+
+    fn inflight_unbond(&self) -> InflightUnbond {
+        self.inflight_unbond
+    }
+
+    fn last_reconcile_height(&self) -> Option<LastReconcileHeight> {
+        self.last_reconcile_height
+    }
+
+    fn msg_issued_count(&self) -> MsgIssuedCount {
+        self.msg_issued_count
+    }
+
+    fn msg_success_count(&self) -> MsgSuccessCount {
+        self.msg_success_count
+    }
+
+    fn pending_deposit(&self) -> PendingDeposit {
+        self.pending_deposit
+    }
+
+    fn pending_unbond(&self) -> PendingUnbond {
+        self.pending_unbond
+    }
+
+    fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    fn state(&self) -> State {
+        self.state
+    }
+
+    fn redelegation_slot(&self) -> Option<RedelegationSlot> {
+        self.redelegation_slot.clone()
+    }
+
+    fn redelegate_to_validator(&self) -> Option<Validator> {
+        self.redelegate_to_validator.clone()
+    }
+
+    fn setup_accounts(&self) -> Option<SetupAccounts> {
+        self.setup_accounts.clone()
+    }
+
+    fn undelegate_deferred_slots(&self) -> UndelegateDeferredSlots {
+        self.undelegate_deferred_slots.clone()
+    }
+
+    fn undelegate_retry_count(&self) -> UndelegateRetryCount {
+        self.undelegate_retry_count
+    }
+
+    fn undelegate_start_slot(&self) -> UndelegateStartSlot {
+        self.undelegate_start_slot
+    }
+
+    fn weights(&self) -> Cow<'_, Weights> {
+        Cow::Borrowed(&self.weights)
+    }
+}
+
+/// An owned, in-memory [Env] that reports the same environment on every round
+#[derive(Debug, Clone)]
+pub struct MemoryEnv(EnvSnapshot);
+
+impl MemoryEnv {
+    pub fn from_snapshot(snapshot: EnvSnapshot) -> Self {
+        Self(snapshot)
+    }
+}
+
+impl Env for MemoryEnv {
+    fn current_height(&self) -> CurrentHeight {
+        CurrentHeight(self.0.current_height)
+    }
+
+    fn now(&self) -> Now {
+        Now(self.0.now)
+    }
+
+    fn delegation_account_address(&self) -> Option<Account> {
+        self.0.delegation_account_address.clone()
+    }
+
+    fn rewards_account_address(&self) -> Option<Account> {
+        self.0.rewards_account_address.clone()
+    }
+
+    fn fee_recipient(&self) -> Option<FeeRecipient> {
+        self.0.fee_recipient.clone()
+    }
+
+    fn delegations_report(&self) -> Option<DelegationsReport> {
+        let delegations = self.0.delegations.as_ref()?;
+
+        Some(DelegationsReport {
+            height: delegations.height,
+            total_delegated: delegations.delegated_amounts_per_slot.iter().sum(),
+            delegated_amounts_per_slot: delegations.delegated_amounts_per_slot.clone(),
+        })
+    }
+
+    fn rewards_balance_report(&self) -> Option<RemoteBalanceReport> {
+        self.0.rewards_balance.map(|balance| RemoteBalanceReport {
+            height: balance.height,
+            amount: RemoteBalance(balance.amount),
+        })
+    }
+
+    fn undelegated_balance_report(&self) -> Option<UndelegatedBalanceReport> {
+        self.0
+            .undelegated_balance
+            .map(|balance| UndelegatedBalanceReport {
+                last_updated_timestamp: self.0.undelegated_balance_timestamp,
+                remote_balance: RemoteBalanceReport {
+                    height: balance.height,
+                    amount: RemoteBalance(balance.amount),
+                },
+            })
+    }
+
+    fn unbonding_entries_report(&self) -> Option<UnbondingEntriesReport> {
+        self.0
+            .unbonding_entries_per_slot
+            .clone()
+            .map(|entries_per_slot| UnbondingEntriesReport { entries_per_slot })
+    }
+}
+
+/// Predict the responses of the next `rounds` reconciliations starting from the given snapshots.
+/// Every tx issued in a round is assumed to succeed, with the next round acting as its continuation.
+pub fn plan(
+    config: &dyn Config,
+    repo_snapshot: RepositorySnapshot,
+    env_snapshot: EnvSnapshot,
+    rounds: usize,
+) -> Result<Vec<Response>, ReconcileError> {
+    let mut repo = MemoryRepository::from_snapshot(repo_snapshot)?;
+
+    let env = MemoryEnv::from_snapshot(env_snapshot);
+
+    let mut responses = Vec::with_capacity(rounds);
+
+    for _ in 0..rounds {
+        let response = fsm(config, &repo, &env).reconcile()?;
+
+        for cmd in response.cmds.iter().cloned() {
+            repo.handle_cmd(cmd);
+        }
+
+        responses.push(response);
+    }
+
+    Ok(responses)
+}
@@ -1,8 +1,11 @@
-use std::collections::BTreeMap;
+use std::{borrow::Cow, collections::BTreeMap};
 
 use test_utils::prelude::*;
 
-use super::*;
+use super::{
+    types::{DurationSecs, Timestamp},
+    *,
+};
 
 fn weights(n_slots: u32) -> Weights {
     assert!(n_slots > 0);
@@ -27,9 +30,12 @@ struct Context {
     starting_weights: Option<Weights>,
     current_height: u64,
     delegate_start_slot: Option<DelegateStartSlot>,
+    delegate_slot_order: Option<DelegateSlotOrder>,
     delegated: Option<Delegated>,
     delegations: BTreeMap<usize, u128>,
+    delegation_order: DelegationOrder,
     fee_recipient: Option<FeeRecipient>,
+    fee_payouts_disabled: bool,
     inflight_delegation: Option<InflightDelegation>,
     inflight_deposit: Option<InflightDeposit>,
     inflight_fee_payable: Option<InflightFeePayable>,
@@ -43,16 +49,25 @@ struct Context {
     phase: Option<Phase>,
     redelegation_slot: Option<RedelegationSlot>,
     redelegate_to_validator: Option<Validator>,
+    rewards_account: Option<Account>,
+    icas_unregistered: bool,
+    rewards_account_unregistered: bool,
+    setup_accounts: Option<SetupAccounts>,
     delegations_report: Option<DelegationsReport>,
     rewards_balance_report: Option<RemoteBalanceReport>,
+    undelegated_balance_report: Option<RemoteBalanceReport>,
     state: Option<State>,
+    target_weights: Option<Weights>,
+    unbonding_entries_per_slot: Option<Vec<u64>>,
+    undelegate_deferred_slots: Option<UndelegateDeferredSlots>,
+    undelegate_retry_count: Option<UndelegateRetryCount>,
     undelegate_start_slot: Option<UndelegateStartSlot>,
     weights: Option<Weights>,
 }
 
 macro_rules! progress_fsm {
     ($ctx:ident) => {{
-        let response = fsm(&$ctx, &$ctx, &$ctx).reconcile();
+        let response = fsm(&$ctx, &$ctx, &$ctx).reconcile().unwrap();
 
         for cmd in response.cmds.clone() {
             $ctx.handle_cmd(cmd);
@@ -72,7 +87,8 @@ macro_rules! force_next {
     ($ctx:ident) => {{
         let response = fsm(&$ctx, &$ctx, &$ctx)
             .force_next()
-            .expect("failed undelegate or delegate phase");
+            .unwrap()
+            .expect("failed phase with force next handling");
 
         for cmd in response.cmds.clone() {
             $ctx.handle_cmd(cmd);
@@ -100,11 +116,25 @@ macro_rules! failure {
     }};
 }
 
+// Reconcile without applying the issued tx msgs, for batches that go on to fail
+macro_rules! progress_fsm_no_tx {
+    ($ctx:ident) => {{
+        let response = fsm(&$ctx, &$ctx, &$ctx).reconcile().unwrap();
+
+        for cmd in response.cmds.clone() {
+            $ctx.handle_cmd(cmd);
+        }
+
+        response
+    }};
+}
+
 impl Context {
     fn handle_cmd(&mut self, cmd: Cmd) {
         match cmd {
             Cmd::ClearRedelegationRequest => self.redelegation_slot = None,
             Cmd::DelegateStartSlot(v) => self.delegate_start_slot = Some(v),
+            Cmd::DelegateSlotOrder(v) => self.delegate_slot_order = Some(v),
             Cmd::Delegated(v) => self.delegated = Some(v),
             Cmd::InflightDelegation(v) => self.inflight_delegation = Some(v),
             Cmd::InflightDeposit(v) => self.inflight_deposit = Some(v),
@@ -117,7 +147,10 @@ impl Context {
             Cmd::PendingDeposit(v) => self.pending_deposit = Some(v),
             Cmd::PendingUnbond(v) => self.pending_unbond = Some(v),
             Cmd::Phase(v) => self.phase = Some(v),
+            Cmd::SetupAccounts(v) => self.setup_accounts = Some(v),
             Cmd::State(v) => self.state = Some(v),
+            Cmd::UndelegateDeferredSlots(v) => self.undelegate_deferred_slots = Some(v),
+            Cmd::UndelegateRetryCount(v) => self.undelegate_retry_count = Some(v),
             Cmd::UndelegateStartSlot(v) => self.undelegate_start_slot = Some(v),
             Cmd::Weights(v) => self.weights = Some(v),
         }
@@ -158,11 +191,46 @@ impl Context {
         self
     }
 
+    fn with_undelegated_balance_report(mut self, height: u64, amount: u128) -> Self {
+        self.undelegated_balance_report = Some(RemoteBalanceReport {
+            height,
+            amount: RemoteBalance(amount),
+        });
+        self
+    }
+
+    fn with_rewards_account(mut self, account: &str) -> Self {
+        self.rewards_account = Some(account.to_owned());
+        self
+    }
+
+    fn without_icas(mut self) -> Self {
+        self.icas_unregistered = true;
+        self
+    }
+
+    fn without_rewards_account(mut self) -> Self {
+        self.rewards_account_unregistered = true;
+        self
+    }
+
     fn with_fee_recipient(mut self, recipient: &str) -> Self {
         self.fee_recipient = Some(recipient.to_owned());
         self
     }
 
+    fn with_fee_payouts_enabled(mut self, enabled: bool) -> Self {
+        self.fee_payouts_disabled = !enabled;
+        self
+    }
+
+    fn with_unbonding_entries(mut self, entries_per_slot: Vec<u64>) -> Self {
+        assert_eq!(entries_per_slot.len(), self.validator_set_size().0);
+
+        self.unbonding_entries_per_slot = Some(entries_per_slot);
+        self
+    }
+
     fn with_delegations_report(
         mut self,
         height: u64,
@@ -185,7 +253,7 @@ impl Context {
 
 impl Config for Context {
     fn unbonding_time(&self) -> UnbondingTimeSecs {
-        UnbondingTimeSecs(600)
+        UnbondingTimeSecs(DurationSecs(600))
     }
 
     fn max_msg_count(&self) -> MaxMsgCount {
@@ -196,6 +264,10 @@ impl Config for Context {
         FeePaymentCooldownBlocks(100)
     }
 
+    fn fee_payouts_enabled(&self) -> FeePayoutsEnabled {
+        FeePayoutsEnabled(!self.fee_payouts_disabled)
+    }
+
     fn fee_bps_block_increment(&self) -> FeeBpsBlockIncrement {
         FeeBpsBlockIncrement(1)
     }
@@ -214,6 +286,10 @@ impl Config for Context {
         weights(n as u32)
     }
 
+    fn target_weights(&self) -> Option<Weights> {
+        self.target_weights.clone()
+    }
+
     fn validator_set_size(&self) -> ValidatorSetSize {
         let size = self
             .starting_weights
@@ -222,6 +298,22 @@ impl Config for Context {
 
         ValidatorSetSize(size)
     }
+
+    fn max_undelegate_retries(&self) -> MaxUndelegateRetries {
+        MaxUndelegateRetries(3)
+    }
+
+    fn max_unbonding_entries(&self) -> MaxUnbondingEntries {
+        MaxUnbondingEntries(7)
+    }
+
+    fn delegation_order(&self) -> DelegationOrder {
+        self.delegation_order
+    }
+
+    fn delegation_drift_tolerance(&self) -> DelegationDriftTolerance {
+        DelegationDriftTolerance(self.validator_set_size().0 as u128)
+    }
 }
 
 impl Repository for Context {
@@ -229,6 +321,10 @@ impl Repository for Context {
         self.delegate_start_slot.unwrap_or_default()
     }
 
+    fn delegate_slot_order(&self) -> DelegateSlotOrder {
+        self.delegate_slot_order.clone().unwrap_or_default()
+    }
+
     fn delegated(&self) -> Delegated {
         self.delegated.unwrap_or_default()
     }
@@ -289,14 +385,83 @@ impl Repository for Context {
         self.redelegate_to_validator.clone()
     }
 
+    fn setup_accounts(&self) -> Option<SetupAccounts> {
+        self.setup_accounts.clone()
+    }
+
+    fn undelegate_deferred_slots(&self) -> UndelegateDeferredSlots {
+        self.undelegate_deferred_slots.clone().unwrap_or_default()
+    }
+
+    fn undelegate_retry_count(&self) -> UndelegateRetryCount {
+        self.undelegate_retry_count.unwrap_or_default()
+    }
+
     fn undelegate_start_slot(&self) -> UndelegateStartSlot {
         self.undelegate_start_slot.unwrap_or_default()
     }
 
-    fn weights(&self) -> Weights {
+    fn weights(&self) -> Cow<'_, Weights> {
         self.weights
-            .clone()
-            .unwrap_or_else(|| self.starting_weights())
+            .as_ref()
+            .map_or_else(|| Cow::Owned(self.starting_weights()), Cow::Borrowed)
+    }
+}
+
+/// Counts the weights cloned out of the wrapped repository, as a storage-backed repository would have to
+struct CloneCountingRepo<'a> {
+    inner: &'a Context,
+    weights_cloned: std::cell::Cell<usize>,
+}
+
+impl<'a> CloneCountingRepo<'a> {
+    fn new(inner: &'a Context) -> Self {
+        Self {
+            inner,
+            weights_cloned: std::cell::Cell::default(),
+        }
+    }
+}
+
+macro_rules! delegate_repo {
+    ($($method:ident -> $ret:ty),+) => {
+        $(
+            fn $method(&self) -> $ret {
+                self.inner.$method()
+            }
+        )+
+    };
+}
+
+impl<'a> Repository for CloneCountingRepo<'a> {
+    delegate_repo![
+        delegated -> Delegated,
+        delegate_start_slot -> DelegateStartSlot,
+        delegate_slot_order -> DelegateSlotOrder,
+        inflight_delegation -> InflightDelegation,
+        inflight_deposit -> InflightDeposit,
+        inflight_fee_payable -> InflightFeePayable,
+        inflight_rewards_receivable -> InflightRewardsReceivable,
+        inflight_unbond -> InflightUnbond,
+        last_reconcile_height -> Option<LastReconcileHeight>,
+        msg_issued_count -> MsgIssuedCount,
+        msg_success_count -> MsgSuccessCount,
+        pending_deposit -> PendingDeposit,
+        pending_unbond -> PendingUnbond,
+        phase -> Phase,
+        state -> State,
+        redelegation_slot -> Option<RedelegationSlot>,
+        redelegate_to_validator -> Option<Validator>,
+        setup_accounts -> Option<SetupAccounts>,
+        undelegate_deferred_slots -> UndelegateDeferredSlots,
+        undelegate_retry_count -> UndelegateRetryCount,
+        undelegate_start_slot -> UndelegateStartSlot
+    ];
+
+    fn weights(&self) -> Cow<'_, Weights> {
+        self.weights_cloned.set(self.weights_cloned.get() + 1);
+
+        Cow::Owned(self.inner.weights().into_owned())
     }
 }
 
@@ -306,15 +471,26 @@ impl Env for Context {
     }
 
     fn now(&self) -> Now {
-        Now(self.current_height)
+        // the mock chain produces a block every second from genesis
+        Now(Timestamp(self.current_height))
     }
 
     fn delegation_account_address(&self) -> Option<Account> {
+        if self.icas_unregistered {
+            return None;
+        }
+
         Some("delegation_account".to_owned())
     }
 
     fn rewards_account_address(&self) -> Option<Account> {
-        Some("rewards_account".to_owned())
+        if self.icas_unregistered || self.rewards_account_unregistered {
+            return None;
+        }
+
+        self.rewards_account
+            .clone()
+            .or_else(|| Some("rewards_account".to_owned()))
     }
 
     fn fee_recipient(&self) -> Option<FeeRecipient> {
@@ -330,7 +506,17 @@ impl Env for Context {
     }
 
     fn undelegated_balance_report(&self) -> Option<UndelegatedBalanceReport> {
-        None
+        self.undelegated_balance_report
+            .map(|remote_balance| UndelegatedBalanceReport {
+                last_updated_timestamp: Timestamp(0),
+                remote_balance,
+            })
+    }
+
+    fn unbonding_entries_report(&self) -> Option<UnbondingEntriesReport> {
+        self.unbonding_entries_per_slot
+            .clone()
+            .map(|entries_per_slot| UnbondingEntriesReport { entries_per_slot })
     }
 }
 
@@ -343,20 +529,23 @@ fn initial_deposit() {
     check(
         response,
         expect![[r#"
-                (
-                  cmds: [
-                    MsgIssuedCount((1)),
-                    Phase(SetupRewardsAddress),
-                    State(Pending),
-                  ],
-                  events: [],
-                  tx_msgs: Some((
-                    msgs: [
-                      SetRewardsWithdrawalAddress("delegation_account", "rewards_account"),
-                    ],
-                  )),
-                  tx_skip_count: 0,
-                )"#]],
+            (
+              cmds: [
+                MsgIssuedCount((1)),
+                Phase(SetupRewardsAddress),
+                State(Pending),
+              ],
+              events: [],
+              tx_msgs: Some((
+                msgs: [
+                  SetRewardsWithdrawalAddress("delegation_account", "rewards_account"),
+                ],
+              )),
+              tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 0,
+            )"#]],
     );
 
     let response = progress_fsm!(ctx);
@@ -374,10 +563,13 @@ fn initial_deposit() {
               events: [],
               tx_msgs: Some((
                 msgs: [
-                  GrantAuthzSend("rewards_account", "delegation_account"),
+                  GrantAuthz("rewards_account", "delegation_account", "/cosmos.bank.v1beta1.MsgSend"),
                 ],
               )),
               tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 2,
+              events_emitted: 0,
             )"#]],
     );
 
@@ -391,6 +583,10 @@ fn initial_deposit() {
                 InflightDeposit((200)),
                 MsgIssuedCount((1)),
                 MsgSuccessCount((0)),
+                SetupAccounts((
+                  delegation: "delegation_account",
+                  rewards: "rewards_account",
+                )),
                 Phase(TransferPendingDeposits),
                 State(Pending),
               ],
@@ -401,6 +597,9 @@ fn initial_deposit() {
                 ],
               )),
               tx_skip_count: 3,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 0,
             )"#]],
     );
 
@@ -423,14 +622,17 @@ fn initial_deposit() {
               ],
               tx_msgs: Some((
                 msgs: [
-                  Delegate((0), 44),
-                  Delegate((1), 39),
-                  Delegate((2), 39),
-                  Delegate((3), 39),
-                  Delegate((4), 39),
+                  Delegate((0), 40),
+                  Delegate((1), 40),
+                  Delegate((2), 40),
+                  Delegate((3), 40),
+                  Delegate((4), 40),
                 ],
               )),
               tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 2,
+              events_emitted: 1,
             )"#]],
     );
 
@@ -449,24 +651,208 @@ fn initial_deposit() {
                 InflightRewardsReceivable((0)),
                 MsgIssuedCount((0)),
                 MsgSuccessCount((0)),
-                Weights(([
-                  (("0.21999999999999999999999999999999")),
-                  (("0.19499999999999999999999999999999")),
-                  (("0.19499999999999999999999999999999")),
-                  (("0.19499999999999999999999999999999")),
-                  (("0.19499999999999999999999999999999")),
-                ])),
                 LastReconcileHeight((0)),
                 Phase(StartReconcile),
                 State(Idle),
               ],
               events: [
                 DelegationsIncreased(200),
+                ReconcileCompleted(
+                  height: 0,
+                ),
               ],
               tx_msgs: None,
               tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 2,
+            )"#]],
+    );
+}
+
+#[test]
+fn abort_until_icas_registered() {
+    let mut ctx = Context::default().with_pending_deposit(200).without_icas();
+
+    check(
+        progress_fsm!(ctx),
+        expect![[r#"
+            (
+              cmds: [],
+              events: [],
+              tx_msgs: None,
+              tx_skip_count: 7,
+              abort_reason: Some(IcaNotRegistered),
+              transitions_traversed: 1,
+              events_emitted: 0,
+            )"#]],
+    );
+
+    let mut ctx = Context::default()
+        .with_pending_deposit(200)
+        .without_rewards_account();
+
+    check(
+        progress_fsm!(ctx),
+        expect![[r#"
+            (
+              cmds: [],
+              events: [],
+              tx_msgs: None,
+              tx_skip_count: 7,
+              abort_reason: Some(MissingRewardsAccount),
+              transitions_traversed: 1,
+              events_emitted: 0,
+            )"#]],
+    );
+}
+
+#[test]
+fn plan_matches_stepwise_reconcile() {
+    let mut ctx = Context::default().with_pending_deposit(200);
+
+    let expected: Vec<_> = (0..5).map(|_| progress_fsm!(ctx).tx_msgs).collect();
+
+    let ctx = Context::default();
+
+    let repo = plan::RepositorySnapshot {
+        pending_deposit: 200,
+        weights_bps: vec![2_000; 5],
+        ..Default::default()
+    };
+
+    let env = plan::EnvSnapshot {
+        delegation_account_address: ctx.delegation_account_address(),
+        rewards_account_address: ctx.rewards_account_address(),
+        ..Default::default()
+    };
+
+    let planned: Vec<_> = plan::plan(&ctx, repo, env, 5)
+        .unwrap()
+        .into_iter()
+        .map(|response| response.tx_msgs)
+        .collect();
+
+    assert_eq!(planned, expected);
+}
+
+#[test]
+fn rewards_account_changed_reruns_setup() {
+    let mut ctx = Context::default().with_pending_deposit(200);
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    let mut ctx = ctx
+        .with_rewards_account("new_rewards_account")
+        .with_current_height(1);
+
+    let response = progress_fsm!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                MsgIssuedCount((1)),
+                Phase(SetupRewardsAddress),
+                State(Pending),
+              ],
+              events: [],
+              tx_msgs: Some((
+                msgs: [
+                  SetRewardsWithdrawalAddress("delegation_account", "new_rewards_account"),
+                ],
+              )),
+              tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 0,
+            )"#]],
+    );
+
+    let response = progress_fsm!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                MsgIssuedCount((1)),
+                MsgSuccessCount((0)),
+                Phase(SetupAuthz),
+                State(Pending),
+              ],
+              events: [],
+              tx_msgs: Some((
+                msgs: [
+                  GrantAuthz("new_rewards_account", "delegation_account", "/cosmos.bank.v1beta1.MsgSend"),
+                ],
+              )),
+              tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 2,
+              events_emitted: 0,
+            )"#]],
+    );
+
+    let response = progress_fsm!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                MsgIssuedCount((5)),
+                MsgSuccessCount((0)),
+                SetupAccounts((
+                  delegation: "delegation_account",
+                  rewards: "new_rewards_account",
+                )),
+                Phase(Delegate),
+                State(Pending),
+              ],
+              events: [],
+              tx_msgs: Some((
+                msgs: [
+                  WithdrawRewards((0)),
+                  WithdrawRewards((1)),
+                  WithdrawRewards((2)),
+                  WithdrawRewards((3)),
+                  WithdrawRewards((4)),
+                ],
+              )),
+              tx_skip_count: 4,
+              abort_reason: None,
+              transitions_traversed: 7,
+              events_emitted: 0,
             )"#]],
     );
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    check(
+        &ctx.setup_accounts,
+        expect![[r#"
+        Some((
+          delegation: "delegation_account",
+          rewards: "new_rewards_account",
+        ))"#]],
+    );
+
+    // subsequent rounds continue the normal sequence
+    let mut ctx = ctx.with_pending_deposit(100).with_current_height(2);
+
+    let response = progress_fsm!(ctx);
+
+    check(
+        response.tx_msgs,
+        expect![[r#"
+        Some((
+          msgs: [
+            TransferOutPendingDeposit(100),
+          ],
+        ))"#]],
+    );
 }
 
 #[test]
@@ -497,14 +883,17 @@ fn collect_rewards() {
                   Authz([
                     SendRewardsReceivable((100)),
                   ]),
-                  Delegate((0), 18),
-                  Delegate((1), 22),
+                  Delegate((0), 20),
+                  Delegate((1), 20),
                   Delegate((2), 20),
                   Delegate((3), 20),
                   Delegate((4), 20),
                 ],
               )),
               tx_skip_count: 4,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 0,
             )"#]],
     );
 
@@ -524,11 +913,11 @@ fn collect_rewards() {
                 MsgIssuedCount((0)),
                 MsgSuccessCount((0)),
                 Weights(([
-                  (("0.20333333333333333333333333333333")),
-                  (("0.19999999999999999999999999999999")),
-                  (("0.19333333333333333333333333333333")),
-                  (("0.19333333333333333333333333333333")),
-                  (("0.19333333333333333333333333333333")),
+                  (("0.19666666666666666666666666666666")),
+                  (("0.19666666666666666666666666666666")),
+                  (("0.19666666666666666666666666666666")),
+                  (("0.19666666666666666666666666666666")),
+                  (("0.19666666666666666666666666666666")),
                 ])),
                 LastReconcileHeight((0)),
                 Phase(StartReconcile),
@@ -536,9 +925,15 @@ fn collect_rewards() {
               ],
               events: [
                 DelegationsIncreased(100),
+                ReconcileCompleted(
+                  height: 0,
+                ),
               ],
               tx_msgs: None,
               tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 2,
             )"#]],
     );
 }
@@ -575,8 +970,8 @@ fn collect_rewards_with_reconcile_fee() {
                   Authz([
                     SendRewardsReceivable((99)),
                   ]),
-                  Delegate((0), 17),
-                  Delegate((1), 22),
+                  Delegate((0), 19),
+                  Delegate((1), 20),
                   Delegate((2), 20),
                   Delegate((3), 20),
                   Delegate((4), 20),
@@ -586,6 +981,9 @@ fn collect_rewards_with_reconcile_fee() {
                 ],
               )),
               tx_skip_count: 4,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 0,
             )"#]],
     );
 
@@ -605,11 +1003,11 @@ fn collect_rewards_with_reconcile_fee() {
                 MsgIssuedCount((0)),
                 MsgSuccessCount((0)),
                 Weights(([
-                  (("0.20066889632107023411371237458193")),
-                  (("0.20066889632107023411371237458193")),
-                  (("0.19397993311036789297658862876254")),
-                  (("0.19397993311036789297658862876254")),
                   (("0.19397993311036789297658862876254")),
+                  (("0.19732441471571906354515050167224")),
+                  (("0.19732441471571906354515050167224")),
+                  (("0.19732441471571906354515050167224")),
+                  (("0.19732441471571906354515050167224")),
                 ])),
                 LastReconcileHeight((1000)),
                 Phase(StartReconcile),
@@ -617,13 +1015,54 @@ fn collect_rewards_with_reconcile_fee() {
               ],
               events: [
                 DelegationsIncreased(99),
+                ReconcileCompleted(
+                  height: 1000,
+                ),
               ],
               tx_msgs: None,
               tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 2,
             )"#]],
     );
 }
 
+fn rewards_ctx_with_fee_payouts(enabled: bool) -> Context {
+    let mut ctx = Context::default().with_pending_deposit(1_000);
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    ctx.with_rewards_balance_report(1, 10_000)
+        .with_fee_recipient("reconciler")
+        .with_fee_payouts_enabled(enabled)
+        .with_current_height(1000)
+}
+
+#[test]
+fn disabled_fee_payouts_delegate_all_rewards() {
+    let mut ctx = rewards_ctx_with_fee_payouts(false);
+
+    progress_fsm!(ctx);
+
+    assert_eq!(ctx.inflight_delegation, Some(InflightDelegation(10_000)));
+    assert_eq!(ctx.inflight_fee_payable, Some(InflightFeePayable(0)));
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    assert_eq!(ctx.delegated, Some(Delegated(11_000)));
+}
+
+#[test]
+fn re_enabled_fee_payouts_restore_fee_split() {
+    let mut ctx = rewards_ctx_with_fee_payouts(false).with_fee_payouts_enabled(true);
+
+    progress_fsm!(ctx);
+
+    assert_eq!(ctx.inflight_delegation, Some(InflightDelegation(9_801)));
+    assert_eq!(ctx.inflight_fee_payable, Some(InflightFeePayable(199)));
+}
+
 #[test]
 fn collect_rewards_with_reconcile_fee_multi_stage_delegations() {
     let mut ctx = Context {
@@ -660,8 +1099,8 @@ fn collect_rewards_with_reconcile_fee_multi_stage_delegations() {
                   Authz([
                     SendRewardsReceivable((99)),
                   ]),
-                  Delegate((0), 1),
-                  Delegate((1), 8),
+                  Delegate((0), 4),
+                  Delegate((1), 5),
                   Delegate((2), 5),
                   Delegate((3), 5),
                   Delegate((4), 5),
@@ -678,6 +1117,9 @@ fn collect_rewards_with_reconcile_fee_multi_stage_delegations() {
                 ],
               )),
               tx_skip_count: 6,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 0,
             )"#]],
     );
 
@@ -707,6 +1149,9 @@ fn collect_rewards_with_reconcile_fee_multi_stage_delegations() {
                 ],
               )),
               tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 0,
             )"#]],
     );
 
@@ -726,26 +1171,26 @@ fn collect_rewards_with_reconcile_fee_multi_stage_delegations() {
                 MsgIssuedCount((0)),
                 MsgSuccessCount((0)),
                 Weights(([
-                  (("0.09698996655518394648829431438127")),
-                  (("0.05351170568561872909698996655518")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
-                  (("0.04347826086956521739130434782608")),
                   (("0.04347826086956521739130434782608")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
+                  (("0.04682274247491638795986622073578")),
                 ])),
                 LastReconcileHeight((1000)),
                 Phase(StartReconcile),
@@ -753,9 +1198,15 @@ fn collect_rewards_with_reconcile_fee_multi_stage_delegations() {
               ],
               events: [
                 DelegationsIncreased(99),
+                ReconcileCompleted(
+                  height: 1000,
+                ),
               ],
               tx_msgs: None,
               tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 2,
             )"#]],
     );
 }
@@ -777,17 +1228,85 @@ fn nothing_to_do() {
               cmds: [
                 MsgIssuedCount((0)),
                 MsgSuccessCount((0)),
+                SetupAccounts((
+                  delegation: "delegation_account",
+                  rewards: "rewards_account",
+                )),
                 LastReconcileHeight((0)),
                 Phase(StartReconcile),
                 State(Idle),
               ],
-              events: [],
-              tx_msgs: None,
+              events: [
+                ReconcileCompleted(
+                  height: 0,
+                ),
+              ],
+              tx_msgs: None,
               tx_skip_count: 5,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 1,
             )"#]],
     );
 }
 
+#[test]
+fn idle_sweep_reports_transitions_traversed() {
+    let mut ctx = Context {
+        phase: Some(Phase::StartReconcile),
+        state: Some(State::Idle),
+        ..Default::default()
+    };
+
+    let response = progress_fsm!(ctx);
+
+    // start reconcile -> redelegate -> undelegate -> transfer undelegated -> transfer pending deposits -> delegate
+    assert_eq!(response.transitions_traversed, 6);
+    assert_eq!(response.events_emitted, 1);
+    assert!(response.tx_msgs.is_none());
+
+    let mut ctx = ctx.with_pending_deposit(100).with_current_height(1);
+
+    let response = progress_fsm!(ctx);
+
+    // stops at transfer pending deposits to issue the transfer
+    assert_eq!(response.transitions_traversed, 5);
+    assert!(response.tx_msgs.is_some());
+}
+
+#[test]
+fn full_cycle_emits_single_completion_event() {
+    let is_completion = |event: &Event| matches!(event, Event::ReconcileCompleted { .. });
+
+    let mut ctx = Context {
+        phase: Some(Phase::StartReconcile),
+        state: Some(State::Idle),
+        ..Default::default()
+    }
+    .with_current_height(10);
+
+    let response = progress_fsm!(ctx);
+
+    let completions: Vec<_> = response
+        .events
+        .iter()
+        .filter(|event| is_completion(event))
+        .collect();
+
+    assert!(matches!(
+        completions.as_slice(),
+        [Event::ReconcileCompleted { height: 10 }]
+    ));
+
+    // pausing mid-cycle to issue a tx does not complete the cycle
+    let mut ctx = ctx.with_pending_deposit(100).with_current_height(11);
+
+    let response = progress_fsm!(ctx);
+
+    assert!(response.tx_msgs.is_some());
+    assert!(!response.events.iter().any(is_completion));
+}
+
 #[test]
 fn withdraw_rewards_only() {
     let mut ctx = Context {
@@ -806,13 +1325,24 @@ fn withdraw_rewards_only() {
               cmds: [
                 MsgIssuedCount((0)),
                 MsgSuccessCount((0)),
+                SetupAccounts((
+                  delegation: "delegation_account",
+                  rewards: "rewards_account",
+                )),
                 LastReconcileHeight((0)),
                 Phase(StartReconcile),
                 State(Idle),
               ],
-              events: [],
+              events: [
+                ReconcileCompleted(
+                  height: 0,
+                ),
+              ],
               tx_msgs: None,
               tx_skip_count: 5,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 1,
             )"#]],
     );
 
@@ -829,11 +1359,383 @@ fn withdraw_rewards_only() {
                 Phase(StartReconcile),
                 State(Idle),
               ],
-              events: [],
+              events: [
+                ReconcileCompleted(
+                  height: 0,
+                ),
+              ],
               tx_msgs: None,
               tx_skip_count: 5,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 1,
+            )"#]],
+    );
+}
+
+#[test]
+fn reconcile_rewards_only() {
+    let mut ctx = Context::default().with_pending_deposit(1_000);
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    let mut ctx = ctx
+        .with_current_height(1)
+        .with_pending_deposit(200)
+        .with_pending_unbond(300)
+        .with_rewards_balance_report(1, 100);
+
+    macro_rules! reconcile_rewards_only {
+        ($ctx:ident) => {{
+            let response = fsm(&$ctx, &$ctx, &$ctx)
+                .reconcile_rewards_only()
+                .unwrap()
+                .expect("idle at the start of a cycle");
+
+            for cmd in response.cmds.clone() {
+                $ctx.handle_cmd(cmd);
+            }
+
+            if let Some(tx_msgs) = response.tx_msgs.clone() {
+                for tx_msg in tx_msgs.msgs {
+                    $ctx.handle_tx_msg(tx_msg);
+                }
+            }
+
+            response
+        }};
+    }
+
+    // delegate the accrued rewards
+    let response = reconcile_rewards_only!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                InflightDelegation((100)),
+                InflightRewardsReceivable((100)),
+                MsgIssuedCount((6)),
+                MsgSuccessCount((0)),
+                Phase(Delegate),
+                State(Pending),
+              ],
+              events: [],
+              tx_msgs: Some((
+                msgs: [
+                  Authz([
+                    SendRewardsReceivable((100)),
+                  ]),
+                  Delegate((0), 20),
+                  Delegate((1), 20),
+                  Delegate((2), 20),
+                  Delegate((3), 20),
+                  Delegate((4), 20),
+                ],
+              )),
+              tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 0,
+            )"#]],
+    );
+
+    let response = progress_fsm!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                Delegated((1100)),
+                DelegateStartSlot((0)),
+                InflightDelegation((0)),
+                InflightDeposit((0)),
+                InflightFeePayable((0)),
+                InflightRewardsReceivable((0)),
+                MsgIssuedCount((0)),
+                MsgSuccessCount((0)),
+                Weights(([
+                  (("0.1990909090909090909090909090909")),
+                  (("0.1990909090909090909090909090909")),
+                  (("0.1990909090909090909090909090909")),
+                  (("0.1990909090909090909090909090909")),
+                  (("0.1990909090909090909090909090909")),
+                ])),
+                LastReconcileHeight((1)),
+                Phase(StartReconcile),
+                State(Idle),
+              ],
+              events: [
+                DelegationsIncreased(100),
+                ReconcileCompleted(
+                  height: 1,
+                ),
+              ],
+              tx_msgs: None,
+              tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 2,
+            )"#]],
+    );
+
+    // no further rewards to delegate, withdraw the next lot
+    let response = reconcile_rewards_only!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                MsgIssuedCount((5)),
+                MsgSuccessCount((0)),
+                Phase(Delegate),
+                State(Pending),
+              ],
+              events: [],
+              tx_msgs: Some((
+                msgs: [
+                  WithdrawRewards((0)),
+                  WithdrawRewards((1)),
+                  WithdrawRewards((2)),
+                  WithdrawRewards((3)),
+                  WithdrawRewards((4)),
+                ],
+              )),
+              tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 0,
+            )"#]],
+    );
+
+    let response = progress_fsm!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                MsgIssuedCount((0)),
+                MsgSuccessCount((0)),
+                LastReconcileHeight((1)),
+                Phase(StartReconcile),
+                State(Idle),
+              ],
+              events: [
+                ReconcileCompleted(
+                  height: 1,
+                ),
+              ],
+              tx_msgs: None,
+              tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 1,
             )"#]],
     );
+
+    // pending deposits & unbonds are left for the next full reconcile
+    assert_eq!(ctx.pending_deposit, Some(PendingDeposit(200)));
+    assert_eq!(ctx.pending_unbond, Some(PendingUnbond(300)));
+    assert_eq!(ctx.inflight_unbond, None);
+}
+
+#[test]
+fn full_reconcile_after_forced_rewards_only_round() {
+    let mut ctx = Context::default().with_pending_deposit(1_000);
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    let mut ctx = ctx
+        .with_current_height(1)
+        .with_pending_deposit(200)
+        .with_pending_unbond(300)
+        .with_rewards_balance_report(1, 100);
+
+    let response = fsm(&ctx, &ctx, &ctx)
+        .reconcile_rewards_only()
+        .unwrap()
+        .expect("idle at the start of a cycle");
+
+    for cmd in response.cmds {
+        ctx.handle_cmd(cmd);
+    }
+
+    // a failed rewards-only delegate phase is forced on like any other
+    failure!(ctx);
+
+    let response = force_next!(ctx);
+
+    assert!(response.tx_msgs.is_none());
+    assert_eq!(ctx.phase, Some(Phase::StartReconcile));
+    assert_eq!(ctx.state, Some(State::Idle));
+    assert_eq!(ctx.pending_deposit, Some(PendingDeposit(200)));
+    assert_eq!(ctx.pending_unbond, Some(PendingUnbond(300)));
+
+    // the next full reconcile runs the phases skipped by the rewards-only round
+    let mut phases = vec![];
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {
+        phases.extend(ctx.phase);
+    }
+
+    check(
+        phases,
+        expect![[r#"
+        [
+          Undelegate,
+          TransferPendingDeposits,
+          Delegate,
+        ]"#]],
+    );
+
+    assert_eq!(ctx.pending_deposit, Some(PendingDeposit(0)));
+    assert_eq!(ctx.pending_unbond, Some(PendingUnbond(0)));
+    // 1_000 + 200 deposited - 300 unbonded
+    assert_eq!(ctx.delegated, Some(Delegated(900)));
+}
+
+#[test]
+fn rewards_only_sequence_tx_count() {
+    let set_size = ValidatorSetSize(20);
+    let max_msg_count = MaxMsgCount(16);
+
+    assert_eq!(
+        ReconcileScope::Full.sequence_tx_count(
+            Phase::StartReconcile,
+            State::Idle,
+            set_size,
+            max_msg_count,
+            DelegateAuthzMsgs::ALL,
+        ),
+        Phase::StartReconcile.sequence_tx_count(
+            State::Idle,
+            set_size,
+            max_msg_count,
+            DelegateAuthzMsgs::ALL,
+        ),
+    );
+
+    assert_eq!(
+        ReconcileScope::RewardsOnly.sequence_tx_count(
+            Phase::StartReconcile,
+            State::Idle,
+            set_size,
+            max_msg_count,
+            DelegateAuthzMsgs::ALL,
+        ),
+        Phase::Delegate.tx_count(set_size, max_msg_count, DelegateAuthzMsgs::ALL),
+    );
+
+    assert_eq!(
+        ReconcileScope::RewardsOnly.sequence_tx_count(
+            Phase::Delegate,
+            State::Pending,
+            set_size,
+            max_msg_count,
+            DelegateAuthzMsgs::ALL,
+        ),
+        0,
+    );
+}
+
+// The delegate phase txs predicted at the start of a cycle vs those actually issued by the cycle
+fn delegate_phase_tx_counts(ctx: &mut Context) -> (usize, usize) {
+    let predicted = Phase::Delegate.tx_count(
+        ctx.validator_set_size(),
+        ctx.max_msg_count(),
+        delegate_authz_msgs(ctx, ctx, ctx),
+    );
+
+    let mut issued = 0;
+
+    let mut cycle_ctx = std::mem::take(ctx);
+
+    while progress_fsm!(cycle_ctx).tx_msgs.is_some() {
+        if cycle_ctx.phase == Some(Phase::Delegate) {
+            issued += 1;
+        }
+    }
+
+    *ctx = cycle_ctx;
+
+    (predicted, issued)
+}
+
+#[test]
+fn delegate_phase_tx_count_accounts_for_authz_msgs() {
+    // 16 slots: a single bundled Authz send spills into a second tx
+    let mut ctx = Context {
+        starting_weights: Some(weights(16)),
+        ..Default::default()
+    }
+    .with_pending_deposit(1_600);
+
+    // no rewards or fee on the first cycle
+    assert_eq!(delegate_phase_tx_counts(&mut ctx), (1, 1));
+
+    let mut ctx = ctx
+        .with_pending_deposit(1_600)
+        .with_rewards_balance_report(1, 100);
+
+    // rewards sent, no fee recipient
+    assert_eq!(delegate_phase_tx_counts(&mut ctx), (2, 2));
+
+    // 15 slots: only both bundled Authz sends spill into a second tx
+    let mut ctx = Context {
+        starting_weights: Some(weights(15)),
+        ..Default::default()
+    }
+    .with_pending_deposit(1_500);
+
+    assert_eq!(delegate_phase_tx_counts(&mut ctx), (1, 1));
+
+    let mut ctx = ctx
+        .with_pending_deposit(1_500)
+        .with_rewards_balance_report(1, 100);
+
+    // rewards sent, no fee recipient
+    assert_eq!(delegate_phase_tx_counts(&mut ctx), (1, 1));
+
+    let mut ctx = ctx
+        .with_pending_deposit(1_500)
+        .with_rewards_balance_report(2, 200)
+        .with_fee_recipient("reconciler")
+        .with_current_height(1000);
+
+    // rewards & fee sent
+    assert_eq!(delegate_phase_tx_counts(&mut ctx), (2, 2));
+}
+
+#[test]
+fn reconcile_rewards_only_not_started_mid_cycle_or_while_undelegating() {
+    let mid_cycle = Context {
+        phase: Some(Phase::Delegate),
+        state: Some(State::Pending),
+        last_reconcile_height: Some(LastReconcileHeight(0)),
+        ..Default::default()
+    };
+
+    assert!(fsm(&mid_cycle, &mid_cycle, &mid_cycle)
+        .reconcile_rewards_only()
+        .unwrap()
+        .is_none());
+
+    let undelegating = Context {
+        phase: Some(Phase::StartReconcile),
+        state: Some(State::Idle),
+        last_reconcile_height: Some(LastReconcileHeight(0)),
+        inflight_unbond: Some(InflightUnbond(100)),
+        ..Default::default()
+    };
+
+    assert!(fsm(&undelegating, &undelegating, &undelegating)
+        .reconcile_rewards_only()
+        .unwrap()
+        .is_none());
 }
 
 #[test]
@@ -873,14 +1775,17 @@ fn pending_unbond_only() {
               events: [],
               tx_msgs: Some((
                 msgs: [
-                  Undelegate((0), 100001),
+                  Undelegate((0), 99999),
                   Undelegate((1), 99999),
                   Undelegate((2), 99999),
                   Undelegate((3), 99999),
-                  Undelegate((4), 99999),
+                  Undelegate((4), 100004),
                 ],
               )),
               tx_skip_count: 1,
+              abort_reason: None,
+              transitions_traversed: 3,
+              events_emitted: 0,
             )"#]],
     );
 
@@ -912,6 +1817,9 @@ fn pending_unbond_only() {
                 ],
               )),
               tx_skip_count: 2,
+              abort_reason: None,
+              transitions_traversed: 4,
+              events_emitted: 1,
             )"#]],
     );
 
@@ -928,9 +1836,16 @@ fn pending_unbond_only() {
                 Phase(StartReconcile),
                 State(Idle),
               ],
-              events: [],
+              events: [
+                ReconcileCompleted(
+                  height: 0,
+                ),
+              ],
               tx_msgs: None,
               tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 1,
             )"#]],
     );
 
@@ -938,11 +1853,11 @@ fn pending_unbond_only() {
         ctx.delegations,
         expect![[r#"
             {
-              0: 100003,
-              1: 100000,
-              2: 100000,
-              3: 100000,
-              4: 100000,
+              0: 100001,
+              1: 100001,
+              2: 100001,
+              3: 100001,
+              4: 99996,
             }"#]],
     );
 }
@@ -966,15 +1881,18 @@ fn notify_failure_in_pending_state() {
     check(
         &response,
         expect![[r#"
-        (
-          cmds: [
-            State(Failed),
-            MsgIssuedCount((0)),
-          ],
-          events: [],
-          tx_msgs: None,
-          tx_skip_count: 0,
-        )"#]],
+            (
+              cmds: [
+                State(Failed),
+                MsgIssuedCount((0)),
+              ],
+              events: [],
+              tx_msgs: None,
+              tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 0,
+              events_emitted: 0,
+            )"#]],
     );
 
     for cmd in response.cmds {
@@ -1007,35 +1925,42 @@ fn pending_deposits_unbonded_before_delegation() {
 
     let response = progress_fsm!(ctx);
 
-    check(
-        response,
-        expect![[r#"
-        (
-          cmds: [
-            InflightDeposit((500000)),
-            MsgIssuedCount((1)),
-            MsgSuccessCount((0)),
-            Phase(TransferPendingDeposits),
-            State(Pending),
-          ],
-          events: [],
-          tx_msgs: Some((
-            msgs: [
-              TransferOutPendingDeposit(500000),
-            ],
-          )),
-          tx_skip_count: 3,
-        )"#]],
-    );
-
-    let response = progress_fsm!(ctx);
-
     check(
         response,
         expect![[r#"
             (
               cmds: [
-                InflightDelegation((500000)),
+                InflightDeposit((500000)),
+                MsgIssuedCount((1)),
+                MsgSuccessCount((0)),
+                SetupAccounts((
+                  delegation: "delegation_account",
+                  rewards: "rewards_account",
+                )),
+                Phase(TransferPendingDeposits),
+                State(Pending),
+              ],
+              events: [],
+              tx_msgs: Some((
+                msgs: [
+                  TransferOutPendingDeposit(500000),
+                ],
+              )),
+              tx_skip_count: 3,
+              abort_reason: None,
+              transitions_traversed: 5,
+              events_emitted: 0,
+            )"#]],
+    );
+
+    let response = progress_fsm!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                InflightDelegation((500000)),
                 MsgIssuedCount((5)),
                 MsgSuccessCount((0)),
                 PendingDeposit((0)),
@@ -1047,14 +1972,17 @@ fn pending_deposits_unbonded_before_delegation() {
               ],
               tx_msgs: Some((
                 msgs: [
-                  Delegate((0), 100004),
-                  Delegate((1), 99999),
-                  Delegate((2), 99999),
-                  Delegate((3), 99999),
-                  Delegate((4), 99999),
+                  Delegate((0), 100000),
+                  Delegate((1), 100000),
+                  Delegate((2), 100000),
+                  Delegate((3), 100000),
+                  Delegate((4), 100000),
                 ],
               )),
               tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 2,
+              events_emitted: 1,
             )"#]],
     );
 
@@ -1073,22 +2001,21 @@ fn pending_deposits_unbonded_before_delegation() {
                 InflightRewardsReceivable((0)),
                 MsgIssuedCount((0)),
                 MsgSuccessCount((0)),
-                Weights(([
-                  (("0.20000799999999999999999999999999")),
-                  (("0.19999799999999999999999999999999")),
-                  (("0.19999799999999999999999999999999")),
-                  (("0.19999799999999999999999999999999")),
-                  (("0.19999799999999999999999999999999")),
-                ])),
                 LastReconcileHeight((0)),
                 Phase(StartReconcile),
                 State(Idle),
               ],
               events: [
                 DelegationsIncreased(500000),
+                ReconcileCompleted(
+                  height: 0,
+                ),
               ],
               tx_msgs: None,
               tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 2,
             )"#]],
     );
 
@@ -1108,14 +2035,17 @@ fn pending_deposits_unbonded_before_delegation() {
               events: [],
               tx_msgs: Some((
                 msgs: [
-                  Undelegate((0), 100003),
-                  Undelegate((1), 99998),
-                  Undelegate((2), 99998),
-                  Undelegate((3), 99998),
-                  Undelegate((4), 99998),
+                  Undelegate((0), 99999),
+                  Undelegate((1), 99999),
+                  Undelegate((2), 99999),
+                  Undelegate((3), 99999),
+                  Undelegate((4), 99999),
                 ],
               )),
               tx_skip_count: 1,
+              abort_reason: None,
+              transitions_traversed: 3,
+              events_emitted: 0,
             )"#]],
     );
 
@@ -1137,9 +2067,15 @@ fn pending_deposits_unbonded_before_delegation() {
               ],
               events: [
                 UnbondStarted(500000),
+                ReconcileCompleted(
+                  height: 0,
+                ),
               ],
               tx_msgs: None,
               tx_skip_count: 3,
+              abort_reason: None,
+              transitions_traversed: 4,
+              events_emitted: 2,
             )"#]],
     );
 
@@ -1156,9 +2092,16 @@ fn pending_deposits_unbonded_before_delegation() {
                 Phase(StartReconcile),
                 State(Idle),
               ],
-              events: [],
+              events: [
+                ReconcileCompleted(
+                  height: 0,
+                ),
+              ],
               tx_msgs: None,
               tx_skip_count: 5,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 1,
             )"#]],
     );
 
@@ -1175,6 +2118,88 @@ fn pending_deposits_unbonded_before_delegation() {
     );
 }
 
+#[test]
+fn target_weights_change_delegation_distribution() {
+    let delegate_msgs = |target_weights: Option<Weights>| {
+        let mut ctx = Context {
+            phase: Some(Phase::StartReconcile),
+            state: Some(State::Idle),
+            last_reconcile_height: Some(LastReconcileHeight(0)),
+            pending_deposit: Some(PendingDeposit(500_000)),
+            target_weights,
+            ..Default::default()
+        };
+
+        // transfer pending deposits
+        progress_fsm!(ctx);
+
+        progress_fsm!(ctx).tx_msgs.unwrap().msgs
+    };
+
+    check(
+        delegate_msgs(None),
+        expect![[r#"
+            [
+              Delegate((0), 100000),
+              Delegate((1), 100000),
+              Delegate((2), 100000),
+              Delegate((3), 100000),
+              Delegate((4), 100000),
+            ]"#]],
+    );
+
+    let target_weights =
+        [4_000u32, 3_000, 2_000, 1_000, 0].map(|bps| Weight::checked_from_bps(bps).unwrap());
+
+    check(
+        delegate_msgs(Weights::new(&target_weights).ok()),
+        expect![[r#"
+            [
+              Delegate((0), 200001),
+              Delegate((1), 150000),
+              Delegate((2), 100000),
+              Delegate((3), 49999),
+            ]"#]],
+    );
+}
+
+#[test]
+fn target_weights_converge_over_delegations() {
+    let target_weights =
+        [4_000u32, 3_000, 2_000, 1_000, 0].map(|bps| Weight::checked_from_bps(bps).unwrap());
+
+    let mut ctx = Context {
+        phase: Some(Phase::StartReconcile),
+        state: Some(State::Idle),
+        delegated: Some(Delegated(1_000_000)),
+        target_weights: Weights::new(&target_weights).ok(),
+        ..Default::default()
+    };
+
+    for _ in 0..2 {
+        ctx.pending_deposit = Some(PendingDeposit(1_000_000));
+
+        // transfer pending deposits
+        progress_fsm!(ctx);
+        // delegate
+        progress_fsm!(ctx);
+        // delegations succeeded
+        progress_fsm!(ctx);
+    }
+
+    check(
+        ctx.weights,
+        expect![[r#"
+            Some(([
+              (("0.37222133333333333333333333333333")),
+              (("0.27962899999999999999999999999999")),
+              (("0.18703633333333333333333333333333")),
+              (("0.09444399999999999999999999999999")),
+              (("0.06666599999999999999999999999999")),
+            ]))"#]],
+    );
+}
+
 #[test]
 fn total_unbonding() {
     let deposits: Vec<Vec<u128>> = vec![
@@ -1279,6 +2304,9 @@ fn detect_slashing() {
                 ],
               )),
               tx_skip_count: 4,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 1,
             )"#]],
     );
 
@@ -1329,11 +2357,11 @@ fn undelegate_post_slashing() {
                 MsgSuccessCount((0)),
                 PendingUnbond((496999999)),
                 Weights(([
-                  (("0.20120724748490945674044265593561")),
-                  (("0.20120724245472837022132796780684")),
-                  (("0.20120724245472837022132796780684")),
-                  (("0.20120724245472837022132796780684")),
-                  (("0.19517102515090543259557344064386")),
+                  (("0.20120724346076458752515090543259")),
+                  (("0.20120724346076458752515090543259")),
+                  (("0.20120724346076458752515090543259")),
+                  (("0.20120724346076458752515090543259")),
+                  (("0.19517102615694164989939637826961")),
                 ])),
                 Phase(Undelegate),
                 State(Pending),
@@ -1343,14 +2371,17 @@ fn undelegate_post_slashing() {
               ],
               tx_msgs: Some((
                 msgs: [
-                  Undelegate((0), 100000001),
+                  Undelegate((0), 99999999),
                   Undelegate((1), 99999999),
                   Undelegate((2), 99999999),
                   Undelegate((3), 99999999),
-                  Undelegate((4), 96999999),
+                  Undelegate((4), 97000003),
                 ],
               )),
               tx_skip_count: 1,
+              abort_reason: None,
+              transitions_traversed: 3,
+              events_emitted: 1,
             )"#]],
     );
 
@@ -1382,13 +2413,24 @@ fn undelegate_post_slashing() {
                 ],
               )),
               tx_skip_count: 2,
+              abort_reason: None,
+              transitions_traversed: 4,
+              events_emitted: 1,
             )"#]],
     );
 
     // finish round
     while progress_fsm!(ctx).tx_msgs.is_some() {}
 
-    ctx = ctx.with_pending_unbond(497_000_001);
+    // the last slot undelegated the rounding remainder, which the next delegations report reflects
+    let delegations = ctx.delegations.values().copied().collect();
+
+    let report_height = ctx.last_reconcile_height.as_ref().unwrap().0 + 1;
+
+    ctx = ctx
+        .with_pending_unbond(497_000_001)
+        .with_delegations_report(report_height, 497_000_001, delegations)
+        .with_current_height(report_height + 1);
 
     // next round
     while progress_fsm!(ctx).tx_msgs.is_some() {}
@@ -1396,6 +2438,144 @@ fn undelegate_post_slashing() {
     assert_eq!(ctx.delegated, Some(Delegated(0)));
 }
 
+fn delegated_ctx_with_drift(drift_per_slot: &[i128]) -> Context {
+    let mut ctx = Context::default().with_pending_deposit(1_000_000_000);
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    let reported_delegations: Vec<u128> = ctx
+        .delegations
+        .values()
+        .zip(drift_per_slot)
+        .map(|(&delegation, &drift)| delegation.checked_add_signed(drift).unwrap())
+        .collect();
+
+    let total_delegated = reported_delegations.iter().sum();
+
+    let report_height = ctx.last_reconcile_height.as_ref().unwrap().0 + 1;
+
+    ctx.with_delegations_report(report_height, total_delegated, reported_delegations)
+        .with_current_height(report_height + 1)
+}
+
+#[test]
+fn delegation_drift_gain_corrected() {
+    let mut ctx = delegated_ctx_with_drift(&[1, 1, 0, 0, 1]);
+
+    let response = progress_fsm!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                Delegated((1000000003)),
+                MsgIssuedCount((5)),
+                MsgSuccessCount((0)),
+                Weights(([
+                  (("0.20000000039999999880000000359999")),
+                  (("0.20000000039999999880000000359999")),
+                  (("0.1999999994000000017999999946")),
+                  (("0.1999999994000000017999999946")),
+                  (("0.20000000039999999880000000359999")),
+                ])),
+                Phase(Delegate),
+                State(Pending),
+              ],
+              events: [
+                DelegationDriftCorrected(
+                  delta: 3,
+                ),
+              ],
+              tx_msgs: Some((
+                msgs: [
+                  WithdrawRewards((0)),
+                  WithdrawRewards((1)),
+                  WithdrawRewards((2)),
+                  WithdrawRewards((3)),
+                  WithdrawRewards((4)),
+                ],
+              )),
+              tx_skip_count: 4,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 1,
+            )"#]],
+    );
+
+    assert_eq!(ctx.delegated, Some(Delegated(1_000_000_003)));
+}
+
+#[test]
+fn delegation_drift_loss_corrected() {
+    let mut ctx = delegated_ctx_with_drift(&[0, -1, -1, -1, -1]);
+
+    let response = progress_fsm!(ctx);
+
+    assert!(matches!(
+        response.events.as_slice(),
+        [Event::DelegationDriftCorrected { delta: -4 }]
+    ));
+
+    assert!(
+        !response
+            .cmds
+            .iter()
+            .any(|cmd| matches!(cmd, Cmd::PendingUnbond(_) | Cmd::InflightUnbond(_))),
+        "drift within tolerance is not treated as a slashing"
+    );
+
+    assert_eq!(ctx.delegated, Some(Delegated(999_999_996)));
+}
+
+#[test]
+fn delegation_drift_loss_scales_unbonds() {
+    let mut ctx = delegated_ctx_with_drift(&[0, -1, -1, -1, -1]).with_pending_unbond(500_000_000);
+
+    ctx.inflight_unbond = Some(InflightUnbond(250_000_000));
+
+    let response = progress_fsm!(ctx);
+
+    assert!(matches!(
+        response.events.as_slice(),
+        [Event::DelegationDriftCorrected { delta: -4 }]
+    ));
+
+    check(
+        (&ctx.delegated, &ctx.pending_unbond, &ctx.inflight_unbond),
+        expect!["(Some((999999996)), Some((499999997)), Some((249999998)))"],
+    );
+}
+
+#[test]
+fn delegation_surplus_beyond_tolerance_absorbed() {
+    let mut ctx = delegated_ctx_with_drift(&[1_000, 0, 0, 0, 0]);
+
+    let response = progress_fsm!(ctx);
+
+    assert!(matches!(
+        response.events.as_slice(),
+        [Event::DelegationSurplusAbsorbed(1_000)]
+    ));
+
+    assert_eq!(ctx.delegated, Some(Delegated(1_000_001_000)));
+}
+
+#[test]
+fn delegation_report_matching_records_is_noop() {
+    let mut ctx = delegated_ctx_with_drift(&[0, 0, 0, 0, 0]);
+
+    let weights_before = ctx.weights.clone();
+
+    let response = progress_fsm!(ctx);
+
+    assert!(response.events.is_empty());
+
+    assert_eq!(ctx.delegated, Some(Delegated(1_000_000_000)));
+
+    assert_eq!(ctx.weights, weights_before);
+}
+
 #[test]
 fn undelegate_force_next() {
     let mut ctx = Context {
@@ -1408,32 +2588,7 @@ fn undelegate_force_next() {
 
     ctx = ctx.with_pending_unbond(500_000_000);
 
-    check(
-        &ctx.weights,
-        expect![[r#"
-        Some(([
-          (("0.05000001899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-          (("0.04999999899999999999999999999999")),
-        ]))"#]],
-    );
+    check(&ctx.weights, expect!["None"]);
 
     // first undelegate batch
     progress_fsm!(ctx);
@@ -1442,33 +2597,33 @@ fn undelegate_force_next() {
         &ctx.delegations,
         expect![[r#"
             {
-              0: 25000010,
-              1: 25000000,
-              2: 25000000,
-              3: 25000000,
-              4: 25000000,
-              5: 25000000,
-              6: 25000000,
-              7: 25000000,
-              8: 25000000,
-              9: 25000000,
-              10: 25000000,
-              11: 25000000,
-              12: 25000000,
-              13: 25000000,
-              14: 25000000,
-              15: 25000000,
-              16: 49999999,
-              17: 49999999,
-              18: 49999999,
-              19: 49999999,
+              0: 25000001,
+              1: 25000001,
+              2: 25000001,
+              3: 25000001,
+              4: 25000001,
+              5: 25000001,
+              6: 25000001,
+              7: 25000001,
+              8: 25000001,
+              9: 25000001,
+              10: 25000001,
+              11: 25000001,
+              12: 25000001,
+              13: 25000001,
+              14: 25000001,
+              15: 25000001,
+              16: 50000000,
+              17: 50000000,
+              18: 50000000,
+              19: 50000000,
             }"#]],
     );
 
     assert_eq!(ctx.phase, Some(Phase::Undelegate));
 
     // second undelegate batch
-    let response = fsm(&ctx, &ctx, &ctx).reconcile();
+    let response = fsm(&ctx, &ctx, &ctx).reconcile().unwrap();
 
     for cmd in response.cmds.clone() {
         ctx.handle_cmd(cmd);
@@ -1490,10 +2645,13 @@ fn undelegate_force_next() {
                   Undelegate((16), 24999999),
                   Undelegate((17), 24999999),
                   Undelegate((18), 24999999),
-                  Undelegate((19), 24999999),
+                  Undelegate((19), 25000019),
                 ],
               )),
               tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 0,
             )"#]],
     );
 
@@ -1509,39 +2667,39 @@ fn undelegate_force_next() {
         expect![[r#"
             (
               cmds: [
-                Delegated((600000006)),
-                InflightUnbond((100000006)),
+                Delegated((600000016)),
+                InflightUnbond((100000016)),
                 MsgIssuedCount((16)),
                 MsgSuccessCount((0)),
-                PendingUnbond((100000006)),
+                PendingUnbond((100000016)),
                 UndelegateStartSlot((16)),
                 Weights(([
-                  (("0.04166668124999985416666812499998")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.04166666458333335416666645833333")),
-                  (("0.08333332916666670833333291666667")),
-                  (("0.08333332916666670833333291666667")),
-                  (("0.08333332916666670833333291666667")),
-                  (("0.08333332916666670833333291666667")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.04166666555555558518518439506174")),
+                  (("0.08333332944444454814814538271612")),
+                  (("0.08333332944444454814814538271612")),
+                  (("0.08333332944444454814814538271612")),
+                  (("0.08333332944444454814814538271612")),
                 ])),
                 Phase(Delegate),
                 State(Pending),
               ],
               events: [
-                UnbondStarted(399999994),
+                UnbondStarted(399999984),
               ],
               tx_msgs: Some((
                 msgs: [
@@ -1564,6 +2722,9 @@ fn undelegate_force_next() {
                 ],
               )),
               tx_skip_count: 2,
+              abort_reason: None,
+              transitions_traversed: 3,
+              events_emitted: 1,
             )"#]],
     );
 
@@ -1586,13 +2747,16 @@ fn undelegate_force_next() {
               events: [],
               tx_msgs: Some((
                 msgs: [
-                  Undelegate((16), 25000001),
-                  Undelegate((17), 25000001),
-                  Undelegate((18), 25000001),
-                  Undelegate((19), 25000001),
+                  Undelegate((16), 25000004),
+                  Undelegate((17), 25000004),
+                  Undelegate((18), 25000004),
+                  Undelegate((19), 25000004),
                 ],
               )),
               tx_skip_count: 2,
+              abort_reason: None,
+              transitions_traversed: 3,
+              events_emitted: 0,
             )"#]],
     );
 
@@ -1611,32 +2775,32 @@ fn undelegate_force_next() {
                 PendingUnbond((0)),
                 UndelegateStartSlot((0)),
                 Weights(([
-                  (("0.05000001599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999599999999999999999999999")),
-                  (("0.04999999199999999999999999999999")),
-                  (("0.04999999199999999999999999999999")),
-                  (("0.04999999199999999999999999999999")),
-                  (("0.04999999199999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999999799999999999999999999999")),
+                  (("0.04999998799999999999999999999999")),
+                  (("0.04999998799999999999999999999999")),
+                  (("0.04999998799999999999999999999999")),
+                  (("0.04999998799999999999999999999999")),
                 ])),
                 Phase(Delegate),
                 State(Pending),
               ],
               events: [
-                UnbondStarted(100000006),
+                UnbondStarted(100000016),
               ],
               tx_msgs: Some((
                 msgs: [
@@ -1659,6 +2823,9 @@ fn undelegate_force_next() {
                 ],
               )),
               tx_skip_count: 2,
+              abort_reason: None,
+              transitions_traversed: 4,
+              events_emitted: 1,
             )"#]],
     );
 
@@ -1666,32 +2833,32 @@ fn undelegate_force_next() {
         ctx.delegations,
         expect![[r#"
             {
-              0: 25000010,
-              1: 25000000,
-              2: 25000000,
-              3: 25000000,
-              4: 25000000,
-              5: 25000000,
-              6: 25000000,
-              7: 25000000,
-              8: 25000000,
-              9: 25000000,
-              10: 25000000,
-              11: 25000000,
-              12: 25000000,
-              13: 25000000,
-              14: 25000000,
-              15: 25000000,
-              16: 24999998,
-              17: 24999998,
-              18: 24999998,
-              19: 24999998,
+              0: 25000001,
+              1: 25000001,
+              2: 25000001,
+              3: 25000001,
+              4: 25000001,
+              5: 25000001,
+              6: 25000001,
+              7: 25000001,
+              8: 25000001,
+              9: 25000001,
+              10: 25000001,
+              11: 25000001,
+              12: 25000001,
+              13: 25000001,
+              14: 25000001,
+              15: 25000001,
+              16: 24999996,
+              17: 24999996,
+              18: 24999996,
+              19: 24999996,
             }"#]],
     )
 }
 
 #[test]
-fn undelegate_all_force_next() {
+fn undelegate_retries_increment() {
     let mut ctx = Context {
         starting_weights: Some(weights(20)),
         ..Default::default()
@@ -1700,255 +2867,339 @@ fn undelegate_all_force_next() {
 
     while progress_fsm!(ctx).tx_msgs.is_some() {}
 
-    ctx = ctx.with_pending_unbond(1_000_000_000);
+    ctx = ctx.with_pending_unbond(500_000_000);
 
     // first undelegate batch
+    progress_fsm_no_tx!(ctx);
+
+    failure!(ctx);
+
+    for expected_retry_count in 1..=2 {
+        let response = progress_fsm_no_tx!(ctx);
+
+        assert!(response.tx_msgs.is_some());
+        assert!(response.events.is_empty());
+        assert_eq!(ctx.phase, Some(Phase::Undelegate));
+        assert_eq!(
+            ctx.undelegate_retry_count,
+            Some(UndelegateRetryCount(expected_retry_count))
+        );
+
+        failure!(ctx);
+    }
+
+    // the last retry permitted succeeds
+    progress_fsm!(ctx);
+
+    assert_eq!(ctx.undelegate_retry_count, Some(UndelegateRetryCount(3)));
+
+    // the success resets the count for the second batch
     progress_fsm!(ctx);
 
     assert_eq!(ctx.phase, Some(Phase::Undelegate));
 
-    // second undelegate batch
-    let response = fsm(&ctx, &ctx, &ctx).reconcile();
+    assert_eq!(ctx.undelegate_retry_count, Some(UndelegateRetryCount(0)));
+}
 
-    for cmd in response.cmds.clone() {
-        ctx.handle_cmd(cmd);
+#[test]
+fn undelegate_skips_slot_at_retry_threshold() {
+    let mut ctx = Context {
+        starting_weights: Some(weights(20)),
+        ..Default::default()
     }
+    .with_pending_deposit(1_000_000_000);
 
-    assert_eq!(ctx.phase, Some(Phase::Undelegate));
-    assert_eq!(ctx.state, Some(State::Pending));
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    ctx = ctx.with_pending_unbond(500_000_000);
+
+    // first undelegate batch
+    progress_fsm!(ctx);
+
+    // second undelegate batch keeps failing
+    progress_fsm_no_tx!(ctx);
+
+    for _ in 0..3 {
+        failure!(ctx);
+        progress_fsm_no_tx!(ctx);
+    }
+
+    assert_eq!(ctx.undelegate_retry_count, Some(UndelegateRetryCount(3)));
 
     failure!(ctx);
 
-    let response = force_next!(ctx);
+    let response = progress_fsm!(ctx);
 
     check(
-        response,
+        (&response.cmds, &response.events),
         expect![[r#"
-            (
-              cmds: [
-                Delegated((200000012)),
-                InflightUnbond((200000012)),
-                MsgIssuedCount((16)),
-                MsgSuccessCount((0)),
-                PendingUnbond((200000012)),
-                UndelegateStartSlot((16)),
-                Weights(([
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.0")),
-                  (("0.24999997500000149999991000000539")),
-                  (("0.24999997500000149999991000000539")),
-                  (("0.24999997500000149999991000000539")),
-                  (("0.24999997500000149999991000000539")),
-                ])),
-                Phase(Delegate),
-                State(Pending),
-              ],
-              events: [
-                UnbondStarted(799999988),
-              ],
-              tx_msgs: Some((
-                msgs: [
-                  WithdrawRewards((0)),
-                  WithdrawRewards((1)),
-                  WithdrawRewards((2)),
-                  WithdrawRewards((3)),
-                  WithdrawRewards((4)),
-                  WithdrawRewards((5)),
-                  WithdrawRewards((6)),
-                  WithdrawRewards((7)),
-                  WithdrawRewards((8)),
-                  WithdrawRewards((9)),
-                  WithdrawRewards((10)),
-                  WithdrawRewards((11)),
-                  WithdrawRewards((12)),
-                  WithdrawRewards((13)),
-                  WithdrawRewards((14)),
-                  WithdrawRewards((15)),
-                ],
-              )),
-              tx_skip_count: 2,
-            )"#]],
+            ([
+              Delegated((600000016)),
+              InflightUnbond((100000016)),
+              MsgIssuedCount((16)),
+              MsgSuccessCount((0)),
+              PendingUnbond((100000016)),
+              UndelegateRetryCount((0)),
+              UndelegateStartSlot((17)),
+              Weights(([
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.04166666555555558518518439506174")),
+                (("0.08333332944444454814814538271612")),
+                (("0.08333332944444454814814538271612")),
+                (("0.08333332944444454814814538271612")),
+                (("0.08333332944444454814814538271612")),
+              ])),
+              Phase(Delegate),
+              State(Pending),
+            ], [
+              UnbondStarted(399999984),
+              UndelegateSlotSkipped((16)),
+            ])"#]],
     );
 
+    assert_eq!(ctx.phase, Some(Phase::Delegate));
+    assert_eq!(ctx.undelegate_start_slot, Some(UndelegateStartSlot(17)));
+    assert_eq!(ctx.undelegate_retry_count, Some(UndelegateRetryCount(0)));
+}
+
+#[test]
+fn undelegate_skips_first_slot_at_retry_threshold() {
+    let mut ctx = Context::default().with_pending_deposit(1_000_000_000);
+
     while progress_fsm!(ctx).tx_msgs.is_some() {}
 
-    // resume undelegation
+    ctx = ctx.with_pending_unbond(500_000_000);
+
+    // single undelegate batch keeps failing
+    progress_fsm_no_tx!(ctx);
+
+    for _ in 0..3 {
+        failure!(ctx);
+        progress_fsm_no_tx!(ctx);
+    }
+
+    failure!(ctx);
+
     let response = progress_fsm!(ctx);
 
     check(
-        response,
+        (&response.cmds, &response.events),
         expect![[r#"
-            (
-              cmds: [
-                MsgIssuedCount((4)),
-                MsgSuccessCount((0)),
-                Phase(Undelegate),
-                State(Pending),
-              ],
-              events: [],
-              tx_msgs: Some((
-                msgs: [
-                  Undelegate((16), 49999997),
-                  Undelegate((17), 49999997),
-                  Undelegate((18), 49999997),
-                  Undelegate((19), 49999997),
-                ],
-              )),
-              tx_skip_count: 2,
-            )"#]],
+            ([
+              MsgIssuedCount((5)),
+              MsgSuccessCount((0)),
+              UndelegateRetryCount((0)),
+              UndelegateStartSlot((1)),
+              Phase(Delegate),
+              State(Pending),
+            ], [
+              UndelegateSlotSkipped((0)),
+            ])"#]],
     );
 
+    // nothing was unbonded, the remainder starts after the skipped slot next round
+    assert_eq!(ctx.undelegate_start_slot, Some(UndelegateStartSlot(1)));
+    assert_eq!(ctx.undelegate_retry_count, Some(UndelegateRetryCount(0)));
+    assert_eq!(ctx.inflight_unbond, Some(InflightUnbond(500_000_000)));
+}
+
+#[test]
+fn undelegate_defers_slot_at_max_unbonding_entries() {
+    let mut ctx = Context::default().with_pending_deposit(1_000);
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    let deferred_slot_delegation = ctx.delegations[&1];
+
+    ctx = ctx
+        .with_pending_unbond(500)
+        .with_unbonding_entries(vec![0, 7, 3, 0, 0]);
+
     let response = progress_fsm!(ctx);
 
     check(
-        response,
+        (&response.cmds, &response.events, &response.tx_msgs),
         expect![[r#"
-            (
-              cmds: [
-                Delegated((0)),
-                InflightUnbond((0)),
-                MsgIssuedCount((0)),
-                MsgSuccessCount((0)),
-                PendingUnbond((0)),
-                UndelegateStartSlot((0)),
-                Weights(([
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                  (("0.04999999999999999999999999999999")),
-                ])),
-                LastReconcileHeight((0)),
-                Phase(StartReconcile),
-                State(Idle),
-              ],
-              events: [
-                UnbondStarted(200000012),
+            ([
+              InflightUnbond((500)),
+              MsgIssuedCount((4)),
+              MsgSuccessCount((0)),
+              UndelegateDeferredSlots(([
+                (1),
+              ])),
+              Phase(Undelegate),
+              State(Pending),
+            ], [
+              UnbondingEntriesFull((1)),
+            ], Some((
+              msgs: [
+                Undelegate((0), 125),
+                Undelegate((2), 125),
+                Undelegate((3), 125),
+                Undelegate((4), 125),
               ],
-              tx_msgs: None,
-              tx_skip_count: 4,
-            )"#]],
+            )))"#]],
+    );
+
+    let response = progress_fsm!(ctx);
+
+    check(
+        (&response.cmds, &response.events),
+        expect![[r#"
+            ([
+              Delegated((500)),
+              InflightUnbond((0)),
+              MsgIssuedCount((5)),
+              MsgSuccessCount((0)),
+              PendingUnbond((0)),
+              UndelegateDeferredSlots(([])),
+              Weights(([
+                (("0.14799999999999999999999999999999")),
+                (("0.39799999999999999999999999999999")),
+                (("0.14799999999999999999999999999999")),
+                (("0.14799999999999999999999999999999")),
+                (("0.14799999999999999999999999999999")),
+              ])),
+              Phase(Delegate),
+              State(Pending),
+            ], [
+              UnbondStarted(500),
+            ])"#]],
     );
+
+    assert_eq!(ctx.delegations[&1], deferred_slot_delegation);
+    assert_eq!(ctx.delegated, Some(Delegated(500)));
+    assert_eq!(ctx.pending_unbond, Some(PendingUnbond(0)));
 }
 
 #[test]
-fn delegate_force_next() {
-    let mut ctx = Context {
-        starting_weights: Some(weights(20)),
-        ..Default::default()
-    }
-    .with_pending_deposit(1_000_000_000);
+fn undelegate_defers_unbond_exceeding_remaining_slots() {
+    let mut ctx = Context::default().with_pending_deposit(1_000);
 
     while progress_fsm!(ctx).tx_msgs.is_some() {}
 
     ctx = ctx
-        .with_rewards_balance_report(1, 1_000_000)
-        .with_current_height(2)
-        .with_pending_deposit(200_000_000);
+        .with_pending_unbond(500)
+        .with_unbonding_entries(vec![0, 7, 7, 7, 7]);
 
-    // transfer out deposits
     let response = progress_fsm!(ctx);
 
     check(
-        response,
+        (&response.cmds, &response.events, &response.tx_msgs),
         expect![[r#"
-        (
-          cmds: [
-            InflightDeposit((200000000)),
-            MsgIssuedCount((1)),
-            MsgSuccessCount((0)),
-            Phase(TransferPendingDeposits),
-            State(Pending),
-          ],
-          events: [],
-          tx_msgs: Some((
-            msgs: [
-              TransferOutPendingDeposit(200000000),
-            ],
-          )),
-          tx_skip_count: 5,
-        )"#]],
+            ([
+              InflightUnbond((199)),
+              MsgIssuedCount((1)),
+              MsgSuccessCount((0)),
+              UndelegateDeferredSlots(([
+                (1),
+                (2),
+                (3),
+                (4),
+              ])),
+              Phase(Undelegate),
+              State(Pending),
+            ], [
+              UnbondingEntriesFull((1)),
+              UnbondingEntriesFull((2)),
+              UnbondingEntriesFull((3)),
+              UnbondingEntriesFull((4)),
+            ], Some((
+              msgs: [
+                Undelegate((0), 199),
+              ],
+            )))"#]],
     );
 
+    progress_fsm!(ctx);
+
+    // the remainder is left pending until the deferred slots have unbonding entries free
+    assert_eq!(ctx.delegated, Some(Delegated(801)));
+    assert_eq!(ctx.pending_unbond, Some(PendingUnbond(301)));
+}
+
+#[test]
+fn undelegate_skipped_while_all_slots_at_max_unbonding_entries() {
+    let mut ctx = Context::default().with_pending_deposit(1_000);
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    ctx = ctx
+        .with_pending_unbond(500)
+        .with_unbonding_entries(vec![7; 5]);
+
     let response = progress_fsm!(ctx);
 
     check(
-        response,
+        (&response.cmds, &response.events, &response.tx_msgs),
         expect![[r#"
-            (
-              cmds: [
-                InflightDelegation((201000000)),
-                InflightRewardsReceivable((1000000)),
-                MsgIssuedCount((16)),
-                MsgSuccessCount((0)),
-                PendingDeposit((0)),
-                Phase(Delegate),
-                State(Pending),
-              ],
-              events: [
-                DepositsTransferred(200000000),
+            ([
+              MsgIssuedCount((5)),
+              MsgSuccessCount((0)),
+              Phase(Delegate),
+              State(Pending),
+            ], [
+              UnbondingEntriesFull((0)),
+              UnbondingEntriesFull((1)),
+              UnbondingEntriesFull((2)),
+              UnbondingEntriesFull((3)),
+              UnbondingEntriesFull((4)),
+            ], Some((
+              msgs: [
+                WithdrawRewards((0)),
+                WithdrawRewards((1)),
+                WithdrawRewards((2)),
+                WithdrawRewards((3)),
+                WithdrawRewards((4)),
               ],
-              tx_msgs: Some((
-                msgs: [
-                  Authz([
-                    SendRewardsReceivable((1000000)),
-                  ]),
-                  Delegate((0), 10049996),
-                  Delegate((1), 10050004),
-                  Delegate((2), 10050000),
-                  Delegate((3), 10050000),
-                  Delegate((4), 10050000),
-                  Delegate((5), 10050000),
-                  Delegate((6), 10050000),
-                  Delegate((7), 10050000),
-                  Delegate((8), 10050000),
-                  Delegate((9), 10050000),
-                  Delegate((10), 10050000),
-                  Delegate((11), 10050000),
-                  Delegate((12), 10050000),
-                  Delegate((13), 10050000),
-                  Delegate((14), 10050000),
-                ],
-              )),
-              tx_skip_count: 0,
-            )"#]],
+            )))"#]],
     );
 
-    let response = fsm(&ctx, &ctx, &ctx).reconcile();
+    assert_eq!(ctx.delegated, Some(Delegated(1_000)));
+    assert_eq!(ctx.pending_unbond, Some(PendingUnbond(500)));
+}
+
+#[test]
+fn undelegate_all_force_next() {
+    let mut ctx = Context {
+        starting_weights: Some(weights(20)),
+        ..Default::default()
+    }
+    .with_pending_deposit(1_000_000_000);
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    ctx = ctx.with_pending_unbond(1_000_000_000);
+
+    // first undelegate batch
+    progress_fsm!(ctx);
+
+    assert_eq!(ctx.phase, Some(Phase::Undelegate));
+
+    // second undelegate batch
+    let response = fsm(&ctx, &ctx, &ctx).reconcile().unwrap();
 
     for cmd in response.cmds.clone() {
         ctx.handle_cmd(cmd);
     }
 
+    assert_eq!(ctx.phase, Some(Phase::Undelegate));
+    assert_eq!(ctx.state, Some(State::Pending));
+
     failure!(ctx);
 
     let response = force_next!(ctx);
@@ -1958,48 +3209,70 @@ fn delegate_force_next() {
         expect![[r#"
             (
               cmds: [
-                Delegated((1150750000)),
-                InflightDelegation((0)),
-                InflightDeposit((50250000)),
-                InflightRewardsReceivable((0)),
-                InflightFeePayable((0)),
-                DelegateStartSlot((15)),
+                Delegated((200000016)),
+                InflightUnbond((200000016)),
+                MsgIssuedCount((16)),
+                MsgSuccessCount((0)),
+                PendingUnbond((200000016)),
+                UndelegateStartSlot((16)),
                 Weights(([
-                  (("0.05218337084510102107321312187703")),
-                  (("0.0521833604171192700412774277645")),
-                  (("0.05218335694112535303063219639365")),
-                  (("0.05218335694112535303063219639365")),
-                  (("0.05218335694112535303063219639365")),
-                  (("0.05218335694112535303063219639365")),
-                  (("0.05218335694112535303063219639365")),
-                  (("0.05218335694112535303063219639365")),
-                  (("0.05218335694112535303063219639365")),
-                  (("0.05218335694112535303063219639365")),
-                  (("0.05218335694112535303063219639365")),
-                  (("0.05218335694112535303063219639365")),
-                  (("0.05218335694112535303063219639365")),
-                  (("0.05218335694112535303063219639365")),
-                  (("0.05218335694112535303063219639365")),
-                  (("0.04344992222463610688681294807734")),
-                  (("0.04344992222463610688681294807734")),
-                  (("0.04344992222463610688681294807734")),
-                  (("0.04344992222463610688681294807734")),
-                  (("0.04344992222463610688681294807734")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.0")),
+                  (("0.24999997500000199999984000001279")),
+                  (("0.24999997500000199999984000001279")),
+                  (("0.24999997500000199999984000001279")),
+                  (("0.24999997500000199999984000001279")),
                 ])),
-                MsgIssuedCount((0)),
-                MsgSuccessCount((0)),
-                LastReconcileHeight((2)),
-                Phase(StartReconcile),
-                State(Idle),
+                Phase(Delegate),
+                State(Pending),
               ],
               events: [
-                DelegationsIncreased(150750000),
+                UnbondStarted(799999984),
               ],
-              tx_msgs: None,
-              tx_skip_count: 0,
+              tx_msgs: Some((
+                msgs: [
+                  WithdrawRewards((0)),
+                  WithdrawRewards((1)),
+                  WithdrawRewards((2)),
+                  WithdrawRewards((3)),
+                  WithdrawRewards((4)),
+                  WithdrawRewards((5)),
+                  WithdrawRewards((6)),
+                  WithdrawRewards((7)),
+                  WithdrawRewards((8)),
+                  WithdrawRewards((9)),
+                  WithdrawRewards((10)),
+                  WithdrawRewards((11)),
+                  WithdrawRewards((12)),
+                  WithdrawRewards((13)),
+                  WithdrawRewards((14)),
+                  WithdrawRewards((15)),
+                ],
+              )),
+              tx_skip_count: 2,
+              abort_reason: None,
+              transitions_traversed: 3,
+              events_emitted: 1,
             )"#]],
     );
 
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    // resume undelegation
     let response = progress_fsm!(ctx);
 
     check(
@@ -2007,27 +3280,27 @@ fn delegate_force_next() {
         expect![[r#"
             (
               cmds: [
-                InflightDelegation((50250000)),
-                MsgIssuedCount((5)),
+                MsgIssuedCount((4)),
                 MsgSuccessCount((0)),
-                Phase(Delegate),
+                Phase(Undelegate),
                 State(Pending),
               ],
               events: [],
               tx_msgs: Some((
                 msgs: [
-                  Delegate((15), 10050004),
-                  Delegate((16), 10049999),
-                  Delegate((17), 10049999),
-                  Delegate((18), 10049999),
-                  Delegate((19), 10049999),
+                  Undelegate((16), 49999998),
+                  Undelegate((17), 49999998),
+                  Undelegate((18), 49999998),
+                  Undelegate((19), 49999998),
                 ],
               )),
-              tx_skip_count: 6,
+              tx_skip_count: 2,
+              abort_reason: None,
+              transitions_traversed: 3,
+              events_emitted: 0,
             )"#]],
     );
 
-    // resume delegation
     let response = progress_fsm!(ctx);
 
     check(
@@ -2035,78 +3308,55 @@ fn delegate_force_next() {
         expect![[r#"
             (
               cmds: [
-                Delegated((1201000000)),
-                DelegateStartSlot((0)),
-                InflightDelegation((0)),
-                InflightDeposit((0)),
-                InflightFeePayable((0)),
-                InflightRewardsReceivable((0)),
+                Delegated((0)),
+                InflightUnbond((0)),
                 MsgIssuedCount((0)),
                 MsgSuccessCount((0)),
+                PendingUnbond((0)),
+                UndelegateStartSlot((0)),
                 Weights(([
-                  (("0.05000001082431307243963363863447")),
-                  (("0.05000000083263946711074104912572")),
-                  (("0.04999999750208159866777685262281")),
-                  (("0.04999999750208159866777685262281")),
-                  (("0.04999999750208159866777685262281")),
-                  (("0.04999999750208159866777685262281")),
-                  (("0.04999999750208159866777685262281")),
-                  (("0.04999999750208159866777685262281")),
-                  (("0.04999999750208159866777685262281")),
-                  (("0.04999999750208159866777685262281")),
-                  (("0.04999999750208159866777685262281")),
-                  (("0.04999999750208159866777685262281")),
-                  (("0.04999999750208159866777685262281")),
-                  (("0.04999999750208159866777685262281")),
-                  (("0.04999999750208159866777685262281")),
-                  (("0.05000000083263946711074104912572")),
-                  (("0.04999999666944213155703580349708")),
-                  (("0.04999999666944213155703580349708")),
-                  (("0.04999999666944213155703580349708")),
-                  (("0.04999999666944213155703580349708")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
+                  (("0.04999999999999999999999999999999")),
                 ])),
-                LastReconcileHeight((2)),
+                LastReconcileHeight((0)),
                 Phase(StartReconcile),
                 State(Idle),
               ],
               events: [
-                DelegationsIncreased(50250000),
+                UnbondStarted(200000016),
+                ReconcileCompleted(
+                  height: 0,
+                ),
               ],
               tx_msgs: None,
-              tx_skip_count: 0,
+              tx_skip_count: 4,
+              abort_reason: None,
+              transitions_traversed: 4,
+              events_emitted: 2,
             )"#]],
     );
-
-    check(
-        ctx.delegations,
-        expect![[r#"
-            {
-              0: 60050015,
-              1: 60050003,
-              2: 60049999,
-              3: 60049999,
-              4: 60049999,
-              5: 60049999,
-              6: 60049999,
-              7: 60049999,
-              8: 60049999,
-              9: 60049999,
-              10: 60049999,
-              11: 60049999,
-              12: 60049999,
-              13: 60049999,
-              14: 60049999,
-              15: 60050003,
-              16: 60049998,
-              17: 60049998,
-              18: 60049998,
-              19: 60049998,
-            }"#]],
-    );
 }
 
 #[test]
-fn delegate_force_next_rewards_only() {
+fn transfer_undelegated_force_next() {
     let mut ctx = Context {
         starting_weights: Some(weights(20)),
         ..Default::default()
@@ -2116,18 +3366,26 @@ fn delegate_force_next_rewards_only() {
     while progress_fsm!(ctx).tx_msgs.is_some() {}
 
     ctx = ctx
-        .with_rewards_balance_report(1, 1_000_000)
+        .with_undelegated_balance_report(2, 1_000_000)
         .with_current_height(2);
 
-    let response = progress_fsm!(ctx);
+    // transfer in undelegated assets, which goes on to fail
+    let response = progress_fsm_no_tx!(ctx);
 
-    check(
-        response,
-        expect![[r#"
-            (
-              cmds: [
-                InflightDelegation((1000000)),
-                InflightRewardsReceivable((1000000)),
+    assert_eq!(
+        response.tx_msgs.unwrap().msgs,
+        vec![TxMsg::TransferInUndelegated(1_000_000)]
+    );
+
+    failure!(ctx);
+
+    let response = force_next!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
                 MsgIssuedCount((16)),
                 MsgSuccessCount((0)),
                 Phase(Delegate),
@@ -2136,60 +3394,222 @@ fn delegate_force_next_rewards_only() {
               events: [],
               tx_msgs: Some((
                 msgs: [
-                  Authz([
-                    SendRewardsReceivable((1000000)),
-                  ]),
-                  Delegate((0), 49999),
-                  Delegate((1), 50001),
-                  Delegate((2), 50000),
-                  Delegate((3), 50000),
-                  Delegate((4), 50000),
-                  Delegate((5), 50000),
-                  Delegate((6), 50000),
-                  Delegate((7), 50000),
-                  Delegate((8), 50000),
-                  Delegate((9), 50000),
-                  Delegate((10), 50000),
-                  Delegate((11), 50000),
-                  Delegate((12), 50000),
-                  Delegate((13), 50000),
-                  Delegate((14), 50000),
+                  WithdrawRewards((0)),
+                  WithdrawRewards((1)),
+                  WithdrawRewards((2)),
+                  WithdrawRewards((3)),
+                  WithdrawRewards((4)),
+                  WithdrawRewards((5)),
+                  WithdrawRewards((6)),
+                  WithdrawRewards((7)),
+                  WithdrawRewards((8)),
+                  WithdrawRewards((9)),
+                  WithdrawRewards((10)),
+                  WithdrawRewards((11)),
+                  WithdrawRewards((12)),
+                  WithdrawRewards((13)),
+                  WithdrawRewards((14)),
+                  WithdrawRewards((15)),
                 ],
               )),
-              tx_skip_count: 6,
+              tx_skip_count: 1,
+              abort_reason: None,
+              transitions_traversed: 2,
+              events_emitted: 0,
             )"#]],
     );
 
-    let response = fsm(&ctx, &ctx, &ctx).reconcile();
+    // complete the rest of the cycle
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    // the undelegated assets are transferred again next cycle
+    ctx = ctx
+        .with_undelegated_balance_report(3, 1_000_000)
+        .with_current_height(3);
 
-    for cmd in response.cmds.clone() {
-        ctx.handle_cmd(cmd);
+    let response = progress_fsm!(ctx);
+
+    assert_eq!(
+        response.tx_msgs.unwrap().msgs,
+        vec![TxMsg::TransferInUndelegated(1_000_000)]
+    );
+}
+
+#[test]
+fn transfer_pending_deposits_force_next() {
+    let mut ctx = Context {
+        starting_weights: Some(weights(20)),
+        ..Default::default()
     }
+    .with_pending_deposit(1_000_000_000);
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    ctx = ctx.with_current_height(2).with_pending_deposit(200_000_000);
+
+    // transfer out deposits, which goes on to fail
+    let response = progress_fsm_no_tx!(ctx);
+
+    assert_eq!(
+        response.tx_msgs.unwrap().msgs,
+        vec![TxMsg::TransferOutPendingDeposit(200_000_000)]
+    );
+
+    failure!(ctx);
+
+    let response = force_next!(ctx);
 
     check(
         response,
         expect![[r#"
             (
               cmds: [
-                MsgIssuedCount((5)),
-                MsgSuccessCount((16)),
+                InflightDeposit((0)),
+                MsgIssuedCount((16)),
+                MsgSuccessCount((0)),
                 Phase(Delegate),
                 State(Pending),
               ],
               events: [],
               tx_msgs: Some((
                 msgs: [
-                  Delegate((15), 50000),
-                  Delegate((16), 50000),
-                  Delegate((17), 50000),
-                  Delegate((18), 50000),
-                  Delegate((19), 50000),
+                  WithdrawRewards((0)),
+                  WithdrawRewards((1)),
+                  WithdrawRewards((2)),
+                  WithdrawRewards((3)),
+                  WithdrawRewards((4)),
+                  WithdrawRewards((5)),
+                  WithdrawRewards((6)),
+                  WithdrawRewards((7)),
+                  WithdrawRewards((8)),
+                  WithdrawRewards((9)),
+                  WithdrawRewards((10)),
+                  WithdrawRewards((11)),
+                  WithdrawRewards((12)),
+                  WithdrawRewards((13)),
+                  WithdrawRewards((14)),
+                  WithdrawRewards((15)),
+                ],
+              )),
+              tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 0,
+            )"#]],
+    );
+
+    assert_eq!(ctx.pending_deposit, Some(PendingDeposit(200_000_000)));
+    assert_eq!(ctx.inflight_deposit, Some(InflightDeposit(0)));
+
+    // complete the rest of the cycle
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    // the deposits are transferred again next cycle
+    ctx = ctx.with_current_height(3);
+
+    let response = progress_fsm!(ctx);
+
+    assert_eq!(
+        response.tx_msgs.unwrap().msgs,
+        vec![TxMsg::TransferOutPendingDeposit(200_000_000)]
+    );
+}
+
+#[test]
+fn delegate_force_next() {
+    let mut ctx = Context {
+        starting_weights: Some(weights(20)),
+        ..Default::default()
+    }
+    .with_pending_deposit(1_000_000_000);
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    ctx = ctx
+        .with_rewards_balance_report(1, 1_000_000)
+        .with_current_height(2)
+        .with_pending_deposit(200_000_000);
+
+    // transfer out deposits
+    let response = progress_fsm!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                InflightDeposit((200000000)),
+                MsgIssuedCount((1)),
+                MsgSuccessCount((0)),
+                Phase(TransferPendingDeposits),
+                State(Pending),
+              ],
+              events: [],
+              tx_msgs: Some((
+                msgs: [
+                  TransferOutPendingDeposit(200000000),
+                ],
+              )),
+              tx_skip_count: 5,
+              abort_reason: None,
+              transitions_traversed: 5,
+              events_emitted: 0,
+            )"#]],
+    );
+
+    let response = progress_fsm!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                InflightDelegation((201000000)),
+                InflightRewardsReceivable((1000000)),
+                MsgIssuedCount((16)),
+                MsgSuccessCount((0)),
+                PendingDeposit((0)),
+                Phase(Delegate),
+                State(Pending),
+              ],
+              events: [
+                DepositsTransferred(200000000),
+              ],
+              tx_msgs: Some((
+                msgs: [
+                  Authz([
+                    SendRewardsReceivable((1000000)),
+                  ]),
+                  Delegate((0), 10050000),
+                  Delegate((1), 10050000),
+                  Delegate((2), 10050000),
+                  Delegate((3), 10050000),
+                  Delegate((4), 10050000),
+                  Delegate((5), 10050000),
+                  Delegate((6), 10050000),
+                  Delegate((7), 10050000),
+                  Delegate((8), 10050000),
+                  Delegate((9), 10050000),
+                  Delegate((10), 10050000),
+                  Delegate((11), 10050000),
+                  Delegate((12), 10050000),
+                  Delegate((13), 10050000),
+                  Delegate((14), 10050000),
                 ],
               )),
               tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 2,
+              events_emitted: 1,
             )"#]],
     );
 
+    let response = fsm(&ctx, &ctx, &ctx).reconcile().unwrap();
+
+    for cmd in response.cmds.clone() {
+        ctx.handle_cmd(cmd);
+    }
+
     failure!(ctx);
 
     let response = force_next!(ctx);
@@ -2199,33 +3619,33 @@ fn delegate_force_next_rewards_only() {
         expect![[r#"
             (
               cmds: [
-                Delegated((1000750000)),
+                Delegated((1150750000)),
                 InflightDelegation((0)),
-                InflightDeposit((0)),
-                InflightRewardsReceivable((250000)),
+                InflightDeposit((50250000)),
+                InflightRewardsReceivable((0)),
                 InflightFeePayable((0)),
                 DelegateStartSlot((15)),
                 Weights(([
-                  (("0.05001250761928553584811391456407")),
-                  (("0.05001248963277541843617287034723")),
-                  (("0.05001248863352485635773170122408")),
-                  (("0.05001248863352485635773170122408")),
-                  (("0.05001248863352485635773170122408")),
-                  (("0.05001248863352485635773170122408")),
-                  (("0.05001248863352485635773170122408")),
-                  (("0.05001248863352485635773170122408")),
-                  (("0.05001248863352485635773170122408")),
-                  (("0.05001248863352485635773170122408")),
-                  (("0.05001248863352485635773170122408")),
-                  (("0.05001248863352485635773170122408")),
-                  (("0.05001248863352485635773170122408")),
-                  (("0.05001248863352485635773170122408")),
-                  (("0.05001248863352485635773170122408")),
-                  (("0.04996252610542093429927554334249")),
-                  (("0.04996252610542093429927554334249")),
-                  (("0.04996252610542093429927554334249")),
-                  (("0.04996252610542093429927554334249")),
-                  (("0.04996252610542093429927554334249")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.05218335781012383228329350423636")),
+                  (("0.04344992309363458613947425592005")),
+                  (("0.04344992309363458613947425592005")),
+                  (("0.04344992309363458613947425592005")),
+                  (("0.04344992309363458613947425592005")),
+                  (("0.04344992309363458613947425592005")),
                 ])),
                 MsgIssuedCount((0)),
                 MsgSuccessCount((0)),
@@ -2234,10 +3654,16 @@ fn delegate_force_next_rewards_only() {
                 State(Idle),
               ],
               events: [
-                DelegationsIncreased(750000),
+                DelegationsIncreased(150750000),
+                ReconcileCompleted(
+                  height: 2,
+                ),
               ],
               tx_msgs: None,
               tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 0,
+              events_emitted: 2,
             )"#]],
     );
 
@@ -2248,9 +3674,8 @@ fn delegate_force_next_rewards_only() {
         expect![[r#"
             (
               cmds: [
-                InflightDelegation((250000)),
-                InflightRewardsReceivable((250000)),
-                MsgIssuedCount((6)),
+                InflightDelegation((50250000)),
+                MsgIssuedCount((5)),
                 MsgSuccessCount((0)),
                 Phase(Delegate),
                 State(Pending),
@@ -2258,17 +3683,17 @@ fn delegate_force_next_rewards_only() {
               events: [],
               tx_msgs: Some((
                 msgs: [
-                  Authz([
-                    SendRewardsReceivable((250000)),
-                  ]),
-                  Delegate((15), 50004),
-                  Delegate((16), 49999),
-                  Delegate((17), 49999),
-                  Delegate((18), 49999),
-                  Delegate((19), 49999),
+                  Delegate((15), 10050000),
+                  Delegate((16), 10050000),
+                  Delegate((17), 10050000),
+                  Delegate((18), 10050000),
+                  Delegate((19), 10050000),
                 ],
               )),
               tx_skip_count: 6,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 0,
             )"#]],
     );
 
@@ -2280,7 +3705,7 @@ fn delegate_force_next_rewards_only() {
         expect![[r#"
             (
               cmds: [
-                Delegated((1001000000)),
+                Delegated((1201000000)),
                 DelegateStartSlot((0)),
                 InflightDelegation((0)),
                 InflightDeposit((0)),
@@ -2289,36 +3714,42 @@ fn delegate_force_next_rewards_only() {
                 MsgIssuedCount((0)),
                 MsgSuccessCount((0)),
                 Weights(([
-                  (("0.05000001598401598401598401598401")),
-                  (("0.04999999800199800199800199800199")),
-                  (("0.04999999700299700299700299700299")),
-                  (("0.04999999700299700299700299700299")),
-                  (("0.04999999700299700299700299700299")),
-                  (("0.04999999700299700299700299700299")),
-                  (("0.04999999700299700299700299700299")),
-                  (("0.04999999700299700299700299700299")),
-                  (("0.04999999700299700299700299700299")),
-                  (("0.04999999700299700299700299700299")),
-                  (("0.04999999700299700299700299700299")),
-                  (("0.04999999700299700299700299700299")),
-                  (("0.04999999700299700299700299700299")),
-                  (("0.04999999700299700299700299700299")),
-                  (("0.04999999700299700299700299700299")),
-                  (("0.050000000999000999000999000999")),
-                  (("0.04999999600399600399600399600399")),
-                  (("0.04999999600399600399600399600399")),
-                  (("0.04999999600399600399600399600399")),
-                  (("0.04999999600399600399600399600399")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
+                  (("0.04999999833472106577851790174854")),
                 ])),
                 LastReconcileHeight((2)),
                 Phase(StartReconcile),
                 State(Idle),
               ],
               events: [
-                DelegationsIncreased(250000),
+                DelegationsIncreased(50250000),
+                ReconcileCompleted(
+                  height: 2,
+                ),
               ],
               tx_msgs: None,
               tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 2,
             )"#]],
     );
 
@@ -2326,26 +3757,1369 @@ fn delegate_force_next_rewards_only() {
         ctx.delegations,
         expect![[r#"
             {
-              0: 50050018,
-              1: 50050000,
-              2: 50049999,
-              3: 50049999,
-              4: 50049999,
-              5: 50049999,
-              6: 50049999,
-              7: 50049999,
-              8: 50049999,
-              9: 50049999,
-              10: 50049999,
-              11: 50049999,
-              12: 50049999,
-              13: 50049999,
-              14: 50049999,
-              15: 50050003,
-              16: 50049998,
-              17: 50049998,
-              18: 50049998,
-              19: 50049998,
+              0: 60050000,
+              1: 60050000,
+              2: 60050000,
+              3: 60050000,
+              4: 60050000,
+              5: 60050000,
+              6: 60050000,
+              7: 60050000,
+              8: 60050000,
+              9: 60050000,
+              10: 60050000,
+              11: 60050000,
+              12: 60050000,
+              13: 60050000,
+              14: 60050000,
+              15: 60050000,
+              16: 60050000,
+              17: 60050000,
+              18: 60050000,
+              19: 60050000,
             }"#]],
     );
 }
+
+#[test]
+fn most_underweight_first_funds_lowest_weighted_slot_first() {
+    let bps = |bps: u32| Weight::checked_from_bps(bps).unwrap();
+
+    // the last slot is well below the rest, which fall outside the first batch in slot order
+    let mut uneven_weights = vec![bps(520); 20];
+    uneven_weights[19] = bps(120);
+
+    let delegate_batches = |delegation_order| {
+        let mut ctx = Context {
+            starting_weights: Some(weights(20)),
+            delegation_order,
+            ..Default::default()
+        }
+        .with_pending_deposit(1_000_000_000);
+
+        while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+        ctx.weights = Some(Weights::new(&uneven_weights).unwrap());
+
+        ctx = ctx.with_current_height(2).with_pending_deposit(200_000_000);
+
+        // transfer out deposits
+        progress_fsm!(ctx);
+
+        let mut batches = vec![];
+
+        while let Some(tx_msgs) = progress_fsm!(ctx).tx_msgs {
+            let slots: Vec<usize> = tx_msgs
+                .msgs
+                .into_iter()
+                .filter_map(|msg| match msg {
+                    TxMsg::Delegate(ValidatorSetSlot(slot), _) => Some(slot),
+                    _ => None,
+                })
+                .collect();
+
+            if slots.is_empty() {
+                break;
+            }
+
+            batches.push(slots);
+        }
+
+        batches
+    };
+
+    check(
+        delegate_batches(DelegationOrder::SlotIndex),
+        expect![[r#"
+            [
+              [
+                0,
+                1,
+                2,
+                3,
+                4,
+                5,
+                6,
+                7,
+                8,
+                9,
+                10,
+                11,
+                12,
+                13,
+                14,
+                15,
+              ],
+              [
+                16,
+                17,
+                18,
+                19,
+              ],
+            ]"#]],
+    );
+
+    check(
+        delegate_batches(DelegationOrder::MostUnderweightFirst),
+        expect![[r#"
+            [
+              [
+                19,
+                0,
+                1,
+                2,
+                3,
+                4,
+                5,
+                6,
+                7,
+                8,
+                9,
+                10,
+                11,
+                12,
+                13,
+                14,
+              ],
+              [
+                15,
+                16,
+                17,
+                18,
+              ],
+            ]"#]],
+    );
+}
+
+#[test]
+fn most_underweight_first_force_next_resumes_in_phase_order() {
+    let bps = |bps: u32| Weight::checked_from_bps(bps).unwrap();
+
+    let mut ctx = Context {
+        starting_weights: Some(weights(20)),
+        delegation_order: DelegationOrder::MostUnderweightFirst,
+        ..Default::default()
+    }
+    .with_pending_deposit(1_000_000_000);
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    // the last slot is well below the rest, so it is funded first
+    let mut uneven_weights = vec![bps(520); 20];
+    uneven_weights[19] = bps(120);
+
+    ctx.weights = Some(Weights::new(&uneven_weights).unwrap());
+
+    ctx = ctx.with_current_height(2).with_pending_deposit(200_000_000);
+
+    // transfer out deposits
+    progress_fsm!(ctx);
+
+    let delegated_slots = |response: &Response| -> Vec<usize> {
+        response
+            .tx_msgs
+            .iter()
+            .flat_map(|tx_msgs| &tx_msgs.msgs)
+            .filter_map(|msg| match msg {
+                TxMsg::Delegate(ValidatorSetSlot(slot), _) => Some(*slot),
+                _ => None,
+            })
+            .collect()
+    };
+
+    let first_batch = delegated_slots(&progress_fsm!(ctx));
+
+    check(
+        &first_batch,
+        expect![[r#"
+            [
+              19,
+              0,
+              1,
+              2,
+              3,
+              4,
+              5,
+              6,
+              7,
+              8,
+              9,
+              10,
+              11,
+              12,
+              13,
+              14,
+            ]"#]],
+    );
+
+    // the second batch is issued but fails
+    let response = fsm(&ctx, &ctx, &ctx).reconcile().unwrap();
+
+    for cmd in response.cmds.clone() {
+        ctx.handle_cmd(cmd);
+    }
+
+    failure!(ctx);
+
+    let response = force_next!(ctx);
+
+    // the start slot is the position after the first batch in the phase's order, not a slot index
+    assert!(response
+        .cmds
+        .contains(&Cmd::DelegateStartSlot(DelegateStartSlot(
+            first_batch.len()
+        ))));
+
+    // the first batch shifted the weights, but the remainder is delegated in the order the phase began in
+    let resumed_batch = delegated_slots(&progress_fsm!(ctx));
+
+    check(
+        &resumed_batch,
+        expect![[r#"
+            [
+              15,
+              16,
+              17,
+              18,
+            ]"#]],
+    );
+
+    let mut all_slots: Vec<_> = first_batch.into_iter().chain(resumed_batch).collect();
+
+    all_slots.sort();
+
+    assert_eq!(all_slots, (0..20).collect::<Vec<_>>());
+}
+
+#[test]
+fn delegate_force_next_rewards_only() {
+    let mut ctx = Context {
+        starting_weights: Some(weights(20)),
+        ..Default::default()
+    }
+    .with_pending_deposit(1_000_000_000);
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    ctx = ctx
+        .with_rewards_balance_report(1, 1_000_000)
+        .with_current_height(2);
+
+    let response = progress_fsm!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                InflightDelegation((1000000)),
+                InflightRewardsReceivable((1000000)),
+                MsgIssuedCount((16)),
+                MsgSuccessCount((0)),
+                Phase(Delegate),
+                State(Pending),
+              ],
+              events: [],
+              tx_msgs: Some((
+                msgs: [
+                  Authz([
+                    SendRewardsReceivable((1000000)),
+                  ]),
+                  Delegate((0), 50000),
+                  Delegate((1), 50000),
+                  Delegate((2), 50000),
+                  Delegate((3), 50000),
+                  Delegate((4), 50000),
+                  Delegate((5), 50000),
+                  Delegate((6), 50000),
+                  Delegate((7), 50000),
+                  Delegate((8), 50000),
+                  Delegate((9), 50000),
+                  Delegate((10), 50000),
+                  Delegate((11), 50000),
+                  Delegate((12), 50000),
+                  Delegate((13), 50000),
+                  Delegate((14), 50000),
+                ],
+              )),
+              tx_skip_count: 6,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 0,
+            )"#]],
+    );
+
+    let response = fsm(&ctx, &ctx, &ctx).reconcile().unwrap();
+
+    for cmd in response.cmds.clone() {
+        ctx.handle_cmd(cmd);
+    }
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                MsgIssuedCount((5)),
+                MsgSuccessCount((16)),
+                Phase(Delegate),
+                State(Pending),
+              ],
+              events: [],
+              tx_msgs: Some((
+                msgs: [
+                  Delegate((15), 50000),
+                  Delegate((16), 50000),
+                  Delegate((17), 50000),
+                  Delegate((18), 50000),
+                  Delegate((19), 50000),
+                ],
+              )),
+              tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 0,
+            )"#]],
+    );
+
+    failure!(ctx);
+
+    let response = force_next!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                Delegated((1000750000)),
+                InflightDelegation((0)),
+                InflightDeposit((0)),
+                InflightRewardsReceivable((250000)),
+                InflightFeePayable((0)),
+                DelegateStartSlot((15)),
+                Weights(([
+                  (("0.05001248963277541843617287034723")),
+                  (("0.05001248963277541843617287034723")),
+                  (("0.05001248963277541843617287034723")),
+                  (("0.05001248963277541843617287034723")),
+                  (("0.05001248963277541843617287034723")),
+                  (("0.05001248963277541843617287034723")),
+                  (("0.05001248963277541843617287034723")),
+                  (("0.05001248963277541843617287034723")),
+                  (("0.05001248963277541843617287034723")),
+                  (("0.05001248963277541843617287034723")),
+                  (("0.05001248963277541843617287034723")),
+                  (("0.05001248963277541843617287034723")),
+                  (("0.05001248963277541843617287034723")),
+                  (("0.05001248963277541843617287034723")),
+                  (("0.05001248963277541843617287034723")),
+                  (("0.04996252710467149637771671246565")),
+                  (("0.04996252710467149637771671246565")),
+                  (("0.04996252710467149637771671246565")),
+                  (("0.04996252710467149637771671246565")),
+                  (("0.04996252710467149637771671246565")),
+                ])),
+                MsgIssuedCount((0)),
+                MsgSuccessCount((0)),
+                LastReconcileHeight((2)),
+                Phase(StartReconcile),
+                State(Idle),
+              ],
+              events: [
+                DelegationsIncreased(750000),
+                ReconcileCompleted(
+                  height: 2,
+                ),
+              ],
+              tx_msgs: None,
+              tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 0,
+              events_emitted: 2,
+            )"#]],
+    );
+
+    let response = progress_fsm!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                InflightDelegation((250000)),
+                InflightRewardsReceivable((250000)),
+                MsgIssuedCount((6)),
+                MsgSuccessCount((0)),
+                Phase(Delegate),
+                State(Pending),
+              ],
+              events: [],
+              tx_msgs: Some((
+                msgs: [
+                  Authz([
+                    SendRewardsReceivable((250000)),
+                  ]),
+                  Delegate((15), 50000),
+                  Delegate((16), 50000),
+                  Delegate((17), 50000),
+                  Delegate((18), 50000),
+                  Delegate((19), 50000),
+                ],
+              )),
+              tx_skip_count: 6,
+              abort_reason: None,
+              transitions_traversed: 6,
+              events_emitted: 0,
+            )"#]],
+    );
+
+    // resume delegation
+    let response = progress_fsm!(ctx);
+
+    check(
+        response,
+        expect![[r#"
+            (
+              cmds: [
+                Delegated((1001000000)),
+                DelegateStartSlot((0)),
+                InflightDelegation((0)),
+                InflightDeposit((0)),
+                InflightFeePayable((0)),
+                InflightRewardsReceivable((0)),
+                MsgIssuedCount((0)),
+                MsgSuccessCount((0)),
+                Weights(([
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                  (("0.04999999800199800199800199800199")),
+                ])),
+                LastReconcileHeight((2)),
+                Phase(StartReconcile),
+                State(Idle),
+              ],
+              events: [
+                DelegationsIncreased(250000),
+                ReconcileCompleted(
+                  height: 2,
+                ),
+              ],
+              tx_msgs: None,
+              tx_skip_count: 0,
+              abort_reason: None,
+              transitions_traversed: 1,
+              events_emitted: 2,
+            )"#]],
+    );
+
+    check(
+        ctx.delegations,
+        expect![[r#"
+            {
+              0: 50050000,
+              1: 50050000,
+              2: 50050000,
+              3: 50050000,
+              4: 50050000,
+              5: 50050000,
+              6: 50050000,
+              7: 50050000,
+              8: 50050000,
+              9: 50050000,
+              10: 50050000,
+              11: 50050000,
+              12: 50050000,
+              13: 50050000,
+              14: 50050000,
+              15: 50050000,
+              16: 50050000,
+              17: 50050000,
+              18: 50050000,
+              19: 50050000,
+            }"#]],
+    );
+}
+
+#[test]
+fn malformed_weights_err() {
+    let bps = |bps: u32| Weight::checked_from_bps(bps).unwrap();
+
+    let ctx = Context {
+        phase: Some(Phase::StartReconcile),
+        state: Some(State::Idle),
+        weights: Some(Weights::new_unchecked(vec![])),
+        ..Default::default()
+    };
+
+    let err = fsm(&ctx, &ctx, &ctx).reconcile().err().unwrap();
+
+    assert_eq!(err, ReconcileError::EmptyWeights);
+
+    let ctx = Context {
+        phase: Some(Phase::StartReconcile),
+        state: Some(State::Idle),
+        weights: Some(Weights::new_unchecked(vec![bps(6_000); 5])),
+        ..Default::default()
+    };
+
+    let err = fsm(&ctx, &ctx, &ctx).reconcile().err().unwrap();
+
+    assert_eq!(err, ReconcileError::WeightsExceedOne);
+
+    let ctx = Context {
+        phase: Some(Phase::StartReconcile),
+        state: Some(State::Idle),
+        weights: Some(Weights::new_unchecked(vec![bps(5_000); 2])),
+        ..Default::default()
+    };
+
+    let err = fsm(&ctx, &ctx, &ctx).reconcile().err().unwrap();
+
+    check(
+        err.to_string(),
+        expect![[r#""invalid validator slot weights: expected 5 slot weights, got 2""#]],
+    );
+
+    let ctx = Context {
+        phase: Some(Phase::Undelegate),
+        state: Some(State::Failed),
+        weights: Some(Weights::new_unchecked(vec![bps(6_000); 5])),
+        ..Default::default()
+    };
+
+    let err = fsm(&ctx, &ctx, &ctx).force_next().err().unwrap();
+
+    assert_eq!(err, ReconcileError::WeightsExceedOne);
+}
+
+#[test]
+fn delegate_start_slot_out_of_range_errs() {
+    let mut ctx = Context {
+        phase: Some(Phase::StartReconcile),
+        state: Some(State::Idle),
+        last_reconcile_height: Some(LastReconcileHeight(0)),
+        pending_deposit: Some(PendingDeposit(500_000)),
+        delegate_start_slot: Some(DelegateStartSlot(7)),
+        ..Default::default()
+    };
+
+    // transfer pending deposits
+    progress_fsm!(ctx);
+
+    let err = fsm(&ctx, &ctx, &ctx).reconcile().err().unwrap();
+
+    check(
+        err.to_string(),
+        expect![[r#""validator slot 7 out of range for 5 slots""#]],
+    );
+}
+
+#[test]
+fn checked_quantity_arithmetic() {
+    assert_eq!(PendingDeposit(100).checked_add(50), Ok(PendingDeposit(150)));
+    assert_eq!(Delegated(100).checked_sub(100), Ok(Delegated(0)));
+    assert_eq!(InflightUnbond(100).saturating_sub(150), InflightUnbond(0));
+    assert_eq!(
+        InflightDeposit::from(PendingDeposit(10)),
+        InflightDeposit(10)
+    );
+    assert_eq!(
+        InflightDelegation::from(InflightDeposit(10)),
+        InflightDelegation(10)
+    );
+
+    check(
+        Delegated(u128::MAX).checked_add(1).unwrap_err().to_string(),
+        expect![[r#""Delegated of 340282366920938463463374607431768211455 overflows adding 1""#]],
+    );
+
+    check(
+        PendingUnbond(100).checked_sub(150).unwrap_err().to_string(),
+        expect![[r#""PendingUnbond of 100 is less than the 150 subtracted""#]],
+    );
+}
+
+#[test]
+fn inconsistent_unbond_state_errs() {
+    let mut ctx = Context {
+        starting_weights: Some(weights(20)),
+        ..Default::default()
+    }
+    .with_pending_deposit(1_000_000_000);
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    ctx = ctx.with_pending_unbond(500_000_000);
+
+    // first undelegate batch
+    progress_fsm!(ctx);
+
+    // second undelegate batch
+    let response = fsm(&ctx, &ctx, &ctx).reconcile().unwrap();
+
+    for cmd in response.cmds {
+        ctx.handle_cmd(cmd);
+    }
+
+    // less is pending than is inflight
+    ctx.pending_unbond = Some(PendingUnbond(1));
+
+    let err = fsm(&ctx, &ctx, &ctx).reconcile().err().unwrap();
+
+    check(
+        err.to_string(),
+        expect![[
+            r#""inconsistent reconcile state: PendingUnbond of 1 is less than the 500000000 subtracted""#
+        ]],
+    );
+
+    failure!(ctx);
+
+    let err = fsm(&ctx, &ctx, &ctx).force_next().err().unwrap();
+
+    check(
+        err.to_string(),
+        expect![[
+            r#""inconsistent reconcile state: PendingUnbond of 1 is less than the 399999984 subtracted""#
+        ]],
+    );
+}
+
+#[test]
+fn inconsistent_deposit_state_errs() {
+    let mut ctx = Context {
+        starting_weights: Some(weights(20)),
+        ..Default::default()
+    }
+    .with_pending_deposit(1_000_000_000);
+
+    while progress_fsm!(ctx).tx_msgs.is_some() {}
+
+    ctx = ctx.with_current_height(2).with_pending_deposit(200_000_000);
+
+    // transfer out deposits
+    progress_fsm_no_tx!(ctx);
+
+    assert_eq!(ctx.phase, Some(Phase::TransferPendingDeposits));
+
+    // less is pending than was transferred out
+    ctx.pending_deposit = Some(PendingDeposit(1));
+
+    let err = fsm(&ctx, &ctx, &ctx).reconcile().err().unwrap();
+
+    assert_eq!(
+        err,
+        ReconcileError::Arithmetic(ArithmeticError::Underflow {
+            quantity: "PendingDeposit",
+            lhs: 1,
+            rhs: 200_000_000,
+        })
+    );
+
+    ctx.pending_deposit = Some(PendingDeposit(200_000_000));
+
+    // first delegate batch
+    progress_fsm!(ctx);
+
+    // second delegate batch
+    let response = fsm(&ctx, &ctx, &ctx).reconcile().unwrap();
+
+    for cmd in response.cmds {
+        ctx.handle_cmd(cmd);
+    }
+
+    assert_eq!(ctx.phase, Some(Phase::Delegate));
+
+    failure!(ctx);
+
+    // less is inflight than was delegated by the successful batch
+    ctx.inflight_deposit = Some(InflightDeposit(1));
+
+    let err = fsm(&ctx, &ctx, &ctx).force_next().err().unwrap();
+
+    check(
+        err.to_string(),
+        expect![[
+            r#""inconsistent reconcile state: InflightDeposit of 1 is less than the 160000000 subtracted""#
+        ]],
+    );
+}
+
+#[test]
+fn weight_apply_rounding() {
+    let third = Weight::checked_from_fraction(1, 3).unwrap();
+    let two_thirds = Weight::checked_from_fraction(2, 3).unwrap();
+    let half = Weight::checked_from_bps(5_000u32).unwrap();
+    let one = Weight::checked_from_bps(10_000u32).unwrap();
+
+    for (weight, amount, floor, ceil, half_even) in [
+        (third, 100, 33, 34, 33),
+        (two_thirds, 100, 66, 67, 67),
+        (half, 101, 50, 51, 50),
+        (half, 103, 51, 52, 52),
+        (half, 100, 50, 50, 50),
+        (one, 100, 100, 100, 100),
+        (Weight::zero(), 100, 0, 0, 0),
+        (third, 0, 0, 0, 0),
+    ] {
+        assert_eq!(weight.apply(amount), floor);
+        assert_eq!(weight.apply_ceil(amount), ceil);
+        assert_eq!(weight.apply_round(amount), half_even);
+    }
+}
+
+#[test]
+fn delegate_phase_msgs_send_rewards_then_delegate_then_send_fee() {
+    let slots = DelegationSlots {
+        slots: vec![0, 1, 2],
+        weights: weights(3).as_slice().to_vec(),
+        target: None,
+    };
+
+    let msgs: Vec<_> = delegate_phase_msgs(
+        &slots,
+        DelegatePhaseBalances {
+            delegation: InflightDelegation(1_000),
+            rewards_receivable: InflightRewardsReceivable(100),
+            fee_payable: InflightFeePayable(10),
+        },
+        Some("fee_recipient".to_owned()),
+    )
+    .unwrap()
+    .collect();
+
+    let (first, rest) = msgs.split_first().unwrap();
+
+    let (last, delegate_msgs) = rest.split_last().unwrap();
+
+    assert_eq!(
+        first,
+        &TxMsg::Authz(vec![AuthzMsg::SendRewardsReceivable(RewardsReceivable(
+            100
+        ))])
+    );
+
+    assert_eq!(delegate_msgs.len(), 3);
+
+    assert!(delegate_msgs
+        .iter()
+        .all(|msg| matches!(msg, TxMsg::Delegate(..))));
+
+    assert_eq!(
+        last,
+        &TxMsg::Authz(vec![AuthzMsg::SendFee(
+            "fee_recipient".to_owned(),
+            ReconcilerFee(10)
+        )])
+    );
+}
+
+#[test]
+fn delegation_shares_sum_to_total_within_one_unit_of_exact() {
+    for n_slots in 1..=12 {
+        let weights = weights(n_slots);
+
+        for total in [0, 1, 2, 7, 99, 1_000, 123_456_789] {
+            let delegations: Vec<_> = distribute_delegations(
+                weights.as_slice(),
+                None,
+                total,
+                (0..n_slots as usize).collect(),
+            )
+            .unwrap()
+            .collect();
+
+            let delegated: u128 = delegations.iter().map(|(_, amount)| amount.get()).sum();
+
+            assert_eq!(delegated, total, "{n_slots} slots");
+
+            let scaled_weights = rebalance_weights(normalize_weights(weights.as_slice()).unwrap());
+
+            for (ValidatorSetSlot(idx), amount) in delegations {
+                let exact = scaled_weights.as_slice()[idx];
+
+                assert!(
+                    (exact.apply(total)..=exact.apply_ceil(total)).contains(&amount.get()),
+                    "{n_slots} slots: slot {idx} delegated {amount} of {total}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn undelegation_totals_by_rounding_mode() {
+    let weights = weights(3);
+
+    let unbond_amount = 1_000;
+
+    let total_with = |apply: fn(Weight, u128) -> u128| -> u128 {
+        weights
+            .as_slice()
+            .iter()
+            .map(|w| apply(*w, unbond_amount))
+            .sum()
+    };
+
+    // flooring every share leaves the unbond amount short
+    assert_eq!(total_with(Weight::apply), 999);
+    assert_eq!(total_with(Weight::apply_round), 999);
+    assert_eq!(total_with(Weight::apply_ceil), 1_002);
+
+    let undelegated: u128 =
+        distribute_undelegations(weights.as_slice(), Delegated(1_000_000), unbond_amount, 0)
+            .unwrap()
+            .map(|(_, amount)| amount.get())
+            .sum();
+
+    assert_eq!(undelegated, unbond_amount);
+
+    // shares are still limited by the slot delegations
+    let undelegated: u128 =
+        distribute_undelegations(weights.as_slice(), Delegated(999), unbond_amount, 0)
+            .unwrap()
+            .map(|(_, amount)| amount.get())
+            .sum();
+
+    assert_eq!(undelegated, 997);
+}
+
+#[test]
+fn undelegation_shares_sum_to_unbond_amount() {
+    for n_slots in 1..=12 {
+        let weights = weights(n_slots);
+
+        for unbond_amount in [0, 1, 2, 7, 99, 1_000, 123_456_789] {
+            let undelegated: u128 = distribute_undelegations(
+                weights.as_slice(),
+                Delegated(u128::from(u64::MAX)),
+                unbond_amount,
+                0,
+            )
+            .unwrap()
+            .map(|(_, amount)| amount.get())
+            .sum();
+
+            assert_eq!(undelegated, unbond_amount, "{n_slots} slots");
+        }
+    }
+}
+
+#[test]
+fn weights_approx_eq() {
+    let one_ulp = FixedU256::raw(U256::one());
+
+    let offset_weights = |offset: FixedU256| {
+        let [first, second]: [Weight; 2] = weights(2).as_slice().try_into().unwrap();
+
+        Weights::new_unchecked(vec![
+            Weight::checked_from_fixed(first.into_fixed().checked_sub(offset).unwrap()).unwrap(),
+            Weight::checked_from_fixed(second.into_fixed().checked_add(offset).unwrap()).unwrap(),
+        ])
+    };
+
+    // exactly equal
+    assert!(weights(2).approx_eq(&weights(2), FixedU256::zero()));
+
+    // within epsilon
+    assert!(offset_weights(one_ulp).approx_eq(&weights(2), one_ulp));
+    assert!(!offset_weights(one_ulp).approx_eq(&weights(2), FixedU256::zero()));
+
+    // beyond epsilon
+    assert!(
+        !offset_weights(WEIGHTS_EPSILON.checked_add(one_ulp).unwrap())
+            .approx_eq(&weights(2), WEIGHTS_EPSILON)
+    );
+
+    // different slot counts are never equal
+    assert!(!weights(2).approx_eq(&weights(3), FixedU256::from_u128(1)));
+}
+
+#[test]
+fn weights_new_errs() {
+    let bps = |bps: u32| Weight::checked_from_bps(bps).unwrap();
+
+    assert_eq!(Weights::new(&[]).unwrap_err(), WeightsError::Empty);
+
+    assert_eq!(
+        Weights::new(&[bps(6_000), bps(6_000)]).unwrap_err(),
+        WeightsError::SumNotOne {
+            total: bps(6_000)
+                .into_fixed()
+                .checked_add(bps(6_000).into_fixed())
+                .unwrap()
+        }
+    );
+
+    check(
+        Weights::new(&[bps(6_000), bps(6_000)])
+            .unwrap_err()
+            .to_string(),
+        expect![[r#""slot weights add up to 1.19999999999999999999999999999999, not 1.0""#]],
+    );
+
+    assert_eq!(
+        weights(2).ensure_slot_count(3).unwrap_err(),
+        WeightsError::SlotCountMismatch {
+            expected: 3,
+            actual: 2
+        }
+    );
+
+    assert!(weights(3).ensure_slot_count(3).is_ok());
+}
+
+#[test]
+fn weights_new_lossy_falls_back() {
+    let bps = |bps: u32| Weight::checked_from_bps(bps).unwrap();
+
+    let (lossy, err) = Weights::new_lossy(&[bps(4_000), bps(6_000)], || weights(2));
+
+    assert_eq!(lossy.as_slice(), &[bps(4_000), bps(6_000)]);
+    assert_eq!(err, None);
+
+    let (lossy, err) = Weights::new_lossy(&[], || weights(2));
+
+    assert_eq!(lossy, weights(2));
+    assert_eq!(err, Some(WeightsError::Empty));
+}
+
+#[test]
+fn undelegate_adjust_weights_resets_invalid_weights() {
+    let bps = |bps: u32| Weight::checked_from_bps(bps).unwrap();
+
+    let current = Weights::new(&[bps(4_000), bps(6_000)]).unwrap();
+
+    // nothing left delegated, the starting weights are intended
+    let (adjusted, err) =
+        undelegate_adjust_weights(&current, 1_000, 0, std::iter::empty(), || weights(2));
+
+    assert_eq!(adjusted, weights(2));
+    assert_eq!(err, None);
+
+    // less delegated than accounted for, the adjusted weights exceed 1.0
+    let (adjusted, err) =
+        undelegate_adjust_weights(&current, 1_000, 700, std::iter::empty(), || weights(2));
+
+    assert_eq!(adjusted, weights(2));
+
+    check(
+        err.unwrap().to_string(),
+        expect![[r#""slot weights add up to 1.42571428571428571428571428571428, not 1.0""#]],
+    );
+}
+
+#[test]
+fn remove_slot_redistributes_weight() {
+    let bps = |bps: u32| Weight::checked_from_bps(bps).unwrap();
+
+    let weights = Weights::new(&[bps(2_000), bps(5_000), bps(3_000)]).unwrap();
+
+    let removed = weights.remove_slot(1).unwrap();
+
+    check(
+        &removed,
+        expect![[r#"
+            ([
+              (("0.39999999999999999999999999999999")),
+              (("0.59999999999999999999999999999999")),
+            ])"#]],
+    );
+
+    assert!(removed.approx_eq(
+        &Weights::new(&[bps(4_000), bps(6_000)]).unwrap(),
+        WEIGHTS_EPSILON
+    ));
+
+    // the redistributed weights never exceed 1.0
+    assert!(Weights::new(removed.as_slice()).is_ok());
+}
+
+#[test]
+fn remove_slot_with_no_remaining_weight_splits_equally() {
+    let bps = |bps: u32| Weight::checked_from_bps(bps).unwrap();
+
+    let weights = Weights::new(&[bps(0), bps(10_000), bps(0)]).unwrap();
+
+    assert_eq!(
+        weights.remove_slot(1).unwrap(),
+        Weights::new(&[bps(5_000), bps(5_000)]).unwrap()
+    );
+}
+
+#[test]
+fn remove_only_or_out_of_range_slot_is_none() {
+    assert_eq!(weights(1).remove_slot(0), None);
+    assert_eq!(weights(3).remove_slot(3), None);
+}
+
+#[test]
+fn adjusted_weights_within_epsilon_are_not_written() {
+    let one_ulp = FixedU256::raw(U256::one());
+
+    let current = weights(2);
+
+    let [first, second]: [Weight; 2] = current.as_slice().try_into().unwrap();
+
+    let nudged = Weights::new_unchecked(vec![
+        Weight::checked_from_fixed(first.into_fixed().checked_sub(one_ulp).unwrap()).unwrap(),
+        Weight::checked_from_fixed(second.into_fixed().checked_add(one_ulp).unwrap()).unwrap(),
+    ]);
+
+    let mut cmds = vec![];
+
+    push_adjusted_weights(&mut cmds, &current, nudged);
+
+    assert!(cmds.is_empty());
+
+    push_adjusted_weights(&mut cmds, &current, weights(2));
+
+    assert!(cmds.is_empty());
+
+    let rebalanced = Weights::new(&[
+        Weight::checked_from_bps(4_000u32).unwrap(),
+        Weight::checked_from_bps(6_000u32).unwrap(),
+    ])
+    .unwrap();
+
+    push_adjusted_weights(&mut cmds, &current, rebalanced.clone());
+
+    assert_eq!(cmds, vec![Cmd::Weights(rebalanced)]);
+}
+
+#[test]
+fn intermediate_repo_borrows_cached_weights() {
+    let ctx = Context {
+        starting_weights: Some(weights(50)),
+        ..Default::default()
+    };
+
+    let counting_repo = CloneCountingRepo::new(&ctx);
+
+    let mut intermediate_repo = IntermediateRepo {
+        repo: &counting_repo,
+        cache: Cache::default(),
+    };
+
+    // uncached weights are read through to the underlying repository
+    assert!(matches!(intermediate_repo.weights(), Cow::Owned(_)));
+    assert_eq!(counting_repo.weights_cloned.get(), 1);
+
+    intermediate_repo.handle_cmd(weights(50).into());
+
+    for _ in 0..100 {
+        assert!(matches!(intermediate_repo.weights(), Cow::Borrowed(_)));
+    }
+
+    assert_eq!(counting_repo.weights_cloned.get(), 1);
+}
+
+#[test]
+fn large_validator_set_reconcile_weights_clones() {
+    let mut ctx = Context {
+        starting_weights: Some(weights(50)),
+        ..Default::default()
+    }
+    .with_pending_deposit(1_000_000);
+
+    let mut weights_cloned = vec![];
+
+    loop {
+        let counting_repo = CloneCountingRepo::new(&ctx);
+
+        let response = fsm(&ctx, &counting_repo, &ctx).reconcile().unwrap();
+
+        weights_cloned.push(counting_repo.weights_cloned.get());
+
+        for cmd in response.cmds {
+            ctx.handle_cmd(cmd);
+        }
+
+        let Some(tx_msgs) = response.tx_msgs else {
+            break;
+        };
+
+        for tx_msg in tx_msgs.msgs {
+            ctx.handle_tx_msg(tx_msg);
+        }
+    }
+
+    // only reads of the stored weights clone them, weights cached within a round are borrowed
+    check(
+        weights_cloned,
+        expect![[r#"
+        [
+          1,
+          1,
+          1,
+          2,
+          2,
+          2,
+          2,
+          3,
+        ]"#]],
+    );
+}
+
+#[test]
+fn resize_weights_renormalizes_retained_slots() {
+    let bps = |bps: u32| Weight::checked_from_bps(bps).unwrap();
+
+    let weights = Weights::new(&[bps(2_000), bps(5_000), bps(3_000)]).unwrap();
+
+    // grow: the new slot starts with zero weight
+    assert_eq!(
+        weights.resize(&[Some(0), Some(1), Some(2), None]).unwrap(),
+        weights
+            .resize(&[Some(0), Some(1), Some(2)])
+            .unwrap()
+            .resize(&[Some(0), Some(1), Some(2), None])
+            .unwrap(),
+    );
+
+    check(
+        weights.resize(&[Some(0), Some(1), Some(2), None]).unwrap(),
+        expect![[r#"
+            ([
+              (("0.19999999999999999999999999999999")),
+              (("0.5")),
+              (("0.29999999999999999999999999999999")),
+              (("0.0")),
+            ])"#]],
+    );
+
+    // shrink: the removed slot weight is redistributed
+    check(
+        weights.resize(&[Some(2), Some(0)]).unwrap(),
+        expect![[r#"
+        ([
+          (("0.59999999999999999999999999999999")),
+          (("0.39999999999999999999999999999999")),
+        ])"#]],
+    );
+
+    // no retained weight splits equally
+    assert_eq!(
+        weights.resize(&[None, None]).unwrap(),
+        Weights::new(&[bps(5_000), bps(5_000)]).unwrap()
+    );
+
+    assert_eq!(weights.resize(&[]), None);
+    assert_eq!(weights.resize(&[Some(3)]), None);
+    assert_eq!(weights.resize(&[Some(1), Some(1)]), None);
+}
+
+fn resize_ctx(ctx: &mut Context, slot_map: &[Option<usize>]) {
+    let cmds = resize_validator_set(ctx, slot_map).unwrap();
+
+    for cmd in cmds {
+        ctx.handle_cmd(cmd);
+    }
+
+    // the storage backed config follows the resized set
+    ctx.starting_weights = ctx.starting_weights().resize(slot_map);
+    ctx.target_weights = ctx.target_weights.as_ref().and_then(|w| w.resize(slot_map));
+
+    let delegations = std::mem::take(&mut ctx.delegations);
+
+    for (new_slot, old_slot) in slot_map.iter().enumerate() {
+        if let Some(amount) = old_slot.and_then(|old_slot| delegations.get(&old_slot)) {
+            ctx.delegations.insert(new_slot, *amount);
+        }
+    }
+}
+
+fn delegate_deposit(mut ctx: Context, amount: u128) -> (Context, Vec<TxMsg>) {
+    ctx.pending_deposit = Some(PendingDeposit(amount));
+
+    // transfer pending deposits
+    progress_fsm!(ctx);
+    // delegate
+    let msgs = progress_fsm!(ctx).tx_msgs.unwrap().msgs;
+    // delegations succeeded
+    progress_fsm!(ctx);
+
+    (ctx, msgs)
+}
+
+#[test]
+fn delegation_distribution_after_validator_set_grow() {
+    let mut ctx = Context {
+        starting_weights: Some(weights(3)),
+        phase: Some(Phase::StartReconcile),
+        state: Some(State::Idle),
+        last_reconcile_height: Some(LastReconcileHeight(0)),
+        ..Default::default()
+    };
+
+    (ctx, _) = delegate_deposit(ctx, 300_000);
+
+    resize_ctx(&mut ctx, &[Some(0), Some(1), Some(2), None]);
+
+    assert_eq!(ctx.validator_set_size(), ValidatorSetSize(4));
+
+    // without a target the new slot keeps its zero weight
+    let msgs;
+
+    (ctx, msgs) = delegate_deposit(ctx, 300_000);
+
+    check(
+        msgs,
+        expect![[r#"
+        [
+          Delegate((0), 100020),
+          Delegate((1), 99990),
+          Delegate((2), 99990),
+        ]"#]],
+    );
+
+    ctx.target_weights = Some(weights(4));
+
+    // with a target the new slot catches up
+    let msgs;
+
+    (ctx, msgs) = delegate_deposit(ctx, 400_000);
+
+    check(
+        msgs,
+        expect![[r#"
+        [
+          Delegate((0), 50000),
+          Delegate((1), 50000),
+          Delegate((2), 50000),
+          Delegate((3), 250000),
+        ]"#]],
+    );
+
+    check(
+        &ctx.delegations,
+        expect![[r#"
+        {
+          0: 250000,
+          1: 250000,
+          2: 250000,
+          3: 250000,
+        }"#]],
+    );
+}
+
+#[test]
+fn delegation_distribution_after_validator_set_shrink() {
+    let bps = |bps: u32| Weight::checked_from_bps(bps).unwrap();
+
+    let mut ctx = Context {
+        starting_weights: Some(weights(3)),
+        phase: Some(Phase::StartReconcile),
+        state: Some(State::Idle),
+        last_reconcile_height: Some(LastReconcileHeight(0)),
+        ..Default::default()
+    };
+
+    (ctx, _) = delegate_deposit(ctx, 300_000);
+
+    // the middle slot is still delegated to
+    assert_eq!(
+        resize_validator_set(&ctx, &[Some(0), Some(2)]),
+        Err(ResizeError::RemovedSlotDelegated(1))
+    );
+
+    // move everything off the middle slot
+    ctx.target_weights = Weights::new(&[bps(5_000), bps(0), bps(5_000)]).ok();
+    ctx.weights = Weights::new(&[bps(5_000), bps(0), bps(5_000)]).ok();
+    ctx.delegations = BTreeMap::from([(0, 150_000), (2, 150_000)]);
+    ctx.undelegate_start_slot = Some(UndelegateStartSlot(2));
+
+    resize_ctx(&mut ctx, &[Some(0), Some(2)]);
+
+    assert_eq!(ctx.validator_set_size(), ValidatorSetSize(2));
+    // a start slot beyond the resized set starts over
+    assert_eq!(ctx.undelegate_start_slot, Some(UndelegateStartSlot(0)));
+
+    let msgs;
+
+    (ctx, msgs) = delegate_deposit(ctx, 100_001);
+
+    check(
+        msgs,
+        expect![[r#"
+        [
+          Delegate((0), 50001),
+          Delegate((1), 50000),
+        ]"#]],
+    );
+
+    check(
+        &ctx.delegations,
+        expect![[r#"
+        {
+          0: 200001,
+          1: 200000,
+        }"#]],
+    );
+}
+
+#[test]
+fn resize_validator_set_mid_cycle_errs() {
+    let ctx = Context {
+        phase: Some(Phase::Delegate),
+        state: Some(State::Pending),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        resize_validator_set(&ctx, &[Some(0)]),
+        Err(ResizeError::ReconcileInProgress)
+    );
+
+    let ctx = Context {
+        phase: Some(Phase::StartReconcile),
+        state: Some(State::Idle),
+        inflight_unbond: Some(InflightUnbond(100)),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        resize_validator_set(&ctx, &[Some(0)]),
+        Err(ResizeError::ReconcileInProgress)
+    );
+
+    let ctx = Context {
+        phase: Some(Phase::StartReconcile),
+        state: Some(State::Idle),
+        redelegation_slot: Some(RedelegationSlot(ValidatorSetSlot(0))),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        resize_validator_set(&ctx, &[Some(0)]),
+        Err(ResizeError::RedelegationInProgress)
+    );
+}
+
+#[test]
+fn timestamp_duration_arithmetic() {
+    let start = Timestamp(1_000);
+    let period = DurationSecs(600);
+
+    assert_eq!(start.plus(period), Timestamp(1_600));
+    assert_eq!(start.plus(period).saturating_since(start), period);
+    assert_eq!(start.saturating_since(start.plus(period)), DurationSecs(0));
+    assert_eq!(period.plus(DurationSecs(5)).times(2), DurationSecs(1_210));
+}
@@ -1,16 +1,84 @@
 use std::{collections::HashMap, num::NonZeroU128};
 
-use num::{FixedU256, U256};
+use num::{FixedU256, Rounding, U256};
 
 pub type Denom = String;
 pub type FeeRecipient = String;
 pub type StakeDenom = String;
 pub type Validator = String;
 pub type Account = String;
+pub type MsgTypeUrl = String;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
 pub struct CurrentHeight(pub u64);
 
+/// A point in time, in seconds since the unix epoch
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    derive_more::Display,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(transparent)]
+pub struct Timestamp(pub u64);
+
+impl Timestamp {
+    /// The timestamp `duration` after this one
+    pub fn plus(self, duration: DurationSecs) -> Timestamp {
+        self.0
+            .checked_add(duration.0)
+            .map(Timestamp)
+            .expect("always: timestamps are far from overflowing")
+    }
+
+    /// The time elapsed since an `earlier` timestamp, zero if it is not earlier
+    pub fn saturating_since(self, earlier: Timestamp) -> DurationSecs {
+        DurationSecs(self.0.saturating_sub(earlier.0))
+    }
+}
+
+/// A length of time, in seconds
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    derive_more::Display,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(transparent)]
+pub struct DurationSecs(pub u64);
+
+impl DurationSecs {
+    /// The sum of both durations
+    pub fn plus(self, rhs: DurationSecs) -> DurationSecs {
+        self.0
+            .checked_add(rhs.0)
+            .map(DurationSecs)
+            .expect("always: durations are far from overflowing")
+    }
+
+    /// The duration repeated `n` times
+    pub fn times(self, n: u64) -> DurationSecs {
+        self.0
+            .checked_mul(n)
+            .map(DurationSecs)
+            .expect("always: durations are far from overflowing")
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct Delegated(pub u128);
@@ -19,12 +87,52 @@ pub struct Delegated(pub u128);
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct DelegateStartSlot(pub usize);
 
+/// The slots in the order the current delegate phase funds them, fixed when the phase starts so that
+/// the `DelegateStartSlot` position of a partial delegation resumes within the same order.
+/// Empty when the slots are funded in ascending slot index order.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct DelegateSlotOrder(pub Vec<ValidatorSetSlot>);
+
+/// The max difference between the recorded delegated amount and a delegations report that is
+/// treated as rounding drift and silently corrected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub struct DelegationDriftTolerance(pub u128);
+
+/// The order in which validator slots are funded when a delegation is split across batches
+#[repr(u8)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub enum DelegationOrder {
+    /// Ascending slot index
+    #[default]
+    SlotIndex = 0,
+    /// The slots furthest below their target weight first, or the lowest weighted if there is no target
+    MostUnderweightFirst = 1,
+}
+
+impl TryFrom<u8> for DelegationOrder {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Ok(match value {
+            0 => Self::SlotIndex,
+            1 => Self::MostUnderweightFirst,
+            _ => return Err(()),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
 pub struct FeeBpsBlockIncrement(pub u64);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
 pub struct FeePaymentCooldownBlocks(pub u64);
 
+/// Whether reconciler fees are paid out of rewards, all rewards are delegated while disabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub struct FeePayoutsEnabled(pub bool);
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct InflightDelegation(pub u128);
@@ -59,6 +167,14 @@ pub struct MaxFeeBps(pub u64);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
 pub struct MaxMsgCount(pub usize);
 
+/// The max number of concurrent unbonding entries the remote chain allows per delegator-validator pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub struct MaxUnbondingEntries(pub u64);
+
+/// The number of times a failed undelegation is re-issued before the offending slot is skipped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub struct MaxUndelegateRetries(pub usize);
+
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct MsgIssuedCount(pub usize);
@@ -68,7 +184,7 @@ pub struct MsgIssuedCount(pub usize);
 pub struct MsgSuccessCount(pub usize);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
-pub struct Now(pub u64);
+pub struct Now(pub Timestamp);
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
 #[cfg_attr(test, derive(serde::Serialize))]
@@ -94,10 +210,19 @@ pub struct RewardsReceivable(pub u128);
 pub struct UnbondedAmount(pub u128);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
-pub struct UnbondingTimeSecs(pub u64);
+pub struct UnbondingTimeSecs(pub DurationSecs);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
-pub struct UnbondCompleteTimestamp(pub u64);
+pub struct UnbondCompleteTimestamp(pub Timestamp);
+
+/// The slots left out of the current undelegation for having reached the max unbonding entries
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct UndelegateDeferredSlots(pub Vec<ValidatorSetSlot>);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub struct UndelegateRetryCount(pub usize);
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
 #[cfg_attr(test, derive(serde::Serialize))]
@@ -110,6 +235,13 @@ pub struct ValidatorSetSize(pub usize);
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct ValidatorSetSlot(pub usize);
 
+/// The account addresses in use when the setup phases last completed
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SetupAccounts {
+    pub delegation: Account,
+    pub rewards: Account,
+}
+
 #[derive(Debug, Clone)]
 #[cfg_attr(test, derive(serde::Serialize))]
 pub struct Delegation {
@@ -209,6 +341,7 @@ impl FeeBps {
 pub struct FeeMetadata {
     pub fee_recipient: Option<FeeRecipient>,
     pub fee_payout_cooldown: FeePaymentCooldownBlocks,
+    pub fee_payouts_enabled: FeePayoutsEnabled,
     pub fee_bps_block_increment: FeeBpsBlockIncrement,
     pub max_fee_bps: MaxFeeBps,
 }
@@ -219,7 +352,7 @@ impl FeeMetadata {
         last_reconcile_height: LastReconcileHeight,
         current_height: CurrentHeight,
     ) -> Option<FeeBps> {
-        if self.fee_recipient.is_none() || self.max_fee_bps.0 == 0 {
+        if !self.fee_payouts_enabled.0 || self.fee_recipient.is_none() || self.max_fee_bps.0 == 0 {
             return None;
         }
 
@@ -239,8 +372,17 @@ impl FeeMetadata {
 }
 
 #[repr(u8)]
-#[derive(Debug, Default, Copy, Clone, PartialEq, derive_more::IsVariant, derive_more::Display)]
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    derive_more::IsVariant,
+    derive_more::Display,
+    serde::Serialize,
+    serde::Deserialize,
+)]
 pub enum Phase {
     #[default]
     #[display(fmt = "setup_rewards_address")]
@@ -258,19 +400,42 @@ pub enum Phase {
     Delegate = 7,
 }
 
+/// The Authz sends bundled into the delegate phase alongside the slot delegations,
+/// whose presence depends on the rewards & fee balances at the time the phase runs
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DelegateAuthzMsgs {
+    /// Whether or not rewards are sent from the rewards account to the delegator
+    pub has_rewards: bool,
+    /// Whether or not a reconciler fee is sent from the rewards account to the fee recipient
+    pub has_fee_payable: bool,
+}
+
+impl DelegateAuthzMsgs {
+    /// Both sends are present, the most messages the delegate phase can issue
+    pub const ALL: Self = Self {
+        has_rewards: true,
+        has_fee_payable: true,
+    };
+
+    pub const fn count(self) -> usize {
+        self.has_rewards as usize + self.has_fee_payable as usize
+    }
+}
+
 impl Phase {
     // how many txs are issued by the phase in total
     pub fn tx_count(
         self,
         ValidatorSetSize(set_size): ValidatorSetSize,
         MaxMsgCount(max_msg_count): MaxMsgCount,
+        authz_msgs: DelegateAuthzMsgs,
     ) -> usize {
         // in addition to one per slot
         let extra_msg_count = match self {
             // no messages other than `{Un, Re}delegate`
             Phase::Redelegate | Phase::Undelegate => 0,
-            // extra messages required: one to send rewards + one to send fee
-            Phase::Delegate => 2,
+            // extra messages if required: one to send rewards + one to send fee
+            Phase::Delegate => authz_msgs.count(),
             // no need to continue in these cases:
             // no txs issued
             Phase::StartReconcile => return 0,
@@ -312,17 +477,62 @@ impl Phase {
         state: State,
         validator_set_size: ValidatorSetSize,
         max_msg_count: MaxMsgCount,
+        authz_msgs: DelegateAuthzMsgs,
+    ) -> usize {
+        ReconcileScope::Full.sequence_tx_count(
+            self,
+            state,
+            validator_set_size,
+            max_msg_count,
+            authz_msgs,
+        )
+    }
+}
+
+/// The phases run by a reconcile cycle
+#[derive(Debug, Default, Copy, Clone, PartialEq, derive_more::IsVariant, serde::Serialize)]
+pub enum ReconcileScope {
+    /// Every phase of the sequence
+    #[default]
+    Full,
+    /// Only the rewards path: sending the receivable rewards to the delegator, delegating them
+    /// & withdrawing the next rewards. The redelegate, undelegate & transfer phases are skipped.
+    RewardsOnly,
+}
+
+impl ReconcileScope {
+    /// Whether or not cycles of this scope run the phase
+    pub const fn includes(self, phase: Phase) -> bool {
+        match self {
+            ReconcileScope::Full => true,
+            ReconcileScope::RewardsOnly => {
+                matches!(phase, Phase::StartReconcile | Phase::Delegate)
+            }
+        }
+    }
+
+    // how many txs are issued by the phases in scope, from `phase` to the end of the sequence
+    pub fn sequence_tx_count(
+        self,
+        phase: Phase,
+        state: State,
+        validator_set_size: ValidatorSetSize,
+        max_msg_count: MaxMsgCount,
+        authz_msgs: DelegateAuthzMsgs,
     ) -> usize {
         if state.is_pending() {
             return 0;
         }
 
-        let mut count = self.tx_count(validator_set_size, max_msg_count);
-        let mut phase = self;
+        let mut count = 0;
+        let mut next = Some(phase);
 
-        while let Some(next_phase) = phase.next() {
-            phase = next_phase;
-            count += phase.tx_count(validator_set_size, max_msg_count);
+        while let Some(phase) = next {
+            if self.includes(phase) {
+                count += phase.tx_count(validator_set_size, max_msg_count, authz_msgs);
+            }
+
+            next = phase.next();
         }
 
         count
@@ -368,10 +578,16 @@ pub struct RemoteBalanceReport {
 #[derive(Debug, Clone, Copy)]
 #[allow(clippy::manual_non_exhaustive)]
 pub struct UndelegatedBalanceReport {
-    pub last_updated_timestamp: u64,
+    pub last_updated_timestamp: Timestamp,
     pub remote_balance: RemoteBalanceReport,
 }
 
+/// The number of unbonding entries in-flight on the remote chain for each validator set slot
+#[derive(Debug, Clone, Default)]
+pub struct UnbondingEntriesReport {
+    pub entries_per_slot: Vec<u64>,
+}
+
 pub struct BalancesIcqResult {
     pub last_submitted_height: u64,
     pub coins: Vec<(Denom, RemoteBalance)>,
@@ -393,8 +609,17 @@ impl BalancesIcqResult {
 }
 
 #[repr(u8)]
-#[derive(Debug, Default, Copy, Clone, PartialEq, derive_more::IsVariant, derive_more::Display)]
-#[cfg_attr(test, derive(serde::Serialize))]
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    derive_more::IsVariant,
+    derive_more::Display,
+    serde::Serialize,
+    serde::Deserialize,
+)]
 pub enum State {
     #[default]
     Idle = 0,
@@ -475,7 +700,7 @@ impl Weight {
 
     /// Returns `Some(weight)` if `fixed` <= 1.0, otherwise `None`
     pub fn checked_from_fixed(fixed: FixedU256) -> Option<Self> {
-        if fixed > FixedU256::from_u128(1) {
+        if fixed.gt_u128(1) {
             return None;
         }
 
@@ -488,13 +713,116 @@ impl Weight {
             return 0;
         }
 
+        self.0.checked_mul_u128(rhs).expect("always: weight <= 1")
+    }
+
+    /// Apply the weight to `rhs` rounding away from zero
+    pub fn apply_ceil(self, rhs: u128) -> u128 {
         self.0
-            .checked_mul(FixedU256::from_u128(rhs))
+            .mul_u128_with_rounding(rhs, Rounding::Ceil)
             .expect("always: weight <= 1")
-            .floor()
+    }
+
+    /// Apply the weight to `rhs` rounding to the nearest integer, halves to even
+    pub fn apply_round(self, rhs: u128) -> u128 {
+        self.0
+            .mul_u128_with_rounding(rhs, Rounding::HalfEven)
+            .expect("always: weight <= 1")
+    }
+}
+
+/// An accounting quantity would overflow or go negative, meaning the recorded state is inconsistent
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub enum ArithmeticError {
+    #[error("{quantity} of {lhs} overflows adding {rhs}")]
+    Overflow {
+        quantity: &'static str,
+        lhs: u128,
+        rhs: u128,
+    },
+
+    #[error("{quantity} of {lhs} is less than the {rhs} subtracted")]
+    Underflow {
+        quantity: &'static str,
+        lhs: u128,
+        rhs: u128,
+    },
+}
+
+macro_rules! impl_checked_arithmetic {
+    ($($quantity:ident),+ $(,)?) => {
+        $(
+            impl $quantity {
+                pub fn checked_add(self, rhs: u128) -> Result<Self, ArithmeticError> {
+                    self.0
+                        .checked_add(rhs)
+                        .map(Self)
+                        .ok_or(ArithmeticError::Overflow {
+                            quantity: stringify!($quantity),
+                            lhs: self.0,
+                            rhs,
+                        })
+                }
+
+                pub fn checked_sub(self, rhs: u128) -> Result<Self, ArithmeticError> {
+                    self.0
+                        .checked_sub(rhs)
+                        .map(Self)
+                        .ok_or(ArithmeticError::Underflow {
+                            quantity: stringify!($quantity),
+                            lhs: self.0,
+                            rhs,
+                        })
+                }
+
+                pub fn saturating_sub(self, rhs: u128) -> Self {
+                    Self(self.0.saturating_sub(rhs))
+                }
+            }
+        )+
+    };
+}
+
+impl_checked_arithmetic!(
+    Delegated,
+    InflightDelegation,
+    InflightDeposit,
+    InflightFeePayable,
+    InflightRewardsReceivable,
+    InflightUnbond,
+    PendingDeposit,
+    PendingUnbond,
+    RemoteBalance,
+);
+
+/// The whole pending deposit is transferred out at once
+impl From<PendingDeposit> for InflightDeposit {
+    fn from(PendingDeposit(amount): PendingDeposit) -> Self {
+        Self(amount)
+    }
+}
+
+/// Deposits are delegated on arrival, without any rewards
+impl From<InflightDeposit> for InflightDelegation {
+    fn from(InflightDeposit(amount): InflightDeposit) -> Self {
+        Self(amount)
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(test, derive(serde::Serialize))]
+pub enum WeightsError {
+    #[error("no slot weights")]
+    Empty,
+
+    #[error("slot weights add up to {total}, not 1.0")]
+    SumNotOne { total: FixedU256 },
+
+    #[error("expected {expected} slot weights, got {actual}")]
+    SlotCountMismatch { expected: usize, actual: usize },
+}
+
 /// The weights for all the validator slots, initially provided at instantiation
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(test, derive(serde::Serialize))]
@@ -505,28 +833,153 @@ impl Weights {
         Self(weights)
     }
 
-    pub fn new(weights: &[Weight]) -> Option<Self> {
+    /// Errors if no weights are given or they add up to more than 1.0.
+    /// Totals short of 1.0 (e.g. from rounding) are normalised when the weights are applied.
+    pub fn new(weights: &[Weight]) -> Result<Self, WeightsError> {
         // ensure at least one weight was provided
         if weights.is_empty() {
-            return None;
+            return Err(WeightsError::Empty);
         }
 
         let mut total = FixedU256::from_u128(0);
 
         for weight in weights {
-            total = total.checked_add(weight.0)?;
+            total = total
+                .checked_add(weight.0)
+                .expect("always: running total <= 1.0 & weight <= 1.0");
 
-            if total > FixedU256::from_u128(1) {
-                return None;
+            if total.gt_u128(1) {
+                return Err(WeightsError::SumNotOne { total });
             }
         }
 
-        Some(Weights(weights.to_owned()))
+        Ok(Weights(weights.to_owned()))
+    }
+
+    /// As [Weights::new], but falls back to the `fallback` weights if the given weights are invalid.
+    /// The reason for rejecting the given weights is returned alongside the fallback.
+    pub fn new_lossy(
+        weights: &[Weight],
+        fallback: impl FnOnce() -> Weights,
+    ) -> (Self, Option<WeightsError>) {
+        match Self::new(weights) {
+            Ok(weights) => (weights, None),
+            Err(err) => (fallback(), Some(err)),
+        }
+    }
+
+    /// Errors if there is not exactly one weight per slot
+    pub fn ensure_slot_count(&self, slot_count: usize) -> Result<(), WeightsError> {
+        if self.0.len() != slot_count {
+            return Err(WeightsError::SlotCountMismatch {
+                expected: slot_count,
+                actual: self.0.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Removes the slot at `idx`, redistributing its weight pro-rata over the remaining slots so they add up to 1.0.
+    /// If the remaining slots have no weight, they are each given an equal weight.
+    /// Returns `None` if `idx` is out of range or it is the only remaining slot.
+    pub fn remove_slot(&self, idx: usize) -> Option<Weights> {
+        if idx >= self.0.len() || self.0.len() == 1 {
+            return None;
+        }
+
+        let mut remaining = self.0.clone();
+
+        remaining.remove(idx);
+
+        let total = remaining
+            .iter()
+            .try_fold(FixedU256::zero(), |acc, w| acc.checked_add(w.0))
+            .expect("always: remaining weights add up to <= 1.0");
+
+        if total.is_zero() {
+            let equal_weight = FixedU256::from_u128(1)
+                .checked_div(FixedU256::from_u128(remaining.len() as u128))
+                .expect("checked: remaining slots > 0");
+
+            return Some(Weights(vec![Weight(equal_weight); remaining.len()]));
+        }
+
+        let scaled = remaining
+            .into_iter()
+            .map(|w| {
+                w.0.checked_div(total)
+                    .map(Weight)
+                    .expect("checked: total weight > 0")
+            })
+            .collect();
+
+        Some(Weights(scaled))
+    }
+
+    /// Resizes the weights to `slot_map.len()` slots, where each new slot takes the weight of the existing slot it maps to, or
+    /// zero if it maps to none. The retained weights are rescaled pro-rata so they add up to 1.0, any unmapped existing slot
+    /// has its weight redistributed. If the retained slots have no weight, they are each given an equal weight.
+    /// Returns `None` if `slot_map` is empty, maps to an out of range slot or maps to the same slot more than once.
+    pub fn resize(&self, slot_map: &[Option<usize>]) -> Option<Weights> {
+        if slot_map.is_empty() {
+            return None;
+        }
+
+        let mut mapped: Vec<_> = slot_map.iter().flatten().collect();
+
+        mapped.sort_unstable();
+
+        if mapped.windows(2).any(|pair| pair[0] == pair[1]) {
+            return None;
+        }
+
+        let retained = slot_map
+            .iter()
+            .map(|idx| match idx {
+                Some(idx) => self.0.get(*idx).copied(),
+                None => Some(Weight(FixedU256::zero())),
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let total = retained
+            .iter()
+            .try_fold(FixedU256::zero(), |acc, w| acc.checked_add(w.0))
+            .expect("always: retained weights add up to <= 1.0");
+
+        if total.is_zero() {
+            let equal_weight = FixedU256::from_u128(1)
+                .checked_div(FixedU256::from_u128(retained.len() as u128))
+                .expect("checked: retained slots > 0");
+
+            return Some(Weights(vec![Weight(equal_weight); retained.len()]));
+        }
+
+        let scaled = retained
+            .into_iter()
+            .map(|w| {
+                w.0.checked_div(total)
+                    .map(Weight)
+                    .expect("checked: total weight > 0")
+            })
+            .collect();
+
+        Some(Weights(scaled))
     }
 
     pub fn as_slice(&self) -> &[Weight] {
         self.0.as_slice()
     }
+
+    /// Returns true if both weight sets have the same number of slots and every slot weight is within `epsilon` of the other
+    pub fn approx_eq(&self, other: &Weights, epsilon: FixedU256) -> bool {
+        self.0.len() == other.0.len()
+            && self
+                .0
+                .iter()
+                .zip(&other.0)
+                .all(|(lhs, rhs)| lhs.0.abs_diff(rhs.0) <= epsilon)
+    }
 }
 
 impl IntoIterator for Weights {
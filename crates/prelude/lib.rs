@@ -0,0 +1,26 @@
+/// Create a `Vec` of commands from different command types, converting each with `Into`
+///
+/// ```
+/// use amulet_core::{
+///     hub::{BalanceSheetCmd, Cmd, VaultCmd},
+///     Identifier,
+/// };
+/// use amulet_prelude::into_cmds;
+///
+/// let cmds: Vec<Cmd> = into_cmds![
+///     VaultCmd::SetDepositsEnabled {
+///         vault: Identifier::from("vault"),
+///         enabled: true,
+///     },
+///     BalanceSheetCmd::SetGlobalPause { paused: false },
+/// ];
+///
+/// assert!(matches!(
+///     cmds.as_slice(),
+///     [Cmd::Vault(_), Cmd::BalanceSheet(_)]
+/// ));
+/// ```
+#[macro_export]
+macro_rules! into_cmds {
+    ($($cmd:expr),+ $(,)?) => { vec![$($cmd.into()),+] };
+}
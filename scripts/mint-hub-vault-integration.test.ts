@@ -18,6 +18,7 @@ import {
 import { GENESIS_ALLOCATION } from "./suite/constants";
 import {
   QueryClient,
+  assertContractEmittedEvent,
   createFee,
   createHostClient,
   createHostWallet,
@@ -262,7 +263,7 @@ describe("Mint, Hub & Vault Integration", () => {
   it("alice makes a deposit while the redemption rate is 1.0", async () => {
     const depositAmount = GENESIS_ALLOCATION / 10;
 
-    await aliceClient.execute(
+    const res = await aliceClient.execute(
       aliceAddress,
       hubAddress,
       { deposit: { vault: vaultAddress } },
@@ -271,6 +272,13 @@ describe("Mint, Hub & Vault Integration", () => {
       [coin(depositAmount, "untrn")],
     );
 
+    assertContractEmittedEvent(res, "wasm", {
+      _contract_address: hubAddress,
+      kind: "deposit",
+      vault: vaultAddress,
+      account: aliceAddress,
+    });
+
     const position: PositionResponse = await operatorClient.queryContractSmart(
       hubAddress,
       { position: { account: aliceAddress, vault: vaultAddress } },
@@ -1,7 +1,7 @@
 import { describe, it, beforeAll, afterAll, expect } from "bun:test";
 import { TestSuite } from "./suite";
 import { artifact, readContractFileBytes } from "./utils";
-import { StdFee } from "@cosmjs/stargate";
+import { StdFee, calculateFee } from "@cosmjs/stargate";
 import { SigningCosmWasmClient } from "@cosmjs/cosmwasm-stargate";
 import { Validator } from "cosmjs-types/cosmos/staking/v1beta1/staking";
 import { DirectSecp256k1HdWallet, coin } from "@cosmjs/proto-signing";
@@ -22,6 +22,8 @@ import { GENESIS_ALLOCATION } from "./suite/constants";
 import {
   QueryClient,
   HostClient,
+  advanceBlocks,
+  advanceTime,
   createFee,
   createHostClient,
   createHostWallet,
@@ -399,6 +401,47 @@ describe("Remote Proof-of-Stake Vault", () => {
     throw new Error("timeout waiting for IBC transfer to complete");
   });
 
+  it("advancing the remote chain past the unbonding period completes an undelegation", async () => {
+    const remoteWallet = await createRemoteWallet(suite, "demo3");
+    const remoteAddress = (await remoteWallet.getAccounts())[0].address;
+    const remoteClient = await createRemoteClient(suite, remoteWallet);
+    const validator = validators[0].operatorAddress;
+    const fee = calculateFee(500_000, suite.getRemoteGasPrices());
+
+    await remoteClient.delegateTokens(
+      remoteAddress,
+      validator,
+      coin(1_000, "stake"),
+      fee,
+    );
+
+    await advanceBlocks(remoteClient, 1);
+
+    await remoteClient.undelegateTokens(
+      remoteAddress,
+      validator,
+      coin(1_000, "stake"),
+      fee,
+    );
+
+    const preAdvanceUnbondings =
+      await remoteQueryClient.staking.delegatorUnbondingDelegations(
+        remoteAddress,
+      );
+
+    expect(preAdvanceUnbondings.unbondingResponses.length).toBe(1);
+
+    await advanceTime(remoteClient, UNBONDING_PERIOD_SECS);
+    await advanceBlocks(remoteClient, 1);
+
+    const postAdvanceUnbondings =
+      await remoteQueryClient.staking.delegatorUnbondingDelegations(
+        remoteAddress,
+      );
+
+    expect(postAdvanceUnbondings.unbondingResponses.length).toBe(0);
+  });
+
   it("alice makes the initial deposit for vault 1, shares received and pending deposits increase", async () => {
     const depositAmount = VALIDATOR_BALANCE / 10;
 
@@ -1,5 +1,8 @@
 import { expect } from "bun:test";
-import { SigningCosmWasmClient } from "@cosmjs/cosmwasm-stargate";
+import {
+  ExecuteResult,
+  SigningCosmWasmClient,
+} from "@cosmjs/cosmwasm-stargate";
 import { Coin, DirectSecp256k1HdWallet, coin } from "@cosmjs/proto-signing";
 import {
   BankExtension,
@@ -122,6 +125,74 @@ export async function ibcTransfer(
   await client.signAndBroadcast(sender, [transferMsg], gas);
 }
 
+// asserts the execute `result` contains a wasm event of `eventType` carrying all of `attrs`,
+// where `eventType` is either "wasm" for plain response attributes or the custom event type
+// (emitted by the chain prefixed with "wasm-")
+export function assertContractEmittedEvent(
+  result: ExecuteResult,
+  eventType: string,
+  attrs: Record<string, string>,
+) {
+  const type = eventType == "wasm" ? eventType : `wasm-${eventType}`;
+
+  const matching = result.events
+    .filter((event) => event.type == type)
+    .find((event) =>
+      Object.entries(attrs).every(([key, value]) =>
+        event.attributes.some(
+          (attr) => attr.key == key && attr.value == value,
+        ),
+      ),
+    );
+
+  if (!matching) {
+    const emitted = result.events
+      .filter((event) => event.type.startsWith("wasm"))
+      .map((event) => JSON.stringify(event));
+
+    throw new Error(
+      `no ${type} event with attributes ${JSON.stringify(attrs)}, emitted: ${emitted.join(", ")}`,
+    );
+  }
+}
+
+// the on-chain suite runs live nodes, so advancing the chain means waiting
+// for it to produce blocks rather than warping its clock
+export async function advanceBlocks(
+  client: HostClient | RemoteClient,
+  n: number,
+): Promise<number> {
+  const targetHeight = (await client.getHeight()) + n;
+
+  while (true) {
+    const height = await client.getHeight();
+
+    if (height >= targetHeight) return height;
+
+    await Bun.sleep(1_000);
+  }
+}
+
+// waits until a block is committed with a timestamp at least `seconds` past the
+// latest block time, so anything maturing before then (e.g. unbondings) has
+// been processed by the chain's end blocker
+export async function advanceTime(
+  client: HostClient | RemoteClient,
+  seconds: number,
+): Promise<Date> {
+  const start = new Date((await client.getBlock()).header.time);
+  const target = start.getTime() + seconds * 1_000;
+
+  while (true) {
+    const block = await client.getBlock();
+    const time = new Date(block.header.time);
+
+    if (time.getTime() >= target) return time;
+
+    await Bun.sleep(1_000);
+  }
+}
+
 export async function initGenericLstVault(
   suite: ITestSuite,
   client: HostClient,